@@ -0,0 +1,237 @@
+//! `adopt` command for deployments that predate the position store.
+//!
+//! Pulls open positions from both venues, asks the operator to confirm each
+//! one, and seeds the store so `SettlementChecker` manages them from then on.
+
+use crate::clients::{KalshiClient, PolymarketClient};
+use crate::event::Event;
+use crate::money::{Price, UsdAmount};
+use crate::position_tracker::{Position, PositionTracker};
+use anyhow::Result;
+use std::io::{BufRead, Write};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+#[derive(Debug, Clone)]
+pub struct VenuePosition {
+    pub platform: String,
+    pub event_id: String,
+    pub title: String,
+    pub outcome: String,
+    pub amount: f64,
+    pub cost: UsdAmount,
+    /// Instrument to trade when closing: the Kalshi market ticker or the
+    /// Polymarket outcome token id.
+    pub market: String,
+}
+
+impl VenuePosition {
+    /// Average price paid per share.
+    pub fn price(&self) -> Price {
+        self.cost.per_share(self.amount)
+    }
+
+    pub fn to_position(&self) -> Position {
+        let event = Event::new(
+            self.platform.clone(),
+            self.event_id.clone(),
+            self.title.clone(),
+            String::new(),
+        );
+        Position::new(
+            self.platform.clone(),
+            &event,
+            self.outcome.clone(),
+            self.amount,
+            self.cost,
+            self.price(),
+            None,
+        )
+    }
+}
+
+/// Maps a data-API position onto our YES/NO convention (outcome index 0 is
+/// YES/Up, 1 is NO/Down).
+pub fn polymarket_position_from_json(value: &serde_json::Value) -> Option<VenuePosition> {
+    let event_id = value["conditionId"].as_str()?.to_string();
+    let market = value["asset"].as_str().unwrap_or_default().to_string();
+    let amount = value["size"].as_f64()?;
+    if amount <= 0.0 {
+        return None;
+    }
+    let outcome = match value["outcomeIndex"].as_i64() {
+        Some(0) => "YES",
+        Some(1) => "NO",
+        _ => match value["outcome"].as_str()?.to_lowercase().as_str() {
+            "yes" | "up" => "YES",
+            "no" | "down" => "NO",
+            _ => return None,
+        },
+    };
+    let cost = value["initialValue"]
+        .as_f64()
+        .map(UsdAmount::from_f64)
+        .or_else(|| value["avgPrice"].as_f64().map(|p| UsdAmount::for_shares(amount, Price::from_f64(p))))
+        .unwrap_or_default();
+
+    Some(VenuePosition {
+        platform: "polymarket".to_string(),
+        event_id,
+        title: value["title"].as_str().unwrap_or_default().to_string(),
+        outcome: outcome.to_string(),
+        amount,
+        cost,
+        market,
+    })
+}
+
+/// Kalshi reports a signed contract count per market (negative = NO) and the
+/// exposure in cents; the event ticker and title come from the market itself.
+async fn kalshi_position_from_json(
+    kalshi: &KalshiClient,
+    value: &serde_json::Value,
+) -> Option<VenuePosition> {
+    let ticker = value["ticker"].as_str()?;
+    let count = value["position"].as_i64()?;
+    if count == 0 {
+        return None;
+    }
+    let cost = UsdAmount::from_cents(value["market_exposure"].as_i64().unwrap_or(0));
+
+    let market = kalshi.get_market(ticker).await.ok().flatten();
+    let market = market.as_ref().map(|m| &m["market"]);
+    let event_id = market
+        .and_then(|m| m["event_ticker"].as_str())
+        .unwrap_or(ticker)
+        .to_string();
+    let title = market
+        .and_then(|m| m["title"].as_str())
+        .unwrap_or(ticker)
+        .to_string();
+
+    Some(VenuePosition {
+        platform: "kalshi".to_string(),
+        event_id,
+        title,
+        outcome: if count > 0 { "YES" } else { "NO" }.to_string(),
+        amount: count.unsigned_abs() as f64,
+        cost,
+        market: ticker.to_string(),
+    })
+}
+
+pub async fn fetch_venue_positions(
+    polymarket_client: &PolymarketClient,
+    kalshi_client: &KalshiClient,
+) -> Vec<VenuePosition> {
+    let (positions, errors) = fetch_venue_positions_checked(polymarket_client, kalshi_client).await;
+    for e in errors {
+        warn!("{}", e);
+    }
+    positions
+}
+
+/// Like `fetch_venue_positions`, but hands back the venues that could not be
+/// read instead of only logging them.
+pub async fn fetch_venue_positions_checked(
+    polymarket_client: &PolymarketClient,
+    kalshi_client: &KalshiClient,
+) -> (Vec<VenuePosition>, Vec<String>) {
+    let (pm_raw, kalshi_raw) = tokio::join!(
+        polymarket_client.fetch_positions(),
+        kalshi_client.fetch_positions()
+    );
+
+    let mut positions = Vec::new();
+    let mut errors = Vec::new();
+
+    match pm_raw {
+        Ok(raw) => positions.extend(raw.iter().filter_map(polymarket_position_from_json)),
+        Err(e) => errors.push(format!("Could not fetch Polymarket positions: {}", e)),
+    }
+
+    match kalshi_raw {
+        Ok(raw) => {
+            for value in &raw {
+                if let Some(position) = kalshi_position_from_json(kalshi_client, value).await {
+                    positions.push(position);
+                }
+            }
+        }
+        Err(e) => errors.push(format!("Could not fetch Kalshi positions: {}", e)),
+    }
+
+    (positions, errors)
+}
+
+enum Answer {
+    Yes,
+    No,
+    All,
+    Quit,
+}
+
+fn prompt(position: &VenuePosition) -> Answer {
+    print!(
+        "Adopt {} {} {:.2} @ {} (cost {}) — {} [{}]? [y]es/[n]o/[a]ll/[q]uit: ",
+        position.platform,
+        position.outcome,
+        position.amount,
+        position.price(),
+        position.cost,
+        position.title,
+        position.event_id
+    );
+    let _ = std::io::stdout().flush();
+
+    let mut line = String::new();
+    if std::io::stdin().lock().read_line(&mut line).is_err() {
+        return Answer::Quit;
+    }
+    match line.trim().to_lowercase().as_str() {
+        "y" | "yes" => Answer::Yes,
+        "a" | "all" => Answer::All,
+        "q" | "quit" => Answer::Quit,
+        _ => Answer::No,
+    }
+}
+
+/// Runs the interactive adoption and returns how many positions were added.
+pub async fn run_adopt(
+    polymarket_client: &PolymarketClient,
+    kalshi_client: &KalshiClient,
+    position_tracker: &Mutex<PositionTracker>,
+) -> Result<usize> {
+    let venue_positions = fetch_venue_positions(polymarket_client, kalshi_client).await;
+    info!("Found {} open positions across venues", venue_positions.len());
+
+    let mut adopted = 0;
+    let mut accept_all = false;
+
+    for position in &venue_positions {
+        {
+            let tracker = position_tracker.lock().await;
+            if tracker.has_open_position(&position.platform, &position.event_id, &position.outcome) {
+                info!(
+                    "Skipping {} {} {} — already tracked",
+                    position.platform, position.event_id, position.outcome
+                );
+                continue;
+            }
+        }
+
+        if !accept_all {
+            match prompt(position) {
+                Answer::Yes => {}
+                Answer::No => continue,
+                Answer::All => accept_all = true,
+                Answer::Quit => break,
+            }
+        }
+
+        position_tracker.lock().await.add_position(position.to_position());
+        adopted += 1;
+    }
+
+    Ok(adopted)
+}
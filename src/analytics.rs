@@ -0,0 +1,241 @@
+//! Performance analytics over the position history.
+//!
+//! Closed trades (a pair's legs together, or a single position) give the
+//! win rate overall and over the last `ANALYTICS_WINDOW` trades (default
+//! 50), the average edge detected at entry against the return actually
+//! captured, a Sharpe ratio over daily PnL (annualised over 365 days; crypto
+//! trades every day) and the maximum drawdown of cumulative PnL. Logged
+//! every `ANALYTICS_INTERVAL_SECS` (default 3600, 0 turns it off) and
+//! published as `analytics_*` gauges on the metrics endpoint.
+
+use crate::metrics::metrics;
+use crate::money::UsdAmount;
+use crate::position_tracker::{Position, PositionStatus, PositionTracker};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use std::collections::{BTreeMap, HashMap};
+use tracing::info;
+
+pub const DEFAULT_WINDOW: usize = 50;
+const DEFAULT_INTERVAL_SECS: u64 = 3600;
+
+/// One closed trade.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeOutcome {
+    pub closed_at: DateTime<Utc>,
+    pub cost: f64,
+    pub profit: f64,
+    pub detected_edge: Option<f64>,
+}
+
+impl TradeOutcome {
+    pub fn captured_edge(&self) -> Option<f64> {
+        (self.cost > 0.0).then(|| self.profit / self.cost)
+    }
+}
+
+/// Closed trades, oldest first. A pair counts once, when its last leg
+/// closes; trades with an open leg are left out.
+pub fn trade_outcomes<'a>(positions: impl IntoIterator<Item = &'a Position>) -> Vec<TradeOutcome> {
+    let mut trades: HashMap<String, Vec<&Position>> = HashMap::new();
+    for position in positions {
+        let key = position.group_id.clone().unwrap_or_else(|| position.id.clone());
+        trades.entry(key).or_default().push(position);
+    }
+    let mut outcomes: Vec<TradeOutcome> = trades
+        .into_values()
+        .filter(|legs| legs.iter().all(|p| p.status != PositionStatus::Open))
+        .filter_map(|legs| {
+            Some(TradeOutcome {
+                closed_at: legs.iter().filter_map(|p| p.settled_at).max()?,
+                cost: legs.iter().map(|p| p.cost).sum::<UsdAmount>().to_f64(),
+                profit: legs.iter().filter_map(|p| p.profit).sum::<UsdAmount>().to_f64(),
+                detected_edge: legs.iter().find_map(|p| p.detected_edge),
+            })
+        })
+        .collect();
+    outcomes.sort_by_key(|t| t.closed_at);
+    outcomes
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PerformanceReport {
+    pub trades: usize,
+    pub win_rate: Option<f64>,
+    /// Win rate over the last `window` trades.
+    pub rolling_win_rate: Option<f64>,
+    pub window: usize,
+    /// Mean return on cost expected at entry, over trades that recorded it.
+    pub avg_edge_detected: Option<f64>,
+    /// Mean return on cost realized by those same trades.
+    pub avg_edge_captured: Option<f64>,
+    pub sharpe: Option<f64>,
+    /// Largest fall of cumulative PnL from a previous peak, in dollars.
+    pub max_drawdown: f64,
+    pub total_profit: f64,
+}
+
+fn win_rate(trades: &[TradeOutcome]) -> Option<f64> {
+    if trades.is_empty() {
+        return None;
+    }
+    Some(trades.iter().filter(|t| t.profit > 0.0).count() as f64 / trades.len() as f64)
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// PnL per calendar day (UTC) from the first close to the last, with quiet
+/// days as zero.
+pub fn daily_pnl(trades: &[TradeOutcome]) -> Vec<f64> {
+    let mut by_day: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+    for trade in trades {
+        *by_day.entry(trade.closed_at.date_naive()).or_default() += trade.profit;
+    }
+    let (Some(first), Some(last)) = (by_day.keys().next().copied(), by_day.keys().last().copied()) else {
+        return Vec::new();
+    };
+    let mut days = Vec::new();
+    let mut day = first;
+    while day <= last {
+        days.push(by_day.get(&day).copied().unwrap_or(0.0));
+        day += Duration::days(1);
+    }
+    days
+}
+
+/// Annualised Sharpe ratio of daily PnL; `None` with under two days or no
+/// variation.
+pub fn sharpe(daily: &[f64]) -> Option<f64> {
+    if daily.len() < 2 {
+        return None;
+    }
+    let mean = mean(daily)?;
+    let variance = daily.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / (daily.len() - 1) as f64;
+    let std = variance.sqrt();
+    (std > 0.0).then(|| mean / std * 365f64.sqrt())
+}
+
+pub fn max_drawdown(trades: &[TradeOutcome]) -> f64 {
+    let (mut cumulative, mut peak, mut drawdown) = (0.0f64, 0.0f64, 0.0f64);
+    for trade in trades {
+        cumulative += trade.profit;
+        peak = peak.max(cumulative);
+        drawdown = drawdown.max(peak - cumulative);
+    }
+    drawdown
+}
+
+pub fn analyze(trades: &[TradeOutcome], window: usize) -> PerformanceReport {
+    let recent = &trades[trades.len().saturating_sub(window)..];
+    let with_edge: Vec<&TradeOutcome> = trades.iter().filter(|t| t.detected_edge.is_some()).collect();
+    let detected: Vec<f64> = with_edge.iter().filter_map(|t| t.detected_edge).collect();
+    let captured: Vec<f64> = with_edge.iter().filter_map(|t| t.captured_edge()).collect();
+    PerformanceReport {
+        trades: trades.len(),
+        win_rate: win_rate(trades),
+        rolling_win_rate: win_rate(recent),
+        window,
+        avg_edge_detected: mean(&detected),
+        avg_edge_captured: mean(&captured),
+        sharpe: sharpe(&daily_pnl(trades)),
+        max_drawdown: max_drawdown(trades),
+        total_profit: trades.iter().map(|t| t.profit).sum(),
+    }
+}
+
+/// `ANALYTICS_WINDOW`, or the default.
+pub fn window_from_env() -> usize {
+    std::env::var("ANALYTICS_WINDOW")
+        .ok()
+        .and_then(|s| s.trim().parse::<usize>().ok())
+        .filter(|w| *w > 0)
+        .unwrap_or(DEFAULT_WINDOW)
+}
+
+/// `ANALYTICS_INTERVAL_SECS`; `None` when set to 0.
+pub fn interval_from_env() -> Option<std::time::Duration> {
+    let secs = std::env::var("ANALYTICS_INTERVAL_SECS")
+        .ok()
+        .and_then(|s| s.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_INTERVAL_SECS);
+    (secs > 0).then(|| std::time::Duration::from_secs(secs))
+}
+
+impl PerformanceReport {
+    pub fn from_tracker(tracker: &PositionTracker, window: usize) -> Self {
+        analyze(&trade_outcomes(tracker.get_all_positions()), window)
+    }
+
+    /// Sets the `analytics_*` gauges.
+    pub fn publish(&self) {
+        let m = metrics();
+        m.set_gauge("analytics_trades", &[], self.trades as f64);
+        m.set_gauge("analytics_total_profit_usd", &[], self.total_profit);
+        m.set_gauge("analytics_max_drawdown_usd", &[], self.max_drawdown);
+        let optional = [
+            ("analytics_win_rate", self.win_rate),
+            ("analytics_rolling_win_rate", self.rolling_win_rate),
+            ("analytics_edge_detected", self.avg_edge_detected),
+            ("analytics_edge_captured", self.avg_edge_captured),
+            ("analytics_sharpe", self.sharpe),
+        ];
+        for (name, value) in optional {
+            if let Some(value) = value {
+                m.set_gauge(name, &[], value);
+            }
+        }
+    }
+
+    pub fn log(&self) {
+        let pct = |v: Option<f64>| v.map_or("n/a".to_string(), |v| format!("{:.1}%", v * 100.0));
+        info!(
+            "📈 Performance - {} trades, win rate {} (last {}: {}), edge detected {} vs captured {}, Sharpe {}, max drawdown ${:.2}, profit ${:.2}",
+            self.trades,
+            pct(self.win_rate),
+            self.window,
+            pct(self.rolling_win_rate),
+            pct(self.avg_edge_detected),
+            pct(self.avg_edge_captured),
+            self.sharpe.map_or("n/a".to_string(), |s| format!("{:.2}", s)),
+            self.max_drawdown,
+            self.total_profit
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn trade(day: u32, profit: f64, edge: Option<f64>) -> TradeOutcome {
+        TradeOutcome {
+            closed_at: Utc.with_ymd_and_hms(2026, 1, day, 12, 0, 0).unwrap(),
+            cost: 100.0,
+            profit,
+            detected_edge: edge,
+        }
+    }
+
+    #[test]
+    fn reports_win_rate_edge_and_drawdown() {
+        let trades = [
+            trade(1, 10.0, Some(0.05)),
+            trade(1, -30.0, Some(0.05)),
+            trade(3, 5.0, None),
+            trade(4, 20.0, Some(0.02)),
+        ];
+        let report = analyze(&trades, 2);
+        assert_eq!(report.trades, 4);
+        assert_eq!(report.win_rate, Some(0.75));
+        assert_eq!(report.rolling_win_rate, Some(1.0));
+        assert!((report.avg_edge_detected.unwrap() - 0.04).abs() < 1e-9);
+        assert!((report.avg_edge_captured.unwrap() - 0.0).abs() < 1e-9);
+        // Peak 10 after the first trade, trough -20 after the second.
+        assert!((report.max_drawdown - 30.0).abs() < 1e-9);
+        assert_eq!(daily_pnl(&trades), vec![-20.0, 0.0, 5.0, 20.0]);
+        assert!(report.sharpe.is_some());
+        assert_eq!(sharpe(&[1.0, 1.0]), None);
+    }
+}
@@ -0,0 +1,281 @@
+//! Balance change auditing.
+//!
+//! Every `BALANCE_AUDIT_INTERVAL_SECS` (unset or 0 turns it off) each venue's
+//! balance is snapshotted and the change since the last snapshot is
+//! attributed to what the tracker recorded in between: the cost of positions
+//! opened, the payouts of positions sold or settled, and the fees charged. A
+//! won Polymarket position only counts once it's redeemed, since its payout
+//! isn't collateral before that. Whatever's left over beyond
+//! `BALANCE_AUDIT_TOLERANCE` dollars (default 1) is unexplained: more money
+//! than expected is most likely a deposit (or a redemption done by hand),
+//! less is a withdrawal - or a compromised key - and alerts as critical.
+//! Every snapshot is appended to `BALANCE_AUDIT_LOG` (default
+//! `logs/balance_audit.jsonl`) and the unexplained amount is exported as
+//! `balance_unexplained_usd{venue}`.
+//!
+//! Polymarket gas is paid in POL, not the USDC balance audited here; it's
+//! tracked per position in `gas_pol`. Money held by a resting order that
+//! hasn't filled yet shows up as unexplained until it does.
+
+use crate::exchange::ExchangeClient;
+use crate::metrics::metrics;
+use crate::money::UsdAmount;
+use crate::notify::{BotEvent, Notifier};
+use crate::position_tracker::{Position, PositionStatus, PositionTracker};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+const DEFAULT_TOLERANCE: f64 = 1.0;
+pub const DEFAULT_LOG_PATH: &str = "logs/balance_audit.jsonl";
+
+/// The balance moves the tracker accounts for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Flows {
+    /// Cost of positions opened.
+    pub trades: UsdAmount,
+    /// Proceeds of sales and settlement payouts.
+    pub payouts: UsdAmount,
+    pub fees: UsdAmount,
+}
+
+impl Flows {
+    pub fn net(&self) -> UsdAmount {
+        self.payouts - self.trades - self.fees
+    }
+}
+
+/// The payout once it's cash in the venue balance.
+fn cash_payout(position: &Position) -> Option<UsdAmount> {
+    if position.status == PositionStatus::Open {
+        return None;
+    }
+    let unredeemed = position.status == PositionStatus::Won && position.redemption_tx.is_none();
+    if position.platform == "polymarket" && unredeemed {
+        return None;
+    }
+    Some(position.payout.unwrap_or_default())
+}
+
+/// One venue's audit trail: the last balance and which positions' cash
+/// moves it already covers.
+pub struct VenueAudit {
+    platform: String,
+    balance: Option<UsdAmount>,
+    opened: HashSet<String>,
+    paid: HashSet<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    pub at: DateTime<Utc>,
+    pub platform: String,
+    pub previous: UsdAmount,
+    pub current: UsdAmount,
+    #[serde(flatten)]
+    pub flows: Flows,
+    pub unexplained: UsdAmount,
+}
+
+impl AuditEntry {
+    pub fn event(&self) -> BotEvent {
+        BotEvent::BalanceDiscrepancy {
+            platform: self.platform.clone(),
+            unexplained: self.unexplained.to_f64(),
+            previous: self.previous.to_f64(),
+            current: self.current.to_f64(),
+        }
+    }
+}
+
+impl VenueAudit {
+    pub fn new(platform: &str) -> Self {
+        Self { platform: platform.to_string(), balance: None, opened: HashSet::new(), paid: HashSet::new() }
+    }
+
+    /// The flows of `positions` not yet covered by an earlier snapshot.
+    fn take_flows(&mut self, positions: &[&Position]) -> Flows {
+        let mut flows = Flows::default();
+        for position in positions.iter().filter(|p| p.platform == self.platform) {
+            if self.opened.insert(position.id.clone()) {
+                flows.trades += position.cost;
+            }
+            if let Some(payout) = cash_payout(position) {
+                if self.paid.insert(position.id.clone()) {
+                    flows.payouts += payout;
+                    flows.fees += position.fees.unwrap_or_default();
+                }
+            }
+        }
+        flows
+    }
+
+    /// Records a balance snapshot. The first one is the baseline - whatever
+    /// the tracker holds then is taken as already in it - and returns `None`.
+    pub fn snapshot(&mut self, balance: f64, positions: &[&Position], at: DateTime<Utc>) -> Option<AuditEntry> {
+        let balance = UsdAmount::from_f64(balance);
+        let flows = self.take_flows(positions);
+        let previous = self.balance.replace(balance)?;
+        let unexplained = (balance - previous - flows.net()).round_cents();
+        Some(AuditEntry { at, platform: self.platform.clone(), previous, current: balance, flows, unexplained })
+    }
+}
+
+pub struct BalanceAuditor {
+    interval: Duration,
+    tolerance: f64,
+    log_path: PathBuf,
+}
+
+impl BalanceAuditor {
+    pub fn new(interval: Duration) -> Self {
+        Self { interval, tolerance: DEFAULT_TOLERANCE, log_path: PathBuf::from(DEFAULT_LOG_PATH) }
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn with_log_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.log_path = path.into();
+        self
+    }
+
+    /// `BALANCE_AUDIT_INTERVAL_SECS`, `BALANCE_AUDIT_TOLERANCE` and
+    /// `BALANCE_AUDIT_LOG`; `None` when the interval is unset or 0.
+    pub fn from_env() -> Option<Self> {
+        let secs = std::env::var("BALANCE_AUDIT_INTERVAL_SECS")
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .filter(|secs| *secs > 0)?;
+        let mut auditor = Self::new(Duration::from_secs(secs));
+        if let Some(tolerance) = std::env::var("BALANCE_AUDIT_TOLERANCE")
+            .ok()
+            .and_then(|s| s.trim().parse::<f64>().ok())
+            .filter(|t| *t >= 0.0)
+        {
+            auditor = auditor.with_tolerance(tolerance);
+        }
+        if let Ok(path) = std::env::var("BALANCE_AUDIT_LOG") {
+            if !path.trim().is_empty() {
+                auditor = auditor.with_log_path(path.trim());
+            }
+        }
+        Some(auditor)
+    }
+
+    fn append(&self, entry: &AuditEntry) -> Result<()> {
+        if let Some(dir) = self.log_path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log_path)
+            .with_context(|| format!("Failed to open {}", self.log_path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+
+    /// Snapshots each client's balance every interval and alerts on changes
+    /// the tracker doesn't explain.
+    pub fn spawn(
+        self,
+        clients: Vec<Arc<dyn ExchangeClient>>,
+        tracker: Arc<Mutex<PositionTracker>>,
+        notifier: Arc<Notifier>,
+    ) -> JoinHandle<()> {
+        info!(
+            "🧮 Auditing balances every {}s (tolerance ${:.2}) to {}",
+            self.interval.as_secs(),
+            self.tolerance,
+            self.log_path.display()
+        );
+        tokio::spawn(async move {
+            let mut audits: Vec<VenueAudit> = clients.iter().map(|c| VenueAudit::new(c.platform())).collect();
+            let mut ticker = tokio::time::interval(self.interval);
+            loop {
+                ticker.tick().await;
+                for (client, audit) in clients.iter().zip(audits.iter_mut()) {
+                    let balance = match client.get_balance().await {
+                        Ok(balance) => balance,
+                        Err(e) => {
+                            warn!("Balance audit of {} failed: {}", client.platform(), e);
+                            continue;
+                        }
+                    };
+                    let entry = {
+                        let tracker = tracker.lock().await;
+                        audit.snapshot(balance, &tracker.get_all_positions(), Utc::now())
+                    };
+                    let Some(entry) = entry else {
+                        continue;
+                    };
+                    metrics().set_gauge("balance_unexplained_usd", &[("venue", client.platform())], entry.unexplained.to_f64());
+                    if let Err(e) = self.append(&entry) {
+                        warn!("Failed to write the balance audit log: {}", e);
+                    }
+                    if entry.unexplained.abs() > UsdAmount::from_f64(self.tolerance) {
+                        warn!("🧮 {}", entry.event().message());
+                        notifier.notify(entry.event()).await;
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::position_tracker::Position;
+    use crate::Event;
+
+    #[test]
+    fn attributes_balance_changes_to_trades_and_payouts() {
+        let position = |platform: &str, cost: f64| {
+            let event = Event::new(platform.to_string(), "event".to_string(), String::new(), String::new());
+            let cost = UsdAmount::from_f64(cost);
+            Position::new(platform.to_string(), &event, "YES".to_string(), 10.0, cost, cost.per_share(10.0), None)
+        };
+        let held = position("kalshi", 5.0);
+        let mut audit = VenueAudit::new("kalshi");
+        assert!(audit.snapshot(100.0, &[&held], Utc::now()).is_none());
+
+        // A $4 buy, then the held position pays out $10 less $0.20 in fees.
+        let bought = position("kalshi", 4.0);
+        let mut settled = held.clone();
+        settled.status = PositionStatus::Won;
+        settled.payout = Some(UsdAmount::from_f64(10.0));
+        settled.fees = Some(UsdAmount::from_f64(0.2));
+        let other_venue = position("polymarket", 50.0);
+        let entry = audit.snapshot(105.8, &[&settled, &bought, &other_venue], Utc::now()).unwrap();
+        let usd = UsdAmount::from_f64;
+        assert_eq!(entry.flows, Flows { trades: usd(4.0), payouts: usd(10.0), fees: usd(0.2) });
+        assert_eq!(entry.unexplained, UsdAmount::ZERO);
+
+        // Nothing tracked moved, so $50 showing up is a deposit.
+        let entry = audit.snapshot(155.8, &[&settled, &bought], Utc::now()).unwrap();
+        assert_eq!(entry.unexplained, usd(50.0));
+        assert!(matches!(entry.event(), BotEvent::BalanceDiscrepancy { .. }));
+
+        // A won Polymarket position isn't cash until it's redeemed.
+        let mut won = position("polymarket", 6.0);
+        let mut audit = VenueAudit::new("polymarket");
+        audit.snapshot(20.0, &[&won], Utc::now());
+        won.status = PositionStatus::Won;
+        won.payout = Some(usd(10.0));
+        assert_eq!(audit.snapshot(20.0, &[&won], Utc::now()).unwrap().unexplained, UsdAmount::ZERO);
+        won.redemption_tx = Some("0xabc".to_string());
+        assert_eq!(audit.snapshot(30.0, &[&won], Utc::now()).unwrap().unexplained, UsdAmount::ZERO);
+    }
+}
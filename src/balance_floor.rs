@@ -0,0 +1,139 @@
+//! Low-balance pause per platform.
+//!
+//! `MIN_BALANCE_POLYMARKET` and `MIN_BALANCE_KALSHI` (dollars) set a floor
+//! per venue. Every `BALANCE_CHECK_INTERVAL_SECS` (default 60) the balances
+//! are read, and a venue under its floor is paused in `Controls`: no strategy
+//! opens positions there, so orders stop bouncing for insufficient funds,
+//! while exits and settlement carry on. The operator is alerted when a venue
+//! pauses and again when a top-up brings it back over the floor. A balance
+//! that can't be read leaves the venue as it was.
+
+use crate::control::Controls;
+use crate::exchange::ExchangeClient;
+use crate::metrics::metrics;
+use crate::notify::{BotEvent, Notifier};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+const DEFAULT_CHECK_SECS: u64 = 60;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceFloors {
+    /// Floor in dollars per platform.
+    floors: Vec<(&'static str, f64)>,
+    interval: Duration,
+}
+
+impl BalanceFloors {
+    pub fn new(interval: Duration) -> Self {
+        Self { floors: Vec::new(), interval }
+    }
+
+    pub fn with_floor(mut self, platform: &'static str, floor: f64) -> Self {
+        self.floors.push((platform, floor));
+        self
+    }
+
+    /// `MIN_BALANCE_POLYMARKET` / `MIN_BALANCE_KALSHI`; `None` when neither is set.
+    pub fn from_env() -> Option<Self> {
+        let parse = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|s| s.trim().parse::<f64>().ok())
+                .filter(|v| *v > 0.0)
+        };
+        let secs = std::env::var("BALANCE_CHECK_INTERVAL_SECS")
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_CHECK_SECS);
+        let mut floors = Self::new(Duration::from_secs(secs));
+        if let Some(floor) = parse("MIN_BALANCE_POLYMARKET") {
+            floors = floors.with_floor("polymarket", floor);
+        }
+        if let Some(floor) = parse("MIN_BALANCE_KALSHI") {
+            floors = floors.with_floor("kalshi", floor);
+        }
+        (!floors.floors.is_empty()).then_some(floors)
+    }
+
+    pub fn floor(&self, platform: &str) -> Option<f64> {
+        self.floors.iter().find(|(p, _)| *p == platform).map(|(_, floor)| *floor)
+    }
+
+    /// Pauses or resumes `platform` for one balance reading; returns the
+    /// alert when that changed anything.
+    pub fn apply(&self, controls: &Controls, platform: &str, balance: f64) -> Option<BotEvent> {
+        let floor = self.floor(platform)?;
+        let low = balance < floor;
+        metrics().set_gauge("balance_floor_paused", &[("venue", platform)], if low { 1.0 } else { 0.0 });
+        if !controls.set_platform_paused(platform, low) {
+            return None;
+        }
+        let platform = platform.to_string();
+        Some(if low {
+            BotEvent::LowBalance { platform, balance, floor }
+        } else {
+            BotEvent::BalanceRestored { platform, balance, floor }
+        })
+    }
+
+    /// Checks each client's balance against its floor every interval.
+    pub fn spawn(
+        self,
+        clients: Vec<Arc<dyn ExchangeClient>>,
+        controls: Arc<Controls>,
+        notifier: Arc<Notifier>,
+    ) -> JoinHandle<()> {
+        for (platform, floor) in &self.floors {
+            info!("🪫 Pausing new {} positions under a ${:.2} balance", platform, floor);
+        }
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.interval);
+            loop {
+                ticker.tick().await;
+                for client in &clients {
+                    if self.floor(client.platform()).is_none() {
+                        continue;
+                    }
+                    let balance = match client.get_balance().await {
+                        Ok(balance) => balance,
+                        Err(e) => {
+                            warn!("Balance floor check on {} failed: {}", client.platform(), e);
+                            continue;
+                        }
+                    };
+                    if let Some(event) = self.apply(&controls, client.platform(), balance) {
+                        match &event {
+                            BotEvent::LowBalance { .. } => warn!("🪫 {}", event.message()),
+                            _ => info!("🔋 {}", event.message()),
+                        }
+                        notifier.notify(event).await;
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pauses_under_the_floor_and_resumes_over_it() {
+        let floors = BalanceFloors::new(Duration::from_secs(60)).with_floor("kalshi", 50.0);
+        let controls = Controls::new();
+        assert!(floors.apply(&controls, "kalshi", 80.0).is_none());
+        assert!(matches!(floors.apply(&controls, "kalshi", 20.0), Some(BotEvent::LowBalance { .. })));
+        assert!(controls.is_platform_paused("kalshi"));
+        // Only the change alerts.
+        assert!(floors.apply(&controls, "kalshi", 10.0).is_none());
+        assert!(floors.apply(&controls, "polymarket", 0.0).is_none());
+        assert!(!controls.is_platform_paused("polymarket"));
+        assert!(matches!(floors.apply(&controls, "kalshi", 60.0), Some(BotEvent::BalanceRestored { .. })));
+        assert!(!controls.is_platform_paused("kalshi"));
+    }
+}
@@ -0,0 +1,142 @@
+//! Full-basket arbitrage on multi-outcome (Polymarket neg-risk) events.
+//!
+//! Exactly one outcome of a neg-risk event resolves YES, so one YES share of
+//! every outcome pays $1. When the YES asks sum to less than $1 after fees,
+//! buying the whole basket locks in the difference.
+
+use crate::event::{Event, EventOutcome, MarketPrices};
+use crate::money::Price;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct BasketLeg {
+    pub outcome: EventOutcome,
+    pub price: Price,
+}
+
+#[derive(Debug, Clone)]
+pub struct BasketOpportunity {
+    pub event: Event,
+    pub legs: Vec<BasketLeg>,
+    pub total_cost: Price,
+    pub fees: Price,
+    pub net_profit: Price,
+    pub roi_percent: f64,
+}
+
+pub struct BasketDetector {
+    min_profit_threshold: f64,
+    fee_per_leg: f64,
+}
+
+impl BasketDetector {
+    pub fn new(min_profit_threshold: f64) -> Self {
+        Self {
+            min_profit_threshold,
+            fee_per_leg: 0.01,
+        }
+    }
+
+    pub fn with_fee_per_leg(mut self, fee: f64) -> Self {
+        self.fee_per_leg = fee;
+        self
+    }
+
+    /// `prices` is keyed by outcome market id. Every outcome needs a YES ask;
+    /// a basket with a missing leg is not a hedge.
+    pub fn check_basket(
+        &self,
+        event: &Event,
+        prices: &HashMap<String, MarketPrices>,
+    ) -> Option<BasketOpportunity> {
+        if !event.is_multi_outcome() {
+            return None;
+        }
+
+        let mut legs = Vec::with_capacity(event.outcomes.len());
+        for outcome in &event.outcomes {
+            let price = prices.get(&outcome.market_id)?.yes_ask;
+            if price <= Price::ZERO || price >= Price::ONE {
+                return None;
+            }
+            legs.push(BasketLeg {
+                outcome: outcome.clone(),
+                price,
+            });
+        }
+
+        let total_cost: Price = legs.iter().map(|l| l.price).sum();
+        let fees: Price = legs.iter().map(|_| Price::from_f64(self.fee_per_leg)).sum();
+        let net_profit = total_cost.complement() - fees;
+        if net_profit <= Price::from_f64(self.min_profit_threshold) {
+            return None;
+        }
+
+        Some(BasketOpportunity {
+            event: event.clone(),
+            legs,
+            total_cost,
+            fees,
+            net_profit,
+            roi_percent: net_profit.ratio(total_cost) * 100.0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn election(names: &[&str]) -> Event {
+        let outcomes = names
+            .iter()
+            .map(|name| EventOutcome {
+                name: name.to_string(),
+                market_id: format!("0x{}", name.to_lowercase()),
+                yes_token_id: None,
+                no_token_id: None,
+            })
+            .collect();
+        Event::new(
+            "polymarket".to_string(),
+            "0xelection".to_string(),
+            "Who will win?".to_string(),
+            String::new(),
+        )
+        .with_outcomes(outcomes)
+    }
+
+    fn priced(asks: &[(&str, f64)]) -> HashMap<String, MarketPrices> {
+        asks.iter()
+            .map(|(name, ask)| {
+                (
+                    format!("0x{}", name.to_lowercase()),
+                    MarketPrices::new(*ask, 1.0 - ask, 1000.0),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn buys_basket_when_outcomes_sum_below_one() {
+        let event = election(&["Alice", "Bob", "Carol"]);
+        let prices = priced(&[("Alice", 0.50), ("Bob", 0.30), ("Carol", 0.12)]);
+
+        let opp = BasketDetector::new(0.02).check_basket(&event, &prices).unwrap();
+        assert_eq!(opp.legs.len(), 3);
+        assert_eq!(opp.total_cost, Price::from_cents(92));
+        assert_eq!(opp.net_profit, Price::from_cents(5));
+    }
+
+    #[test]
+    fn missing_or_expensive_legs_are_skipped() {
+        let event = election(&["Alice", "Bob", "Carol"]);
+        let detector = BasketDetector::new(0.0);
+
+        let partial = priced(&[("Alice", 0.50), ("Bob", 0.30)]);
+        assert!(detector.check_basket(&event, &partial).is_none());
+
+        let fair = priced(&[("Alice", 0.50), ("Bob", 0.30), ("Carol", 0.20)]);
+        assert!(detector.check_basket(&event, &fair).is_none());
+    }
+}
@@ -13,9 +13,9 @@ const DEFAULT_INTERVAL_MS: u64 = 2000;
 const BTC_SERIES_TICKER: &str = "KXBTC15M";
 
 fn format_prices_line(ticker: &str, p: &MarketPrices) -> String {
-    let up_ask = p.yes_ask_or_fallback();
-    let down_ask = p.no_ask_or_fallback();
-    let last = p.last_price.unwrap_or((up_ask + down_ask) * 0.5);
+    let up_ask = p.yes_ask.to_f64();
+    let down_ask = p.no_ask.to_f64();
+    let last = p.last_price.map_or((up_ask + down_ask) * 0.5, |last| last.to_f64());
     format!(
         "UP ask={:.2}  |  DOWN ask={:.2}  |  last={:.2}  @ {}",
         up_ask,
@@ -0,0 +1,354 @@
+//! Venue order-book websockets that trigger per-pair scans.
+//!
+//! With `BOOK_FEED=polymarket,kalshi` (either or both), a task per venue
+//! subscribes to the books of the pairs matched by the latest cross-platform
+//! scan: Polymarket's public CLOB market channel (`POLYMARKET_WS_URL`) and
+//! Kalshi's `orderbook_delta` channel (signed with the API key). Every book
+//! update drops that market's cached prices and is published as a
+//! `BookUpdate`, on which the cross-platform strategy re-prices just that pair
+//! instead of waiting for the next timed scan. Updates arriving while a pair
+//! is being evaluated are coalesced.
+
+use crate::clients::{KalshiClient, PolymarketClient};
+use crate::event::Event;
+use crate::health::health;
+use anyhow::{Context, Result};
+use futures::{SinkExt, StreamExt};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{HeaderName, HeaderValue};
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, info, warn};
+
+const POLYMARKET_WS_URL: &str = "wss://ws-subscriptions-clob.polymarket.com/ws/market";
+/// Polymarket drops market-channel connections that send nothing for a while.
+const POLYMARKET_PING: Duration = Duration::from_secs(10);
+const UPDATE_CHANNEL_CAPACITY: usize = 1024;
+const MAX_BACKOFF_SECS: u64 = 30;
+
+/// A book changed on one side of a matched pair.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookUpdate {
+    pub platform: &'static str,
+    /// Polymarket condition id or Kalshi event ticker, as in `Event::event_id`.
+    pub event_id: String,
+}
+
+/// Venue market ids to subscribe to, mapped to the event ids they price.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Subscriptions {
+    /// Token id -> condition id.
+    polymarket: BTreeMap<String, String>,
+    /// Market ticker -> event ticker.
+    kalshi: BTreeMap<String, String>,
+}
+
+/// Condition ids touched by a Polymarket market-channel message (a single
+/// event or an array of them); `book` and `price_change` events count.
+pub fn parse_polymarket_message(text: &str, tokens: &BTreeMap<String, String>) -> Vec<String> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(text) else {
+        return Vec::new();
+    };
+    let messages = match value {
+        serde_json::Value::Array(items) => items,
+        other => vec![other],
+    };
+    let mut markets = Vec::new();
+    for message in messages {
+        if !matches!(message["event_type"].as_str(), Some("book" | "price_change")) {
+            continue;
+        }
+        let market = message["market"].as_str().map(String::from).or_else(|| {
+            let asset = message["asset_id"]
+                .as_str()
+                .or_else(|| message["price_changes"][0]["asset_id"].as_str())?;
+            tokens.get(asset).cloned()
+        });
+        if let Some(market) = market.filter(|m| !markets.contains(m)) {
+            markets.push(market);
+        }
+    }
+    markets
+}
+
+/// Market ticker of a Kalshi `orderbook_snapshot` or `orderbook_delta`.
+pub fn parse_kalshi_message(text: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    if !matches!(value["type"].as_str(), Some("orderbook_snapshot" | "orderbook_delta")) {
+        return None;
+    }
+    value["msg"]["market_ticker"].as_str().map(String::from)
+}
+
+pub struct BookFeed {
+    polymarket: Option<Arc<PolymarketClient>>,
+    kalshi: Option<Arc<KalshiClient>>,
+    polymarket_ws_url: String,
+    updates: broadcast::Sender<BookUpdate>,
+    subscriptions: watch::Sender<Subscriptions>,
+}
+
+impl Default for BookFeed {
+    fn default() -> Self {
+        Self {
+            polymarket: None,
+            kalshi: None,
+            polymarket_ws_url: POLYMARKET_WS_URL.to_string(),
+            updates: broadcast::channel(UPDATE_CHANNEL_CAPACITY).0,
+            subscriptions: watch::channel(Subscriptions::default()).0,
+        }
+    }
+}
+
+impl BookFeed {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_polymarket(mut self, client: Arc<PolymarketClient>) -> Self {
+        self.polymarket = Some(client);
+        self
+    }
+
+    pub fn with_kalshi(mut self, client: Arc<KalshiClient>) -> Self {
+        self.kalshi = Some(client);
+        self
+    }
+
+    /// `BOOK_FEED`: the venues to stream (`polymarket`, `kalshi`), with
+    /// `POLYMARKET_WS_URL` overriding the Polymarket endpoint.
+    pub fn from_env(polymarket: Arc<PolymarketClient>, kalshi: Arc<KalshiClient>) -> Option<Self> {
+        let venues: Vec<String> = std::env::var("BOOK_FEED")
+            .unwrap_or_default()
+            .split(',')
+            .map(|v| v.trim().to_lowercase())
+            .filter(|v| !v.is_empty())
+            .collect();
+        let mut feed = Self::new();
+        for venue in &venues {
+            match venue.as_str() {
+                "polymarket" => feed = feed.with_polymarket(polymarket.clone()),
+                "kalshi" => feed = feed.with_kalshi(kalshi.clone()),
+                other => warn!("⚠️ Unknown venue '{}' in BOOK_FEED - ignoring", other),
+            }
+        }
+        if let Ok(url) = std::env::var("POLYMARKET_WS_URL") {
+            feed.polymarket_ws_url = url.trim().to_string();
+        }
+        (feed.polymarket.is_some() || feed.kalshi.is_some()).then_some(feed)
+    }
+
+    pub fn venues(&self) -> Vec<&'static str> {
+        let mut venues = Vec::new();
+        if self.polymarket.is_some() {
+            venues.push("polymarket");
+        }
+        if self.kalshi.is_some() {
+            venues.push("kalshi");
+        }
+        venues
+    }
+
+    /// Book updates, for `StrategyRegistry::spawn_updates`.
+    pub fn updates(&self) -> &broadcast::Sender<BookUpdate> {
+        &self.updates
+    }
+
+    /// Follows the books of these pairs from now on; the venue connections
+    /// resubscribe when the set changes.
+    pub async fn watch_pairs(&self, pairs: &[(Event, Event)]) {
+        let mut subscriptions = Subscriptions::default();
+        for (pm_event, kalshi_event) in pairs {
+            if self.polymarket.is_some() {
+                for token in [&pm_event.yes_token_id, &pm_event.no_token_id].into_iter().flatten() {
+                    subscriptions.polymarket.insert(token.clone(), pm_event.event_id.clone());
+                }
+            }
+            if let Some(kalshi) = &self.kalshi {
+                match kalshi.market_ticker(&kalshi_event.event_id).await {
+                    Ok(ticker) => {
+                        subscriptions.kalshi.insert(ticker, kalshi_event.event_id.clone());
+                    }
+                    Err(e) => debug!("No Kalshi market ticker for {}: {}", kalshi_event.event_id, e),
+                }
+            }
+        }
+        self.subscriptions.send_if_modified(|current| {
+            let changed = *current != subscriptions;
+            *current = subscriptions;
+            changed
+        });
+    }
+
+    /// Drops the market's cached prices and tells the strategies.
+    async fn publish(&self, platform: &'static str, event_id: String) {
+        match platform {
+            "kalshi" => {
+                if let Some(kalshi) = &self.kalshi {
+                    kalshi.invalidate_prices(&event_id).await;
+                }
+            }
+            _ => {
+                if let Some(polymarket) = &self.polymarket {
+                    polymarket.invalidate_prices(&event_id).await;
+                }
+            }
+        }
+        // No receivers just means no strategy follows books yet.
+        let _ = self.updates.send(BookUpdate { platform, event_id });
+    }
+
+    /// Streams each enabled venue forever, reconnecting with exponential
+    /// backoff and whenever the watched pairs change.
+    pub fn spawn(self: Arc<Self>) -> Vec<JoinHandle<()>> {
+        self.venues()
+            .into_iter()
+            .map(|venue| {
+                let feed = self.clone();
+                tokio::spawn(async move { feed.run(venue).await })
+            })
+            .collect()
+    }
+
+    async fn run(&self, venue: &'static str) {
+        let name = format!("{}_book", venue);
+        let mut subscriptions = self.subscriptions.subscribe();
+        let mut backoff = 1;
+        loop {
+            let current = subscriptions.borrow_and_update().clone();
+            let watched = match venue {
+                "kalshi" => current.kalshi.len(),
+                _ => current.polymarket.len(),
+            };
+            if watched == 0 {
+                health().set_websocket(&name, false);
+                if subscriptions.changed().await.is_err() {
+                    return;
+                }
+                continue;
+            }
+            let result = match venue {
+                "kalshi" => self.stream_kalshi(&current.kalshi, &mut subscriptions, &name).await,
+                _ => self.stream_polymarket(&current.polymarket, &mut subscriptions, &name).await,
+            };
+            health().set_websocket(&name, false);
+            match result {
+                // Resubscribing to a new pair set.
+                Ok(true) => {
+                    backoff = 1;
+                    continue;
+                }
+                Ok(false) => warn!("📖 {} book feed disconnected - reconnecting", venue),
+                Err(e) => warn!("📖 {} book feed failed: {:#} (retry in {}s)", venue, e, backoff),
+            }
+            tokio::time::sleep(Duration::from_secs(backoff)).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF_SECS);
+        }
+    }
+
+    /// Returns `Ok(true)` when the watched pairs changed, `Ok(false)` when the
+    /// venue closed the stream.
+    async fn stream_polymarket(
+        &self,
+        tokens: &BTreeMap<String, String>,
+        subscriptions: &mut watch::Receiver<Subscriptions>,
+        name: &str,
+    ) -> Result<bool> {
+        let (mut stream, _) = tokio_tungstenite::connect_async(self.polymarket_ws_url.as_str())
+            .await
+            .context("connect")?;
+        let assets: Vec<&String> = tokens.keys().collect();
+        stream
+            .send(Message::Text(json!({ "assets_ids": assets, "type": "market" }).to_string()))
+            .await
+            .context("subscribe")?;
+        info!("📖 Polymarket book feed: {} tokens", assets.len());
+        health().set_websocket(name, true);
+        let mut ping = tokio::time::interval(POLYMARKET_PING);
+        loop {
+            tokio::select! {
+                changed = subscriptions.changed() => return Ok(changed.is_ok()),
+                _ = ping.tick() => stream.send(Message::Text("PING".to_string())).await.context("ping")?,
+                message = stream.next() => match message {
+                    Some(Ok(Message::Text(text))) => {
+                        for market in parse_polymarket_message(&text, tokens) {
+                            self.publish("polymarket", market).await;
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None => return Ok(false),
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(e.into()),
+                },
+            }
+        }
+    }
+
+    async fn stream_kalshi(
+        &self,
+        tickers: &BTreeMap<String, String>,
+        subscriptions: &mut watch::Receiver<Subscriptions>,
+        name: &str,
+    ) -> Result<bool> {
+        let Some(kalshi) = &self.kalshi else {
+            return Ok(false);
+        };
+        let mut request = kalshi.websocket_url().into_client_request().context("websocket url")?;
+        for (key, value) in kalshi.websocket_headers()?.iter() {
+            request.headers_mut().insert(
+                HeaderName::from_bytes(key.as_str().as_bytes())?,
+                HeaderValue::from_bytes(value.as_bytes())?,
+            );
+        }
+        let (mut stream, _) = tokio_tungstenite::connect_async(request).await.context("connect")?;
+        let market_tickers: Vec<&String> = tickers.keys().collect();
+        let subscribe = json!({
+            "id": 1,
+            "cmd": "subscribe",
+            "params": { "channels": ["orderbook_delta"], "market_tickers": market_tickers },
+        });
+        stream.send(Message::Text(subscribe.to_string())).await.context("subscribe")?;
+        info!("📖 Kalshi book feed: {} markets", market_tickers.len());
+        health().set_websocket(name, true);
+        loop {
+            tokio::select! {
+                changed = subscriptions.changed() => return Ok(changed.is_ok()),
+                message = stream.next() => match message {
+                    Some(Ok(Message::Text(text))) => {
+                        if let Some(event_id) = parse_kalshi_message(&text).and_then(|t| tickers.get(&t).cloned()) {
+                            self.publish("kalshi", event_id).await;
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None => return Ok(false),
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(e.into()),
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_book_messages() {
+        let tokens = BTreeMap::from([("111".to_string(), "0xabc".to_string())]);
+        let book = r#"[{"event_type":"book","asset_id":"111","market":"0xabc","bids":[],"asks":[]}]"#;
+        assert_eq!(parse_polymarket_message(book, &tokens), vec!["0xabc"]);
+        let change = r#"{"event_type":"price_change","price_changes":[{"asset_id":"111","price":"0.5"}]}"#;
+        assert_eq!(parse_polymarket_message(change, &tokens), vec!["0xabc"]);
+        let trade = r#"{"event_type":"last_trade_price","market":"0xabc"}"#;
+        assert!(parse_polymarket_message(trade, &tokens).is_empty());
+        assert!(parse_polymarket_message("PONG", &tokens).is_empty());
+
+        let delta = r#"{"type":"orderbook_delta","sid":1,"seq":2,"msg":{"market_ticker":"KXBTC15M-A","price":45,"delta":10,"side":"yes"}}"#;
+        assert_eq!(parse_kalshi_message(delta).as_deref(), Some("KXBTC15M-A"));
+        assert_eq!(parse_kalshi_message(r#"{"type":"subscribed","msg":{"channel":"orderbook_delta"}}"#), None);
+    }
+}
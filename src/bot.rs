@@ -1,11 +1,29 @@
 use crate::arbitrage_detector::{ArbitrageDetector, ArbitrageOpportunity};
+use crate::confidence::ConfidenceModel;
+use crate::debug_capture::DebugCapture;
+use crate::embeddings::EmbeddingClient;
 use crate::event::{Event, MarketPrices};
 use crate::event_matcher::EventMatcher;
 use crate::gabagool_detector::{GabagoolDetector, GabagoolOpportunity};
+use crate::latency::EdgeDecay;
+use crate::mappings::MarketMappings;
+use crate::match_store::{MatchSource, MatchStore};
+use crate::money::{Price, UsdAmount};
+use crate::metrics::metrics;
+use crate::recorder::MarketRecorder;
+use crate::resolution_rules::{self, RulesCheck};
+use crate::shadow::{LiveSettings, ShadowEvaluator};
+use crate::skip_reason::{SkipCounts, SkipReason};
+use crate::spread::SpreadLimits;
+use crate::strike;
 use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::Duration as StdDuration;
 use tokio::time;
 
+#[derive(Debug, Clone)]
 pub struct MarketFilters {
     pub categories: Vec<String>,
     pub max_hours_until_resolution: i64,
@@ -24,11 +42,126 @@ impl Default for MarketFilters {
     }
 }
 
+/// A matched pair's quotes from the latest scan.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PairQuote {
+    pub polymarket_id: String,
+    pub polymarket_title: String,
+    pub kalshi_id: String,
+    pub score: f64,
+    pub pm_yes_ask: Price,
+    pub pm_no_ask: Price,
+    pub kalshi_yes_ask: Price,
+    pub kalshi_no_ask: Price,
+    /// Widest YES bid-ask spread of the two venues.
+    pub spread: Price,
+    /// Cheaper of the two ways to hold both outcomes, before fees.
+    pub pair_cost: Price,
+    /// Liquid enough and within spread limits to trade.
+    pub tradable: bool,
+}
+
+impl PairQuote {
+    fn new(
+        pm_event: &Event,
+        kalshi_event: &Event,
+        score: f64,
+        pm_prices: &MarketPrices,
+        kalshi_prices: &MarketPrices,
+        inverted: bool,
+        tradable: bool,
+    ) -> Self {
+        let (pm_yes_pair, pm_no_pair) = if inverted {
+            (kalshi_prices.yes_ask, kalshi_prices.no_ask)
+        } else {
+            (kalshi_prices.no_ask, kalshi_prices.yes_ask)
+        };
+        let pair_cost = (pm_prices.yes_ask + pm_yes_pair).min(pm_prices.no_ask + pm_no_pair);
+        Self {
+            polymarket_id: pm_event.event_id.clone(),
+            polymarket_title: pm_event.title.clone(),
+            kalshi_id: kalshi_event.event_id.clone(),
+            score,
+            pm_yes_ask: pm_prices.yes_ask,
+            pm_no_ask: pm_prices.no_ask,
+            kalshi_yes_ask: kalshi_prices.yes_ask,
+            kalshi_no_ask: kalshi_prices.no_ask,
+            spread: pm_prices.spread("YES").max(kalshi_prices.spread("YES")),
+            pair_cost,
+            tradable,
+        }
+    }
+
+    /// Gross edge of holding both outcomes: what $1 pays minus the pair cost.
+    pub fn edge(&self) -> Price {
+        self.pair_cost.complement()
+    }
+}
+
+/// First-seen time per market. Markets already listed on a platform's first
+/// scan count as established, since their listing time is unknown.
+#[derive(Default)]
+struct FirstSeen {
+    /// (platform, event id) -> (first seen, listed before the first scan)
+    at: HashMap<(String, String), (DateTime<Utc>, bool)>,
+    platforms: HashSet<String>,
+}
+
+/// Delisted markets' first-seen entries are dropped after this long.
+const FIRST_SEEN_RETENTION_HOURS: i64 = 24;
+/// Default `MAX_PRICE_AGE_SECS`.
+pub const DEFAULT_MAX_PRICE_AGE_SECS: i64 = 30;
+
+/// Re-trade suppression for matched pairs. A pair is skipped for `cooldown`
+/// after it trades, and after that for as long as its combined cost stays in
+/// the price band it last traded in: the same quote is the same
+/// opportunity, and trading it again only stacks exposure on one event.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TradeDedup {
+    pub cooldown: Duration,
+    /// Band width in cents; 0 disables the price-band check.
+    pub band_cents: i64,
+}
+
+/// (pm event id, kalshi event id) -> (last trade, its price band)
+type LastTraded = HashMap<(String, String), (DateTime<Utc>, Option<i64>)>;
+
+impl TradeDedup {
+    fn band(&self, opportunity: &ArbitrageOpportunity) -> Option<i64> {
+        (self.band_cents > 0)
+            .then(|| opportunity.total_cost.cents().div_euclid(self.band_cents))
+    }
+}
+
 pub struct ShortTermArbitrageBot {
     filters: MarketFilters,
     event_matcher: EventMatcher,
     arbitrage_detector: ArbitrageDetector,
     gabagool_detector: GabagoolDetector,
+    recorder: Option<Arc<MarketRecorder>>,
+    debug_capture: Option<Arc<DebugCapture>>,
+    spread_limits: Option<SpreadLimits>,
+    edge_decay: Option<EdgeDecay>,
+    shadow: Option<ShadowEvaluator>,
+    min_profit_threshold: f64,
+    warmup: Duration,
+    resolution_window: (Duration, Duration),
+    first_seen: RwLock<FirstSeen>,
+    dedup: Option<TradeDedup>,
+    last_traded: RwLock<LastTraded>,
+    time_override: RwLock<Option<DateTime<Utc>>>,
+    confirmed: Option<Arc<MatchStore>>,
+    rules_check: RulesCheck,
+    /// Quotes older than this are skipped; `None` takes any age.
+    max_price_age: Option<Duration>,
+    /// Pairs already flagged for differing settlement sources.
+    flagged_rules: RwLock<HashSet<(String, String)>>,
+    latest_pairs: RwLock<Vec<PairQuote>>,
+    /// Pairs matched by the latest cross-platform scan, with their scores.
+    matched: RwLock<Vec<(Event, Event, f64)>>,
+    /// Skips of the cross-platform / Gabagool scans since they were last taken.
+    arbitrage_skips: Mutex<SkipCounts>,
+    gabagool_skips: Mutex<SkipCounts>,
 }
 
 impl ShortTermArbitrageBot {
@@ -42,15 +175,348 @@ impl ShortTermArbitrageBot {
             event_matcher: EventMatcher::new(similarity_threshold),
             arbitrage_detector: ArbitrageDetector::new(min_profit_threshold),
             gabagool_detector: GabagoolDetector::new(min_profit_threshold),
+            recorder: None,
+            debug_capture: None,
+            spread_limits: None,
+            edge_decay: None,
+            shadow: None,
+            min_profit_threshold,
+            warmup: Duration::zero(),
+            resolution_window: (Duration::minutes(10), Duration::minutes(30)),
+            first_seen: RwLock::new(FirstSeen::default()),
+            dedup: None,
+            last_traded: RwLock::new(HashMap::new()),
+            time_override: RwLock::new(None),
+            confirmed: None,
+            rules_check: RulesCheck::default(),
+            max_price_age: Some(Duration::seconds(DEFAULT_MAX_PRICE_AGE_SECS)),
+            flagged_rules: RwLock::new(HashSet::new()),
+            latest_pairs: RwLock::new(Vec::new()),
+            matched: RwLock::new(Vec::new()),
+            arbitrage_skips: Mutex::new(SkipCounts::default()),
+            gabagool_skips: Mutex::new(SkipCounts::default()),
+        }
+    }
+
+    /// What the cross-platform scans skipped since the last call.
+    pub fn take_arbitrage_skips(&self) -> SkipCounts {
+        std::mem::take(&mut *self.arbitrage_skips.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// What the Gabagool scans skipped since the last call.
+    pub fn take_gabagool_skips(&self) -> SkipCounts {
+        std::mem::take(&mut *self.gabagool_skips.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Matched pairs and their quotes from the latest cross-platform scan.
+    pub fn latest_pairs(&self) -> Vec<PairQuote> {
+        self.latest_pairs.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Pairs matched by the latest cross-platform scan.
+    pub fn matched_pairs(&self) -> Vec<(Event, Event)> {
+        self.matched
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(pm_event, kalshi_event, _)| (pm_event.clone(), kalshi_event.clone()))
+            .collect()
+    }
+
+    fn clear_matches(&self) {
+        self.matched.write().unwrap_or_else(|e| e.into_inner()).clear();
+        self.latest_pairs.write().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    /// Skips quotes fetched longer than `max_age` ago (by the bot's clock,
+    /// so replays compare against the recorded time); `None` takes any age.
+    pub fn with_max_price_age(mut self, max_age: Option<Duration>) -> Self {
+        self.max_price_age = max_age;
+        self
+    }
+
+    pub fn with_recorder(mut self, recorder: Arc<MarketRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    pub fn with_debug_capture(mut self, capture: Arc<DebugCapture>) -> Self {
+        self.debug_capture = Some(capture);
+        self
+    }
+
+    /// Skip markets whose bid-ask spread on either venue exceeds its limit.
+    pub fn with_spread_limits(mut self, limits: SpreadLimits) -> Self {
+        self.spread_limits = Some(limits);
+        self
+    }
+
+    /// Raises the cross-platform minimum profit by the edge the venues'
+    /// observed latency costs.
+    pub fn with_edge_decay(mut self, decay: EdgeDecay) -> Self {
+        self.edge_decay = Some(decay);
+        self
+    }
+
+    /// Also evaluate every matched pair under shadow thresholds (logged only).
+    pub fn with_shadow(mut self, shadow: ShadowEvaluator) -> Self {
+        self.shadow = Some(shadow);
+        self
+    }
+
+    pub fn shadow(&self) -> Option<&ShadowEvaluator> {
+        self.shadow.as_ref()
+    }
+
+    /// Don't trade a market until it has been listed for `warmup`: opening
+    /// quotes on new markets are often placeholders.
+    pub fn with_warmup(mut self, warmup: Duration) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// Only trade markets resolving between `min` and `max` from now
+    /// (default 10-30 minutes).
+    pub fn with_resolution_window(mut self, min: Duration, max: Duration) -> Self {
+        self.resolution_window = (min, max);
+        self
+    }
+
+    /// How far apart matched markets' resolution dates may be (`None` = unchecked).
+    pub fn with_resolution_tolerance(mut self, tolerance: Option<Duration>) -> Self {
+        self.event_matcher = self.event_matcher.with_resolution_tolerance(tolerance);
+        self
+    }
+
+    /// Blend title embeddings into match scores; see [`EmbeddingClient`].
+    pub fn with_embeddings(mut self, embeddings: Arc<EmbeddingClient>) -> Self {
+        self.event_matcher = self.event_matcher.with_embeddings(embeddings);
+        self
+    }
+
+    /// Pinned and denied pairs from the mappings file.
+    pub fn with_mappings(mut self, mappings: MarketMappings) -> Self {
+        self.event_matcher = self.event_matcher.with_mappings(mappings);
+        self
+    }
+
+    /// Stop re-detecting pairs that were just traded; see [`TradeDedup`].
+    pub fn with_trade_dedup(mut self, dedup: TradeDedup) -> Self {
+        self.dedup = Some(dedup);
+        self
+    }
+
+    /// Pairs confirmed by trading (or by hand) are matched straight from
+    /// `store` instead of being re-scored; traded pairs are added to it and
+    /// pairs it denies are never matched.
+    pub fn with_match_store(mut self, store: Arc<MatchStore>) -> Self {
+        self.event_matcher = self.event_matcher.with_denied_pairs(store.clone());
+        self.confirmed = Some(store);
+        self
+    }
+
+    /// What to do with matched pairs whose rules name different settlement
+    /// sources; see [`resolution_rules`].
+    pub fn with_rules_check(mut self, check: RulesCheck) -> Self {
+        self.rules_check = check;
+        self
+    }
+
+    /// False when the pair settles on different price sources and the rules
+    /// check blocks it. Each such pair is logged once.
+    fn settlement_sources_agree(&self, pm_event: &Event, kalshi_event: &Event) -> bool {
+        if self.rules_check == RulesCheck::Off {
+            return true;
+        }
+        let Some(reason) = resolution_rules::settlement_mismatch(pm_event, kalshi_event) else {
+            return true;
+        };
+        let key = (pm_event.event_id.clone(), kalshi_event.event_id.clone());
+        let mut flagged = self.flagged_rules.write().unwrap_or_else(|e| e.into_inner());
+        if flagged.len() > 10_000 {
+            flagged.clear();
+        }
+        if flagged.insert(key) {
+            tracing::warn!("⚖️ {} ↔ {}: {}", pm_event.title, kalshi_event.event_id, reason);
+            metrics().inc_counter("settlement_source_mismatches_total", &[]);
+        }
+        self.rules_check != RulesCheck::Block
+    }
+
+    /// Confirmed pairs among the events, plus fuzzy matches among the rest.
+    fn match_events(&self, pm_events: &[Event], kalshi_events: &[Event]) -> Vec<(Event, Event, f64)> {
+        let Some(store) = self.confirmed.as_ref().filter(|s| !s.is_empty()) else {
+            return self.event_matcher.find_matches(pm_events, kalshi_events);
+        };
+        let mut confirmed = Vec::new();
+        for pm_event in pm_events {
+            let Some(kalshi_id) = store.kalshi_for(&pm_event.event_id) else {
+                continue;
+            };
+            if let Some(kalshi_event) = kalshi_events.iter().find(|k| k.event_id == kalshi_id) {
+                confirmed.push((pm_event.clone(), kalshi_event.clone(), 1.0));
+            }
+        }
+        let pm_taken: HashSet<&str> = confirmed.iter().map(|(pm, _, _)| pm.event_id.as_str()).collect();
+        let kalshi_taken: HashSet<&str> = confirmed.iter().map(|(_, k, _)| k.event_id.as_str()).collect();
+        let pm_rest: Vec<Event> = pm_events
+            .iter()
+            .filter(|e| !pm_taken.contains(e.event_id.as_str()))
+            .cloned()
+            .collect();
+        let kalshi_rest: Vec<Event> = kalshi_events
+            .iter()
+            .filter(|e| !kalshi_taken.contains(e.event_id.as_str()))
+            .cloned()
+            .collect();
+        let fuzzy = if pm_rest.is_empty() || kalshi_rest.is_empty() {
+            Vec::new()
+        } else {
+            self.event_matcher.find_matches(&pm_rest, &kalshi_rest)
+        };
+        confirmed.extend(fuzzy);
+        confirmed
+    }
+
+    /// Records that a pair traded (or partly filled) at `opportunity`.
+    pub fn record_trade(&self, pm_event: &Event, kalshi_event: &Event, opportunity: &ArbitrageOpportunity) {
+        if let Some(store) = &self.confirmed {
+            store.confirm(&pm_event.event_id, &kalshi_event.event_id, MatchSource::Traded);
         }
+        let Some(dedup) = &self.dedup else {
+            return;
+        };
+        let now = self.now();
+        let mut last_traded = self.last_traded.write().unwrap_or_else(|e| e.into_inner());
+        let cutoff = now - Duration::hours(FIRST_SEEN_RETENTION_HOURS);
+        last_traded.retain(|_, (at, _)| *at >= cutoff);
+        last_traded.insert(
+            (pm_event.event_id.clone(), kalshi_event.event_id.clone()),
+            (now, dedup.band(opportunity)),
+        );
+    }
+
+    /// Why `opportunity` repeats a recent trade on the same pair, if it does.
+    fn repeat_of_recent_trade(
+        &self,
+        pm_event: &Event,
+        kalshi_event: &Event,
+        opportunity: &ArbitrageOpportunity,
+    ) -> Option<&'static str> {
+        let dedup = self.dedup.as_ref()?;
+        let last_traded = self.last_traded.read().unwrap_or_else(|e| e.into_inner());
+        let (at, band) = last_traded.get(&(pm_event.event_id.clone(), kalshi_event.event_id.clone()))?;
+        if self.now() - *at < dedup.cooldown {
+            Some("cooldown")
+        } else if band.is_some() && *band == dedup.band(opportunity) {
+            Some("already traded at this price")
+        } else {
+            None
+        }
+    }
+
+    /// When the bot first saw the market; `None` for markets that were
+    /// already listed on the platform's first scan.
+    pub fn first_seen(&self, event: &Event) -> Option<DateTime<Utc>> {
+        let first_seen = self.first_seen.read().unwrap_or_else(|e| e.into_inner());
+        first_seen
+            .at
+            .get(&(event.platform.clone(), event.event_id.clone()))
+            .filter(|(_, established)| !established)
+            .map(|(seen, _)| *seen)
+    }
+
+    /// Records first-seen times for `events`.
+    fn observe_listings(&self, events: &[Event]) {
+        let now = self.now();
+        let mut first_seen = self.first_seen.write().unwrap_or_else(|e| e.into_inner());
+        for event in events {
+            let established = !first_seen.platforms.contains(&event.platform);
+            first_seen
+                .at
+                .entry((event.platform.clone(), event.event_id.clone()))
+                .or_insert((now, established));
+        }
+        let listed: HashSet<(&str, &str)> = events
+            .iter()
+            .map(|e| (e.platform.as_str(), e.event_id.as_str()))
+            .collect();
+        let platforms: HashSet<&str> = listed.iter().map(|(platform, _)| *platform).collect();
+        // Forget delisted markets once they are old enough to have settled.
+        let cutoff = now - Duration::hours(FIRST_SEEN_RETENTION_HOURS);
+        first_seen.at.retain(|(platform, id), (seen, _)| {
+            *seen >= cutoff
+                || !platforms.contains(platform.as_str())
+                || listed.contains(&(platform.as_str(), id.as_str()))
+        });
+        first_seen
+            .platforms
+            .extend(platforms.into_iter().map(str::to_string));
+    }
+
+    fn warmed_up(&self, event: &Event) -> bool {
+        self.warmup <= Duration::zero()
+            || self
+                .first_seen(event)
+                .is_none_or(|seen| self.now() - seen >= self.warmup)
+    }
+
+    /// Rejects broken quotes - a failed fetch, out of range or crossed
+    /// prices - and stale ones, so they skip the market instead of showing
+    /// up as an arb. Rejections are counted in
+    /// `prices_rejected_total{venue,reason}`.
+    fn price_problem(&self, event: &Event, prices: &MarketPrices) -> Option<SkipReason> {
+        let (reason, detail) = if let Some(problem) = prices.invalid_reason() {
+            (SkipReason::InvalidPrice, problem)
+        } else {
+            let age = self.now() - prices.fetched_at;
+            match self.max_price_age {
+                Some(max_age) if age > max_age => (
+                    SkipReason::StalePrice,
+                    format!("quotes {:.1}s old", age.num_milliseconds() as f64 / 1000.0),
+                ),
+                _ => return None,
+            }
+        };
+        tracing::debug!("Skipping {} {}: {}", event.platform, event.event_id, detail);
+        let label = if reason == SkipReason::InvalidPrice { "invalid" } else { "stale" };
+        metrics().inc_counter("prices_rejected_total", &[("venue", event.platform.as_str()), ("reason", label)]);
+        Some(reason)
+    }
+
+    fn spread_ok(&self, event: &Event, prices: &MarketPrices) -> bool {
+        let ok = self
+            .spread_limits
+            .as_ref()
+            .is_none_or(|limits| limits.allows(event, prices));
+        if !ok {
+            tracing::debug!(
+                "Skipping {} {}: spread {} over limit",
+                event.platform,
+                event.event_id,
+                prices.spread("YES")
+            );
+        }
+        ok
+    }
+
+    /// Pins the bot's notion of "now" (used by replay); `None` restores the wall clock.
+    pub fn set_time_override(&self, at: Option<DateTime<Utc>>) {
+        *self.time_override.write().unwrap_or_else(|e| e.into_inner()) = at;
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        self.time_override
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .unwrap_or_else(Utc::now)
     }
 
     pub fn is_within_timeframe(&self, resolution_date: Option<DateTime<Utc>>) -> bool {
         if let Some(date) = resolution_date {
-            let now = Utc::now();
+            let now = self.now();
             let time_until_resolution = date - now;
-            let max_time = Duration::minutes(30);
-            let min_time = Duration::minutes(10);
+            let (min_time, max_time) = self.resolution_window;
 
             time_until_resolution >= min_time && time_until_resolution <= max_time
         } else {
@@ -75,15 +541,22 @@ impl ShortTermArbitrageBot {
     }
 
     pub fn filter_events(&self, events: &[Event]) -> Vec<Event> {
+        self.observe_listings(events);
         events
             .iter()
             .filter(|event| {
-                self.matches_category(event) && self.is_within_timeframe(event.resolution_date)
+                self.matches_category(event)
+                    && self.is_within_timeframe(event.resolution_date)
+                    && self.warmed_up(event)
             })
             .cloned()
             .collect()
     }
 
+    #[tracing::instrument(
+        skip_all,
+        fields(pm_events = pm_events.len(), kalshi_events = kalshi_events.len())
+    )]
     pub async fn scan_for_opportunities<F, Fut>(
         &self,
         pm_events: &[Event],
@@ -98,19 +571,126 @@ impl ShortTermArbitrageBot {
         let pm_filtered = self.filter_events(pm_events);
         let kalshi_filtered = self.filter_events(kalshi_events);
 
+        let mut skips = SkipCounts::default();
         if pm_filtered.is_empty() || kalshi_filtered.is_empty() {
+            pm_filtered.iter().for_each(|_| skips.add(SkipReason::NoMatch));
+            self.record_skips(&self.arbitrage_skips, &skips);
+            self.clear_matches();
             return Vec::new();
         }
 
-        let matches = self.event_matcher.find_matches(&pm_filtered, &kalshi_filtered);
+        if let Some(embeddings) = self.event_matcher.embeddings() {
+            let titles: Vec<&str> = pm_filtered
+                .iter()
+                .chain(&kalshi_filtered)
+                .map(|e| e.title.as_str())
+                .collect();
+            if let Err(e) = embeddings.prefetch(&titles).await {
+                tracing::warn!("Embedding titles failed: {} - matching on string similarity", e);
+            }
+        }
+
+        let mut matches = self.match_events(&pm_filtered, &kalshi_filtered);
+        let matched: HashSet<&str> = matches.iter().map(|(pm, _, _)| pm.event_id.as_str()).collect();
+        pm_filtered
+            .iter()
+            .filter(|e| !matched.contains(e.event_id.as_str()))
+            .for_each(|_| skips.add(SkipReason::NoMatch));
+        matches.retain(|(pm_event, kalshi_event, _)| {
+            let agree = self.settlement_sources_agree(pm_event, kalshi_event);
+            if !agree {
+                skips.add(SkipReason::SettlementMismatch);
+            }
+            agree
+        });
 
         if matches.is_empty() {
+            self.record_skips(&self.arbitrage_skips, &skips);
+            self.clear_matches();
             return Vec::new();
         }
 
+        if let Some(recorder) = &self.recorder {
+            for (pm_event, kalshi_event, score) in &matches {
+                recorder.record_match(pm_event, kalshi_event, *score);
+            }
+        }
+
+        *self.matched.write().unwrap_or_else(|e| e.into_inner()) = matches.clone();
+        let (opportunities, quotes) = self.evaluate_pairs(matches, &fetch_prices, &mut skips).await;
+        *self.latest_pairs.write().unwrap_or_else(|e| e.into_inner()) = quotes;
+        self.record_skips(&self.arbitrage_skips, &skips);
+        opportunities
+    }
+
+    fn record_skips(&self, into: &Mutex<SkipCounts>, skips: &SkipCounts) {
+        into.lock().unwrap_or_else(|e| e.into_inner()).merge(skips);
+    }
+
+    /// Re-prices the latest scan's matched pairs that include `event_id` on
+    /// `platform` (e.g. after a book update on it), refreshing their quotes in
+    /// `latest_pairs`, without fetching listings or matching again.
+    #[tracing::instrument(skip(self, fetch_prices))]
+    pub async fn scan_pair<F, Fut>(
+        &self,
+        platform: &str,
+        event_id: &str,
+        fetch_prices: F,
+    ) -> Vec<(Event, Event, ArbitrageOpportunity)>
+    where
+        F: Fn(&str, &str) -> Fut,
+        Fut: std::future::Future<Output = MarketPrices> + Send,
+    {
+        let matches: Vec<(Event, Event, f64)> = self
+            .matched
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|(pm_event, kalshi_event, _)| match platform {
+                "kalshi" => kalshi_event.event_id == event_id,
+                _ => pm_event.event_id == event_id,
+            })
+            .filter(|(pm_event, kalshi_event, _)| {
+                self.is_within_timeframe(pm_event.resolution_date)
+                    && self.is_within_timeframe(kalshi_event.resolution_date)
+            })
+            .cloned()
+            .collect();
+        if matches.is_empty() {
+            return Vec::new();
+        }
+        let mut skips = SkipCounts::default();
+        let (opportunities, quotes) = self.evaluate_pairs(matches, &fetch_prices, &mut skips).await;
+        self.record_skips(&self.arbitrage_skips, &skips);
+        let mut latest = self.latest_pairs.write().unwrap_or_else(|e| e.into_inner());
+        for quote in quotes {
+            match latest
+                .iter_mut()
+                .find(|q| q.polymarket_id == quote.polymarket_id && q.kalshi_id == quote.kalshi_id)
+            {
+                Some(existing) => *existing = quote,
+                None => latest.push(quote),
+            }
+        }
+        opportunities
+    }
+
+    /// Fetches both venues' prices for each pair and checks them for
+    /// arbitrage, counting the pairs passed on in `skips`.
+    async fn evaluate_pairs<F, Fut>(
+        &self,
+        matches: Vec<(Event, Event, f64)>,
+        fetch_prices: &F,
+        skips: &mut SkipCounts,
+    ) -> (Vec<(Event, Event, ArbitrageOpportunity)>, Vec<PairQuote>)
+    where
+        F: Fn(&str, &str) -> Fut,
+        Fut: std::future::Future<Output = MarketPrices> + Send,
+    {
         let price_futures: Vec<_> = matches
             .iter()
-            .map(|(pm_event, kalshi_event, _)| {
+            .map(|(pm_event, kalshi_event, score)| {
+                let score = *score;
                 let pm_id = pm_event.event_id.clone();
                 let kalshi_id = kalshi_event.event_id.clone();
                 let pm_event_clone = pm_event.clone();
@@ -120,7 +700,7 @@ impl ShortTermArbitrageBot {
                         fetch_prices(&pm_id, "polymarket"),
                         fetch_prices(&kalshi_id, "kalshi")
                     );
-                    (pm_event_clone, kalshi_event_clone, pm_prices, kalshi_prices)
+                    (pm_event_clone, kalshi_event_clone, score, pm_prices, kalshi_prices)
                 }
             })
             .collect();
@@ -128,20 +708,108 @@ impl ShortTermArbitrageBot {
         let price_results = futures::future::join_all(price_futures).await;
 
         let mut opportunities = Vec::new();
+        let mut quotes = Vec::with_capacity(price_results.len());
 
-        for (pm_event, kalshi_event, pm_prices, kalshi_prices) in price_results {
-            if pm_prices.liquidity < self.filters.min_liquidity
-                || kalshi_prices.liquidity < self.filters.min_liquidity
-            {
+        for (pm_event, kalshi_event, score, pm_prices, kalshi_prices) in price_results {
+            if let Some(recorder) = &self.recorder {
+                recorder.record_prices("polymarket", &pm_event.event_id, &pm_prices);
+                recorder.record_prices("kalshi", &kalshi_event.event_id, &kalshi_prices);
+            }
+            if let Some(capture) = &self.debug_capture {
+                capture.observe_prices("polymarket", &pm_event.event_id, &pm_prices).await;
+                capture.observe_prices("kalshi", &kalshi_event.event_id, &kalshi_prices).await;
+            }
+
+            let pm_problem = self.price_problem(&pm_event, &pm_prices);
+            if let Some(problem) = self.price_problem(&kalshi_event, &kalshi_prices).or(pm_problem) {
+                skips.add(problem);
                 continue;
             }
+            let min_liquidity = UsdAmount::from_f64(self.filters.min_liquidity);
+            let liquid = pm_prices.depth >= min_liquidity && kalshi_prices.depth >= min_liquidity;
+            let spread_ok =
+                self.spread_ok(&pm_event, &pm_prices) && self.spread_ok(&kalshi_event, &kalshi_prices);
+            let inverted = strike::inverted_pair(&pm_event.title, &kalshi_event.title);
+            let quote = PairQuote::new(
+                &pm_event,
+                &kalshi_event,
+                score,
+                &pm_prices,
+                &kalshi_prices,
+                inverted,
+                liquid && spread_ok,
+            );
+            let edge = quote.edge();
+            quotes.push(quote);
+            // Capital is tied up until the later of the two markets resolves.
+            let resolves_at = pm_event.resolution_date.max(kalshi_event.resolution_date);
+            let live = (liquid && spread_ok)
+                .then(|| {
+                    self.arbitrage_detector
+                        .check_arbitrage_with_polarity(&pm_prices, &kalshi_prices, inverted)
+                })
+                .flatten()
+                .map(|mut opp| {
+                    opp.confidence = Some(ConfidenceModel::default().score(
+                        score,
+                        &[(&pm_prices, &opp.polymarket_action.1), (&kalshi_prices, &opp.kalshi_action.1)],
+                        self.now(),
+                    ));
+                    opp.detected_at = Some(self.now());
+                    opp.with_lockup(resolves_at, self.now())
+                });
+            if let Some(shadow) = &self.shadow {
+                let settings = LiveSettings {
+                    min_profit: self.min_profit_threshold,
+                    min_liquidity: self.filters.min_liquidity,
+                    spread_ok,
+                };
+                shadow.observe(&pm_event, &pm_prices, &kalshi_prices, inverted, &settings, live.as_ref());
+            }
 
-            if let Some(opportunity) = self.arbitrage_detector.check_arbitrage(&pm_prices, &kalshi_prices) {
-                opportunities.push((pm_event, kalshi_event, opportunity));
+            let Some(opportunity) = live else {
+                skips.add(if !liquid {
+                    SkipReason::LowLiquidity
+                } else if !spread_ok {
+                    SkipReason::WideSpread
+                } else if edge <= Price::ZERO {
+                    SkipReason::NoEdge
+                } else if edge <= self.arbitrage_detector.total_fees() {
+                    SkipReason::FeesEatEdge
+                } else {
+                    SkipReason::BelowMinProfit
+                });
+                continue;
+            };
+            if let Some(decay) = &self.edge_decay {
+                let extra = decay.extra_profit(&["polymarket", "kalshi"], self.now());
+                if opportunity.net_profit <= Price::from_f64(self.min_profit_threshold + extra) {
+                    tracing::debug!(
+                        "Skipping {}: {} profit under ${:.4} with ${:.4} latency decay",
+                        pm_event.title,
+                        opportunity.net_profit,
+                        self.min_profit_threshold + extra,
+                        extra
+                    );
+                    skips.add(SkipReason::BelowMinProfit);
+                    continue;
+                }
             }
+            if let Some(recorder) = &self.recorder {
+                recorder.record_arbitrage(&pm_event, &kalshi_event, &pm_prices, &kalshi_prices, &opportunity);
+            }
+            if let Some(reason) = self.repeat_of_recent_trade(&pm_event, &kalshi_event, &opportunity) {
+                tracing::debug!("Skipping {}: {}", pm_event.title, reason);
+                skips.add(SkipReason::RecentlyTraded);
+                continue;
+            }
+            if let Some(capture) = &self.debug_capture {
+                capture.observe_edge(&pm_event.title, opportunity.net_profit).await;
+            }
+            opportunities.push((pm_event, kalshi_event, opportunity));
         }
 
-        opportunities
+        (opportunities, quotes)
     }
 
     pub async fn scan_gabagool_opportunities<F, Fut, G, Gfut>(
@@ -154,7 +822,7 @@ impl ShortTermArbitrageBot {
         F: Fn(&str) -> Fut,
         Fut: std::future::Future<Output = MarketPrices> + Send,
         G: Fn(&str) -> Gfut,
-        Gfut: std::future::Future<Output = (f64, f64, f64, f64)> + Send,
+        Gfut: std::future::Future<Output = (f64, UsdAmount, f64, UsdAmount)> + Send,
     {
 
         let pm_filtered = self.filter_events(pm_events);
@@ -163,6 +831,8 @@ impl ShortTermArbitrageBot {
             return Vec::new();
         }
 
+        let fetch_prices = &fetch_prices;
+        let get_position_balance = &get_position_balance;
         let opportunity_futures: Vec<_> = pm_filtered
             .iter()
             .map(|event| {
@@ -181,13 +851,31 @@ impl ShortTermArbitrageBot {
         let results = futures::future::join_all(opportunity_futures).await;
 
         let mut opportunities = Vec::new();
+        let mut skips = SkipCounts::default();
 
         for (event, prices, yes_qty, yes_cost, no_qty, no_cost) in results {
-            if prices.liquidity < self.filters.min_liquidity {
+            if let Some(recorder) = &self.recorder {
+                recorder.record_prices("polymarket", &event.event_id, &prices);
+            }
+            if let Some(capture) = &self.debug_capture {
+                capture.observe_prices("polymarket", &event.event_id, &prices).await;
+            }
+
+            let skip = if let Some(problem) = self.price_problem(&event, &prices) {
+                Some(problem)
+            } else if prices.depth < UsdAmount::from_f64(self.filters.min_liquidity) {
+                Some(SkipReason::LowLiquidity)
+            } else if !self.spread_ok(&event, &prices) {
+                Some(SkipReason::WideSpread)
+            } else {
+                None
+            };
+            if let Some(reason) = skip {
+                skips.add(reason);
                 continue;
             }
 
-            if let Some(opportunity) = self.gabagool_detector.check_opportunity(
+            match self.gabagool_detector.check_opportunity(
                 &event,
                 &prices,
                 yes_qty,
@@ -195,10 +883,23 @@ impl ShortTermArbitrageBot {
                 yes_cost,
                 no_cost,
             ) {
-                opportunities.push(opportunity);
+                Some(opportunity) => {
+                    let confidence = ConfidenceModel::default().score(
+                        1.0,
+                        &[(&prices, opportunity.cheap_side.as_str())],
+                        self.now(),
+                    );
+                    let opportunity = GabagoolOpportunity { confidence: Some(confidence), ..opportunity };
+                    if let Some(recorder) = &self.recorder {
+                        recorder.record_gabagool(&prices, &opportunity);
+                    }
+                    opportunities.push(opportunity);
+                }
+                None => skips.add(SkipReason::NoEdge),
             }
         }
 
+        self.record_skips(&self.gabagool_skips, &skips);
         opportunities
     }
 
@@ -226,7 +927,7 @@ impl ShortTermArbitrageBot {
                 tracing::info!("Found {} arbitrage opportunities", opportunities.len());
                 for (pm_event, kalshi_event, opp) in &opportunities {
                     tracing::info!(
-                        "Opportunity: {} - Profit: ${:.4}, ROI: {:.2}%",
+                        "Opportunity: {} - Profit: {}, ROI: {:.2}%",
                         pm_event.title,
                         opp.net_profit,
                         opp.roi_percent
@@ -0,0 +1,65 @@
+//! Confidence score of an opportunity.
+//!
+//! Not every detection deserves the same trust: a pair matched on a loose
+//! title, quotes fetched seconds ago, a thin book or a wide spread all make
+//! the computed edge less likely to be real. Each of those is scored from 0
+//! to 1 and the score is their geometric mean, so one weak factor drags the
+//! whole score down. `MIN_CONFIDENCE` (0 to 1) skips opportunities under it.
+
+use crate::event::MarketPrices;
+use chrono::{DateTime, Utc};
+
+/// What the confidence score measures against.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConfidenceModel {
+    /// Quotes this old (seconds) score zero for freshness.
+    pub stale_after_secs: f64,
+    /// Dollars of depth that score full marks.
+    pub full_depth: f64,
+    /// Spread (dollars per share) that scores zero.
+    pub max_spread: f64,
+}
+
+impl Default for ConfidenceModel {
+    fn default() -> Self {
+        Self { stale_after_secs: 10.0, full_depth: 1000.0, max_spread: 0.10 }
+    }
+}
+
+impl ConfidenceModel {
+    /// Scores buying `outcome` off each of `legs`, on a pair matched with
+    /// `match_score` (1.0 for a single market), as of `now`.
+    pub fn score(&self, match_score: f64, legs: &[(&MarketPrices, &str)], now: DateTime<Utc>) -> f64 {
+        let worst = |f: &dyn Fn(&MarketPrices, &str) -> f64| {
+            legs.iter().map(|(prices, outcome)| f(prices, outcome)).fold(1.0, f64::min)
+        };
+        let freshness = worst(&|prices, _| {
+            let age = (now - prices.fetched_at).num_milliseconds().max(0) as f64 / 1000.0;
+            1.0 - age / self.stale_after_secs
+        });
+        let depth = worst(&|prices, _| prices.depth.to_f64() / self.full_depth);
+        let spread = worst(&|prices, outcome| 1.0 - prices.spread(outcome).to_f64() / self.max_spread);
+        let factors = [match_score, freshness, depth, spread].map(|f| f.clamp(0.0, 1.0));
+        factors.iter().product::<f64>().powf(1.0 / factors.len() as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn weak_factors_drag_the_score_down() {
+        let model = ConfidenceModel::default();
+        let tight = MarketPrices::new(0.45, 0.56, 1000.0);
+        assert!(model.score(1.0, &[(&tight, "YES")], Utc::now()) > 0.95);
+        // A looser match and a thinner book each lower it.
+        let thin = MarketPrices::new(0.45, 0.56, 250.0);
+        let looser = model.score(0.8, &[(&tight, "YES"), (&thin, "NO")], Utc::now());
+        assert!(looser < 0.85 && looser > 0.5);
+        // Quotes past the stale limit score zero.
+        let stale = MarketPrices::new(0.45, 0.56, 1000.0).with_fetched_at(Utc::now() - Duration::seconds(30));
+        assert_eq!(model.score(1.0, &[(&stale, "YES")], Utc::now()), 0.0);
+    }
+}
@@ -0,0 +1,437 @@
+//! Runtime controls and the HTTP control API.
+//!
+//! `Controls` holds what operators can change without a restart: paused
+//! strategies, a trade size and minimum profit (per share) overriding the
+//! configured ones, and a trigger for an immediate scan. It also holds the
+//! platforms paused for a low balance (see [`crate::balance_floor`]), which
+//! no strategy opens positions on, and the UTC day the daily loss limit
+//! stopped entries on (see [`crate::loss_limit`]). With
+//! `CONTROL_ADDR` and `CONTROL_TOKEN` both set, an axum server exposes them;
+//! every request needs `Authorization: Bearer <CONTROL_TOKEN>`.
+//!
+//! - `GET /status`: current controls
+//! - `POST /pause`, `POST /resume`: `{"strategy": "gabagool"}`, or every
+//!   strategy without a body
+//! - `POST /limits`: `{"trade_amount": 50, "min_profit": 0.03}`; `null`
+//!   clears an override, a missing field leaves it alone
+//! - `POST /loss-limit`: `{"override": true}` keeps trading today despite
+//!   the daily loss limit; `false` withdraws the override
+//! - `POST /scan`: scan now instead of waiting for the next tick
+//! - `POST /close`: `{"position_id": "...", "tolerance": 0.05}` sells an
+//!   open position into the bid, at most `tolerance` below it; only the
+//!   shares confirmed filled are closed, at the fill price
+//!
+//! The minimum profit only raises the bar: detectors still apply their own.
+
+use crate::exchange::{confirm_fill, ExchangeClient, FILL_CONFIRM_TIMEOUT};
+use crate::flatten::{limit_price, DEFAULT_FLATTEN_TOLERANCE};
+use crate::money::{Price, UsdAmount};
+use crate::position_tracker::{PositionStatus, PositionTracker};
+use anyhow::{Context, Result};
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::sync::{Arc, RwLock};
+use subtle::ConstantTimeEq;
+use tokio::sync::{Mutex, Notify};
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ControlState {
+    /// Every strategy paused.
+    pub paused_all: bool,
+    pub paused: BTreeSet<String>,
+    pub trade_amount: Option<f64>,
+    pub min_profit: Option<f64>,
+    /// Platforms no new positions are opened on.
+    pub paused_platforms: BTreeSet<String>,
+    /// UTC day the daily loss limit was hit on.
+    pub loss_limit_hit: Option<NaiveDate>,
+    /// UTC day the operator chose to trade through the loss limit.
+    pub loss_limit_override: Option<NaiveDate>,
+}
+
+#[derive(Default)]
+pub struct Controls {
+    state: RwLock<ControlState>,
+    scan_now: Notify,
+}
+
+impl Controls {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn state(&self) -> ControlState {
+        self.state.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Pauses one strategy, or all of them.
+    pub fn pause(&self, strategy: Option<&str>) {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        match strategy {
+            Some(name) => {
+                state.paused.insert(name.to_string());
+            }
+            None => state.paused_all = true,
+        }
+    }
+
+    /// Resumes one strategy, or all of them.
+    pub fn resume(&self, strategy: Option<&str>) {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        match strategy {
+            Some(name) => {
+                state.paused.remove(name);
+            }
+            None => {
+                state.paused_all = false;
+                state.paused.clear();
+            }
+        }
+    }
+
+    pub fn is_paused(&self, strategy: &str) -> bool {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        state.paused_all || state.paused.contains(strategy)
+    }
+
+    /// Stops (or resumes) opening positions on `platform`; returns whether
+    /// that changed anything.
+    pub fn set_platform_paused(&self, platform: &str, paused: bool) -> bool {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        if paused {
+            state.paused_platforms.insert(platform.to_string())
+        } else {
+            state.paused_platforms.remove(platform)
+        }
+    }
+
+    pub fn is_platform_paused(&self, platform: &str) -> bool {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        state.paused_platforms.contains(platform)
+    }
+
+    pub fn loss_limit_hit(&self) -> Option<NaiveDate> {
+        self.state.read().unwrap_or_else(|e| e.into_inner()).loss_limit_hit
+    }
+
+    pub fn set_loss_limit_hit(&self, day: Option<NaiveDate>) {
+        self.state.write().unwrap_or_else(|e| e.into_inner()).loss_limit_hit = day;
+    }
+
+    /// Trades through (or stops trading through) the loss limit on `today`.
+    pub fn override_loss_limit(&self, today: NaiveDate, enabled: bool) {
+        self.state.write().unwrap_or_else(|e| e.into_inner()).loss_limit_override = enabled.then_some(today);
+    }
+
+    /// Whether the loss limit stops new entries on `today`.
+    pub fn loss_limited(&self, today: NaiveDate) -> bool {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        state.loss_limit_hit == Some(today) && state.loss_limit_override != Some(today)
+    }
+
+    pub fn trade_amount(&self) -> Option<f64> {
+        self.state.read().unwrap_or_else(|e| e.into_inner()).trade_amount
+    }
+
+    pub fn min_profit(&self) -> Option<f64> {
+        self.state.read().unwrap_or_else(|e| e.into_inner()).min_profit
+    }
+
+    pub fn set_trade_amount(&self, amount: Option<f64>) {
+        self.state.write().unwrap_or_else(|e| e.into_inner()).trade_amount = amount;
+    }
+
+    pub fn set_min_profit(&self, min_profit: Option<f64>) {
+        self.state.write().unwrap_or_else(|e| e.into_inner()).min_profit = min_profit;
+    }
+
+    pub fn request_scan(&self) {
+        self.scan_now.notify_one();
+    }
+
+    /// Resolves once a scan is requested.
+    pub async fn scan_requested(&self) {
+        self.scan_now.notified().await;
+    }
+}
+
+/// What a manual close did.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ManualClose {
+    pub position_id: String,
+    /// Shares the sell filled.
+    pub shares: f64,
+    pub min_price: Price,
+    /// Average fill price.
+    pub price: Price,
+    pub order_id: Option<String>,
+    pub profit: Option<UsdAmount>,
+}
+
+pub struct ControlApi {
+    controls: Arc<Controls>,
+    token: String,
+    polymarket_client: Arc<dyn ExchangeClient>,
+    kalshi_client: Arc<dyn ExchangeClient>,
+    position_tracker: Arc<Mutex<PositionTracker>>,
+}
+
+impl ControlApi {
+    pub fn new(
+        controls: Arc<Controls>,
+        token: &str,
+        polymarket_client: Arc<dyn ExchangeClient>,
+        kalshi_client: Arc<dyn ExchangeClient>,
+        position_tracker: Arc<Mutex<PositionTracker>>,
+    ) -> Self {
+        Self {
+            controls,
+            token: token.to_string(),
+            polymarket_client,
+            kalshi_client,
+            position_tracker,
+        }
+    }
+
+    /// `CONTROL_ADDR` and `CONTROL_TOKEN`; the API is off unless both are set.
+    pub fn config_from_env() -> Option<(String, String)> {
+        let var = |key: &str| std::env::var(key).ok().filter(|s| !s.trim().is_empty());
+        let addr = var("CONTROL_ADDR")?;
+        match var("CONTROL_TOKEN") {
+            Some(token) => Some((addr, token.trim().to_string())),
+            None => {
+                warn!("⚠️ CONTROL_ADDR set without CONTROL_TOKEN - control API disabled");
+                None
+            }
+        }
+    }
+
+    /// Sells an open position into the bid, at most `tolerance` below it,
+    /// and closes the shares that filled. Errors when nothing filled.
+    pub async fn close_position(&self, position_id: &str, tolerance: f64) -> Result<ManualClose> {
+        let position = {
+            let tracker = self.position_tracker.lock().await;
+            tracker
+                .get_all_positions()
+                .into_iter()
+                .find(|p| p.id == position_id)
+                .cloned()
+                .with_context(|| format!("No position {}", position_id))?
+        };
+        if position.status != PositionStatus::Open {
+            return Err(anyhow::anyhow!("Position {} is not open", position_id));
+        }
+        let client = if position.platform == "kalshi" {
+            &self.kalshi_client
+        } else {
+            &self.polymarket_client
+        };
+        let bid = client.fetch_prices(&position.event_id).await?.bid_for(&position.outcome);
+        if bid <= Price::ZERO {
+            return Err(anyhow::anyhow!("No bid for {} {}", position.event_title, position.outcome));
+        }
+        let min_price = limit_price(bid, tolerance);
+        let order_id = client
+            .sell_order(position.event_id.clone(), position.outcome.clone(), position.amount, min_price.to_f64())
+            .await?;
+        let fill = confirm_fill(
+            client.as_ref(),
+            &position.event_id,
+            order_id.as_deref(),
+            position.amount,
+            min_price.to_f64(),
+            FILL_CONFIRM_TIMEOUT,
+        )
+        .await?;
+        if fill.shares <= 0.0 {
+            return Err(anyhow::anyhow!(
+                "Sell of {} {} at ≥{} did not fill",
+                position.event_title,
+                position.outcome,
+                min_price
+            ));
+        }
+        let profit = self
+            .position_tracker
+            .lock()
+            .await
+            .close_shares(&position.id, fill.shares, fill.notional());
+        info!(
+            "🕹️ Manual close of {} {} ({}): {:.2} of {:.2} shares at ${:.2}",
+            position.event_title, position.outcome, position.platform, fill.shares, position.amount, fill.price
+        );
+        Ok(ManualClose {
+            position_id: position.id,
+            shares: fill.shares,
+            min_price,
+            price: Price::from_f64(fill.price),
+            order_id,
+            profit,
+        })
+    }
+
+    pub fn router(self: Arc<Self>) -> Router {
+        Router::new()
+            .route("/status", get(status))
+            .route("/pause", post(pause))
+            .route("/resume", post(resume))
+            .route("/limits", post(limits))
+            .route("/loss-limit", post(loss_limit))
+            .route("/scan", post(scan))
+            .route("/close", post(close))
+            .route_layer(middleware::from_fn_with_state(self.clone(), authorize))
+            .with_state(self)
+    }
+
+    /// Serves the API on `addr` until the process exits.
+    pub async fn serve(self: Arc<Self>, addr: &str) -> Result<()> {
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind control API listener on {}", addr))?;
+        info!("🕹️ Control API on http://{}/", addr);
+        axum::serve(listener, self.router())
+            .await
+            .context("Control API server failed")
+    }
+}
+
+async fn authorize(State(api): State<Arc<ControlApi>>, request: Request, next: Next) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|token| bool::from(token.trim().as_bytes().ct_eq(api.token.as_bytes())));
+    if !authorized {
+        return (StatusCode::UNAUTHORIZED, "missing or wrong bearer token").into_response();
+    }
+    next.run(request).await
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct StrategyRequest {
+    strategy: Option<String>,
+}
+
+/// Distinguishes a field set to `null` from a missing one.
+fn explicit<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Option<f64>>, D::Error> {
+    Option::<f64>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct LimitsRequest {
+    #[serde(default, deserialize_with = "explicit")]
+    trade_amount: Option<Option<f64>>,
+    #[serde(default, deserialize_with = "explicit")]
+    min_profit: Option<Option<f64>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LossLimitRequest {
+    #[serde(rename = "override")]
+    enabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct CloseRequest {
+    position_id: String,
+    tolerance: Option<f64>,
+}
+
+fn bad_request(message: impl std::fmt::Display) -> Response {
+    (StatusCode::BAD_REQUEST, Json(json!({ "error": message.to_string() }))).into_response()
+}
+
+async fn status(State(api): State<Arc<ControlApi>>) -> Json<ControlState> {
+    Json(api.controls.state())
+}
+
+async fn pause(State(api): State<Arc<ControlApi>>, body: Option<Json<StrategyRequest>>) -> Json<ControlState> {
+    let strategy = body.and_then(|Json(b)| b.strategy);
+    api.controls.pause(strategy.as_deref());
+    warn!("⏸️ Control API: paused {}", strategy.as_deref().unwrap_or("all strategies"));
+    Json(api.controls.state())
+}
+
+async fn resume(State(api): State<Arc<ControlApi>>, body: Option<Json<StrategyRequest>>) -> Json<ControlState> {
+    let strategy = body.and_then(|Json(b)| b.strategy);
+    api.controls.resume(strategy.as_deref());
+    info!("▶️ Control API: resumed {}", strategy.as_deref().unwrap_or("all strategies"));
+    Json(api.controls.state())
+}
+
+async fn limits(State(api): State<Arc<ControlApi>>, Json(body): Json<LimitsRequest>) -> Response {
+    let positive = |v: &Option<f64>| v.is_none_or(|v| v > 0.0);
+    if !body.trade_amount.as_ref().is_none_or(positive) || !body.min_profit.as_ref().is_none_or(positive) {
+        return bad_request("trade_amount and min_profit must be positive");
+    }
+    if let Some(amount) = body.trade_amount {
+        api.controls.set_trade_amount(amount);
+    }
+    if let Some(min_profit) = body.min_profit {
+        api.controls.set_min_profit(min_profit);
+    }
+    let state = api.controls.state();
+    info!(
+        "🎚️ Control API: trade amount {:?}, min profit {:?}",
+        state.trade_amount, state.min_profit
+    );
+    Json(state).into_response()
+}
+
+async fn loss_limit(State(api): State<Arc<ControlApi>>, Json(body): Json<LossLimitRequest>) -> Json<ControlState> {
+    api.controls.override_loss_limit(Utc::now().date_naive(), body.enabled);
+    if body.enabled {
+        warn!("🧊 Control API: trading through the daily loss limit for the rest of today");
+    } else {
+        info!("🧊 Control API: daily loss limit override withdrawn");
+    }
+    Json(api.controls.state())
+}
+
+async fn scan(State(api): State<Arc<ControlApi>>) -> StatusCode {
+    api.controls.request_scan();
+    info!("🔎 Control API: scan requested");
+    StatusCode::ACCEPTED
+}
+
+async fn close(State(api): State<Arc<ControlApi>>, Json(body): Json<CloseRequest>) -> Response {
+    let tolerance = body.tolerance.unwrap_or(DEFAULT_FLATTEN_TOLERANCE);
+    match api.close_position(&body.position_id, tolerance).await {
+        Ok(closed) => Json(closed).into_response(),
+        Err(e) => {
+            warn!("🕹️ Manual close of {} failed: {}", body.position_id, e);
+            bad_request(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pauses_and_resumes_strategies() {
+        let controls = Controls::new();
+        controls.pause(Some("gabagool"));
+        assert!(controls.is_paused("gabagool"));
+        assert!(!controls.is_paused("cross_platform"));
+        controls.pause(None);
+        assert!(controls.is_paused("cross_platform"));
+        controls.resume(None);
+        assert!(!controls.is_paused("gabagool"));
+
+        let limits: LimitsRequest = serde_json::from_str(r#"{"trade_amount": null}"#).unwrap();
+        assert_eq!(limits.trade_amount, Some(None));
+        assert_eq!(limits.min_profit, None);
+    }
+}
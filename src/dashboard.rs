@@ -0,0 +1,295 @@
+//! Embedded web dashboard for live monitoring.
+//!
+//! With `DASHBOARD_ADDR` set (e.g. `127.0.0.1:8080`) an axum server shows
+//! matched markets and their quotes, open positions, the opportunities from
+//! each strategy's latest scan, the most recent trades, venue balances and
+//! Gabagool pair costs on one page, pushed over server-sent events every
+//! `DASHBOARD_REFRESH_SECS` (default 2). `/api/snapshot` returns the same
+//! data as JSON. Balances are fetched at most once a minute. There is no
+//! authentication: bind it to localhost or put it behind a proxy that has
+//! some. The `--tui` terminal view renders the same snapshot.
+
+use crate::bot::{PairQuote, ShortTermArbitrageBot};
+use crate::exchange::ExchangeClient;
+use crate::gabagool_executor::{GabagoolExecutor, GabagoolPairCost};
+use crate::position_tracker::{LedgerRow, PositionStatus, PositionTracker};
+use crate::strategy::{OpportunitySummary, StrategyRegistry};
+use anyhow::{Context, Result};
+use axum::extract::State;
+use axum::response::sse::{Event as SseEvent, KeepAlive, Sse};
+use axum::response::{Html, IntoResponse};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use futures::stream::Stream;
+use serde::Serialize;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::info;
+
+const DEFAULT_REFRESH_SECS: u64 = 2;
+const BALANCE_REFRESH: Duration = Duration::from_secs(60);
+/// Trades listed under recent trades.
+const RECENT_TRADES: usize = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Balances {
+    pub polymarket: f64,
+    pub kalshi: f64,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Everything the dashboard shows at one moment.
+#[derive(Debug, Clone, Serialize)]
+pub struct DashboardSnapshot {
+    pub at: DateTime<Utc>,
+    /// Matched cross-platform markets from the latest scan.
+    pub pairs: Vec<PairQuote>,
+    pub open_positions: Vec<LedgerRow>,
+    pub opportunities: Vec<OpportunitySummary>,
+    /// Newest first.
+    pub recent_trades: Vec<LedgerRow>,
+    pub balances: Option<Balances>,
+    pub gabagool: Vec<GabagoolPairCost>,
+    pub total_profit: f64,
+}
+
+pub struct Dashboard {
+    position_tracker: Arc<Mutex<PositionTracker>>,
+    bot: Option<Arc<ShortTermArbitrageBot>>,
+    strategies: Option<Arc<StrategyRegistry>>,
+    gabagool: Option<Arc<GabagoolExecutor>>,
+    venues: Option<(Arc<dyn ExchangeClient>, Arc<dyn ExchangeClient>)>,
+    balances: Mutex<Option<(tokio::time::Instant, Balances)>>,
+    refresh: Duration,
+}
+
+impl Dashboard {
+    pub fn new(position_tracker: Arc<Mutex<PositionTracker>>) -> Self {
+        Self {
+            position_tracker,
+            bot: None,
+            strategies: None,
+            gabagool: None,
+            venues: None,
+            balances: Mutex::new(None),
+            refresh: Duration::from_secs(DEFAULT_REFRESH_SECS),
+        }
+    }
+
+    /// Bot whose matched markets are shown.
+    pub fn with_bot(mut self, bot: Arc<ShortTermArbitrageBot>) -> Self {
+        self.bot = Some(bot);
+        self
+    }
+
+    pub fn with_strategies(mut self, strategies: Arc<StrategyRegistry>) -> Self {
+        self.strategies = Some(strategies);
+        self
+    }
+
+    pub fn with_gabagool(mut self, executor: Arc<GabagoolExecutor>) -> Self {
+        self.gabagool = Some(executor);
+        self
+    }
+
+    /// Venues whose balances are shown.
+    pub fn with_balances(
+        mut self,
+        polymarket: Arc<dyn ExchangeClient>,
+        kalshi: Arc<dyn ExchangeClient>,
+    ) -> Self {
+        self.venues = Some((polymarket, kalshi));
+        self
+    }
+
+    pub fn with_refresh(mut self, refresh: Duration) -> Self {
+        self.refresh = refresh.max(Duration::from_millis(250));
+        self
+    }
+
+    pub fn refresh(&self) -> Duration {
+        self.refresh
+    }
+
+    /// `DASHBOARD_ADDR`; the dashboard is off without it.
+    pub fn addr_from_env() -> Option<String> {
+        std::env::var("DASHBOARD_ADDR").ok().filter(|s| !s.trim().is_empty())
+    }
+
+    /// `DASHBOARD_REFRESH_SECS`, or the default.
+    pub fn refresh_from_env() -> Duration {
+        let secs = std::env::var("DASHBOARD_REFRESH_SECS")
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .filter(|s| *s > 0)
+            .unwrap_or(DEFAULT_REFRESH_SECS);
+        Duration::from_secs(secs)
+    }
+
+    async fn balances(&self) -> Option<Balances> {
+        let (polymarket, kalshi) = self.venues.as_ref()?;
+        let mut cached = self.balances.lock().await;
+        if let Some((fetched, balances)) = cached.as_ref() {
+            if fetched.elapsed() < BALANCE_REFRESH {
+                return Some(*balances);
+            }
+        }
+        let (pm_balance, kalshi_balance) = tokio::join!(polymarket.get_balance(), kalshi.get_balance());
+        let balances = Balances {
+            polymarket: pm_balance.ok()?,
+            kalshi: kalshi_balance.ok()?,
+            fetched_at: Utc::now(),
+        };
+        *cached = Some((tokio::time::Instant::now(), balances));
+        Some(balances)
+    }
+
+    pub async fn snapshot(&self) -> DashboardSnapshot {
+        let (open_positions, recent_trades, total_profit) = {
+            let tracker = self.position_tracker.lock().await;
+            let ledger = tracker.ledger();
+            let open = ledger
+                .iter()
+                .filter(|row| row.status == PositionStatus::Open)
+                .cloned()
+                .collect();
+            let recent = ledger.iter().rev().take(RECENT_TRADES).cloned().collect();
+            (open, recent, tracker.get_total_profit())
+        };
+        let opportunities = match &self.strategies {
+            Some(strategies) => strategies.latest_opportunities().await,
+            None => Vec::new(),
+        };
+        let gabagool = match &self.gabagool {
+            Some(executor) => executor.pair_costs().await,
+            None => Vec::new(),
+        };
+        DashboardSnapshot {
+            at: Utc::now(),
+            pairs: self.bot.as_ref().map(|bot| bot.latest_pairs()).unwrap_or_default(),
+            open_positions,
+            opportunities,
+            recent_trades,
+            balances: self.balances().await,
+            gabagool,
+            total_profit,
+        }
+    }
+
+    pub fn router(self: Arc<Self>) -> Router {
+        Router::new()
+            .route("/", get(index))
+            .route("/api/snapshot", get(snapshot))
+            .route("/events", get(events))
+            .with_state(self)
+    }
+
+    /// Serves the dashboard on `addr` until the process exits.
+    pub async fn serve(self: Arc<Self>, addr: &str) -> Result<()> {
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind dashboard listener on {}", addr))?;
+        info!("🖥️ Dashboard on http://{}/", addr);
+        axum::serve(listener, self.router())
+            .await
+            .context("Dashboard server failed")
+    }
+}
+
+async fn index() -> Html<&'static str> {
+    Html(INDEX_HTML)
+}
+
+async fn snapshot(State(dashboard): State<Arc<Dashboard>>) -> impl IntoResponse {
+    Json(dashboard.snapshot().await)
+}
+
+async fn events(
+    State(dashboard): State<Arc<Dashboard>>,
+) -> Sse<impl Stream<Item = Result<SseEvent, Infallible>>> {
+    let interval = tokio::time::interval(dashboard.refresh);
+    let stream = futures::stream::unfold((dashboard, interval), |(dashboard, mut interval)| async move {
+        interval.tick().await;
+        let data = serde_json::to_string(&dashboard.snapshot().await).unwrap_or_default();
+        Some((Ok(SseEvent::default().event("snapshot").data(data)), (dashboard, interval)))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+const INDEX_HTML: &str = r#"<!doctype html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Arbitrage Bot</title>
+<style>
+body { font-family: ui-monospace, monospace; background: #111; color: #ddd; margin: 1.5em; }
+h1 { font-size: 1.2em; } h2 { font-size: 1em; margin-top: 1.5em; color: #9cf; }
+table { border-collapse: collapse; width: 100%; font-size: 0.85em; }
+th, td { text-align: left; padding: 2px 8px; border-bottom: 1px solid #333; }
+.pos { color: #6d6; } .neg { color: #e66; } #status { color: #888; }
+</style>
+</head>
+<body>
+<h1>Polymarket-Kalshi Arbitrage Bot <span id="status">connecting…</span></h1>
+<div id="summary"></div>
+<h2>Matched markets</h2><table id="pairs"></table>
+<h2>Open positions</h2><table id="open"></table>
+<h2>Opportunities (latest scan)</h2><table id="opportunities"></table>
+<h2>Gabagool pair costs</h2><table id="gabagool"></table>
+<h2>Recent trades</h2><table id="recent"></table>
+<script>
+const money = v => v == null ? "" : (v < 0 ? "-$" : "$") + Math.abs(v).toFixed(2);
+const num = v => v == null ? "" : (+v).toFixed(4).replace(/\.?0+$/, "");
+const cls = v => v == null ? "" : v >= 0 ? "pos" : "neg";
+function table(id, columns, rows) {
+  const head = "<tr>" + columns.map(c => "<th>" + c[0] + "</th>").join("") + "</tr>";
+  const body = rows.map(r => "<tr>" + columns.map(c => {
+    const [text, klass] = c[1](r);
+    return "<td class='" + (klass || "") + "'>" + String(text ?? "").replace(/</g, "&lt;") + "</td>";
+  }).join("") + "</tr>").join("");
+  document.getElementById(id).innerHTML = head + (body || "<tr><td>none</td></tr>");
+}
+const positionColumns = [
+  ["opened", r => [r.created_at.slice(0, 19).replace("T", " ")]],
+  ["strategy", r => [r.strategy]], ["venue", r => [r.platform]], ["event", r => [r.event_title]],
+  ["side", r => [r.outcome]], ["shares", r => [num(r.shares)]], ["price", r => [num(r.price)]],
+  ["cost", r => [money(r.cost)]], ["status", r => [r.status]], ["profit", r => [money(r.profit), cls(r.profit)]],
+];
+function render(s) {
+  const b = s.balances;
+  document.getElementById("summary").innerHTML =
+    "Realized PnL <span class='" + cls(s.total_profit) + "'>" + money(s.total_profit) + "</span>" +
+    (b ? " · Polymarket " + money(b.polymarket) + " · Kalshi " + money(b.kalshi) +
+         " · Total " + money(b.polymarket + b.kalshi) : "");
+  table("pairs", [
+    ["Polymarket", r => [r.polymarket_title]], ["Kalshi", r => [r.kalshi_id]], ["score", r => [r.score.toFixed(2)]],
+    ["PM yes/no", r => [num(r.pm_yes_ask) + " / " + num(r.pm_no_ask)]],
+    ["Kalshi yes/no", r => [num(r.kalshi_yes_ask) + " / " + num(r.kalshi_no_ask)]],
+    ["spread", r => [num(r.spread)]], ["pair cost", r => [num(r.pair_cost), cls(1 - r.pair_cost)]],
+    ["tradable", r => [r.tradable ? "yes" : "no"]],
+  ], s.pairs);
+  table("open", positionColumns, s.open_positions);
+  table("opportunities", [
+    ["strategy", r => [r.strategy]], ["market", r => [r.title]], ["net profit", r => [money(r.net_profit), cls(r.net_profit)]],
+    ["ROI", r => [r.roi_percent.toFixed(2) + "%"]],
+    ["ROI/h", r => [r.roi_per_hour == null ? "-" : r.roi_per_hour.toFixed(2) + "%"]],
+    ["confidence", r => [r.confidence == null ? "-" : r.confidence.toFixed(2)]], ["capital", r => [money(r.capital)]],
+  ], s.opportunities);
+  table("gabagool", [
+    ["event", r => [r.event_id]], ["coin", r => [r.coin]], ["YES", r => [num(r.yes_qty) + " / " + money(r.yes_cost)]],
+    ["NO", r => [num(r.no_qty) + " / " + money(r.no_cost)]], ["pair cost", r => [num(r.pair_cost), r.pair_cost == null ? "" : cls(1 - r.pair_cost)]],
+    ["locked", r => [money(r.locked_profit), cls(r.locked_profit)]],
+  ], s.gabagool);
+  table("recent", positionColumns, s.recent_trades);
+  document.getElementById("status").textContent = "updated " + s.at.slice(11, 19) + " UTC";
+}
+const source = new EventSource("/events");
+source.addEventListener("snapshot", e => render(JSON.parse(e.data)));
+source.onerror = () => { document.getElementById("status").textContent = "disconnected, retrying…"; };
+</script>
+</body>
+</html>
+"#;
@@ -0,0 +1,301 @@
+//! Rate-limited debug bundles written when something looks wrong.
+//!
+//! While capture is enabled (`DEBUG_CAPTURE_DIR`), the clients keep the last
+//! few raw API responses in memory. When an anomaly is reported (huge edge,
+//! price jump, failed leg) a JSON bundle with those responses, the open
+//! positions and the redacted configuration is written to disk, at most once
+//! per `DEBUG_CAPTURE_MIN_INTERVAL_SECS` per anomaly kind.
+
+use crate::event::MarketPrices;
+use crate::money::Price;
+use crate::position_tracker::PositionTracker;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex as StdMutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+const RECENT_RESPONSES: usize = 50;
+const DEFAULT_MIN_INTERVAL_SECS: u64 = 300;
+const DEFAULT_EDGE_THRESHOLD: f64 = 0.25;
+const DEFAULT_JUMP_THRESHOLD: f64 = 0.30;
+
+/// Env vars copied into bundles; values of names containing a secret marker
+/// are redacted.
+const CONFIG_PREFIXES: &[&str] = &[
+    "KALSHI_", "POLYMARKET_", "POLYGON_", "DRY_RUN", "COIN_FILTER", "STRATEGY", "WATCHLIST",
+    "RESTART_", "RECORDER_", "POSITION_", "GABAGOOL_", "DEBUG_CAPTURE_",
+];
+const SECRET_MARKERS: &[&str] = &["KEY", "SECRET", "PASSPHRASE", "PASSWORD", "TOKEN"];
+
+static CAPTURING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RawResponse {
+    pub source: String,
+    pub at: DateTime<Utc>,
+    pub body: serde_json::Value,
+}
+
+fn recent() -> &'static StdMutex<VecDeque<RawResponse>> {
+    static RECENT: OnceLock<StdMutex<VecDeque<RawResponse>>> = OnceLock::new();
+    RECENT.get_or_init(|| StdMutex::new(VecDeque::with_capacity(RECENT_RESPONSES)))
+}
+
+/// Remembers a raw API response for the next bundle. No-op unless a
+/// `DebugCapture` has been created.
+pub fn record_response(source: &str, body: &serde_json::Value) {
+    if !CAPTURING.load(Ordering::Relaxed) {
+        return;
+    }
+    let mut recent = recent().lock().unwrap_or_else(|e| e.into_inner());
+    if recent.len() == RECENT_RESPONSES {
+        recent.pop_front();
+    }
+    recent.push_back(RawResponse {
+        source: source.to_string(),
+        at: Utc::now(),
+        body: body.clone(),
+    });
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    HugeEdge,
+    PriceJump,
+    FailedLeg,
+}
+
+impl AnomalyKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AnomalyKind::HugeEdge => "huge_edge",
+            AnomalyKind::PriceJump => "price_jump",
+            AnomalyKind::FailedLeg => "failed_leg",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct DebugBundle<'a> {
+    kind: AnomalyKind,
+    detail: &'a str,
+    captured_at: DateTime<Utc>,
+    config: HashMap<String, String>,
+    open_positions: serde_json::Value,
+    recent_responses: Vec<RawResponse>,
+}
+
+pub struct DebugCapture {
+    dir: PathBuf,
+    min_interval: Duration,
+    edge_threshold: f64,
+    jump_threshold: f64,
+    position_tracker: Option<Arc<Mutex<PositionTracker>>>,
+    last_capture: StdMutex<HashMap<AnomalyKind, Instant>>,
+    last_prices: StdMutex<HashMap<(String, String), MarketPrices>>,
+}
+
+impl DebugCapture {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        CAPTURING.store(true, Ordering::Relaxed);
+        Self {
+            dir: dir.into(),
+            min_interval: Duration::from_secs(DEFAULT_MIN_INTERVAL_SECS),
+            edge_threshold: DEFAULT_EDGE_THRESHOLD,
+            jump_threshold: DEFAULT_JUMP_THRESHOLD,
+            position_tracker: None,
+            last_capture: StdMutex::new(HashMap::new()),
+            last_prices: StdMutex::new(HashMap::new()),
+        }
+    }
+
+    /// Enabled by `DEBUG_CAPTURE_DIR`; `DEBUG_CAPTURE_MIN_INTERVAL_SECS`,
+    /// `DEBUG_CAPTURE_EDGE` and `DEBUG_CAPTURE_JUMP` tune it.
+    pub fn from_env() -> Option<Self> {
+        let dir = std::env::var("DEBUG_CAPTURE_DIR").ok()?;
+        if dir.trim().is_empty() {
+            return None;
+        }
+        let mut capture = Self::new(dir.trim());
+        if let Some(secs) = env_parse::<u64>("DEBUG_CAPTURE_MIN_INTERVAL_SECS") {
+            capture.min_interval = Duration::from_secs(secs);
+        }
+        if let Some(edge) = env_parse::<f64>("DEBUG_CAPTURE_EDGE") {
+            capture.edge_threshold = edge;
+        }
+        if let Some(jump) = env_parse::<f64>("DEBUG_CAPTURE_JUMP") {
+            capture.jump_threshold = jump;
+        }
+        Some(capture)
+    }
+
+    pub fn with_position_tracker(mut self, tracker: Arc<Mutex<PositionTracker>>) -> Self {
+        self.position_tracker = Some(tracker);
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Flags net edges so large they are more likely bad data than free money.
+    pub async fn observe_edge(&self, title: &str, net_profit: Price) {
+        if net_profit >= Price::from_f64(self.edge_threshold) {
+            let detail = format!(
+                "{}: net edge {} >= {:.4}",
+                title, net_profit, self.edge_threshold
+            );
+            self.capture(AnomalyKind::HugeEdge, &detail).await;
+        }
+    }
+
+    /// Flags YES/NO moves larger than the jump threshold since the last tick.
+    pub async fn observe_prices(&self, platform: &str, event_id: &str, prices: &MarketPrices) {
+        let previous = {
+            let mut last = self.last_prices.lock().unwrap_or_else(|e| e.into_inner());
+            last.insert((platform.to_string(), event_id.to_string()), prices.clone())
+        };
+        let Some(previous) = previous else {
+            return;
+        };
+        if previous.yes_ask <= Price::ZERO || prices.yes_ask <= Price::ZERO {
+            return;
+        }
+        let jump = (prices.yes_ask - previous.yes_ask)
+            .abs()
+            .max((prices.no_ask - previous.no_ask).abs());
+        if jump >= Price::from_f64(self.jump_threshold) {
+            let detail = format!(
+                "{} {}: yes {} -> {}, no {} -> {}",
+                platform, event_id, previous.yes_ask, prices.yes_ask, previous.no_ask, prices.no_ask
+            );
+            self.capture(AnomalyKind::PriceJump, &detail).await;
+        }
+    }
+
+    pub async fn observe_failed_leg(&self, detail: &str) {
+        self.capture(AnomalyKind::FailedLeg, detail).await;
+    }
+
+    /// Writes a bundle unless one of the same kind was written within
+    /// `min_interval`. Returns the bundle path when written.
+    pub async fn capture(&self, kind: AnomalyKind, detail: &str) -> Option<PathBuf> {
+        {
+            let mut last = self.last_capture.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            if let Some(at) = last.get(&kind) {
+                if now.duration_since(*at) < self.min_interval {
+                    return None;
+                }
+            }
+            last.insert(kind, now);
+        }
+
+        match self.write_bundle(kind, detail).await {
+            Ok(path) => {
+                info!("🐞 Captured {} debug bundle: {}", kind.as_str(), path.display());
+                Some(path)
+            }
+            Err(e) => {
+                warn!("Failed to write debug bundle: {}", e);
+                None
+            }
+        }
+    }
+
+    async fn write_bundle(&self, kind: AnomalyKind, detail: &str) -> Result<PathBuf> {
+        let open_positions = match &self.position_tracker {
+            Some(tracker) => serde_json::to_value(tracker.lock().await.get_open_positions())?,
+            None => serde_json::Value::Null,
+        };
+        let recent_responses = recent()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .cloned()
+            .collect();
+        let captured_at = Utc::now();
+        let bundle = DebugBundle {
+            kind,
+            detail,
+            captured_at,
+            config: config_snapshot(),
+            open_positions,
+            recent_responses,
+        };
+
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let path = self.dir.join(format!(
+            "{}_{}.json",
+            captured_at.format("%Y%m%dT%H%M%S%.3fZ"),
+            kind.as_str()
+        ));
+        std::fs::write(&path, serde_json::to_string_pretty(&bundle)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+fn env_parse<T: std::str::FromStr>(name: &str) -> Option<T> {
+    std::env::var(name).ok().and_then(|s| s.trim().parse().ok())
+}
+
+fn config_snapshot() -> HashMap<String, String> {
+    std::env::vars()
+        .filter(|(name, _)| CONFIG_PREFIXES.iter().any(|p| name.starts_with(p)))
+        .map(|(name, value)| {
+            let upper = name.to_uppercase();
+            if SECRET_MARKERS.iter().any(|m| upper.contains(m)) {
+                (name, "<redacted>".to_string())
+            } else {
+                (name, value)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn captures_are_rate_limited_per_kind() {
+        let dir = std::env::temp_dir().join(format!("debug-capture-{}", uuid::Uuid::new_v4()));
+        let capture = DebugCapture::new(&dir);
+        record_response("test", &serde_json::json!({ "yes": 0.5 }));
+
+        let first = capture.capture(AnomalyKind::FailedLeg, "leg failed").await;
+        assert!(first.is_some());
+        assert!(capture.capture(AnomalyKind::FailedLeg, "again").await.is_none());
+        assert!(capture.capture(AnomalyKind::HugeEdge, "other kind").await.is_some());
+
+        let bundle: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(first.unwrap()).unwrap()).unwrap();
+        assert_eq!(bundle["kind"], "failed_leg");
+        assert!(bundle["recent_responses"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|r| r["source"] == "test"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn price_jump_needs_previous_tick() {
+        let dir = std::env::temp_dir().join(format!("debug-capture-{}", uuid::Uuid::new_v4()));
+        let capture = DebugCapture::new(&dir);
+        capture.observe_prices("kalshi", "T", &MarketPrices::new(0.40, 0.60, 0.0)).await;
+        assert!(!dir.exists());
+        capture.observe_prices("kalshi", "T", &MarketPrices::new(0.80, 0.20, 0.0)).await;
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,62 @@
+//! Executable depth: dollars that can actually be bought near the touch.
+//!
+//! `MIN_LIQUIDITY` filters on this rather than traded volume. A market's
+//! depth is the notional resting on the ask side within
+//! `LIQUIDITY_BAND_CENTS` (default 2) of the best ask, taken on the thinner
+//! of its YES and NO sides.
+
+use crate::event::Quote;
+use crate::money::{Price, UsdAmount};
+use std::sync::OnceLock;
+
+pub const DEFAULT_BAND_CENTS: f64 = 2.0;
+
+/// `LIQUIDITY_BAND_CENTS`, in dollars.
+pub fn band() -> f64 {
+    static BAND: OnceLock<f64> = OnceLock::new();
+    *BAND.get_or_init(|| {
+        std::env::var("LIQUIDITY_BAND_CENTS")
+            .ok()
+            .and_then(|s| s.trim().parse::<f64>().ok())
+            .filter(|c| *c >= 0.0)
+            .unwrap_or(DEFAULT_BAND_CENTS)
+            / 100.0
+    })
+}
+
+/// Dollars of `asks` priced within `band` of the best (lowest) ask.
+pub fn notional_within(asks: &[Quote], band: f64) -> f64 {
+    let Some(best) = asks.iter().map(|q| q.price).reduce(f64::min) else {
+        return 0.0;
+    };
+    let limit = Price::from_f64(best + band);
+    asks.iter()
+        .map(|q| (Price::from_f64(q.price), q.size))
+        .filter(|(price, _)| *price <= limit)
+        .map(|(price, size)| UsdAmount::for_shares(size, price))
+        .sum::<UsdAmount>()
+        .to_f64()
+}
+
+/// Depth of a binary market: the thinner side's notional within `band`.
+pub fn market_depth(yes_asks: &[Quote], no_asks: &[Quote], band: f64) -> f64 {
+    notional_within(yes_asks, band).min(notional_within(no_asks, band))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_only_asks_near_the_touch() {
+        let quote = |price, size| Quote { price, size };
+        let yes = [quote(0.45, 100.0), quote(0.47, 50.0), quote(0.60, 1000.0)];
+        // 45 + 23.50 within two cents; the 0.60 level doesn't count.
+        assert!((notional_within(&yes, 0.02) - 68.5).abs() < 1e-9);
+        assert!((notional_within(&yes, 0.0) - 45.0).abs() < 1e-9);
+        assert_eq!(notional_within(&[], 0.02), 0.0);
+
+        let no = [quote(0.52, 20.0)];
+        assert!((market_depth(&yes, &no, 0.02) - 10.4).abs() < 1e-9);
+    }
+}
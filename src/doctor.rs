@@ -0,0 +1,292 @@
+//! `doctor`: pre-flight checks before going live.
+//!
+//! Checks that the credentials a live run needs are set, that the Kalshi RSA
+//! keys (every `KALSHI_CREDENTIALS` one too) parse and the trade accounts
+//! authenticate (a balance call), that the Polygon RPC in `POLYGON_RPC_URL`
+//! answers as Polygon mainnet, the remote signer's or Ledger's settings when
+//! one is configured, the wallet's USDC and POL balances, and that the
+//! Polymarket CLOB accepts the wallet's credentials.
+//! Prints a pass / warn / fail line per check; any failure fails the command.
+
+use crate::clients::KalshiClient;
+use crate::config::KalshiConfig;
+use crate::kalshi_credentials::KalshiCredentials;
+use crate::polymarket_blockchain::PolymarketBlockchain;
+use crate::polymarket_clob;
+use crate::signer;
+use anyhow::Result;
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::RsaPrivateKey;
+use std::future::Future;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+const POLYGON_CHAIN_ID: u64 = 137;
+/// Below this much POL, approvals and redemptions may run out of gas.
+const MIN_POL_BALANCE: f64 = 0.1;
+const CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self { name, status: CheckStatus::Pass, detail: detail.into() }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>) -> Self {
+        Self { name, status: CheckStatus::Warn, detail: detail.into() }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>) -> Self {
+        Self { name, status: CheckStatus::Fail, detail: detail.into() }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DoctorReport {
+    pub checks: Vec<Check>,
+}
+
+impl DoctorReport {
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.status != CheckStatus::Fail)
+    }
+
+    pub fn failures(&self) -> usize {
+        self.checks.iter().filter(|c| c.status == CheckStatus::Fail).count()
+    }
+
+    pub fn log(&self) {
+        for check in &self.checks {
+            match check.status {
+                CheckStatus::Pass => info!("   ✅ {:<18} {}", check.name, check.detail),
+                CheckStatus::Warn => warn!("   ⚠️ {:<18} {}", check.name, check.detail),
+                CheckStatus::Fail => error!("   ❌ {:<18} {}", check.name, check.detail),
+            }
+        }
+        if self.passed() {
+            info!("🩺 Doctor: all {} checks passed - ready to go live", self.checks.len());
+        } else {
+            error!("🩺 Doctor: {} of {} checks failed", self.failures(), self.checks.len());
+        }
+    }
+}
+
+/// Whether each credential a live run needs is set, by any of its names.
+fn env_checks(var: impl Fn(&str) -> Option<String>) -> Vec<Check> {
+    let set = |names: &[&'static str]| -> Option<&'static str> {
+        names
+            .iter()
+            .find(|name| var(name).is_some_and(|v| !v.trim().is_empty()))
+            .copied()
+    };
+    let required: [(&'static str, &[&'static str]); 3] = [
+        ("Kalshi API key", &["KALSHI_API_ID", "KALSHI_API_KEY"]),
+        (
+            "Kalshi RSA key",
+            &["KALSHI_RSA_PRIVATE_KEY", "KALSHI_PRIVATE_KEY_PATH", "KALSHI_PRIVATE_KEY_PEM"],
+        ),
+        ("Polymarket wallet", &["POLYMARKET_WALLET_PRIVATE_KEY", "POLYMARKET_PRIVATE_KEY"]),
+    ];
+    let mut checks: Vec<Check> = required
+        .iter()
+        .map(|(name, vars)| match set(vars) {
+            Some(found) => Check::pass(name, format!("{} set", found)),
+            None => Check::fail(name, format!("set one of {}", vars.join(", "))),
+        })
+        .collect();
+    checks.push(match set(&["POLYGON_RPC_URL"]) {
+        Some(_) => Check::pass("Polygon RPC URL", "POLYGON_RPC_URL set"),
+        None => Check::warn("Polygon RPC URL", "POLYGON_RPC_URL unset - using the public polygon-rpc.com"),
+    });
+    let dry_run = ["DRY_RUN", "KALSHI_DRY_RUN"]
+        .iter()
+        .any(|name| var(name).is_some_and(|v| v.trim().eq_ignore_ascii_case("true")));
+    if dry_run {
+        checks.push(Check::warn("Dry run", "DRY_RUN is on - no real orders will be placed"));
+    }
+    checks
+}
+
+/// Runs a network check, failing it once `CHECK_TIMEOUT` passes.
+async fn timed<T>(future: impl Future<Output = Result<T>>) -> Result<T> {
+    tokio::time::timeout(CHECK_TIMEOUT, future)
+        .await
+        .unwrap_or_else(|_| Err(anyhow::anyhow!("timed out after {}s", CHECK_TIMEOUT.as_secs())))
+}
+
+fn parse_rsa_key(pem: &str) -> Result<RsaPrivateKey> {
+    RsaPrivateKey::from_pkcs8_pem(pem)
+        .or_else(|_| RsaPrivateKey::from_pkcs1_pem(pem))
+        .map_err(|e| anyhow::anyhow!("not a PKCS#1 or PKCS#8 PEM RSA key: {}", e))
+}
+
+async fn kalshi_checks(checks: &mut Vec<Check>) {
+    let config = KalshiConfig::from_env();
+    if config.api_id.is_empty() || config.rsa_private_key.is_empty() {
+        return;
+    }
+    if let Err(e) = parse_rsa_key(&config.rsa_private_key) {
+        checks.push(Check::fail("Kalshi RSA parse", e.to_string()));
+        return;
+    }
+    checks.push(Check::pass("Kalshi RSA parse", "key parses"));
+    let mut client = KalshiClient::from_config(&config);
+    match KalshiCredentials::from_env(&config.api_id, &config.rsa_private_key) {
+        Ok(Some(credentials)) => {
+            let unparsed: Vec<&str> = credentials
+                .iter()
+                .filter(|c| parse_rsa_key(&c.rsa_private_key).is_err())
+                .map(|c| c.name.as_str())
+                .collect();
+            if !unparsed.is_empty() {
+                let detail = format!("RSA keys don't parse: {}", unparsed.join(", "));
+                checks.push(Check::fail("Kalshi credentials", detail));
+                return;
+            }
+            let names: Vec<String> = credentials
+                .iter()
+                .map(|c| format!("{}:{}", c.name, c.role.as_str()))
+                .collect();
+            checks.push(Check::pass("Kalshi credentials", names.join(", ")));
+            client = client.with_credentials(credentials);
+        }
+        Ok(None) => {}
+        Err(e) => {
+            checks.push(Check::fail("Kalshi credentials", format!("{:#}", e)));
+            return;
+        }
+    }
+    checks.push(match timed(client.get_balance()).await {
+        Ok(balance) => Check::pass(
+            "Kalshi auth",
+            format!("{} ({}) - balance ${:.2}", config.base_url(), config.signing_scheme.as_str(), balance),
+        ),
+        Err(e) => Check::fail(
+            "Kalshi auth",
+            format!("{} ({}): {:#}", config.base_url(), config.signing_scheme.as_str(), e),
+        ),
+    });
+}
+
+async fn polygon_checks(checks: &mut Vec<Check>) {
+    let rpc = std::env::var("POLYGON_RPC_URL").unwrap_or_else(|_| "https://polygon-rpc.com".to_string());
+    let chain = match PolymarketBlockchain::new(&rpc) {
+        Ok(chain) => chain,
+        Err(e) => {
+            checks.push(Check::fail("Polygon RPC", format!("{:#}", e)));
+            return;
+        }
+    };
+    match timed(chain.rpc_chain_id()).await {
+        Ok(POLYGON_CHAIN_ID) => checks.push(Check::pass("Polygon RPC", "reachable, chain id 137")),
+        Ok(other) => {
+            let detail = format!("chain id {} - expected Polygon mainnet (137)", other);
+            checks.push(Check::fail("Polygon RPC", detail));
+            return;
+        }
+        Err(e) => {
+            checks.push(Check::fail("Polygon RPC", format!("{:#}", e)));
+            return;
+        }
+    }
+
+    let key = std::env::var("POLYMARKET_WALLET_PRIVATE_KEY")
+        .or_else(|_| std::env::var("POLYMARKET_PRIVATE_KEY"))
+        .ok();
+    let signer = match signer::from_env(key.as_deref(), POLYGON_CHAIN_ID).await {
+        Ok(Some(signer)) => signer,
+        Ok(None) => return,
+        Err(e) => {
+            checks.push(Check::fail("Signer", format!("{:#}", e)));
+            return;
+        }
+    };
+    if std::env::var("POLYMARKET_REMOTE_SIGNER_URL").is_ok_and(|s| !s.trim().is_empty()) {
+        checks.push(Check::pass("Signer", format!("remote, signing as {:?}", signer.address())));
+    } else if matches!(signer::LedgerConfig::from_env(), Ok(Some(_))) {
+        checks.push(Check::pass("Signer", format!("Ledger, signing as {:?}", signer.address())));
+    }
+    let chain = match chain.with_signer(signer).with_funder_from_env() {
+        Ok(chain) => chain,
+        Err(e) => {
+            checks.push(Check::fail("Wallet", format!("{:#}", e)));
+            return;
+        }
+    };
+    checks.push(match timed(chain.get_usdc_balance()).await {
+        Ok(balance) if balance > 0.0 => Check::pass("Wallet USDC", format!("${:.2}", balance)),
+        Ok(_) => Check::warn("Wallet USDC", "$0.00 - CLOB V2 trades pUSD, check the Polymarket balance"),
+        Err(e) => Check::fail("Wallet USDC", format!("{:#}", e)),
+    });
+    checks.push(match timed(chain.get_pol_balance()).await {
+        Ok(pol) if pol >= MIN_POL_BALANCE => Check::pass("Wallet POL", format!("{:.4} POL", pol)),
+        Ok(pol) => Check::warn(
+            "Wallet POL",
+            format!("{:.4} POL - under {} for approval and redemption gas", pol, MIN_POL_BALANCE),
+        ),
+        Err(e) => Check::fail("Wallet POL", format!("{:#}", e)),
+    });
+}
+
+async fn clob_checks(checks: &mut Vec<Check>) {
+    checks.push(match timed(polymarket_clob::check_clob_credentials()).await {
+        Ok(address) => Check::pass("Polymarket CLOB", format!("authenticated as {}", address)),
+        Err(e) => Check::fail("Polymarket CLOB", format!("{:#}", e)),
+    });
+}
+
+/// Runs every check; network checks are skipped when their credentials are
+/// missing (already reported as failures).
+pub async fn run() -> DoctorReport {
+    let mut checks = env_checks(|name| std::env::var(name).ok());
+    kalshi_checks(&mut checks).await;
+    polygon_checks(&mut checks).await;
+    let has_wallet = checks
+        .iter()
+        .any(|c| c.name == "Polymarket wallet" && c.status == CheckStatus::Pass);
+    if has_wallet {
+        clob_checks(&mut checks).await;
+    }
+    DoctorReport { checks }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn missing_credentials_fail_the_report() {
+        let env = HashMap::from([
+            ("KALSHI_API_KEY", "abc"),
+            ("KALSHI_PRIVATE_KEY_PATH", "kalshi.pem"),
+            ("DRY_RUN", "true"),
+        ]);
+        let report = DoctorReport {
+            checks: env_checks(|name| env.get(name).map(|v| v.to_string())),
+        };
+        let status = |name: &str| report.checks.iter().find(|c| c.name == name).unwrap().status;
+        assert_eq!(status("Kalshi API key"), CheckStatus::Pass);
+        assert_eq!(status("Kalshi RSA key"), CheckStatus::Pass);
+        assert_eq!(status("Polymarket wallet"), CheckStatus::Fail);
+        assert_eq!(status("Polygon RPC URL"), CheckStatus::Warn);
+        assert_eq!(status("Dry run"), CheckStatus::Warn);
+        assert!(!report.passed());
+        assert_eq!(report.failures(), 1);
+        assert!(parse_rsa_key("not a key").is_err());
+    }
+}
@@ -0,0 +1,229 @@
+//! Early exit for hedged cross-platform pairs.
+//!
+//! A pair bought below $1 pays its profit at settlement, but once the two
+//! venues converge most of that profit is already available by selling both
+//! legs into their bids. When the exit profit reaches `capture` of the
+//! worst-case settlement profit, both legs are sold so the capital is free
+//! for the next 15-minute cycle. Legs are closed only for the shares the
+//! sells are confirmed to have filled, at the fill price.
+//!
+//! Enabled with `EARLY_EXIT_CAPTURE` (e.g. `0.8`).
+
+use crate::exchange::{confirm_fill, ExchangeClient, FILL_CONFIRM_TIMEOUT};
+use crate::money::{Price, UsdAmount};
+use crate::position_tracker::{Position, PositionTracker};
+use crate::strategy::CROSS_PLATFORM;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+
+/// One leg as seen at exit time.
+#[derive(Debug, Clone, Copy)]
+pub struct ExitLeg {
+    pub shares: f64,
+    pub cost: UsdAmount,
+    pub bid: Price,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExitQuote {
+    /// Sale value of both legs after fees.
+    pub proceeds: UsdAmount,
+    pub exit_profit: UsdAmount,
+    /// Profit at settlement if the smaller leg is the one that pays.
+    pub hold_profit: UsdAmount,
+}
+
+/// What `shares` fetch sold at `bid`, net of fees.
+pub fn sale_value(shares: f64, bid: Price, fee_per_share: f64) -> UsdAmount {
+    UsdAmount::for_shares(shares, bid - Price::from_f64(fee_per_share))
+}
+
+/// Prices an exit of complementary legs; `Some` when selling now captures
+/// at least `capture` of the worst-case settlement profit.
+pub fn evaluate_exit(legs: &[ExitLeg], fee_per_share: f64, capture: f64) -> Option<ExitQuote> {
+    if legs.len() != 2 || legs.iter().any(|l| l.bid <= Price::ZERO || l.shares <= 0.0) {
+        return None;
+    }
+    let cost: UsdAmount = legs.iter().map(|l| l.cost).sum();
+    let proceeds: UsdAmount = legs.iter().map(|l| sale_value(l.shares, l.bid, fee_per_share)).sum();
+    let payout = legs.iter().map(|l| l.shares).fold(f64::INFINITY, f64::min);
+    let hold_profit = UsdAmount::for_shares(payout, Price::ONE) - cost;
+    let exit_profit = proceeds - cost;
+    if exit_profit <= UsdAmount::ZERO || exit_profit.to_f64() < capture * hold_profit.to_f64() {
+        return None;
+    }
+    Some(ExitQuote {
+        proceeds,
+        exit_profit,
+        hold_profit,
+    })
+}
+
+pub struct EarlyExit {
+    polymarket_client: Arc<dyn ExchangeClient>,
+    kalshi_client: Arc<dyn ExchangeClient>,
+    position_tracker: Arc<Mutex<PositionTracker>>,
+    capture: f64,
+    fee_per_share: f64,
+}
+
+impl EarlyExit {
+    pub fn new(
+        polymarket_client: Arc<dyn ExchangeClient>,
+        kalshi_client: Arc<dyn ExchangeClient>,
+        position_tracker: Arc<Mutex<PositionTracker>>,
+        capture: f64,
+    ) -> Self {
+        Self {
+            polymarket_client,
+            kalshi_client,
+            position_tracker,
+            capture,
+            fee_per_share: 0.01,
+        }
+    }
+
+    pub fn with_fee_per_share(mut self, fee: f64) -> Self {
+        self.fee_per_share = fee;
+        self
+    }
+
+    /// Reads `EARLY_EXIT_CAPTURE`; `None` when unset.
+    pub fn capture_from_env() -> Option<f64> {
+        std::env::var("EARLY_EXIT_CAPTURE")
+            .ok()
+            .and_then(|s| s.trim().parse::<f64>().ok())
+            .filter(|c| *c > 0.0)
+    }
+
+    fn client(&self, platform: &str) -> &dyn ExchangeClient {
+        if platform == "kalshi" {
+            self.kalshi_client.as_ref()
+        } else {
+            self.polymarket_client.as_ref()
+        }
+    }
+
+    /// Open cross-platform pairs, by group id.
+    async fn open_pairs(&self) -> Vec<Vec<Position>> {
+        let tracker = self.position_tracker.lock().await;
+        let mut groups: HashMap<String, Vec<Position>> = HashMap::new();
+        for position in tracker.get_open_positions() {
+            if position.strategy.as_deref() != Some(CROSS_PLATFORM) {
+                continue;
+            }
+            if let Some(group) = &position.group_id {
+                groups.entry(group.clone()).or_default().push(position.clone());
+            }
+        }
+        groups.into_values().filter(|legs| legs.len() == 2).collect()
+    }
+
+    /// Checks every open pair and sells the converged ones; returns how many
+    /// pairs were fully exited.
+    pub async fn run_tick(&self) -> usize {
+        let mut exited = 0;
+        for legs in self.open_pairs().await {
+            match self.try_exit(&legs).await {
+                Ok(true) => exited += 1,
+                Ok(false) => {}
+                Err(e) => warn!("Early exit check failed for {}: {}", legs[0].event_title, e),
+            }
+        }
+        exited
+    }
+
+    async fn try_exit(&self, legs: &[Position]) -> anyhow::Result<bool> {
+        let prices = futures::future::join_all(
+            legs.iter()
+                .map(|leg| self.client(&leg.platform).fetch_prices(&leg.event_id)),
+        )
+        .await;
+        let mut exit_legs = Vec::with_capacity(legs.len());
+        for (leg, prices) in legs.iter().zip(prices) {
+            exit_legs.push(ExitLeg {
+                shares: leg.amount,
+                cost: leg.cost,
+                bid: prices?.bid_for(&leg.outcome),
+            });
+        }
+        let Some(quote) = evaluate_exit(&exit_legs, self.fee_per_share, self.capture) else {
+            return Ok(false);
+        };
+
+        info!(
+            "💸 Early exit {}: {} now vs {} at settlement",
+            legs[0].event_title, quote.exit_profit, quote.hold_profit
+        );
+        let sells = futures::future::join_all(legs.iter().zip(&exit_legs).map(|(leg, exit)| async move {
+            let client = self.client(&leg.platform);
+            let order_id = client
+                .sell_order(leg.event_id.clone(), leg.outcome.clone(), leg.amount, exit.bid.to_f64())
+                .await?;
+            confirm_fill(
+                client,
+                &leg.event_id,
+                order_id.as_deref(),
+                leg.amount,
+                exit.bid.to_f64(),
+                FILL_CONFIRM_TIMEOUT,
+            )
+            .await
+        }))
+        .await;
+
+        let mut tracker = self.position_tracker.lock().await;
+        let mut all_sold = true;
+        for (leg, result) in legs.iter().zip(sells) {
+            match result {
+                Ok(fill) => {
+                    if fill.shares > 0.0 {
+                        let proceeds = sale_value(fill.shares, Price::from_f64(fill.price), self.fee_per_share);
+                        tracker.close_shares(&leg.id, fill.shares, proceeds);
+                    }
+                    if fill.shares < leg.amount - 1e-9 {
+                        all_sold = false;
+                        warn!(
+                            "⚠️ Early exit sell on {} filled {:.4} of {:.4} {} shares - the rest stays open",
+                            leg.platform, fill.shares, leg.amount, leg.outcome
+                        );
+                    }
+                }
+                Err(e) => {
+                    all_sold = false;
+                    error!(
+                        "❌ Early exit sell failed on {}: {} - {:.4} {} shares left open",
+                        leg.platform, e, leg.amount, leg.outcome
+                    );
+                }
+            }
+        }
+        Ok(all_sold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exits_only_once_most_profit_is_available() {
+        // $100 on each leg: 222.2 NO @ 0.45 and 250 YES @ 0.40; settlement
+        // pays at least 222.2, so $22.2 profit is locked in.
+        let legs = |pm_bid: f64, kalshi_bid: f64| {
+            let cost = UsdAmount::from_f64(100.0);
+            [
+                ExitLeg { shares: 100.0 / 0.45, cost, bid: Price::from_f64(pm_bid) },
+                ExitLeg { shares: 250.0, cost, bid: Price::from_f64(kalshi_bid) },
+            ]
+        };
+        assert!(evaluate_exit(&legs(0.45, 0.40), 0.01, 0.8).is_none());
+
+        let quote = evaluate_exit(&legs(0.50, 0.49), 0.01, 0.8).unwrap();
+        assert_eq!(quote.hold_profit.round_cents(), UsdAmount::from_f64(22.22));
+        assert!(quote.exit_profit.to_f64() > 0.8 * quote.hold_profit.to_f64());
+        assert!(evaluate_exit(&legs(0.50, 0.49), 0.01, 1.5).is_none());
+    }
+}
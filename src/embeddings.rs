@@ -0,0 +1,184 @@
+//! Sentence-embedding similarity for event titles.
+//!
+//! String similarity misses identical markets worded differently ("Bitcoin
+//! Up or Down" vs "BTC price up in next 15 mins?"). With `EMBEDDINGS_URL`
+//! set to an OpenAI-compatible `/embeddings` endpoint, titles are embedded
+//! (`EMBEDDINGS_MODEL`, default `text-embedding-3-small`, authorised with
+//! `EMBEDDINGS_API_KEY`) before each match and `EventMatcher` blends their
+//! cosine similarity into its text score with weight `EMBEDDINGS_WEIGHT`
+//! (default 0.7; 1 replaces string similarity outright).
+//!
+//! Vectors are cached per title. When the API fails the matcher falls back
+//! to string similarity for titles it has no vector for.
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+const DEFAULT_MODEL: &str = "text-embedding-3-small";
+const DEFAULT_WEIGHT: f64 = 0.7;
+/// Titles per request.
+const BATCH_SIZE: usize = 256;
+/// The cache is dropped once it holds this many titles; 15-minute series
+/// mint new titles all day.
+const MAX_CACHED: usize = 20_000;
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f64, 0.0f64, 0.0f64);
+    for (x, y) in a.iter().zip(b) {
+        let (x, y) = (*x as f64, *y as f64);
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Vectors of an `/embeddings` response, in input order.
+fn parse_embeddings(response: &Value, expected: usize) -> Result<Vec<Vec<f32>>> {
+    let data = response["data"]
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("Embeddings response has no data"))?;
+    let mut vectors = vec![None; expected];
+    for (position, item) in data.iter().enumerate() {
+        let index = item["index"].as_u64().map_or(position, |i| i as usize);
+        let vector: Vec<f32> = item["embedding"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("Embedding {} has no vector", index))?
+            .iter()
+            .filter_map(|v| v.as_f64().map(|f| f as f32))
+            .collect();
+        if let Some(slot) = vectors.get_mut(index) {
+            *slot = Some(vector);
+        }
+    }
+    vectors
+        .into_iter()
+        .enumerate()
+        .map(|(i, v)| v.ok_or_else(|| anyhow::anyhow!("Embeddings response is missing input {}", i)))
+        .collect()
+}
+
+pub struct EmbeddingClient {
+    http: Client,
+    url: String,
+    model: String,
+    api_key: Option<String>,
+    weight: f64,
+    cache: Mutex<HashMap<String, Vec<f32>>>,
+}
+
+impl EmbeddingClient {
+    pub fn new(url: &str, model: &str) -> Self {
+        Self {
+            http: Client::builder()
+                .timeout(std::time::Duration::from_secs(20))
+                .build()
+                .unwrap_or_else(|_| Client::new()),
+            url: url.to_string(),
+            model: model.to_string(),
+            api_key: None,
+            weight: DEFAULT_WEIGHT,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn from_env() -> Option<Self> {
+        let var = |key: &str| std::env::var(key).ok().filter(|s| !s.trim().is_empty());
+        let url = var("EMBEDDINGS_URL")?;
+        let model = var("EMBEDDINGS_MODEL").unwrap_or_else(|| DEFAULT_MODEL.to_string());
+        let mut client = Self::new(url.trim(), model.trim());
+        client.api_key = var("EMBEDDINGS_API_KEY");
+        if let Some(weight) = var("EMBEDDINGS_WEIGHT").and_then(|s| s.trim().parse::<f64>().ok()) {
+            client = client.with_weight(weight);
+        }
+        Some(client)
+    }
+
+    /// Share of the text score taken by embedding similarity, in [0, 1].
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = weight.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    /// Cosine similarity of two embedded texts; `None` unless both are cached.
+    pub fn similarity(&self, a: &str, b: &str) -> Option<f64> {
+        let cache = self.cache.lock().ok()?;
+        Some(cosine_similarity(cache.get(a)?, cache.get(b)?))
+    }
+
+    /// Embeds whichever of `texts` aren't cached yet.
+    pub async fn prefetch(&self, texts: &[&str]) -> Result<()> {
+        let mut missing: Vec<String> = {
+            let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+            texts
+                .iter()
+                .filter(|t| !cache.contains_key(**t))
+                .map(|t| t.to_string())
+                .collect()
+        };
+        missing.sort();
+        missing.dedup();
+        for batch in missing.chunks(BATCH_SIZE) {
+            let vectors = self.embed(batch).await?;
+            let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+            if cache.len() + batch.len() > MAX_CACHED {
+                cache.clear();
+            }
+            cache.extend(batch.iter().cloned().zip(vectors));
+        }
+        Ok(())
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut request = self
+            .http
+            .post(&self.url)
+            .json(&json!({ "model": self.model, "input": texts }));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let response = request.send().await.context("Failed to call embeddings API")?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Embeddings API error {}: {}", status, body));
+        }
+        let body: Value = response.json().await.context("Failed to parse embeddings response")?;
+        parse_embeddings(&body, texts.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_vectors_in_input_order() {
+        let response = json!({
+            "data": [
+                {"index": 1, "embedding": [0.0, 1.0]},
+                {"index": 0, "embedding": [1.0, 0.0]}
+            ]
+        });
+        let vectors = parse_embeddings(&response, 2).unwrap();
+        assert_eq!(vectors, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+        assert!(parse_embeddings(&response, 3).is_err());
+
+        assert!((cosine_similarity(&[1.0, 1.0], &[2.0, 2.0]) - 1.0).abs() < 1e-9);
+        assert!(cosine_similarity(&vectors[0], &vectors[1]).abs() < 1e-9);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+    }
+}
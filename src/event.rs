@@ -1,5 +1,7 @@
+use crate::money::{Price, UsdAmount};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Event {
@@ -13,6 +15,25 @@ pub struct Event {
     pub slug: Option<String>,
     pub yes_token_id: Option<String>,
     pub no_token_id: Option<String>,
+    /// Mutually exclusive outcomes of a multi-outcome (Polymarket neg-risk)
+    /// event, each traded as its own YES/NO market. Empty for binary events.
+    #[serde(default)]
+    pub outcomes: Vec<EventOutcome>,
+    /// Resolution rules: Polymarket's resolution source and description,
+    /// Kalshi's market rules. Compared to catch pairs settling on different
+    /// price sources.
+    #[serde(default)]
+    pub rules: Option<String>,
+}
+
+/// One outcome of a multi-outcome event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventOutcome {
+    pub name: String,
+    /// Condition id of the outcome's own YES/NO market.
+    pub market_id: String,
+    pub yes_token_id: Option<String>,
+    pub no_token_id: Option<String>,
 }
 
 impl Event {
@@ -33,9 +54,16 @@ impl Event {
             slug: None,
             yes_token_id: None,
             no_token_id: None,
+            outcomes: Vec::new(),
+            rules: None,
         }
     }
 
+    pub fn with_rules(mut self, rules: String) -> Self {
+        self.rules = Some(rules);
+        self
+    }
+
     pub fn with_resolution_date(mut self, date: DateTime<Utc>) -> Self {
         self.resolution_date = Some(date);
         self
@@ -62,6 +90,32 @@ impl Event {
         self
     }
 
+    pub fn with_outcomes(mut self, outcomes: Vec<EventOutcome>) -> Self {
+        self.outcomes = outcomes;
+        self
+    }
+
+    /// True for events with more than two mutually exclusive outcomes.
+    pub fn is_multi_outcome(&self) -> bool {
+        self.outcomes.len() > 2
+    }
+
+    /// The outcome's own market as a standalone event, for pricing, orders
+    /// and position tracking.
+    pub fn outcome_event(&self, outcome: &EventOutcome) -> Event {
+        let mut event = Event::new(
+            self.platform.clone(),
+            outcome.market_id.clone(),
+            format!("{} — {}", self.title, outcome.name),
+            String::new(),
+        );
+        event.resolution_date = self.resolution_date;
+        event.category = self.category.clone();
+        event.yes_token_id = outcome.yes_token_id.clone();
+        event.no_token_id = outcome.no_token_id.clone();
+        event
+    }
+
     pub fn slug_is_15m_crypto(&self) -> bool {
         self.slug
             .as_deref()
@@ -112,45 +166,172 @@ impl Event {
     }
 }
 
+/// One price level: price in dollars, size in contracts or shares.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quote {
+    pub price: f64,
+    pub size: f64,
+}
+
+/// Best bid and ask on each side of a binary market's book.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BookTop {
+    pub yes_bid: Option<Quote>,
+    pub yes_ask: Option<Quote>,
+    pub no_bid: Option<Quote>,
+    pub no_ask: Option<Quote>,
+}
+
+/// Every level of a binary market's book, best price first on each side.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BookLevels {
+    pub yes_bids: Vec<Quote>,
+    pub yes_asks: Vec<Quote>,
+    pub no_bids: Vec<Quote>,
+    pub no_asks: Vec<Quote>,
+}
+
+impl BookLevels {
+    /// Orders each side best first: bids high to low, asks low to high.
+    pub fn sorted(
+        mut yes_bids: Vec<Quote>,
+        mut yes_asks: Vec<Quote>,
+        mut no_bids: Vec<Quote>,
+        mut no_asks: Vec<Quote>,
+    ) -> Self {
+        for bids in [&mut yes_bids, &mut no_bids] {
+            bids.sort_by(|a, b| b.price.total_cmp(&a.price));
+        }
+        for asks in [&mut yes_asks, &mut no_asks] {
+            asks.sort_by(|a, b| a.price.total_cmp(&b.price));
+        }
+        Self { yes_bids, yes_asks, no_bids, no_asks }
+    }
+}
+
+/// Executable quotes for a binary market: the ask is what buying a side
+/// costs, the bid what selling it fetches. Anything that buys must price
+/// off the asks; bids (or mids) overstate the edge.
 #[derive(Debug, Clone)]
 pub struct MarketPrices {
-    pub yes: f64,
-    pub no: f64,
-    pub liquidity: f64,
-    pub yes_ask: Option<f64>,
-    pub no_ask: Option<f64>,
-    pub last_price: Option<f64>,
+    pub yes_bid: Option<Price>,
+    pub yes_ask: Price,
+    pub no_bid: Option<Price>,
+    pub no_ask: Price,
+    /// Dollars buyable near the best ask on the thinner side (see
+    /// [`crate::depth`]).
+    pub depth: UsdAmount,
+    pub last_price: Option<Price>,
+    /// Top of the book with sizes, when the venue's book was read.
+    pub book: Option<BookTop>,
+    /// The full book, when the venue's book was read.
+    pub levels: Option<Arc<BookLevels>>,
+    pub fetched_at: DateTime<Utc>,
 }
 
 impl MarketPrices {
-    pub fn new(yes: f64, no: f64, liquidity: f64) -> Self {
+    /// Quotes with only the asks known, fetched now, from the venue's
+    /// dollar figures.
+    pub fn new(yes_ask: f64, no_ask: f64, depth: f64) -> Self {
         Self {
-            yes,
-            no,
-            liquidity,
-            yes_ask: None,
-            no_ask: None,
+            yes_bid: None,
+            yes_ask: Price::from_f64(yes_ask),
+            no_bid: None,
+            no_ask: Price::from_f64(no_ask),
+            depth: UsdAmount::from_f64(depth),
             last_price: None,
+            book: None,
+            levels: None,
+            fetched_at: Utc::now(),
         }
     }
 
-    pub fn with_asks(mut self, yes_ask: f64, no_ask: f64, last_price: Option<f64>) -> Self {
-        self.yes_ask = Some(yes_ask);
-        self.no_ask = Some(no_ask);
-        self.last_price = last_price;
+    pub fn with_bids(mut self, yes_bid: Option<f64>, no_bid: Option<f64>) -> Self {
+        self.yes_bid = yes_bid.map(Price::from_f64);
+        self.no_bid = no_bid.map(Price::from_f64);
         self
     }
 
+    pub fn with_last_price(mut self, last_price: Option<f64>) -> Self {
+        self.last_price = last_price.map(Price::from_f64);
+        self
+    }
+
+    /// Sets the book, taking the bids from it where it has them.
+    pub fn with_book(mut self, book: BookTop) -> Self {
+        self.yes_bid = book.yes_bid.map(|q| Price::from_f64(q.price)).or(self.yes_bid);
+        self.no_bid = book.no_bid.map(|q| Price::from_f64(q.price)).or(self.no_bid);
+        self.book = Some(book);
+        self
+    }
+
+    pub fn with_levels(mut self, levels: BookLevels) -> Self {
+        self.levels = Some(Arc::new(levels));
+        self
+    }
+
+    pub fn with_fetched_at(mut self, fetched_at: DateTime<Utc>) -> Self {
+        self.fetched_at = fetched_at;
+        self
+    }
+
+    /// Whether these quotes can be traded on at all; see `invalid_reason`.
     pub fn validate(&self) -> bool {
-        (self.yes + self.no - 1.0).abs() < 0.01
+        self.invalid_reason().is_none()
+    }
+
+    /// What's wrong with these quotes, if anything: an ask outside (0, 1)
+    /// (a failed fetch, or a NaN, comes back as zero), a bid outside [0, 1]
+    /// or above its ask, or a negative depth.
+    pub fn invalid_reason(&self) -> Option<String> {
+        for (side, ask, bid) in [("YES", self.yes_ask, self.yes_bid), ("NO", self.no_ask, self.no_bid)] {
+            if !(ask > Price::ZERO && ask < Price::ONE) {
+                return Some(format!("{} ask {} outside (0, 1)", side, ask));
+            }
+            match bid {
+                Some(bid) if !(Price::ZERO..=Price::ONE).contains(&bid) => {
+                    return Some(format!("{} bid {} outside [0, 1]", side, bid));
+                }
+                Some(bid) if bid > ask => {
+                    return Some(format!("{} bid {} above its ask {}", side, bid, ask));
+                }
+                _ => {}
+            }
+        }
+        if self.depth < UsdAmount::ZERO {
+            return Some(format!("depth {}", self.depth));
+        }
+        None
+    }
+
+    /// Price to buy `outcome` ("YES" / "NO").
+    pub fn ask_for(&self, outcome: &str) -> Price {
+        if outcome.eq_ignore_ascii_case("YES") {
+            self.yes_ask
+        } else {
+            self.no_ask
+        }
+    }
+
+    /// Best bid for `outcome`: the quoted bid when known, otherwise one
+    /// minus the opposite side's ask.
+    pub fn bid_for(&self, outcome: &str) -> Price {
+        let (bid, opposite_ask) = if outcome.eq_ignore_ascii_case("YES") {
+            (self.yes_bid, self.no_ask)
+        } else {
+            (self.no_bid, self.yes_ask)
+        };
+        bid.unwrap_or_else(|| opposite_ask.complement())
     }
 
-    pub fn yes_ask_or_fallback(&self) -> f64 {
-        self.yes_ask.unwrap_or(self.yes)
+    /// Bid-ask spread of `outcome`.
+    pub fn spread(&self, outcome: &str) -> Price {
+        (self.ask_for(outcome) - self.bid_for(outcome)).max(Price::ZERO)
     }
 
-    pub fn no_ask_or_fallback(&self) -> f64 {
-        self.no_ask.unwrap_or(self.no)
+    /// How long ago these quotes were fetched.
+    pub fn age(&self) -> chrono::Duration {
+        Utc::now() - self.fetched_at
     }
 }
 
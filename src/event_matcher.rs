@@ -1,16 +1,32 @@
+use crate::embeddings::EmbeddingClient;
 use crate::event::Event;
-use chrono::{DateTime, Utc, FixedOffset, TimeZone};
+use crate::mappings::{Mapping, MarketMappings};
+use crate::match_store::MatchStore;
+use crate::strike::{self, MarketTerms};
+use chrono::{DateTime, Duration, Utc};
 use regex::Regex;
 use std::collections::HashSet;
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
 
 #[derive(Debug, Clone)]
 pub struct MatchConfidence {
+    /// Title similarity: string similarity, blended with embedding
+    /// similarity when both titles have been embedded.
     pub text_similarity: f64,
+    pub embedding_similarity: Option<f64>,
     pub date_match: bool,
     pub category_match: bool,
     pub keyword_overlap: f64,
     pub number_match: bool,
+    /// Parsed underlying, strike/range/up-down terms agree (or neither event
+    /// has any). A pair that fails this scores zero.
+    pub terms_match: bool,
+    /// Resolution dates are both set and within the matcher's tolerance.
+    /// A pair that fails this scores zero.
+    pub resolution_aligned: bool,
+    /// Pinned in the mappings file: scores 1.0 when resolution-aligned,
+    /// whatever the titles say.
+    pub pinned: bool,
     pub overall_score: f64,
 }
 
@@ -49,17 +65,60 @@ fn get_number_patterns() -> &'static [Regex] {
     })
 }
 
+/// Matched events must resolve within this of each other: a 15-minute
+/// window matched against the next one is a guaranteed mis-hedge.
+pub const DEFAULT_RESOLUTION_TOLERANCE_SECS: i64 = 120;
+
 pub struct EventMatcher {
     similarity_threshold: f64,
+    resolution_tolerance: Option<Duration>,
+    mappings: Option<MarketMappings>,
+    denied: Option<Arc<MatchStore>>,
+    embeddings: Option<Arc<EmbeddingClient>>,
 }
 
 impl EventMatcher {
     pub fn new(similarity_threshold: f64) -> Self {
         Self {
             similarity_threshold,
+            resolution_tolerance: Some(Duration::seconds(DEFAULT_RESOLUTION_TOLERANCE_SECS)),
+            mappings: None,
+            denied: None,
+            embeddings: None,
         }
     }
 
+    /// Blend embedding similarity into the text score; titles have to be
+    /// embedded with [`EmbeddingClient::prefetch`] before matching.
+    pub fn with_embeddings(mut self, embeddings: Arc<EmbeddingClient>) -> Self {
+        self.embeddings = Some(embeddings);
+        self
+    }
+
+    pub fn embeddings(&self) -> Option<&Arc<EmbeddingClient>> {
+        self.embeddings.as_ref()
+    }
+
+    /// Pinned and denied pairs consulted before fuzzy matching.
+    pub fn with_mappings(mut self, mappings: MarketMappings) -> Self {
+        self.mappings = Some(mappings);
+        self
+    }
+
+    /// Pairs denied in `store` after settling inconsistently are never
+    /// matched.
+    pub fn with_denied_pairs(mut self, store: Arc<MatchStore>) -> Self {
+        self.denied = Some(store);
+        self
+    }
+
+    /// How far apart matched events' resolution dates may be; `None` turns
+    /// the check off.
+    pub fn with_resolution_tolerance(mut self, tolerance: Option<Duration>) -> Self {
+        self.resolution_tolerance = tolerance;
+        self
+    }
+
     pub fn normalize_text(&self, text: &str) -> String {
         text.to_lowercase()
             .chars()
@@ -133,6 +192,18 @@ impl EventMatcher {
         }
     }
 
+    /// Whether both events resolve within the tolerance of each other. An
+    /// event without a resolution date can't be checked and fails.
+    pub fn resolution_aligned(&self, event1: &Event, event2: &Event) -> bool {
+        let Some(tolerance) = self.resolution_tolerance else {
+            return true;
+        };
+        match (event1.resolution_date, event2.resolution_date) {
+            (Some(d1), Some(d2)) => (d1 - d2).abs() <= tolerance,
+            _ => false,
+        }
+    }
+
     pub fn extract_numbers(&self, text: &str) -> Vec<String> {
         let patterns = get_number_patterns();
         let mut numbers = Vec::new();
@@ -144,6 +215,30 @@ impl EventMatcher {
         numbers
     }
 
+    /// Terms from the title, falling back to the slug, then the title with
+    /// the description (Kalshi puts the strike in the subtitle).
+    fn market_terms(event: &Event) -> Option<MarketTerms> {
+        strike::parse_terms(&event.title)
+            .or_else(|| event.slug.as_deref().and_then(strike::parse_terms))
+            .or_else(|| strike::parse_terms(&format!("{} {}", event.title, event.description)))
+    }
+
+    /// Whether two events can resolve on the same thing: both parse to
+    /// agreeing terms, or neither parses and they don't name different assets.
+    pub fn terms_agree(&self, event1: &Event, event2: &Event) -> bool {
+        match (Self::market_terms(event1), Self::market_terms(event2)) {
+            (Some(a), Some(b)) => a.agrees_with(&b),
+            (None, None) => match (
+                strike::parse_underlying(&event1.title),
+                strike::parse_underlying(&event2.title),
+            ) {
+                (Some(a), Some(b)) => a == b,
+                _ => true,
+            },
+            _ => false,
+        }
+    }
+
     pub fn calculate_similarity(&self, event1: &Event, event2: &Event) -> f64 {
         self.calculate_similarity_with_confidence(event1, event2).overall_score
     }
@@ -152,7 +247,18 @@ impl EventMatcher {
 
         let title1 = self.normalize_text(&event1.title);
         let title2 = self.normalize_text(&event2.title);
-        let text_similarity = strsim::jaro_winkler(&title1, &title2);
+        let string_similarity = strsim::jaro_winkler(&title1, &title2);
+        let embedding_similarity = self
+            .embeddings
+            .as_ref()
+            .and_then(|e| e.similarity(&event1.title, &event2.title));
+        let text_similarity = match (&self.embeddings, embedding_similarity) {
+            (Some(embeddings), Some(similarity)) => {
+                let weight = embeddings.weight();
+                (1.0 - weight) * string_similarity + weight * similarity.max(0.0)
+            }
+            _ => string_similarity,
+        };
 
         let keywords1 = self.extract_keywords(&event1.title);
         let keywords2 = self.extract_keywords(&event2.title);
@@ -194,22 +300,61 @@ impl EventMatcher {
             false
         };
 
-        let overall_score = text_similarity * 0.4
-            + keyword_overlap * 0.25
-            + if date_match_final { 0.15 } else { 0.0 }
-            + if category_match { 0.1 } else { 0.0 }
-            + if number_match { 0.1 } else { 0.0 };
+        let terms_match = self.terms_agree(event1, event2);
+        let resolution_aligned = self.resolution_aligned(event1, event2);
+
+        let overall_score = if terms_match && resolution_aligned {
+            text_similarity * 0.4
+                + keyword_overlap * 0.25
+                + if date_match_final { 0.15 } else { 0.0 }
+                + if category_match { 0.1 } else { 0.0 }
+                + if number_match { 0.1 } else { 0.0 }
+        } else {
+            0.0
+        };
 
         MatchConfidence {
             text_similarity,
+            embedding_similarity,
             date_match: date_match_final,
             category_match,
             keyword_overlap,
             number_match,
+            terms_match,
+            resolution_aligned,
+            pinned: false,
             overall_score,
         }
     }
 
+    /// Confidence that a Polymarket and a Kalshi event are the same market,
+    /// or `None` when the mappings rule the pair out (denied, or one side
+    /// pinned to something else) or it was denied after settling.
+    pub fn pair_confidence(&self, pm_event: &Event, kalshi_event: &Event) -> Option<MatchConfidence> {
+        if self
+            .denied
+            .as_ref()
+            .is_some_and(|store| store.is_denied(&pm_event.event_id, &kalshi_event.event_id))
+        {
+            return None;
+        }
+        let mapping = self
+            .mappings
+            .as_ref()
+            .map_or(Mapping::Unmapped, |m| m.lookup(pm_event, kalshi_event));
+        let mut confidence = match mapping {
+            Mapping::Denied | Mapping::PinnedElsewhere => return None,
+            Mapping::Pinned | Mapping::Unmapped => {
+                self.calculate_similarity_with_confidence(pm_event, kalshi_event)
+            }
+        };
+        if mapping == Mapping::Pinned {
+            confidence.pinned = true;
+            confidence.overall_score = if confidence.resolution_aligned { 1.0 } else { 0.0 };
+        }
+        Some(confidence)
+    }
+
     pub fn find_matches(
         &self,
         polymarket_events: &[Event],
@@ -230,7 +375,9 @@ impl EventMatcher {
 
         for pm_event in polymarket_events {
             for kalshi_event in kalshi_events {
-                let confidence = self.calculate_similarity_with_confidence(pm_event, kalshi_event);
+                let Some(confidence) = self.pair_confidence(pm_event, kalshi_event) else {
+                    continue;
+                };
 
                 if confidence.overall_score >= self.similarity_threshold {
                     matches.push((
@@ -259,7 +406,12 @@ impl EventMatcher {
         let mut best_similarity = 0.0;
 
         for candidate in candidate_events {
-            let similarity = self.calculate_similarity(target_event, candidate);
+            let confidence = if target_event.platform == "kalshi" {
+                self.pair_confidence(candidate, target_event)
+            } else {
+                self.pair_confidence(target_event, candidate)
+            };
+            let similarity = confidence.map_or(0.0, |c| c.overall_score);
             if similarity > best_similarity {
                 best_similarity = similarity;
                 best_match = Some((candidate.clone(), similarity));
@@ -274,3 +426,78 @@ impl EventMatcher {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolves() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-01-01T12:15:00Z").unwrap().with_timezone(&Utc)
+    }
+
+    fn event(platform: &str, title: &str) -> Event {
+        Event::new(platform.to_string(), title.to_string(), title.to_string(), String::new())
+            .with_resolution_date(resolves())
+    }
+
+    #[test]
+    fn rejects_pairs_with_different_strikes() {
+        let matcher = EventMatcher::new(0.5);
+        let pm = event("polymarket", "BTC above $100,000 on January 1?");
+
+        let confidence = matcher
+            .calculate_similarity_with_confidence(&pm, &event("kalshi", "BTC above $105,000 on January 1?"));
+        assert!(!confidence.terms_match);
+        assert_eq!(confidence.overall_score, 0.0);
+
+        let mut kalshi = event("kalshi", "Bitcoin price on January 1?");
+        kalshi.description = "$100,000 or above".to_string();
+        assert!(matcher.terms_agree(&pm, &kalshi));
+        assert!(matcher.find_best_match(&pm, &[event("kalshi", "BTC above $100k on January 1?")]).is_some());
+    }
+
+    #[test]
+    fn rejects_pairs_resolving_in_different_windows() {
+        let matcher = EventMatcher::new(0.5);
+        let pm = event("polymarket", "Bitcoin Up or Down 15 minutes");
+        let next_window = event("kalshi", "Bitcoin Up or Down 15 minutes")
+            .with_resolution_date(resolves() + Duration::minutes(15));
+        let confidence = matcher.calculate_similarity_with_confidence(&pm, &next_window);
+        assert!(!confidence.resolution_aligned);
+        assert_eq!(confidence.overall_score, 0.0);
+
+        let close = event("kalshi", "Bitcoin Up or Down 15 minutes")
+            .with_resolution_date(resolves() + Duration::seconds(90));
+        assert!(matcher.resolution_aligned(&pm, &close));
+        let mut undated = close.clone();
+        undated.resolution_date = None;
+        assert!(!matcher.resolution_aligned(&pm, &undated));
+        assert!(matcher.with_resolution_tolerance(None).resolution_aligned(&pm, &undated));
+    }
+
+    #[test]
+    fn pinned_pairs_skip_fuzzy_matching() {
+        let mappings = MarketMappings::parse(
+            "[[pair]]\npolymarket = \"0xpm\"\nkalshi = \"KXBTC15M-*\"\n\n[[deny]]\npolymarket = \"0xother\"\nkalshi = \"KXBTC15M-*\"",
+        )
+        .unwrap();
+        let matcher = EventMatcher::new(0.8).with_mappings(mappings);
+        let pm = Event::new("polymarket".to_string(), "0xpm".to_string(), "BTC 15m".to_string(), String::new())
+            .with_resolution_date(resolves());
+        let kalshi = Event::new(
+            "kalshi".to_string(),
+            "KXBTC15M-26JAN011215".to_string(),
+            "Bitcoin price up in next 15 mins?".to_string(),
+            String::new(),
+        )
+        .with_resolution_date(resolves());
+        let lookalike = event("kalshi", "BTC 15m");
+        let other = Event { event_id: "0xother".to_string(), ..event("polymarket", "Bitcoin price up in next 15 mins?") };
+
+        let matches = matcher.find_matches_with_confidence(&[pm.clone(), other], &[kalshi.clone(), lookalike]);
+        assert_eq!(matches.len(), 1);
+        assert!(matches[0].2.pinned);
+        assert_eq!(matches[0].1.event_id, kalshi.event_id);
+        assert_eq!(matcher.find_best_match(&kalshi, &[pm]).unwrap().1, 1.0);
+    }
+}
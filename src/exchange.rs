@@ -0,0 +1,462 @@
+//! Venue-agnostic client interface used by the executors and settlement
+//! checker, implemented by the real clients and by `mock`.
+//!
+//! The real clients' implementations time every call per method, note the
+//! resolution times of listed markets (which pick their price-cache TTL) and
+//! drop a market's cached prices after our own order in it.
+
+use crate::clients::{KalshiClient, PolymarketClient};
+use crate::event::{Event, MarketPrices};
+use crate::metrics::track_call;
+use crate::money::{whole_contracts, Price, UsdAmount};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+/// Order id the clients hand back for an order they only logged (dry run).
+pub const DRY_RUN_ORDER_ID: &str = "dry-run";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+/// How long an order may rest on the book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeInForce {
+    /// Fills what it can at once; the rest is cancelled.
+    #[default]
+    Ioc,
+    /// Fills in full at once or not at all.
+    Fok,
+    /// Rests until filled or cancelled - or, with `expires_after` (seconds),
+    /// until it expires.
+    Gtc { expires_after: Option<u64> },
+    /// Rests like GTC but only as a maker: rejected instead of crossing the
+    /// spread.
+    PostOnly { expires_after: Option<u64> },
+}
+
+impl TimeInForce {
+    /// `ioc`, `fok`, `gtc`, `post`, or `gtc:<seconds>` / `post:<seconds>`
+    /// for an order that expires.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_ascii_lowercase();
+        match s.split_once(':') {
+            Some((kind @ ("gtc" | "gtd" | "post"), secs)) => {
+                let expires_after = Some(secs.trim().parse::<u64>().ok().filter(|secs| *secs > 0)?);
+                Some(if kind == "post" {
+                    Self::PostOnly { expires_after }
+                } else {
+                    Self::Gtc { expires_after }
+                })
+            }
+            Some(_) => None,
+            None => match s.as_str() {
+                "ioc" => Some(Self::Ioc),
+                "fok" => Some(Self::Fok),
+                "gtc" => Some(Self::Gtc { expires_after: None }),
+                "post" => Some(Self::PostOnly { expires_after: None }),
+                _ => None,
+            },
+        }
+    }
+
+    pub fn is_post_only(&self) -> bool {
+        matches!(self, Self::PostOnly { .. })
+    }
+
+    /// When an order placed at `now` expires, if it does.
+    pub fn expires_at(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Gtc { expires_after: Some(secs) } | Self::PostOnly { expires_after: Some(secs) } => {
+                Some(now + chrono::Duration::seconds(*secs as i64))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for TimeInForce {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ioc => write!(f, "IOC"),
+            Self::Fok => write!(f, "FOK"),
+            Self::Gtc { expires_after: None } => write!(f, "GTC"),
+            Self::Gtc { expires_after: Some(secs) } => write!(f, "GTC {}s", secs),
+            Self::PostOnly { expires_after: None } => write!(f, "post-only"),
+            Self::PostOnly { expires_after: Some(secs) } => write!(f, "post-only {}s", secs),
+        }
+    }
+}
+
+/// How a market resolved.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resolution {
+    Yes,
+    No,
+    /// Resolved to prices other than 1/0 (a Polymarket 50/50): each YES
+    /// share pays `yes`, each NO share `no`.
+    Split { yes: f64, no: f64 },
+    /// Voided or cancelled (Kalshi finalized without a result): stakes are
+    /// refunded at cost.
+    Refunded,
+}
+
+impl Resolution {
+    /// Dollars paid out to `shares` of `outcome` (YES/NO), or `None` for a
+    /// refund, which pays back whatever the position cost.
+    pub fn payout(&self, outcome: &str, shares: f64) -> Option<UsdAmount> {
+        let yes = outcome.eq_ignore_ascii_case("YES");
+        let per_share = match self {
+            Resolution::Yes => if yes { Price::ONE } else { Price::ZERO },
+            Resolution::No => if yes { Price::ZERO } else { Price::ONE },
+            Resolution::Split { yes: y, no: n } => Price::from_f64(if yes { *y } else { *n }),
+            Resolution::Refunded => return None,
+        };
+        Some(UsdAmount::for_shares(shares, per_share))
+    }
+}
+
+/// The venue's own account of a settled market.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettlementRecord {
+    /// Event the market belongs to (what positions are keyed by).
+    pub event_id: String,
+    pub market: String,
+    pub resolution: Resolution,
+    /// Dollars credited to the account on settlement.
+    pub revenue: f64,
+    /// Fees charged on the market, in dollars, when the venue reports them.
+    pub fees: Option<f64>,
+}
+
+/// One of the account's buy fills.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fill {
+    pub order_id: String,
+    pub outcome: String,
+    pub shares: f64,
+    /// Dollars paid, before fees.
+    pub cost: f64,
+    pub fees: f64,
+}
+
+/// How much of one order has filled so far.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct OrderFill {
+    pub shares: f64,
+    /// Average price of the filled shares; 0 when none filled.
+    pub price: f64,
+    /// Nothing more can fill: filled in full, cancelled or expired.
+    pub done: bool,
+}
+
+impl OrderFill {
+    /// Dollars paid (buys) or received (sells) for the filled shares, to
+    /// the micro-dollar USDC settles in.
+    pub fn notional(&self) -> UsdAmount {
+        UsdAmount::from_usdc_units(UsdAmount::for_shares(self.shares, Price::from_f64(self.price)).usdc_units())
+    }
+}
+
+/// How long callers wait on a sell or hedge to stop filling by default.
+pub const FILL_CONFIRM_TIMEOUT: Duration = Duration::from_secs(5);
+const FILL_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Polls `order_id` until nothing more of it can fill or `timeout` passes,
+/// and returns what filled. A dry-run order counts as `shares` filled at
+/// `price`; an accepted order the venue gave no id for is an error, since
+/// its fill can't be known.
+pub async fn confirm_fill<C: ExchangeClient + ?Sized>(
+    client: &C,
+    event_id: &str,
+    order_id: Option<&str>,
+    shares: f64,
+    price: f64,
+    timeout: Duration,
+) -> Result<OrderFill> {
+    let order_id = order_id
+        .ok_or_else(|| anyhow::anyhow!("{} returned no order id to confirm the fill", client.platform()))?;
+    if order_id == DRY_RUN_ORDER_ID {
+        return Ok(OrderFill { shares, price, done: true });
+    }
+    client
+        .poll_order_fill(event_id, order_id, tokio::time::Instant::now() + timeout)
+        .await
+}
+
+/// Runs `lookup` every poll interval until nothing more can fill or
+/// `deadline` passes, and returns the last fill it saw.
+pub async fn poll_fill<F, Fut>(deadline: tokio::time::Instant, mut lookup: F) -> Result<OrderFill>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<OrderFill>>,
+{
+    loop {
+        let fill = lookup().await?;
+        if fill.done || tokio::time::Instant::now() + FILL_POLL_INTERVAL > deadline {
+            return Ok(fill);
+        }
+        tokio::time::sleep(FILL_POLL_INTERVAL).await;
+    }
+}
+
+#[async_trait]
+pub trait ExchangeClient: Send + Sync {
+    fn platform(&self) -> &'static str;
+
+    async fn fetch_events(&self) -> Result<Vec<Event>>;
+
+    /// Individual markets, for venues whose events group several markets
+    /// (e.g. strike ladders). Defaults to `fetch_events`.
+    async fn fetch_markets(&self) -> Result<Vec<Event>> {
+        self.fetch_events().await
+    }
+
+    async fn fetch_prices(&self, event_id: &str) -> Result<MarketPrices>;
+
+    /// Buys `outcome` for `amount` dollars at no more than `price`, resting
+    /// per `tif`.
+    async fn place_order(
+        &self,
+        event_id: String,
+        outcome: String,
+        amount: f64,
+        price: f64,
+        tif: TimeInForce,
+    ) -> Result<Option<String>>;
+
+    /// Sells `shares` of `outcome` at no less than `min_price`; used to
+    /// unwind a leg whose hedge could not be placed.
+    async fn sell_order(
+        &self,
+        event_id: String,
+        outcome: String,
+        shares: f64,
+        min_price: f64,
+    ) -> Result<Option<String>>;
+
+    /// How much of `order_id`, placed in `event_id`, has filled.
+    async fn order_fill(&self, event_id: &str, order_id: &str) -> Result<OrderFill>;
+
+    /// Polls `order_id` until nothing more of it can fill or `deadline`
+    /// passes; see [`confirm_fill`].
+    async fn poll_order_fill(
+        &self,
+        event_id: &str,
+        order_id: &str,
+        deadline: tokio::time::Instant,
+    ) -> Result<OrderFill> {
+        poll_fill(deadline, || self.order_fill(event_id, order_id)).await
+    }
+
+    /// How the market resolved, or `None` while it is open.
+    async fn check_settlement(&self, event_id: &str) -> Result<Option<Resolution>>;
+
+    /// Markets settled for the account since `since`, as the venue credited
+    /// them. Empty for venues that don't report settlements.
+    async fn fetch_settlement_records(&self, _since: DateTime<Utc>) -> Result<Vec<SettlementRecord>> {
+        Ok(Vec::new())
+    }
+
+    /// The account's buy fills in `market`.
+    async fn fetch_fills(&self, _market: &str) -> Result<Vec<Fill>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_balance(&self) -> Result<f64>;
+}
+
+#[async_trait]
+impl ExchangeClient for PolymarketClient {
+    fn platform(&self) -> &'static str {
+        "polymarket"
+    }
+
+    async fn fetch_events(&self) -> Result<Vec<Event>> {
+        track_call("polymarket", "fetch_events", PolymarketClient::fetch_events(self))
+            .await
+            .inspect(|events| self.note_resolutions(events))
+    }
+
+    async fn fetch_markets(&self) -> Result<Vec<Event>> {
+        let tag_slug = std::env::var("POLYMARKET_TAG_SLUG").ok();
+        let tag_slug = tag_slug.as_deref().filter(|s| !s.is_empty());
+        track_call(
+            "polymarket",
+            "fetch_markets",
+            self.fetch_markets_from_gamma(tag_slug, 200),
+        )
+        .await
+        .inspect(|markets| self.note_resolutions(markets))
+    }
+
+    async fn fetch_prices(&self, event_id: &str) -> Result<MarketPrices> {
+        track_call(
+            "polymarket",
+            "fetch_prices",
+            PolymarketClient::fetch_prices(self, event_id),
+        )
+        .await
+    }
+
+    async fn place_order(
+        &self,
+        event_id: String,
+        outcome: String,
+        amount: f64,
+        price: f64,
+        tif: TimeInForce,
+    ) -> Result<Option<String>> {
+        let market = event_id.clone();
+        let result = track_call(
+            "polymarket",
+            "place_order",
+            PolymarketClient::place_order(self, event_id, outcome, amount, price, tif),
+        )
+        .await;
+        if result.is_ok() {
+            self.invalidate_prices(&market).await;
+        }
+        result
+    }
+
+    async fn sell_order(
+        &self,
+        event_id: String,
+        outcome: String,
+        shares: f64,
+        min_price: f64,
+    ) -> Result<Option<String>> {
+        let result = track_call(
+            "polymarket",
+            "sell_order",
+            PolymarketClient::sell_order(self, &event_id, &outcome, shares, min_price),
+        )
+        .await;
+        if result.is_ok() {
+            self.invalidate_prices(&event_id).await;
+        }
+        result
+    }
+
+    async fn order_fill(&self, _event_id: &str, order_id: &str) -> Result<OrderFill> {
+        track_call("polymarket", "order_fill", PolymarketClient::order_fill(self, order_id)).await
+    }
+
+    async fn poll_order_fill(
+        &self,
+        _event_id: &str,
+        order_id: &str,
+        deadline: tokio::time::Instant,
+    ) -> Result<OrderFill> {
+        track_call(
+            "polymarket",
+            "poll_order_fill",
+            PolymarketClient::poll_order_fill(self, order_id, deadline),
+        )
+        .await
+    }
+
+    async fn check_settlement(&self, event_id: &str) -> Result<Option<Resolution>> {
+        track_call(
+            "polymarket",
+            "check_settlement",
+            PolymarketClient::check_settlement(self, event_id),
+        )
+        .await
+    }
+
+    async fn get_balance(&self) -> Result<f64> {
+        track_call("polymarket", "get_balance", PolymarketClient::get_balance(self)).await
+    }
+}
+
+#[async_trait]
+impl ExchangeClient for KalshiClient {
+    fn platform(&self) -> &'static str {
+        "kalshi"
+    }
+
+    async fn fetch_events(&self) -> Result<Vec<Event>> {
+        track_call("kalshi", "fetch_events", KalshiClient::fetch_events(self))
+            .await
+            .inspect(|events| self.note_resolutions(events))
+    }
+
+    async fn fetch_prices(&self, event_id: &str) -> Result<MarketPrices> {
+        track_call("kalshi", "fetch_prices", KalshiClient::fetch_prices(self, event_id)).await
+    }
+
+    async fn place_order(
+        &self,
+        event_id: String,
+        outcome: String,
+        amount: f64,
+        price: f64,
+        tif: TimeInForce,
+    ) -> Result<Option<String>> {
+        let market = event_id.clone();
+        let result = track_call(
+            "kalshi",
+            "place_order",
+            KalshiClient::place_order(self, event_id, outcome, amount, price, tif),
+        )
+        .await;
+        if result.is_ok() {
+            self.invalidate_prices(&market).await;
+        }
+        result
+    }
+
+    async fn sell_order(
+        &self,
+        event_id: String,
+        outcome: String,
+        shares: f64,
+        min_price: f64,
+    ) -> Result<Option<String>> {
+        let result = track_call("kalshi", "sell_order", async {
+            let ticker = self.market_ticker(&event_id).await?;
+            self.sell_position(&ticker, &outcome, whole_contracts(shares), min_price).await
+        })
+        .await;
+        if result.is_ok() {
+            self.invalidate_prices(&event_id).await;
+        }
+        result
+    }
+
+    async fn order_fill(&self, event_id: &str, order_id: &str) -> Result<OrderFill> {
+        track_call("kalshi", "order_fill", KalshiClient::order_fill(self, event_id, order_id)).await
+    }
+
+    async fn check_settlement(&self, event_id: &str) -> Result<Option<Resolution>> {
+        track_call(
+            "kalshi",
+            "check_settlement",
+            KalshiClient::check_settlement(self, event_id),
+        )
+        .await
+    }
+
+    async fn fetch_settlement_records(&self, since: DateTime<Utc>) -> Result<Vec<SettlementRecord>> {
+        track_call(
+            "kalshi",
+            "fetch_settlement_records",
+            KalshiClient::fetch_settlements(self, since),
+        )
+        .await
+    }
+
+    async fn fetch_fills(&self, market: &str) -> Result<Vec<Fill>> {
+        track_call("kalshi", "fetch_fills", KalshiClient::fetch_fills(self, market)).await
+    }
+
+    async fn get_balance(&self) -> Result<f64> {
+        track_call("kalshi", "get_balance", KalshiClient::get_balance(self)).await
+    }
+}
@@ -0,0 +1,413 @@
+//! Fair value of 15-minute crypto up/down markets.
+//!
+//! A 15m market resolves "up" when the coin closes the window above its price
+//! at the window open. Treating the log price as driftless Brownian motion
+//! with the realized 1-minute volatility, P(up) = N(d), where
+//! d = (ln(S/K) - σ²τ/2) / (σ√τ) for spot S, window open K and τ minutes left.
+//! Spot comes from the Binance feed when it is running and fresh, otherwise
+//! from Coinbase, which also supplies the candles; Polymarket resolves on
+//! Chainlink, so this is an approximation good enough to flag markets trading
+//! far from fair.
+//!
+//! Enabled with `FAIR_VALUE_THRESHOLD` (minimum |fair − ask| to flag).
+
+use crate::event::{Event, MarketPrices};
+use crate::exchange::ExchangeClient;
+use crate::monitor_logger::append_monitor_log;
+use crate::spot_feed::SpotFeed;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use reqwest::Client;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tracing::{info, warn};
+
+const COINBASE_API_BASE: &str = "https://api.exchange.coinbase.com";
+const WINDOW_MINUTES: i64 = 15;
+const VOL_LOOKBACK_MINUTES: i64 = 60;
+
+/// Standard normal CDF (Abramowitz & Stegun 7.1.26, |error| < 1.5e-7).
+pub fn normal_cdf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs() / std::f64::consts::SQRT_2);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-(x * x) / 2.0).exp();
+    if x >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+/// Probability the price finishes above `strike` after `minutes_left`, with
+/// `sigma` the standard deviation of 1-minute log returns.
+pub fn prob_up(spot: f64, strike: f64, sigma: f64, minutes_left: f64) -> f64 {
+    if spot <= 0.0 || strike <= 0.0 {
+        return 0.5;
+    }
+    let spread = sigma * minutes_left.max(0.0).sqrt();
+    if spread <= 0.0 {
+        return match spot.partial_cmp(&strike) {
+            Some(std::cmp::Ordering::Greater) => 1.0,
+            Some(std::cmp::Ordering::Less) => 0.0,
+            _ => 0.5,
+        };
+    }
+    normal_cdf(((spot / strike).ln() - spread * spread / 2.0) / spread)
+}
+
+/// Standard deviation of log returns between consecutive closes.
+pub fn realized_volatility(closes: &[f64]) -> Option<f64> {
+    let returns: Vec<f64> = closes
+        .windows(2)
+        .filter(|w| w[0] > 0.0 && w[1] > 0.0)
+        .map(|w| (w[1] / w[0]).ln())
+        .collect();
+    if returns.len() < 2 {
+        return None;
+    }
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let variance =
+        returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+    Some(variance.sqrt())
+}
+
+#[derive(Debug, Clone)]
+pub struct Candle {
+    pub start: DateTime<Utc>,
+    pub open: f64,
+    pub close: f64,
+}
+
+/// Spot price plus recent 1-minute candles (oldest first) for one coin.
+#[derive(Debug, Clone)]
+pub struct SpotSnapshot {
+    pub spot: f64,
+    pub candles: Vec<Candle>,
+}
+
+impl SpotSnapshot {
+    /// Open of the 1-minute candle starting at `at`.
+    pub fn open_at(&self, at: DateTime<Utc>) -> Option<f64> {
+        self.candles.iter().find(|c| c.start == at).map(|c| c.open)
+    }
+
+    pub fn sigma(&self) -> Option<f64> {
+        let closes: Vec<f64> = self.candles.iter().map(|c| c.close).collect();
+        realized_volatility(&closes)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FairValue {
+    pub event_id: String,
+    pub title: String,
+    pub coin: String,
+    pub spot: f64,
+    pub strike: f64,
+    pub minutes_left: f64,
+    pub sigma: f64,
+    pub fair_up: f64,
+    pub market_up: f64,
+    pub market_down: f64,
+    pub at: DateTime<Utc>,
+}
+
+impl FairValue {
+    /// Fair minus ask for "up" (positive = up is cheap).
+    pub fn up_edge(&self) -> f64 {
+        self.fair_up - self.market_up
+    }
+
+    /// Fair minus ask for "down" (positive = down is cheap).
+    pub fn down_edge(&self) -> f64 {
+        (1.0 - self.fair_up) - self.market_down
+    }
+
+    pub fn is_flagged(&self, threshold: f64) -> bool {
+        self.up_edge().max(self.down_edge()) >= threshold
+    }
+}
+
+/// Prices an up/down market from a spot snapshot. `None` when the event is
+/// not a running 15m window or the snapshot lacks its opening candle.
+pub fn evaluate(
+    event: &Event,
+    prices: &MarketPrices,
+    snapshot: &SpotSnapshot,
+    now: DateTime<Utc>,
+) -> Option<FairValue> {
+    let end = event.resolution_date?;
+    let start = end - Duration::minutes(WINDOW_MINUTES);
+    if now < start || now >= end {
+        return None;
+    }
+    let strike = snapshot.open_at(start)?;
+    let sigma = snapshot.sigma()?;
+    let minutes_left = (end - now).num_milliseconds() as f64 / 60_000.0;
+
+    Some(FairValue {
+        event_id: event.event_id.clone(),
+        title: event.title.clone(),
+        coin: event.coin_from_slug().unwrap_or_default(),
+        spot: snapshot.spot,
+        strike,
+        minutes_left,
+        sigma,
+        fair_up: prob_up(snapshot.spot, strike, sigma, minutes_left),
+        market_up: prices.yes_ask.to_f64(),
+        market_down: prices.no_ask.to_f64(),
+        at: now,
+    })
+}
+
+pub struct FairValueEngine {
+    http: Client,
+    base_url: String,
+    threshold: f64,
+    spot_feed: Option<Arc<SpotFeed>>,
+    latest: RwLock<Vec<FairValue>>,
+}
+
+impl FairValueEngine {
+    pub fn new(threshold: f64) -> Self {
+        Self {
+            http: Client::builder()
+                .timeout(std::time::Duration::from_secs(10))
+                .build()
+                .unwrap_or_else(|_| Client::new()),
+            base_url: COINBASE_API_BASE.to_string(),
+            threshold,
+            spot_feed: None,
+            latest: RwLock::new(Vec::new()),
+        }
+    }
+
+    pub fn from_env() -> Option<Self> {
+        let threshold = std::env::var("FAIR_VALUE_THRESHOLD")
+            .ok()
+            .and_then(|s| s.trim().parse::<f64>().ok())?;
+        Some(Self::new(threshold))
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_spot_feed(mut self, feed: Arc<SpotFeed>) -> Self {
+        self.spot_feed = Some(feed);
+        self
+    }
+
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Readings from the last tick, flagged or not.
+    pub fn latest(&self) -> Vec<FairValue> {
+        self.latest.read().map(|l| l.clone()).unwrap_or_default()
+    }
+
+    async fn get_json(&self, path: &str, query: &[(&str, String)]) -> Result<serde_json::Value> {
+        let response = self
+            .http
+            .get(format!("{}{}", self.base_url, path))
+            .header("User-Agent", "polymarket-kalshi-arbitrage-bot")
+            .query(query)
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch {}", path))?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Coinbase {} error: {}",
+                path,
+                response.status()
+            ));
+        }
+        response
+            .json()
+            .await
+            .with_context(|| format!("Failed to parse {}", path))
+    }
+
+    pub async fn spot_snapshot(&self, coin: &str) -> Result<SpotSnapshot> {
+        let product = format!("{}-USD", coin.to_uppercase());
+        let now = Utc::now();
+        let ticker_path = format!("/products/{}/ticker", product);
+        let candles_path = format!("/products/{}/candles", product);
+        let candles_query = [
+            ("granularity", "60".to_string()),
+            ("start", (now - Duration::minutes(VOL_LOOKBACK_MINUTES)).to_rfc3339()),
+            ("end", now.to_rfc3339()),
+        ];
+        let feed_spot = self
+            .spot_feed
+            .as_ref()
+            .and_then(|feed| feed.fresh_price(coin, now));
+        let (ticker, candles) = tokio::join!(
+            async {
+                match feed_spot {
+                    Some(spot) => Ok(spot),
+                    None => self.get_json(&ticker_path, &[]).await?["price"]
+                        .as_str()
+                        .and_then(|s| s.parse::<f64>().ok())
+                        .context("Coinbase ticker has no price"),
+                }
+            },
+            self.get_json(&candles_path, &candles_query)
+        );
+        let spot = ticker?;
+
+        // Rows are [time, low, high, open, close, volume], newest first.
+        let mut candles: Vec<Candle> = candles?
+            .as_array()
+            .map(|rows| {
+                rows.iter()
+                    .filter_map(|row| {
+                        let row = row.as_array()?;
+                        Some(Candle {
+                            start: Utc.timestamp_opt(row.first()?.as_i64()?, 0).single()?,
+                            open: row.get(3)?.as_f64()?,
+                            close: row.get(4)?.as_f64()?,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        candles.sort_by_key(|c| c.start);
+
+        Ok(SpotSnapshot { spot, candles })
+    }
+
+    /// Prices every running 15m up/down market and logs the ones whose ask
+    /// is at least `threshold` away from fair value. Never trades.
+    pub async fn run_tick(
+        &self,
+        pm_events: &[Event],
+        polymarket_client: &dyn ExchangeClient,
+    ) -> Vec<FairValue> {
+        let now = Utc::now();
+        let window = Duration::minutes(WINDOW_MINUTES);
+        let running: Vec<&Event> = pm_events
+            .iter()
+            .filter(|e| e.slug_is_15m_crypto())
+            .filter(|e| {
+                e.resolution_date
+                    .is_some_and(|end| now >= end - window && now < end)
+            })
+            .collect();
+        if running.is_empty() {
+            return Vec::new();
+        }
+
+        let mut coins: Vec<String> = running.iter().filter_map(|e| e.coin_from_slug()).collect();
+        coins.sort();
+        coins.dedup();
+        let snapshots: HashMap<String, SpotSnapshot> =
+            futures::future::join_all(coins.iter().map(|coin| async move {
+                (coin.clone(), self.spot_snapshot(coin).await)
+            }))
+            .await
+            .into_iter()
+            .filter_map(|(coin, snapshot)| match snapshot {
+                Ok(snapshot) => Some((coin, snapshot)),
+                Err(e) => {
+                    warn!("📐 Spot fetch for {} failed: {}", coin, e);
+                    None
+                }
+            })
+            .collect();
+
+        let prices = futures::future::join_all(
+            running
+                .iter()
+                .map(|e| polymarket_client.fetch_prices(&e.event_id)),
+        )
+        .await;
+
+        let readings: Vec<FairValue> = running
+            .iter()
+            .zip(prices)
+            .filter_map(|(event, prices)| {
+                let snapshot = snapshots.get(&event.coin_from_slug()?)?;
+                evaluate(event, &prices.ok()?, snapshot, now)
+            })
+            .collect();
+
+        for reading in readings.iter().filter(|r| r.is_flagged(self.threshold)) {
+            let line = format!(
+                "FAIR {}  |  spot={:.2} open={:.2} left={:.1}m σ={:.5}  |  fair up={:.3}  ask up={:.3} down={:.3}  |  edge up={:+.3} down={:+.3}",
+                reading.title,
+                reading.spot,
+                reading.strike,
+                reading.minutes_left,
+                reading.sigma,
+                reading.fair_up,
+                reading.market_up,
+                reading.market_down,
+                reading.up_edge(),
+                reading.down_edge()
+            );
+            info!("📐 {}", line);
+            append_monitor_log(&format!("[{}] {}", reading.at.to_rfc3339(), line), &reading.at);
+        }
+
+        if let Ok(mut latest) = self.latest.write() {
+            *latest = readings.clone();
+        }
+        readings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prob_up_behaves_at_the_edges() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-3);
+
+        // At the money with time left: a hair under 0.5 (σ²τ/2 drag).
+        let atm = prob_up(100.0, 100.0, 0.001, 10.0);
+        assert!(atm < 0.5 && atm > 0.49);
+        // Far above the open with a minute left: almost certainly up.
+        assert!(prob_up(101.0, 100.0, 0.001, 1.0) > 0.99);
+        // Window over: decided by the close.
+        assert_eq!(prob_up(99.0, 100.0, 0.001, 0.0), 0.0);
+    }
+
+    #[test]
+    fn flags_market_far_from_fair() {
+        let open = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let candles: Vec<Candle> = (0..20)
+            .map(|i| Candle {
+                start: open - Duration::minutes(10) + Duration::minutes(i),
+                open: 100_000.0,
+                close: if i % 2 == 0 { 100_050.0 } else { 99_950.0 },
+            })
+            .collect();
+        let snapshot = SpotSnapshot {
+            spot: 100_400.0,
+            candles,
+        };
+        let event = Event::new(
+            "polymarket".to_string(),
+            "0xbtc".to_string(),
+            "Bitcoin Up or Down".to_string(),
+            String::new(),
+        )
+        .with_slug("btc-updown-15m-1735732800".to_string())
+        .with_resolution_date(open + Duration::minutes(15));
+
+        // Spot 0.4% above the open with 5 minutes left, but "up" asks 0.55.
+        let prices = MarketPrices::new(0.55, 0.47, 1000.0);
+        let fair = evaluate(&event, &prices, &snapshot, open + Duration::minutes(10)).unwrap();
+        assert_eq!(fair.strike, 100_000.0);
+        assert!(fair.fair_up > 0.9);
+        assert!(fair.is_flagged(0.10));
+        assert!(!fair.is_flagged(0.50));
+    }
+}
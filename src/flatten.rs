@@ -0,0 +1,267 @@
+//! `flatten-all` command: the emergency stop.
+//!
+//! Cancels every resting order on both venues, then sells each open venue
+//! position into the current bid, accepting at most `tolerance` below it.
+//! Only confirmed fills count as closed. Whatever could not be cancelled or
+//! sold — including a partial fill's remainder and the fraction under one
+//! whole Kalshi contract — is listed in the report so the operator knows
+//! exactly what is still at risk.
+
+use crate::adopt::{self, VenuePosition};
+use crate::clients::{KalshiClient, PolymarketClient};
+use crate::exchange::{confirm_fill, FILL_CONFIRM_TIMEOUT};
+use crate::money::{whole_contracts, Price, UsdAmount};
+use crate::position_tracker::PositionTracker;
+use anyhow::Result;
+use futures::future::join_all;
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+
+pub const DEFAULT_FLATTEN_TOLERANCE: f64 = 0.05;
+
+#[derive(Debug, Clone)]
+pub struct ClosedPosition {
+    pub position: VenuePosition,
+    /// Shares the sell filled.
+    pub shares: f64,
+    /// Average fill price.
+    pub price: Price,
+    pub order_id: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnclosedPosition {
+    pub position: VenuePosition,
+    pub reason: String,
+}
+
+#[derive(Debug, Default)]
+pub struct FlattenReport {
+    pub cancelled_orders: usize,
+    pub closed: Vec<ClosedPosition>,
+    pub unclosed: Vec<UnclosedPosition>,
+    /// Venue-level failures (order cancels, position fetches).
+    pub errors: Vec<String>,
+}
+
+impl FlattenReport {
+    pub fn is_flat(&self) -> bool {
+        self.unclosed.is_empty() && self.errors.is_empty()
+    }
+
+    pub fn log(&self) {
+        info!(
+            "🧯 Flatten: cancelled {} orders, closed {} positions, {} left open",
+            self.cancelled_orders,
+            self.closed.len(),
+            self.unclosed.len()
+        );
+        for closed in &self.closed {
+            info!(
+                "   ✅ {} {} {:.2} @ {} — {}",
+                closed.position.platform,
+                closed.position.outcome,
+                closed.shares,
+                closed.price,
+                closed.position.title
+            );
+        }
+        for unclosed in &self.unclosed {
+            error!(
+                "   ❌ {} {} {:.2} — {} [{}]: {}",
+                unclosed.position.platform,
+                unclosed.position.outcome,
+                unclosed.position.amount,
+                unclosed.position.title,
+                unclosed.position.market,
+                unclosed.reason
+            );
+        }
+        for e in &self.errors {
+            error!("   ❌ {}", e);
+        }
+    }
+}
+
+/// Lowest acceptable sell price: `tolerance` under the bid, rounded down to
+/// the cent and never below one cent.
+pub fn limit_price(bid: Price, tolerance: f64) -> Price {
+    (bid - Price::from_f64(tolerance.max(0.0)))
+        .floor_cents()
+        .max(Price::from_cents(1))
+}
+
+/// Sells `position` and confirms the fill. Returns what sold, and what is
+/// left of the position when the fill came up short.
+async fn close_position(
+    polymarket_client: &PolymarketClient,
+    kalshi_client: &KalshiClient,
+    position: &VenuePosition,
+    tolerance: f64,
+) -> Result<(ClosedPosition, Option<UnclosedPosition>)> {
+    if position.market.is_empty() {
+        return Err(anyhow::anyhow!("venue did not report a tradable market"));
+    }
+    let bid = match position.platform.as_str() {
+        "polymarket" => polymarket_client.best_bid(&position.market).await?,
+        "kalshi" => kalshi_client.best_bid(&position.market, &position.outcome).await?,
+        other => return Err(anyhow::anyhow!("unknown platform {}", other)),
+    };
+    let bid = bid.ok_or_else(|| anyhow::anyhow!("no bid"))?;
+    let price = limit_price(Price::from_f64(bid), tolerance);
+
+    let (order_id, fill) = match position.platform.as_str() {
+        "polymarket" => {
+            let order_id = polymarket_client
+                .sell_position(&position.market, position.amount, price.to_f64())
+                .await?;
+            let fill = confirm_fill(
+                polymarket_client,
+                &position.event_id,
+                order_id.as_deref(),
+                position.amount,
+                price.to_f64(),
+                FILL_CONFIRM_TIMEOUT,
+            )
+            .await?;
+            (order_id, fill)
+        }
+        _ => {
+            let count = whole_contracts(position.amount);
+            if count < 1 {
+                return Err(anyhow::anyhow!(
+                    "{:.2} shares is under one whole Kalshi contract",
+                    position.amount
+                ));
+            }
+            let order_id = kalshi_client
+                .sell_position(&position.market, &position.outcome, count, price.to_f64())
+                .await?;
+            let fill = confirm_fill(
+                kalshi_client,
+                &position.market,
+                order_id.as_deref(),
+                count as f64,
+                price.to_f64(),
+                FILL_CONFIRM_TIMEOUT,
+            )
+            .await?;
+            (order_id, fill)
+        }
+    };
+    if fill.shares <= 0.0 {
+        return Err(anyhow::anyhow!("sell at ≥{} did not fill", price));
+    }
+
+    let left = position.amount - fill.shares;
+    let unclosed = (left > 1e-6).then(|| UnclosedPosition {
+        position: VenuePosition {
+            amount: left,
+            cost: position.cost.pro_rata(left, position.amount),
+            ..position.clone()
+        },
+        reason: if !fill.done {
+            format!("sell at ≥{} still resting unfilled", price)
+        } else if position.platform == "kalshi" && left < 1.0 {
+            "fraction under one whole Kalshi contract".to_string()
+        } else {
+            format!("sell at ≥{} filled only in part", price)
+        },
+    });
+    let closed = ClosedPosition {
+        position: position.clone(),
+        shares: fill.shares,
+        price: Price::from_f64(fill.price),
+        order_id,
+    };
+    Ok((closed, unclosed))
+}
+
+/// Cancels all orders and sells every open position on both venues. Tracked
+/// positions on markets that were sold are closed in the store, up to the
+/// shares that filled, at the fill price.
+pub async fn run_flatten_all(
+    polymarket_client: &PolymarketClient,
+    kalshi_client: &KalshiClient,
+    position_tracker: &Mutex<PositionTracker>,
+    tolerance: f64,
+) -> Result<FlattenReport> {
+    let mut report = FlattenReport::default();
+
+    let (pm_cancel, kalshi_cancel) = tokio::join!(
+        polymarket_client.cancel_all_orders(),
+        kalshi_client.cancel_all_orders()
+    );
+    for (venue, result) in [("Polymarket", pm_cancel), ("Kalshi", kalshi_cancel)] {
+        match result {
+            Ok(n) => report.cancelled_orders += n,
+            Err(e) => report.errors.push(format!("{} order cancel: {}", venue, e)),
+        }
+    }
+
+    let (positions, fetch_errors) =
+        adopt::fetch_venue_positions_checked(polymarket_client, kalshi_client).await;
+    report.errors.extend(fetch_errors);
+    info!("Flattening {} open venue positions (tolerance ${:.2})", positions.len(), tolerance);
+
+    let results = join_all(
+        positions
+            .iter()
+            .map(|p| close_position(polymarket_client, kalshi_client, p, tolerance)),
+    )
+    .await;
+
+    for (position, result) in positions.into_iter().zip(results) {
+        match result {
+            Ok((closed, unclosed)) => {
+                report.closed.push(closed);
+                report.unclosed.extend(unclosed);
+            }
+            Err(e) => report.unclosed.push(UnclosedPosition {
+                position,
+                reason: e.to_string(),
+            }),
+        }
+    }
+
+    let mut tracker = position_tracker.lock().await;
+    for closed in &report.closed {
+        let ids: Vec<(String, f64)> = tracker
+            .get_open_positions()
+            .into_iter()
+            .filter(|p| {
+                p.platform == closed.position.platform
+                    && p.event_id == closed.position.event_id
+                    && p.outcome.eq_ignore_ascii_case(&closed.position.outcome)
+            })
+            .map(|p| (p.id.clone(), p.amount))
+            .collect();
+        let mut sold = closed.shares;
+        for (id, amount) in ids {
+            if sold <= 1e-9 {
+                break;
+            }
+            let shares = amount.min(sold);
+            sold -= shares;
+            if tracker.close_shares(&id, shares, UsdAmount::for_shares(shares, closed.price)).is_none() {
+                warn!("Could not close tracked position {}", id);
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_price_stays_within_tolerance() {
+        let limit = |bid, tolerance| limit_price(Price::from_f64(bid), tolerance);
+        assert_eq!(limit(0.57, 0.05), Price::from_cents(52));
+        assert_eq!(limit(0.55, 0.0), Price::from_cents(55));
+        assert_eq!(limit(0.03, 0.05), Price::from_cents(1));
+        assert_eq!(limit(0.574, 0.02), Price::from_cents(55));
+    }
+}
@@ -1,4 +1,6 @@
+use crate::arbitrage_detector::capital_efficiency;
 use crate::event::{Event, MarketPrices};
+use crate::money::{Price, UsdAmount};
 
 #[derive(Debug, Clone)]
 pub struct GabagoolOpportunity {
@@ -10,6 +12,12 @@ pub struct GabagoolOpportunity {
     pub pair_cost_after: f64,
     pub total_cost: f64,
     pub profit_locked: bool,
+    /// Hours until the market resolves.
+    pub hours_to_resolution: Option<f64>,
+    /// ROI (percent) per hour of capital lockup.
+    pub roi_per_hour: Option<f64>,
+    /// 0 to 1, see [`crate::confidence`]; `None` until scored.
+    pub confidence: Option<f64>,
 }
 
 pub struct GabagoolDetector {
@@ -29,24 +37,20 @@ impl GabagoolDetector {
         prices: &MarketPrices,
         yes_qty: f64,
         no_qty: f64,
-        yes_cost: f64,
-        no_cost: f64,
+        yes_cost: UsdAmount,
+        no_cost: UsdAmount,
     ) -> Option<GabagoolOpportunity> {
-        let yes_ask = prices.yes_ask_or_fallback();
-        let no_ask = prices.no_ask_or_fallback();
+        let yes_ask = prices.yes_ask;
+        let no_ask = prices.no_ask;
 
-        if yes_ask <= 0.0 || no_ask <= 0.0 {
+        if yes_ask <= Price::ZERO || no_ask <= Price::ZERO {
             return None;
         }
 
         let min_pairs = yes_qty.min(no_qty);
-        let profit_locked = min_pairs > 0.0 && (yes_cost + no_cost) / min_pairs < 1.0;
+        let profit_locked = min_pairs > 0.0 && (yes_cost + no_cost).per_share(min_pairs) < Price::ONE;
 
-        let (cheap_side, cheap_price) = if yes_ask <= no_ask {
-            ("YES".to_string(), yes_ask)
-        } else {
-            ("NO".to_string(), no_ask)
-        };
+        let cheap_side = if yes_ask <= no_ask { "YES" } else { "NO" };
 
         let target_side = if (yes_qty - no_qty).abs() > 0.01 {
             if yes_qty < no_qty {
@@ -55,11 +59,11 @@ impl GabagoolDetector {
                 "NO".to_string()
             }
         } else {
-            cheap_side.clone()
+            cheap_side.to_string()
         };
 
-        let buy_price = if target_side == "YES" { yes_ask } else { no_ask };
-        let unit_cost = buy_price;
+        let buy_price = prices.ask_for(&target_side);
+        let unit_cost = UsdAmount::for_shares(1.0, buy_price);
 
         let (new_yes_qty, new_no_qty, new_yes_cost, new_no_cost) = if target_side == "YES" {
             (yes_qty + 1.0, no_qty, yes_cost + unit_cost, no_cost)
@@ -72,28 +76,33 @@ impl GabagoolDetector {
             return None;
         }
 
-        let pair_cost_after = (new_yes_cost + new_no_cost) / new_min_pairs;
-        if pair_cost_after >= 1.0 {
+        let pair_cost_after = (new_yes_cost + new_no_cost).per_share(new_min_pairs);
+        if pair_cost_after >= Price::ONE {
             return None;
         }
 
-        let net_profit = 1.0 - pair_cost_after;
-        if net_profit <= self.min_profit_threshold && !profit_locked {
+        let net_profit = pair_cost_after.complement();
+        if net_profit <= Price::from_f64(self.min_profit_threshold) && !profit_locked {
             return None;
         }
 
         let total_cost = pair_cost_after;
-        let roi_percent = (net_profit / total_cost) * 100.0;
+        let roi_percent = net_profit.ratio(total_cost) * 100.0;
+        let (hours_to_resolution, roi_per_hour) =
+            capital_efficiency(roi_percent, event.resolution_date, chrono::Utc::now());
 
         Some(GabagoolOpportunity {
             event: event.clone(),
             cheap_side: target_side,
-            cheap_price: buy_price,
-            net_profit,
+            cheap_price: buy_price.to_f64(),
+            net_profit: net_profit.to_f64(),
             roi_percent,
-            pair_cost_after,
-            total_cost,
+            pair_cost_after: pair_cost_after.to_f64(),
+            total_cost: total_cost.to_f64(),
             profit_locked,
+            hours_to_resolution,
+            roi_per_hour,
+            confidence: None,
         })
     }
 }
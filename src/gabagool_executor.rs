@@ -1,35 +1,243 @@
-use crate::clients::PolymarketClient;
+use crate::exchange::{ExchangeClient, TimeInForce};
 use crate::event::Event;
 use crate::gabagool_detector::GabagoolOpportunity;
+use crate::money::{Price, UsdAmount};
+use crate::polymarket_blockchain::PolymarketBlockchain;
 use crate::position_tracker::{Position, PositionTracker};
-use anyhow::Result;
-use std::collections::HashMap;
+use crate::quote_detector::TwoSidedQuote;
+use crate::strategy::{GABAGOOL, QUOTING};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 use tracing::{error, info, warn};
 
-#[derive(Debug, Clone)]
+pub const DEFAULT_GABAGOOL_STORE_PATH: &str = "state/gabagool.json";
+
+/// Share difference below which tracked and on-chain holdings agree.
+const RECONCILE_TOLERANCE: f64 = 0.01;
+
+/// Post-only pricing for the cheap side: rest just inside the spread and
+/// collect maker pricing, crossing only once the market is about to resolve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MakerMode {
+    /// Dollars above the best bid to rest at.
+    pub improve_by: f64,
+    /// Markets resolving sooner than this cross the spread instead.
+    pub cross_within: chrono::Duration,
+    /// Seconds a maker order rests before it expires.
+    pub rest_secs: u64,
+}
+
+impl Default for MakerMode {
+    fn default() -> Self {
+        Self { improve_by: 0.01, cross_within: chrono::Duration::minutes(5), rest_secs: 60 }
+    }
+}
+
+impl MakerMode {
+    /// `GABAGOOL_MAKER` (true/1) turns it on; `GABAGOOL_MAKER_IMPROVE`,
+    /// `GABAGOOL_MAKER_CROSS_SECS` and `GABAGOOL_MAKER_REST_SECS` override
+    /// the defaults.
+    pub fn from_env() -> Option<Self> {
+        let on = std::env::var("GABAGOOL_MAKER")
+            .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        if !on {
+            return None;
+        }
+        let var = |name: &str| std::env::var(name).ok().and_then(|v| v.trim().parse::<f64>().ok());
+        let mut mode = Self::default();
+        if let Some(improve) = var("GABAGOOL_MAKER_IMPROVE").filter(|v| *v >= 0.0) {
+            mode.improve_by = improve;
+        }
+        if let Some(secs) = var("GABAGOOL_MAKER_CROSS_SECS").filter(|v| *v >= 0.0) {
+            mode.cross_within = chrono::Duration::seconds(secs as i64);
+        }
+        if let Some(secs) = var("GABAGOOL_MAKER_REST_SECS").filter(|v| *v >= 1.0) {
+            mode.rest_secs = secs as u64;
+        }
+        Some(mode)
+    }
+
+    /// Price to rest a buy at, given the side's best `bid` and `ask`: the bid
+    /// improved by `improve_by`, or joining the bid when that would reach
+    /// the ask. `None` when there's no bid to rest behind.
+    pub fn quote(&self, bid: Price, ask: Price) -> Option<Price> {
+        if bid <= Price::ZERO || bid >= ask {
+            return None;
+        }
+        let inside = bid + Price::from_f64(self.improve_by);
+        Some(if inside < ask { inside } else { bid })
+    }
+}
+
+/// Selling back part of a side that far outweighs the other, instead of only
+/// ever buying the light side to catch up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rebalance {
+    /// How often every event is checked.
+    pub interval: Duration,
+    /// The heavy side is trimmed once it holds more than this many times the
+    /// light side's shares.
+    pub max_ratio: f64,
+    /// Share of the excess sold per trim.
+    pub trim_fraction: f64,
+    /// Dollars per share under the heavy side's average cost a trim may sell
+    /// at.
+    pub max_loss: f64,
+}
+
+impl Rebalance {
+    pub fn new(max_ratio: f64) -> Self {
+        Self { interval: Duration::from_secs(30), max_ratio, trim_fraction: 0.5, max_loss: 0.0 }
+    }
+
+    /// `None` unless `GABAGOOL_REBALANCE_RATIO` is set; tuned by
+    /// `GABAGOOL_REBALANCE_TRIM`, `GABAGOOL_REBALANCE_MAX_LOSS` and
+    /// `GABAGOOL_REBALANCE_INTERVAL_SECS`.
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| std::env::var(name).ok().and_then(|v| v.trim().parse::<f64>().ok());
+        let mut rebalance = Self::new(var("GABAGOOL_REBALANCE_RATIO").filter(|r| *r > 1.0)?);
+        if let Some(trim) = var("GABAGOOL_REBALANCE_TRIM").filter(|t| *t > 0.0 && *t <= 1.0) {
+            rebalance.trim_fraction = trim;
+        }
+        if let Some(loss) = var("GABAGOOL_REBALANCE_MAX_LOSS").filter(|l| *l >= 0.0) {
+            rebalance.max_loss = loss;
+        }
+        if let Some(secs) = var("GABAGOOL_REBALANCE_INTERVAL_SECS").filter(|s| *s >= 1.0) {
+            rebalance.interval = Duration::from_secs(secs as u64);
+        }
+        Some(rebalance)
+    }
+
+    /// The side to trim and how many shares, given the event's YES and NO
+    /// shares; `None` while both are held in proportion, or one isn't held.
+    pub fn trim(&self, yes_qty: f64, no_qty: f64) -> Option<(&'static str, f64)> {
+        let (side, heavy, light) = if yes_qty > no_qty {
+            ("YES", yes_qty, no_qty)
+        } else {
+            ("NO", no_qty, yes_qty)
+        };
+        if light <= 0.0 || heavy <= light * self.max_ratio {
+            return None;
+        }
+        Some((side, (heavy - light) * self.trim_fraction))
+            .filter(|(_, shares)| *shares >= MIN_TRIM_SHARES)
+    }
+}
+
+/// Trims smaller than this many shares aren't worth an order.
+const MIN_TRIM_SHARES: f64 = 1.0;
+
+/// Turns matched YES/NO pairs back into collateral before resolution.
+#[async_trait]
+pub trait Merger: Send + Sync {
+    /// Merges `pairs` pairs of a condition; returns the tx hash.
+    async fn merge(&self, condition_id: &str, pairs: f64) -> Result<String>;
+}
+
+#[async_trait]
+impl Merger for PolymarketBlockchain {
+    async fn merge(&self, condition_id: &str, pairs: f64) -> Result<String> {
+        let tx_hash = self.merge_positions(condition_id, pairs).await?;
+        Ok(format!("{:?}", tx_hash))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct GabagoolPosition {
     event_id: String,
     yes_qty: f64,
-    yes_cost: f64,
+    yes_cost: UsdAmount,
     no_qty: f64,
-    no_cost: f64,
+    no_cost: UsdAmount,
+    /// Underlying coin of the market, when known.
+    #[serde(default)]
+    coin: Option<String>,
+    /// First fill; `None` for positions stored before it was recorded.
+    #[serde(default)]
+    opened_at: Option<DateTime<Utc>>,
 }
 
+/// One buy order.
+struct Buy<'a> {
+    event: &'a Event,
+    side: &'a str,
+    amount: UsdAmount,
+    price: Price,
+    tif: TimeInForce,
+}
+
+/// Expiry of the maker order resting on each (event, side).
+type RestingOrders = HashMap<(String, String), DateTime<Utc>>;
+
 pub struct GabagoolExecutor {
-    polymarket_client: Arc<PolymarketClient>,
+    polymarket_client: Arc<dyn ExchangeClient>,
     position_tracker: Option<Arc<Mutex<PositionTracker>>>,
     gabagool_positions: Arc<Mutex<HashMap<String, GabagoolPosition>>>,
+    store_path: Option<PathBuf>,
+    tif: TimeInForce,
+    maker: Option<MakerMode>,
+    resting: Arc<Mutex<RestingOrders>>,
+    /// Merges pairs once at least this many are held.
+    merger: Option<(Arc<dyn Merger>, f64)>,
 }
 
 impl GabagoolExecutor {
-    pub fn new(polymarket_client: Arc<PolymarketClient>) -> Self {
+    pub fn new(polymarket_client: Arc<dyn ExchangeClient>) -> Self {
         Self {
             polymarket_client,
             position_tracker: None,
             gabagool_positions: Arc::new(Mutex::new(HashMap::new())),
+            store_path: None,
+            tif: TimeInForce::default(),
+            maker: None,
+            resting: Arc::new(Mutex::new(HashMap::new())),
+            merger: None,
+        }
+    }
+
+    /// Persists per-event YES/NO balances to a JSON file, loading any that
+    /// were saved by a previous run.
+    pub fn with_store(mut self, path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        if path.exists() {
+            let data = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read Gabagool store {}", path.display()))?;
+            let positions: HashMap<String, GabagoolPosition> = serde_json::from_str(&data)
+                .with_context(|| format!("Failed to parse Gabagool store {}", path.display()))?;
+            info!("📂 Loaded {} Gabagool positions from {}", positions.len(), path.display());
+            self.gabagool_positions = Arc::new(Mutex::new(positions));
         }
+        self.store_path = Some(path);
+        Ok(self)
+    }
+
+    pub async fn save(&self) -> Result<()> {
+        let positions = self.gabagool_positions.lock().await;
+        self.write_store(&positions)
+    }
+
+    fn write_store(&self, positions: &HashMap<String, GabagoolPosition>) -> Result<()> {
+        let Some(path) = &self.store_path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let data = serde_json::to_string_pretty(positions)?;
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, data).with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+        Ok(())
     }
 
     pub fn with_position_tracker(mut self, tracker: Arc<Mutex<PositionTracker>>) -> Self {
@@ -37,7 +245,207 @@ impl GabagoolExecutor {
         self
     }
 
-    pub async fn get_position_balance(&self, event_id: &str) -> (f64, f64, f64, f64) {
+    /// Time in force of the orders (IOC by default).
+    pub fn with_time_in_force(mut self, tif: TimeInForce) -> Self {
+        self.tif = tif;
+        self
+    }
+
+    /// Rests post-only orders inside the spread instead of crossing it.
+    pub fn with_maker_mode(mut self, maker: MakerMode) -> Self {
+        self.maker = Some(maker);
+        self
+    }
+
+    /// Merges an event's matched pairs back into collateral as soon as at
+    /// least `min_pairs` of them cost under $1 a pair.
+    pub fn with_merger(mut self, merger: Arc<dyn Merger>, min_pairs: f64) -> Self {
+        self.merger = Some((merger, min_pairs));
+        self
+    }
+
+    /// `GABAGOOL_MERGE_MIN_PAIRS`; unset disables merging.
+    pub fn merge_min_pairs_from_env() -> Option<f64> {
+        std::env::var("GABAGOOL_MERGE_MIN_PAIRS")
+            .ok()
+            .and_then(|s| s.trim().parse::<f64>().ok())
+            .filter(|p| *p > 0.0)
+    }
+
+    /// Limit price and time in force for `opportunity`: a post-only quote
+    /// inside the spread in maker mode, unless the market resolves within
+    /// the cross window or the book leaves no room; otherwise the ask.
+    async fn order_terms(&self, opportunity: &GabagoolOpportunity) -> (Price, TimeInForce) {
+        let crossing = (Price::from_f64(opportunity.cheap_price), self.tif);
+        let Some(maker) = self.maker else {
+            return crossing;
+        };
+        let now = Utc::now();
+        let until_cross = match opportunity.event.resolution_date {
+            Some(date) => date - maker.cross_within - now,
+            None => chrono::Duration::seconds(maker.rest_secs as i64),
+        };
+        if until_cross.num_seconds() < 1 {
+            return crossing;
+        }
+        let prices = match self.polymarket_client.fetch_prices(&opportunity.event.event_id).await {
+            Ok(prices) => prices,
+            Err(e) => {
+                warn!("Gabagool maker quote unavailable, crossing instead: {}", e);
+                return crossing;
+            }
+        };
+        let side = &opportunity.cheap_side;
+        match maker.quote(prices.bid_for(side), crossing.0.min(prices.ask_for(side))) {
+            Some(price) => {
+                let rest = maker.rest_secs.min(until_cross.num_seconds() as u64);
+                (price, TimeInForce::PostOnly { expires_after: Some(rest) })
+            }
+            None => crossing,
+        }
+    }
+
+    /// Sells part of `event_id`'s heavy side back at its bid when it far
+    /// outweighs the light one and the bid is within `max_loss` of the side's
+    /// average cost. Returns the shares sold.
+    pub async fn rebalance(&self, event_id: &str, rebalance: &Rebalance) -> Result<Option<f64>> {
+        let (yes_qty, yes_cost, no_qty, no_cost) = self.get_position_balance(event_id).await;
+        let Some((side, shares)) = rebalance.trim(yes_qty, no_qty) else {
+            return Ok(None);
+        };
+        let (qty, cost) = if side == "YES" { (yes_qty, yes_cost) } else { (no_qty, no_cost) };
+        let average = cost.per_share(qty);
+        let bid = self.polymarket_client.fetch_prices(event_id).await?.bid_for(side);
+        if bid <= Price::ZERO || bid < average - Price::from_f64(rebalance.max_loss) {
+            info!(
+                "⚖️ {} {} {:.2} vs {:.2} out of balance, but the {} bid is under its {} average cost - not trimming",
+                event_id, side, qty, yes_qty + no_qty - qty, bid, average
+            );
+            return Ok(None);
+        }
+
+        self.polymarket_client
+            .sell_order(event_id.to_string(), side.to_string(), shares, bid.to_f64())
+            .await?;
+        let proceeds = UsdAmount::for_shares(shares, bid);
+
+        let mut positions = self.gabagool_positions.lock().await;
+        if let Some(position) = positions.get_mut(event_id) {
+            let (qty, cost) = if side == "YES" {
+                (&mut position.yes_qty, &mut position.yes_cost)
+            } else {
+                (&mut position.no_qty, &mut position.no_cost)
+            };
+            *cost -= UsdAmount::for_shares(shares, average);
+            *qty -= shares;
+        }
+        if let Err(e) = self.write_store(&positions) {
+            warn!("Failed to persist Gabagool positions: {}", e);
+        }
+        drop(positions);
+
+        self.close_tracked(event_id, side, shares, proceeds).await;
+
+        info!(
+            "✂️ Trimmed {:.2} {} shares of {} at {} (average cost {}) to rebalance toward pairs",
+            shares, side, event_id, bid, average
+        );
+        Ok(Some(shares))
+    }
+
+    /// Merges `event_id`'s matched pairs back into collateral when there are
+    /// at least the merger's minimum of them and their average costs add up
+    /// to under $1, realizing the locked profit now instead of at
+    /// resolution. Returns the pairs merged.
+    pub async fn merge_pairs(&self, event_id: &str) -> Result<Option<f64>> {
+        let Some((merger, min_pairs)) = &self.merger else {
+            return Ok(None);
+        };
+        let (yes_qty, yes_cost, no_qty, no_cost) = self.get_position_balance(event_id).await;
+        let pairs = yes_qty.min(no_qty);
+        if pairs <= 0.0 || pairs < *min_pairs {
+            return Ok(None);
+        }
+        let (yes_average, no_average) = (yes_cost.per_share(yes_qty), no_cost.per_share(no_qty));
+        let pair_cost = yes_average + no_average;
+        if pair_cost >= Price::ONE {
+            return Ok(None);
+        }
+
+        let tx_hash = merger.merge(event_id, pairs).await?;
+
+        let mut positions = self.gabagool_positions.lock().await;
+        if let Some(position) = positions.get_mut(event_id) {
+            position.yes_qty -= pairs;
+            position.yes_cost -= UsdAmount::for_shares(pairs, yes_average);
+            position.no_qty -= pairs;
+            position.no_cost -= UsdAmount::for_shares(pairs, no_average);
+        }
+        if let Err(e) = self.write_store(&positions) {
+            warn!("Failed to persist Gabagool positions: {}", e);
+        }
+        drop(positions);
+
+        // Each side's share of the $1 a pair pays, in proportion to its cost.
+        let merged = UsdAmount::for_shares(pairs, Price::ONE);
+        let yes_proceeds = merged.portion(yes_average, pair_cost);
+        self.close_tracked(event_id, "YES", pairs, yes_proceeds).await;
+        self.close_tracked(event_id, "NO", pairs, merged - yes_proceeds).await;
+
+        info!(
+            "🔀 Merged {:.2} pairs of {} at {} a pair into {}: {} profit realized ({})",
+            pairs,
+            event_id,
+            pair_cost,
+            merged,
+            UsdAmount::for_shares(pairs, pair_cost.complement()),
+            tx_hash
+        );
+        Ok(Some(pairs))
+    }
+
+    /// Closes `shares` of the tracker's open Gabagool and quoting positions
+    /// on `side` of `event_id`, oldest fills first, splitting `proceeds`
+    /// across them.
+    async fn close_tracked(&self, event_id: &str, side: &str, shares: f64, proceeds: UsdAmount) {
+        let Some(tracker) = &self.position_tracker else {
+            return;
+        };
+        let mut tracker = tracker.lock().await;
+        let mut held: Vec<(DateTime<Utc>, String, f64)> = tracker
+            .get_open_positions()
+            .into_iter()
+            .filter(|p| p.platform == "polymarket" && p.event_id == event_id && p.outcome == side)
+            .filter(|p| matches!(p.strategy.as_deref(), Some(GABAGOOL) | Some(QUOTING)))
+            .map(|p| (p.created_at, p.id.clone(), p.amount))
+            .collect();
+        held.sort_by_key(|(created_at, ..)| *created_at);
+        let mut left = shares;
+        for (_, id, amount) in held {
+            if left <= 0.0 {
+                break;
+            }
+            let sold = amount.min(left);
+            tracker.close_shares(&id, sold, proceeds.pro_rata(sold, shares));
+            left -= sold;
+        }
+    }
+
+    /// Checks every event for a side to trim every `rebalance.interval`.
+    pub async fn run_rebalancing(self: Arc<Self>, rebalance: Rebalance) {
+        let mut ticker = tokio::time::interval(rebalance.interval);
+        loop {
+            ticker.tick().await;
+            let event_ids: Vec<String> = self.gabagool_positions.lock().await.keys().cloned().collect();
+            for event_id in event_ids {
+                if let Err(e) = self.rebalance(&event_id, &rebalance).await {
+                    warn!("Gabagool rebalance of {} failed: {}", event_id, e);
+                }
+            }
+        }
+    }
+
+    pub async fn get_position_balance(&self, event_id: &str) -> (f64, UsdAmount, f64, UsdAmount) {
         let positions = self.gabagool_positions.lock().await;
         if let Some(pos) = positions.get(event_id) {
             (
@@ -47,7 +455,7 @@ impl GabagoolExecutor {
                 pos.no_cost,
             )
         } else {
-            (0.0, 0.0, 0.0, 0.0)
+            (0.0, UsdAmount::ZERO, 0.0, UsdAmount::ZERO)
         }
     }
 
@@ -66,34 +474,84 @@ impl GabagoolExecutor {
             opportunity.roi_percent
         );
 
-        let shares = amount / opportunity.cheap_price;
+        let (price, tif) = self.order_terms(opportunity).await;
+        let amount = UsdAmount::from_f64(amount);
+        let order = Buy { event: &opportunity.event, side: &opportunity.cheap_side, amount, price, tif };
+        self.buy(order, GABAGOOL, opportunity.roi_percent / 100.0).await
+    }
+
+    /// Rests a post-only bid for `shares` of `side` at `bid` for one of
+    /// `quote`'s sides, expiring after `rest_secs`. Booked into the same pair
+    /// balances as Gabagool's fills, under the quoting strategy.
+    pub async fn rest_quote(
+        &self,
+        quote: &TwoSidedQuote,
+        side: &str,
+        bid: Price,
+        shares: f64,
+        rest_secs: u64,
+    ) -> Result<bool> {
+        let order = Buy {
+            event: &quote.event,
+            side,
+            amount: UsdAmount::for_shares(shares, bid),
+            price: bid,
+            tif: TimeInForce::PostOnly { expires_after: Some(rest_secs) },
+        };
+        self.buy(order, QUOTING, quote.roi_percent / 100.0).await
+    }
+
+    /// Places `order` and books it into the pair balances and the tracker,
+    /// unless a post-only order is still resting on the same side.
+    async fn buy(&self, order: Buy<'_>, strategy: &'static str, detected_edge: f64) -> Result<bool> {
+        let Buy { event, side, amount, price, tif } = order;
+        let resting_key = (event.event_id.clone(), side.to_string());
+        if self.resting.lock().await.get(&resting_key).is_some_and(|expires| *expires > Utc::now()) {
+            info!(
+                "⏳ Maker order still resting on {} {} - not requoting",
+                event.title, side
+            );
+            return Ok(false);
+        }
+
+        let shares = amount.shares_at(price);
+        if tif.is_post_only() {
+            info!("🧾 Resting post-only {} bid @ {} ({})", side, price, tif);
+        }
 
         let order_id = self
             .polymarket_client
             .place_order(
-                opportunity.event.event_id.clone(),
-                opportunity.cheap_side.clone(),
-                amount,
-                opportunity.cheap_price,
+                event.event_id.clone(),
+                side.to_string(),
+                amount.to_f64(),
+                price.to_f64(),
+                tif,
             )
             .await?;
 
+        if let Some(expires) = tif.expires_at(Utc::now()).filter(|_| tif.is_post_only()) {
+            self.resting.lock().await.insert(resting_key, expires);
+        }
+
         if order_id.is_none() {
             warn!("⚠️ Gabagool order placed but no order ID returned");
         }
 
         let mut positions = self.gabagool_positions.lock().await;
         let position = positions
-            .entry(opportunity.event.event_id.clone())
+            .entry(event.event_id.clone())
             .or_insert_with(|| GabagoolPosition {
-                event_id: opportunity.event.event_id.clone(),
+                event_id: event.event_id.clone(),
                 yes_qty: 0.0,
-                yes_cost: 0.0,
+                yes_cost: UsdAmount::ZERO,
                 no_qty: 0.0,
-                no_cost: 0.0,
+                no_cost: UsdAmount::ZERO,
+                coin: event.coin_from_slug(),
+                opened_at: Some(Utc::now()),
             });
 
-        if opportunity.cheap_side == "YES" {
+        if side == "YES" {
             position.yes_qty += shares;
             position.yes_cost += amount;
         } else {
@@ -106,87 +564,232 @@ impl GabagoolExecutor {
         let new_yes_cost = position.yes_cost;
         let new_no_cost = position.no_cost;
 
+        if let Err(e) = self.write_store(&positions) {
+            warn!("Failed to persist Gabagool positions: {}", e);
+        }
         drop(positions);
 
         if let Some(tracker) = &self.position_tracker {
             let mut tracker = tracker.lock().await;
             let position = Position::new(
                 "polymarket".to_string(),
-                &opportunity.event,
-                opportunity.cheap_side.clone(),
+                event,
+                side.to_string(),
                 shares,
                 amount,
-                opportunity.cheap_price,
+                price,
                 order_id,
-            );
+            )
+            .with_strategy(strategy)
+            .with_detected_edge(detected_edge);
             tracker.add_position(position);
         }
 
         let min_qty = new_yes_qty.min(new_no_qty);
-        let pair_cost = if min_qty > 0.0 {
-            (new_yes_cost + new_no_cost) / min_qty
-        } else {
-            opportunity.total_cost
-        };
+        let pair_cost = (min_qty > 0.0).then(|| (new_yes_cost + new_no_cost).per_share(min_qty));
 
         info!(
-            "📊 Position updated - YES: {:.2} (${:.2}), NO: {:.2} (${:.2}), Pairs: {:.2}, Pair Cost: ${:.4}",
-            new_yes_qty, new_yes_cost, new_no_qty, new_no_cost, min_qty, pair_cost
+            "📊 Position updated - YES: {:.2} ({}), NO: {:.2} ({}), Pairs: {:.2}, Pair Cost: {}",
+            new_yes_qty,
+            new_yes_cost,
+            new_no_qty,
+            new_no_cost,
+            min_qty,
+            pair_cost.map_or("n/a".to_string(), |cost| cost.to_string())
         );
 
-        if pair_cost < 1.0 && min_qty > 0.0 {
-            let locked_profit = (1.0 - pair_cost) * min_qty;
+        if let Some(pair_cost) = pair_cost.filter(|cost| *cost < Price::ONE) {
+            let locked_profit = UsdAmount::for_shares(min_qty, pair_cost.complement());
             info!(
-                "🔒 Profit LOCKED! ${:.2} guaranteed profit on {:.2} pairs",
+                "🔒 Profit LOCKED! {} guaranteed profit on {:.2} pairs",
                 locked_profit, min_qty
             );
         }
 
+        if let Err(e) = self.merge_pairs(&event.event_id).await {
+            warn!("⚠️ Merging {} pairs failed, holding them to resolution: {}", event.title, e);
+        }
+
         Ok(true)
     }
 
+    /// Corrects tracked YES/NO quantities to actual holdings. `fetch_holdings`
+    /// is called once with every tracked event id and returns (yes, no)
+    /// shares per event; events it leaves out are skipped. A side holding
+    /// less than tracked has its cost scaled down with it (the shares were
+    /// never bought); a side holding more keeps its cost, since what the
+    /// extra shares cost is unknown. Returns how many events were corrected.
+    pub async fn reconcile_holdings<F, Fut>(&self, fetch_holdings: F) -> usize
+    where
+        F: FnOnce(Vec<String>) -> Fut,
+        Fut: std::future::Future<Output = Result<HashMap<String, (f64, f64)>>>,
+    {
+        let event_ids: Vec<String> = self.gabagool_positions.lock().await.keys().cloned().collect();
+        if event_ids.is_empty() {
+            return 0;
+        }
+        let holdings = match fetch_holdings(event_ids).await {
+            Ok(holdings) => holdings,
+            Err(e) => {
+                warn!("Gabagool holdings check failed: {}", e);
+                return 0;
+            }
+        };
+        let mut corrected = 0;
+        let mut positions = self.gabagool_positions.lock().await;
+        for (event_id, (yes, no)) in holdings {
+            let Some(position) = positions.get_mut(&event_id) else {
+                continue;
+            };
+            let drifted = |tracked: f64, held: f64| (tracked - held).abs() > RECONCILE_TOLERANCE;
+            if !drifted(position.yes_qty, yes) && !drifted(position.no_qty, no) {
+                continue;
+            }
+            warn!(
+                "🔧 Gabagool drift on {}: tracked YES {:.2} / NO {:.2}, on-chain YES {:.2} / NO {:.2} - correcting",
+                event_id, position.yes_qty, position.no_qty, yes, no
+            );
+            let rescale = |qty: f64, cost: UsdAmount, held: f64| {
+                if held < qty && qty > 0.0 {
+                    cost.pro_rata(held, qty)
+                } else {
+                    cost
+                }
+            };
+            position.yes_cost = rescale(position.yes_qty, position.yes_cost, yes);
+            position.no_cost = rescale(position.no_qty, position.no_cost, no);
+            position.yes_qty = yes;
+            position.no_qty = no;
+            corrected += 1;
+        }
+        if corrected > 0 {
+            if let Err(e) = self.write_store(&positions) {
+                warn!("Failed to persist Gabagool positions: {}", e);
+            }
+        }
+        corrected
+    }
+
+    /// `GABAGOOL_RECONCILE_INTERVAL_SECS`; unset disables on-chain
+    /// reconciliation.
+    pub fn reconcile_interval_from_env() -> Option<Duration> {
+        std::env::var("GABAGOOL_RECONCILE_INTERVAL_SECS")
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .filter(|s| *s > 0)
+            .map(Duration::from_secs)
+    }
+
+    /// Reconciles against on-chain ERC-1155 balances every `interval`, all
+    /// tracked events in one batched read.
+    pub async fn run_reconciliation(self: Arc<Self>, chain: Arc<PolymarketBlockchain>, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let corrected = self
+                .reconcile_holdings(|event_ids| {
+                    let chain = chain.clone();
+                    async move { chain.get_outcome_token_balances_batch(&event_ids).await }
+                })
+                .await;
+            if corrected > 0 {
+                info!("⛓️ Corrected {} Gabagool positions to on-chain holdings", corrected);
+            }
+        }
+    }
+
     pub async fn get_statistics(&self) -> GabagoolStatistics {
         let positions = self.gabagool_positions.lock().await;
-        
-        let mut total_events = 0;
-        let mut total_yes_qty = 0.0;
-        let mut total_no_qty = 0.0;
-        let mut total_yes_cost = 0.0;
-        let mut total_no_cost = 0.0;
-        let mut locked_profit = 0.0;
-        let mut locked_pairs = 0.0;
 
+        let mut stats = GabagoolStatistics::default();
+        let (mut yes_cost, mut no_cost, mut locked) = (UsdAmount::ZERO, UsdAmount::ZERO, UsdAmount::ZERO);
         for pos in positions.values() {
-            total_events += 1;
-            total_yes_qty += pos.yes_qty;
-            total_no_qty += pos.no_qty;
-            total_yes_cost += pos.yes_cost;
-            total_no_cost += pos.no_cost;
-
-            let min_qty = pos.yes_qty.min(pos.no_qty);
-            if min_qty > 0.0 {
-                let pair_cost = (pos.yes_cost + pos.no_cost) / min_qty;
-                if pair_cost < 1.0 {
-                    locked_pairs += min_qty;
-                    locked_profit += (1.0 - pair_cost) * min_qty;
-                }
+            stats.total_events += 1;
+            stats.total_yes_qty += pos.yes_qty;
+            stats.total_no_qty += pos.no_qty;
+            yes_cost += pos.yes_cost;
+            no_cost += pos.no_cost;
+
+            let (locked_pairs, locked_profit) = pos.locked();
+            stats.locked_pairs += locked_pairs;
+            locked += locked_profit;
+
+            let coin = pos.coin.clone().unwrap_or_else(|| "other".to_string());
+            stats.by_coin.entry(coin).or_default().add(pos);
+            if let Some(opened_at) = pos.opened_at {
+                stats.by_hour.entry(opened_at.hour()).or_default().add(pos);
             }
         }
+        stats.total_yes_cost = yes_cost.to_f64();
+        stats.total_no_cost = no_cost.to_f64();
+        stats.total_cost = (yes_cost + no_cost).to_f64();
+        stats.locked_profit = locked.to_f64();
+        stats
+    }
+}
 
-        GabagoolStatistics {
-            total_events,
-            total_yes_qty,
-            total_no_qty,
-            total_yes_cost,
-            total_no_cost,
-            total_cost: total_yes_cost + total_no_cost,
-            locked_profit,
-            locked_pairs,
+/// One event's YES/NO holdings and what a matched pair cost.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GabagoolPairCost {
+    pub event_id: String,
+    pub coin: Option<String>,
+    pub yes_qty: f64,
+    pub yes_cost: f64,
+    pub no_qty: f64,
+    pub no_cost: f64,
+    /// Combined average cost of one YES and one NO share; `None` until both
+    /// sides are held.
+    pub pair_cost: Option<f64>,
+    pub locked_profit: f64,
+}
+
+impl GabagoolExecutor {
+    /// Pair cost per event, cheapest pairs first.
+    pub async fn pair_costs(&self) -> Vec<GabagoolPairCost> {
+        let positions = self.gabagool_positions.lock().await;
+        let mut costs: Vec<GabagoolPairCost> = positions
+            .values()
+            .map(|pos| {
+                let average = |cost: UsdAmount, qty: f64| (qty > 0.0).then(|| cost.per_share(qty));
+                GabagoolPairCost {
+                    event_id: pos.event_id.clone(),
+                    coin: pos.coin.clone(),
+                    yes_qty: pos.yes_qty,
+                    yes_cost: pos.yes_cost.to_f64(),
+                    no_qty: pos.no_qty,
+                    no_cost: pos.no_cost.to_f64(),
+                    pair_cost: average(pos.yes_cost, pos.yes_qty)
+                        .zip(average(pos.no_cost, pos.no_qty))
+                        .map(|(yes, no)| (yes + no).to_f64()),
+                    locked_profit: pos.locked().1.to_f64(),
+                }
+            })
+            .collect();
+        costs.sort_by(|a, b| {
+            a.pair_cost
+                .unwrap_or(f64::MAX)
+                .total_cmp(&b.pair_cost.unwrap_or(f64::MAX))
+        });
+        costs
+    }
+}
+
+impl GabagoolPosition {
+    /// Matched YES/NO pairs and the profit they lock in, when the pair cost
+    /// is under $1.
+    fn locked(&self) -> (f64, UsdAmount) {
+        let min_qty = self.yes_qty.min(self.no_qty);
+        if min_qty > 0.0 {
+            let pair_cost = (self.yes_cost + self.no_cost).per_share(min_qty);
+            if pair_cost < Price::ONE {
+                return (min_qty, UsdAmount::for_shares(min_qty, pair_cost.complement()));
+            }
         }
+        (0.0, UsdAmount::ZERO)
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct GabagoolStatistics {
     pub total_events: usize,
     pub total_yes_qty: f64,
@@ -196,5 +799,25 @@ pub struct GabagoolStatistics {
     pub total_cost: f64,
     pub locked_profit: f64,
     pub locked_pairs: f64,
+    /// Keyed by coin (btc/eth/sol), `other` when the market has none.
+    pub by_coin: BTreeMap<String, GabagoolBreakdown>,
+    /// Keyed by the UTC hour of the event's first fill.
+    pub by_hour: BTreeMap<u32, GabagoolBreakdown>,
+}
+
+/// Events, cost and locked profit in one slice of `GabagoolStatistics`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GabagoolBreakdown {
+    pub events: usize,
+    pub cost: f64,
+    pub locked_profit: f64,
+}
+
+impl GabagoolBreakdown {
+    fn add(&mut self, position: &GabagoolPosition) {
+        self.events += 1;
+        self.cost = (UsdAmount::from_f64(self.cost) + position.yes_cost + position.no_cost).to_f64();
+        self.locked_profit = (UsdAmount::from_f64(self.locked_profit) + position.locked().1).to_f64();
+    }
 }
 
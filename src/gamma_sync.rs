@@ -0,0 +1,131 @@
+//! Incremental sync of the Gamma events listing.
+//!
+//! A full listing pages through every open event. With incremental sync on
+//! (`GAMMA_FULL_RESYNC_SECS` set), later scans only page through events
+//! ordered by `updatedAt` until they reach ones already seen, merge them into
+//! the cached set (dropping events that closed) and redo a full listing every
+//! `GAMMA_FULL_RESYNC_SECS` to shed anything the updates missed.
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// `updatedAt` of a Gamma event.
+pub fn updated_at(event: &Value) -> Option<DateTime<Utc>> {
+    event["updatedAt"]
+        .as_str()
+        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+fn is_open(event: &Value) -> bool {
+    event["closed"].as_bool() != Some(true) && event["active"].as_bool() != Some(false)
+}
+
+/// Open Gamma events as of the last sync, keyed by Gamma event id.
+pub struct GammaEventCache {
+    full_resync_every: Duration,
+    events: HashMap<String, Value>,
+    /// Latest `updatedAt` seen; later scans fetch from here.
+    watermark: Option<DateTime<Utc>>,
+    tag_slug: Option<String>,
+    last_full_sync: Option<Instant>,
+}
+
+impl GammaEventCache {
+    pub fn new(full_resync_every: Duration) -> Self {
+        Self {
+            full_resync_every,
+            events: HashMap::new(),
+            watermark: None,
+            tag_slug: None,
+            last_full_sync: None,
+        }
+    }
+
+    /// `GAMMA_FULL_RESYNC_SECS`; unset disables incremental sync.
+    pub fn resync_interval_from_env() -> Option<Duration> {
+        std::env::var("GAMMA_FULL_RESYNC_SECS")
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .filter(|s| *s > 0)
+            .map(Duration::from_secs)
+    }
+
+    /// Where an incremental fetch can stop, or `None` when the next fetch
+    /// has to be a full listing (first scan, tag change, resync due).
+    pub fn updated_since(&self, tag_slug: Option<&str>) -> Option<DateTime<Utc>> {
+        if self.tag_slug.as_deref() != tag_slug {
+            return None;
+        }
+        let fresh = self
+            .last_full_sync
+            .is_some_and(|at| at.elapsed() < self.full_resync_every);
+        self.watermark.filter(|_| fresh)
+    }
+
+    /// Replaces the cache with a full listing.
+    pub fn replace(&mut self, tag_slug: Option<&str>, events: Vec<Value>) {
+        self.events.clear();
+        self.watermark = None;
+        self.tag_slug = tag_slug.map(str::to_string);
+        self.last_full_sync = Some(Instant::now());
+        self.merge(events);
+    }
+
+    /// Applies events updated since the last sync; ones that closed or went
+    /// inactive are dropped.
+    pub fn merge(&mut self, events: Vec<Value>) {
+        for event in events {
+            if let Some(updated) = updated_at(&event) {
+                self.watermark = Some(self.watermark.map_or(updated, |w| w.max(updated)));
+            }
+            let Some(id) = event["id"].as_str().map(str::to_string) else {
+                continue;
+            };
+            if is_open(&event) {
+                self.events.insert(id, event);
+            } else {
+                self.events.remove(&id);
+            }
+        }
+    }
+
+    pub fn events(&self) -> Vec<Value> {
+        self.events.values().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merges_updates_and_drops_closed_events() {
+        let mut cache = GammaEventCache::new(Duration::from_secs(600));
+        assert_eq!(cache.updated_since(Some("crypto")), None);
+
+        cache.replace(
+            Some("crypto"),
+            vec![
+                json!({"id": "1", "updatedAt": "2026-01-01T12:00:00Z"}),
+                json!({"id": "2", "updatedAt": "2026-01-01T12:05:00Z"}),
+            ],
+        );
+        let since = cache.updated_since(Some("crypto")).unwrap();
+        assert_eq!(since.to_rfc3339(), "2026-01-01T12:05:00+00:00");
+        // A different tag needs a full listing.
+        assert_eq!(cache.updated_since(None), None);
+
+        cache.merge(vec![
+            json!({"id": "1", "updatedAt": "2026-01-01T12:10:00Z", "closed": true}),
+            json!({"id": "3", "updatedAt": "2026-01-01T12:09:00Z"}),
+        ]);
+        let mut ids: Vec<String> = cache.events().iter().map(|e| e["id"].as_str().unwrap().to_string()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["2", "3"]);
+        assert_eq!(cache.updated_since(Some("crypto")).unwrap().to_rfc3339(), "2026-01-01T12:10:00+00:00");
+    }
+}
@@ -0,0 +1,100 @@
+//! EIP-1559 fees and stuck-transaction replacement for Polygon.
+//!
+//! Fees come from the node's EIP-1559 estimate, with the priority fee kept
+//! between `GAS_MIN_PRIORITY_FEE_GWEI` (default 30, Polygon's floor) and
+//! `GAS_MAX_PRIORITY_FEE_GWEI` (default 200), and the max fee capped at
+//! `GAS_MAX_FEE_GWEI` (default 1000). A transaction not mined within
+//! `TX_STUCK_TIMEOUT_SECS` (default 120) is re-sent with the same nonce and
+//! fees bumped by `TX_FEE_BUMP_PCT` (default 20; nodes require at least 10),
+//! up to `TX_MAX_REPLACEMENTS` (default 3) times.
+
+use ethers::types::U256;
+use std::time::Duration;
+
+const GWEI: f64 = 1_000_000_000.0;
+
+pub fn gwei(value: f64) -> U256 {
+    U256::from((value * GWEI).round() as u128)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GasPolicy {
+    pub min_priority_fee: U256,
+    pub max_priority_fee: U256,
+    pub max_fee_cap: U256,
+    pub stuck_after: Duration,
+    pub bump_pct: u64,
+    pub max_replacements: u32,
+}
+
+impl Default for GasPolicy {
+    fn default() -> Self {
+        Self {
+            min_priority_fee: gwei(30.0),
+            max_priority_fee: gwei(200.0),
+            max_fee_cap: gwei(1000.0),
+            stuck_after: Duration::from_secs(120),
+            bump_pct: 20,
+            max_replacements: 3,
+        }
+    }
+}
+
+impl GasPolicy {
+    pub fn from_env() -> Self {
+        fn parse<T: std::str::FromStr>(key: &str) -> Option<T> {
+            std::env::var(key).ok().and_then(|s| s.trim().parse().ok())
+        }
+        let d = Self::default();
+        let gwei_var = |key: &str, default: U256| {
+            parse::<f64>(key).filter(|v| *v >= 0.0).map(gwei).unwrap_or(default)
+        };
+        Self {
+            min_priority_fee: gwei_var("GAS_MIN_PRIORITY_FEE_GWEI", d.min_priority_fee),
+            max_priority_fee: gwei_var("GAS_MAX_PRIORITY_FEE_GWEI", d.max_priority_fee),
+            max_fee_cap: gwei_var("GAS_MAX_FEE_GWEI", d.max_fee_cap),
+            stuck_after: parse::<u64>("TX_STUCK_TIMEOUT_SECS")
+                .filter(|s| *s > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(d.stuck_after),
+            bump_pct: parse::<u64>("TX_FEE_BUMP_PCT").map(|p| p.max(10)).unwrap_or(d.bump_pct),
+            max_replacements: parse("TX_MAX_REPLACEMENTS").unwrap_or(d.max_replacements),
+        }
+    }
+
+    /// (max fee, priority fee) from the node's estimate, clamped to the policy.
+    pub fn fees(&self, estimated_max_fee: U256, estimated_priority_fee: U256) -> (U256, U256) {
+        let priority = estimated_priority_fee
+            .max(self.min_priority_fee)
+            .min(self.max_priority_fee);
+        // The estimate's max fee assumes its own priority fee; keep the base
+        // fee headroom when the priority fee was raised to the floor.
+        let base_headroom = estimated_max_fee.saturating_sub(estimated_priority_fee);
+        let max_fee = (base_headroom + priority).min(self.max_fee_cap).max(priority);
+        (max_fee, priority)
+    }
+
+    /// Fees for a replacement of a transaction sent at (max fee, priority).
+    /// The max fee cap is ignored here: a stuck transaction has to outbid
+    /// itself to be replaced at all.
+    pub fn bumped(&self, max_fee: U256, priority: U256) -> (U256, U256) {
+        let bump = |fee: U256| fee + fee * U256::from(self.bump_pct) / U256::from(100);
+        (bump(max_fee), bump(priority).min(bump(max_fee)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamps_estimates_and_bumps_replacements() {
+        let policy = GasPolicy::default();
+        // Priority raised to the 30 gwei floor, base fee headroom kept.
+        assert_eq!(policy.fees(gwei(110.0), gwei(10.0)), (gwei(130.0), gwei(30.0)));
+        // Spikes are capped.
+        assert_eq!(policy.fees(gwei(5000.0), gwei(500.0)), (gwei(1000.0), gwei(200.0)));
+
+        assert_eq!(policy.bumped(gwei(100.0), gwei(30.0)), (gwei(120.0), gwei(36.0)));
+    }
+}
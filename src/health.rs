@@ -0,0 +1,200 @@
+//! Liveness: `/healthz` and an optional heartbeat ping.
+//!
+//! The process-wide `health()` records the last successful scan (both
+//! venues' markets fetched), the last successful API call per client (every
+//! `send_tracked` request that got a 2xx) and whether each websocket is
+//! connected. `GET /healthz` on `METRICS_ADDR` returns it as JSON, with 503
+//! once the last scan is older than `HEALTH_MAX_SCAN_AGE_SECS` (default three
+//! scan intervals). With `HEARTBEAT_URL` set, that URL is fetched every
+//! `HEARTBEAT_INTERVAL_SECS` (default 60) while healthy, so a monitor such as
+//! healthchecks.io alerts when a process hangs without exiting.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+const DEFAULT_HEARTBEAT_SECS: u64 = 60;
+/// Allowed scan age, in scan intervals, unless `HEALTH_MAX_SCAN_AGE_SECS` is set.
+const SCAN_INTERVALS_ALLOWED: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct WebsocketStatus {
+    pub connected: bool,
+    /// When it last connected or dropped.
+    pub since: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthReport {
+    pub healthy: bool,
+    pub started_at: DateTime<Utc>,
+    pub last_scan: Option<DateTime<Utc>>,
+    pub last_scan_age_secs: Option<i64>,
+    pub max_scan_age_secs: u64,
+    /// Last 2xx response per client.
+    pub last_api_success: BTreeMap<String, DateTime<Utc>>,
+    pub websockets: BTreeMap<String, WebsocketStatus>,
+}
+
+struct HealthState {
+    started_at: DateTime<Utc>,
+    last_scan: Option<DateTime<Utc>>,
+    max_scan_age: Duration,
+    last_api_success: BTreeMap<String, DateTime<Utc>>,
+    websockets: BTreeMap<String, WebsocketStatus>,
+}
+
+pub struct Health {
+    state: RwLock<HealthState>,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self {
+            state: RwLock::new(HealthState {
+                started_at: Utc::now(),
+                last_scan: None,
+                max_scan_age: Duration::from_secs(180),
+                last_api_success: BTreeMap::new(),
+                websockets: BTreeMap::new(),
+            }),
+        }
+    }
+}
+
+impl Health {
+    pub fn set_max_scan_age(&self, max_age: Duration) {
+        self.state.write().unwrap_or_else(|e| e.into_inner()).max_scan_age = max_age;
+    }
+
+    pub fn record_scan(&self) {
+        self.state.write().unwrap_or_else(|e| e.into_inner()).last_scan = Some(Utc::now());
+    }
+
+    pub fn record_api_success(&self, client: &str) {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        state.last_api_success.insert(client.to_string(), Utc::now());
+    }
+
+    pub fn set_websocket(&self, name: &str, connected: bool) {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        state
+            .websockets
+            .insert(name.to_string(), WebsocketStatus { connected, since: Utc::now() });
+    }
+
+    /// Healthy while the last scan (or, before the first one, startup) is
+    /// within the allowed age.
+    pub fn report_at(&self, now: DateTime<Utc>) -> HealthReport {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        let since = state.last_scan.unwrap_or(state.started_at);
+        let max_age = chrono::Duration::from_std(state.max_scan_age).unwrap_or(chrono::Duration::MAX);
+        HealthReport {
+            healthy: now - since <= max_age,
+            started_at: state.started_at,
+            last_scan: state.last_scan,
+            last_scan_age_secs: state.last_scan.map(|at| (now - at).num_seconds()),
+            max_scan_age_secs: state.max_scan_age.as_secs(),
+            last_api_success: state.last_api_success.clone(),
+            websockets: state.websockets.clone(),
+        }
+    }
+
+    pub fn report(&self) -> HealthReport {
+        self.report_at(Utc::now())
+    }
+}
+
+/// The process-wide health state.
+pub fn health() -> &'static Health {
+    static HEALTH: OnceLock<Health> = OnceLock::new();
+    HEALTH.get_or_init(Health::default)
+}
+
+/// `HEALTH_MAX_SCAN_AGE_SECS`, or three scan intervals.
+pub fn max_scan_age_from_env(scan_interval: Duration) -> Duration {
+    std::env::var("HEALTH_MAX_SCAN_AGE_SECS")
+        .ok()
+        .and_then(|s| s.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(scan_interval * SCAN_INTERVALS_ALLOWED)
+}
+
+/// Pings a monitoring URL while the process is healthy.
+pub struct Heartbeat {
+    url: String,
+    interval: Duration,
+    http: reqwest::Client,
+}
+
+impl Heartbeat {
+    /// `HEARTBEAT_URL`, pinged every `HEARTBEAT_INTERVAL_SECS`.
+    pub fn from_env() -> Option<Self> {
+        let url = std::env::var("HEARTBEAT_URL").ok().filter(|s| !s.trim().is_empty())?;
+        let secs = std::env::var("HEARTBEAT_INTERVAL_SECS")
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_HEARTBEAT_SECS);
+        Some(Self {
+            url: url.trim().to_string(),
+            interval: Duration::from_secs(secs),
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+        })
+    }
+
+    pub fn spawn(self) -> JoinHandle<()> {
+        info!("💓 Heartbeat to {} every {}s", self.url, self.interval.as_secs());
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.interval);
+            loop {
+                ticker.tick().await;
+                let report = health().report();
+                if !report.healthy {
+                    warn!(
+                        "💓 Heartbeat withheld: last scan {}",
+                        report
+                            .last_scan_age_secs
+                            .map_or("never".to_string(), |secs| format!("{}s ago", secs))
+                    );
+                    continue;
+                }
+                match self.http.get(&self.url).send().await {
+                    Ok(response) if response.status().is_success() => {}
+                    Ok(response) => warn!("💓 Heartbeat returned {}", response.status()),
+                    Err(e) => warn!("💓 Heartbeat failed: {}", e),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unhealthy_once_scans_stop() {
+        let health = Health::default();
+        health.set_max_scan_age(Duration::from_secs(180));
+        let now = Utc::now();
+        assert!(health.report_at(now).healthy);
+        assert!(!health.report_at(now + chrono::Duration::seconds(200)).healthy);
+
+        health.record_scan();
+        health.record_api_success("kalshi");
+        health.set_websocket("binance_spot", false);
+        let report = health.report_at(Utc::now() + chrono::Duration::seconds(60));
+        assert!(report.healthy);
+        assert!(report.last_api_success.contains_key("kalshi"));
+        assert!(!report.websockets["binance_spot"].connected);
+    }
+}
@@ -0,0 +1,310 @@
+//! Append-only trade journal.
+//!
+//! With `TRADE_JOURNAL_PATH` set, every decision the bot takes is appended
+//! to that file as one JSON line: opportunities detected, skipped (with the
+//! reason) and executed, orders submitted and what the venue answered, fills
+//! booked, and positions settled or closed. Each line carries a sequence
+//! number that only ever grows, across restarts too (it resumes after the
+//! file's last line), so a gap or a reorder shows a lost write. The file is
+//! only ever appended to, one write per line, so what the bot did and why
+//! can be rebuilt from it after an incident.
+
+use crate::event::{Event, MarketPrices};
+use crate::exchange::{ExchangeClient, Fill, OrderFill, Resolution, SettlementRecord, TimeInForce};
+use crate::skip_reason::SkipReason;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+/// One decision.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JournalEntry {
+    OpportunityDetected {
+        strategy: String,
+        title: String,
+        net_profit: f64,
+        roi_percent: f64,
+        capital: f64,
+    },
+    OpportunitySkipped {
+        strategy: String,
+        title: String,
+        reason: SkipReason,
+        detail: String,
+    },
+    /// What `Strategy::execute` returned: traded or not, or the error.
+    OpportunityExecuted {
+        strategy: String,
+        title: String,
+        traded: bool,
+        error: Option<String>,
+    },
+    /// `amount` is dollars for a buy, shares for a sell.
+    OrderSubmitted {
+        platform: String,
+        event_id: String,
+        side: String,
+        outcome: String,
+        amount: f64,
+        price: f64,
+        time_in_force: Option<String>,
+    },
+    OrderResponse {
+        platform: String,
+        event_id: String,
+        side: String,
+        outcome: String,
+        order_id: Option<String>,
+        error: Option<String>,
+    },
+    /// A position booked from an order.
+    Fill {
+        position_id: String,
+        platform: String,
+        event_id: String,
+        outcome: String,
+        shares: f64,
+        cost: f64,
+        price: f64,
+        order_id: Option<String>,
+        strategy: Option<String>,
+    },
+    /// A position settled at resolution or closed before it.
+    Settled {
+        position_id: String,
+        platform: String,
+        event_id: String,
+        outcome: String,
+        status: String,
+        payout: Option<f64>,
+        profit: Option<f64>,
+    },
+}
+
+/// A journal line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalRecord {
+    pub seq: u64,
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub entry: JournalEntry,
+}
+
+/// The open journal file and the last sequence number written to it.
+pub struct Journal {
+    path: PathBuf,
+    file: Mutex<(File, u64)>,
+}
+
+impl Journal {
+    /// Opens `path` for appending, resuming after its last sequence number.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let last_seq = if path.exists() {
+            read(&path)?.last().map_or(0, |record| record.seq)
+        } else {
+            0
+        };
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open trade journal {}", path.display()))?;
+        Ok(Self { path, file: Mutex::new((file, last_seq)) })
+    }
+
+    /// `TRADE_JOURNAL_PATH`, when set.
+    pub fn path_from_env() -> Option<PathBuf> {
+        std::env::var("TRADE_JOURNAL_PATH")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .map(PathBuf::from)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends `entry` under the next sequence number. A failed write is
+    /// logged, not returned: the journal never stops trading.
+    pub fn record(&self, entry: JournalEntry) {
+        let mut guard = self.file.lock().unwrap();
+        let (file, seq) = &mut *guard;
+        let record = JournalRecord { seq: *seq + 1, at: Utc::now(), entry };
+        let written = serde_json::to_string(&record)
+            .map_err(anyhow::Error::from)
+            .and_then(|line| Ok(file.write_all(format!("{}\n", line).as_bytes())?));
+        match written {
+            Ok(()) => *seq += 1,
+            Err(e) => warn!("Failed to append to trade journal {}: {}", self.path.display(), e),
+        }
+    }
+}
+
+/// Every record in the journal at `path`, in order.
+pub fn read(path: &Path) -> Result<Vec<JournalRecord>> {
+    let file = File::open(path).with_context(|| format!("Failed to open trade journal {}", path.display()))?;
+    let mut records = Vec::new();
+    for (n, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(&line)
+            .with_context(|| format!("Bad trade journal line {} in {}", n + 1, path.display()))?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Wraps a venue client and journals every order placed through it, and
+/// the venue's answer.
+pub struct JournaledClient {
+    inner: Arc<dyn ExchangeClient>,
+    journal: Arc<Journal>,
+}
+
+impl JournaledClient {
+    pub fn new(inner: Arc<dyn ExchangeClient>, journal: Arc<Journal>) -> Self {
+        Self { inner, journal }
+    }
+
+    fn record_response(&self, event_id: &str, side: &str, outcome: &str, result: &Result<Option<String>>) {
+        self.journal.record(JournalEntry::OrderResponse {
+            platform: self.inner.platform().to_string(),
+            event_id: event_id.to_string(),
+            side: side.to_string(),
+            outcome: outcome.to_string(),
+            order_id: result.as_ref().ok().cloned().flatten(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+        });
+    }
+}
+
+#[async_trait]
+impl ExchangeClient for JournaledClient {
+    fn platform(&self) -> &'static str {
+        self.inner.platform()
+    }
+
+    async fn fetch_events(&self) -> Result<Vec<Event>> {
+        self.inner.fetch_events().await
+    }
+
+    async fn fetch_markets(&self) -> Result<Vec<Event>> {
+        self.inner.fetch_markets().await
+    }
+
+    async fn fetch_prices(&self, event_id: &str) -> Result<MarketPrices> {
+        self.inner.fetch_prices(event_id).await
+    }
+
+    async fn place_order(
+        &self,
+        event_id: String,
+        outcome: String,
+        amount: f64,
+        price: f64,
+        tif: TimeInForce,
+    ) -> Result<Option<String>> {
+        self.journal.record(JournalEntry::OrderSubmitted {
+            platform: self.inner.platform().to_string(),
+            event_id: event_id.clone(),
+            side: "buy".to_string(),
+            outcome: outcome.clone(),
+            amount,
+            price,
+            time_in_force: Some(tif.to_string()),
+        });
+        let result = self.inner.place_order(event_id.clone(), outcome.clone(), amount, price, tif).await;
+        self.record_response(&event_id, "buy", &outcome, &result);
+        result
+    }
+
+    async fn sell_order(
+        &self,
+        event_id: String,
+        outcome: String,
+        shares: f64,
+        min_price: f64,
+    ) -> Result<Option<String>> {
+        self.journal.record(JournalEntry::OrderSubmitted {
+            platform: self.inner.platform().to_string(),
+            event_id: event_id.clone(),
+            side: "sell".to_string(),
+            outcome: outcome.clone(),
+            amount: shares,
+            price: min_price,
+            time_in_force: None,
+        });
+        let result = self.inner.sell_order(event_id.clone(), outcome.clone(), shares, min_price).await;
+        self.record_response(&event_id, "sell", &outcome, &result);
+        result
+    }
+
+    async fn order_fill(&self, event_id: &str, order_id: &str) -> Result<OrderFill> {
+        self.inner.order_fill(event_id, order_id).await
+    }
+
+    async fn poll_order_fill(
+        &self,
+        event_id: &str,
+        order_id: &str,
+        deadline: tokio::time::Instant,
+    ) -> Result<OrderFill> {
+        self.inner.poll_order_fill(event_id, order_id, deadline).await
+    }
+
+    async fn check_settlement(&self, event_id: &str) -> Result<Option<Resolution>> {
+        self.inner.check_settlement(event_id).await
+    }
+
+    async fn fetch_settlement_records(&self, since: DateTime<Utc>) -> Result<Vec<SettlementRecord>> {
+        self.inner.fetch_settlement_records(since).await
+    }
+
+    async fn fetch_fills(&self, market: &str) -> Result<Vec<Fill>> {
+        self.inner.fetch_fills(market).await
+    }
+
+    async fn get_balance(&self) -> Result<f64> {
+        self.inner.get_balance().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequence_numbers_resume_after_a_restart() {
+        let path = std::env::temp_dir().join(format!("journal-{}.jsonl", uuid::Uuid::new_v4()));
+        let skipped = |title: &str| JournalEntry::OpportunitySkipped {
+            strategy: "gabagool".to_string(),
+            title: title.to_string(),
+            reason: SkipReason::OverBudget,
+            detail: "needs $100.00, $50.00 of its budget left".to_string(),
+        };
+        Journal::open(&path).unwrap().record(skipped("first"));
+        let journal = Journal::open(&path).unwrap();
+        journal.record(skipped("second"));
+        journal.record(skipped("third"));
+
+        let records = read(&path).unwrap();
+        assert_eq!(records.iter().map(|r| r.seq).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(records[1].entry, skipped("second"));
+        assert!(std::fs::read_to_string(&path).unwrap().contains(r#""kind":"opportunity_skipped""#));
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,109 @@
+//! Kalshi request timestamps, kept in step with the exchange's clock.
+//!
+//! Every Kalshi request is signed over a millisecond timestamp the exchange
+//! checks against its own clock, so a host whose clock has drifted has every
+//! call rejected. The offset to the server's clock is estimated from the
+//! `Date` header of `/exchange/status` and added to every timestamp. A request
+//! rejected as unauthorized resyncs the offset (at most once a minute) and is
+//! retried once; `KALSHI_TIME_SYNC_SECS` (unset or 0 turns it off) also syncs
+//! at startup and on that interval. The offset is exported as
+//! `kalshi_clock_offset_ms`.
+
+use crate::clients::KalshiClient;
+use chrono::{DateTime, Utc};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// A rejected request doesn't resync more often than this.
+pub const RESYNC_COOLDOWN: Duration = Duration::from_secs(60);
+/// Offsets beyond this are worth a warning: the host clock needs fixing.
+const DRIFT_WARN_MS: i64 = 1000;
+
+/// Offset from the local clock to Kalshi's.
+#[derive(Debug, Default)]
+pub struct KalshiClock {
+    offset_ms: AtomicI64,
+    synced_at: Mutex<Option<Instant>>,
+}
+
+impl KalshiClock {
+    pub fn offset_ms(&self) -> i64 {
+        self.offset_ms.load(Ordering::Relaxed)
+    }
+
+    /// Milliseconds since the epoch on Kalshi's clock.
+    pub fn now_ms(&self) -> i64 {
+        Utc::now().timestamp_millis() + self.offset_ms()
+    }
+
+    pub fn set_offset(&self, offset_ms: i64) {
+        self.offset_ms.store(offset_ms, Ordering::Relaxed);
+        *self.synced_at.lock().unwrap() = Some(Instant::now());
+        if offset_ms.abs() > DRIFT_WARN_MS {
+            warn!("🕰️ Host clock is {}ms off Kalshi's - request timestamps corrected", -offset_ms);
+        }
+    }
+
+    /// Whether the offset was synced within `window`.
+    pub fn synced_within(&self, window: Duration) -> bool {
+        self.synced_at.lock().unwrap().is_some_and(|at| at.elapsed() < window)
+    }
+}
+
+/// Offset (ms) from the local clock to the server's, from an HTTP `Date`
+/// header on a response to a request sent at `sent` and received at
+/// `received`. The header is truncated to the second, so it's taken as the
+/// middle of that second, against the middle of the round trip.
+pub fn offset_from_date(date: &str, sent: DateTime<Utc>, received: DateTime<Utc>) -> Option<i64> {
+    let server = DateTime::parse_from_rfc2822(date).ok()?.timestamp_millis() + 500;
+    let local = sent.timestamp_millis() + (received - sent).num_milliseconds() / 2;
+    Some(server - local)
+}
+
+/// `KALSHI_TIME_SYNC_SECS`; `None` when it's unset or 0.
+pub fn sync_interval_from_env() -> Option<Duration> {
+    std::env::var("KALSHI_TIME_SYNC_SECS")
+        .ok()
+        .and_then(|s| s.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+}
+
+/// Syncs `client`'s clock now and every `interval`.
+pub fn spawn_sync(client: KalshiClient, interval: Duration) -> JoinHandle<()> {
+    info!("🕰️ Syncing Kalshi request timestamps with the server every {}s", interval.as_secs());
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = client.sync_server_time().await {
+                warn!("Kalshi server time sync failed: {}", e);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn offset_is_measured_against_the_middle_of_the_round_trip() {
+        let sent = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let received = sent + chrono::Duration::milliseconds(200);
+        // The server said 12:00:03, i.e. ~12:00:03.5; we were at 12:00:00.1.
+        let offset = offset_from_date("Wed, 01 Jan 2025 12:00:03 GMT", sent, received);
+        assert_eq!(offset, Some(3400));
+        assert_eq!(offset_from_date("not a date", sent, received), None);
+
+        let clock = KalshiClock::default();
+        assert!(!clock.synced_within(RESYNC_COOLDOWN));
+        clock.set_offset(3400);
+        assert!(clock.synced_within(RESYNC_COOLDOWN));
+        assert!((clock.now_ms() - Utc::now().timestamp_millis() - 3400).abs() < 50);
+    }
+}
@@ -0,0 +1,354 @@
+//! Several Kalshi API credentials on one client.
+//!
+//! `KALSHI_CREDENTIALS=scan1:read,scan2:read,acct2:trade` adds named
+//! credentials next to the primary `KALSHI_API_ID` one (named `primary`, role
+//! `both` unless listed, e.g. `primary:trade`). Each reads
+//! `KALSHI_API_ID_<NAME>` and `KALSHI_RSA_PRIVATE_KEY_<NAME>` (or
+//! `KALSHI_PRIVATE_KEY_PATH_<NAME>`). Market data requests rotate round robin
+//! over the `read` / `both` credentials, spreading the rate limit.
+//!
+//! Every `trade` / `both` credential is an account of its own. Orders in one
+//! event always go through the same account (picked from the event ticker, so
+//! an exit sells where the entry bought), and account-wide reads - positions,
+//! balance, fills, settlements, resting orders - are merged across them.
+//! Changing the trade accounts while positions are open moves events to other
+//! accounts. Signed requests are counted per credential in
+//! `kalshi_credential_requests_total{credential,role}`.
+
+use crate::config::normalize_pem;
+use crate::metrics::metrics;
+use anyhow::{Context, Result};
+use rsa::RsaPrivateKey;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tracing::warn;
+
+pub const PRIMARY: &str = "primary";
+
+/// How requests are signed: `KALSHI_SIGNING_SCHEME=pss` (the default, what
+/// Kalshi documents) or `pkcs1v15`, both over SHA-256.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SigningScheme {
+    #[default]
+    Pss,
+    Pkcs1v15,
+}
+
+impl SigningScheme {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().replace(['-', '_'], "").as_str() {
+            "pss" | "rsapss" => Some(Self::Pss),
+            "pkcs1v15" | "pkcs1" => Some(Self::Pkcs1v15),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pss => "pss",
+            Self::Pkcs1v15 => "pkcs1v15",
+        }
+    }
+
+    /// The scheme this one isn't.
+    pub fn other(&self) -> Self {
+        match self {
+            Self::Pss => Self::Pkcs1v15,
+            Self::Pkcs1v15 => Self::Pss,
+        }
+    }
+}
+
+/// Parses a PKCS#8 or PKCS#1 PEM RSA key.
+pub fn parse_key(rsa_private_key: &str) -> Result<RsaPrivateKey> {
+    use rsa::pkcs1::DecodeRsaPrivateKey;
+    use rsa::pkcs8::DecodePrivateKey;
+
+    RsaPrivateKey::from_pkcs8_pem(rsa_private_key)
+        .or_else(|_| RsaPrivateKey::from_pkcs1_pem(rsa_private_key))
+        .context("Failed to parse RSA private key. Expected PEM format (PKCS1 or PKCS8)")
+}
+
+/// What Kalshi expects signed: the millisecond timestamp, the upper-case
+/// method and the request's full path from `/trade-api/...`, without its
+/// query string, concatenated.
+pub fn signing_message(timestamp_ms: i64, method: &str, path: &str) -> String {
+    let path = path.split('?').next().unwrap_or(path);
+    format!("{}{}{}", timestamp_ms, method.to_ascii_uppercase(), path)
+}
+
+/// Base64 signature of `message`.
+pub fn sign(key: &RsaPrivateKey, scheme: SigningScheme, message: &[u8]) -> String {
+    use base64::{engine::general_purpose, Engine as _};
+    use rsa::signature::{RandomizedSigner, SignatureEncoding, Signer};
+    use sha2::Sha256;
+
+    let signature = match scheme {
+        SigningScheme::Pss => rsa::pss::BlindedSigningKey::<Sha256>::new(key.clone())
+            .sign_with_rng(&mut rsa::rand_core::OsRng, message)
+            .to_vec(),
+        SigningScheme::Pkcs1v15 => rsa::pkcs1v15::SigningKey::<Sha256>::new(key.clone()).sign(message).to_vec(),
+    };
+    general_purpose::STANDARD.encode(signature)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialRole {
+    /// Market data only.
+    Read,
+    /// Orders and the account's portfolio only.
+    Trade,
+    Both,
+}
+
+impl CredentialRole {
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "read" => Some(Self::Read),
+            "trade" => Some(Self::Trade),
+            "both" => Some(Self::Both),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Trade => "trade",
+            Self::Both => "both",
+        }
+    }
+
+    fn reads(&self) -> bool {
+        *self != Self::Trade
+    }
+
+    fn trades(&self) -> bool {
+        *self != Self::Read
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct KalshiCredential {
+    pub name: String,
+    pub api_id: String,
+    pub rsa_private_key: String,
+    pub role: CredentialRole,
+    /// `rsa_private_key` parsed once; `None` when it isn't a valid key.
+    signing_key: Option<Arc<RsaPrivateKey>>,
+}
+
+impl KalshiCredential {
+    /// Parses the key up front; a key that doesn't parse is warned about
+    /// here and its requests go out unsigned (and are rejected).
+    pub fn new(name: &str, api_id: &str, rsa_private_key: String, role: CredentialRole) -> Self {
+        let signing_key = match parse_key(&rsa_private_key) {
+            Ok(key) => Some(Arc::new(key)),
+            Err(e) => {
+                warn!("Kalshi credential {}: {:#}. Authentication will fail.", name, e);
+                None
+            }
+        };
+        Self { name: name.to_string(), api_id: api_id.to_string(), rsa_private_key, role, signing_key }
+    }
+
+    /// Base64 signature of `message`, or `None` without a valid key.
+    pub fn sign(&self, scheme: SigningScheme, message: &[u8]) -> Option<String> {
+        self.signing_key.as_deref().map(|key| sign(key, scheme, message))
+    }
+
+    /// Counts a request signed with this credential.
+    pub fn record_use(&self) {
+        metrics().inc_counter(
+            "kalshi_credential_requests_total",
+            &[("credential", &self.name), ("role", self.role.as_str())],
+        );
+    }
+}
+
+pub struct KalshiCredentials {
+    all: Vec<KalshiCredential>,
+    next_read: AtomicUsize,
+}
+
+/// The event ticker of a market ticker (`EVENT-SUFFIX`); event tickers
+/// themselves (one dash at most) are returned as they are.
+pub fn event_ticker(ticker: &str) -> &str {
+    match ticker.rsplit_once('-') {
+        Some((event, _)) if event.contains('-') => event,
+        _ => ticker,
+    }
+}
+
+impl KalshiCredentials {
+    /// Needs at least one credential able to read and one able to trade.
+    pub fn new(all: Vec<KalshiCredential>) -> Result<Self> {
+        if !all.iter().any(|c| c.role.reads()) {
+            anyhow::bail!("No Kalshi credential can read market data (role read or both)");
+        }
+        if !all.iter().any(|c| c.role.trades()) {
+            anyhow::bail!("No Kalshi credential can trade (role trade or both)");
+        }
+        Ok(Self { all, next_read: AtomicUsize::new(0) })
+    }
+
+    pub fn single(api_id: String, rsa_private_key: String) -> Self {
+        Self {
+            all: vec![KalshiCredential::new(PRIMARY, &api_id, rsa_private_key, CredentialRole::Both)],
+            next_read: AtomicUsize::new(0),
+        }
+    }
+
+    /// The primary credential plus those in `KALSHI_CREDENTIALS`, read
+    /// through `var`; `None` when it's unset.
+    pub fn from_vars(
+        api_id: &str,
+        rsa_private_key: &str,
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<Option<Self>> {
+        let var = |name: &str| var(name).filter(|v| !v.trim().is_empty());
+        let Some(spec) = var("KALSHI_CREDENTIALS") else {
+            return Ok(None);
+        };
+        let mut primary_role = CredentialRole::Both;
+        let mut extra = Vec::new();
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, role) = match entry.split_once(':') {
+                Some((name, role)) => (
+                    name.trim(),
+                    CredentialRole::parse(role).with_context(|| {
+                        format!("KALSHI_CREDENTIALS: {} - role must be read, trade or both", entry)
+                    })?,
+                ),
+                None => (entry, CredentialRole::Both),
+            };
+            if name.eq_ignore_ascii_case(PRIMARY) {
+                primary_role = role;
+                continue;
+            }
+            let suffix = name.to_ascii_uppercase();
+            let api_id = var(&format!("KALSHI_API_ID_{}", suffix))
+                .with_context(|| format!("KALSHI_CREDENTIALS: {} needs KALSHI_API_ID_{}", name, suffix))?;
+            let pem = match var(&format!("KALSHI_PRIVATE_KEY_PATH_{}", suffix)) {
+                Some(path) => std::fs::read_to_string(path.trim())
+                    .with_context(|| format!("Failed to read KALSHI_PRIVATE_KEY_PATH_{}", suffix))?,
+                None => var(&format!("KALSHI_RSA_PRIVATE_KEY_{}", suffix)).with_context(|| {
+                    format!("KALSHI_CREDENTIALS: {} needs KALSHI_RSA_PRIVATE_KEY_{}", name, suffix)
+                })?,
+            };
+            extra.push(KalshiCredential::new(name, api_id.trim(), normalize_pem(&pem), role));
+        }
+        let mut all = Vec::new();
+        if !api_id.is_empty() {
+            all.push(KalshiCredential::new(PRIMARY, api_id, rsa_private_key.to_string(), primary_role));
+        }
+        all.extend(extra);
+        Self::new(all).map(Some)
+    }
+
+    pub fn from_env(api_id: &str, rsa_private_key: &str) -> Result<Option<Self>> {
+        Self::from_vars(api_id, rsa_private_key, |name| std::env::var(name).ok())
+    }
+
+    pub fn len(&self) -> usize {
+        self.all.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.all.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &KalshiCredential> {
+        self.all.iter()
+    }
+
+    /// The next credential for market data, round robin.
+    pub fn for_read(&self) -> &KalshiCredential {
+        let readers: Vec<&KalshiCredential> = self.all.iter().filter(|c| c.role.reads()).collect();
+        readers[self.next_read.fetch_add(1, Ordering::Relaxed) % readers.len()]
+    }
+
+    /// Every account that trades.
+    pub fn trade_accounts(&self) -> impl Iterator<Item = &KalshiCredential> {
+        self.all.iter().filter(|c| c.role.trades())
+    }
+
+    /// The account that trades `ticker`'s event (an event or market ticker).
+    pub fn for_market(&self, ticker: &str) -> &KalshiCredential {
+        let accounts: Vec<&KalshiCredential> = self.trade_accounts().collect();
+        let hash = event_ticker(ticker)
+            .bytes()
+            .fold(0u64, |h, b| h.wrapping_mul(31).wrapping_add(b as u64));
+        accounts[(hash % accounts.len() as u64) as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn splits_read_and_trade_traffic() {
+        let env = HashMap::from([
+            ("KALSHI_CREDENTIALS", "primary:trade, scan:read, second:trade"),
+            ("KALSHI_API_ID_SCAN", "scan-id"),
+            ("KALSHI_RSA_PRIVATE_KEY_SCAN", "scan-pem"),
+            ("KALSHI_API_ID_SECOND", "second-id"),
+            ("KALSHI_RSA_PRIVATE_KEY_SECOND", "second-pem"),
+        ]);
+        let from = |env: &HashMap<&str, &str>| {
+            KalshiCredentials::from_vars("main-id", "main-pem", |name| env.get(name).map(|v| v.to_string()))
+        };
+        let pool = from(&env).unwrap().unwrap();
+        assert_eq!(pool.len(), 3);
+        assert_eq!(pool.for_read().name, "scan");
+        assert_eq!(pool.for_read().name, "scan");
+        assert_eq!(pool.trade_accounts().count(), 2);
+
+        // A market's exits go through the account its event's entries used.
+        assert_eq!(event_ticker("KXBTC15M-25OCT161500-00"), "KXBTC15M-25OCT161500");
+        assert_eq!(event_ticker("KXBTC15M-25OCT161500"), "KXBTC15M-25OCT161500");
+        assert_eq!(
+            pool.for_market("KXBTC15M-25OCT161500").name,
+            pool.for_market("KXBTC15M-25OCT161500-00").name
+        );
+
+        assert!(from(&HashMap::from([("KALSHI_CREDENTIALS", "other:read")])).is_err());
+        assert!(from(&HashMap::from([("KALSHI_CREDENTIALS", "primary:read")])).is_err());
+        assert!(from(&HashMap::new()).unwrap().is_none());
+    }
+
+    #[test]
+    fn signs_with_the_configured_scheme() {
+        use base64::{engine::general_purpose, Engine as _};
+        use rsa::pkcs8::{EncodePrivateKey, LineEnding};
+        use rsa::signature::Verifier;
+        use sha2::Sha256;
+
+        let key = rsa::RsaPrivateKey::new(&mut rsa::rand_core::OsRng, 1024).unwrap();
+        let pem = key.to_pkcs8_pem(LineEnding::LF).unwrap();
+        let public = key.to_public_key();
+        let signed = signing_message(1700000000000, "get", "/trade-api/v2/portfolio/balance?limit=1");
+        assert_eq!(signed, "1700000000000GET/trade-api/v2/portfolio/balance");
+        let message = signed.as_bytes();
+        let credential = KalshiCredential::new("primary", "id", pem.to_string(), CredentialRole::Both);
+        let decode = |scheme| general_purpose::STANDARD.decode(credential.sign(scheme, message).unwrap()).unwrap();
+
+        let pss = rsa::pss::Signature::try_from(decode(SigningScheme::Pss).as_slice()).unwrap();
+        assert!(rsa::pss::VerifyingKey::<Sha256>::new(public.clone()).verify(message, &pss).is_ok());
+        let pkcs1 = rsa::pkcs1v15::Signature::try_from(decode(SigningScheme::Pkcs1v15).as_slice()).unwrap();
+        let pkcs1_key = rsa::pkcs1v15::VerifyingKey::<Sha256>::new(public);
+        assert!(pkcs1_key.verify(message, &pkcs1).is_ok());
+        // A PSS signature doesn't pass as PKCS#1 v1.5.
+        let as_pkcs1 = rsa::pkcs1v15::Signature::try_from(decode(SigningScheme::Pss).as_slice()).unwrap();
+        assert!(pkcs1_key.verify(message, &as_pkcs1).is_err());
+
+        assert_eq!(SigningScheme::parse("RSA-PSS"), Some(SigningScheme::Pss));
+        assert_eq!(SigningScheme::parse("pkcs1v15"), Some(SigningScheme::Pkcs1v15));
+        assert_eq!(SigningScheme::parse("ecdsa"), None);
+        assert!(parse_key("not a key").is_err());
+        let unparsed = KalshiCredential::new("bad", "id", "not a key".to_string(), CredentialRole::Both);
+        assert!(unparsed.sign(SigningScheme::Pss, message).is_none());
+    }
+}
@@ -0,0 +1,148 @@
+//! Detection-to-fill latency and the edge it costs.
+//!
+//! The executor times every cross-platform leg from the moment its
+//! opportunity was detected to its confirmed fill (not the order
+//! acknowledgement), per venue and per UTC hour,
+//! and exports it as `detection_to_fill_seconds{venue}`. An edge decays
+//! while the order is on its way, so with `EDGE_DECAY_PER_SEC` set (dollars
+//! per share lost per second) the bot requires that much more than the
+//! minimum profit for every second of the slower venue's latency at
+//! `EDGE_DECAY_PERCENTILE` (default 90). The hour's samples are used once
+//! there are `EDGE_DECAY_MIN_SAMPLES` (default 20) of them, else the venue's;
+//! until then no extra is required. `EDGE_DECAY_MAX` (default 0.05) caps it.
+
+use crate::metrics::metrics;
+use chrono::{DateTime, Timelike, Utc};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// Samples kept per venue and hour.
+const MAX_SAMPLES: usize = 500;
+
+/// Recent detection-to-fill latencies (seconds) by venue and UTC hour.
+#[derive(Default)]
+pub struct ExecutionLatency {
+    samples: Mutex<HashMap<(String, u32), VecDeque<f64>>>,
+}
+
+impl ExecutionLatency {
+    /// Records a fill on `platform` at `filled_at` of an opportunity detected
+    /// at `detected_at`.
+    pub fn record(&self, platform: &str, detected_at: DateTime<Utc>, filled_at: DateTime<Utc>) {
+        let seconds = ((filled_at - detected_at).num_milliseconds() as f64 / 1000.0).max(0.0);
+        metrics().observe("detection_to_fill_seconds", &[("venue", platform)], seconds);
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        let hour = samples.entry((platform.to_string(), filled_at.hour())).or_default();
+        if hour.len() == MAX_SAMPLES {
+            hour.pop_front();
+        }
+        hour.push_back(seconds);
+    }
+
+    /// The `percentile` latency on `platform` during `hour`, or over every
+    /// hour when `hour` has under `min_samples`; `None` when neither has.
+    pub fn percentile(&self, platform: &str, hour: u32, percentile: f64, min_samples: usize) -> Option<f64> {
+        let samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        let mut values: Vec<f64> = samples
+            .get(&(platform.to_string(), hour))
+            .filter(|s| s.len() >= min_samples.max(1))
+            .map(|s| s.iter().copied().collect())
+            .unwrap_or_else(|| {
+                samples
+                    .iter()
+                    .filter(|((venue, _), _)| venue == platform)
+                    .flat_map(|(_, s)| s.iter().copied())
+                    .collect()
+            });
+        if values.len() < min_samples.max(1) {
+            return None;
+        }
+        values.sort_by(f64::total_cmp);
+        let rank = ((percentile / 100.0) * values.len() as f64).ceil() as usize;
+        Some(values[rank.clamp(1, values.len()) - 1])
+    }
+}
+
+/// Extra minimum profit for the edge lost to latency.
+#[derive(Clone)]
+pub struct EdgeDecay {
+    latency: Arc<ExecutionLatency>,
+    /// Dollars per share of edge lost per second.
+    pub per_second: f64,
+    pub percentile: f64,
+    pub min_samples: usize,
+    pub max_extra: f64,
+}
+
+impl EdgeDecay {
+    pub fn new(latency: Arc<ExecutionLatency>, per_second: f64) -> Self {
+        Self {
+            latency,
+            per_second,
+            percentile: 90.0,
+            min_samples: 20,
+            max_extra: 0.05,
+        }
+    }
+
+    /// `None` unless `EDGE_DECAY_PER_SEC` is set.
+    pub fn from_env(latency: Arc<ExecutionLatency>) -> Option<Self> {
+        let var = |name: &str| std::env::var(name).ok().and_then(|s| s.trim().parse::<f64>().ok());
+        let per_second = var("EDGE_DECAY_PER_SEC").filter(|v| *v > 0.0)?;
+        let mut decay = Self::new(latency, per_second);
+        if let Some(percentile) = var("EDGE_DECAY_PERCENTILE").filter(|p| *p > 0.0 && *p <= 100.0) {
+            decay.percentile = percentile;
+        }
+        if let Some(min) = var("EDGE_DECAY_MIN_SAMPLES").filter(|m| *m >= 1.0) {
+            decay.min_samples = min as usize;
+        }
+        if let Some(max) = var("EDGE_DECAY_MAX").filter(|m| *m >= 0.0) {
+            decay.max_extra = max;
+        }
+        Some(decay)
+    }
+
+    /// Dollars per share over the minimum profit a trade on `platforms` at
+    /// `at` must clear: the legs go out together, so the slowest one counts.
+    pub fn extra_profit(&self, platforms: &[&str], at: DateTime<Utc>) -> f64 {
+        platforms
+            .iter()
+            .filter_map(|platform| {
+                self.latency
+                    .percentile(platform, at.hour(), self.percentile, self.min_samples)
+            })
+            .map(|seconds| seconds * self.per_second)
+            .fold(0.0, f64::max)
+            .min(self.max_extra)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn slower_venues_and_hours_need_more_edge() {
+        let latency = Arc::new(ExecutionLatency::default());
+        let night = Utc.with_ymd_and_hms(2026, 1, 5, 3, 0, 0).unwrap();
+        let open = Utc.with_ymd_and_hms(2026, 1, 5, 14, 0, 0).unwrap();
+        for ms in [200, 300, 400, 500] {
+            latency.record("polymarket", night, night + Duration::milliseconds(ms));
+            latency.record("kalshi", night, night + Duration::milliseconds(ms * 2));
+        }
+        for secs in [2, 3, 4, 5] {
+            latency.record("kalshi", open, open + Duration::seconds(secs));
+        }
+        let decay = EdgeDecay { min_samples: 4, ..EdgeDecay::new(latency.clone(), 0.01) };
+
+        assert_eq!(latency.percentile("kalshi", 3, 50.0, 4), Some(0.6));
+        // Kalshi at 03:00 p90 is 1s; at 14:00, 5s.
+        assert!((decay.extra_profit(&["polymarket", "kalshi"], night) - 0.01).abs() < 1e-9);
+        assert!((decay.extra_profit(&["polymarket", "kalshi"], open) - 0.05).abs() < 1e-9);
+        // Polymarket has no 14:00 samples: its overall p90 counts.
+        assert!((decay.extra_profit(&["polymarket"], open) - 0.005).abs() < 1e-9);
+        let capped = EdgeDecay { max_extra: 0.02, ..decay };
+        assert_eq!(capped.extra_profit(&["kalshi"], open), 0.02);
+    }
+}
@@ -1,26 +1,91 @@
+pub mod adopt;
+pub mod analytics;
+pub mod balance_audit;
+pub mod balance_floor;
+pub mod basket_detector;
+pub mod book_feed;
+pub mod confidence;
 pub mod config;
+pub mod control;
+pub mod dashboard;
+pub mod debug_capture;
+pub mod depth;
+pub mod doctor;
+pub mod early_exit;
+pub mod embeddings;
 pub mod event;
 pub mod event_matcher;
+pub mod exchange;
+pub mod fair_value;
+pub mod flatten;
+pub mod gamma_sync;
+pub mod gas;
+pub mod health;
+pub mod journal;
+pub mod kalshi_clock;
+pub mod kalshi_credentials;
+pub mod latency;
+pub mod loss_limit;
+pub mod mappings;
+pub mod match_store;
+pub mod metrics;
 pub mod monitor_logger;
+pub mod money;
+pub mod notify;
+pub mod oracle;
+pub mod order_lanes;
+pub mod rebalance;
+pub mod recorder;
+pub mod replay;
+pub mod resolution_rules;
+pub mod restart;
 pub mod arbitrage_detector;
 pub mod bot;
 pub mod clients;
 pub mod trade_executor;
+pub mod position_monitor;
 pub mod position_tracker;
+pub mod preflight;
+pub mod profit_target;
+pub mod quote_detector;
+pub mod rate_limit;
+pub mod rpc_failover;
 pub mod settlement_checker;
+pub mod shadow;
+pub mod signer;
+pub mod simulate;
+pub mod skip_reason;
+pub mod slicing;
+pub mod spot_feed;
+pub mod spread;
+pub mod store_writer;
+pub mod strategy;
+pub mod strike;
+pub mod strike_detector;
+pub mod tax_report;
+pub mod telemetry;
+pub mod tick;
+pub mod tui;
+pub mod uma_monitor;
+pub mod watchlist;
 pub mod polymarket_blockchain;
 pub mod polymarket_clob;
 pub mod gabagool_detector;
 pub mod gabagool_executor;
+pub mod mock;
 pub use event::{Event, MarketPrices};
 pub use event_matcher::EventMatcher;
 pub use arbitrage_detector::{ArbitrageDetector, ArbitrageOpportunity};
 pub use bot::{ShortTermArbitrageBot, MarketFilters};
 pub use clients::{PolymarketClient, KalshiClient};
-pub use trade_executor::{TradeExecutor, TradeResult};
+pub use exchange::ExchangeClient;
+pub use mock::{MockExchange, MockKalshiClient, MockPolymarketClient};
+pub use trade_executor::{LegFailurePolicy, LegSequence, ResidualExposure, TradeExecutor, TradeResult};
 pub use position_tracker::{PositionTracker, Position, PositionStatus, PositionStatistics};
 pub use settlement_checker::SettlementChecker;
 pub use gabagool_detector::{GabagoolDetector, GabagoolOpportunity};
 pub use gabagool_executor::{GabagoolExecutor, GabagoolStatistics};
+pub use recorder::MarketRecorder;
+pub use simulate::{simulate, ParamsGrid, SimParams, SimResult};
 pub use monitor_logger::{append_monitor_log, append_monitor_log_with_timestamp, time_bucket_15m};
 
@@ -0,0 +1,107 @@
+//! Daily loss limit with an automatic cool-off.
+//!
+//! `DAILY_LOSS_LIMIT=100` (dollars): once the profit realized by positions
+//! settled or closed since 00:00 UTC is a loss of at least that much, the
+//! day is marked in `Controls` and no strategy opens positions until the UTC
+//! rollover, while settlement and exits carry on. `POST /loss-limit` on the
+//! control API (`{"override": true}`) lets trading carry on for the rest of
+//! the day anyway; `false` takes that back.
+
+use crate::control::Controls;
+use crate::notify::BotEvent;
+use crate::position_tracker::PositionTracker;
+use crate::profit_target::{realized_since, TargetPeriod};
+use chrono::{DateTime, Utc};
+use tracing::{info, warn};
+
+pub struct DailyLossLimit {
+    limit: f64,
+}
+
+impl DailyLossLimit {
+    pub fn new(limit: f64) -> Self {
+        Self { limit }
+    }
+
+    pub fn from_env() -> Option<Self> {
+        std::env::var("DAILY_LOSS_LIMIT")
+            .ok()
+            .and_then(|s| s.trim().parse::<f64>().ok())
+            .filter(|limit| *limit > 0.0)
+            .map(Self::new)
+    }
+
+    pub fn limit(&self) -> f64 {
+        self.limit
+    }
+
+    /// Marks today in `controls` once its realized loss reaches the limit,
+    /// and clears an earlier day's mark. Returns the alert when today is
+    /// first marked.
+    pub fn check(&self, tracker: &PositionTracker, controls: &Controls, now: DateTime<Utc>) -> Option<BotEvent> {
+        let today = now.date_naive();
+        match controls.loss_limit_hit() {
+            Some(day) if day == today => return None,
+            Some(_) => {
+                controls.set_loss_limit_hit(None);
+                info!("🧊 New UTC day - daily loss limit reset, entries resumed");
+            }
+            None => {}
+        }
+        let realized = realized_since(tracker, TargetPeriod::Daily.start_of(now));
+        if realized > -self.limit {
+            return None;
+        }
+        controls.set_loss_limit_hit(Some(today));
+        warn!(
+            "🧊 Daily loss limit hit: ${:.2} realized today against a ${:.2} limit - no new entries until {}",
+            realized,
+            self.limit,
+            TargetPeriod::Daily.end_of(now).to_rfc3339()
+        );
+        Some(BotEvent::DailyLossLimit { realized, limit: self.limit })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Event;
+    use crate::money::{Price, UsdAmount};
+    use crate::position_tracker::Position;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn stops_entries_for_the_rest_of_the_day() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 15, 0, 0).unwrap();
+        let mut tracker = PositionTracker::new();
+        let event = Event::new("kalshi".into(), "KXBTC".into(), "BTC".into(), String::new());
+        for (hours_ago, profit) in [(20, -80.0), (3, -70.0), (1, -40.0)] {
+            let mut position = Position::new("kalshi".into(), &event, "YES".into(), 100.0, UsdAmount::from_f64(50.0), Price::from_cents(50), None);
+            position.settled_at = Some(now - Duration::hours(hours_ago));
+            position.profit = Some(UsdAmount::from_f64(profit));
+            tracker.add_position(position);
+        }
+        let controls = Controls::new();
+        let today = now.date_naive();
+
+        // Yesterday's loss doesn't count: $110 today reaches the $100 limit.
+        assert!(DailyLossLimit::new(150.0).check(&tracker, &controls, now).is_none());
+        let limit = DailyLossLimit::new(100.0);
+        assert!(matches!(
+            limit.check(&tracker, &controls, now),
+            Some(BotEvent::DailyLossLimit { realized, .. }) if realized == -110.0
+        ));
+        assert!(controls.loss_limited(today));
+        assert!(limit.check(&tracker, &controls, now).is_none());
+
+        controls.override_loss_limit(today, true);
+        assert!(!controls.loss_limited(today));
+
+        // The next day starts fresh.
+        let tomorrow = now + Duration::hours(12);
+        assert!(limit.check(&tracker, &controls, tomorrow).is_none());
+        assert_eq!(controls.loss_limit_hit(), None);
+        assert!(!controls.loss_limited(tomorrow.date_naive()));
+    }
+}
@@ -1,46 +1,167 @@
 use anyhow::Result;
 use polymarket_kalshi_arbitrage_bot::{
-    config::KalshiConfig,
-    bot::{MarketFilters, ShortTermArbitrageBot},
-    clients::{KalshiClient, PolymarketClient},
-    event::MarketPrices,
-    gabagool_executor::GabagoolExecutor,
-    position_tracker::PositionTracker,
+    adopt,
+    balance_audit::BalanceAuditor,
+    balance_floor::BalanceFloors,
+    basket_detector::BasketDetector,
+    book_feed::BookFeed,
+    config::{BotConfig, KalshiConfig},
+    control::{ControlApi, Controls},
+    dashboard::Dashboard,
+    debug_capture::DebugCapture,
+    doctor,
+    early_exit::EarlyExit,
+    embeddings::EmbeddingClient,
+    exchange::ExchangeClient,
+    fair_value::FairValueEngine,
+    flatten::{self, DEFAULT_FLATTEN_TOLERANCE},
+    gamma_sync::GammaEventCache,
+    health::{self, health, Heartbeat},
+    journal::{Journal, JournaledClient},
+    kalshi_clock,
+    kalshi_credentials::KalshiCredentials,
+    latency::{EdgeDecay, ExecutionLatency},
+    loss_limit::DailyLossLimit,
+    mappings::MarketMappings,
+    match_store::{MatchSource, MatchStore},
+    metrics,
+    notify::{BotEvent, Notifier},
+    oracle::OracleCheck,
+    order_lanes::{LanedClient, OrderLanes},
+    polymarket_blockchain::{self, PolymarketBlockchain},
+    analytics::{self, PerformanceReport},
+    bot::{MarketFilters, ShortTermArbitrageBot, TradeDedup},
+    clients::{self, KalshiClient, PolymarketClient, PriceCacheTtl},
+    gabagool_executor::{GabagoolExecutor, MakerMode, Rebalance, DEFAULT_GABAGOOL_STORE_PATH},
+    position_monitor::{ExitRules, PositionMonitor},
+    position_tracker::{format_breakdown, ExportFormat, PositionTracker, DEFAULT_STORE_PATH},
+    preflight::OrderSizeLimits,
+    profit_target::ProfitTarget,
+    quote_detector::QuoteDetector,
+    rebalance::RebalanceAdvisor,
+    recorder::MarketRecorder,
+    replay::{self, ReplaySource},
+    resolution_rules::RulesCheck,
+    restart::{self, RestartSchedule, RESTART_EXIT_CODE, SHUTDOWN_GRACE},
     settlement_checker::SettlementChecker,
-    trade_executor::TradeExecutor,
+    shadow::ShadowEvaluator,
+    signer,
+    slicing::OrderSlicing,
+    spot_feed::SpotFeed,
+    spread::SpreadLimits,
+    strategy::{
+        self, CapitalAllocation, CrossPlatformStrategy, GabagoolStrategy, MarketSnapshot,
+        NegRiskStrategy, OpportunityRanking, OrderTimeInForce, QuotingStrategy, StrategyIntervals, StrategyRegistry,
+        StrikeStrategy, TradeLimits,
+    },
+    strike_detector::StrikeDetector,
+    tax_report::{self, TaxReport, DEFAULT_REPORT_DIR},
+    telemetry::Telemetry,
+    trade_executor::{LegFailurePolicy, LegSequence, TradeExecutor},
+    tui,
+    uma_monitor::UmaMonitor,
+    watchlist::WatchList,
 };
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::Mutex;
-use tracing::{error, info, warn, Level};
+use tokio::sync::{Mutex, Notify};
+use tracing::{error, info, warn};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
 
 #[tokio::main]
 async fn main() -> Result<()> {
 
-    tracing_subscriber::fmt()
-        .with_max_level(Level::INFO)
+    dotenv::dotenv().ok();
+
+    let args: Vec<String> = std::env::args().collect();
+    let tui_mode = args.iter().any(|a| a == "--tui");
+    let log_layer = if tui_mode {
+        // The terminal belongs to the dashboard; logs go to a file.
+        let path = std::path::PathBuf::from(tui::log_path_from_env());
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = std::fs::OpenOptions::new().create(true).append(true).open(&path)?;
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(std::sync::Mutex::new(file))
+            .boxed()
+    } else {
+        tracing_subscriber::fmt::layer().boxed()
+    };
+    let (_telemetry, otel_layer) = match Telemetry::from_env()? {
+        Some((telemetry, layer)) => (Some(telemetry), Some(layer)),
+        None => (None, None),
+    };
+    tracing_subscriber::registry()
+        .with(LevelFilter::INFO)
+        .with(log_layer)
+        .with(otel_layer)
         .init();
 
     info!("Starting Polymarket-Kalshi Arbitrage Bot");
 
-    dotenv::dotenv().ok();
+    let command = args.get(1).cloned();
+    if command.as_deref() == Some("doctor") {
+        info!("🩺 Checking environment and connectivity");
+        let report = doctor::run().await;
+        report.log();
+        if !report.passed() {
+            return Err(anyhow::anyhow!("doctor: {} checks failed", report.failures()));
+        }
+        return Ok(());
+    }
+    let replay_dir = match command.as_deref() {
+        Some("--replay") => Some(
+            args.get(2)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Usage: --replay <recording dir>"))?,
+        ),
+        _ => None,
+    };
+    if replay_dir.is_some() {
+        // Replays never touch real order endpoints.
+        std::env::set_var("DRY_RUN", "true");
+    }
+
+    let config = BotConfig::from_env();
 
     let polygon_rpc = std::env::var("POLYGON_RPC_URL")
         .unwrap_or_else(|_| "https://polygon-rpc.com".to_string());
     let wallet_key = std::env::var("POLYMARKET_WALLET_PRIVATE_KEY")
         .ok();
     
+    for venue in ["polymarket", "kalshi"] {
+        if let Some((_, proxy)) = clients::venue_proxy(venue)? {
+            info!("🌐 {} requests go through {}", venue, proxy);
+        }
+    }
+    let price_cache_ttl = Duration::from_secs(config.price_cache_ttl_secs);
     let mut polymarket_client = PolymarketClient::new()
-        .with_rpc(polygon_rpc);
+        .with_rpc(polygon_rpc.clone())
+        .with_event_limit(config.event_fetch_limit)
+        .with_max_event_pages(config.gamma_max_event_pages)
+        .with_price_cache_ttls(PriceCacheTtl::from_env("polymarket", price_cache_ttl))
+        .with_price_cache_capacity(config.price_cache_max_entries);
+    if let Some(every) = GammaEventCache::resync_interval_from_env() {
+        info!("🔄 Incremental Gamma sync on (full relisting every {}s)", every.as_secs());
+        polymarket_client = polymarket_client.with_incremental_sync(every);
+    }
     
-    if let Some(key) = wallet_key {
+    if let Some(key) = wallet_key.clone() {
         polymarket_client = polymarket_client.with_wallet(key);
     } else {
         warn!("⚠️ POLYMARKET_WALLET_PRIVATE_KEY not set - trading will fail!");
     }
+    // Signs on-chain approvals and redemptions; a remote signer or Ledger
+    // keeps that key off this host.
+    let chain_signer = signer::from_env(wallet_key.as_deref(), 137).await?;
 
     let kalshi_config = KalshiConfig::from_env();
-    if kalshi_config.api_id.is_empty() || kalshi_config.rsa_private_key.is_empty() {
+    if replay_dir.is_none()
+        && (kalshi_config.api_id.is_empty() || kalshi_config.rsa_private_key.is_empty())
+    {
         error!("❌ Kalshi API credentials missing!");
         error!("   Required: KALSHI_API_ID (or KALSHI_API_KEY)");
         error!("   Required: KALSHI_RSA_PRIVATE_KEY or KALSHI_PRIVATE_KEY_PATH (PEM file)");
@@ -49,31 +170,281 @@ async fn main() -> Result<()> {
     if kalshi_config.dry_run {
         info!("🔒 DRY RUN enabled (DRY_RUN or KALSHI_DRY_RUN=true) — no real orders will be placed");
     }
-    let kalshi_client = KalshiClient::from_config(&kalshi_config);
+    let mut kalshi_client = KalshiClient::from_config(&kalshi_config)
+        .with_event_limit(config.event_fetch_limit)
+        .with_max_event_pages(config.kalshi_max_event_pages)
+        .with_price_cache_ttls(PriceCacheTtl::from_env("kalshi", price_cache_ttl))
+        .with_price_cache_capacity(config.price_cache_max_entries);
+    if let Some(credentials) =
+        KalshiCredentials::from_env(&kalshi_config.api_id, &kalshi_config.rsa_private_key)?
+    {
+        info!(
+            "🔑 {} Kalshi credentials ({} trade accounts)",
+            credentials.len(),
+            credentials.trade_accounts().count()
+        );
+        kalshi_client = kalshi_client.with_credentials(credentials);
+    }
 
     let polymarket_client = Arc::new(polymarket_client);
     let kalshi_client = Arc::new(kalshi_client);
+    if let (Some(every), None) = (kalshi_clock::sync_interval_from_env(), &replay_dir) {
+        kalshi_clock::spawn_sync(kalshi_client.as_ref().clone(), every);
+    }
+    let journal = match (Journal::path_from_env(), &replay_dir) {
+        (Some(path), None) => {
+            let journal = Arc::new(Journal::open(path)?);
+            info!("📓 Journaling decisions to {}", journal.path().display());
+            Some(journal)
+        }
+        _ => None,
+    };
+    let journaled = |client: Arc<dyn ExchangeClient>| -> Arc<dyn ExchangeClient> {
+        match &journal {
+            Some(journal) => Arc::new(JournaledClient::new(client, journal.clone())),
+            None => client,
+        }
+    };
+    // Order-placing paths go through per-venue entry/protective lanes.
+    let pm_orders: Arc<dyn ExchangeClient> =
+        Arc::new(LanedClient::new(journaled(polymarket_client.clone()), OrderLanes::from_env()));
+    let kalshi_orders: Arc<dyn ExchangeClient> =
+        Arc::new(LanedClient::new(journaled(kalshi_client.clone()), OrderLanes::from_env()));
 
-    let position_tracker = Arc::new(Mutex::new(PositionTracker::new()));
+    let store_path = std::env::var("POSITION_STORE_PATH")
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_STORE_PATH.to_string());
+    let position_tracker = if replay_dir.is_some() {
+        Arc::new(Mutex::new(PositionTracker::new()))
+    } else {
+        let mut tracker = PositionTracker::with_store(&store_path)?;
+        if let Some(journal) = &journal {
+            tracker = tracker.with_journal(journal.clone());
+        }
+        Arc::new(Mutex::new(tracker))
+    };
+
+    if command.as_deref() == Some("export") {
+        let usage = || anyhow::anyhow!("Usage: export <path> [csv|json]");
+        let path = std::path::PathBuf::from(args.get(2).ok_or_else(usage)?);
+        let format = match args.get(3) {
+            Some(format) => ExportFormat::parse(format),
+            None => ExportFormat::from_path(&path),
+        }
+        .ok_or_else(usage)?;
+        let exported = position_tracker.lock().await.export(&path, format)?;
+        info!("✅ Exported {} positions from {} to {}", exported, store_path, path.display());
+        return Ok(());
+    }
 
-    let trade_executor = Arc::new(
-        TradeExecutor::new(
-            (*polymarket_client.clone()).clone(),
-            (*kalshi_client.clone()).clone(),
+    if command.as_deref() == Some("tax-report") {
+        let year = args
+            .get(2)
+            .and_then(|s| s.trim().parse::<i32>().ok())
+            .ok_or_else(|| anyhow::anyhow!("Usage: tax-report <year> [dir]"))?;
+        let dir = args.get(3).map(String::as_str).unwrap_or(DEFAULT_REPORT_DIR);
+        let pol_usd = tax_report::pol_usd_from_env();
+        if pol_usd.is_none() {
+            warn!("⚠️ TAX_POL_USD not set - gas is reported in POL only");
+        }
+        let report = TaxReport::build(position_tracker.lock().await.get_all_positions(), year, pol_usd);
+        report.log();
+        for path in report.write(dir)? {
+            info!("✅ Wrote {}", path.display());
+        }
+        return Ok(());
+    }
+
+    let notifier = Arc::new(Notifier::from_env());
+
+    let match_store_path = MatchStore::path_from_env();
+    if command.as_deref() == Some("confirm-match") {
+        let (Some(pm_id), Some(kalshi_id)) = (args.get(2), args.get(3)) else {
+            return Err(anyhow::anyhow!("Usage: confirm-match <polymarket event id> <kalshi ticker>"));
+        };
+        MatchStore::with_store(&match_store_path)?.confirm(pm_id, kalshi_id, MatchSource::Manual);
+        info!("✅ Confirmed {} ↔ {} in {}", pm_id, kalshi_id, match_store_path);
+        return Ok(());
+    }
+    let match_store = match replay_dir {
+        None => Some(Arc::new(MatchStore::with_store(&match_store_path)?)),
+        Some(_) => None,
+    };
+
+    if command.as_deref() == Some("adopt") {
+        info!("Adopting live venue positions into {}", store_path);
+        let adopted = adopt::run_adopt(&polymarket_client, &kalshi_client, &position_tracker).await?;
+        position_tracker.lock().await.save().await?;
+        info!("✅ Adopted {} positions", adopted);
+        return Ok(());
+    }
+
+    if command.as_deref() == Some("flatten-all") {
+        let tolerance = args
+            .get(2)
+            .cloned()
+            .or_else(|| std::env::var("FLATTEN_TOLERANCE").ok())
+            .and_then(|s| s.trim().parse::<f64>().ok())
+            .unwrap_or(DEFAULT_FLATTEN_TOLERANCE);
+        warn!("🧯 flatten-all: cancelling all orders and closing all positions");
+        let report = flatten::run_flatten_all(
+            &polymarket_client,
+            &kalshi_client,
+            &position_tracker,
+            tolerance,
         )
-        .with_position_tracker(position_tracker.clone()),
-    );
+        .await?;
+        report.log();
+        if let Err(e) = position_tracker.lock().await.save().await {
+            error!("Failed to persist positions after flatten-all: {}", e);
+        }
+        if !report.is_flat() {
+            notifier
+                .notify(BotEvent::FlattenIncomplete {
+                    unclosed: report.unclosed.len(),
+                    errors: report.errors.len(),
+                })
+                .await;
+            return Err(anyhow::anyhow!(
+                "flatten-all incomplete: {} positions left open, {} venue errors",
+                report.unclosed.len(),
+                report.errors.len()
+            ));
+        }
+        return Ok(());
+    }
 
-    let gabagool_executor = Arc::new(
-        GabagoolExecutor::new(polymarket_client.clone())
-            .with_position_tracker(position_tracker.clone()),
-    );
+    if replay_dir.is_none() {
+        if let Err(e) = kalshi_client.verify_auth().await {
+            warn!("⚠️ Kalshi auth self-test failed: {:#}", e);
+        }
+    }
+
+    let debug_capture = DebugCapture::from_env()
+        .map(|capture| Arc::new(capture.with_position_tracker(position_tracker.clone())));
+    if let Some(capture) = &debug_capture {
+        info!("🐞 Anomaly debug capture enabled: {}", capture.dir().display());
+    }
+
+    let time_in_force = OrderTimeInForce::from_env();
+    let latency = Arc::new(ExecutionLatency::default());
+    let mut trade_executor = TradeExecutor::new(pm_orders.clone(), kalshi_orders.clone())
+        .with_position_tracker(position_tracker.clone())
+        .with_latency(latency.clone())
+        .with_time_in_force(time_in_force.get(strategy::CROSS_PLATFORM))
+        .with_leg_failure_policy(LegFailurePolicy::from_env())
+        .with_leg_sequence(LegSequence::from_env())
+        .with_order_limits(OrderSizeLimits::from_env())
+        .with_notifier(notifier.clone());
+    if let Some(slicing) = OrderSlicing::from_env() {
+        info!(
+            "🔪 Slicing trades past {:.0}% of the touch, {}ms apart (up to {} slices)",
+            slicing.touch_fraction * 100.0,
+            slicing.interval.as_millis(),
+            slicing.max_slices
+        );
+        trade_executor = trade_executor.with_slicing(slicing);
+    }
+    if let Some(capture) = &debug_capture {
+        trade_executor = trade_executor.with_debug_capture(capture.clone());
+    }
+    let trade_executor = Arc::new(trade_executor);
+
+    let mut gabagool_executor = GabagoolExecutor::new(pm_orders.clone())
+        .with_position_tracker(position_tracker.clone())
+        .with_time_in_force(time_in_force.get(strategy::GABAGOOL));
+    if let Some(maker) = MakerMode::from_env() {
+        info!(
+            "🧾 Gabagool maker mode: post-only ${:.4} inside the bid, resting {}s, crossing within {}s of resolution",
+            maker.improve_by,
+            maker.rest_secs,
+            maker.cross_within.num_seconds()
+        );
+        gabagool_executor = gabagool_executor.with_maker_mode(maker);
+    }
+    if let (Some(min_pairs), false, None) = (
+        GabagoolExecutor::merge_min_pairs_from_env(),
+        kalshi_config.dry_run,
+        &replay_dir,
+    ) {
+        match &chain_signer {
+            Some(signer) if !signer.signs_unattended() => warn!(
+                "⚠️ GABAGOOL_MERGE_MIN_PAIRS would wait on the Ledger - set POLYMARKET_LEDGER_CONFIRM=always to allow it"
+            ),
+            Some(signer) => {
+                let chain = PolymarketBlockchain::new(&polygon_rpc)?.with_signer(signer.clone());
+                if polymarket_blockchain::funder_address(chain.address()?)?.is_some() {
+                    warn!("⚠️ GABAGOOL_MERGE_MIN_PAIRS only merges from the signing wallet, not a proxy/Safe - not merging");
+                } else {
+                    info!("🔀 Merging Gabagool pairs into USDC once {} are held under $1 a pair", min_pairs);
+                    gabagool_executor = gabagool_executor.with_merger(Arc::new(chain), min_pairs);
+                }
+            }
+            None => warn!(
+                "⚠️ GABAGOOL_MERGE_MIN_PAIRS needs POLYMARKET_WALLET_PRIVATE_KEY or a remote signer - not merging"
+            ),
+        }
+    }
+    if replay_dir.is_none() {
+        let gabagool_store = std::env::var("GABAGOOL_STORE_PATH")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_GABAGOOL_STORE_PATH.to_string());
+        gabagool_executor = gabagool_executor.with_store(gabagool_store)?;
+    }
+    let gabagool_executor = Arc::new(gabagool_executor);
+    if let (Some(every), None) = (GabagoolExecutor::reconcile_interval_from_env(), &replay_dir) {
+        let mut chain = PolymarketBlockchain::new(&polygon_rpc)?;
+        if let Some(signer) = &chain_signer {
+            chain = chain.with_signer(signer.clone()).with_funder_from_env()?;
+        }
+        info!("⛓️ Reconciling Gabagool holdings on-chain every {}s", every.as_secs());
+        tokio::spawn(gabagool_executor.clone().run_reconciliation(Arc::new(chain), every));
+    }
+    if let Some(rebalance) = Rebalance::from_env() {
+        info!(
+            "⚖️ Trimming Gabagool sides over {:.1}x the other by {:.0}% of the excess, checked every {}s",
+            rebalance.max_ratio,
+            rebalance.trim_fraction * 100.0,
+            rebalance.interval.as_secs()
+        );
+        tokio::spawn(gabagool_executor.clone().run_rebalancing(rebalance));
+    }
 
-    let settlement_checker = Arc::new(SettlementChecker::new(
+    let mut settlement_checker = SettlementChecker::new(
         polymarket_client.clone(),
         kalshi_client.clone(),
         position_tracker.clone(),
-    ));
+    )
+    .with_env_limits()
+    .with_notifier(notifier.clone());
+    if let Some(store) = &match_store {
+        settlement_checker = settlement_checker.with_match_store(store.clone());
+    }
+    let auto_redeem = std::env::var("POLYMARKET_AUTO_REDEEM")
+        .map(|s| s == "1" || s.eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
+    if auto_redeem && !kalshi_config.dry_run && replay_dir.is_none() {
+        match &chain_signer {
+            Some(signer) if !signer.signs_unattended() => warn!(
+                "⚠️ POLYMARKET_AUTO_REDEEM would wait on the Ledger - set POLYMARKET_LEDGER_CONFIRM=always to allow it"
+            ),
+            Some(signer) => {
+                info!("💵 Redeeming won Polymarket positions on settlement");
+                let chain = PolymarketBlockchain::new(&polygon_rpc)?.with_signer(signer.clone());
+                if polymarket_blockchain::funder_address(chain.address()?)?.is_some() {
+                    warn!("⚠️ POLYMARKET_AUTO_REDEEM only redeems from the signing wallet, not a proxy/Safe - not redeeming");
+                } else {
+                    settlement_checker = settlement_checker.with_redeemer(Arc::new(chain));
+                }
+            }
+            None => warn!(
+                "⚠️ POLYMARKET_AUTO_REDEEM needs POLYMARKET_WALLET_PRIVATE_KEY or a remote signer - not redeeming"
+            ),
+        }
+    }
+    let settlement_checker = Arc::new(settlement_checker);
 
     let coin_filter = std::env::var("COIN_FILTER").ok();
     let coin_filter = coin_filter.as_ref().and_then(|s| {
@@ -88,39 +459,327 @@ async fn main() -> Result<()> {
     let filters = MarketFilters {
         categories: vec!["crypto".to_string()],
         max_hours_until_resolution: 1,
-        min_liquidity: 200.0,
+        min_liquidity: config.min_liquidity,
         coin_filter: coin_filter.clone(),
     };
 
-    let bot = ShortTermArbitrageBot::new(
+    let recorder = MarketRecorder::from_env().map(Arc::new);
+
+    let mut bot = ShortTermArbitrageBot::new(
         filters,
-        0.80,
-        0.02,
+        config.similarity_threshold,
+        config.min_profit,
+    )
+    .with_resolution_window(
+        chrono::Duration::minutes(config.min_minutes_to_resolution),
+        chrono::Duration::minutes(config.max_minutes_to_resolution),
+    )
+    .with_resolution_tolerance(
+        Some(chrono::Duration::seconds(config.resolution_tolerance_secs))
+            .filter(|_| config.resolution_tolerance_secs > 0),
     );
+    bot = bot.with_rules_check(RulesCheck::from_env());
+    if let Some(store) = &match_store {
+        bot = bot.with_match_store(store.clone());
+    }
+    if let Some(mappings) = MarketMappings::from_env()? {
+        info!(
+            "📌 Market mappings: {} pinned, {} denied pairs",
+            mappings.pins.len(),
+            mappings.deny.len()
+        );
+        bot = bot.with_mappings(mappings);
+    }
+    if let Some(embeddings) = EmbeddingClient::from_env() {
+        info!("🧠 Embedding similarity on (weight {:.2})", embeddings.weight());
+        bot = bot.with_embeddings(Arc::new(embeddings));
+    }
+    if let Some(limits) = SpreadLimits::from_env() {
+        bot = bot.with_spread_limits(limits);
+    }
+    if let Some(decay) = EdgeDecay::from_env(latency.clone()) {
+        info!(
+            "⏱️ Edge decay: +${:.4}/share per second of p{:.0} fill latency (up to +${:.4})",
+            decay.per_second, decay.percentile, decay.max_extra
+        );
+        bot = bot.with_edge_decay(decay);
+    }
+    if let Some(secs) = std::env::var("MAX_PRICE_AGE_SECS")
+        .ok()
+        .and_then(|s| s.trim().parse::<i64>().ok())
+    {
+        bot = bot.with_max_price_age((secs > 0).then(|| chrono::Duration::seconds(secs)));
+    }
+    if config.trade_cooldown_secs > 0 || config.trade_price_band_cents > 0 {
+        bot = bot.with_trade_dedup(TradeDedup {
+            cooldown: chrono::Duration::seconds(config.trade_cooldown_secs),
+            band_cents: config.trade_price_band_cents,
+        });
+    }
+    if let Some(secs) = std::env::var("MARKET_WARMUP_SECS")
+        .ok()
+        .and_then(|s| s.trim().parse::<i64>().ok())
+        .filter(|s| *s > 0)
+    {
+        info!("🐣 Newly listed markets warm up for {}s before trading", secs);
+        bot = bot.with_warmup(chrono::Duration::seconds(secs));
+    }
+    if let Some(dir) = &replay_dir {
+        let source = ReplaySource::load(dir)?;
+        let speed = std::env::var("REPLAY_SPEED")
+            .ok()
+            .and_then(|s| s.parse::<f64>().ok())
+            .unwrap_or(0.0);
+        info!("⏪ Replaying {} recorded scans from {} (speed: {}x)", source.scans().len(), dir, speed);
+        let summary = replay::run_replay(&bot, &trade_executor, &source, speed, config.trade_amount).await;
+        info!(
+            "⏪ Replay done - Scans: {}, Opportunities: {}, Executed: {}, Failed: {}, Expected Profit: ${:.2}",
+            summary.scans,
+            summary.opportunities,
+            summary.executed,
+            summary.failed,
+            summary.expected_profit
+        );
+        return Ok(());
+    }
 
-    let fetch_prices = {
-        let pm = polymarket_client.clone();
-        let kalshi = kalshi_client.clone();
-        move |event_id: &str, platform: &str| {
-            let event_id = event_id.to_string();
-            let platform = platform.to_string();
-            let pm = pm.clone();
-            let kalshi = kalshi.clone();
-            async move {
-                match platform.as_str() {
-                    "polymarket" => pm.fetch_prices(&event_id).await.unwrap_or_default(),
-                    "kalshi" => kalshi.fetch_prices(&event_id).await.unwrap_or_default(),
-                    _ => MarketPrices::new(0.0, 0.0, 0.0),
+    if let Some(recorder) = &recorder {
+        info!("📼 Recording scans to {}", recorder.dir().display());
+        bot = bot.with_recorder(recorder.clone());
+    }
+    if let Some(capture) = &debug_capture {
+        bot = bot.with_debug_capture(capture.clone());
+    }
+    if let Some(shadow) = ShadowEvaluator::from_env() {
+        info!("👻 Shadow threshold sets (logged, never traded): {}", shadow.names().join(", "));
+        bot = bot.with_shadow(shadow);
+    }
+    let bot = Arc::new(bot);
+
+    if let Ok(addr) = std::env::var("METRICS_ADDR") {
+        tokio::spawn(async move {
+            if let Err(e) = metrics::serve(&addr).await {
+                error!("Metrics server stopped: {}", e);
+            }
+        });
+    }
+
+    let spot_feed = SpotFeed::from_env().map(Arc::new);
+    if let Some(feed) = &spot_feed {
+        info!("📡 Binance spot feed: {}", feed.coins().join(", "));
+        feed.clone().spawn();
+    }
+
+    let book_feed = BookFeed::from_env(polymarket_client.clone(), kalshi_client.clone()).map(Arc::new);
+    if let Some(feed) = &book_feed {
+        info!("📖 Book feed: {} - pairs re-priced on every book update", feed.venues().join(", "));
+        feed.clone().spawn();
+    }
+
+    let oracle = OracleCheck::from_env()?.map(Arc::new);
+    if let Some(oracle) = &oracle {
+        info!("🔗 Chainlink oracle cross-check: {}", oracle.coins().join(", "));
+    }
+
+    let controls = Arc::new(Controls::new());
+    let balance_floors = BalanceFloors::from_env();
+    if let Some(floors) = balance_floors.clone() {
+        floors.spawn(
+            vec![pm_orders.clone(), kalshi_orders.clone()],
+            controls.clone(),
+            notifier.clone(),
+        );
+    }
+    if let (Some(auditor), false, None) = (BalanceAuditor::from_env(), kalshi_config.dry_run, &replay_dir) {
+        auditor.spawn(
+            vec![pm_orders.clone(), kalshi_orders.clone()],
+            position_tracker.clone(),
+            notifier.clone(),
+        );
+    }
+    let cross_platform_enabled = strategy::enabled_strategies_from_env()
+        .iter()
+        .any(|name| name == strategy::CROSS_PLATFORM);
+    if let (Some(mut advisor), true, None) = (
+        RebalanceAdvisor::from_env(config.trade_amount),
+        cross_platform_enabled,
+        &replay_dir,
+    ) {
+        advisor = advisor.with_controls(controls.clone());
+        if let Some(floors) = balance_floors {
+            advisor = advisor.with_floors(floors);
+        }
+        advisor.spawn(pm_orders.clone(), kalshi_orders.clone(), notifier.clone());
+    }
+    let mut strategies = StrategyRegistry::new()
+        .with_position_tracker(position_tracker.clone())
+        .with_controls(controls.clone())
+        .with_intervals(StrategyIntervals::from_env());
+    if let Some(allocation) = CapitalAllocation::from_env() {
+        strategies = strategies.with_allocation(allocation);
+    }
+    if let Some(journal) = &journal {
+        strategies = strategies.with_journal(journal.clone());
+    }
+    let ranking = OpportunityRanking::from_env();
+    if ranking != OpportunityRanking::default() {
+        info!(
+            "🏅 Opportunity ranking: {:?}, min ROI/h {:?}, min confidence {:?}",
+            ranking.by, ranking.min_roi_per_hour, ranking.min_confidence
+        );
+        strategies = strategies.with_ranking(ranking);
+    }
+    let trade_limits = TradeLimits::from_env();
+    if !trade_limits.is_empty() {
+        info!("🚦 Trade caps: {}", trade_limits.describe());
+        strategies = strategies.with_limits(trade_limits);
+    }
+    for name in strategy::enabled_strategies_from_env() {
+        match name.as_str() {
+            strategy::CROSS_PLATFORM => {
+                let mut cross_platform = CrossPlatformStrategy::new(
+                    bot.clone(),
+                    trade_executor.clone(),
+                    pm_orders.clone(),
+                    kalshi_orders.clone(),
+                )
+                .with_trade_amount(config.trade_amount);
+                if let Some(feed) = &spot_feed {
+                    cross_platform = cross_platform.with_spot_feed(feed.clone());
+                }
+                if let Some(oracle) = &oracle {
+                    cross_platform = cross_platform.with_oracle(oracle.clone());
                 }
+                if let Some(feed) = &book_feed {
+                    cross_platform = cross_platform.with_book_feed(feed.clone());
+                }
+                strategies.register(cross_platform)
+            }
+            strategy::GABAGOOL => strategies.register(
+                GabagoolStrategy::new(bot.clone(), gabagool_executor.clone(), pm_orders.clone())
+                    .with_trade_amount(config.trade_amount),
+            ),
+            strategy::STRIKE => strategies.register(
+                StrikeStrategy::new(StrikeDetector::new(config.min_profit), pm_orders.clone())
+                    .with_trade_amount(config.trade_amount)
+                    .with_position_tracker(position_tracker.clone())
+                    .with_time_in_force(time_in_force.get(strategy::STRIKE)),
+            ),
+            strategy::NEG_RISK => strategies.register(
+                NegRiskStrategy::new(BasketDetector::new(config.min_profit), pm_orders.clone())
+                    .with_trade_amount(config.trade_amount)
+                    .with_position_tracker(position_tracker.clone())
+                    .with_time_in_force(time_in_force.get(strategy::NEG_RISK)),
+            ),
+            strategy::QUOTING => {
+                let env_f64 = |name: &str| std::env::var(name).ok().and_then(|s| s.trim().parse::<f64>().ok());
+                let target = env_f64("QUOTE_TARGET_PAIR_COST").filter(|t| *t > 0.0 && *t < 1.0).unwrap_or(0.97);
+                let mut quoting = QuotingStrategy::new(
+                    bot.clone(),
+                    QuoteDetector::new(target),
+                    gabagool_executor.clone(),
+                    pm_orders.clone(),
+                )
+                .with_trade_amount(config.trade_amount);
+                if let Some(secs) = env_f64("QUOTE_REST_SECS") {
+                    quoting = quoting.with_rest_secs(secs as u64);
+                }
+                strategies.register(quoting)
             }
+            other => warn!("⚠️ Unknown strategy '{}' in STRATEGIES - ignoring", other),
         }
-    };
+    }
+    if strategies.is_empty() {
+        return Err(anyhow::anyhow!("No strategies enabled (check STRATEGIES)"));
+    }
+    let strategies = Arc::new(strategies);
 
-    info!("Starting dual-strategy scanning (interval: 60s)");
+    if let Some((addr, token)) = ControlApi::config_from_env() {
+        let api = Arc::new(ControlApi::new(
+            controls.clone(),
+            &token,
+            pm_orders.clone(),
+            kalshi_orders.clone(),
+            position_tracker.clone(),
+        ));
+        tokio::spawn(async move {
+            if let Err(e) = api.serve(&addr).await {
+                error!("Control API stopped: {}", e);
+            }
+        });
+    }
+
+    let dashboard_addr = Dashboard::addr_from_env();
+    let dashboard = (dashboard_addr.is_some() || tui_mode).then(|| {
+        Arc::new(
+            Dashboard::new(position_tracker.clone())
+                .with_bot(bot.clone())
+                .with_strategies(strategies.clone())
+                .with_gabagool(gabagool_executor.clone())
+                .with_balances(polymarket_client.clone(), kalshi_client.clone())
+                .with_refresh(Dashboard::refresh_from_env()),
+        )
+    });
+    if let (Some(addr), Some(dashboard)) = (dashboard_addr, dashboard.clone()) {
+        tokio::spawn(async move {
+            if let Err(e) = dashboard.serve(&addr).await {
+                error!("Dashboard stopped: {}", e);
+            }
+        });
+    }
+    let tui_quit = Arc::new(Notify::new());
+    if let (true, Some(dashboard)) = (tui_mode, dashboard) {
+        let quit = tui_quit.clone();
+        tokio::spawn(async move {
+            if let Err(e) = tui::run(dashboard).await {
+                error!("Terminal dashboard stopped: {}", e);
+            }
+            quit.notify_one();
+        });
+    }
+
+    let watchlist = WatchList::from_env();
+    let profit_target = ProfitTarget::from_env();
+    let loss_limit = DailyLossLimit::from_env();
+    let early_exit = EarlyExit::capture_from_env().map(|capture| {
+        EarlyExit::new(
+            pm_orders.clone(),
+            kalshi_orders.clone(),
+            position_tracker.clone(),
+            capture,
+        )
+    });
+    let fair_value = FairValueEngine::from_env().map(|engine| match &spot_feed {
+        Some(feed) => engine.with_spot_feed(feed.clone()),
+        None => engine,
+    });
+
+    info!("Starting event listing and strategy scanning (interval: {}s)", config.scan_interval_secs);
     info!("🎯 Target: Crypto price prediction 15-minute markets ONLY");
-    info!("  Strategy 1: Cross-platform arbitrage (Polymarket ↔ Kalshi)");
-    info!("  Strategy 2: Gabagool hedged arbitrage (Polymarket only)");
-    info!("  Timeframe: 10-30 minutes until resolution");
+    info!("  Strategies: {}", strategies.names().join(", "));
+    if let Some(watchlist) = &watchlist {
+        info!("  Watching {} market pairs (no trading)", watchlist.pairs().len());
+    }
+    if let Some(capture) = EarlyExit::capture_from_env() {
+        info!("  Early exit: sell converged pairs at ≥{:.0}% of settlement profit", capture * 100.0);
+    }
+    if let Some(target) = &profit_target {
+        info!("  Profit target: ${:.2} per {:?} period, then manage-only", target.target(), target.period());
+    }
+    if let Some(limit) = &loss_limit {
+        info!("  Daily loss limit: ${:.2}, then no new entries until 00:00 UTC", limit.limit());
+    }
+    if let Some(engine) = &fair_value {
+        info!("  Fair value: flagging 15m markets ≥{:.2} from fair (no trading)", engine.threshold());
+    }
+    info!(
+        "  Timeframe: {}-{} minutes until resolution",
+        config.min_minutes_to_resolution, config.max_minutes_to_resolution
+    );
+    info!(
+        "  Trade size: ${:.2}, min profit: ${:.4}/share, min liquidity: ${:.0}",
+        config.trade_amount, config.min_profit, config.min_liquidity
+    );
     info!("  Requirements: Slug/tag 15m crypto or keywords (Crypto + Price + 15min)");
     if let Some(coin) = &coin_filter {
         info!("  Coin filter: {} only", coin);
@@ -129,177 +788,81 @@ async fn main() -> Result<()> {
     }
     info!("  Polymarket: Gamma API when POLYMARKET_USE_GAMMA=1, tag_slug from POLYMARKET_TAG_SLUG");
     info!("  Kalshi: series_ticker from KALSHI_SERIES_TICKER when set");
-    info!("Settlement checking (every 5 minutes)");
+    let settlement_every = config.settlement_interval_secs;
+    info!("Settlement checking (every {}s, background worker)", settlement_every);
     
-    let mut scan_interval = tokio::time::interval(Duration::from_secs(60));
-    let mut settlement_interval = tokio::time::interval(Duration::from_secs(300));
-
-    let fetch_prices_cross = {
-        let pm = polymarket_client.clone();
-        let kalshi = kalshi_client.clone();
-        move |event_id: &str, platform: &str| {
-            let event_id = event_id.to_string();
-            let platform = platform.to_string();
-            let pm = pm.clone();
-            let kalshi = kalshi.clone();
-            async move {
-                match platform.as_str() {
-                    "polymarket" => pm.fetch_prices(&event_id).await.unwrap_or_default(),
-                    "kalshi" => kalshi.fetch_prices(&event_id).await.unwrap_or_default(),
-                    _ => MarketPrices::new(0.0, 0.0, 0.0),
-                }
-            }
-        }
-    };
-
-    let fetch_prices_gabagool = {
-        let pm = polymarket_client.clone();
-        move |event_id: &str| {
-            let event_id = event_id.to_string();
-            let pm = pm.clone();
-            async move {
-                pm.fetch_prices(&event_id).await.unwrap_or_default()
-            }
-        }
-    };
-
-    let get_position_balance = {
-        let executor = gabagool_executor.clone();
-        move |event_id: &str| {
-            let event_id = event_id.to_string();
-            let executor = executor.clone();
-            async move {
-                executor.get_position_balance(&event_id).await
-            }
-        }
-    };
-    
-    loop {
-        tokio::select! {
-            _ = scan_interval.tick() => {
-
-        let (pm_events, kalshi_events) = tokio::join!(
-            polymarket_client.fetch_events(),
-            kalshi_client.fetch_events()
+    if let Some(rules) = ExitRules::from_env() {
+        let every = PositionMonitor::interval_from_env();
+        info!(
+            "Unhedged leg monitor (every {}s): take-profit {:?}, stop-loss {:?}",
+            every.as_secs(),
+            rules.take_profit,
+            rules.stop_loss
         );
-        
-        let pm_events = pm_events.unwrap_or_default();
-        let kalshi_events = kalshi_events.unwrap_or_default();
-
-        let (cross_platform_opps, gabagool_opps) = tokio::join!(
-
-            bot.scan_for_opportunities(&pm_events, &kalshi_events, fetch_prices_cross.clone()),
-
-            bot.scan_gabagool_opportunities(&pm_events, fetch_prices_gabagool.clone(), get_position_balance.clone())
-        );
-
-        if !cross_platform_opps.is_empty() {
-            info!("🔀 Strategy 1: Found {} cross-platform arbitrage opportunities", cross_platform_opps.len());
-            
-            let trade_futures: Vec<_> = cross_platform_opps
-                .into_iter()
-                .map(|(pm_event, kalshi_event, opp)| {
-                    let executor = trade_executor.clone();
-                    let trade_amount = 100.0;
-                    async move {
-                        info!(
-                            "🚨 Cross-Platform Opportunity: {} - Profit: ${:.4}, ROI: {:.2}%",
-                            pm_event.title,
-                            opp.net_profit,
-                            opp.roi_percent
-                        );
-                        executor
-                            .execute_arbitrage(&opp, &pm_event, &kalshi_event, trade_amount)
-                            .await
-                    }
-                })
-                .collect();
-
-            let trade_results = futures::future::join_all(trade_futures).await;
+        let monitor = Arc::new(PositionMonitor::new(
+            pm_orders.clone(),
+            kalshi_orders.clone(),
+            position_tracker.clone(),
+            rules,
+        ));
+        tokio::spawn(monitor.run(every));
+    }
 
-            for result in trade_results {
-                match result {
-                    Ok(trade_result) => {
-                        if trade_result.success {
-                            info!(
-                                "✅ Cross-platform trade executed! PM: {:?}, Kalshi: {:?}",
-                                trade_result.polymarket_order_id, trade_result.kalshi_order_id
-                            );
-                        } else {
-                            warn!(
-                                "⚠️ Cross-platform trade failed: {}",
-                                trade_result.error.unwrap_or_default()
-                            );
-                        }
-                    }
-                    Err(e) => {
-                        error!("Error executing cross-platform trade: {}", e);
-                    }
-                }
+    if let Some(every) = analytics::interval_from_env() {
+        let window = analytics::window_from_env();
+        info!("Performance analytics (every {}s, rolling window {} trades)", every.as_secs(), window);
+        let position_tracker = position_tracker.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(every);
+            loop {
+                interval.tick().await;
+                let report = PerformanceReport::from_tracker(&*position_tracker.lock().await, window);
+                report.publish();
+                report.log();
             }
-        }
-
-        if !gabagool_opps.is_empty() {
-            info!("🎯 Strategy 2: Found {} Gabagool opportunities", gabagool_opps.len());
-            
-            let gabagool_futures: Vec<_> = gabagool_opps
-                .into_iter()
-                .map(|opp| {
-                    let executor = gabagool_executor.clone();
-                    let trade_amount = 100.0;
-                    async move {
-                        info!(
-                            "🎯 Gabagool Opportunity: {} - Buy {} @ ${:.4}, Profit: ${:.4} ({:.2}% ROI), Pair Cost: ${:.4}",
-                            opp.event.title,
-                            opp.cheap_side,
-                            opp.cheap_price,
-                            opp.net_profit,
-                            opp.roi_percent,
-                            opp.pair_cost_after
-                        );
-
-                        if opp.profit_locked {
-                            info!("🔒 Profit already LOCKED for this position!");
-                        }
-
-                        executor.execute_trade(&opp, trade_amount).await
-                    }
-                })
-                .collect();
-
-            let gabagool_results = futures::future::join_all(gabagool_futures).await;
+        });
+    }
 
-            for result in gabagool_results {
-                match result {
-                    Ok(success) => {
-                        if success {
-                            info!("✅ Gabagool trade executed successfully!");
-                        } else {
-                            warn!("⚠️ Gabagool trade execution returned false");
+    let scan_period = Duration::from_secs(config.scan_interval_secs);
+    let mut scan_interval = tokio::time::interval(scan_period);
+    health().set_max_scan_age(health::max_scan_age_from_env(scan_period));
+    if let Some(heartbeat) = Heartbeat::from_env() {
+        heartbeat.spawn();
+    }
+    // Each strategy runs on its own task off the latest event listing.
+    let (snapshots, snapshot_rx) = tokio::sync::watch::channel(None);
+    strategies.spawn(snapshot_rx);
+    if let Some(feed) = &book_feed {
+        strategies.spawn_updates(feed.updates());
+    }
+    // Settlement runs on its own task so a long pass never delays a scan tick.
+    tokio::spawn({
+        let settlement_checker = settlement_checker.clone();
+        let notifier = notifier.clone();
+        // Replays have no live Gamma markets to watch.
+        let uma_monitor = replay_dir
+            .is_none()
+            .then(|| UmaMonitor::new(polymarket_client.clone(), position_tracker.clone()));
+        async move {
+            let mut settlement_interval = tokio::time::interval(Duration::from_secs(settlement_every));
+            loop {
+                settlement_interval.tick().await;
+                if let Some(uma_monitor) = &uma_monitor {
+                    match uma_monitor.check().await {
+                        Ok(disputed) => {
+                            for market in disputed {
+                                warn!("⚖️ UMA dispute on {} ({})", market.title, market.condition_id);
+                                notifier
+                                    .notify(BotEvent::ResolutionDisputed {
+                                        event_id: market.condition_id,
+                                        title: market.title,
+                                    })
+                                    .await;
+                            }
                         }
-                    }
-                    Err(e) => {
-                        error!("Error executing Gabagool trade: {}", e);
+                        Err(e) => warn!("UMA dispute check failed: {}", e),
                     }
                 }
-            }
-        }
-
-        if !cross_platform_opps.is_empty() || !gabagool_opps.is_empty() {
-            let gabagool_stats = gabagool_executor.get_statistics().await;
-            info!(
-                "📊 Gabagool Stats - Events: {}, YES: {:.2}, NO: {:.2}, Total Cost: ${:.2}, Locked Profit: ${:.2} ({:.2} pairs)",
-                gabagool_stats.total_events,
-                gabagool_stats.total_yes_qty,
-                gabagool_stats.total_no_qty,
-                gabagool_stats.total_cost,
-                gabagool_stats.locked_profit,
-                gabagool_stats.locked_pairs
-            );
-        }
-            }
-            _ = settlement_interval.tick() => {
-
                 info!("Checking for settled positions...");
                 match settlement_checker.check_settlements().await {
                     Ok(count) => {
@@ -315,6 +878,14 @@ async fn main() -> Result<()> {
                                 stats.lost_positions,
                                 stats.total_profit
                             );
+                            info!("📊 Profit by strategy: {}", format_breakdown(&stats.by_strategy));
+                            info!("📊 Profit by coin: {}", format_breakdown(&stats.by_coin));
+                            notifier
+                                .notify(BotEvent::Settled {
+                                    count,
+                                    total_profit: stats.total_profit,
+                                })
+                                .await;
 
                             if let Ok((pm_balance, kalshi_balance)) = settlement_checker.check_balances().await {
                                 info!(
@@ -334,5 +905,159 @@ async fn main() -> Result<()> {
                 }
             }
         }
+    });
+
+    if let (Some(signer), false) = (&chain_signer, kalshi_config.dry_run) {
+        // Both legs of a cross-platform trade need this much USDC approved.
+        let required = config.trade_amount * 2.0;
+        let approve_cap = std::env::var("USDC_AUTO_APPROVE_CAP")
+            .ok()
+            .and_then(|s| s.trim().parse::<f64>().ok())
+            .filter(|cap| *cap > 0.0);
+        let chain = PolymarketBlockchain::new(&polygon_rpc)?
+            .with_signer(signer.clone())
+            .with_funder_from_env()?;
+        match chain.ensure_usdc_allowances(required, approve_cap).await {
+            Ok(short) if short.is_empty() => info!("✅ USDC allowances cover ${:.2} per trade", required),
+            Ok(short) => warn!(
+                "⚠️ {} exchange contracts lack USDC allowance - set USDC_AUTO_APPROVE_CAP or approve on Polymarket",
+                short.len()
+            ),
+            Err(e) => warn!("⚠️ USDC allowance check failed: {}", e),
+        }
+    }
+
+    let discrepancies =
+        restart::verify_restored_state(&polymarket_client, &kalshi_client, &position_tracker).await;
+    if discrepancies.is_empty() {
+        info!("✅ Restored state matches venue holdings");
+    } else {
+        for d in &discrepancies {
+            warn!(
+                "⚠️ State mismatch {} {} {}: tracked {:.2}, venue {:.2}",
+                d.platform, d.event_id, d.outcome, d.tracked_qty, d.venue_qty
+            );
+        }
+        warn!("⚠️ {} positions differ from venue holdings - run `adopt` or review the store", discrepancies.len());
+        notifier
+            .notify(BotEvent::StateMismatch { count: discrepancies.len() })
+            .await;
+    }
+
+    notifier
+        .notify(BotEvent::Started {
+            strategies: strategies.names().iter().map(|n| n.to_string()).collect(),
+        })
+        .await;
+
+    let restart_schedule = RestartSchedule::from_env();
+    let restart_in = restart_schedule
+        .map(|schedule| schedule.until_next(chrono::Utc::now()))
+        .unwrap_or(Duration::MAX);
+    if let Some(schedule) = &restart_schedule {
+        info!("🔁 Scheduled restart at {}", schedule.next_after(chrono::Utc::now()).to_rfc3339());
+    }
+    let restart_timer = tokio::time::sleep(restart_in);
+    tokio::pin!(restart_timer);
+
+    loop {
+        tokio::select! {
+            _ = &mut restart_timer, if restart_schedule.is_some() => {
+                info!("🔁 Scheduled restart: draining strategies, persisting state and exiting for supervisor relaunch");
+                let running = strategies.shutdown(SHUTDOWN_GRACE).await;
+                if running > 0 {
+                    warn!("⚠️ {} strategy tasks still running after {:?} - aborted", running, SHUTDOWN_GRACE);
+                }
+                if let Err(e) = position_tracker.lock().await.save().await {
+                    error!("Failed to persist positions before restart: {}", e);
+                }
+                if let Err(e) = gabagool_executor.save().await {
+                    error!("Failed to persist Gabagool positions before restart: {}", e);
+                }
+                if let Some(recorder) = &recorder {
+                    recorder.flush().await;
+                }
+                std::process::exit(RESTART_EXIT_CODE);
+            }
+            _ = tui_quit.notified(), if tui_mode => {
+                info!("👋 Terminal dashboard closed: draining strategies, persisting state and exiting");
+                let running = strategies.shutdown(SHUTDOWN_GRACE).await;
+                if running > 0 {
+                    warn!("⚠️ {} strategy tasks still running after {:?} - aborted", running, SHUTDOWN_GRACE);
+                }
+                if let Err(e) = position_tracker.lock().await.save().await {
+                    error!("Failed to persist positions: {}", e);
+                }
+                if let Err(e) = gabagool_executor.save().await {
+                    error!("Failed to persist Gabagool positions: {}", e);
+                }
+                if let Some(recorder) = &recorder {
+                    recorder.flush().await;
+                }
+                return Ok(());
+            }
+            _ = scan_interval.tick() => {}
+            _ = controls.scan_requested() => info!("🔎 Running requested scan"),
+        }
+
+        // Through the trait, so the calls are timed per client method.
+        let (pm_events, kalshi_events) = tokio::join!(
+            ExchangeClient::fetch_events(polymarket_client.as_ref()),
+            ExchangeClient::fetch_events(kalshi_client.as_ref())
+        );
+        if pm_events.is_ok() && kalshi_events.is_ok() {
+            health().record_scan();
+        }
+
+        let pm_events = pm_events.unwrap_or_default();
+        let kalshi_events = kalshi_events.unwrap_or_default();
+
+        if let Some(recorder) = &recorder {
+            recorder.record_events(&pm_events, &kalshi_events);
+        }
+
+        if let Some(watchlist) = &watchlist {
+            watchlist
+                .run_tick(&pm_events, polymarket_client.as_ref(), kalshi_client.as_ref())
+                .await;
+        }
+
+        if let Some(engine) = &fair_value {
+            engine.run_tick(&pm_events, polymarket_client.as_ref()).await;
+        }
+
+        if let Some(early_exit) = &early_exit {
+            let exited = early_exit.run_tick().await;
+            if exited > 0 {
+                info!("💸 Exited {} converged pairs early", exited);
+            }
+        }
+
+        if let Some(limit) = &loss_limit {
+            let hit = limit.check(&*position_tracker.lock().await, &controls, chrono::Utc::now());
+            if let Some(event) = hit {
+                notifier.notify(event).await;
+            }
+        }
+
+        if let Some(target) = &profit_target {
+            let (manage_only, reached) =
+                target.check(&*position_tracker.lock().await, chrono::Utc::now());
+            if let Some(realized) = reached {
+                notifier
+                    .notify(BotEvent::ProfitTargetReached { realized, target: target.target() })
+                    .await;
+            }
+            if manage_only {
+                info!("🎯 Manage-only: profit target met, no new entries this period");
+                snapshots.send_replace(None);
+                continue;
+            }
+        }
+
+        snapshots.send_replace(Some(Arc::new(MarketSnapshot { pm_events, kalshi_events })));
+        if let Some(shadow) = bot.shadow() {
+            shadow.log_summary();
+        }
     }
 }
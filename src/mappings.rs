@@ -0,0 +1,148 @@
+//! Operator-pinned market pairings.
+//!
+//! `MARKET_MAPPINGS_FILE` (default `mappings.toml`, skipped when absent)
+//! lists Polymarket ↔ Kalshi pairs that `EventMatcher` takes as matched
+//! before any fuzzy matching, and pairs it must never match:
+//!
+//! ```toml
+//! [[pair]]
+//! polymarket = "btc-updown-15m-*"
+//! kalshi = "KXBTC15M-*"
+//!
+//! [[deny]]
+//! polymarket = "eth-updown-15m-*"
+//! kalshi = "KXBTC15M-*"
+//! ```
+//!
+//! Each side is an event id or slug; a trailing `*` matches a prefix, which
+//! is how a recurring 15-minute series is pinned (the resolution-time check
+//! still picks the right window). An event named by a pin is only matched
+//! through its pins.
+
+use crate::event::Event;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::Path;
+
+const DEFAULT_FILE: &str = "mappings.toml";
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct MappingRule {
+    pub polymarket: String,
+    pub kalshi: String,
+}
+
+fn pattern_matches(pattern: &str, event: &Event) -> bool {
+    let mut ids = std::iter::once(event.event_id.as_str()).chain(event.slug.as_deref());
+    match pattern.strip_suffix('*') {
+        Some(prefix) => ids
+            .map(str::to_lowercase)
+            .any(|id| id.starts_with(&prefix.to_lowercase())),
+        None => ids.any(|id| id.eq_ignore_ascii_case(pattern)),
+    }
+}
+
+impl MappingRule {
+    pub fn matches(&self, polymarket: &Event, kalshi: &Event) -> bool {
+        pattern_matches(&self.polymarket, polymarket) && pattern_matches(&self.kalshi, kalshi)
+    }
+}
+
+/// What the mappings say about a candidate pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mapping {
+    Pinned,
+    Denied,
+    /// One side is pinned to something else.
+    PinnedElsewhere,
+    /// Not mentioned: left to fuzzy matching.
+    Unmapped,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct MarketMappings {
+    #[serde(default, rename = "pair")]
+    pub pins: Vec<MappingRule>,
+    #[serde(default)]
+    pub deny: Vec<MappingRule>,
+}
+
+impl MarketMappings {
+    pub fn parse(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Mappings from `MARKET_MAPPINGS_FILE`, or `None` when there is no file.
+    /// A file that is set but missing, or doesn't parse, is an error.
+    pub fn from_env() -> Result<Option<Self>> {
+        match std::env::var("MARKET_MAPPINGS_FILE").ok().filter(|s| !s.trim().is_empty()) {
+            Some(path) => Self::load(path.trim()).map(Some),
+            None if Path::new(DEFAULT_FILE).exists() => Self::load(DEFAULT_FILE).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn lookup(&self, polymarket: &Event, kalshi: &Event) -> Mapping {
+        if self.deny.iter().any(|rule| rule.matches(polymarket, kalshi)) {
+            return Mapping::Denied;
+        }
+        if self.pins.iter().any(|rule| rule.matches(polymarket, kalshi)) {
+            return Mapping::Pinned;
+        }
+        let pinned = self.pins.iter().any(|rule| {
+            pattern_matches(&rule.polymarket, polymarket) || pattern_matches(&rule.kalshi, kalshi)
+        });
+        if pinned {
+            Mapping::PinnedElsewhere
+        } else {
+            Mapping::Unmapped
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(platform: &str, id: &str, slug: &str) -> Event {
+        Event::new(platform.to_string(), id.to_string(), String::new(), String::new())
+            .with_slug(slug.to_string())
+    }
+
+    #[test]
+    fn pins_and_denies_pairs() {
+        let mappings = MarketMappings::parse(
+            r#"
+            [[pair]]
+            polymarket = "btc-updown-15m-*"
+            kalshi = "KXBTC15M-*"
+
+            [[deny]]
+            polymarket = "0xbad"
+            kalshi = "KXETH15M-26JAN011215"
+            "#,
+        )
+        .unwrap();
+        let pm_btc = event("polymarket", "0xbtc", "btc-updown-15m-1767269700");
+        let pm_bad = event("polymarket", "0xbad", "some-eth-market");
+        let kalshi_btc = event("kalshi", "KXBTC15M-26JAN011215", "KXBTC15M-26JAN011215");
+        let kalshi_eth = event("kalshi", "KXETH15M-26JAN011215", "KXETH15M-26JAN011215");
+
+        assert_eq!(mappings.lookup(&pm_btc, &kalshi_btc), Mapping::Pinned);
+        assert_eq!(mappings.lookup(&pm_btc, &kalshi_eth), Mapping::PinnedElsewhere);
+        assert_eq!(mappings.lookup(&pm_bad, &kalshi_btc), Mapping::PinnedElsewhere);
+        assert_eq!(mappings.lookup(&pm_bad, &kalshi_eth), Mapping::Denied);
+        assert_eq!(
+            mappings.lookup(&event("polymarket", "0xother", "other"), &kalshi_eth),
+            Mapping::Unmapped
+        );
+        assert!(MarketMappings::parse("[[pair]]\npolymarket = 1").is_err());
+    }
+}
@@ -0,0 +1,263 @@
+//! Confirmed Polymarket ↔ Kalshi pairs, persisted across restarts.
+//!
+//! A pair is confirmed once it trades, or by hand with
+//! `confirm-match <polymarket id> <kalshi ticker>`. Confirmed pairs are
+//! matched directly on later scans instead of being re-scored against every
+//! candidate, so a match can't flip between scans. Stored as JSON at
+//! `MATCH_STORE_PATH` (default `state/matches.json`); entries are dropped
+//! after `RETENTION_DAYS`, long after their markets resolve.
+//!
+//! Pairs whose legs settled the same way (both won or both lost) weren't the
+//! same market; they're recorded as denied and `EventMatcher` never matches
+//! them again. Confirming a denied pair by hand lifts the denial.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use tracing::{info, warn};
+
+pub const DEFAULT_MATCH_STORE_PATH: &str = "state/matches.json";
+const RETENTION_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MatchSource {
+    Traded,
+    Manual,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfirmedMatch {
+    pub polymarket_id: String,
+    pub kalshi_id: String,
+    pub source: MatchSource,
+    pub confirmed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeniedMatch {
+    pub polymarket_id: String,
+    pub kalshi_id: String,
+    pub reason: String,
+    pub denied_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum StoredMatches {
+    Current {
+        confirmed: Vec<ConfirmedMatch>,
+        #[serde(default)]
+        denied: Vec<DeniedMatch>,
+    },
+    /// Stores written before denials were recorded: confirmed pairs only.
+    Legacy(Vec<ConfirmedMatch>),
+}
+
+/// Confirmed pairs keyed by Polymarket event id, and denied pairs.
+pub struct MatchStore {
+    matches: RwLock<HashMap<String, ConfirmedMatch>>,
+    denied: RwLock<Vec<DeniedMatch>>,
+    store_path: Option<PathBuf>,
+}
+
+impl Default for MatchStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MatchStore {
+    pub fn new() -> Self {
+        Self {
+            matches: RwLock::new(HashMap::new()),
+            denied: RwLock::new(Vec::new()),
+            store_path: None,
+        }
+    }
+
+    /// Opens a store backed by a JSON file, loading any pairs already in it.
+    pub fn with_store(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut matches = HashMap::new();
+        let mut denied = Vec::new();
+        if path.exists() {
+            let data = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read match store {}", path.display()))?;
+            let stored: StoredMatches = serde_json::from_str(&data)
+                .with_context(|| format!("Failed to parse match store {}", path.display()))?;
+            let confirmed = match stored {
+                StoredMatches::Current { confirmed, denied: stored_denied } => {
+                    denied = stored_denied;
+                    confirmed
+                }
+                StoredMatches::Legacy(confirmed) => confirmed,
+            };
+            for confirmed in confirmed {
+                matches.insert(confirmed.polymarket_id.clone(), confirmed);
+            }
+            info!(
+                "📂 Loaded {} confirmed and {} denied matches from {}",
+                matches.len(),
+                denied.len(),
+                path.display()
+            );
+        }
+        Ok(Self {
+            matches: RwLock::new(matches),
+            denied: RwLock::new(denied),
+            store_path: Some(path),
+        })
+    }
+
+    /// `MATCH_STORE_PATH`, or the default.
+    pub fn path_from_env() -> String {
+        std::env::var("MATCH_STORE_PATH")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_MATCH_STORE_PATH.to_string())
+    }
+
+    /// Kalshi ticker confirmed for a Polymarket event.
+    pub fn kalshi_for(&self, polymarket_id: &str) -> Option<String> {
+        let matches = self.matches.read().unwrap_or_else(|e| e.into_inner());
+        matches.get(polymarket_id).map(|m| m.kalshi_id.clone())
+    }
+
+    /// Whether the pair was denied after settling inconsistently.
+    pub fn is_denied(&self, polymarket_id: &str, kalshi_id: &str) -> bool {
+        let denied = self.denied.read().unwrap_or_else(|e| e.into_inner());
+        denied
+            .iter()
+            .any(|d| d.polymarket_id == polymarket_id && d.kalshi_id == kalshi_id)
+    }
+
+    /// Number of confirmed pairs.
+    pub fn len(&self) -> usize {
+        self.matches.read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Denies a pair (dropping it from the confirmed pairs) and writes the
+    /// store back.
+    pub fn deny(&self, polymarket_id: &str, kalshi_id: &str, reason: &str) {
+        let now = Utc::now();
+        {
+            let mut matches = self.matches.write().unwrap_or_else(|e| e.into_inner());
+            if matches.get(polymarket_id).is_some_and(|m| m.kalshi_id == kalshi_id) {
+                matches.remove(polymarket_id);
+            }
+            let mut denied = self.denied.write().unwrap_or_else(|e| e.into_inner());
+            let cutoff = now - Duration::days(RETENTION_DAYS);
+            denied.retain(|d| d.denied_at >= cutoff);
+            if denied
+                .iter()
+                .any(|d| d.polymarket_id == polymarket_id && d.kalshi_id == kalshi_id)
+            {
+                return;
+            }
+            denied.push(DeniedMatch {
+                polymarket_id: polymarket_id.to_string(),
+                kalshi_id: kalshi_id.to_string(),
+                reason: reason.to_string(),
+                denied_at: now,
+            });
+        }
+        if let Err(e) = self.save() {
+            warn!("Failed to persist denied matches: {}", e);
+        }
+    }
+
+    /// Confirms a pair (replacing any earlier pairing of the Polymarket
+    /// event, and lifting a denial of this one) and writes the store back.
+    pub fn confirm(&self, polymarket_id: &str, kalshi_id: &str, source: MatchSource) {
+        let now = Utc::now();
+        {
+            let mut matches = self.matches.write().unwrap_or_else(|e| e.into_inner());
+            let mut denied = self.denied.write().unwrap_or_else(|e| e.into_inner());
+            let was_denied = denied.len();
+            denied.retain(|d| d.polymarket_id != polymarket_id || d.kalshi_id != kalshi_id);
+            let lifted = denied.len() != was_denied;
+            if !lifted && matches.get(polymarket_id).is_some_and(|m| m.kalshi_id == kalshi_id) {
+                return;
+            }
+            let cutoff = now - Duration::days(RETENTION_DAYS);
+            matches.retain(|_, m| m.confirmed_at >= cutoff);
+            matches.insert(
+                polymarket_id.to_string(),
+                ConfirmedMatch {
+                    polymarket_id: polymarket_id.to_string(),
+                    kalshi_id: kalshi_id.to_string(),
+                    source,
+                    confirmed_at: now,
+                },
+            );
+        }
+        if let Err(e) = self.save() {
+            warn!("Failed to persist confirmed matches: {}", e);
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.store_path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let data = {
+            let matches = self.matches.read().unwrap_or_else(|e| e.into_inner());
+            let mut confirmed: Vec<ConfirmedMatch> = matches.values().cloned().collect();
+            confirmed.sort_by_key(|m| m.confirmed_at);
+            let denied = self.denied.read().unwrap_or_else(|e| e.into_inner()).clone();
+            serde_json::to_string_pretty(&StoredMatches::Current { confirmed, denied })?
+        };
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, data).with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confirmed_matches_survive_a_restart() {
+        let path = std::env::temp_dir().join(format!("matches-{}.json", uuid::Uuid::new_v4()));
+        let store = MatchStore::with_store(&path).unwrap();
+        store.confirm("0xpm", "KXBTC15M-A", MatchSource::Traded);
+        store.confirm("0xpm", "KXBTC15M-B", MatchSource::Manual);
+
+        let reopened = MatchStore::with_store(&path).unwrap();
+        assert_eq!(reopened.len(), 1);
+        assert_eq!(reopened.kalshi_for("0xpm").as_deref(), Some("KXBTC15M-B"));
+        assert_eq!(reopened.kalshi_for("0xother"), None);
+
+        reopened.deny("0xpm", "KXBTC15M-B", "both legs lost");
+        let reopened = MatchStore::with_store(&path).unwrap();
+        assert!(reopened.is_denied("0xpm", "KXBTC15M-B"));
+        assert_eq!(reopened.kalshi_for("0xpm"), None);
+        reopened.confirm("0xpm", "KXBTC15M-B", MatchSource::Manual);
+        assert!(!reopened.is_denied("0xpm", "KXBTC15M-B"));
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn reads_stores_written_before_denials() {
+        let path = std::env::temp_dir().join(format!("matches-{}.json", uuid::Uuid::new_v4()));
+        let legacy = r#"[{"polymarket_id": "0xpm", "kalshi_id": "KXBTC15M-A", "source": "Traded", "confirmed_at": "2026-01-01T00:00:00Z"}]"#;
+        fs::write(&path, legacy).unwrap();
+        let store = MatchStore::with_store(&path).unwrap();
+        assert_eq!(store.kalshi_for("0xpm").as_deref(), Some("KXBTC15M-A"));
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,342 @@
+//! Process-wide counters, gauges and histograms in Prometheus text format.
+//!
+//! Both venue clients record price-cache size and hits/misses, HTTP requests
+//! by endpoint and status, latency histograms and errors per endpoint and
+//! per client method, and in-flight requests; the executor records retries.
+//! reqwest does not expose its connection pool, so `http_in_flight_requests`
+//! against `http_pool_max_idle` is the pool utilization signal. Served on `METRICS_ADDR` (e.g. `0.0.0.0:9100`),
+//! alongside `/healthz` (see `health`).
+
+use crate::health::health;
+use crate::rate_limit::{pacing, MAX_RETRY_WAIT};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+
+/// Upper bounds (seconds) of the latency histogram buckets.
+pub const LATENCY_BUCKETS: [f64; 11] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 20.0, 30.0, 60.0];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Histogram {
+    /// Observations per bucket (not cumulative), one per `LATENCY_BUCKETS` bound.
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+#[derive(Default)]
+pub struct Metrics {
+    counters: Mutex<BTreeMap<String, BTreeMap<String, f64>>>,
+    gauges: Mutex<BTreeMap<String, BTreeMap<String, f64>>>,
+    histograms: Mutex<BTreeMap<String, BTreeMap<String, Histogram>>>,
+}
+
+fn label_key(labels: &[(&str, &str)]) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let pairs: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, v.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect();
+    format!("{{{}}}", pairs.join(","))
+}
+
+/// `labels` (as from `label_key`) with `le` added.
+fn with_le(labels: &str, le: &str) -> String {
+    match labels.strip_suffix('}') {
+        Some(inner) => format!("{},le=\"{}\"}}", inner, le),
+        None => format!("{{le=\"{}\"}}", le),
+    }
+}
+
+fn update(
+    series: &Mutex<BTreeMap<String, BTreeMap<String, f64>>>,
+    name: &str,
+    labels: &[(&str, &str)],
+    f: impl FnOnce(&mut f64),
+) {
+    if let Ok(mut series) = series.lock() {
+        f(series
+            .entry(name.to_string())
+            .or_default()
+            .entry(label_key(labels))
+            .or_insert(0.0));
+    }
+}
+
+fn read(
+    series: &Mutex<BTreeMap<String, BTreeMap<String, f64>>>,
+    name: &str,
+    labels: &[(&str, &str)],
+) -> f64 {
+    series
+        .lock()
+        .ok()
+        .and_then(|s| s.get(name)?.get(&label_key(labels)).copied())
+        .unwrap_or(0.0)
+}
+
+impl Metrics {
+    pub fn add_counter(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        update(&self.counters, name, labels, |v| *v += value);
+    }
+
+    pub fn inc_counter(&self, name: &str, labels: &[(&str, &str)]) {
+        self.add_counter(name, labels, 1.0);
+    }
+
+    pub fn set_gauge(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        update(&self.gauges, name, labels, |v| *v = value);
+    }
+
+    pub fn add_gauge(&self, name: &str, labels: &[(&str, &str)], delta: f64) {
+        update(&self.gauges, name, labels, |v| *v += delta);
+    }
+
+    /// Records one latency observation, in seconds.
+    pub fn observe(&self, name: &str, labels: &[(&str, &str)], seconds: f64) {
+        let Ok(mut histograms) = self.histograms.lock() else { return };
+        let histogram = histograms
+            .entry(name.to_string())
+            .or_default()
+            .entry(label_key(labels))
+            .or_default();
+        if histogram.buckets.is_empty() {
+            histogram.buckets = vec![0; LATENCY_BUCKETS.len()];
+        }
+        if let Some(i) = LATENCY_BUCKETS.iter().position(|bound| seconds <= *bound) {
+            histogram.buckets[i] += 1;
+        }
+        histogram.sum += seconds;
+        histogram.count += 1;
+    }
+
+    /// Observations recorded in a histogram.
+    pub fn observations(&self, name: &str, labels: &[(&str, &str)]) -> u64 {
+        self.histograms
+            .lock()
+            .ok()
+            .and_then(|h| Some(h.get(name)?.get(&label_key(labels))?.count))
+            .unwrap_or(0)
+    }
+
+    pub fn counter(&self, name: &str, labels: &[(&str, &str)]) -> f64 {
+        read(&self.counters, name, labels)
+    }
+
+    pub fn gauge(&self, name: &str, labels: &[(&str, &str)]) -> f64 {
+        read(&self.gauges, name, labels)
+    }
+
+    /// Prometheus text exposition of every series.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for (kind, series) in [("counter", &self.counters), ("gauge", &self.gauges)] {
+            let Ok(series) = series.lock() else { continue };
+            for (name, values) in series.iter() {
+                out.push_str(&format!("# TYPE {} {}\n", name, kind));
+                for (labels, value) in values {
+                    out.push_str(&format!("{}{} {}\n", name, labels, value));
+                }
+            }
+        }
+        if let Ok(histograms) = self.histograms.lock() {
+            for (name, values) in histograms.iter() {
+                out.push_str(&format!("# TYPE {} histogram\n", name));
+                for (labels, histogram) in values {
+                    let mut cumulative = 0;
+                    for (bound, count) in LATENCY_BUCKETS.iter().zip(&histogram.buckets) {
+                        cumulative += count;
+                        let le = with_le(labels, &bound.to_string());
+                        out.push_str(&format!("{}_bucket{} {}\n", name, le, cumulative));
+                    }
+                    let inf = with_le(labels, "+Inf");
+                    out.push_str(&format!("{}_bucket{} {}\n", name, inf, histogram.count));
+                    out.push_str(&format!("{}_sum{} {}\n", name, labels, histogram.sum));
+                    out.push_str(&format!("{}_count{} {}\n", name, labels, histogram.count));
+                }
+            }
+        }
+        out
+    }
+}
+
+/// The process-wide registry.
+pub fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(Metrics::default)
+}
+
+/// Error class of a failed request: `timeout`, `connect`, `transport`, or
+/// `4xx`/`5xx` for an error status.
+fn error_kind(result: &reqwest::Result<reqwest::Response>) -> Option<&'static str> {
+    match result {
+        Ok(response) if response.status().is_client_error() => Some("4xx"),
+        Ok(response) if response.status().is_server_error() => Some("5xx"),
+        Ok(_) => None,
+        Err(e) if e.is_timeout() => Some("timeout"),
+        Err(e) if e.is_connect() => Some("connect"),
+        Err(_) => Some("transport"),
+    }
+}
+
+/// Records one HTTP request: in-flight gauge while it runs, then count by
+/// status (or `error`), a latency histogram and errors by kind. The request
+/// waits for the venue's adaptive pacing and its response feeds it.
+pub async fn track_http<F>(venue: &str, endpoint: &str, request: F) -> reqwest::Result<reqwest::Response>
+where
+    F: Future<Output = reqwest::Result<reqwest::Response>>,
+{
+    pacing().wait(venue).await;
+    let m = metrics();
+    m.add_gauge("http_in_flight_requests", &[("venue", venue)], 1.0);
+    let started = Instant::now();
+    let result = request.await;
+    m.add_gauge("http_in_flight_requests", &[("venue", venue)], -1.0);
+
+    let status = match &result {
+        Ok(response) => {
+            pacing().observe(venue, response.status(), response.headers());
+            if response.status().is_success() {
+                health().record_api_success(venue);
+            }
+            response.status().as_u16().to_string()
+        }
+        Err(_) => "error".to_string(),
+    };
+    m.inc_counter(
+        "http_requests_total",
+        &[("venue", venue), ("endpoint", endpoint), ("status", &status)],
+    );
+    m.observe(
+        "http_request_seconds",
+        &[("venue", venue), ("endpoint", endpoint)],
+        started.elapsed().as_secs_f64(),
+    );
+    if let Some(kind) = error_kind(&result) {
+        m.inc_counter(
+            "http_request_errors_total",
+            &[("venue", venue), ("endpoint", endpoint), ("kind", kind)],
+        );
+    }
+    result
+}
+
+/// Times one client method end to end (pagination, retries and parsing
+/// included): `client_call_seconds` histogram and `client_call_errors_total`.
+pub async fn track_call<T, F>(venue: &str, method: &str, call: F) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    let started = Instant::now();
+    let result = call.await;
+    let labels = [("venue", venue), ("method", method)];
+    metrics().observe("client_call_seconds", &labels, started.elapsed().as_secs_f64());
+    if result.is_err() {
+        metrics().inc_counter("client_call_errors_total", &labels);
+    }
+    result
+}
+
+/// `.send_tracked(venue, endpoint)` in place of `.send()` runs the request
+/// through `track_http`, and once more after a short rate-limit hold.
+/// `endpoint` is the path template, ids elided.
+#[async_trait]
+pub trait TrackedSend {
+    async fn send_tracked(self, venue: &str, endpoint: &str) -> reqwest::Result<reqwest::Response>;
+}
+
+#[async_trait]
+impl TrackedSend for reqwest::RequestBuilder {
+    async fn send_tracked(self, venue: &str, endpoint: &str) -> reqwest::Result<reqwest::Response> {
+        let retry = self.try_clone();
+        let response = track_http(venue, endpoint, self.send()).await?;
+        match retry {
+            Some(retry)
+                if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    && pacing().held_for(venue) <= MAX_RETRY_WAIT =>
+            {
+                track_http(venue, endpoint, retry.send()).await
+            }
+            _ => Ok(response),
+        }
+    }
+}
+
+/// Serves `GET /healthz`, and `GET /metrics` on any other path, on `addr`
+/// until the process exits.
+pub async fn serve(addr: &str) -> Result<()> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Failed to bind metrics listener on {}", addr))?;
+    info!("📈 Metrics on http://{}/metrics", addr);
+    loop {
+        let (mut socket, _) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!("Metrics accept failed: {}", e);
+                continue;
+            }
+        };
+        tokio::spawn(async move {
+            let mut request = [0u8; 1024];
+            let read = socket.read(&mut request).await.unwrap_or(0);
+            let path = String::from_utf8_lossy(&request[..read])
+                .split_whitespace()
+                .nth(1)
+                .unwrap_or("/")
+                .to_string();
+            let (status, content_type, body) = if path.starts_with("/healthz") {
+                let report = health().report();
+                let status = if report.healthy { "200 OK" } else { "503 Service Unavailable" };
+                let body = serde_json::to_string(&report).unwrap_or_default();
+                (status, "application/json", body)
+            } else {
+                ("200 OK", "text/plain; version=0.0.4", metrics().render())
+            };
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                content_type,
+                body.len(),
+                body
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_labelled_series() {
+        let m = Metrics::default();
+        m.inc_counter("price_cache_hits_total", &[("venue", "kalshi")]);
+        m.inc_counter("price_cache_hits_total", &[("venue", "kalshi")]);
+        m.set_gauge("price_cache_entries", &[("venue", "polymarket")], 12.0);
+
+        assert_eq!(m.counter("price_cache_hits_total", &[("venue", "kalshi")]), 2.0);
+        let text = m.render();
+        assert!(text.contains("# TYPE price_cache_hits_total counter\n"));
+        assert!(text.contains("price_cache_hits_total{venue=\"kalshi\"} 2\n"));
+        assert!(text.contains("price_cache_entries{venue=\"polymarket\"} 12\n"));
+
+        m.observe("http_request_seconds", &[("venue", "polymarket")], 0.2);
+        m.observe("http_request_seconds", &[("venue", "polymarket")], 12.0);
+        let text = m.render();
+        assert!(text.contains("# TYPE http_request_seconds histogram\n"));
+        assert!(text.contains("http_request_seconds_bucket{venue=\"polymarket\",le=\"0.25\"} 1\n"));
+        assert!(text.contains("http_request_seconds_bucket{venue=\"polymarket\",le=\"20\"} 2\n"));
+        assert!(text.contains("http_request_seconds_bucket{venue=\"polymarket\",le=\"+Inf\"} 2\n"));
+        assert!(text.contains("http_request_seconds_count{venue=\"polymarket\"} 2\n"));
+    }
+}
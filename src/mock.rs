@@ -0,0 +1,484 @@
+//! Scriptable in-memory exchange clients for tests and paper runs.
+//!
+//! `MockPolymarketClient` and `MockKalshiClient` implement `ExchangeClient`
+//! over a shared `MockExchange` whose events, books, fills, latency,
+//! failures, outages and settlements are set by the test.
+
+use crate::event::{Event, MarketPrices};
+use crate::exchange::{ExchangeClient, Fill, OrderFill, OrderSide, Resolution, SettlementRecord, TimeInForce};
+use crate::money::Price;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, VecDeque};
+use std::ops::Deref;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct MockOrder {
+    pub order_id: String,
+    pub event_id: String,
+    pub outcome: String,
+    pub side: OrderSide,
+    /// Dollars spent (buys) or received (sells) when filled.
+    pub amount: f64,
+    pub shares: f64,
+    pub price: f64,
+    pub filled: bool,
+    /// Sells always go out immediate-or-cancel.
+    pub tif: TimeInForce,
+}
+
+#[derive(Default)]
+struct MockState {
+    events: Vec<Event>,
+    books: HashMap<String, MarketPrices>,
+    settlements: HashMap<String, Resolution>,
+    settlement_records: Vec<SettlementRecord>,
+    fee_per_share: f64,
+    balance: f64,
+    latency: Duration,
+    order_failures: VecDeque<String>,
+    fetch_failures: VecDeque<String>,
+    outages: Vec<(Instant, Instant)>,
+    orders: Vec<MockOrder>,
+    next_order_id: u64,
+}
+
+pub struct MockExchange {
+    platform: &'static str,
+    state: Mutex<MockState>,
+}
+
+impl MockExchange {
+    pub fn new(platform: &'static str) -> Self {
+        Self {
+            platform,
+            state: Mutex::new(MockState {
+                balance: 10_000.0,
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn set_events(&self, events: Vec<Event>) {
+        self.state().events = events;
+    }
+
+    pub fn set_prices(&self, event_id: &str, prices: MarketPrices) {
+        self.state().books.insert(event_id.to_string(), prices);
+    }
+
+    pub fn set_settlement(&self, event_id: &str, yes_won: bool) {
+        let resolution = if yes_won { Resolution::Yes } else { Resolution::No };
+        self.set_resolution(event_id, resolution);
+    }
+
+    pub fn set_resolution(&self, event_id: &str, resolution: Resolution) {
+        self.state().settlements.insert(event_id.to_string(), resolution);
+    }
+
+    /// Reports `record` from `fetch_settlement_records`.
+    pub fn add_settlement_record(&self, record: SettlementRecord) {
+        self.state().settlement_records.push(record);
+    }
+
+    /// Fee charged per filled share, reported on fills.
+    pub fn set_fee_per_share(&self, fee: f64) {
+        self.state().fee_per_share = fee;
+    }
+
+    pub fn set_balance(&self, balance: f64) {
+        self.state().balance = balance;
+    }
+
+    pub fn set_latency(&self, latency: Duration) {
+        self.state().latency = latency;
+    }
+
+    /// The next `count` order placements fail with `message`.
+    pub fn fail_next_orders(&self, count: usize, message: &str) {
+        let mut state = self.state();
+        for _ in 0..count {
+            state.order_failures.push_back(message.to_string());
+        }
+    }
+
+    /// The next `count` price fetches fail with `message`.
+    pub fn fail_next_fetches(&self, count: usize, message: &str) {
+        let mut state = self.state();
+        for _ in 0..count {
+            state.fetch_failures.push_back(message.to_string());
+        }
+    }
+
+    /// Every call fails with "venue unavailable" from `starts_in` from now
+    /// for `lasts`, e.g. the venue going down between the two legs of a trade.
+    pub fn schedule_outage(&self, starts_in: Duration, lasts: Duration) {
+        let start = Instant::now() + starts_in;
+        self.state().outages.push((start, start + lasts));
+    }
+
+    pub fn orders(&self) -> Vec<MockOrder> {
+        self.state().orders.clone()
+    }
+
+    pub fn balance(&self) -> f64 {
+        self.state().balance
+    }
+
+    /// Net filled shares held in one outcome of a market.
+    pub fn exposure(&self, event_id: &str, outcome: &str) -> f64 {
+        self.net_exposure()
+            .get(&(event_id.to_string(), outcome.to_uppercase()))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Net filled shares per (event id, outcome), omitting flat holdings.
+    pub fn net_exposure(&self) -> HashMap<(String, String), f64> {
+        let mut exposure: HashMap<(String, String), f64> = HashMap::new();
+        for order in self.state().orders.iter().filter(|o| o.filled) {
+            let shares = match order.side {
+                OrderSide::Buy => order.shares,
+                OrderSide::Sell => -order.shares,
+            };
+            *exposure
+                .entry((order.event_id.clone(), order.outcome.to_uppercase()))
+                .or_default() += shares;
+        }
+        exposure.retain(|_, shares| shares.abs() > 1e-9);
+        exposure
+    }
+
+    /// Panics unless the venue holds nothing.
+    pub fn assert_flat(&self) {
+        let exposure = self.net_exposure();
+        assert!(
+            exposure.is_empty(),
+            "{} expected flat, holds {:?}",
+            self.platform,
+            exposure
+        );
+    }
+
+    /// Panics unless the venue holds exactly `shares` of one outcome and
+    /// nothing else.
+    pub fn assert_residual(&self, event_id: &str, outcome: &str, shares: f64) {
+        let exposure = self.net_exposure();
+        let held = self.exposure(event_id, outcome);
+        assert!(
+            (held - shares).abs() < 1e-6 && exposure.len() == 1,
+            "{} expected residual {:.4} {} {}, holds {:?}",
+            self.platform,
+            shares,
+            outcome,
+            event_id,
+            exposure
+        );
+    }
+
+    /// Errors while a scheduled outage is in progress.
+    fn ensure_up(&self) -> Result<()> {
+        let now = Instant::now();
+        if self
+            .state()
+            .outages
+            .iter()
+            .any(|(start, end)| now >= *start && now < *end)
+        {
+            return Err(anyhow::anyhow!("{} venue unavailable", self.platform));
+        }
+        Ok(())
+    }
+
+    async fn simulate_latency(&self) {
+        let latency = self.state().latency;
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+    }
+
+}
+
+#[async_trait]
+impl ExchangeClient for MockExchange {
+    fn platform(&self) -> &'static str {
+        self.platform
+    }
+
+    async fn fetch_events(&self) -> Result<Vec<Event>> {
+        self.simulate_latency().await;
+        self.ensure_up()?;
+        Ok(self.state().events.clone())
+    }
+
+    async fn fetch_prices(&self, event_id: &str) -> Result<MarketPrices> {
+        self.simulate_latency().await;
+        self.ensure_up()?;
+        let mut state = self.state();
+        if let Some(message) = state.fetch_failures.pop_front() {
+            return Err(anyhow::anyhow!(message));
+        }
+        state
+            .books
+            .get(event_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No mock book for {}", event_id))
+    }
+
+    /// Fills immediately when the limit price crosses the mocked ask and the
+    /// balance covers it; otherwise the order goes unfilled (resting, if GTC).
+    /// A post-only order that would fill is rejected instead.
+    async fn place_order(
+        &self,
+        event_id: String,
+        outcome: String,
+        amount: f64,
+        price: f64,
+        tif: TimeInForce,
+    ) -> Result<Option<String>> {
+        self.simulate_latency().await;
+        self.ensure_up()?;
+        let mut state = self.state();
+        if let Some(message) = state.order_failures.pop_front() {
+            return Err(anyhow::anyhow!(message));
+        }
+        if amount > state.balance {
+            return Err(anyhow::anyhow!(
+                "Insufficient balance: {:.2} < {:.2}",
+                state.balance,
+                amount
+            ));
+        }
+
+        let filled = state
+            .books
+            .get(&event_id)
+            .map(|book| Price::from_f64(price) >= book.ask_for(&outcome))
+            .unwrap_or(false);
+        if filled && tif.is_post_only() {
+            return Err(anyhow::anyhow!("Post-only order would cross the book"));
+        }
+        if filled {
+            state.balance -= amount;
+        }
+
+        state.next_order_id += 1;
+        let order_id = format!("{}-mock-{}", self.platform, state.next_order_id);
+        state.orders.push(MockOrder {
+            order_id: order_id.clone(),
+            event_id,
+            outcome,
+            side: OrderSide::Buy,
+            amount,
+            shares: amount / price,
+            price,
+            filled,
+            tif,
+        });
+        Ok(Some(order_id))
+    }
+
+    /// Fills when the implied bid is at or above `min_price` and the shares
+    /// are held; otherwise it is cancelled unfilled, like the venues' sells.
+    async fn sell_order(
+        &self,
+        event_id: String,
+        outcome: String,
+        shares: f64,
+        min_price: f64,
+    ) -> Result<Option<String>> {
+        self.simulate_latency().await;
+        self.ensure_up()?;
+        let held = self.exposure(&event_id, &outcome);
+        let mut state = self.state();
+        if let Some(message) = state.order_failures.pop_front() {
+            return Err(anyhow::anyhow!(message));
+        }
+        if shares > held + 1e-9 {
+            return Err(anyhow::anyhow!(
+                "Cannot sell {:.4} {} shares, holding {:.4}",
+                shares,
+                outcome,
+                held
+            ));
+        }
+
+        let filled = state
+            .books
+            .get(&event_id)
+            .map(|book| Price::from_f64(min_price) <= book.bid_for(&outcome))
+            .unwrap_or(false);
+        let amount = shares * min_price;
+        if filled {
+            state.balance += amount;
+        }
+
+        state.next_order_id += 1;
+        let order_id = format!("{}-mock-{}", self.platform, state.next_order_id);
+        state.orders.push(MockOrder {
+            order_id: order_id.clone(),
+            event_id,
+            outcome,
+            side: OrderSide::Sell,
+            amount,
+            shares,
+            price: min_price,
+            filled,
+            tif: TimeInForce::Ioc,
+        });
+        Ok(Some(order_id))
+    }
+
+    /// Filled orders report all their shares at their limit; unfilled GTC
+    /// and post-only orders are still working, the rest are done.
+    async fn order_fill(&self, _event_id: &str, order_id: &str) -> Result<OrderFill> {
+        self.simulate_latency().await;
+        self.ensure_up()?;
+        let state = self.state();
+        let order = state
+            .orders
+            .iter()
+            .find(|o| o.order_id == order_id)
+            .ok_or_else(|| anyhow::anyhow!("No mock order {}", order_id))?;
+        Ok(if order.filled {
+            OrderFill { shares: order.shares, price: order.price, done: true }
+        } else {
+            OrderFill {
+                done: !matches!(order.tif, TimeInForce::Gtc { .. } | TimeInForce::PostOnly { .. }),
+                ..Default::default()
+            }
+        })
+    }
+
+    async fn check_settlement(&self, event_id: &str) -> Result<Option<Resolution>> {
+        self.simulate_latency().await;
+        self.ensure_up()?;
+        Ok(self.state().settlements.get(event_id).copied())
+    }
+
+    async fn fetch_settlement_records(&self, _since: DateTime<Utc>) -> Result<Vec<SettlementRecord>> {
+        self.simulate_latency().await;
+        self.ensure_up()?;
+        Ok(self.state().settlement_records.clone())
+    }
+
+    async fn fetch_fills(&self, market: &str) -> Result<Vec<Fill>> {
+        self.simulate_latency().await;
+        self.ensure_up()?;
+        let state = self.state();
+        Ok(state
+            .orders
+            .iter()
+            .filter(|o| o.filled && o.side == OrderSide::Buy && o.event_id == market)
+            .map(|o| Fill {
+                order_id: o.order_id.clone(),
+                outcome: o.outcome.clone(),
+                shares: o.shares,
+                cost: o.amount,
+                fees: o.shares * state.fee_per_share,
+            })
+            .collect())
+    }
+
+    async fn get_balance(&self) -> Result<f64> {
+        self.simulate_latency().await;
+        self.ensure_up()?;
+        Ok(self.state().balance)
+    }
+}
+
+macro_rules! mock_client {
+    ($name:ident, $platform:literal) => {
+        pub struct $name(MockExchange);
+
+        impl $name {
+            pub fn new() -> Self {
+                Self(MockExchange::new($platform))
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl Deref for $name {
+            type Target = MockExchange;
+
+            fn deref(&self) -> &MockExchange {
+                &self.0
+            }
+        }
+
+        #[async_trait]
+        impl ExchangeClient for $name {
+            fn platform(&self) -> &'static str {
+                self.0.platform()
+            }
+
+            async fn fetch_events(&self) -> Result<Vec<Event>> {
+                self.0.fetch_events().await
+            }
+
+            async fn fetch_markets(&self) -> Result<Vec<Event>> {
+                self.0.fetch_markets().await
+            }
+
+            async fn fetch_prices(&self, event_id: &str) -> Result<MarketPrices> {
+                self.0.fetch_prices(event_id).await
+            }
+
+            async fn place_order(
+                &self,
+                event_id: String,
+                outcome: String,
+                amount: f64,
+                price: f64,
+                tif: TimeInForce,
+            ) -> Result<Option<String>> {
+                self.0.place_order(event_id, outcome, amount, price, tif).await
+            }
+
+            async fn sell_order(
+                &self,
+                event_id: String,
+                outcome: String,
+                shares: f64,
+                min_price: f64,
+            ) -> Result<Option<String>> {
+                self.0.sell_order(event_id, outcome, shares, min_price).await
+            }
+
+            async fn order_fill(&self, event_id: &str, order_id: &str) -> Result<OrderFill> {
+                self.0.order_fill(event_id, order_id).await
+            }
+
+            async fn check_settlement(&self, event_id: &str) -> Result<Option<Resolution>> {
+                self.0.check_settlement(event_id).await
+            }
+
+            async fn fetch_settlement_records(&self, since: DateTime<Utc>) -> Result<Vec<SettlementRecord>> {
+                self.0.fetch_settlement_records(since).await
+            }
+
+            async fn fetch_fills(&self, market: &str) -> Result<Vec<Fill>> {
+                self.0.fetch_fills(market).await
+            }
+
+            async fn get_balance(&self) -> Result<f64> {
+                self.0.get_balance().await
+            }
+        }
+    };
+}
+
+mock_client!(MockPolymarketClient, "polymarket");
+mock_client!(MockKalshiClient, "kalshi");
@@ -0,0 +1,363 @@
+//! Fixed-point money types.
+//!
+//! Venue APIs and most of the bot speak `f64`, but sums like
+//! `0.45 + 0.40` or `0.29 * 100` must come out exact: `0.29 * 100` is
+//! `28.999999999999996` in `f64` and truncates to a 28-cent Kalshi order, and
+//! a cost that sums to `0.8500000000000001` misses an opportunity sitting
+//! exactly on the threshold. `Price` and `UsdAmount` wrap
+//! `rust_decimal::Decimal`; convert at the edges with `from_f64` / `to_f64`
+//! and do the arithmetic in between on these types. Both serialize as plain
+//! numbers, so stored state and CSV columns keep their `f64` format.
+
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+/// Decimal places kept when converting from `f64`; venues quote at most 4.
+const DECIMAL_PLACES: u32 = 6;
+
+/// USDC and CTF outcome tokens both carry 6 decimals on-chain.
+const TOKEN_DECIMALS: u32 = 6;
+
+fn from_f64(value: f64) -> Decimal {
+    Decimal::from_f64(value)
+        .unwrap_or_default()
+        .round_dp(DECIMAL_PLACES)
+}
+
+/// `numerator / denominator` as a plain ratio; zero over zero.
+fn ratio(numerator: Decimal, denominator: Decimal) -> f64 {
+    if denominator.is_zero() {
+        return 0.0;
+    }
+    (numerator / denominator).to_f64().unwrap_or_default()
+}
+
+/// Price of one share in dollars, between 0 and 1 for binary markets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(from = "f64", into = "f64")]
+pub struct Price(Decimal);
+
+impl Price {
+    pub const ZERO: Price = Price(Decimal::ZERO);
+    pub const ONE: Price = Price(Decimal::ONE);
+
+    pub const fn new(value: Decimal) -> Self {
+        Price(value)
+    }
+
+    pub fn from_f64(value: f64) -> Self {
+        Price(from_f64(value))
+    }
+
+    pub fn from_cents(cents: i64) -> Self {
+        Price(Decimal::new(cents, 2))
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0.to_f64().unwrap_or_default()
+    }
+
+    pub fn value(self) -> Decimal {
+        self.0
+    }
+
+    /// Nearest whole cent (half away from zero).
+    pub fn cents(self) -> i64 {
+        (self.0 * Decimal::ONE_HUNDRED)
+            .round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero)
+            .to_i64()
+            .unwrap_or_default()
+    }
+
+    /// The other side of a binary book: `1 - price`.
+    pub fn complement(self) -> Price {
+        Price(Decimal::ONE - self.0)
+    }
+
+    /// Rounded down to the cent.
+    pub fn floor_cents(self) -> Price {
+        Price(self.0.round_dp_with_strategy(2, RoundingStrategy::ToNegativeInfinity))
+    }
+
+    /// `self / other` as a plain ratio, e.g. a return on cost.
+    pub fn ratio(self, other: Price) -> f64 {
+        ratio(self.0, other.0)
+    }
+
+    pub fn abs(self) -> Price {
+        Price(self.0.abs())
+    }
+}
+
+impl From<f64> for Price {
+    fn from(value: f64) -> Self {
+        Price::from_f64(value)
+    }
+}
+
+impl From<Price> for f64 {
+    fn from(price: Price) -> Self {
+        price.to_f64()
+    }
+}
+
+impl Add for Price {
+    type Output = Price;
+
+    fn add(self, rhs: Price) -> Price {
+        Price(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Price {
+    fn add_assign(&mut self, rhs: Price) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for Price {
+    type Output = Price;
+
+    fn sub(self, rhs: Price) -> Price {
+        Price(self.0 - rhs.0)
+    }
+}
+
+impl Sum for Price {
+    fn sum<I: Iterator<Item = Price>>(iter: I) -> Price {
+        iter.fold(Price::ZERO, Add::add)
+    }
+}
+
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "${}", self.0.normalize())
+    }
+}
+
+/// A dollar amount: a cost, proceeds or profit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(from = "f64", into = "f64")]
+pub struct UsdAmount(Decimal);
+
+impl UsdAmount {
+    pub const ZERO: UsdAmount = UsdAmount(Decimal::ZERO);
+
+    pub fn new(value: Decimal) -> Self {
+        UsdAmount(value)
+    }
+
+    pub fn from_f64(value: f64) -> Self {
+        UsdAmount(from_f64(value))
+    }
+
+    pub fn from_cents(cents: i64) -> Self {
+        UsdAmount(Decimal::new(cents, 2))
+    }
+
+    /// An on-chain USDC amount in its 6-decimal base units.
+    pub fn from_usdc_units(units: u128) -> Self {
+        UsdAmount(Decimal::from_i128_with_scale(units as i128, TOKEN_DECIMALS))
+    }
+
+    /// USDC base units, rounded to the nearest; zero for negative amounts.
+    pub fn usdc_units(self) -> u128 {
+        (self.0 * Decimal::from(10u64.pow(TOKEN_DECIMALS)))
+            .round()
+            .to_u128()
+            .unwrap_or_default()
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0.to_f64().unwrap_or_default()
+    }
+
+    pub fn value(self) -> Decimal {
+        self.0
+    }
+
+    /// Cost of `shares` at `price`.
+    pub fn for_shares(shares: f64, price: Price) -> Self {
+        UsdAmount(from_f64(shares) * price.0)
+    }
+
+    /// Shares this amount buys at `price`.
+    pub fn shares_at(self, price: Price) -> f64 {
+        if price.0.is_zero() {
+            return 0.0;
+        }
+        (self.0 / price.0).to_f64().unwrap_or_default()
+    }
+
+    /// Whole contracts this amount buys at `price`, rounded down.
+    pub fn contracts_at(self, price: Price) -> i64 {
+        if price.0.is_zero() {
+            return 0;
+        }
+        (self.0 / price.0).floor().to_i64().unwrap_or_default()
+    }
+
+    pub fn round_cents(self) -> Self {
+        UsdAmount(self.0.round_dp(2))
+    }
+
+    /// Rounded up to the cent.
+    pub fn ceil_cents(self) -> Self {
+        UsdAmount(self.0.round_dp_with_strategy(2, RoundingStrategy::ToPositiveInfinity))
+    }
+
+    /// The share of this amount that `part` of `whole` shares carries.
+    pub fn pro_rata(self, part: f64, whole: f64) -> Self {
+        let whole = from_f64(whole);
+        if whole.is_zero() {
+            return UsdAmount::ZERO;
+        }
+        UsdAmount(self.0 * from_f64(part) / whole)
+    }
+
+    /// The `part / whole` portion of this amount, e.g. one leg's share of a
+    /// pair's proceeds by its price.
+    pub fn portion(self, part: Price, whole: Price) -> Self {
+        if whole.0.is_zero() {
+            return UsdAmount::ZERO;
+        }
+        UsdAmount(self.0 * part.0 / whole.0)
+    }
+
+    /// Price per share of this amount spread over `shares`.
+    pub fn per_share(self, shares: f64) -> Price {
+        let shares = from_f64(shares);
+        if shares.is_zero() {
+            return Price::ZERO;
+        }
+        Price(self.0 / shares)
+    }
+
+    /// `self / other` as a plain ratio, e.g. a return on cost.
+    pub fn ratio(self, other: UsdAmount) -> f64 {
+        ratio(self.0, other.0)
+    }
+
+    pub fn abs(self) -> UsdAmount {
+        UsdAmount(self.0.abs())
+    }
+}
+
+impl From<f64> for UsdAmount {
+    fn from(value: f64) -> Self {
+        UsdAmount::from_f64(value)
+    }
+}
+
+impl From<UsdAmount> for f64 {
+    fn from(amount: UsdAmount) -> Self {
+        amount.to_f64()
+    }
+}
+
+impl Neg for UsdAmount {
+    type Output = UsdAmount;
+
+    fn neg(self) -> UsdAmount {
+        UsdAmount(-self.0)
+    }
+}
+
+impl SubAssign for UsdAmount {
+    fn sub_assign(&mut self, rhs: UsdAmount) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Add for UsdAmount {
+    type Output = UsdAmount;
+
+    fn add(self, rhs: UsdAmount) -> UsdAmount {
+        UsdAmount(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for UsdAmount {
+    fn add_assign(&mut self, rhs: UsdAmount) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for UsdAmount {
+    type Output = UsdAmount;
+
+    fn sub(self, rhs: UsdAmount) -> UsdAmount {
+        UsdAmount(self.0 - rhs.0)
+    }
+}
+
+impl Sum for UsdAmount {
+    fn sum<I: Iterator<Item = UsdAmount>>(iter: I) -> UsdAmount {
+        iter.fold(UsdAmount::ZERO, Add::add)
+    }
+}
+
+impl fmt::Display for UsdAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "${:.2}", self.0)
+    }
+}
+
+/// Exact sum of dollar amounts held as `f64`.
+pub fn sum_usd(values: impl IntoIterator<Item = f64>) -> f64 {
+    values
+        .into_iter()
+        .map(UsdAmount::from_f64)
+        .sum::<UsdAmount>()
+        .to_f64()
+}
+
+/// Outcome-token base units in a share count, rounded down after dropping
+/// `f64` noise so a merge never asks for more than is held.
+pub fn share_units(shares: f64) -> u128 {
+    (from_f64(shares) * Decimal::from(10u64.pow(TOKEN_DECIMALS)))
+        .floor()
+        .to_u128()
+        .unwrap_or_default()
+}
+
+/// Whole contracts in a share count, rounded down after dropping `f64`
+/// noise, so `249.99999999999997` shares sell as 250 contracts.
+pub fn whole_contracts(shares: f64) -> i64 {
+    from_f64(shares).floor().to_i64().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_and_divisions_are_exact() {
+        let cost = Price::from_f64(0.45) + Price::from_f64(0.40);
+        assert_eq!(cost, Price::from_f64(0.85));
+        assert_eq!(cost.complement(), Price::from_cents(15));
+        // 0.29 * 100 in f64 is 28.999999999999996.
+        assert_eq!(Price::from_f64(0.29).cents(), 29);
+        assert_eq!(UsdAmount::from_f64(100.0).contracts_at(Price::from_f64(0.3)), 333);
+        assert_eq!(Price::from_f64(0.565).cents(), 57);
+        assert_eq!(UsdAmount::for_shares(250.0, Price::from_f64(0.4)), UsdAmount::from_f64(100.0));
+        assert_eq!(whole_contracts(249.99999999999997), 250);
+        assert_eq!(sum_usd([0.1, 0.2]), 0.3);
+        assert_eq!(UsdAmount::from_f64(0.29).usdc_units(), 290_000);
+        assert_eq!(UsdAmount::from_usdc_units(1_500_000), UsdAmount::from_f64(1.5));
+        assert_eq!(share_units(0.29), 290_000);
+        assert_eq!(Price::from_f64(0.569).floor_cents(), Price::from_cents(56));
+        assert_eq!(UsdAmount::from_f64(10.0).pro_rata(1.0, 3.0).round_cents(), UsdAmount::from_f64(3.33));
+    }
+
+    #[test]
+    fn serializes_as_plain_numbers() {
+        let amount: UsdAmount = serde_json::from_str("12.34").unwrap();
+        assert_eq!(amount, UsdAmount::from_cents(1234));
+        assert_eq!(serde_json::to_string(&Price::from_cents(45)).unwrap(), "0.45");
+    }
+}
@@ -0,0 +1,543 @@
+//! Severity-routed notifications.
+//!
+//! Every `BotEvent` has a severity; `NotificationPolicy` maps each severity
+//! to the channels it goes out on, so routine events stay in the log while
+//! failures that need a human reach Discord, Telegram or email. Routes and
+//! per-event severity overrides are declared in the environment:
+//!
+//! ```text
+//! NOTIFY_ROUTES=info=log;warning=log,discord;critical=log,telegram,email
+//! NOTIFY_SEVERITY=trade_executed=warning,settled=info
+//! ```
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fmt;
+use tracing::{error, info, warn};
+
+pub const DEFAULT_ROUTES: &str = "info=log;warning=log,discord;critical=log,telegram,email";
+const DEFAULT_EMAIL_API_URL: &str = "https://api.resend.com/emails";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "info" => Some(Severity::Info),
+            "warning" | "warn" => Some(Severity::Warning),
+            "critical" | "crit" => Some(Severity::Critical),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "INFO",
+            Severity::Warning => "WARNING",
+            Severity::Critical => "CRITICAL",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Channel {
+    Log,
+    Discord,
+    Telegram,
+    Email,
+}
+
+impl Channel {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "log" => Some(Channel::Log),
+            "discord" => Some(Channel::Discord),
+            "telegram" => Some(Channel::Telegram),
+            "email" => Some(Channel::Email),
+            _ => None,
+        }
+    }
+}
+
+/// Something the operator may want to hear about.
+#[derive(Debug, Clone)]
+pub enum BotEvent {
+    Started { strategies: Vec<String> },
+    TradeExecuted { strategy: String, title: String, amount: f64, expected_profit: f64 },
+    TradeFailed { strategy: String, title: String, error: String },
+    /// One leg filled and the leg failure policy could not hedge or unwind it.
+    UnhedgedLeg { platform: String, event_id: String, outcome: String, shares: f64 },
+    Settled { count: usize, total_profit: f64 },
+    StateMismatch { count: usize },
+    FlattenIncomplete { unclosed: usize, errors: usize },
+    ProfitTargetReached { realized: f64, target: f64 },
+    /// A held Polymarket market's proposed resolution was disputed on UMA.
+    ResolutionDisputed { event_id: String, title: String },
+    /// Both legs of a pair won or both lost: the markets weren't the same.
+    SettlementMismatch { polymarket_id: String, kalshi_id: String, title: String, both_won: bool },
+    /// A platform's balance fell under its floor; no new positions there.
+    LowBalance { platform: String, balance: f64, floor: f64 },
+    BalanceRestored { platform: String, balance: f64, floor: f64 },
+    /// `short` can't fund its leg of a cross-platform arb; `transfer` from
+    /// `from` evens the venues out.
+    CapitalImbalance {
+        short: String,
+        from: Option<String>,
+        transfer: f64,
+        polymarket: f64,
+        kalshi: f64,
+        leg: f64,
+    },
+    /// `platform`'s balance moved by `unexplained` more than its trades,
+    /// payouts and fees account for: a deposit when positive, a withdrawal
+    /// (or worse) when negative.
+    BalanceDiscrepancy { platform: String, unexplained: f64, previous: f64, current: f64 },
+    /// Today's realized loss reached the daily limit; no new entries until
+    /// the UTC rollover.
+    DailyLossLimit { realized: f64, limit: f64 },
+}
+
+impl BotEvent {
+    /// Stable name used by `NOTIFY_SEVERITY` overrides.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BotEvent::Started { .. } => "started",
+            BotEvent::TradeExecuted { .. } => "trade_executed",
+            BotEvent::TradeFailed { .. } => "trade_failed",
+            BotEvent::UnhedgedLeg { .. } => "unhedged_leg",
+            BotEvent::Settled { .. } => "settled",
+            BotEvent::StateMismatch { .. } => "state_mismatch",
+            BotEvent::FlattenIncomplete { .. } => "flatten_incomplete",
+            BotEvent::ProfitTargetReached { .. } => "profit_target",
+            BotEvent::ResolutionDisputed { .. } => "resolution_disputed",
+            BotEvent::SettlementMismatch { .. } => "settlement_mismatch",
+            BotEvent::LowBalance { .. } => "low_balance",
+            BotEvent::BalanceRestored { .. } => "balance_restored",
+            BotEvent::CapitalImbalance { .. } => "capital_imbalance",
+            BotEvent::BalanceDiscrepancy { .. } => "balance_discrepancy",
+            BotEvent::DailyLossLimit { .. } => "daily_loss_limit",
+        }
+    }
+
+    pub fn default_severity(&self) -> Severity {
+        match self {
+            BotEvent::BalanceDiscrepancy { unexplained, .. } if *unexplained < 0.0 => Severity::Critical,
+            BotEvent::Started { .. }
+            | BotEvent::TradeExecuted { .. }
+            | BotEvent::Settled { .. }
+            | BotEvent::ProfitTargetReached { .. }
+            | BotEvent::BalanceRestored { .. } => Severity::Info,
+            BotEvent::TradeFailed { .. }
+            | BotEvent::StateMismatch { .. }
+            | BotEvent::CapitalImbalance { .. }
+            | BotEvent::BalanceDiscrepancy { .. } => Severity::Warning,
+            BotEvent::UnhedgedLeg { .. }
+            | BotEvent::FlattenIncomplete { .. }
+            | BotEvent::ResolutionDisputed { .. }
+            | BotEvent::SettlementMismatch { .. }
+            | BotEvent::LowBalance { .. }
+            | BotEvent::DailyLossLimit { .. } => Severity::Critical,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            BotEvent::Started { strategies } => {
+                format!("Bot started with strategies: {}", strategies.join(", "))
+            }
+            BotEvent::TradeExecuted { strategy, title, amount, expected_profit } => format!(
+                "{} trade on {}: ${:.2} in, expected profit ${:.4}",
+                strategy, title, amount, expected_profit
+            ),
+            BotEvent::TradeFailed { strategy, title, error } => {
+                format!("{} trade on {} failed: {}", strategy, title, error)
+            }
+            BotEvent::UnhedgedLeg { platform, event_id, outcome, shares } => format!(
+                "Unhedged leg: {:.4} {} shares of {} on {}",
+                shares, outcome, event_id, platform
+            ),
+            BotEvent::Settled { count, total_profit } => format!(
+                "{} positions settled, profit to date ${:.2}",
+                count, total_profit
+            ),
+            BotEvent::StateMismatch { count } => {
+                format!("{} tracked positions differ from venue holdings", count)
+            }
+            BotEvent::FlattenIncomplete { unclosed, errors } => format!(
+                "flatten-all incomplete: {} positions left open, {} venue errors",
+                unclosed, errors
+            ),
+            BotEvent::ProfitTargetReached { realized, target } => format!(
+                "Profit target reached (${:.2} of ${:.2}) - manage-only until next period",
+                realized, target
+            ),
+            BotEvent::ResolutionDisputed { event_id, title } => format!(
+                "Resolution of {} ({}) disputed on UMA - settlement held until finalized",
+                title, event_id
+            ),
+            BotEvent::SettlementMismatch { polymarket_id, kalshi_id, title, both_won } => format!(
+                "Both legs {} on {} ({} / {}) - pair denied, check the matcher",
+                if *both_won { "won" } else { "lost" },
+                title,
+                polymarket_id,
+                kalshi_id
+            ),
+            BotEvent::LowBalance { platform, balance, floor } => format!(
+                "{} balance ${:.2} under the ${:.2} floor - no new positions there until topped up",
+                platform, balance, floor
+            ),
+            BotEvent::BalanceRestored { platform, balance, floor } => format!(
+                "{} balance ${:.2} back over the ${:.2} floor - trading resumed",
+                platform, balance, floor
+            ),
+            BotEvent::CapitalImbalance { short, from, transfer, polymarket, kalshi, leg } => match from {
+                Some(from) => format!(
+                    "{} can't fund a ${:.2} arb leg (deployable: Polymarket ${:.2}, Kalshi ${:.2}) - move ${:.2} from {} to {}",
+                    short, leg, polymarket, kalshi, transfer, from, short
+                ),
+                None => format!(
+                    "Deployable capital (Polymarket ${:.2}, Kalshi ${:.2}) can't fund both ${:.2} legs of an arb - top up {}",
+                    polymarket, kalshi, leg, short
+                ),
+            },
+            BotEvent::BalanceDiscrepancy { platform, unexplained, previous, current } => format!(
+                "{} balance went ${:.2} -> ${:.2}: ${:.2} {} than trades and settlements explain - {}",
+                platform,
+                previous,
+                current,
+                unexplained.abs(),
+                if *unexplained > 0.0 { "more" } else { "less" },
+                if *unexplained > 0.0 {
+                    "a deposit or manual redemption?"
+                } else {
+                    "a withdrawal? check the account and keys"
+                }
+            ),
+            BotEvent::DailyLossLimit { realized, limit } => format!(
+                "Daily loss limit hit: ${:.2} realized today against a ${:.2} limit - no new entries until 00:00 UTC",
+                realized, limit
+            ),
+        }
+    }
+}
+
+/// Which channels each severity goes to, plus per-event severity overrides.
+#[derive(Debug, Clone)]
+pub struct NotificationPolicy {
+    routes: HashMap<Severity, Vec<Channel>>,
+    overrides: HashMap<String, Severity>,
+}
+
+impl NotificationPolicy {
+    /// Parses `severity=channel,channel;severity=...`. Unknown names are
+    /// skipped with a warning.
+    pub fn parse(routes: &str) -> Self {
+        let mut parsed = HashMap::new();
+        for entry in routes.split(';').filter(|e| !e.trim().is_empty()) {
+            let Some((severity, channels)) = entry.split_once('=') else {
+                warn!("Ignoring invalid NOTIFY_ROUTES entry '{}'", entry.trim());
+                continue;
+            };
+            let Some(severity) = Severity::parse(severity) else {
+                warn!("Ignoring unknown severity '{}' in NOTIFY_ROUTES", severity.trim());
+                continue;
+            };
+            let channels = channels
+                .split(',')
+                .filter(|c| !c.trim().is_empty())
+                .filter_map(|c| {
+                    let channel = Channel::parse(c);
+                    if channel.is_none() {
+                        warn!("Ignoring unknown channel '{}' in NOTIFY_ROUTES", c.trim());
+                    }
+                    channel
+                })
+                .collect();
+            parsed.insert(severity, channels);
+        }
+        Self {
+            routes: parsed,
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// Parses `event_kind=severity,...` overrides.
+    pub fn with_overrides(mut self, overrides: &str) -> Self {
+        for entry in overrides.split(',').filter(|e| !e.trim().is_empty()) {
+            match entry.split_once('=').and_then(|(kind, severity)| {
+                Severity::parse(severity).map(|s| (kind.trim().to_lowercase(), s))
+            }) {
+                Some((kind, severity)) => {
+                    self.overrides.insert(kind, severity);
+                }
+                None => warn!("Ignoring invalid NOTIFY_SEVERITY entry '{}'", entry.trim()),
+            }
+        }
+        self
+    }
+
+    pub fn from_env() -> Self {
+        let routes = std::env::var("NOTIFY_ROUTES").unwrap_or_else(|_| DEFAULT_ROUTES.to_string());
+        Self::parse(&routes).with_overrides(&std::env::var("NOTIFY_SEVERITY").unwrap_or_default())
+    }
+
+    pub fn severity(&self, event: &BotEvent) -> Severity {
+        self.overrides
+            .get(event.kind())
+            .copied()
+            .unwrap_or_else(|| event.default_severity())
+    }
+
+    pub fn channels(&self, severity: Severity) -> &[Channel] {
+        self.routes.get(&severity).map(|c| c.as_slice()).unwrap_or(&[])
+    }
+}
+
+impl Default for NotificationPolicy {
+    fn default() -> Self {
+        Self::parse(DEFAULT_ROUTES)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TelegramConfig {
+    bot_token: String,
+    chat_id: String,
+}
+
+#[derive(Debug, Clone)]
+struct EmailConfig {
+    api_url: String,
+    api_key: String,
+    from: String,
+    to: Vec<String>,
+}
+
+pub struct Notifier {
+    http: Client,
+    policy: NotificationPolicy,
+    discord_webhook: Option<String>,
+    telegram: Option<TelegramConfig>,
+    email: Option<EmailConfig>,
+}
+
+impl Notifier {
+    /// Log-only notifier; remote channels are added with the `with_*` builders.
+    pub fn new(policy: NotificationPolicy) -> Self {
+        Self {
+            http: Client::builder()
+                .timeout(std::time::Duration::from_secs(10))
+                .build()
+                .unwrap_or_else(|_| Client::new()),
+            policy,
+            discord_webhook: None,
+            telegram: None,
+            email: None,
+        }
+    }
+
+    /// Policy from `NOTIFY_ROUTES`/`NOTIFY_SEVERITY`; channels from
+    /// `DISCORD_WEBHOOK_URL`, `TELEGRAM_BOT_TOKEN` + `TELEGRAM_CHAT_ID` and
+    /// `EMAIL_API_KEY` + `EMAIL_FROM` + `EMAIL_TO` (optional `EMAIL_API_URL`).
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let mut notifier = Self::new(NotificationPolicy::from_env());
+        if let Some(url) = var("DISCORD_WEBHOOK_URL") {
+            notifier = notifier.with_discord(&url);
+        }
+        if let (Some(token), Some(chat_id)) = (var("TELEGRAM_BOT_TOKEN"), var("TELEGRAM_CHAT_ID")) {
+            notifier = notifier.with_telegram(&token, &chat_id);
+        }
+        if let (Some(key), Some(from), Some(to)) = (var("EMAIL_API_KEY"), var("EMAIL_FROM"), var("EMAIL_TO")) {
+            let to: Vec<String> = to.split(',').map(|s| s.trim().to_string()).collect();
+            notifier = notifier.with_email(&key, &from, to);
+            if let Some(url) = var("EMAIL_API_URL") {
+                if let Some(email) = &mut notifier.email {
+                    email.api_url = url;
+                }
+            }
+        }
+        notifier.warn_unconfigured();
+        notifier
+    }
+
+    pub fn with_discord(mut self, webhook_url: &str) -> Self {
+        self.discord_webhook = Some(webhook_url.to_string());
+        self
+    }
+
+    pub fn with_telegram(mut self, bot_token: &str, chat_id: &str) -> Self {
+        self.telegram = Some(TelegramConfig {
+            bot_token: bot_token.to_string(),
+            chat_id: chat_id.to_string(),
+        });
+        self
+    }
+
+    pub fn with_email(mut self, api_key: &str, from: &str, to: Vec<String>) -> Self {
+        self.email = Some(EmailConfig {
+            api_url: DEFAULT_EMAIL_API_URL.to_string(),
+            api_key: api_key.to_string(),
+            from: from.to_string(),
+            to,
+        });
+        self
+    }
+
+    pub fn policy(&self) -> &NotificationPolicy {
+        &self.policy
+    }
+
+    fn is_configured(&self, channel: Channel) -> bool {
+        match channel {
+            Channel::Log => true,
+            Channel::Discord => self.discord_webhook.is_some(),
+            Channel::Telegram => self.telegram.is_some(),
+            Channel::Email => self.email.is_some(),
+        }
+    }
+
+    fn warn_unconfigured(&self) {
+        for severity in [Severity::Info, Severity::Warning, Severity::Critical] {
+            for channel in self.policy.channels(severity) {
+                if !self.is_configured(*channel) {
+                    warn!(
+                        "🔕 {:?} is routed for {} notifications but not configured - skipping it",
+                        channel, severity
+                    );
+                }
+            }
+        }
+    }
+
+    /// Configured channels `event` is sent to under the policy.
+    pub fn channels_for(&self, event: &BotEvent) -> Vec<Channel> {
+        self.policy
+            .channels(self.policy.severity(event))
+            .iter()
+            .copied()
+            .filter(|c| self.is_configured(*c))
+            .collect()
+    }
+
+    /// Sends `event` to every channel its severity routes to. Delivery
+    /// failures are logged and never propagated.
+    pub async fn notify(&self, event: BotEvent) {
+        let severity = self.policy.severity(&event);
+        let text = format!("[{}] {}", severity, event.message());
+        let event = &event;
+        let sends = self.channels_for(event).into_iter().map(|channel| {
+            let text = text.clone();
+            async move {
+                let result = match channel {
+                    Channel::Log => {
+                        match severity {
+                            Severity::Info => info!("🔔 {}", text),
+                            Severity::Warning => warn!("🔔 {}", text),
+                            Severity::Critical => error!("🔔 {}", text),
+                        }
+                        Ok(())
+                    }
+                    Channel::Discord => self.send_discord(&text).await,
+                    Channel::Telegram => self.send_telegram(&text).await,
+                    Channel::Email => self.send_email(severity, event, &text).await,
+                };
+                if let Err(e) = result {
+                    warn!("🔕 {:?} notification failed: {}", channel, e);
+                }
+            }
+        });
+        futures::future::join_all(sends).await;
+    }
+
+    async fn post(&self, request: reqwest::RequestBuilder, channel: &str) -> Result<()> {
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach {}", channel))?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("{} error: {}", channel, response.status()));
+        }
+        Ok(())
+    }
+
+    async fn send_discord(&self, text: &str) -> Result<()> {
+        let url = self.discord_webhook.as_deref().context("Discord not configured")?;
+        self.post(self.http.post(url).json(&json!({ "content": text })), "Discord")
+            .await
+    }
+
+    async fn send_telegram(&self, text: &str) -> Result<()> {
+        let telegram = self.telegram.as_ref().context("Telegram not configured")?;
+        let url = format!("https://api.telegram.org/bot{}/sendMessage", telegram.bot_token);
+        self.post(
+            self.http
+                .post(url)
+                .json(&json!({ "chat_id": telegram.chat_id, "text": text })),
+            "Telegram",
+        )
+        .await
+    }
+
+    async fn send_email(&self, severity: Severity, event: &BotEvent, text: &str) -> Result<()> {
+        let email = self.email.as_ref().context("Email not configured")?;
+        self.post(
+            self.http
+                .post(&email.api_url)
+                .bearer_auth(&email.api_key)
+                .json(&json!({
+                    "from": email.from,
+                    "to": email.to,
+                    "subject": format!("[{}] arbitrage bot: {}", severity, event.kind()),
+                    "text": text,
+                })),
+            "Email",
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_events_by_severity() {
+        let notifier = Notifier::new(
+            NotificationPolicy::parse("info=log; warning=log,discord; critical=log,telegram,email")
+                .with_overrides("trade_executed=warning"),
+        )
+        .with_discord("https://discord.test/webhook")
+        .with_telegram("token", "42");
+
+        let settled = BotEvent::Settled { count: 1, total_profit: 0.5 };
+        assert_eq!(notifier.channels_for(&settled), vec![Channel::Log]);
+
+        let traded = BotEvent::TradeExecuted {
+            strategy: "cross_platform".to_string(),
+            title: "BTC".to_string(),
+            amount: 100.0,
+            expected_profit: 1.0,
+        };
+        assert_eq!(notifier.policy().severity(&traded), Severity::Warning);
+        assert_eq!(notifier.channels_for(&traded), vec![Channel::Log, Channel::Discord]);
+
+        // Email is routed but not configured, so only log + Telegram fire.
+        let unhedged = BotEvent::UnhedgedLeg {
+            platform: "kalshi".to_string(),
+            event_id: "KXBTC".to_string(),
+            outcome: "YES".to_string(),
+            shares: 10.0,
+        };
+        assert_eq!(notifier.channels_for(&unhedged), vec![Channel::Log, Channel::Telegram]);
+    }
+}
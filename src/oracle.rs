@@ -0,0 +1,228 @@
+//! On-chain oracle cross-check for 15-minute crypto up/down trades.
+//!
+//! Polymarket settles these markets on Chainlink, so before a cross-platform
+//! trade the window's move is read from the Chainlink feed on Polygon
+//! (through `PolymarketBlockchain`'s provider). When the oracle already shows
+//! a clear move and a leg's quote implies the opposite direction, that quote
+//! is stale and the opportunity is rejected.
+//!
+//! Enabled with `ORACLE_CHECK=1`.
+
+use crate::event::Event;
+use crate::polymarket_blockchain::{OracleRound, PolymarketBlockchain};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use ethers::types::{Address, U256};
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+const WINDOW_MINUTES: i64 = 15;
+/// Rounds walked back looking for the window open before giving up.
+const MAX_ROUNDS_BACK: usize = 200;
+
+/// Chainlink USD aggregator proxies on Polygon.
+const DEFAULT_FEEDS: [(&str, &str); 3] = [
+    ("btc", "0xc907E116054Ad103354f2D350FD2514433D57F6f"),
+    ("eth", "0xF9680D99D6C9589e2a93a78A04A279e29C6f4A0a"),
+    ("sol", "0x10C8264C0935b3B9870013e057f330Ff3e9C56dC"),
+];
+
+/// Why a leg's implied P(up) conflicts with the oracle move, if it does.
+/// The oracle only counts once it has moved at least `min_move` from the
+/// window open; a leg conflicts when its implied P(up) sits more than
+/// `margin` on the other side of 0.5.
+pub fn conflict(open: f64, current: f64, implied_up: f64, min_move: f64, margin: f64) -> Option<String> {
+    let moved = current / open - 1.0;
+    if moved.abs() < min_move {
+        return None;
+    }
+    let contradicts = if moved > 0.0 {
+        implied_up < 0.5 - margin
+    } else {
+        implied_up > 0.5 + margin
+    };
+    contradicts.then(|| {
+        format!(
+            "oracle {:+.2}% since open but quote implies P(up)={:.2}",
+            moved * 100.0,
+            implied_up
+        )
+    })
+}
+
+/// Market-implied P(up) of a bought leg: its price for YES, one minus it for NO.
+pub fn implied_up(outcome: &str, price: f64) -> f64 {
+    if outcome.eq_ignore_ascii_case("yes") || outcome.eq_ignore_ascii_case("up") {
+        price
+    } else {
+        1.0 - price
+    }
+}
+
+pub struct OracleCheck {
+    blockchain: PolymarketBlockchain,
+    feeds: HashMap<String, Address>,
+    decimals: Mutex<HashMap<Address, u8>>,
+    window_opens: Mutex<HashMap<(String, i64), f64>>,
+    min_move: f64,
+    margin: f64,
+    max_age: Duration,
+}
+
+impl OracleCheck {
+    pub fn new(blockchain: PolymarketBlockchain) -> Self {
+        let feeds = DEFAULT_FEEDS
+            .iter()
+            .filter_map(|(coin, address)| Some((coin.to_string(), address.parse().ok()?)))
+            .collect();
+        Self {
+            blockchain,
+            feeds,
+            decimals: Mutex::new(HashMap::new()),
+            window_opens: Mutex::new(HashMap::new()),
+            min_move: 0.002,
+            margin: 0.15,
+            max_age: Duration::seconds(120),
+        }
+    }
+
+    /// `ORACLE_CHECK=1`, reading `POLYGON_RPC_URL`. Tuned by `ORACLE_MIN_MOVE`,
+    /// `ORACLE_MARGIN` and `ORACLE_MAX_AGE_SECS`; `ORACLE_FEED_<COIN>`
+    /// overrides a feed address.
+    pub fn from_env() -> Result<Option<Self>> {
+        let enabled = std::env::var("ORACLE_CHECK")
+            .map(|s| s == "1" || s.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        if !enabled {
+            return Ok(None);
+        }
+        let rpc_url = std::env::var("POLYGON_RPC_URL")
+            .unwrap_or_else(|_| "https://polygon-rpc.com".to_string());
+        let mut check = Self::new(PolymarketBlockchain::new(&rpc_url)?);
+
+        let parse = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|s| s.trim().parse::<f64>().ok())
+        };
+        if let Some(min_move) = parse("ORACLE_MIN_MOVE") {
+            check.min_move = min_move;
+        }
+        if let Some(margin) = parse("ORACLE_MARGIN") {
+            check.margin = margin;
+        }
+        if let Some(secs) = parse("ORACLE_MAX_AGE_SECS") {
+            check.max_age = Duration::seconds(secs as i64);
+        }
+        for (key, value) in std::env::vars() {
+            if let Some(coin) = key.strip_prefix("ORACLE_FEED_") {
+                let address: Address = value
+                    .trim()
+                    .parse()
+                    .with_context(|| format!("Invalid {} address", key))?;
+                check.feeds.insert(coin.to_lowercase(), address);
+            }
+        }
+        Ok(Some(check))
+    }
+
+    pub fn coins(&self) -> Vec<String> {
+        let mut coins: Vec<String> = self.feeds.keys().cloned().collect();
+        coins.sort();
+        coins
+    }
+
+    async fn decimals(&self, feed: Address) -> Result<u8> {
+        if let Some(decimals) = self.decimals.lock().await.get(&feed) {
+            return Ok(*decimals);
+        }
+        let decimals = self.blockchain.chainlink_decimals(feed).await?;
+        self.decimals.lock().await.insert(feed, decimals);
+        Ok(decimals)
+    }
+
+    /// Oracle price at `start`: the last round updated at or before it.
+    /// Cached per window since it never changes.
+    async fn window_open(
+        &self,
+        coin: &str,
+        feed: Address,
+        decimals: u8,
+        latest: OracleRound,
+        start: DateTime<Utc>,
+    ) -> Result<f64> {
+        let key = (coin.to_string(), start.timestamp());
+        if let Some(open) = self.window_opens.lock().await.get(&key) {
+            return Ok(*open);
+        }
+        let mut round = latest;
+        for _ in 0..MAX_ROUNDS_BACK {
+            if round.updated_at <= start {
+                self.window_opens.lock().await.insert(key, round.price);
+                return Ok(round.price);
+            }
+            if round.round_id.is_zero() {
+                break;
+            }
+            round = self
+                .blockchain
+                .chainlink_round(feed, round.round_id - U256::one(), decimals)
+                .await?;
+        }
+        Err(anyhow::anyhow!("no {} oracle round at or before {}", coin, start.to_rfc3339()))
+    }
+
+    /// Checks bought legs `(outcome, price)` of a 15m up/down event against
+    /// the oracle. `Ok(Some(reason))` rejects the trade; events that are not
+    /// running 15m windows on a configured coin pass unchecked.
+    pub async fn check(&self, event: &Event, legs: &[(&str, f64)]) -> Result<Option<String>> {
+        if !event.is_15m_crypto_market() {
+            return Ok(None);
+        }
+        let (Some(coin), Some(end)) = (event.coin_from_slug(), event.resolution_date) else {
+            return Ok(None);
+        };
+        let Some(feed) = self.feeds.get(&coin).copied() else {
+            return Ok(None);
+        };
+        let now = Utc::now();
+        let start = end - Duration::minutes(WINDOW_MINUTES);
+        if now < start || now >= end {
+            return Ok(None);
+        }
+
+        let decimals = self.decimals(feed).await?;
+        let latest = self.blockchain.chainlink_latest_round(feed, decimals).await?;
+        if now - latest.updated_at > self.max_age {
+            warn!(
+                "🔗 {} oracle last updated {}s ago - skipping cross-check",
+                coin,
+                (now - latest.updated_at).num_seconds()
+            );
+            return Ok(None);
+        }
+        let open = self.window_open(&coin, feed, decimals, latest, start).await?;
+
+        Ok(legs.iter().find_map(|(outcome, price)| {
+            conflict(open, latest.price, implied_up(outcome, *price), self.min_move, self.margin)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_quotes_against_a_clear_oracle_move() {
+        // Oracle up 0.5% since open; a quote still pricing "up" at 0.20 is stale.
+        assert!(conflict(100_000.0, 100_500.0, implied_up("YES", 0.20), 0.002, 0.15).is_some());
+        assert!(conflict(100_000.0, 100_500.0, implied_up("NO", 0.80), 0.002, 0.15).is_some());
+        // Agreeing or undecided quotes pass.
+        assert!(conflict(100_000.0, 100_500.0, implied_up("YES", 0.70), 0.002, 0.15).is_none());
+        assert!(conflict(100_000.0, 100_500.0, implied_up("YES", 0.40), 0.002, 0.15).is_none());
+        // Too small a move to call.
+        assert!(conflict(100_000.0, 100_100.0, implied_up("YES", 0.10), 0.002, 0.15).is_none());
+    }
+}
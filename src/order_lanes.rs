@@ -0,0 +1,180 @@
+//! Separate execution lanes for new entries and position-protective orders.
+//!
+//! Buys go through the entry lane; sells (exits, unwinds, stop-losses) go
+//! through their own protective lane, so a burst of entries in a volatile
+//! minute can never queue ahead of an exit. `LanedClient` wraps a venue
+//! client and routes each order to its lane.
+//!
+//! `ENTRY_ORDER_SLOTS` (default 4) and `PROTECTIVE_ORDER_SLOTS` (default 2)
+//! cap in-flight orders per lane, per venue.
+
+use crate::event::{Event, MarketPrices};
+use crate::exchange::{ExchangeClient, Fill, OrderFill, Resolution, SettlementRecord, TimeInForce};
+use crate::metrics::metrics;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::instrument;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lane {
+    Entry,
+    Protective,
+}
+
+impl Lane {
+    fn name(&self) -> &'static str {
+        match self {
+            Lane::Entry => "entry",
+            Lane::Protective => "protective",
+        }
+    }
+}
+
+pub struct OrderLanes {
+    entry: Arc<Semaphore>,
+    protective: Arc<Semaphore>,
+}
+
+impl OrderLanes {
+    pub fn new(entry_slots: usize, protective_slots: usize) -> Self {
+        Self {
+            entry: Arc::new(Semaphore::new(entry_slots.max(1))),
+            protective: Arc::new(Semaphore::new(protective_slots.max(1))),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let slots = |name: &str, default: usize| {
+            std::env::var(name)
+                .ok()
+                .and_then(|s| s.trim().parse::<usize>().ok())
+                .unwrap_or(default)
+        };
+        Self::new(slots("ENTRY_ORDER_SLOTS", 4), slots("PROTECTIVE_ORDER_SLOTS", 2))
+    }
+
+    /// Waits for a slot in `lane`; the order may run while the permit is held.
+    pub async fn acquire(&self, lane: Lane) -> OwnedSemaphorePermit {
+        let semaphore = match lane {
+            Lane::Entry => &self.entry,
+            Lane::Protective => &self.protective,
+        };
+        semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("order lane semaphore closed")
+    }
+}
+
+/// A venue client whose buys and sells run in separate lanes.
+pub struct LanedClient {
+    inner: Arc<dyn ExchangeClient>,
+    lanes: OrderLanes,
+}
+
+impl LanedClient {
+    pub fn new(inner: Arc<dyn ExchangeClient>, lanes: OrderLanes) -> Self {
+        Self { inner, lanes }
+    }
+
+    #[instrument(name = "order_lane.wait", skip(self), fields(venue = self.inner.platform()))]
+    async fn acquire(&self, lane: Lane) -> OwnedSemaphorePermit {
+        let labels = [("venue", self.inner.platform()), ("lane", lane.name())];
+        metrics().add_gauge("order_lane_waiting", &labels, 1.0);
+        let permit = self.lanes.acquire(lane).await;
+        metrics().add_gauge("order_lane_waiting", &labels, -1.0);
+        permit
+    }
+}
+
+#[async_trait]
+impl ExchangeClient for LanedClient {
+    fn platform(&self) -> &'static str {
+        self.inner.platform()
+    }
+
+    async fn fetch_events(&self) -> Result<Vec<Event>> {
+        self.inner.fetch_events().await
+    }
+
+    async fn fetch_markets(&self) -> Result<Vec<Event>> {
+        self.inner.fetch_markets().await
+    }
+
+    async fn fetch_prices(&self, event_id: &str) -> Result<MarketPrices> {
+        self.inner.fetch_prices(event_id).await
+    }
+
+    async fn place_order(
+        &self,
+        event_id: String,
+        outcome: String,
+        amount: f64,
+        price: f64,
+        tif: TimeInForce,
+    ) -> Result<Option<String>> {
+        let _permit = self.acquire(Lane::Entry).await;
+        self.inner.place_order(event_id, outcome, amount, price, tif).await
+    }
+
+    async fn sell_order(
+        &self,
+        event_id: String,
+        outcome: String,
+        shares: f64,
+        min_price: f64,
+    ) -> Result<Option<String>> {
+        let _permit = self.acquire(Lane::Protective).await;
+        self.inner.sell_order(event_id, outcome, shares, min_price).await
+    }
+
+    async fn order_fill(&self, event_id: &str, order_id: &str) -> Result<OrderFill> {
+        self.inner.order_fill(event_id, order_id).await
+    }
+
+    async fn poll_order_fill(
+        &self,
+        event_id: &str,
+        order_id: &str,
+        deadline: tokio::time::Instant,
+    ) -> Result<OrderFill> {
+        self.inner.poll_order_fill(event_id, order_id, deadline).await
+    }
+
+    async fn check_settlement(&self, event_id: &str) -> Result<Option<Resolution>> {
+        self.inner.check_settlement(event_id).await
+    }
+
+    async fn fetch_settlement_records(&self, since: DateTime<Utc>) -> Result<Vec<SettlementRecord>> {
+        self.inner.fetch_settlement_records(since).await
+    }
+
+    async fn fetch_fills(&self, market: &str) -> Result<Vec<Fill>> {
+        self.inner.fetch_fills(market).await
+    }
+
+    async fn get_balance(&self) -> Result<f64> {
+        self.inner.get_balance().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn exits_are_not_queued_behind_entries() {
+        let lanes = OrderLanes::new(1, 1);
+        let _busy_entry = lanes.acquire(Lane::Entry).await;
+
+        let exit = tokio::time::timeout(Duration::from_millis(50), lanes.acquire(Lane::Protective)).await;
+        assert!(exit.is_ok());
+        let entry = tokio::time::timeout(Duration::from_millis(50), lanes.acquire(Lane::Entry)).await;
+        assert!(entry.is_err());
+    }
+}
@@ -1,38 +1,475 @@
 use anyhow::{Context, Result};
-use ethers::providers::{Http, Middleware, Provider};
-use ethers::signers::{LocalWallet, Signer};
-use ethers::types::{Address, H256, TransactionRequest, U256, U64};
+use chrono::{DateTime, TimeZone, Utc};
+use crate::rpc_failover::FailoverClient;
+use ethers::providers::{Middleware, Provider};
+use crate::signer::{self, Signer};
+use ethers::abi::{self, ParamType, Token};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use crate::gas::GasPolicy;
+use crate::money::{share_units, UsdAmount};
+use ethers::types::{Address, Bytes, Eip1559TransactionRequest, H256, TransactionRequest, U256, U64};
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+/// Bridged USDC (USDC.e) on Polygon.
+pub const USDC_ADDRESS: &str = "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174";
+/// Gnosis Conditional Tokens Framework.
+pub const CTF_ADDRESS: &str = "0x4D97DCd97eC945f40cF65F87097ACe5EA0476045";
+pub const CTF_EXCHANGE_ADDRESS: &str = "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E";
+pub const NEG_RISK_CTF_EXCHANGE_ADDRESS: &str = "0xC5d563A36AE78145C45a50134d48A1215220f80a";
+pub const NEG_RISK_ADAPTER_ADDRESS: &str = "0xd91E80cF2E7be2e162c6513ceD06f1dD0dA35296";
+
+/// Factory behind Polymarket proxy wallets (Magic / email accounts).
+pub const PROXY_WALLET_FACTORY: &str = "0xaB45c5A4B0c941a2F231C04C3f49182e1A254052";
+/// Factory behind Polymarket Gnosis Safe wallets (browser wallet accounts).
+pub const SAFE_FACTORY: &str = "0xaacFeEa03eb1561C4e67d661e40682Bd20E3541b";
+const PROXY_INIT_CODE_HASH: &str = "0xd21df8dc65880a8606f09fe0ce3df9b8869287ab0b058be05aa9e8af6330a00b";
+const SAFE_INIT_CODE_HASH: &str = "0x2bce2127ff07fb632d16c8347c4ebf501f4841168bed00d9e6ef715ddb6fcecf";
+
+/// How the Polymarket account holds its funds, from `POLYMARKET_SIGNATURE_TYPE`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WalletKind {
+    /// The signing key's own address.
+    Eoa,
+    Proxy,
+    Safe,
+    /// Deposit wallet; its address must be configured.
+    Deposit,
+}
+
+impl WalletKind {
+    pub fn from_env() -> Self {
+        match std::env::var("POLYMARKET_SIGNATURE_TYPE")
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase()
+            .as_str()
+        {
+            "1" | "magic" | "proxy" | "email" => WalletKind::Proxy,
+            "2" | "safe" | "gnosis" | "browser" => WalletKind::Safe,
+            "3" | "poly1271" | "deposit" | "poly_1271" => WalletKind::Deposit,
+            _ => WalletKind::Eoa,
+        }
+    }
+}
+
+fn create2(factory: &str, salt: [u8; 32], init_code_hash: &str) -> Address {
+    let factory: Address = factory.parse().expect("valid factory address");
+    let init_code_hash = H256::from_str(init_code_hash).expect("valid init code hash");
+    ethers::utils::get_create2_address_from_hash(factory, salt, init_code_hash)
+}
+
+/// Polymarket proxy wallet of `owner` (CREATE2, salted with the owner).
+pub fn derive_proxy_wallet(owner: Address) -> Address {
+    create2(PROXY_WALLET_FACTORY, ethers::utils::keccak256(owner.as_bytes()), PROXY_INIT_CODE_HASH)
+}
+
+/// Polymarket Safe wallet of `owner` (CREATE2, salted with the ABI-encoded owner).
+pub fn derive_safe_wallet(owner: Address) -> Address {
+    create2(
+        SAFE_FACTORY,
+        ethers::utils::keccak256(abi::encode(&[Token::Address(owner)])),
+        SAFE_INIT_CODE_HASH,
+    )
+}
+
+/// Wallet that holds funds and positions for the `owner` key:
+/// `POLYMARKET_FUNDER_ADDRESS` (or `DEPOSIT_WALLET_ADDRESS`) when set,
+/// otherwise derived from the wallet kind. `None` when the owner trades
+/// from its own address.
+pub fn funder_address(owner: Address) -> Result<Option<Address>> {
+    let configured = ["POLYMARKET_FUNDER_ADDRESS", "DEPOSIT_WALLET_ADDRESS"]
+        .into_iter()
+        .find_map(|key| std::env::var(key).ok().filter(|s| !s.trim().is_empty()));
+    if let Some(address) = configured {
+        let address = address
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid POLYMARKET_FUNDER_ADDRESS: {}", address))?;
+        return Ok(Some(address));
+    }
+    Ok(match WalletKind::from_env() {
+        WalletKind::Eoa => None,
+        WalletKind::Proxy => Some(derive_proxy_wallet(owner)),
+        WalletKind::Safe => Some(derive_safe_wallet(owner)),
+        WalletKind::Deposit => {
+            return Err(anyhow::anyhow!(
+                "POLYMARKET_SIGNATURE_TYPE=3 needs POLYMARKET_FUNDER_ADDRESS (deposit wallet)"
+            ))
+        }
+    })
+}
+
+/// Contracts that pull USDC from the wallet when orders fill.
+pub const USDC_SPENDERS: [&str; 3] = [
+    CTF_EXCHANGE_ADDRESS,
+    NEG_RISK_CTF_EXCHANGE_ADDRESS,
+    NEG_RISK_ADAPTER_ADDRESS,
+];
+
+/// What to do about one spender's USDC allowance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AllowanceAction {
+    Sufficient,
+    /// Approve up to this many dollars.
+    Approve(f64),
+    /// Short, and auto-approve is off (or its cap is too low).
+    Insufficient,
+}
+
+/// `current` allowance against the `required` dollars, approving up to
+/// `approve_cap` when set.
+pub fn allowance_action(current: f64, required: f64, approve_cap: Option<f64>) -> AllowanceAction {
+    if current >= required {
+        AllowanceAction::Sufficient
+    } else {
+        match approve_cap {
+            Some(cap) if cap >= required => AllowanceAction::Approve(cap),
+            _ => AllowanceAction::Insufficient,
+        }
+    }
+}
+
+/// Multicall3, deployed at the same address on every EVM chain.
+pub const MULTICALL3_ADDRESS: &str = "0xcA11bde05977b3631167028862bE2a173976CA11";
+/// Calls per `aggregate3` request, to stay under node gas and response limits.
+const MULTICALL_BATCH_SIZE: usize = 500;
+
+fn encode_call(signature: &str, args: &[Token]) -> Vec<u8> {
+    let mut data = ethers::utils::id(signature).to_vec();
+    data.extend(abi::encode(args));
+    data
+}
+
+/// `aggregate3((address,bool,bytes)[])` calldata; every call may fail on its
+/// own without reverting the batch.
+fn encode_aggregate3(calls: &[(Address, Vec<u8>)]) -> Vec<u8> {
+    let calls = calls
+        .iter()
+        .map(|(target, data)| {
+            Token::Tuple(vec![Token::Address(*target), Token::Bool(true), Token::Bytes(data.clone())])
+        })
+        .collect();
+    encode_call("aggregate3((address,bool,bytes)[])", &[Token::Array(calls)])
+}
+
+/// Return data of each call in an `aggregate3` response, `None` where the
+/// call reverted.
+fn decode_aggregate3(data: &[u8]) -> Result<Vec<Option<Vec<u8>>>> {
+    let result_type = ParamType::Array(Box::new(ParamType::Tuple(vec![ParamType::Bool, ParamType::Bytes])));
+    let tokens = abi::decode(&[result_type], data).context("Invalid aggregate3 response")?;
+    let Some(Token::Array(results)) = tokens.into_iter().next() else {
+        return Err(anyhow::anyhow!("Invalid aggregate3 response"));
+    };
+    results
+        .into_iter()
+        .map(|result| match result {
+            Token::Tuple(fields) => match fields.as_slice() {
+                [Token::Bool(success), Token::Bytes(data)] => Ok(success.then(|| data.clone())),
+                _ => Err(anyhow::anyhow!("Invalid aggregate3 result")),
+            },
+            _ => Err(anyhow::anyhow!("Invalid aggregate3 result")),
+        })
+        .collect()
+}
+
+/// First 32-byte word of a call's return data.
+fn first_word(data: &[u8]) -> Option<U256> {
+    (data.len() >= 32).then(|| U256::from_big_endian(&data[..32]))
+}
+
+/// Outcome tokens and USDC both use 6 decimals.
+fn from_usdc_units(units: U256) -> f64 {
+    // Unlimited approvals overflow u128; anything that large is plenty.
+    if units > U256::from(u128::MAX) {
+        f64::MAX
+    } else {
+        UsdAmount::from_usdc_units(units.as_u128()).to_f64()
+    }
+}
+
+const RECEIPT_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3);
+
+fn usdc_units(dollars: f64) -> U256 {
+    U256::from(UsdAmount::from_f64(dollars).usdc_units())
+}
+
+/// ERC-20 `approve(address,uint256)`.
+const APPROVE_SELECTOR: [u8; 4] = [0x09, 0x5e, 0xa7, 0xb3];
+/// ERC-1155 `setApprovalForAll(address,bool)`.
+const SET_APPROVAL_FOR_ALL_SELECTOR: [u8; 4] = [0xa2, 0x2c, 0xb4, 0x65];
+
+/// Contracts outbound transactions may target: the Polymarket contracts
+/// plus any in `ALLOWED_CONTRACT_ADDRESSES` (comma-separated).
+fn default_allowed_contracts() -> HashSet<Address> {
+    let extra = std::env::var("ALLOWED_CONTRACT_ADDRESSES").unwrap_or_default();
+    [
+        USDC_ADDRESS,
+        CTF_ADDRESS,
+        CTF_EXCHANGE_ADDRESS,
+        NEG_RISK_CTF_EXCHANGE_ADDRESS,
+        NEG_RISK_ADAPTER_ADDRESS,
+    ]
+    .into_iter()
+    .chain(extra.split(',').map(str::trim).filter(|s| !s.is_empty()))
+    .filter_map(|s| match s.parse::<Address>() {
+        Ok(address) => Some(address),
+        Err(_) => {
+            tracing::warn!("Ignoring invalid ALLOWED_CONTRACT_ADDRESSES entry '{}'", s);
+            None
+        }
+    })
+    .collect()
+}
+
+/// One Chainlink aggregator round, scaled by the feed's decimals.
+#[derive(Debug, Clone, Copy)]
+pub struct OracleRound {
+    pub round_id: U256,
+    pub price: f64,
+    pub updated_at: DateTime<Utc>,
+}
 
 /// Legacy Polygon helpers for balance checks.
 /// Trading is handled via CLOB V2 (`polymarket_clob` + official SDK).
 pub struct PolymarketBlockchain {
-    provider: Provider<Http>,
-    wallet: Option<LocalWallet>,
+    provider: Provider<FailoverClient>,
+    wallet: Option<Arc<dyn Signer>>,
     chain_id: u64,
+    allowed_contracts: HashSet<Address>,
+    collateral: Address,
+    holder: Option<Address>,
+    gas: GasPolicy,
+    /// YES / NO position ids per condition; they never change, so each
+    /// condition is resolved on-chain once.
+    position_ids: Mutex<HashMap<H256, [U256; 2]>>,
 }
 
 impl PolymarketBlockchain {
+    /// `rpc_url` may list several comma-separated providers to fail over
+    /// between.
     pub fn new(rpc_url: &str) -> Result<Self> {
-        let provider = Provider::<Http>::try_from(rpc_url)
-            .context("Failed to create Polygon provider")?;
+        let provider = Provider::new(
+            FailoverClient::new(rpc_url).context("Failed to create Polygon provider")?,
+        );
 
         Ok(Self {
             provider,
             wallet: None,
             chain_id: 137,
+            allowed_contracts: default_allowed_contracts(),
+            collateral: USDC_ADDRESS.parse().context("Invalid USDC contract address")?,
+            holder: None,
+            gas: GasPolicy::from_env(),
+            position_ids: Mutex::new(HashMap::new()),
         })
     }
 
     pub fn with_wallet(mut self, private_key: &str) -> Result<Self> {
-        let wallet: LocalWallet = private_key
-            .parse()
-            .context("Invalid private key format. Must be hex string starting with 0x")?;
+        self.wallet = Some(Arc::new(signer::local_wallet(private_key, self.chain_id)?));
+        Ok(self)
+    }
 
-        let wallet = wallet.with_chain_id(self.chain_id);
-        self.wallet = Some(wallet);
+    /// Signs through `signer` (e.g. a [`signer::RemoteSigner`]) instead of a
+    /// local key.
+    pub fn with_signer(mut self, signer: Arc<dyn Signer>) -> Self {
+        self.wallet = Some(signer);
+        self
+    }
 
-        Ok(self)
+    pub fn with_gas_policy(mut self, gas: GasPolicy) -> Self {
+        self.gas = gas;
+        self
+    }
+
+    /// Collateral the outcome tokens are backed by (USDC by default).
+    pub fn with_collateral(mut self, collateral: Address) -> Self {
+        self.collateral = collateral;
+        self
+    }
+
+    /// Address whose outcome tokens are read, when it isn't the signing
+    /// wallet (proxy / Safe funder wallets).
+    pub fn with_holder(mut self, holder: Address) -> Self {
+        self.holder = Some(holder);
+        self
+    }
+
+    /// Reads balances from the account's proxy / Safe / deposit wallet
+    /// when it has one (see [`funder_address`]).
+    pub fn with_funder_from_env(self) -> Result<Self> {
+        Ok(match funder_address(self.address()?)? {
+            Some(funder) => self.with_holder(funder),
+            None => self,
+        })
+    }
+
+    /// Address whose balances are read: the funder wallet, else the signer.
+    pub fn holder(&self) -> Result<Address> {
+        match self.holder {
+            Some(holder) => Ok(holder),
+            None => self.address(),
+        }
+    }
+
+    pub fn with_allowed_contract(mut self, address: Address) -> Self {
+        self.allowed_contracts.insert(address);
+        self
+    }
+
+    /// Refuses a transaction whose target, or the spender it approves, is
+    /// not on the allow-list. Contract creation is refused outright.
+    pub fn ensure_allowed(&self, tx: &TransactionRequest) -> Result<()> {
+        let to = tx
+            .to
+            .as_ref()
+            .and_then(|to| to.as_address())
+            .context("Refusing to sign a transaction without a plain `to` address")?;
+        if !self.allowed_contracts.contains(to) {
+            return Err(anyhow::anyhow!(
+                "Refusing to sign transaction to {:?}: not on the contract allow-list",
+                to
+            ));
+        }
+        if let Some(data) = tx.data.as_ref().filter(|d| d.len() >= 36) {
+            let selector = &data[..4];
+            if selector == APPROVE_SELECTOR || selector == SET_APPROVAL_FOR_ALL_SELECTOR {
+                let spender = Address::from_slice(&data[16..36]);
+                if !self.allowed_contracts.contains(&spender) {
+                    return Err(anyhow::anyhow!(
+                        "Refusing to approve {:?} on {:?}: spender not on the contract allow-list",
+                        spender,
+                        to
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks `tx` against the allow-list, then sends it as an EIP-1559
+    /// transaction and waits for it to be mined, replacing it with bumped
+    /// fees whenever it sits unmined past the gas policy's timeout. Every
+    /// outbound transaction goes through here. Returns the mined hash.
+    pub async fn send_transaction(&self, tx: TransactionRequest) -> Result<H256> {
+        self.ensure_allowed(&tx)?;
+        let wallet = self.wallet.as_ref().context("Wallet not initialized")?;
+
+        let (max_fee, priority) = self
+            .provider
+            .estimate_eip1559_fees(None)
+            .await
+            .context("Failed to estimate EIP-1559 fees")?;
+        let (max_fee, priority) = self.gas.fees(max_fee, priority);
+        let mut request = Eip1559TransactionRequest::new()
+            .from(wallet.address())
+            .chain_id(self.chain_id)
+            .max_fee_per_gas(max_fee)
+            .max_priority_fee_per_gas(priority);
+        if let Some(to) = tx.to {
+            request = request.to(to);
+        }
+        if let Some(data) = tx.data {
+            request = request.data(data);
+        }
+        if let Some(value) = tx.value {
+            request = request.value(value);
+        }
+        let mut tx = TypedTransaction::Eip1559(request);
+        self.provider
+            .fill_transaction(&mut tx, None)
+            .await
+            .context("Failed to fill transaction")?;
+
+        let mut sent = vec![self.sign_and_send(wallet.as_ref(), &tx).await?];
+        for attempt in 1..=self.gas.max_replacements {
+            if let Some(hash) = self.wait_for_any(&sent, self.gas.stuck_after).await? {
+                return Ok(hash);
+            }
+            let TypedTransaction::Eip1559(inner) = &mut tx else {
+                unreachable!("built as EIP-1559");
+            };
+            let (max_fee, priority) = self.gas.bumped(
+                inner.max_fee_per_gas.unwrap_or_default(),
+                inner.max_priority_fee_per_gas.unwrap_or_default(),
+            );
+            inner.max_fee_per_gas = Some(max_fee);
+            inner.max_priority_fee_per_gas = Some(priority);
+            tracing::warn!(
+                "⛽ Transaction {:?} unmined after {}s - replacing at {} gwei priority ({}/{})",
+                sent.last().copied().unwrap_or_default(),
+                self.gas.stuck_after.as_secs(),
+                priority / U256::exp10(9),
+                attempt,
+                self.gas.max_replacements
+            );
+            match self.sign_and_send(wallet.as_ref(), &tx).await {
+                Ok(hash) => sent.push(hash),
+                // Usually "nonce too low": an earlier send was just mined.
+                Err(e) => tracing::warn!("⛽ Replacement not accepted: {}", e),
+            }
+        }
+        self.wait_for_any(&sent, self.gas.stuck_after)
+            .await?
+            .with_context(|| {
+                format!(
+                    "Transaction {:?} still unmined after {} replacements",
+                    sent.last().copied().unwrap_or_default(),
+                    self.gas.max_replacements
+                )
+            })
+    }
+
+    async fn sign_and_send(&self, wallet: &dyn Signer, tx: &TypedTransaction) -> Result<H256> {
+        let signature = wallet
+            .sign_transaction(tx)
+            .await
+            .context("Failed to sign transaction")?;
+        let pending = self
+            .provider
+            .send_raw_transaction(tx.rlp_signed(&signature))
+            .await
+            .context("Failed to send transaction")?;
+        Ok(pending.tx_hash())
+    }
+
+    /// Polls until one of `hashes` (sends of the same nonce) is mined, for up
+    /// to `timeout`. Errors if the mined one reverted.
+    async fn wait_for_any(&self, hashes: &[H256], timeout: std::time::Duration) -> Result<Option<H256>> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            for hash in hashes {
+                let receipt = self
+                    .provider
+                    .get_transaction_receipt(*hash)
+                    .await
+                    .context("Failed to get transaction receipt")?;
+                if let Some(receipt) = receipt {
+                    if receipt.status != Some(U64::from(1)) {
+                        return Err(anyhow::anyhow!("Transaction {:?} reverted", hash));
+                    }
+                    return Ok(Some(*hash));
+                }
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Ok(None);
+            }
+            tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Gas paid by a mined transaction, in POL; `None` while it is unmined.
+    pub async fn gas_paid(&self, tx_hash: H256) -> Result<Option<f64>> {
+        let receipt = self
+            .provider
+            .get_transaction_receipt(tx_hash)
+            .await
+            .context("Failed to get transaction receipt")?;
+        Ok(receipt.and_then(|r| {
+            let wei = r.gas_used? * r.effective_gas_price?;
+            Some(wei.low_u128() as f64 / 1e18)
+        }))
     }
 
     pub fn address(&self) -> Result<Address> {
@@ -43,11 +480,12 @@ impl PolymarketBlockchain {
         Ok(wallet.address())
     }
 
-    /// Reads bridged USDC balance on Polygon (legacy collateral).
+    /// Reads bridged USDC balance on Polygon (legacy collateral) of the
+    /// funder wallet, or the signer when there is none.
     /// CLOB V2 uses pUSD in the funder wallet; use Polymarket account APIs for trading balance.
     pub async fn get_usdc_balance(&self) -> Result<f64> {
-        let address = self.address()?;
-        let usdc_address: Address = "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174"
+        let address = self.holder()?;
+        let usdc_address: Address = USDC_ADDRESS
             .parse()
             .context("Invalid USDC contract address")?;
 
@@ -71,8 +509,7 @@ impl PolymarketBlockchain {
 
         if result.len() >= 32 {
             let balance = U256::from_big_endian(&result[..32]);
-            let balance_f64 = balance.as_u128() as f64 / 1_000_000.0;
-            Ok(balance_f64)
+            Ok(from_usdc_units(balance))
         } else {
             Err(anyhow::anyhow!(
                 "Invalid balance response from USDC contract"
@@ -80,6 +517,269 @@ impl PolymarketBlockchain {
         }
     }
 
+    /// Runs `calls` (target, calldata) through Multicall3, one RPC round
+    /// trip per [`MULTICALL_BATCH_SIZE`] calls. Returns each call's return
+    /// data in order, `None` where it reverted.
+    pub async fn multicall(&self, calls: &[(Address, Vec<u8>)]) -> Result<Vec<Option<Vec<u8>>>> {
+        let multicall: Address = MULTICALL3_ADDRESS.parse().context("Invalid Multicall3 address")?;
+        let mut results = Vec::with_capacity(calls.len());
+        for batch in calls.chunks(MULTICALL_BATCH_SIZE) {
+            let request = TransactionRequest::new().to(multicall).data(encode_aggregate3(batch));
+            let response = self
+                .provider
+                .call(&request.into(), None)
+                .await
+                .context("Failed to call Multicall3 aggregate3")?;
+            let decoded = decode_aggregate3(&response)?;
+            if decoded.len() != batch.len() {
+                return Err(anyhow::anyhow!(
+                    "Multicall3 returned {} results for {} calls",
+                    decoded.len(),
+                    batch.len()
+                ));
+            }
+            results.extend(decoded);
+        }
+        Ok(results)
+    }
+
+    /// Runs `calls` through Multicall3 and reads the first word of each
+    /// result, `None` where the call reverted or returned too little.
+    async fn multicall_words(&self, calls: &[(Address, Vec<u8>)]) -> Result<Vec<Option<U256>>> {
+        Ok(self
+            .multicall(calls)
+            .await?
+            .into_iter()
+            .map(|data| data.as_deref().and_then(first_word))
+            .collect())
+    }
+
+    /// ERC-1155 position ids of the YES (index set 1) and NO (index set 2)
+    /// outcomes of each binary condition. Uncached ones are resolved with
+    /// two batched rounds: `getCollectionId`, then `getPositionId`.
+    async fn position_ids(&self, conditions: &[H256]) -> Result<HashMap<H256, [U256; 2]>> {
+        let missing: Vec<H256> = {
+            let cache = self.position_ids.lock().unwrap_or_else(|e| e.into_inner());
+            let mut missing: Vec<H256> = conditions.iter().filter(|c| !cache.contains_key(c)).copied().collect();
+            missing.sort();
+            missing.dedup();
+            missing
+        };
+        if !missing.is_empty() {
+            let ctf: Address = CTF_ADDRESS.parse().context("Invalid CTF contract address")?;
+            let collection_calls: Vec<_> = missing
+                .iter()
+                .flat_map(|condition| [1u64, 2].map(|index_set| (condition, index_set)))
+                .map(|(condition, index_set)| {
+                    let data = encode_call(
+                        "getCollectionId(bytes32,bytes32,uint256)",
+                        &[
+                            Token::FixedBytes(vec![0u8; 32]),
+                            Token::FixedBytes(condition.0.to_vec()),
+                            Token::Uint(U256::from(index_set)),
+                        ],
+                    );
+                    (ctf, data)
+                })
+                .collect();
+            let collections = self.multicall(&collection_calls).await?;
+            let position_calls = collections
+                .iter()
+                .map(|collection| {
+                    let collection = collection
+                        .as_ref()
+                        .filter(|c| c.len() >= 32)
+                        .context("Invalid getCollectionId response")?;
+                    let data = encode_call(
+                        "getPositionId(address,bytes32)",
+                        &[Token::Address(self.collateral), Token::FixedBytes(collection[..32].to_vec())],
+                    );
+                    Ok((ctf, data))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let positions = self.multicall_words(&position_calls).await?;
+            let mut cache = self.position_ids.lock().unwrap_or_else(|e| e.into_inner());
+            for (condition, pair) in missing.iter().zip(positions.chunks(2)) {
+                match pair {
+                    [Some(yes), Some(no)] => {
+                        cache.insert(*condition, [*yes, *no]);
+                    }
+                    _ => return Err(anyhow::anyhow!("Invalid getPositionId response for {:?}", condition)),
+                }
+            }
+        }
+        let cache = self.position_ids.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(conditions.iter().filter_map(|c| cache.get(c).map(|ids| (*c, *ids))).collect())
+    }
+
+    /// YES and NO outcome-token balances held for `condition_id`, in shares.
+    pub async fn get_outcome_token_balances(&self, condition_id: &str) -> Result<(f64, f64)> {
+        H256::from_str(condition_id).with_context(|| format!("Invalid condition id {}", condition_id))?;
+        self.get_outcome_token_balances_batch(&[condition_id.to_string()])
+            .await?
+            .remove(condition_id)
+            .with_context(|| format!("Failed to read outcome token balances for {}", condition_id))
+    }
+
+    /// YES and NO outcome-token balances for many conditions, in shares,
+    /// read with a single batched `balanceOf` round trip once position ids
+    /// are cached. Conditions with an invalid id or a failed read are left
+    /// out of the result.
+    pub async fn get_outcome_token_balances_batch(
+        &self,
+        condition_ids: &[String],
+    ) -> Result<HashMap<String, (f64, f64)>> {
+        let holder = self.holder()?;
+        let conditions: Vec<(&String, H256)> = condition_ids
+            .iter()
+            .filter_map(|id| match H256::from_str(id) {
+                Ok(condition) => Some((id, condition)),
+                Err(_) => {
+                    tracing::warn!("Skipping invalid condition id {}", id);
+                    None
+                }
+            })
+            .collect();
+        let hashes: Vec<H256> = conditions.iter().map(|(_, c)| *c).collect();
+        let position_ids = self.position_ids(&hashes).await?;
+
+        let ctf: Address = CTF_ADDRESS.parse().context("Invalid CTF contract address")?;
+        let calls: Vec<_> = conditions
+            .iter()
+            .flat_map(|(_, condition)| position_ids[condition])
+            .map(|position_id| {
+                let data = encode_call(
+                    "balanceOf(address,uint256)",
+                    &[Token::Address(holder), Token::Uint(position_id)],
+                );
+                (ctf, data)
+            })
+            .collect();
+        let balances = self.multicall_words(&calls).await?;
+        Ok(conditions
+            .iter()
+            .zip(balances.chunks(2))
+            .filter_map(|((id, _), pair)| match pair {
+                [Some(yes), Some(no)] => Some(((*id).clone(), (from_usdc_units(*yes), from_usdc_units(*no)))),
+                _ => {
+                    tracing::warn!("Outcome token balance read failed for {}", id);
+                    None
+                }
+            })
+            .collect())
+    }
+
+    /// Redeems every outcome token of a resolved binary condition for
+    /// collateral via `redeemPositions` (losing tokens redeem for nothing).
+    /// Sent from the signing wallet, so it only redeems tokens held there.
+    pub async fn redeem_positions(&self, condition_id: &str) -> Result<H256> {
+        let condition = H256::from_str(condition_id)
+            .with_context(|| format!("Invalid condition id {}", condition_id))?;
+        let ctf: Address = CTF_ADDRESS.parse().context("Invalid CTF contract address")?;
+        let mut data = ethers::utils::id("redeemPositions(address,bytes32,bytes32,uint256[])").to_vec();
+        data.extend(abi::encode(&[
+            Token::Address(self.collateral),
+            Token::FixedBytes(vec![0u8; 32]),
+            Token::FixedBytes(condition.0.to_vec()),
+            Token::Array(vec![Token::Uint(U256::from(1)), Token::Uint(U256::from(2))]),
+        ]));
+        self.send_transaction(TransactionRequest::new().to(ctf).data(data)).await
+    }
+
+    /// Merges `shares` matched YES/NO pairs of a binary condition back into
+    /// as many dollars of collateral via `mergePositions`, before the market
+    /// resolves. Sent from the signing wallet, so both sides must be held
+    /// there; neg-risk markets merge through their adapter instead and aren't
+    /// covered.
+    pub async fn merge_positions(&self, condition_id: &str, shares: f64) -> Result<H256> {
+        let condition = H256::from_str(condition_id)
+            .with_context(|| format!("Invalid condition id {}", condition_id))?;
+        let ctf: Address = CTF_ADDRESS.parse().context("Invalid CTF contract address")?;
+        let mut data = ethers::utils::id("mergePositions(address,bytes32,bytes32,uint256[],uint256)").to_vec();
+        data.extend(abi::encode(&[
+            Token::Address(self.collateral),
+            Token::FixedBytes(vec![0u8; 32]),
+            Token::FixedBytes(condition.0.to_vec()),
+            Token::Array(vec![Token::Uint(U256::from(1)), Token::Uint(U256::from(2))]),
+            // Rounded down: never more than is held.
+            Token::Uint(U256::from(share_units(shares))),
+        ]));
+        self.send_transaction(TransactionRequest::new().to(ctf).data(data)).await
+    }
+
+    /// USDC `spender` may pull from the wallet, in dollars.
+    pub async fn usdc_allowance(&self, spender: Address) -> Result<f64> {
+        Ok(self.usdc_allowances(&[spender]).await?[0])
+    }
+
+    /// USDC each of `spenders` may pull from the wallet, in dollars, read in
+    /// one batched round trip.
+    pub async fn usdc_allowances(&self, spenders: &[Address]) -> Result<Vec<f64>> {
+        let usdc: Address = USDC_ADDRESS.parse().context("Invalid USDC contract address")?;
+        let holder = self.holder()?;
+        let calls: Vec<_> = spenders
+            .iter()
+            .map(|spender| {
+                let data = encode_call(
+                    "allowance(address,address)",
+                    &[Token::Address(holder), Token::Address(*spender)],
+                );
+                (usdc, data)
+            })
+            .collect();
+        self.multicall_words(&calls)
+            .await?
+            .into_iter()
+            .map(|allowance| {
+                allowance
+                    .map(from_usdc_units)
+                    .context("Invalid allowance response from USDC contract")
+            })
+            .collect()
+    }
+
+    /// Approves `spender` to pull up to `dollars` of USDC.
+    pub async fn approve_usdc(&self, spender: Address, dollars: f64) -> Result<H256> {
+        let usdc: Address = USDC_ADDRESS.parse().context("Invalid USDC contract address")?;
+        let mut data = APPROVE_SELECTOR.to_vec();
+        data.extend(abi::encode(&[Token::Address(spender), Token::Uint(usdc_units(dollars))]));
+        self.send_transaction(TransactionRequest::new().to(usdc).data(data)).await
+    }
+
+    /// Checks each exchange contract's USDC allowance covers `required`
+    /// dollars, approving up to `approve_cap` where it doesn't. Returns the
+    /// spenders still short.
+    pub async fn ensure_usdc_allowances(&self, required: f64, approve_cap: Option<f64>) -> Result<Vec<Address>> {
+        let spenders = USDC_SPENDERS
+            .iter()
+            .map(|s| s.parse::<Address>().context("Invalid spender address"))
+            .collect::<Result<Vec<_>>>()?;
+        let allowances = self.usdc_allowances(&spenders).await?;
+        let mut short = Vec::new();
+        for (spender, current) in spenders.into_iter().zip(allowances) {
+            // Approvals are sent from the signer, which can't approve for a
+            // proxy / Safe wallet.
+            let approve_cap = approve_cap.filter(|_| self.holder.is_none());
+            match allowance_action(current, required, approve_cap) {
+                AllowanceAction::Sufficient => {}
+                AllowanceAction::Approve(cap) => {
+                    let tx_hash = self.approve_usdc(spender, cap).await?;
+                    tracing::info!("✅ Approved {:?} for ${:.2} USDC: {:?}", spender, cap, tx_hash);
+                }
+                AllowanceAction::Insufficient => {
+                    tracing::warn!(
+                        "⚠️ USDC allowance for {:?} is ${:.2}, below ${:.2} - orders will revert until it is approved",
+                        spender,
+                        current,
+                        required
+                    );
+                    short.push(spender);
+                }
+            }
+        }
+        Ok(short)
+    }
+
     pub async fn check_transaction(&self, tx_hash: &str) -> Result<bool> {
         let hash = H256::from_str(tx_hash).context("Invalid transaction hash")?;
 
@@ -96,10 +796,142 @@ impl PolymarketBlockchain {
         }
     }
 
+    async fn call_feed(&self, feed: Address, data: Vec<u8>) -> Result<Bytes> {
+        self.provider
+            .call(&TransactionRequest::new().to(feed).data(Bytes::from(data)).into(), None)
+            .await
+            .with_context(|| format!("Failed to call Chainlink feed {:?}", feed))
+    }
+
+    /// `decimals()` of a Chainlink aggregator proxy.
+    pub async fn chainlink_decimals(&self, feed: Address) -> Result<u8> {
+        let result = self.call_feed(feed, vec![0x31, 0x3c, 0xe5, 0x67]).await?;
+        if result.len() < 32 {
+            return Err(anyhow::anyhow!("Invalid decimals response from {:?}", feed));
+        }
+        Ok(result[31])
+    }
+
+    /// `latestRoundData()` of a Chainlink aggregator proxy.
+    pub async fn chainlink_latest_round(&self, feed: Address, decimals: u8) -> Result<OracleRound> {
+        let result = self.call_feed(feed, vec![0xfe, 0xaf, 0x96, 0x8c]).await?;
+        decode_round(&result, decimals)
+    }
+
+    /// `getRoundData(round_id)`; reverts (errors) for rounds outside the
+    /// current aggregator phase.
+    pub async fn chainlink_round(&self, feed: Address, round_id: U256, decimals: u8) -> Result<OracleRound> {
+        let mut data = vec![0x9a, 0x6f, 0xc8, 0xf5];
+        let mut arg = [0u8; 32];
+        round_id.to_big_endian(&mut arg);
+        data.extend_from_slice(&arg);
+        let result = self.call_feed(feed, data).await?;
+        decode_round(&result, decimals)
+    }
+
     pub async fn get_gas_price(&self) -> Result<U256> {
         self.provider
             .get_gas_price()
             .await
             .context("Failed to get gas price")
     }
+
+    /// Chain id the RPC reports (137 on Polygon mainnet).
+    pub async fn rpc_chain_id(&self) -> Result<u64> {
+        let chain_id = self
+            .provider
+            .get_chainid()
+            .await
+            .context("Failed to get chain id")?;
+        Ok(chain_id.low_u64())
+    }
+
+    /// POL (formerly MATIC) the signing wallet holds for gas.
+    pub async fn get_pol_balance(&self) -> Result<f64> {
+        let wei = self
+            .provider
+            .get_balance(self.address()?, None)
+            .await
+            .context("Failed to get POL balance")?;
+        Ok(wei.as_u128() as f64 / 1e18)
+    }
+}
+
+/// Decodes (roundId, answer, startedAt, updatedAt, answeredInRound).
+fn decode_round(result: &[u8], decimals: u8) -> Result<OracleRound> {
+    if result.len() < 160 {
+        return Err(anyhow::anyhow!("Invalid round response from Chainlink feed"));
+    }
+    let word = |i: usize| U256::from_big_endian(&result[i * 32..(i + 1) * 32]);
+    let answer = word(1);
+    if answer.bit(255) {
+        return Err(anyhow::anyhow!("Negative Chainlink answer"));
+    }
+    let updated_at = Utc
+        .timestamp_opt(word(3).low_u64() as i64, 0)
+        .single()
+        .context("Invalid Chainlink updatedAt")?;
+    Ok(OracleRound {
+        round_id: word(0),
+        price: answer.as_u128() as f64 / 10f64.powi(decimals as i32),
+        updated_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn approves_only_when_short_and_capped_high_enough() {
+        assert_eq!(allowance_action(500.0, 200.0, None), AllowanceAction::Sufficient);
+        assert_eq!(allowance_action(0.0, 200.0, None), AllowanceAction::Insufficient);
+        assert_eq!(allowance_action(0.0, 200.0, Some(1000.0)), AllowanceAction::Approve(1000.0));
+        assert_eq!(allowance_action(0.0, 200.0, Some(100.0)), AllowanceAction::Insufficient);
+    }
+
+    #[test]
+    fn only_signs_for_allowed_contracts() {
+        let chain = PolymarketBlockchain::new("http://localhost:8545").unwrap();
+        let usdc: Address = USDC_ADDRESS.parse().unwrap();
+        let exchange: Address = CTF_EXCHANGE_ADDRESS.parse().unwrap();
+        let stranger = Address::repeat_byte(0x42);
+
+        assert!(chain.ensure_allowed(&TransactionRequest::new().to(usdc)).is_ok());
+        assert!(chain.ensure_allowed(&TransactionRequest::new().to(stranger)).is_err());
+        assert!(chain.ensure_allowed(&TransactionRequest::new()).is_err());
+
+        let approve = |spender: Address| {
+            let mut data = APPROVE_SELECTOR.to_vec();
+            data.extend_from_slice(&[0u8; 12]);
+            data.extend_from_slice(spender.as_bytes());
+            data.extend_from_slice(&[0xff; 32]);
+            TransactionRequest::new().to(usdc).data(data)
+        };
+        assert!(chain.ensure_allowed(&approve(exchange)).is_ok());
+        assert!(chain.ensure_allowed(&approve(stranger)).is_err());
+
+        let chain = chain.with_allowed_contract(stranger);
+        assert!(chain.ensure_allowed(&TransactionRequest::new().to(stranger)).is_ok());
+    }
+
+    #[test]
+    fn multicall_round_trips_through_aggregate3() {
+        let calls = vec![
+            (Address::repeat_byte(1), vec![0xaa, 0xbb]),
+            (Address::repeat_byte(2), Vec::new()),
+        ];
+        let data = encode_aggregate3(&calls);
+        assert_eq!(data[..4], [0x82, 0xad, 0x56, 0xcb]);
+
+        let response = abi::encode(&[Token::Array(vec![
+            Token::Tuple(vec![Token::Bool(true), Token::Bytes(abi::encode(&[Token::Uint(U256::from(2_500_000))]))]),
+            Token::Tuple(vec![Token::Bool(false), Token::Bytes(Vec::new())]),
+        ])]);
+        let results = decode_aggregate3(&response).unwrap();
+        assert_eq!(results.len(), 2);
+        let balance = results[0].as_deref().and_then(first_word).map(from_usdc_units);
+        assert_eq!(balance, Some(2.5));
+        assert!(results[1].is_none());
+    }
 }
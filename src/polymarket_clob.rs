@@ -3,15 +3,25 @@
 //! Uses the official `polymarket_client_sdk_v2` for authenticated trading and
 //! public REST endpoints for market data.
 
-use crate::event::MarketPrices;
+use crate::clients::venue_http_client;
+use crate::debug_capture;
+use crate::depth;
+use crate::event::{BookLevels, MarketPrices, Quote};
+use crate::exchange::{poll_fill, OrderFill, OrderSide, TimeInForce, DRY_RUN_ORDER_ID};
+use crate::metrics::TrackedSend;
+use crate::money::{Price, UsdAmount};
+use crate::tick::{snap_price, snap_size, POLYMARKET_DEFAULT_TICK, POLYMARKET_SIZE_DECIMALS};
 use anyhow::{Context, Result};
 use reqwest::Client;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::str::FromStr;
-use tracing::info;
+use std::sync::{Mutex, OnceLock};
+use tracing::{info, warn};
 
 pub const CLOB_HOST: &str = "https://clob.polymarket.com";
 pub const GAMMA_API_BASE: &str = "https://gamma-api.polymarket.com";
+pub const DATA_API_BASE: &str = "https://data-api.polymarket.com";
 
 #[derive(Debug, Clone)]
 pub struct TokenPair {
@@ -55,18 +65,46 @@ fn parse_price(value: &str) -> Option<f64> {
     value.parse::<f64>().ok()
 }
 
+/// Lowest ask. The CLOB lists asks from the top of the book down, so the
+/// first level is the worst price, not the best.
 fn best_ask(book: &OrderBookSummary) -> Option<f64> {
     book.asks
-        .first()
-        .and_then(|level| parse_price(&level.price))
+        .iter()
+        .filter_map(|level| parse_price(&level.price))
+        .reduce(f64::min)
 }
 
-fn book_liquidity(book: &OrderBookSummary) -> f64 {
-    book.asks
+fn best_bid(book: &OrderBookSummary) -> Option<f64> {
+    book.bids
+        .iter()
+        .filter_map(|level| parse_price(&level.price))
+        .reduce(f64::max)
+}
+
+fn ask_quotes(book: &OrderBookSummary) -> Vec<Quote> {
+    quotes(&book.asks)
+}
+
+fn bid_quotes(book: &OrderBookSummary) -> Vec<Quote> {
+    quotes(&book.bids)
+}
+
+fn quotes(levels: &[OrderLevel]) -> Vec<Quote> {
+    levels
         .iter()
-        .chain(book.bids.iter())
-        .filter_map(|level| parse_price(&level.size))
-        .sum()
+        .filter_map(|level| {
+            Some(Quote {
+                price: parse_price(&level.price)?,
+                size: parse_price(&level.size)?,
+            })
+        })
+        .collect()
+}
+
+pub fn dry_run_enabled() -> bool {
+    env("DRY_RUN")
+        .map(|s| s.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
 }
 
 fn clob_host() -> String {
@@ -78,7 +116,7 @@ pub async fn fetch_order_book(http: &Client, token_id: &str) -> Result<OrderBook
     let response = http
         .get(format!("{host}/book"))
         .query(&[("token_id", token_id)])
-        .send()
+        .send_tracked("polymarket", "clob:/book")
         .await
         .with_context(|| format!("Failed to fetch order book for token {token_id}"))?;
 
@@ -90,17 +128,55 @@ pub async fn fetch_order_book(http: &Client, token_id: &str) -> Result<OrderBook
         ));
     }
 
-    response
+    let raw: serde_json::Value = response
         .json()
         .await
+        .with_context(|| format!("Failed to parse order book for token {token_id}"))?;
+    debug_capture::record_response("polymarket /book", &raw);
+    serde_json::from_value(raw)
         .with_context(|| format!("Failed to parse order book for token {token_id}"))
 }
 
+/// YES/NO token ids of a market by condition id: the Gamma market's
+/// `clobTokenIds`, falling back to the CLOB's own market info.
 pub async fn resolve_token_pair(http: &Client, condition_id: &str) -> Result<TokenPair> {
+    match fetch_gamma_token_pair(http, condition_id).await {
+        Ok(Some(pair)) => return Ok(pair),
+        Ok(None) => warn!("Gamma has no clobTokenIds for {condition_id} - asking the CLOB"),
+        Err(e) => warn!("{e} - asking the CLOB"),
+    }
+    fetch_clob_token_pair(http, condition_id).await
+}
+
+async fn fetch_gamma_token_pair(http: &Client, condition_id: &str) -> Result<Option<TokenPair>> {
+    let response = http
+        .get(format!("{GAMMA_API_BASE}/markets"))
+        .query(&[("condition_ids", condition_id)])
+        .send_tracked("polymarket", "gamma:/markets")
+        .await
+        .with_context(|| format!("Failed to fetch Gamma market for {condition_id}"))?;
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!(
+            "Gamma /markets error {} for {condition_id}",
+            response.status()
+        ));
+    }
+    let markets: Vec<serde_json::Value> = response
+        .json()
+        .await
+        .with_context(|| format!("Failed to parse Gamma market for {condition_id}"))?;
+    Ok(markets
+        .iter()
+        .find(|m| m["conditionId"].as_str() == Some(condition_id))
+        .or(markets.first())
+        .and_then(parse_clob_token_ids_from_market))
+}
+
+async fn fetch_clob_token_pair(http: &Client, condition_id: &str) -> Result<TokenPair> {
     let host = clob_host();
     let response = http
         .get(format!("{host}/clob-markets/{condition_id}"))
-        .send()
+        .send_tracked("polymarket", "clob:/clob-markets/:id")
         .await
         .with_context(|| format!("Failed to fetch CLOB market info for {condition_id}"))?;
 
@@ -150,6 +226,60 @@ pub async fn resolve_token_pair(http: &Client, condition_id: &str) -> Result<Tok
     })
 }
 
+fn tick_cache() -> &'static Mutex<HashMap<String, Price>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Price>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Minimum tick size of a token's market (`GET /tick-size`), cached per token.
+pub async fn fetch_tick_size(http: &Client, token_id: &str) -> Result<Price> {
+    if let Some(tick) = tick_cache().lock().ok().and_then(|c| c.get(token_id).copied()) {
+        return Ok(tick);
+    }
+    let host = clob_host();
+    let response = http
+        .get(format!("{host}/tick-size"))
+        .query(&[("token_id", token_id)])
+        .send_tracked("polymarket", "clob:/tick-size")
+        .await
+        .with_context(|| format!("Failed to fetch tick size for token {token_id}"))?;
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!(
+            "CLOB /tick-size error {} for token {token_id}",
+            response.status()
+        ));
+    }
+    let raw: serde_json::Value = response
+        .json()
+        .await
+        .with_context(|| format!("Failed to parse tick size for token {token_id}"))?;
+    let tick = raw["minimum_tick_size"]
+        .as_f64()
+        .or_else(|| raw["minimum_tick_size"].as_str().and_then(parse_price))
+        .filter(|t| *t > 0.0)
+        .map(Price::from_f64)
+        .with_context(|| format!("No tick size for token {token_id}: {raw}"))?;
+    if let Ok(mut cache) = tick_cache().lock() {
+        cache.insert(token_id.to_string(), tick);
+    }
+    Ok(tick)
+}
+
+/// Snaps a limit price onto the token's tick grid, on the safe side for `side`.
+async fn tick_price(http: &Client, token_id: &str, side: OrderSide, price: f64) -> Result<Price> {
+    let tick = fetch_tick_size(http, token_id).await.unwrap_or_else(|e| {
+        warn!("{} - assuming {} tick", e, POLYMARKET_DEFAULT_TICK);
+        POLYMARKET_DEFAULT_TICK
+    });
+    snap_price(Price::from_f64(price), tick, side)
+        .with_context(|| format!("Invalid tick size {tick} for token {token_id}"))
+}
+
+/// Highest bid for a token, or `None` when the book has no bids.
+pub async fn fetch_best_bid(http: &Client, token_id: &str) -> Result<Option<f64>> {
+    Ok(best_bid(&fetch_order_book(http, token_id).await?))
+}
+
 pub async fn fetch_prices_for_tokens(
     http: &Client,
     yes_token_id: &str,
@@ -163,18 +293,30 @@ pub async fn fetch_prices_for_tokens(
     let yes_book = yes_book?;
     let no_book = no_book?;
 
-    let yes_ask = best_ask(&yes_book).unwrap_or(0.0);
-    let no_ask = best_ask(&no_book).unwrap_or(0.0);
-
-    let liquidity = book_liquidity(&yes_book) + book_liquidity(&no_book);
+    // An empty side has no price to buy at; a zero here would read as a
+    // free leg and a phantom arbitrage.
+    let yes_ask = best_ask(&yes_book)
+        .with_context(|| format!("No asks on the YES book (token {yes_token_id})"))?;
+    let no_ask = best_ask(&no_book)
+        .with_context(|| format!("No asks on the NO book (token {no_token_id})"))?;
+
+    let levels = BookLevels::sorted(
+        bid_quotes(&yes_book),
+        ask_quotes(&yes_book),
+        bid_quotes(&no_book),
+        ask_quotes(&no_book),
+    );
+    let depth = depth::market_depth(&levels.yes_asks, &levels.no_asks, depth::band());
     let last_price = yes_book
         .last_trade_price
         .as_deref()
         .and_then(parse_price)
         .or_else(|| no_book.last_trade_price.as_deref().and_then(parse_price));
 
-    // yes/no store best ask — the price to buy each side on CLOB V2.
-    Ok(MarketPrices::new(yes_ask, no_ask, liquidity).with_asks(yes_ask, no_ask, last_price))
+    Ok(MarketPrices::new(yes_ask, no_ask, depth)
+        .with_bids(best_bid(&yes_book), best_bid(&no_book))
+        .with_last_price(last_price)
+        .with_levels(levels))
 }
 
 pub fn parse_clob_token_ids(raw: Option<&str>) -> Option<TokenPair> {
@@ -190,25 +332,82 @@ pub fn parse_clob_token_ids(raw: Option<&str>) -> Option<TokenPair> {
     None
 }
 
-/// Gamma returns `clobTokenIds` as a JSON string or array.
+/// Gamma returns `clobTokenIds` (and `outcomes`) as a JSON string or array.
+/// Token ids follow the order of `outcomes`, which is usually but not always
+/// `["Yes", "No"]`.
 pub fn parse_clob_token_ids_from_market(market: &serde_json::Value) -> Option<TokenPair> {
-    let field = market.get("clobTokenIds")?;
-    if let Some(raw) = field.as_str() {
-        return parse_clob_token_ids(Some(raw));
-    }
-    if let Some(arr) = field.as_array() {
-        let ids: Vec<String> = arr
-            .iter()
-            .filter_map(|v| v.as_str().map(str::to_string))
-            .collect();
-        if ids.len() >= 2 {
-            return Some(TokenPair {
-                yes_token_id: ids[0].clone(),
-                no_token_id: ids[1].clone(),
-            });
+    let list = |field: &serde_json::Value| -> Option<Vec<String>> {
+        match field {
+            serde_json::Value::String(raw) => serde_json::from_str(raw).ok(),
+            serde_json::Value::Array(arr) => Some(
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(str::to_string))
+                    .collect(),
+            ),
+            _ => None,
         }
+    };
+    let ids = list(market.get("clobTokenIds")?)?;
+    if ids.len() < 2 {
+        return None;
     }
-    None
+    let outcomes = market.get("outcomes").and_then(list).unwrap_or_default();
+    let swapped = outcomes.len() == ids.len()
+        && outcomes[0].eq_ignore_ascii_case("no")
+        && outcomes[1].eq_ignore_ascii_case("yes");
+    let (yes, no) = if swapped { (1, 0) } else { (0, 1) };
+    Some(TokenPair {
+        yes_token_id: ids[yes].clone(),
+        no_token_id: ids[no].clone(),
+    })
+}
+
+fn private_key_from_env() -> Result<String> {
+    env("POLYMARKET_WALLET_PRIVATE_KEY")
+        .or_else(|| env("POLYMARKET_PRIVATE_KEY"))
+        .context(
+            "Polymarket private key required (POLYMARKET_WALLET_PRIVATE_KEY or POLYMARKET_PRIVATE_KEY)",
+        )
+}
+
+/// Builds an L1/L2-authenticated SDK client; evaluates to `(signer, client)`.
+macro_rules! authenticated_clob_client {
+    ($private_key:expr) => {{
+        use alloy::signers::local::LocalSigner;
+        use polymarket_client_sdk_v2::clob::{Client, Config};
+        use polymarket_client_sdk_v2::types::POLYGON;
+        use polymarket_client_sdk_v2::PRIVATE_KEY_VAR;
+
+        let _ = PRIVATE_KEY_VAR; // documented SDK env name; we accept both keys above.
+
+        let signer = LocalSigner::from_str($private_key)
+            .with_context(|| "Invalid Polymarket private key format")?
+            .with_chain_id(Some(POLYGON));
+
+        let mut auth = Client::new(clob_host(), Config::default())?
+            .authentication_builder(&signer);
+
+        // Proxy / Safe / deposit wallets sign with the EOA but trade the funder's balance.
+        let owner: ethers::types::Address = signer
+            .address()
+            .to_string()
+            .parse()
+            .context("Invalid Polymarket signer address")?;
+        if let Some(funder) = crate::polymarket_blockchain::funder_address(owner)? {
+            let funder = format!("{:?}", funder)
+                .parse()
+                .with_context(|| format!("Invalid funder address {:?}", funder))?;
+            auth = auth.funder(funder);
+        }
+
+        let client = auth
+            .signature_type(signature_type_from_env())
+            .authenticate()
+            .await
+            .context("Failed to authenticate Polymarket CLOB client (L1/L2)")?;
+
+        (signer, client)
+    }};
 }
 
 pub async fn place_clob_order(
@@ -218,25 +417,19 @@ pub async fn place_clob_order(
     max_price: f64,
     yes_token_id: Option<&str>,
     no_token_id: Option<&str>,
+    tif: TimeInForce,
 ) -> Result<Option<String>> {
-    if env("DRY_RUN")
-        .map(|s| s.eq_ignore_ascii_case("true"))
-        .unwrap_or(false)
-    {
+    if dry_run_enabled() {
         info!(
-            "[DRY RUN] Would place Polymarket CLOB order: condition={} outcome={} amount={} max_price={}",
-            condition_id, outcome, amount_usd, max_price
+            "[DRY RUN] Would place Polymarket CLOB order: condition={} outcome={} amount={} max_price={} tif={}",
+            condition_id, outcome, amount_usd, max_price, tif
         );
-        return Ok(Some("dry-run".to_string()));
+        return Ok(Some(DRY_RUN_ORDER_ID.to_string()));
     }
 
-    let private_key = env("POLYMARKET_WALLET_PRIVATE_KEY")
-        .or_else(|| env("POLYMARKET_PRIVATE_KEY"))
-        .context(
-            "Polymarket private key required (POLYMARKET_WALLET_PRIVATE_KEY or POLYMARKET_PRIVATE_KEY)",
-        )?;
+    let private_key = private_key_from_env()?;
 
-    let http = Client::new();
+    let http = venue_http_client("polymarket");
     let tokens = match (yes_token_id, no_token_id) {
         (Some(yes), Some(no)) => TokenPair {
             yes_token_id: yes.to_string(),
@@ -255,58 +448,180 @@ pub async fn place_clob_order(
         return Err(anyhow::anyhow!("Invalid max price: {max_price}"));
     }
 
-    let shares = amount_usd / max_price;
+    let limit = tick_price(&http, &token_id, OrderSide::Buy, max_price).await?;
+    let shares = snap_size(
+        UsdAmount::from_f64(amount_usd).shares_at(limit),
+        POLYMARKET_SIZE_DECIMALS,
+    );
     if shares <= 0.0 {
         return Err(anyhow::anyhow!("Order size too small for amount {amount_usd}"));
     }
 
-    use alloy::signers::local::LocalSigner;
-    use polymarket_client_sdk_v2::clob::types::Side;
-    use polymarket_client_sdk_v2::clob::{Client, Config};
-    use polymarket_client_sdk_v2::types::{Decimal, U256, POLYGON};
-    use polymarket_client_sdk_v2::PRIVATE_KEY_VAR;
+    // Limit buy at max_price (on tick, rounded down) — fills immediately when ask <= limit.
+    post_clob_limit_order(&private_key, &token_id, OrderSide::Buy, shares, limit, tif)
+        .await
+        .map(Some)
+}
+
+/// Limit sell of `shares` of a token at `min_price`, fill-and-kill: fills
+/// against bids at or above it and cancels the rest, like Kalshi's sells.
+pub async fn place_clob_sell_order(
+    token_id: &str,
+    shares: f64,
+    min_price: f64,
+) -> Result<Option<String>> {
+    if dry_run_enabled() {
+        info!(
+            "[DRY RUN] Would sell on Polymarket CLOB: token={} shares={} min_price={}",
+            token_id, shares, min_price
+        );
+        return Ok(Some(DRY_RUN_ORDER_ID.to_string()));
+    }
+    if min_price <= 0.0 || shares <= 0.0 {
+        return Err(anyhow::anyhow!(
+            "Invalid sell: {shares} shares at {min_price}"
+        ));
+    }
 
-    let _ = PRIVATE_KEY_VAR; // documented SDK env name; we accept both keys above.
+    let private_key = private_key_from_env()?;
+    let limit = tick_price(&venue_http_client("polymarket"), token_id, OrderSide::Sell, min_price).await?;
+    let shares = snap_size(shares, POLYMARKET_SIZE_DECIMALS);
+    if shares <= 0.0 {
+        return Err(anyhow::anyhow!("Sell size rounds to zero for token {token_id}"));
+    }
+    post_clob_limit_order(&private_key, token_id, OrderSide::Sell, shares, limit, TimeInForce::Ioc)
+        .await
+        .map(Some)
+}
 
-    let signer = LocalSigner::from_str(&private_key)
-        .with_context(|| "Invalid Polymarket private key format")?
-        .with_chain_id(Some(POLYGON));
+/// Authenticates with the CLOB (deriving or creating the L2 API key from the
+/// wallet signature), which proves the wallet, funder and signature type
+/// line up; returns the signer address.
+pub async fn check_clob_credentials() -> Result<String> {
+    let private_key = private_key_from_env()?;
+    let (signer, _client) = authenticated_clob_client!(&private_key);
+    Ok(signer.address().to_string())
+}
 
-    let clob_host = env("POLYMARKET_CLOB_HOST").unwrap_or_else(|| CLOB_HOST.to_string());
+/// Cancels every open CLOB order for the account; returns how many were cancelled.
+pub async fn cancel_all_clob_orders() -> Result<usize> {
+    if dry_run_enabled() {
+        info!("[DRY RUN] Would cancel all Polymarket CLOB orders");
+        return Ok(0);
+    }
 
-    let mut auth = Client::new(clob_host, Config::default())?
-        .authentication_builder(&signer);
+    let private_key = private_key_from_env()?;
+    let (_signer, client) = authenticated_clob_client!(&private_key);
 
-    if let Some(funder) = env("POLYMARKET_FUNDER_ADDRESS")
-        .or_else(|| env("DEPOSIT_WALLET_ADDRESS"))
-    {
-        let funder = funder
-            .parse()
-            .with_context(|| format!("Invalid POLYMARKET_FUNDER_ADDRESS: {funder}"))?;
-        auth = auth.funder(funder);
+    let response = client
+        .cancel_all_orders()
+        .await
+        .context("Failed to cancel Polymarket CLOB orders")?;
+
+    Ok(response.canceled.len())
+}
+
+/// How much of a CLOB order has matched, priced at its limit (the order
+/// lookup carries no average). Orders stop filling once they leave the book,
+/// i.e. are no longer live (or delayed for matching).
+fn clob_order_fill(size_matched: &str, price: &str, status: &str) -> OrderFill {
+    let shares: f64 = size_matched.parse().unwrap_or(0.0);
+    let price: f64 = price.parse().unwrap_or(0.0);
+    let status = status.to_ascii_lowercase();
+    OrderFill {
+        shares,
+        price: if shares > 0.0 { price } else { 0.0 },
+        done: !(status.contains("live") || status.contains("delayed")),
     }
+}
 
-    let client = auth
-        .signature_type(signature_type_from_env())
-        .authenticate()
+/// How much of a CLOB order has filled; see [`clob_order_fill`].
+pub async fn fetch_clob_order_fill(order_id: &str) -> Result<OrderFill> {
+    let private_key = private_key_from_env()?;
+    let (_signer, client) = authenticated_clob_client!(&private_key);
+
+    let order = client
+        .order(order_id)
         .await
-        .context("Failed to authenticate Polymarket CLOB client (L1/L2)")?;
+        .with_context(|| format!("Failed to fetch Polymarket CLOB order {order_id}"))?;
+    Ok(clob_order_fill(
+        &order.size_matched.to_string(),
+        &order.price.to_string(),
+        &format!("{:?}", order.status),
+    ))
+}
+
+/// Polls a CLOB order until it leaves the book or `deadline` passes,
+/// authenticating once for all the lookups.
+pub async fn poll_clob_order_fill(order_id: &str, deadline: tokio::time::Instant) -> Result<OrderFill> {
+    let private_key = private_key_from_env()?;
+    let (_signer, client) = authenticated_clob_client!(&private_key);
+    let client = &client;
+
+    poll_fill(deadline, || async move {
+        let order = client
+            .order(order_id)
+            .await
+            .with_context(|| format!("Failed to fetch Polymarket CLOB order {order_id}"))?;
+        Ok(clob_order_fill(
+            &order.size_matched.to_string(),
+            &order.price.to_string(),
+            &format!("{:?}", order.status),
+        ))
+    })
+    .await
+}
 
-    let token = U256::from_str(&token_id)
+/// GTD orders expire this long before their stated expiration.
+const GTD_SECURITY_SECS: i64 = 60;
+
+async fn post_clob_limit_order(
+    private_key: &str,
+    token_id: &str,
+    side: OrderSide,
+    shares: f64,
+    price: Price,
+    tif: TimeInForce,
+) -> Result<String> {
+    use polymarket_client_sdk_v2::clob::types::{OrderType, Side};
+    use polymarket_client_sdk_v2::types::{Decimal, U256};
+
+    let (signer, client) = authenticated_clob_client!(private_key);
+
+    let token = U256::from_str(token_id)
         .with_context(|| format!("Invalid Polymarket token id: {token_id}"))?;
 
-    let size = Decimal::from_f64_retain(shares)
+    // Via strings: from_f64 would carry binary noise past the tick grid.
+    let size = Decimal::from_str(&shares.to_string())
         .with_context(|| format!("Invalid order size: {shares}"))?;
-    let price = Decimal::from_f64_retain(max_price)
-        .with_context(|| format!("Invalid order price: {max_price}"))?;
+    let limit = Decimal::from_str(&price.value().to_string())
+        .with_context(|| format!("Invalid order price: {price}"))?;
 
-    // Limit buy at max_price — fills immediately when ask <= max_price.
-    let order = client
+    // The CLOB's IOC is FAK (fill and kill); an expiring GTC is a GTD order,
+    // which the CLOB expires a minute before its stated expiration. Post-only
+    // orders are GTC/GTD flagged so the CLOB rejects them rather than cross.
+    let mut builder = client
         .limit_order()
         .token_id(token)
         .size(size)
-        .price(price)
-        .side(Side::Buy)
+        .price(limit)
+        .side(match side {
+            OrderSide::Buy => Side::Buy,
+            OrderSide::Sell => Side::Sell,
+        })
+        .order_type(match tif {
+            TimeInForce::Ioc => OrderType::FAK,
+            TimeInForce::Fok => OrderType::FOK,
+            TimeInForce::Gtc { expires_after: None } | TimeInForce::PostOnly { expires_after: None } => OrderType::GTC,
+            TimeInForce::Gtc { expires_after: Some(_) } | TimeInForce::PostOnly { expires_after: Some(_) } => {
+                OrderType::GTD
+            }
+        })
+        .post_only(tif.is_post_only());
+    if let Some(expires) = tif.expires_at(chrono::Utc::now()) {
+        builder = builder.expiration(expires + chrono::Duration::seconds(GTD_SECURITY_SECS));
+    }
+    let order = builder
         .build()
         .await
         .context("Failed to build Polymarket CLOB V2 order")?;
@@ -326,20 +641,63 @@ pub async fn place_clob_order(
         response.order_id, response.status
     );
 
-    Ok(Some(response.order_id))
+    Ok(response.order_id)
 }
 
 fn signature_type_from_env() -> polymarket_client_sdk_v2::clob::types::SignatureType {
     use polymarket_client_sdk_v2::clob::types::SignatureType;
 
-    match env("POLYMARKET_SIGNATURE_TYPE")
-        .unwrap_or_default()
-        .to_lowercase()
-        .as_str()
-    {
-        "1" | "magic" | "proxy" | "email" => SignatureType::Proxy,
-        "2" | "safe" | "gnosis" | "browser" => SignatureType::GnosisSafe,
-        "3" | "poly1271" | "deposit" | "poly_1271" => SignatureType::Poly1271,
-        _ => SignatureType::Eoa,
+    use crate::polymarket_blockchain::WalletKind;
+
+    match WalletKind::from_env() {
+        WalletKind::Proxy => SignatureType::Proxy,
+        WalletKind::Safe => SignatureType::GnosisSafe,
+        WalletKind::Deposit => SignatureType::Poly1271,
+        WalletKind::Eoa => SignatureType::Eoa,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_ids_follow_outcome_order() {
+        let market = serde_json::json!({
+            "clobTokenIds": "[\"111\", \"222\"]",
+            "outcomes": "[\"Yes\", \"No\"]",
+        });
+        let pair = parse_clob_token_ids_from_market(&market).unwrap();
+        assert_eq!((pair.yes_token_id.as_str(), pair.no_token_id.as_str()), ("111", "222"));
+
+        let swapped = serde_json::json!({"clobTokenIds": ["111", "222"], "outcomes": ["No", "Yes"]});
+        let pair = parse_clob_token_ids_from_market(&swapped).unwrap();
+        assert_eq!((pair.yes_token_id.as_str(), pair.no_token_id.as_str()), ("222", "111"));
+
+        assert!(parse_clob_token_ids_from_market(&serde_json::json!({"clobTokenIds": "[]"})).is_none());
+    }
+
+    #[test]
+    fn best_ask_is_the_lowest_ask() {
+        let book: OrderBookSummary = serde_json::from_value(serde_json::json!({
+            "bids": [{"price": "0.40", "size": "10"}, {"price": "0.42", "size": "5"}],
+            "asks": [{"price": "0.99", "size": "100"}, {"price": "0.45", "size": "20"}],
+        }))
+        .unwrap();
+        assert_eq!(best_ask(&book), Some(0.45));
+        assert_eq!(best_bid(&book), Some(0.42));
+    }
+
+    #[test]
+    fn order_fill_is_done_once_the_order_leaves_the_book() {
+        assert_eq!(
+            clob_order_fill("12.5", "0.45", "Live"),
+            OrderFill { shares: 12.5, price: 0.45, done: false }
+        );
+        assert_eq!(
+            clob_order_fill("0", "0.45", "Canceled"),
+            OrderFill { shares: 0.0, price: 0.0, done: true }
+        );
+        assert!(clob_order_fill("20", "0.45", "Matched").done);
     }
 }
@@ -0,0 +1,231 @@
+//! Stop-loss / take-profit on unhedged legs.
+//!
+//! A leg with no open partner (the leftover of a one-sided fill, or the
+//! survivor of a partial early exit) carries full directional risk until
+//! settlement. Every `POSITION_MONITOR_INTERVAL_SECS` (default 15) those legs
+//! are marked to their bid and sold once the mark is up `TAKE_PROFIT_PCT` or
+//! down `STOP_LOSS_PCT` against cost (e.g. `0.5` / `0.3`); either may be unset.
+//! A leg is closed only for the shares its sell is confirmed to have filled.
+
+use crate::early_exit::sale_value;
+use crate::exchange::{confirm_fill, ExchangeClient, FILL_CONFIRM_TIMEOUT};
+use crate::money::{Price, UsdAmount};
+use crate::position_tracker::{Position, PositionTracker};
+use crate::strategy::CROSS_PLATFORM;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExitReason {
+    TakeProfit,
+    StopLoss,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ExitRules {
+    /// Fractional gain on cost that triggers a sale.
+    pub take_profit: Option<f64>,
+    /// Fractional loss on cost that triggers a sale.
+    pub stop_loss: Option<f64>,
+}
+
+impl ExitRules {
+    /// `None` when neither `TAKE_PROFIT_PCT` nor `STOP_LOSS_PCT` is set.
+    pub fn from_env() -> Option<Self> {
+        let parse = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|s| s.trim().parse::<f64>().ok())
+                .filter(|v| *v > 0.0)
+        };
+        let rules = Self {
+            take_profit: parse("TAKE_PROFIT_PCT"),
+            stop_loss: parse("STOP_LOSS_PCT"),
+        };
+        (rules.take_profit.is_some() || rules.stop_loss.is_some()).then_some(rules)
+    }
+
+    /// Which rule, if any, a leg bought for `cost` and now worth `value` hits.
+    pub fn trigger(&self, cost: UsdAmount, value: UsdAmount) -> Option<ExitReason> {
+        if cost <= UsdAmount::ZERO {
+            return None;
+        }
+        let change = value.ratio(cost) - 1.0;
+        if self.take_profit.is_some_and(|tp| change >= tp) {
+            Some(ExitReason::TakeProfit)
+        } else if self.stop_loss.is_some_and(|sl| change <= -sl) {
+            Some(ExitReason::StopLoss)
+        } else {
+            None
+        }
+    }
+}
+
+/// Open cross-platform legs without an open partner in their group.
+pub fn unhedged_legs(tracker: &PositionTracker) -> Vec<Position> {
+    tracker.unhedged_legs(CROSS_PLATFORM).into_iter().cloned().collect()
+}
+
+pub struct PositionMonitor {
+    polymarket_client: Arc<dyn ExchangeClient>,
+    kalshi_client: Arc<dyn ExchangeClient>,
+    position_tracker: Arc<Mutex<PositionTracker>>,
+    rules: ExitRules,
+    fee_per_share: f64,
+}
+
+impl PositionMonitor {
+    pub fn new(
+        polymarket_client: Arc<dyn ExchangeClient>,
+        kalshi_client: Arc<dyn ExchangeClient>,
+        position_tracker: Arc<Mutex<PositionTracker>>,
+        rules: ExitRules,
+    ) -> Self {
+        Self {
+            polymarket_client,
+            kalshi_client,
+            position_tracker,
+            rules,
+            fee_per_share: 0.01,
+        }
+    }
+
+    pub fn with_fee_per_share(mut self, fee: f64) -> Self {
+        self.fee_per_share = fee;
+        self
+    }
+
+    /// `POSITION_MONITOR_INTERVAL_SECS`, default 15.
+    pub fn interval_from_env() -> Duration {
+        Duration::from_secs(
+            std::env::var("POSITION_MONITOR_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.trim().parse::<u64>().ok())
+                .filter(|s| *s > 0)
+                .unwrap_or(15),
+        )
+    }
+
+    fn client(&self, platform: &str) -> &dyn ExchangeClient {
+        if platform == "kalshi" {
+            self.kalshi_client.as_ref()
+        } else {
+            self.polymarket_client.as_ref()
+        }
+    }
+
+    /// Marks every unhedged leg and sells the ones that hit a rule; returns
+    /// how many were closed.
+    pub async fn run_tick(&self) -> usize {
+        let legs = unhedged_legs(&*self.position_tracker.lock().await);
+        let mut closed = 0;
+        for leg in legs {
+            match self.check_leg(&leg).await {
+                Ok(true) => closed += 1,
+                Ok(false) => {}
+                Err(e) => warn!("Mark-to-market failed for {}: {}", leg.event_title, e),
+            }
+        }
+        closed
+    }
+
+    async fn check_leg(&self, leg: &Position) -> anyhow::Result<bool> {
+        let client = self.client(&leg.platform);
+        let bid = client.fetch_prices(&leg.event_id).await?.bid_for(&leg.outcome);
+        if bid <= Price::ZERO {
+            return Ok(false);
+        }
+        let value = sale_value(leg.amount, bid, self.fee_per_share);
+        let Some(reason) = self.rules.trigger(leg.cost, value) else {
+            return Ok(false);
+        };
+
+        let label = match reason {
+            ExitReason::TakeProfit => "🎯 Take-profit",
+            ExitReason::StopLoss => "🛑 Stop-loss",
+        };
+        info!(
+            "{} on {} {} ({}): worth {} vs {} cost",
+            label, leg.event_title, leg.outcome, leg.platform, value, leg.cost
+        );
+        let sold = match client
+            .sell_order(leg.event_id.clone(), leg.outcome.clone(), leg.amount, bid.to_f64())
+            .await
+        {
+            Ok(order_id) => {
+                confirm_fill(client, &leg.event_id, order_id.as_deref(), leg.amount, bid.to_f64(), FILL_CONFIRM_TIMEOUT)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        match sold {
+            Ok(fill) if fill.shares > 0.0 => {
+                let proceeds = sale_value(fill.shares, Price::from_f64(fill.price), self.fee_per_share);
+                self.position_tracker
+                    .lock()
+                    .await
+                    .close_shares(&leg.id, fill.shares, proceeds);
+                if fill.shares < leg.amount - 1e-9 {
+                    warn!(
+                        "⚠️ {} sell on {} filled {:.4} of {:.4} {} shares - the rest stays open",
+                        label, leg.platform, fill.shares, leg.amount, leg.outcome
+                    );
+                    return Ok(false);
+                }
+                Ok(true)
+            }
+            Ok(_) => {
+                warn!(
+                    "⚠️ {} sell on {} did not fill - {:.4} {} shares still open",
+                    label, leg.platform, leg.amount, leg.outcome
+                );
+                Ok(false)
+            }
+            Err(e) => {
+                error!(
+                    "❌ {} sell failed on {}: {} - {:.4} {} shares still open",
+                    label, leg.platform, e, leg.amount, leg.outcome
+                );
+                Ok(false)
+            }
+        }
+    }
+
+    /// Runs `run_tick` every `interval` until the process exits.
+    pub async fn run(self: Arc<Self>, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let closed = self.run_tick().await;
+            if closed > 0 {
+                info!("🛡️ Closed {} unhedged legs on stop-loss/take-profit", closed);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn triggers_on_either_side_of_cost() {
+        let rules = ExitRules {
+            take_profit: Some(0.5),
+            stop_loss: Some(0.3),
+        };
+        let usd = UsdAmount::from_f64;
+        assert_eq!(rules.trigger(usd(100.0), usd(150.0)), Some(ExitReason::TakeProfit));
+        assert_eq!(rules.trigger(usd(100.0), usd(70.0)), Some(ExitReason::StopLoss));
+        assert_eq!(rules.trigger(usd(100.0), usd(120.0)), None);
+        assert_eq!(rules.trigger(usd(100.0), usd(75.0)), None);
+
+        let stop_only = ExitRules {
+            take_profit: None,
+            stop_loss: Some(0.3),
+        };
+        assert_eq!(stop_only.trigger(usd(100.0), usd(500.0)), None);
+    }
+}
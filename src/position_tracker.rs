@@ -1,8 +1,95 @@
 use crate::event::Event;
-use chrono::{DateTime, Utc};
+use crate::exchange::Resolution;
+use crate::journal::{Journal, JournalEntry};
+use crate::money::{Price, UsdAmount};
+use crate::store_writer::StoreWriter;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Timelike, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use tracing::info;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+pub const DEFAULT_STORE_PATH: &str = "state/positions.json";
+
+/// File format for `PositionTracker::export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn parse(format: &str) -> Option<Self> {
+        match format.trim().to_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "json" => Some(ExportFormat::Json),
+            _ => None,
+        }
+    }
+
+    /// Format named by the file extension; CSV when there is none.
+    pub fn from_path(path: &std::path::Path) -> Option<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) => Self::parse(ext),
+            None => Some(ExportFormat::Csv),
+        }
+    }
+}
+
+/// One position in an exported trade ledger.
+#[derive(Debug, Clone, Serialize)]
+pub struct LedgerRow {
+    pub created_at: DateTime<Utc>,
+    pub settled_at: Option<DateTime<Utc>>,
+    pub id: String,
+    pub group_id: Option<String>,
+    pub strategy: Option<String>,
+    pub platform: String,
+    pub event_id: String,
+    pub event_title: String,
+    pub coin: Option<String>,
+    pub outcome: String,
+    pub shares: f64,
+    pub price: f64,
+    pub cost: f64,
+    pub fees: Option<f64>,
+    pub order_id: Option<String>,
+    pub status: PositionStatus,
+    pub payout: Option<f64>,
+    pub profit: Option<f64>,
+    pub redemption_tx: Option<String>,
+    pub gas_pol: Option<f64>,
+}
+
+impl From<&Position> for LedgerRow {
+    fn from(p: &Position) -> Self {
+        Self {
+            created_at: p.created_at,
+            settled_at: p.settled_at,
+            id: p.id.clone(),
+            group_id: p.group_id.clone(),
+            strategy: p.strategy.clone(),
+            platform: p.platform.clone(),
+            event_id: p.event_id.clone(),
+            event_title: p.event_title.clone(),
+            coin: p.coin(),
+            outcome: p.outcome.clone(),
+            shares: p.amount,
+            price: p.price.to_f64(),
+            cost: p.cost.to_f64(),
+            fees: p.fees.map(UsdAmount::to_f64),
+            order_id: p.order_id.clone(),
+            status: p.status.clone(),
+            payout: p.payout.map(UsdAmount::to_f64),
+            profit: p.profit.map(UsdAmount::to_f64),
+            redemption_tx: p.redemption_tx.clone(),
+            gas_pol: p.gas_pol,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum PositionStatus {
@@ -19,15 +106,40 @@ pub struct Position {
     pub event_id: String,
     pub event_title: String,
     pub outcome: String,
+    /// Shares held.
     pub amount: f64,
-    pub cost: f64,
-    pub price: f64,
+    pub cost: UsdAmount,
+    pub price: Price,
     pub order_id: Option<String>,
     pub status: PositionStatus,
     pub created_at: DateTime<Utc>,
     pub settled_at: Option<DateTime<Utc>>,
-    pub payout: Option<f64>,
-    pub profit: Option<f64>,
+    pub payout: Option<UsdAmount>,
+    pub profit: Option<UsdAmount>,
+    /// Strategy that opened the position (`None` for adopted/legacy positions).
+    #[serde(default)]
+    pub strategy: Option<String>,
+    /// Shared by the legs of one hedged trade (the pair id), including a
+    /// leg left over when its partner failed.
+    #[serde(default, alias = "pair_id")]
+    pub group_id: Option<String>,
+    /// Transaction that redeemed a won Polymarket position for collateral.
+    #[serde(default)]
+    pub redemption_tx: Option<String>,
+    /// Venue fees charged on the position, when the venue reported them.
+    #[serde(default)]
+    pub fees: Option<UsdAmount>,
+    /// Underlying coin (btc/eth/sol) of the market, when known.
+    #[serde(default)]
+    pub coin: Option<String>,
+    /// Return on cost the trade was expected to make when placed (0.05 is
+    /// 5%); shared by the legs of a pair.
+    #[serde(default)]
+    pub detected_edge: Option<f64>,
+    /// Gas paid in POL for on-chain transactions on the position (its share
+    /// of a redemption).
+    #[serde(default)]
+    pub gas_pol: Option<f64>,
 }
 
 impl Position {
@@ -36,8 +148,8 @@ impl Position {
         event: &Event,
         outcome: String,
         amount: f64,
-        cost: f64,
-        price: f64,
+        cost: UsdAmount,
+        price: Price,
         order_id: Option<String>,
     ) -> Self {
         Self {
@@ -55,40 +167,203 @@ impl Position {
             settled_at: None,
             payout: None,
             profit: None,
+            strategy: None,
+            group_id: None,
+            redemption_tx: None,
+            fees: None,
+            coin: event.coin_from_slug(),
+            detected_edge: None,
+            gas_pol: None,
         }
     }
 
-    pub fn calculate_profit_if_won(&self) -> f64 {
+    pub fn with_strategy(mut self, strategy: &str) -> Self {
+        self.strategy = Some(strategy.to_string());
+        self
+    }
 
-        let payout = self.amount * 1.0;
-        payout - self.cost
+    pub fn with_detected_edge(mut self, edge: f64) -> Self {
+        self.detected_edge = Some(edge);
+        self
     }
 
-    pub fn calculate_profit_if_lost(&self) -> f64 {
+    pub fn with_group(mut self, group_id: &str) -> Self {
+        self.group_id = Some(group_id.to_string());
+        self
+    }
+
+    /// The market's coin; positions stored before it was recorded fall back
+    /// to the title.
+    pub fn coin(&self) -> Option<String> {
+        self.coin.clone().or_else(|| coin_in_title(&self.event_title).map(str::to_string))
+    }
 
+    pub fn calculate_profit_if_won(&self) -> UsdAmount {
+        // Each winning share pays $1.
+        UsdAmount::for_shares(self.amount, Price::ONE) - self.cost
+    }
+
+    pub fn calculate_profit_if_lost(&self) -> UsdAmount {
         -self.cost
     }
 }
 
+fn coin_in_title(title: &str) -> Option<&'static str> {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .find_map(|word| match word {
+            "btc" | "bitcoin" => Some("btc"),
+            "eth" | "ethereum" => Some("eth"),
+            "sol" | "solana" => Some("sol"),
+            _ => None,
+        })
+}
+
 pub struct PositionTracker {
     positions: HashMap<String, Position>,
+    store: Option<StoreWriter>,
+    journal: Option<Arc<Journal>>,
 }
 
 impl PositionTracker {
     pub fn new() -> Self {
         Self {
             positions: HashMap::new(),
+            store: None,
+            journal: None,
         }
     }
 
+    /// Opens a tracker backed by a JSON file, loading any positions already
+    /// stored there. Every mutation is written back to the file in the
+    /// background (see [`StoreWriter`]).
+    pub fn with_store(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut positions = HashMap::new();
+        if path.exists() {
+            let data = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read position store {}", path.display()))?;
+            let stored: Vec<Position> = serde_json::from_str(&data)
+                .with_context(|| format!("Failed to parse position store {}", path.display()))?;
+            for position in stored {
+                positions.insert(position.id.clone(), position);
+            }
+            info!("📂 Loaded {} positions from {}", positions.len(), path.display());
+        }
+        Ok(Self {
+            positions,
+            store: Some(StoreWriter::spawn(path)),
+            journal: None,
+        })
+    }
+
+    /// Journals every position booked and every settlement.
+    pub fn with_journal(mut self, journal: Arc<Journal>) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    fn journal_settlement(&self, position_id: &str) {
+        let (Some(journal), Some(position)) = (&self.journal, self.positions.get(position_id)) else {
+            return;
+        };
+        journal.record(JournalEntry::Settled {
+            position_id: position.id.clone(),
+            platform: position.platform.clone(),
+            event_id: position.event_id.clone(),
+            outcome: position.outcome.clone(),
+            status: format!("{:?}", position.status).to_lowercase(),
+            payout: position.payout.map(UsdAmount::to_f64),
+            profit: position.profit.map(UsdAmount::to_f64),
+        });
+    }
+
+    /// Writes the current positions and waits until they are on disk.
+    pub async fn save(&self) -> Result<()> {
+        let Some(store) = &self.store else {
+            return Ok(());
+        };
+        self.persist();
+        store.flush().await
+    }
+
+    /// Ledger of every position, oldest first.
+    pub fn ledger(&self) -> Vec<LedgerRow> {
+        let mut positions: Vec<&Position> = self.positions.values().collect();
+        positions.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
+        positions.into_iter().map(LedgerRow::from).collect()
+    }
+
+    /// Writes the trade ledger to `path`; returns the number of rows.
+    pub fn export(&self, path: impl AsRef<std::path::Path>, format: ExportFormat) -> Result<usize> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let rows = self.ledger();
+        match format {
+            ExportFormat::Csv => {
+                let mut writer = csv::Writer::from_path(path)
+                    .with_context(|| format!("Failed to create {}", path.display()))?;
+                for row in &rows {
+                    writer.serialize(row)?;
+                }
+                writer.flush()?;
+            }
+            ExportFormat::Json => {
+                fs::write(path, serde_json::to_string_pretty(&rows)?)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+            }
+        }
+        Ok(rows.len())
+    }
+
+    /// Queues a snapshot of every position for the store writer.
+    fn persist(&self) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        let mut positions: Vec<&Position> = self.positions.values().collect();
+        positions.sort_by_key(|p| p.created_at);
+        match serde_json::to_string_pretty(&positions) {
+            Ok(data) => store.write(data),
+            Err(e) => warn!("Failed to serialize positions for {}: {}", store.path().display(), e),
+        }
+    }
+
+    pub fn has_open_position(&self, platform: &str, event_id: &str, outcome: &str) -> bool {
+        self.positions.values().any(|p| {
+            p.status == PositionStatus::Open
+                && p.platform == platform
+                && p.event_id == event_id
+                && p.outcome.eq_ignore_ascii_case(outcome)
+        })
+    }
+
     pub fn add_position(&mut self, position: Position) {
-        info!("📝 Tracking new position: {} - {} {} @ ${:.4}", 
+        info!("📝 Tracking new position: {} - {} {} @ {}", 
             position.event_title, 
             position.outcome,
             position.amount,
             position.price
         );
+        if let Some(journal) = &self.journal {
+            journal.record(JournalEntry::Fill {
+                position_id: position.id.clone(),
+                platform: position.platform.clone(),
+                event_id: position.event_id.clone(),
+                outcome: position.outcome.clone(),
+                shares: position.amount,
+                cost: position.cost.to_f64(),
+                price: position.price.to_f64(),
+                order_id: position.order_id.clone(),
+                strategy: position.strategy.clone(),
+            });
+        }
         self.positions.insert(position.id.clone(), position);
+        self.persist();
     }
 
     pub fn get_open_positions(&self) -> Vec<&Position> {
@@ -113,8 +388,8 @@ impl PositionTracker {
         &mut self,
         position_id: &str,
         won: bool,
-        payout: Option<f64>,
-    ) -> Option<f64> {
+        payout: Option<UsdAmount>,
+    ) -> Option<UsdAmount> {
         if let Some(position) = self.positions.get_mut(position_id) {
             position.status = if won {
                 PositionStatus::Won
@@ -132,23 +407,210 @@ impl PositionTracker {
             position.profit = Some(profit);
 
             info!(
-                "💰 Position settled: {} - {} - Profit: ${:.2}",
+                "💰 Position settled: {} - {} - Profit: {}",
                 position.event_title,
                 if won { "WON" } else { "LOST" },
                 profit
             );
 
+            self.persist();
+            self.journal_settlement(position_id);
             Some(profit)
         } else {
             None
         }
     }
 
-    pub fn get_total_profit(&self) -> f64 {
-        self.positions
+    pub fn record_redemption(&mut self, position_id: &str, tx_hash: &str, gas_pol: Option<f64>) {
+        if let Some(position) = self.positions.get_mut(position_id) {
+            position.redemption_tx = Some(tx_hash.to_string());
+            if let Some(gas) = gas_pol {
+                position.gas_pol = Some(position.gas_pol.unwrap_or(0.0) + gas);
+            }
+            self.persist();
+        }
+    }
+
+    /// Settles a position in a market that resolved neither YES nor NO
+    /// (voided, cancelled, or split 50/50) at `payout`; `None` refunds its
+    /// cost. Returns the realized profit.
+    pub fn settle_at_payout(&mut self, position_id: &str, payout: Option<UsdAmount>) -> Option<UsdAmount> {
+        let position = self
+            .positions
+            .get_mut(position_id)
+            .filter(|p| p.status == PositionStatus::Open)?;
+        let payout = payout.unwrap_or(position.cost);
+        position.status = PositionStatus::Settled;
+        position.settled_at = Some(Utc::now());
+        position.payout = Some(payout);
+        let profit = payout - position.cost;
+        position.profit = Some(profit);
+
+        info!(
+            "↩️ Position settled without a winner: {} {} - Payout: {} - Profit: {}",
+            position.event_title, position.outcome, payout, profit
+        );
+
+        self.persist();
+        self.journal_settlement(position_id);
+        Some(profit)
+    }
+
+    /// Settles a position from the venue's own settlement record: `payout`
+    /// is what it credited, `cost` the filled cost when known, and `fees`
+    /// what it charged. Returns the realized profit.
+    pub fn settle_from_venue(
+        &mut self,
+        position_id: &str,
+        resolution: Resolution,
+        payout: UsdAmount,
+        cost: Option<UsdAmount>,
+        fees: UsdAmount,
+    ) -> Option<UsdAmount> {
+        let position = self
+            .positions
+            .get_mut(position_id)
+            .filter(|p| p.status == PositionStatus::Open)?;
+        if let Some(cost) = cost {
+            position.cost = cost;
+        }
+        position.status = match resolution {
+            Resolution::Yes | Resolution::No if payout > UsdAmount::ZERO => PositionStatus::Won,
+            Resolution::Yes | Resolution::No => PositionStatus::Lost,
+            Resolution::Split { .. } | Resolution::Refunded => PositionStatus::Settled,
+        };
+        position.settled_at = Some(Utc::now());
+        position.payout = Some(payout);
+        position.fees = Some(fees);
+        let profit = payout - position.cost - fees;
+        position.profit = Some(profit);
+
+        info!(
+            "🧾 Position settled by venue record: {} {} - Payout: {} - Fees: {} - Profit: {}",
+            position.event_title, position.outcome, payout, fees, profit
+        );
+
+        self.persist();
+        self.journal_settlement(position_id);
+        Some(profit)
+    }
+
+    /// Marks an open position as closed before resolution (sold back to the
+    /// venue for `proceeds`). Returns the realized profit.
+    pub fn close_position(&mut self, position_id: &str, proceeds: UsdAmount) -> Option<UsdAmount> {
+        let position = self
+            .positions
+            .get_mut(position_id)
+            .filter(|p| p.status == PositionStatus::Open)?;
+        position.status = PositionStatus::Settled;
+        position.settled_at = Some(Utc::now());
+        position.payout = Some(proceeds);
+        let profit = proceeds - position.cost;
+        position.profit = Some(profit);
+
+        info!(
+            "🔚 Position closed: {} {} - Proceeds: {} - Profit: {}",
+            position.event_title, position.outcome, proceeds, profit
+        );
+
+        self.persist();
+        self.journal_settlement(position_id);
+        Some(profit)
+    }
+
+    /// Closes `shares` of an open position sold for `proceeds`: the whole
+    /// position when that's all of it, else a split-off part of it, the rest
+    /// staying open with its share of the cost. Returns the realized profit.
+    pub fn close_shares(&mut self, position_id: &str, shares: f64, proceeds: UsdAmount) -> Option<UsdAmount> {
+        let position = self
+            .positions
+            .get_mut(position_id)
+            .filter(|p| p.status == PositionStatus::Open)?;
+        if shares >= position.amount - 1e-9 {
+            return self.close_position(position_id, proceeds);
+        }
+        let cost = position.cost.pro_rata(shares, position.amount);
+        position.amount -= shares;
+        position.cost -= cost;
+        let sold = Position {
+            id: format!("{}_{}", position.platform, &uuid::Uuid::new_v4().to_string()[..8]),
+            amount: shares,
+            cost,
+            ..position.clone()
+        };
+        let sold_id = sold.id.clone();
+        self.positions.insert(sold_id.clone(), sold);
+        self.close_position(&sold_id, proceeds)
+    }
+
+    /// Legs of one trade.
+    pub fn positions_in_group(&self, group_id: &str) -> Vec<&Position> {
+        let mut legs: Vec<&Position> = self
+            .positions
             .values()
-            .filter_map(|p| p.profit)
-            .sum()
+            .filter(|p| p.group_id.as_deref() == Some(group_id))
+            .collect();
+        legs.sort_by_key(|p| p.created_at);
+        legs
+    }
+
+    /// Net PnL of one trade across its legs.
+    pub fn pair_pnl(&self, group_id: &str) -> Option<PairPnl> {
+        let legs = self.positions_in_group(group_id);
+        if legs.is_empty() {
+            return None;
+        }
+        Some(PairPnl {
+            group_id: group_id.to_string(),
+            legs: legs.len(),
+            open_legs: legs.iter().filter(|p| p.status == PositionStatus::Open).count(),
+            cost: legs.iter().map(|p| p.cost).sum::<UsdAmount>().to_f64(),
+            realized_profit: legs.iter().filter_map(|p| p.profit).sum::<UsdAmount>().to_f64(),
+        })
+    }
+
+    /// Net PnL of every grouped trade, oldest first.
+    pub fn pair_pnls(&self) -> Vec<PairPnl> {
+        let mut first_seen: HashMap<&str, DateTime<Utc>> = HashMap::new();
+        for position in self.positions.values() {
+            if let Some(group) = &position.group_id {
+                let seen = first_seen.entry(group).or_insert(position.created_at);
+                *seen = (*seen).min(position.created_at);
+            }
+        }
+        let mut groups: Vec<(&str, DateTime<Utc>)> = first_seen.into_iter().collect();
+        groups.sort_by_key(|(group, created)| (*created, *group));
+        groups.into_iter().filter_map(|(group, _)| self.pair_pnl(group)).collect()
+    }
+
+    /// Open legs of `strategy` with no open partner: the survivor of a
+    /// one-sided fill or a partial exit, or an ungrouped legacy position.
+    pub fn unhedged_legs(&self, strategy: &str) -> Vec<&Position> {
+        let open: Vec<&Position> = self
+            .get_open_positions()
+            .into_iter()
+            .filter(|p| p.strategy.as_deref() == Some(strategy))
+            .collect();
+        let mut open_legs: HashMap<&str, usize> = HashMap::new();
+        for position in &open {
+            if let Some(group) = &position.group_id {
+                *open_legs.entry(group).or_default() += 1;
+            }
+        }
+        open.into_iter()
+            .filter(|p| p.group_id.as_deref().is_none_or(|g| open_legs[g] < 2))
+            .collect()
+    }
+
+    /// Open trades: the legs of a hedged pair count once.
+    pub fn open_trades(&self) -> usize {
+        let open = self.get_open_positions();
+        let groups: HashSet<&str> = open.iter().filter_map(|p| p.group_id.as_deref()).collect();
+        groups.len() + open.iter().filter(|p| p.group_id.is_none()).count()
+    }
+
+    pub fn get_total_profit(&self) -> f64 {
+        self.positions.values().filter_map(|p| p.profit).sum::<UsdAmount>().to_f64()
     }
 
     pub fn get_profit_by_platform(&self, platform: &str) -> f64 {
@@ -156,23 +618,76 @@ impl PositionTracker {
             .values()
             .filter(|p| p.platform == platform)
             .filter_map(|p| p.profit)
-            .sum()
+            .sum::<UsdAmount>()
+            .to_f64()
+    }
+
+    /// Cost of the strategy's open positions.
+    pub fn capital_in_use(&self, strategy: &str) -> f64 {
+        self.positions
+            .values()
+            .filter(|p| p.status == PositionStatus::Open && p.strategy.as_deref() == Some(strategy))
+            .map(|p| p.cost)
+            .sum::<UsdAmount>()
+            .to_f64()
     }
 
     pub fn get_statistics(&self) -> PositionStatistics {
-        let total = self.positions.len();
-        let open = self.positions.values().filter(|p| p.status == PositionStatus::Open).count();
-        let won = self.positions.values().filter(|p| p.status == PositionStatus::Won).count();
-        let lost = self.positions.values().filter(|p| p.status == PositionStatus::Lost).count();
-        let total_profit = self.get_total_profit();
+        Self::statistics_for(self.positions.values())
+    }
+
+    pub fn get_statistics_for_strategy(&self, strategy: &str) -> PositionStatistics {
+        Self::statistics_for(
+            self.positions
+                .values()
+                .filter(|p| p.strategy.as_deref() == Some(strategy)),
+        )
+    }
+
+    fn statistics_for<'a>(positions: impl Iterator<Item = &'a Position>) -> PositionStatistics {
+        let positions: Vec<&Position> = positions.collect();
+        let total = positions.len();
+        let open = positions.iter().filter(|p| p.status == PositionStatus::Open).count();
+        let won = positions.iter().filter(|p| p.status == PositionStatus::Won).count();
+        let lost = positions.iter().filter(|p| p.status == PositionStatus::Lost).count();
+        let total_profit = positions.iter().filter_map(|p| p.profit).sum::<UsdAmount>().to_f64();
 
-        PositionStatistics {
+        let mut stats = PositionStatistics {
             total_positions: total,
             open_positions: open,
             won_positions: won,
             lost_positions: lost,
             total_profit,
+            by_strategy: BTreeMap::new(),
+            by_platform: BTreeMap::new(),
+            by_coin: BTreeMap::new(),
+            by_hour: BTreeMap::new(),
+        };
+        for position in positions {
+            let strategy = position.strategy.clone().unwrap_or_else(|| "untagged".to_string());
+            stats.by_strategy.entry(strategy).or_default().add(position);
+            stats.by_platform.entry(position.platform.clone()).or_default().add(position);
+            let coin = position.coin().unwrap_or_else(|| "other".to_string());
+            stats.by_coin.entry(coin).or_default().add(position);
+            stats.by_hour.entry(position.created_at.hour()).or_default().add(position);
         }
+        stats
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PairPnl {
+    pub group_id: String,
+    pub legs: usize,
+    pub open_legs: usize,
+    pub cost: f64,
+    /// Profit realized so far by the settled or closed legs.
+    pub realized_profit: f64,
+}
+
+impl PairPnl {
+    pub fn is_closed(&self) -> bool {
+        self.open_legs == 0
     }
 }
 
@@ -183,5 +698,47 @@ pub struct PositionStatistics {
     pub won_positions: usize,
     pub lost_positions: usize,
     pub total_profit: f64,
+    pub by_strategy: BTreeMap<String, PnlBreakdown>,
+    pub by_platform: BTreeMap<String, PnlBreakdown>,
+    /// Keyed by coin (btc/eth/sol), `other` when the market has none.
+    pub by_coin: BTreeMap<String, PnlBreakdown>,
+    /// Keyed by the UTC hour the position was opened.
+    pub by_hour: BTreeMap<u32, PnlBreakdown>,
+}
+
+/// Positions and realized PnL in one slice of `PositionStatistics`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PnlBreakdown {
+    pub positions: usize,
+    pub open: usize,
+    pub won: usize,
+    pub lost: usize,
+    pub cost: f64,
+    pub profit: f64,
+}
+
+impl PnlBreakdown {
+    fn add(&mut self, position: &Position) {
+        self.positions += 1;
+        match position.status {
+            PositionStatus::Open => self.open += 1,
+            PositionStatus::Won => self.won += 1,
+            PositionStatus::Lost => self.lost += 1,
+            PositionStatus::Settled => {}
+        }
+        self.cost = (UsdAmount::from_f64(self.cost) + position.cost).to_f64();
+        if let Some(profit) = position.profit {
+            self.profit = (UsdAmount::from_f64(self.profit) + profit).to_f64();
+        }
+    }
+}
+
+/// `label: profit (positions)` per key, for one-line logs.
+pub fn format_breakdown<K: std::fmt::Display>(breakdown: &BTreeMap<K, PnlBreakdown>) -> String {
+    breakdown
+        .iter()
+        .map(|(key, b)| format!("{} ${:.2} ({})", key, b.profit, b.positions))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
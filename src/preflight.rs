@@ -0,0 +1,128 @@
+//! Pre-flight checks run before any leg of a trade is submitted.
+//!
+//! Orders the venue would reject — under Kalshi's one-contract minimum or
+//! Polymarket's minimum size/notional, over the configured maximum, or more
+//! than the available balance — are skipped with a reason instead of being
+//! sent, so one leg can't fill while its hedge bounces.
+//!
+//! `MAX_ORDER_USD` caps a single leg; `POLYMARKET_MIN_NOTIONAL` (default $1)
+//! and `POLYMARKET_MIN_SHARES` (default 5) set the Polymarket minimums.
+
+use crate::exchange::ExchangeClient;
+use crate::money::{Price, UsdAmount};
+use tracing::warn;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderSizeLimits {
+    pub kalshi_min_contracts: i64,
+    pub polymarket_min_notional: f64,
+    pub polymarket_min_shares: f64,
+    pub max_order_usd: Option<f64>,
+}
+
+impl Default for OrderSizeLimits {
+    fn default() -> Self {
+        Self {
+            kalshi_min_contracts: 1,
+            polymarket_min_notional: 1.0,
+            polymarket_min_shares: 5.0,
+            max_order_usd: None,
+        }
+    }
+}
+
+impl OrderSizeLimits {
+    pub fn from_env() -> Self {
+        let parse = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|s| s.trim().parse::<f64>().ok())
+                .filter(|v| *v >= 0.0)
+        };
+        let defaults = Self::default();
+        Self {
+            polymarket_min_notional: parse("POLYMARKET_MIN_NOTIONAL")
+                .unwrap_or(defaults.polymarket_min_notional),
+            polymarket_min_shares: parse("POLYMARKET_MIN_SHARES").unwrap_or(defaults.polymarket_min_shares),
+            max_order_usd: parse("MAX_ORDER_USD").filter(|v| *v > 0.0),
+            ..defaults
+        }
+    }
+
+    /// Why a buy of `amount` dollars at `price` on `platform` would be
+    /// rejected, if it would.
+    pub fn check_leg(&self, platform: &str, amount: f64, price: Price) -> Option<String> {
+        if price <= Price::ZERO || price >= Price::ONE {
+            return Some(format!("{} price {} outside (0, 1)", platform, price));
+        }
+        if let Some(max) = self.max_order_usd {
+            if amount > max {
+                return Some(format!("{} order ${:.2} over MAX_ORDER_USD ${:.2}", platform, amount, max));
+            }
+        }
+        let amount_usd = UsdAmount::from_f64(amount);
+        if platform == "kalshi" {
+            let contracts = amount_usd.contracts_at(price);
+            if contracts < self.kalshi_min_contracts {
+                return Some(format!(
+                    "Kalshi order of ${:.2} at {} buys {} contracts (min {})",
+                    amount, price, contracts, self.kalshi_min_contracts
+                ));
+            }
+        } else {
+            if amount < self.polymarket_min_notional {
+                return Some(format!(
+                    "Polymarket order ${:.2} under ${:.2} minimum",
+                    amount, self.polymarket_min_notional
+                ));
+            }
+            let shares = amount_usd.shares_at(price);
+            if shares < self.polymarket_min_shares {
+                return Some(format!(
+                    "Polymarket order of {:.2} shares under {} share minimum",
+                    shares, self.polymarket_min_shares
+                ));
+            }
+        }
+        None
+    }
+}
+
+/// Why `client`'s balance can't cover `amount`, if it can't. A balance that
+/// can't be read doesn't block the order.
+pub async fn check_balance(client: &dyn ExchangeClient, amount: f64) -> Option<String> {
+    match client.get_balance().await {
+        Ok(balance) if balance + 1e-9 < amount => Some(format!(
+            "{} balance ${:.2} below ${:.2} order",
+            client.platform(),
+            balance,
+            amount
+        )),
+        Ok(_) => None,
+        Err(e) => {
+            warn!("Balance check on {} failed: {} - submitting anyway", client.platform(), e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_orders_the_venue_would_bounce() {
+        let limits = OrderSizeLimits {
+            max_order_usd: Some(500.0),
+            ..OrderSizeLimits::default()
+        };
+        assert!(limits.check_leg("kalshi", 100.0, Price::from_cents(40)).is_none());
+        assert!(limits.check_leg("polymarket", 100.0, Price::from_cents(45)).is_none());
+        // $0.30 buys no whole contract at 0.40.
+        assert!(limits.check_leg("kalshi", 0.30, Price::from_cents(40)).is_some());
+        // $2 at 0.50 is 4 shares, under Polymarket's 5.
+        assert!(limits.check_leg("polymarket", 2.0, Price::from_cents(50)).is_some());
+        assert!(limits.check_leg("polymarket", 0.50, Price::from_cents(5)).is_some());
+        assert!(limits.check_leg("kalshi", 600.0, Price::from_cents(40)).is_some());
+    }
+}
@@ -0,0 +1,172 @@
+//! Daily/cycle profit target with early stop.
+//!
+//! Once realized profit for the current period reaches the target the bot
+//! goes manage-only: strategies stop opening positions while settlement and
+//! exits carry on. The next period starts fresh.
+//!
+//! `PROFIT_TARGET=50` (dollars) with `PROFIT_TARGET_PERIOD=daily` (UTC days,
+//! the default) or a cycle length such as `6h` / `90m`.
+
+use crate::money::UsdAmount;
+use crate::position_tracker::PositionTracker;
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Mutex;
+use tracing::info;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TargetPeriod {
+    /// UTC calendar day.
+    Daily,
+    /// Back-to-back cycles of a fixed length, counted from `anchor`.
+    Cycle { length: Duration, anchor: DateTime<Utc> },
+}
+
+impl TargetPeriod {
+    /// `daily`, or a length like `6h`, `90m`; cycles start at `anchor`.
+    pub fn parse(s: &str, anchor: DateTime<Utc>) -> Option<Self> {
+        let s = s.trim().to_lowercase();
+        if s.is_empty() || s == "daily" || s == "day" {
+            return Some(TargetPeriod::Daily);
+        }
+        let (value, unit) = s.split_at(s.len() - 1);
+        let value = value.trim().parse::<i64>().ok().filter(|v| *v > 0)?;
+        let length = match unit {
+            "h" => Duration::hours(value),
+            "m" => Duration::minutes(value),
+            "d" => Duration::days(value),
+            _ => return None,
+        };
+        Some(TargetPeriod::Cycle { length, anchor })
+    }
+
+    pub fn start_of(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        match *self {
+            TargetPeriod::Daily => now
+                .date_naive()
+                .and_hms_opt(0, 0, 0)
+                .map(|midnight| midnight.and_utc())
+                .unwrap_or(now),
+            TargetPeriod::Cycle { length, anchor } => {
+                let elapsed = (now - anchor).num_seconds().max(0);
+                anchor + Duration::seconds(elapsed - elapsed % length.num_seconds().max(1))
+            }
+        }
+    }
+
+    pub fn end_of(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let start = self.start_of(now);
+        match *self {
+            TargetPeriod::Daily => start + Duration::days(1),
+            TargetPeriod::Cycle { length, .. } => start + length,
+        }
+    }
+}
+
+/// Profit realized by positions settled or closed at or after `since`.
+pub fn realized_since(tracker: &PositionTracker, since: DateTime<Utc>) -> f64 {
+    tracker
+        .get_all_positions()
+        .into_iter()
+        .filter(|p| p.settled_at.is_some_and(|at| at >= since))
+        .filter_map(|p| p.profit)
+        .sum::<UsdAmount>()
+        .to_f64()
+}
+
+pub struct ProfitTarget {
+    target: f64,
+    period: TargetPeriod,
+    /// Start of the period in which the target was hit.
+    reached_in: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl ProfitTarget {
+    pub fn new(target: f64, period: TargetPeriod) -> Self {
+        Self {
+            target,
+            period,
+            reached_in: Mutex::new(None),
+        }
+    }
+
+    pub fn from_env() -> Option<Self> {
+        let target = std::env::var("PROFIT_TARGET")
+            .ok()
+            .and_then(|s| s.trim().parse::<f64>().ok())
+            .filter(|t| *t > 0.0)?;
+        let period_raw = std::env::var("PROFIT_TARGET_PERIOD").unwrap_or_default();
+        let period = TargetPeriod::parse(&period_raw, Utc::now()).unwrap_or_else(|| {
+            tracing::warn!("Invalid PROFIT_TARGET_PERIOD '{}' - using daily", period_raw);
+            TargetPeriod::Daily
+        });
+        Some(Self::new(target, period))
+    }
+
+    pub fn target(&self) -> f64 {
+        self.target
+    }
+
+    pub fn period(&self) -> TargetPeriod {
+        self.period
+    }
+
+    /// True while the current period's target is met; new entries should be
+    /// skipped. Returns `Some(realized)` the moment the target is first hit.
+    pub fn check(&self, tracker: &PositionTracker, now: DateTime<Utc>) -> (bool, Option<f64>) {
+        let start = self.period.start_of(now);
+        let mut reached_in = self.reached_in.lock().unwrap_or_else(|e| e.into_inner());
+        if *reached_in == Some(start) {
+            return (true, None);
+        }
+        if reached_in.take().is_some() {
+            info!("🎯 New profit period from {} - entries resumed", start.to_rfc3339());
+        }
+        let realized = realized_since(tracker, start);
+        if realized < self.target {
+            return (false, None);
+        }
+        *reached_in = Some(start);
+        info!(
+            "🎯 Profit target reached: ${:.2} ≥ ${:.2} - manage-only until {}",
+            realized,
+            self.target,
+            self.period.end_of(now).to_rfc3339()
+        );
+        (true, Some(realized))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Event;
+    use crate::money::Price;
+    use crate::position_tracker::Position;
+    use chrono::TimeZone;
+
+    #[test]
+    fn stops_entries_until_next_period() {
+        let anchor = Utc.with_ymd_and_hms(2025, 1, 1, 3, 0, 0).unwrap();
+        let six_hours = TargetPeriod::parse("6h", anchor).unwrap();
+        let now = anchor + Duration::hours(7);
+        assert_eq!(six_hours.start_of(now), anchor + Duration::hours(6));
+        assert_eq!(TargetPeriod::Daily.start_of(now), Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap());
+
+        let mut tracker = PositionTracker::new();
+        let event = Event::new("kalshi".into(), "KXBTC".into(), "BTC".into(), String::new());
+        for (i, profit) in [(1, 30.0), (7, 25.0)] {
+            let mut position = Position::new("kalshi".into(), &event, "YES".into(), 100.0, UsdAmount::from_f64(50.0), Price::from_cents(50), None);
+            position.settled_at = Some(anchor + Duration::hours(i));
+            position.profit = Some(UsdAmount::from_f64(profit));
+            tracker.add_position(position);
+        }
+
+        let target = ProfitTarget::new(20.0, six_hours);
+        // Only the second position settled in the current cycle.
+        assert_eq!(realized_since(&tracker, six_hours.start_of(now)), 25.0);
+        assert_eq!(target.check(&tracker, now), (true, Some(25.0)));
+        assert_eq!(target.check(&tracker, now), (true, None));
+        // Next cycle: nothing realized yet, entries resume.
+        assert_eq!(target.check(&tracker, anchor + Duration::hours(12)), (false, None));
+    }
+}
@@ -0,0 +1,181 @@
+//! Two-sided quotes on one Polymarket market.
+//!
+//! Where Gabagool takes the asks, quoting rests post-only bids on both YES
+//! and NO, priced so a pair costs the target (`QUOTE_TARGET_PAIR_COST`):
+//! once both sides fill, the difference to the $1 a pair pays is locked in.
+//! The target is split between the sides by their mid prices, and each bid
+//! stays under its ask so it rests as a maker order. The position's balance
+//! steers it the way it steers Gabagool: once one side holds more shares,
+//! only the other side is quoted, at whatever still pairs the excess off
+//! under the target.
+
+use crate::arbitrage_detector::capital_efficiency;
+use crate::event::{Event, MarketPrices};
+use crate::exchange::OrderSide;
+use crate::money::{Price, UsdAmount};
+use crate::tick::{snap_price, POLYMARKET_DEFAULT_TICK};
+
+/// Share difference below which the position counts as balanced.
+const BALANCE_TOLERANCE: f64 = 0.01;
+
+#[derive(Debug, Clone)]
+pub struct TwoSidedQuote {
+    pub event: Event,
+    /// YES bid, when YES is quoted.
+    pub yes_bid: Option<Price>,
+    /// NO bid, when NO is quoted.
+    pub no_bid: Option<Price>,
+    /// Shares needed to pair off a heavy side; `None` when balanced.
+    pub shares: Option<f64>,
+    /// Pair cost of the position once the quotes fill.
+    pub pair_cost: Price,
+    pub net_profit: Price,
+    pub roi_percent: f64,
+    /// Hours until the market resolves.
+    pub hours_to_resolution: Option<f64>,
+    /// ROI (percent) per hour of capital lockup.
+    pub roi_per_hour: Option<f64>,
+}
+
+impl TwoSidedQuote {
+    /// Quoted sides ("YES" / "NO") with their bids.
+    pub fn bids(&self) -> Vec<(&'static str, Price)> {
+        [("YES", self.yes_bid), ("NO", self.no_bid)]
+            .into_iter()
+            .filter_map(|(side, bid)| bid.map(|bid| (side, bid)))
+            .collect()
+    }
+
+    /// Shares to bid for on each side, at no more than `trade_amount`
+    /// dollars a pair.
+    pub fn pair_shares(&self, trade_amount: f64) -> f64 {
+        let affordable = UsdAmount::from_f64(trade_amount).shares_at(self.pair_cost);
+        self.shares.map_or(affordable, |shares| shares.min(affordable))
+    }
+}
+
+pub struct QuoteDetector {
+    target_pair_cost: f64,
+}
+
+impl QuoteDetector {
+    pub fn new(target_pair_cost: f64) -> Self {
+        Self { target_pair_cost }
+    }
+
+    /// Bids for `event` against a position holding `yes_qty` / `no_qty`
+    /// shares that cost `yes_cost` / `no_cost`; `None` when no bid keeps the
+    /// pair cost under the target.
+    pub fn check_quote(
+        &self,
+        event: &Event,
+        prices: &MarketPrices,
+        yes_qty: f64,
+        no_qty: f64,
+        yes_cost: UsdAmount,
+        no_cost: UsdAmount,
+    ) -> Option<TwoSidedQuote> {
+        if prices.yes_ask <= Price::ZERO || prices.no_ask <= Price::ZERO {
+            return None;
+        }
+
+        let target = Price::from_f64(self.target_pair_cost);
+        let (yes_bid, no_bid, shares, pair_cost) = if (yes_qty - no_qty).abs() <= BALANCE_TOLERANCE {
+            // Twice each mid; only their ratio matters.
+            let mid = |side: &str| prices.bid_for(side) + prices.ask_for(side);
+            let (yes_mid, no_mid) = (mid("YES"), mid("NO"));
+            if yes_mid + no_mid <= Price::ZERO {
+                return None;
+            }
+            let yes = rest_below(prices, "YES", Price::from_f64(self.target_pair_cost * yes_mid.ratio(yes_mid + no_mid)))?;
+            let no = rest_below(prices, "NO", target - yes)?;
+            (Some(yes), Some(no), None, yes + no)
+        } else {
+            // Pair off the excess at what keeps the whole position on target.
+            let (short_side, heavy_qty, excess) = if yes_qty < no_qty {
+                ("YES", no_qty, no_qty - yes_qty)
+            } else {
+                ("NO", yes_qty, yes_qty - no_qty)
+            };
+            let cost = yes_cost + no_cost;
+            let bid = rest_below(prices, short_side, (UsdAmount::for_shares(heavy_qty, target) - cost).per_share(excess))?;
+            let pair_cost = (cost + UsdAmount::for_shares(excess, bid)).per_share(heavy_qty);
+            if short_side == "YES" {
+                (Some(bid), None, Some(excess), pair_cost)
+            } else {
+                (None, Some(bid), Some(excess), pair_cost)
+            }
+        };
+
+        if pair_cost >= Price::ONE {
+            return None;
+        }
+        let net_profit = pair_cost.complement();
+        let roi_percent = net_profit.ratio(pair_cost) * 100.0;
+        let (hours_to_resolution, roi_per_hour) =
+            capital_efficiency(roi_percent, event.resolution_date, chrono::Utc::now());
+
+        Some(TwoSidedQuote {
+            event: event.clone(),
+            yes_bid,
+            no_bid,
+            shares,
+            pair_cost,
+            net_profit,
+            roi_percent,
+            hours_to_resolution,
+            roi_per_hour,
+        })
+    }
+}
+
+/// `price` on the tick grid, kept a tick under `side`'s ask so the bid rests;
+/// `None` when that leaves no price above zero.
+fn rest_below(prices: &MarketPrices, side: &str, price: Price) -> Option<Price> {
+    let tick = POLYMARKET_DEFAULT_TICK;
+    let price = price.min(prices.ask_for(side) - tick);
+    if price < tick {
+        return None;
+    }
+    snap_price(price, tick, OrderSide::Buy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market() -> Event {
+        Event::new(
+            "polymarket".to_string(),
+            "0xbtc".to_string(),
+            "Bitcoin Up or Down".to_string(),
+            String::new(),
+        )
+    }
+
+    #[test]
+    fn balanced_positions_quote_both_sides_at_the_target() {
+        let prices = MarketPrices::new(0.46, 0.56, 1000.0).with_bids(Some(0.44), Some(0.52));
+        let none = UsdAmount::ZERO;
+        let quote = QuoteDetector::new(0.97).check_quote(&market(), &prices, 0.0, 0.0, none, none).unwrap();
+        assert_eq!(quote.bids(), vec![("YES", Price::from_cents(44)), ("NO", Price::from_cents(53))]);
+        assert_eq!(quote.pair_cost, Price::from_cents(97));
+        assert!((quote.pair_shares(97.0) - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_heavy_side_quotes_only_the_short_one() {
+        let prices = MarketPrices::new(0.46, 0.56, 1000.0).with_bids(Some(0.44), Some(0.52));
+        let detector = QuoteDetector::new(0.97);
+
+        // 10 YES for $5: 10 NO at up to $0.47 pairs them at $0.97.
+        let usd = UsdAmount::from_f64;
+        let quote = detector.check_quote(&market(), &prices, 10.0, 0.0, usd(5.0), usd(0.0)).unwrap();
+        assert_eq!(quote.bids(), vec![("NO", Price::from_cents(47))]);
+        assert_eq!(quote.shares, Some(10.0));
+        assert_eq!(quote.pair_cost, Price::from_cents(97));
+
+        // Bought too dear to pair off under the target.
+        assert!(detector.check_quote(&market(), &prices, 10.0, 0.0, usd(9.7), usd(0.0)).is_none());
+    }
+}
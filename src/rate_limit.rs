@@ -0,0 +1,255 @@
+//! Per-venue request pacing: a cap on in-flight requests plus a minimum gap
+//! between request starts.
+//!
+//! On top of that, every venue request (through `metrics::track_http`)
+//! follows the venue's own rate-limit signals. A 429 holds the venue's
+//! requests off for its `Retry-After` (or a backoff doubling from 1s up to a
+//! minute without one), and doubles a gap kept between request starts, which
+//! shrinks again as requests succeed; a request turned away that way is
+//! retried once when the hold is short. An `X-RateLimit-Remaining` /
+//! `RateLimit-Remaining` of 0 holds requests until the window's reset. 429s
+//! are counted in `rate_limited_total{venue}` and the gap is exported as
+//! `request_pacing_seconds{venue}`.
+
+use crate::metrics::metrics;
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::sync::{Mutex, OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+use tracing::warn;
+
+/// First backoff after a 429 that doesn't say how long to wait.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// Longest hold taken from a venue's headers.
+const MAX_HOLD: Duration = Duration::from_secs(300);
+/// Gap between request starts after the first 429, and its ceiling.
+const MIN_GAP: Duration = Duration::from_millis(50);
+const MAX_GAP: Duration = Duration::from_secs(2);
+/// A 429'd request is retried once if the hold is at most this long.
+pub const MAX_RETRY_WAIT: Duration = Duration::from_secs(5);
+
+pub struct VenueLimiter {
+    permits: Arc<Semaphore>,
+    min_interval: Duration,
+    next_start: Mutex<Instant>,
+}
+
+impl VenueLimiter {
+    pub fn new(max_in_flight: usize, min_interval: Duration) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(max_in_flight.max(1))),
+            min_interval,
+            next_start: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// `max_in_flight` concurrent requests at most `per_second` starts/s.
+    pub fn per_second(max_in_flight: usize, per_second: f64) -> Self {
+        let min_interval = if per_second > 0.0 {
+            Duration::from_secs_f64(1.0 / per_second)
+        } else {
+            Duration::ZERO
+        };
+        Self::new(max_in_flight, min_interval)
+    }
+
+    /// Waits for a free slot and the pacing gap; the request may run while
+    /// the returned permit is held.
+    pub async fn acquire(&self) -> OwnedSemaphorePermit {
+        let permit = self
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("venue limiter semaphore closed");
+        if !self.min_interval.is_zero() {
+            let start = {
+                let mut next = self.next_start.lock().await;
+                let start = (*next).max(Instant::now());
+                *next = start + self.min_interval;
+                start
+            };
+            tokio::time::sleep_until(start).await;
+        }
+        permit
+    }
+}
+
+/// How long a response asks the client to hold off: its `Retry-After`
+/// (seconds or an HTTP date) on a 429 or 503, else the reset of a rate-limit
+/// window it reports exhausted. Resets are taken as epoch seconds or
+/// milliseconds when that large, else as seconds from now.
+pub fn hold_off(status: StatusCode, headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    let header = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| headers.get(*name))
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim().to_string())
+    };
+    let until = |at: DateTime<Utc>| (at - now).to_std().unwrap_or(Duration::ZERO);
+    let hold = if matches!(status, StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE) {
+        header(&["retry-after"]).and_then(|value| match value.parse::<f64>() {
+            Ok(secs) => Duration::try_from_secs_f64(secs).ok(),
+            Err(_) => DateTime::parse_from_rfc2822(&value).ok().map(|at| until(at.with_timezone(&Utc))),
+        })
+    } else {
+        None
+    };
+    let exhausted = header(&["x-ratelimit-remaining", "ratelimit-remaining"])
+        .and_then(|remaining| remaining.parse::<f64>().ok())
+        .is_some_and(|remaining| remaining <= 0.0);
+    let reset = || {
+        let reset = header(&["x-ratelimit-reset", "ratelimit-reset"])?.parse::<f64>().ok()?;
+        if reset > 1e12 {
+            DateTime::from_timestamp_millis(reset as i64).map(until)
+        } else if reset > 1e9 {
+            DateTime::from_timestamp(reset as i64, 0).map(until)
+        } else {
+            Duration::try_from_secs_f64(reset).ok()
+        }
+    };
+    hold.or_else(|| if exhausted { reset() } else { None })
+        .map(|hold| hold.min(MAX_HOLD))
+}
+
+/// One venue's adaptive pacing.
+#[derive(Debug, Default)]
+struct Pace {
+    blocked_until: Option<Instant>,
+    next_start: Option<Instant>,
+    gap: Duration,
+    backoff: Duration,
+}
+
+impl Pace {
+    /// When the next request may start, keeping the gap after it.
+    fn reserve(&mut self, now: Instant) -> Instant {
+        let start = [self.blocked_until, self.next_start].into_iter().flatten().fold(now, Instant::max);
+        self.next_start = Some(start + self.gap);
+        start
+    }
+
+    fn hold(&mut self, now: Instant, hold: Duration) {
+        self.blocked_until = self.blocked_until.max(Some(now + hold));
+    }
+
+    /// A 429: holds off for `hold`, or the next backoff step without one,
+    /// and widens the gap. Returns the hold.
+    fn throttled(&mut self, now: Instant, hold: Option<Duration>) -> Duration {
+        self.backoff = hold.unwrap_or_else(|| (self.backoff * 2).clamp(INITIAL_BACKOFF, MAX_BACKOFF));
+        self.gap = (self.gap * 2).clamp(MIN_GAP, MAX_GAP);
+        self.hold(now, self.backoff);
+        self.backoff
+    }
+
+    /// Narrows the gap by a tenth, down to none.
+    fn succeeded(&mut self) {
+        self.backoff = Duration::ZERO;
+        self.gap = self.gap * 9 / 10;
+        if self.gap < MIN_GAP / 2 {
+            self.gap = Duration::ZERO;
+        }
+    }
+}
+
+/// Adaptive pacing of every venue's requests, fed by their responses.
+#[derive(Debug, Default)]
+pub struct AdaptivePacing {
+    venues: std::sync::Mutex<HashMap<String, Pace>>,
+}
+
+pub fn pacing() -> &'static AdaptivePacing {
+    static PACING: OnceLock<AdaptivePacing> = OnceLock::new();
+    PACING.get_or_init(AdaptivePacing::default)
+}
+
+impl AdaptivePacing {
+    fn with_pace<T>(&self, venue: &str, f: impl FnOnce(&mut Pace) -> T) -> T {
+        let mut venues = self.venues.lock().unwrap();
+        f(venues.entry(venue.to_string()).or_default())
+    }
+
+    /// Waits out any hold on `venue` and its gap since the last request.
+    pub async fn wait(&self, venue: &str) {
+        let start = self.with_pace(venue, |pace| pace.reserve(Instant::now()));
+        tokio::time::sleep_until(start).await;
+    }
+
+    /// How much longer `venue` is held off.
+    pub fn held_for(&self, venue: &str) -> Duration {
+        self.with_pace(venue, |pace| {
+            pace.blocked_until
+                .map(|until| until.saturating_duration_since(Instant::now()))
+                .unwrap_or(Duration::ZERO)
+        })
+    }
+
+    /// Adjusts `venue`'s pacing to a response.
+    pub fn observe(&self, venue: &str, status: StatusCode, headers: &HeaderMap) {
+        let hold = hold_off(status, headers, Utc::now());
+        let now = Instant::now();
+        let gap = self.with_pace(venue, |pace| {
+            if status == StatusCode::TOO_MANY_REQUESTS {
+                let hold = pace.throttled(now, hold);
+                warn!("🐢 {} rate limited - holding requests off {:.1}s", venue, hold.as_secs_f64());
+                metrics().inc_counter("rate_limited_total", &[("venue", venue)]);
+            } else if let Some(hold) = hold {
+                pace.hold(now, hold);
+            }
+            if status.is_success() {
+                pace.succeeded();
+            }
+            pace.gap
+        });
+        metrics().set_gauge("request_pacing_seconds", &[("venue", venue)], gap.as_secs_f64());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn backs_off_on_throttling_and_recovers() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let headers = |pairs: &[(&'static str, &str)]| {
+            let mut map = HeaderMap::new();
+            for (name, value) in pairs {
+                map.insert(*name, HeaderValue::from_str(value).unwrap());
+            }
+            map
+        };
+        let throttled = StatusCode::TOO_MANY_REQUESTS;
+        assert_eq!(hold_off(throttled, &headers(&[("retry-after", "3")]), now), Some(Duration::from_secs(3)));
+        let date = headers(&[("retry-after", "Wed, 01 Jan 2025 12:00:10 GMT")]);
+        assert_eq!(hold_off(throttled, &date, now), Some(Duration::from_secs(10)));
+        assert_eq!(hold_off(throttled, &HeaderMap::new(), now), None);
+        // An exhausted window holds until its reset; one with requests left doesn't.
+        let window = |remaining| headers(&[("x-ratelimit-remaining", remaining), ("x-ratelimit-reset", "1735732805")]);
+        assert_eq!(hold_off(StatusCode::OK, &window("0"), now), Some(Duration::from_secs(5)));
+        assert_eq!(hold_off(StatusCode::OK, &window("12"), now), None);
+
+        let start = Instant::now();
+        let mut pace = Pace::default();
+        assert_eq!(pace.reserve(start), start);
+        assert_eq!(pace.throttled(start, None), INITIAL_BACKOFF);
+        assert_eq!(pace.throttled(start, None), INITIAL_BACKOFF * 2);
+        assert_eq!(pace.gap, MIN_GAP * 2);
+        // Requests wait out the hold, then keep the gap between them.
+        let first = pace.reserve(start);
+        assert_eq!(first, start + INITIAL_BACKOFF * 2);
+        assert_eq!(pace.reserve(start), first + MIN_GAP * 2);
+        for _ in 0..20 {
+            pace.succeeded();
+        }
+        assert_eq!(pace.gap, Duration::ZERO);
+    }
+}
@@ -0,0 +1,187 @@
+//! Cross-platform capital imbalance advisor.
+//!
+//! A cross-platform arb needs one leg's worth of capital on each venue; with
+//! the money all on one side it can't be traded at all. Every
+//! `REBALANCE_CHECK_INTERVAL_SECS` (default 300, 0 turns it off) the
+//! deployable capital on Polymarket and Kalshi - the balance above any
+//! `MIN_BALANCE_*` floor - is compared against a leg at the current trade
+//! amount. When one side can't fund its leg, the operator is alerted with the
+//! transfer that evens the two out, or told to top up when even both together
+//! can't fund an arb. The Polymarket share of deployable capital is exported
+//! as `deployable_capital_ratio`.
+
+use crate::balance_floor::BalanceFloors;
+use crate::control::Controls;
+use crate::exchange::ExchangeClient;
+use crate::metrics::metrics;
+use crate::money::UsdAmount;
+use crate::notify::{BotEvent, Notifier};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+const DEFAULT_CHECK_SECS: u64 = 300;
+
+/// What to move where so both legs are fundable.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RebalanceAdvice {
+    /// The venue that can't fund its leg.
+    pub short: &'static str,
+    /// The venue to move money from; `None` when neither has enough to spare.
+    pub from: Option<&'static str>,
+    pub transfer: UsdAmount,
+    pub polymarket: UsdAmount,
+    pub kalshi: UsdAmount,
+    pub leg: UsdAmount,
+}
+
+impl RebalanceAdvice {
+    pub fn event(&self) -> BotEvent {
+        BotEvent::CapitalImbalance {
+            short: self.short.to_string(),
+            from: self.from.map(str::to_string),
+            transfer: self.transfer.to_f64(),
+            polymarket: self.polymarket.to_f64(),
+            kalshi: self.kalshi.to_f64(),
+            leg: self.leg.to_f64(),
+        }
+    }
+}
+
+/// The advice for `polymarket` and `kalshi` dollars of deployable capital
+/// against legs of `leg` dollars; `None` while both legs are fundable.
+pub fn advise(polymarket: UsdAmount, kalshi: UsdAmount, leg: UsdAmount) -> Option<RebalanceAdvice> {
+    if polymarket >= leg && kalshi >= leg {
+        return None;
+    }
+    let (short, long, short_amount, long_amount) = if polymarket < kalshi {
+        ("polymarket", "kalshi", polymarket, kalshi)
+    } else {
+        ("kalshi", "polymarket", kalshi, polymarket)
+    };
+    let fixable = short_amount + long_amount >= leg + leg;
+    let transfer = if fixable {
+        // Evening the sides out leaves the most room for the next arbs.
+        (long_amount - short_amount).pro_rata(1.0, 2.0).ceil_cents()
+    } else {
+        UsdAmount::ZERO
+    };
+    Some(RebalanceAdvice {
+        short,
+        from: fixable.then_some(long),
+        transfer,
+        polymarket,
+        kalshi,
+        leg,
+    })
+}
+
+pub struct RebalanceAdvisor {
+    interval: Duration,
+    trade_amount: f64,
+    floors: Option<BalanceFloors>,
+    controls: Option<Arc<Controls>>,
+}
+
+impl RebalanceAdvisor {
+    pub fn new(interval: Duration, trade_amount: f64) -> Self {
+        Self { interval, trade_amount, floors: None, controls: None }
+    }
+
+    /// `REBALANCE_CHECK_INTERVAL_SECS`; `None` when it's 0.
+    pub fn from_env(trade_amount: f64) -> Option<Self> {
+        let secs = std::env::var("REBALANCE_CHECK_INTERVAL_SECS")
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .unwrap_or(DEFAULT_CHECK_SECS);
+        (secs > 0).then(|| Self::new(Duration::from_secs(secs), trade_amount))
+    }
+
+    /// Balance under these floors isn't deployable.
+    pub fn with_floors(mut self, floors: BalanceFloors) -> Self {
+        self.floors = Some(floors);
+        self
+    }
+
+    /// Follows the runtime trade amount override.
+    pub fn with_controls(mut self, controls: Arc<Controls>) -> Self {
+        self.controls = Some(controls);
+        self
+    }
+
+    fn deployable(&self, platform: &str, balance: f64) -> UsdAmount {
+        let floor = self.floors.as_ref().and_then(|f| f.floor(platform)).unwrap_or(0.0);
+        (UsdAmount::from_f64(balance) - UsdAmount::from_f64(floor)).max(UsdAmount::ZERO)
+    }
+
+    /// Alerts when a side first can't fund its leg (or which side that is
+    /// changes), and logs once both can again.
+    pub fn spawn(
+        self,
+        polymarket: Arc<dyn ExchangeClient>,
+        kalshi: Arc<dyn ExchangeClient>,
+        notifier: Arc<Notifier>,
+    ) -> JoinHandle<()> {
+        info!("⚖️ Checking cross-platform capital balance every {}s", self.interval.as_secs());
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.interval);
+            let mut short: Option<&'static str> = None;
+            loop {
+                ticker.tick().await;
+                let (pm_balance, kalshi_balance) = tokio::join!(polymarket.get_balance(), kalshi.get_balance());
+                let (pm_balance, kalshi_balance) = match (pm_balance, kalshi_balance) {
+                    (Ok(pm), Ok(kalshi)) => (pm, kalshi),
+                    (Err(e), _) | (_, Err(e)) => {
+                        warn!("Capital balance check failed: {}", e);
+                        continue;
+                    }
+                };
+                let pm = self.deployable("polymarket", pm_balance);
+                let kalshi = self.deployable("kalshi", kalshi_balance);
+                if pm + kalshi > UsdAmount::ZERO {
+                    metrics().set_gauge("deployable_capital_ratio", &[], pm.ratio(pm + kalshi));
+                }
+                let leg = self
+                    .controls
+                    .as_ref()
+                    .and_then(|c| c.trade_amount())
+                    .unwrap_or(self.trade_amount);
+                match advise(pm, kalshi, UsdAmount::from_f64(leg)) {
+                    Some(advice) if short != Some(advice.short) => {
+                        short = Some(advice.short);
+                        notifier.notify(advice.event()).await;
+                    }
+                    Some(_) => {}
+                    None => {
+                        if short.take().is_some() {
+                            info!("⚖️ Both venues can fund a ${:.2} leg again", leg);
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggests_the_transfer_that_evens_out_the_venues() {
+        let usd = UsdAmount::from_f64;
+        assert_eq!(advise(usd(300.0), usd(200.0), usd(100.0)), None);
+
+        let advice = advise(usd(450.0), usd(30.0), usd(100.0)).unwrap();
+        assert_eq!(advice.short, "kalshi");
+        assert_eq!(advice.from, Some("polymarket"));
+        assert_eq!(advice.transfer, usd(210.0));
+
+        // $120 in total can't fund two $100 legs wherever it sits.
+        let advice = advise(usd(20.0), usd(100.0), usd(100.0)).unwrap();
+        assert_eq!(advice.short, "polymarket");
+        assert_eq!(advice.from, None);
+        assert_eq!(advice.transfer, UsdAmount::ZERO);
+    }
+}
@@ -0,0 +1,467 @@
+//! Market data recorder.
+//!
+//! Appends every scan's events, matched pairs and `MarketPrices` to CSV files
+//! under `<dir>/<YYYY-MM-DD>/`, keyed by a per-scan id so a scan can be
+//! reconstructed later for backtesting or post-mortems. Every opportunity
+//! detected, traded or not, goes to `opportunities.csv` with the quotes it
+//! was computed from and its edge, tagged with its market type, so realized
+//! edge can be compared against it and its half-life measured from how many
+//! consecutive scans it shows up in. Every level of each book read goes to
+//! `books.csv`, one row per price level.
+//!
+//! Rows are written on a dedicated `recorder` thread so scans never block on
+//! disk; call [`MarketRecorder::flush`] before reading the files back or
+//! exiting.
+
+use crate::arbitrage_detector::ArbitrageOpportunity;
+use crate::event::{Event, MarketPrices};
+use crate::gabagool_detector::GabagoolOpportunity;
+use crate::money::Price;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use tokio::sync::oneshot;
+use tracing::warn;
+
+pub const EVENTS_FILE: &str = "events.csv";
+pub const MATCHES_FILE: &str = "matches.csv";
+pub const PRICES_FILE: &str = "prices.csv";
+pub const OPPORTUNITIES_FILE: &str = "opportunities.csv";
+pub const BOOKS_FILE: &str = "books.csv";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventRecord {
+    pub scan_id: u64,
+    pub recorded_at: DateTime<Utc>,
+    pub platform: String,
+    pub event_id: String,
+    pub title: String,
+    pub description: String,
+    pub resolution_date: Option<DateTime<Utc>>,
+    pub category: Option<String>,
+    pub tags: String,
+    pub slug: Option<String>,
+    pub yes_token_id: Option<String>,
+    pub no_token_id: Option<String>,
+}
+
+impl EventRecord {
+    fn from_event(scan_id: u64, recorded_at: DateTime<Utc>, event: &Event) -> Self {
+        Self {
+            scan_id,
+            recorded_at,
+            platform: event.platform.clone(),
+            event_id: event.event_id.clone(),
+            title: event.title.clone(),
+            description: event.description.clone(),
+            resolution_date: event.resolution_date,
+            category: event.category.clone(),
+            tags: event.tags.join(";"),
+            slug: event.slug.clone(),
+            yes_token_id: event.yes_token_id.clone(),
+            no_token_id: event.no_token_id.clone(),
+        }
+    }
+
+    pub fn to_event(&self) -> Event {
+        Event {
+            platform: self.platform.clone(),
+            event_id: self.event_id.clone(),
+            title: self.title.clone(),
+            description: self.description.clone(),
+            resolution_date: self.resolution_date,
+            category: self.category.clone(),
+            tags: self
+                .tags
+                .split(';')
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect(),
+            slug: self.slug.clone(),
+            yes_token_id: self.yes_token_id.clone(),
+            no_token_id: self.no_token_id.clone(),
+            outcomes: Vec::new(),
+            rules: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchRecord {
+    pub scan_id: u64,
+    pub recorded_at: DateTime<Utc>,
+    pub pm_event_id: String,
+    pub kalshi_event_id: String,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceRecord {
+    pub scan_id: u64,
+    pub recorded_at: DateTime<Utc>,
+    pub platform: String,
+    pub event_id: String,
+    pub yes: f64,
+    pub no: f64,
+    pub liquidity: f64,
+    pub yes_ask: Option<f64>,
+    pub no_ask: Option<f64>,
+    pub last_price: Option<f64>,
+}
+
+impl PriceRecord {
+    pub fn to_prices(&self) -> MarketPrices {
+        MarketPrices::new(
+            self.yes_ask.unwrap_or(self.yes),
+            self.no_ask.unwrap_or(self.no),
+            self.liquidity,
+        )
+        .with_last_price(self.last_price)
+        .with_fetched_at(self.recorded_at)
+    }
+}
+
+/// One level of a recorded book. `level` counts from 0 at the best price.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookLevelRecord {
+    pub scan_id: u64,
+    pub recorded_at: DateTime<Utc>,
+    pub platform: String,
+    pub event_id: String,
+    pub outcome: String,
+    /// `bid` or `ask`.
+    pub side: String,
+    pub level: usize,
+    pub price: f64,
+    pub size: f64,
+}
+
+/// A detected opportunity and the quotes it was computed from. Kalshi
+/// columns are empty for single-venue (Gabagool) opportunities.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpportunityRecord {
+    pub scan_id: u64,
+    pub recorded_at: DateTime<Utc>,
+    pub strategy: String,
+    /// `btc-15m` for 15-minute crypto markets, else the category.
+    pub market_type: String,
+    pub pm_event_id: String,
+    pub kalshi_event_id: Option<String>,
+    pub title: String,
+    pub legs: String,
+    pub pm_yes_bid: Option<f64>,
+    pub pm_yes_ask: f64,
+    pub pm_no_bid: Option<f64>,
+    pub pm_no_ask: f64,
+    pub pm_depth: f64,
+    pub kalshi_yes_bid: Option<f64>,
+    pub kalshi_yes_ask: Option<f64>,
+    pub kalshi_no_bid: Option<f64>,
+    pub kalshi_no_ask: Option<f64>,
+    pub kalshi_depth: Option<f64>,
+    pub total_cost: f64,
+    pub gross_profit: f64,
+    pub fees: f64,
+    pub net_profit: f64,
+    pub roi_percent: f64,
+    pub hours_to_resolution: Option<f64>,
+    pub roi_per_hour: Option<f64>,
+    pub confidence: Option<f64>,
+}
+
+/// `btc-15m` for a 15-minute crypto market, else its category.
+fn market_type(event: &Event) -> String {
+    if event.is_15m_crypto_market() {
+        return format!("{}-15m", event.coin_from_slug().unwrap_or_else(|| "crypto".to_string()));
+    }
+    event.category.clone().unwrap_or_else(|| "other".to_string())
+}
+
+type WriteJob = Box<dyn FnOnce() + Send>;
+
+pub struct MarketRecorder {
+    dir: PathBuf,
+    current_scan: AtomicU64,
+    writer: mpsc::Sender<WriteJob>,
+}
+
+impl MarketRecorder {
+    /// Starts the writer thread; it exits once the recorder is dropped and
+    /// its queue has drained.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let (writer, jobs) = mpsc::channel::<WriteJob>();
+        thread::Builder::new()
+            .name("recorder".to_string())
+            .spawn(move || {
+                for job in jobs {
+                    job();
+                }
+            })
+            .expect("failed to spawn the recorder thread");
+        Self {
+            dir: dir.into(),
+            current_scan: AtomicU64::new(0),
+            writer,
+        }
+    }
+
+    /// Builds a recorder from `RECORDER_DIR`; recording is off when unset.
+    pub fn from_env() -> Option<Self> {
+        std::env::var("RECORDER_DIR")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .map(Self::new)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn current_scan_id(&self) -> u64 {
+        self.current_scan.load(Ordering::SeqCst)
+    }
+
+    /// Starts a new scan and records the raw event lists fetched for it.
+    /// Matches and prices recorded afterwards are attached to this scan.
+    pub fn record_events(&self, pm_events: &[Event], kalshi_events: &[Event]) -> u64 {
+        let now = Utc::now();
+        let scan_id = now.timestamp_millis().max(0) as u64;
+        let scan_id = scan_id.max(self.current_scan_id() + 1);
+        self.current_scan.store(scan_id, Ordering::SeqCst);
+
+        let rows: Vec<EventRecord> = pm_events
+            .iter()
+            .chain(kalshi_events.iter())
+            .map(|e| EventRecord::from_event(scan_id, now, e))
+            .collect();
+        self.append(&now, EVENTS_FILE, rows);
+        scan_id
+    }
+
+    pub fn record_match(&self, pm_event: &Event, kalshi_event: &Event, score: f64) {
+        let now = Utc::now();
+        let row = MatchRecord {
+            scan_id: self.current_scan_id(),
+            recorded_at: now,
+            pm_event_id: pm_event.event_id.clone(),
+            kalshi_event_id: kalshi_event.event_id.clone(),
+            score,
+        };
+        self.append(&now, MATCHES_FILE, vec![row]);
+    }
+
+    /// Records the quotes and, when the book was read, every level of it.
+    pub fn record_prices(&self, platform: &str, event_id: &str, prices: &MarketPrices) {
+        let now = Utc::now();
+        let row = PriceRecord {
+            scan_id: self.current_scan_id(),
+            recorded_at: now,
+            platform: platform.to_string(),
+            event_id: event_id.to_string(),
+            yes: prices.yes_ask.to_f64(),
+            no: prices.no_ask.to_f64(),
+            liquidity: prices.depth.to_f64(),
+            yes_ask: Some(prices.yes_ask.to_f64()),
+            no_ask: Some(prices.no_ask.to_f64()),
+            last_price: prices.last_price.map(Price::to_f64),
+        };
+        self.append(&now, PRICES_FILE, vec![row]);
+
+        let Some(levels) = &prices.levels else {
+            return;
+        };
+        let sides = [
+            ("YES", "bid", &levels.yes_bids),
+            ("YES", "ask", &levels.yes_asks),
+            ("NO", "bid", &levels.no_bids),
+            ("NO", "ask", &levels.no_asks),
+        ];
+        let rows = sides
+            .into_iter()
+            .flat_map(|(outcome, side, quotes)| {
+                quotes.iter().enumerate().map(move |(level, quote)| BookLevelRecord {
+                    scan_id: self.current_scan_id(),
+                    recorded_at: now,
+                    platform: platform.to_string(),
+                    event_id: event_id.to_string(),
+                    outcome: outcome.to_string(),
+                    side: side.to_string(),
+                    level,
+                    price: quote.price,
+                    size: quote.size,
+                })
+            })
+            .collect();
+        self.append(&now, BOOKS_FILE, rows);
+    }
+
+    pub fn record_arbitrage(
+        &self,
+        pm_event: &Event,
+        kalshi_event: &Event,
+        pm_prices: &MarketPrices,
+        kalshi_prices: &MarketPrices,
+        opportunity: &ArbitrageOpportunity,
+    ) {
+        let now = Utc::now();
+        let row = OpportunityRecord {
+            scan_id: self.current_scan_id(),
+            recorded_at: now,
+            strategy: "cross_platform".to_string(),
+            market_type: market_type(pm_event),
+            pm_event_id: pm_event.event_id.clone(),
+            kalshi_event_id: Some(kalshi_event.event_id.clone()),
+            title: pm_event.title.clone(),
+            legs: opportunity.strategy.clone(),
+            pm_yes_bid: pm_prices.yes_bid.map(Price::to_f64),
+            pm_yes_ask: pm_prices.yes_ask.to_f64(),
+            pm_no_bid: pm_prices.no_bid.map(Price::to_f64),
+            pm_no_ask: pm_prices.no_ask.to_f64(),
+            pm_depth: pm_prices.depth.to_f64(),
+            kalshi_yes_bid: kalshi_prices.yes_bid.map(Price::to_f64),
+            kalshi_yes_ask: Some(kalshi_prices.yes_ask.to_f64()),
+            kalshi_no_bid: kalshi_prices.no_bid.map(Price::to_f64),
+            kalshi_no_ask: Some(kalshi_prices.no_ask.to_f64()),
+            kalshi_depth: Some(kalshi_prices.depth.to_f64()),
+            total_cost: opportunity.total_cost.to_f64(),
+            gross_profit: opportunity.gross_profit.to_f64(),
+            fees: opportunity.fees.to_f64(),
+            net_profit: opportunity.net_profit.to_f64(),
+            roi_percent: opportunity.roi_percent,
+            hours_to_resolution: opportunity.hours_to_resolution,
+            roi_per_hour: opportunity.roi_per_hour,
+            confidence: opportunity.confidence,
+        };
+        self.append(&now, OPPORTUNITIES_FILE, vec![row]);
+    }
+
+    pub fn record_gabagool(&self, prices: &MarketPrices, opportunity: &GabagoolOpportunity) {
+        let now = Utc::now();
+        let event = &opportunity.event;
+        let row = OpportunityRecord {
+            scan_id: self.current_scan_id(),
+            recorded_at: now,
+            strategy: "gabagool".to_string(),
+            market_type: market_type(event),
+            pm_event_id: event.event_id.clone(),
+            kalshi_event_id: None,
+            title: event.title.clone(),
+            legs: format!("Buy {} @ {:.2}", opportunity.cheap_side, opportunity.cheap_price),
+            pm_yes_bid: prices.yes_bid.map(Price::to_f64),
+            pm_yes_ask: prices.yes_ask.to_f64(),
+            pm_no_bid: prices.no_bid.map(Price::to_f64),
+            pm_no_ask: prices.no_ask.to_f64(),
+            pm_depth: prices.depth.to_f64(),
+            kalshi_yes_bid: None,
+            kalshi_yes_ask: None,
+            kalshi_no_bid: None,
+            kalshi_no_ask: None,
+            kalshi_depth: None,
+            total_cost: opportunity.pair_cost_after,
+            gross_profit: opportunity.net_profit,
+            fees: 0.0,
+            net_profit: opportunity.net_profit,
+            roi_percent: opportunity.roi_percent,
+            hours_to_resolution: opportunity.hours_to_resolution,
+            roi_per_hour: opportunity.roi_per_hour,
+            confidence: opportunity.confidence,
+        };
+        self.append(&now, OPPORTUNITIES_FILE, vec![row]);
+    }
+
+    /// Waits until every row recorded so far is on disk.
+    pub async fn flush(&self) {
+        let (done, written) = oneshot::channel();
+        let job: WriteJob = Box::new(move || {
+            let _ = done.send(());
+        });
+        if self.writer.send(job).is_ok() {
+            let _ = written.await;
+        }
+    }
+
+    /// Queues rows for the writer thread.
+    fn append<T: Serialize + Send + 'static>(&self, at: &DateTime<Utc>, file: &'static str, rows: Vec<T>) {
+        if rows.is_empty() {
+            return;
+        }
+        let day_dir = self.dir.join(at.format("%Y-%m-%d").to_string());
+        let job: WriteJob = Box::new(move || {
+            if let Err(e) = write_rows(&day_dir, file, &rows) {
+                warn!("Recorder failed to write {}: {}", file, e);
+            }
+        });
+        if self.writer.send(job).is_err() {
+            warn!("Recorder writer thread is gone; dropped rows for {}", file);
+        }
+    }
+}
+
+fn write_rows<T: Serialize>(day_dir: &Path, file: &str, rows: &[T]) -> Result<()> {
+    fs::create_dir_all(day_dir)
+        .with_context(|| format!("Failed to create {}", day_dir.display()))?;
+    let path = day_dir.join(file);
+    let is_new = fs::metadata(&path).map(|m| m.len() == 0).unwrap_or(true);
+    let handle = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(is_new)
+        .from_writer(handle);
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{BookLevels, Quote};
+
+    #[tokio::test]
+    async fn records_every_book_level_best_first() {
+        let dir = std::env::temp_dir().join(format!("books-{}", uuid::Uuid::new_v4()));
+        let recorder = MarketRecorder::new(&dir);
+        let quote = |price, size| Quote { price, size };
+        let levels = BookLevels::sorted(
+            vec![quote(0.40, 10.0), quote(0.42, 5.0)],
+            vec![quote(0.47, 8.0), quote(0.45, 20.0)],
+            Vec::new(),
+            vec![quote(0.58, 3.0)],
+        );
+        let prices = MarketPrices::new(0.45, 0.58, 100.0).with_levels(levels);
+        recorder.record_prices("kalshi", "KXTEST", &prices);
+        recorder.flush().await;
+
+        let day = fs::read_dir(&dir).unwrap().next().unwrap().unwrap().path();
+        let rows: Vec<BookLevelRecord> = csv::Reader::from_path(day.join(BOOKS_FILE))
+            .unwrap()
+            .deserialize()
+            .map(Result::unwrap)
+            .collect();
+        fs::remove_dir_all(&dir).ok();
+        let summary: Vec<_> = rows
+            .iter()
+            .map(|r| (r.outcome.as_str(), r.side.as_str(), r.level, r.price))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("YES", "bid", 0, 0.42),
+                ("YES", "bid", 1, 0.40),
+                ("YES", "ask", 0, 0.45),
+                ("YES", "ask", 1, 0.47),
+                ("NO", "ask", 0, 0.58),
+            ]
+        );
+    }
+}
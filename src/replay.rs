@@ -0,0 +1,217 @@
+//! Replay of scans captured by `MarketRecorder`.
+//!
+//! Serves `fetch_events`/`fetch_prices` from recorded CSVs so detector and
+//! executor changes can be exercised deterministically before going live.
+
+use crate::bot::ShortTermArbitrageBot;
+use crate::event::{Event, MarketPrices};
+use crate::recorder::{EventRecord, PriceRecord, EVENTS_FILE, PRICES_FILE};
+use crate::trade_executor::TradeExecutor;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{info, warn};
+
+#[derive(Debug, Clone)]
+pub struct RecordedScan {
+    pub scan_id: u64,
+    pub recorded_at: DateTime<Utc>,
+    pub pm_events: Vec<Event>,
+    pub kalshi_events: Vec<Event>,
+    prices: HashMap<(String, String), MarketPrices>,
+}
+
+impl RecordedScan {
+    fn new(scan_id: u64, recorded_at: DateTime<Utc>) -> Self {
+        Self {
+            scan_id,
+            recorded_at,
+            pm_events: Vec::new(),
+            kalshi_events: Vec::new(),
+            prices: HashMap::new(),
+        }
+    }
+
+    /// Prices recorded for this scan; markets that weren't priced come back
+    /// empty, exactly as a failed live fetch would.
+    pub fn prices_for(&self, platform: &str, event_id: &str) -> MarketPrices {
+        self.prices
+            .get(&(platform.to_string(), event_id.to_string()))
+            .cloned()
+            .unwrap_or_else(|| MarketPrices::new(0.0, 0.0, 0.0))
+    }
+
+    /// Every recorded book in this scan as `(platform, event_id, prices)`.
+    pub fn recorded_prices(&self) -> impl Iterator<Item = (&str, &str, &MarketPrices)> {
+        self.prices
+            .iter()
+            .map(|((platform, event_id), prices)| (platform.as_str(), event_id.as_str(), prices))
+    }
+}
+
+#[derive(Clone)]
+pub struct ReplaySource {
+    scans: Vec<RecordedScan>,
+}
+
+impl ReplaySource {
+    /// Loads every `events.csv`/`prices.csv` under `dir` (either a recorder
+    /// root with per-day folders or a single day folder).
+    pub fn load(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+        let mut scans: BTreeMap<u64, RecordedScan> = BTreeMap::new();
+
+        for day_dir in recording_dirs(dir)? {
+            for record in read_rows::<EventRecord>(&day_dir.join(EVENTS_FILE))? {
+                let scan = scans
+                    .entry(record.scan_id)
+                    .or_insert_with(|| RecordedScan::new(record.scan_id, record.recorded_at));
+                let event = record.to_event();
+                if event.platform == "kalshi" {
+                    scan.kalshi_events.push(event);
+                } else {
+                    scan.pm_events.push(event);
+                }
+            }
+
+            for record in read_rows::<PriceRecord>(&day_dir.join(PRICES_FILE))? {
+                let scan = scans
+                    .entry(record.scan_id)
+                    .or_insert_with(|| RecordedScan::new(record.scan_id, record.recorded_at));
+                scan.prices
+                    .entry((record.platform.clone(), record.event_id.clone()))
+                    .or_insert_with(|| record.to_prices());
+            }
+        }
+
+        Ok(Self {
+            scans: scans.into_values().collect(),
+        })
+    }
+
+    pub fn from_scans(scans: Vec<RecordedScan>) -> Self {
+        Self { scans }
+    }
+
+    pub fn scans(&self) -> &[RecordedScan] {
+        &self.scans
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scans.is_empty()
+    }
+}
+
+fn recording_dirs(dir: &Path) -> Result<Vec<PathBuf>> {
+    if dir.join(EVENTS_FILE).exists() {
+        return Ok(vec![dir.to_path_buf()]);
+    }
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read replay directory {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir() && path.join(EVENTS_FILE).exists())
+        .collect();
+    dirs.sort();
+    Ok(dirs)
+}
+
+fn read_rows<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut reader = csv::Reader::from_path(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut rows = Vec::new();
+    for row in reader.deserialize() {
+        match row {
+            Ok(row) => rows.push(row),
+            Err(e) => warn!("Skipping malformed row in {}: {}", path.display(), e),
+        }
+    }
+    Ok(rows)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReplaySummary {
+    pub scans: usize,
+    pub opportunities: usize,
+    pub executed: usize,
+    pub failed: usize,
+    pub expected_profit: f64,
+}
+
+/// Replays every recorded scan through the bot's cross-platform scan and the
+/// executor. `speed` scales the recorded gaps between scans (e.g. 60 = one
+/// recorded minute per second); 0 replays back-to-back.
+pub async fn run_replay(
+    bot: &ShortTermArbitrageBot,
+    executor: &TradeExecutor,
+    source: &ReplaySource,
+    speed: f64,
+    trade_amount: f64,
+) -> ReplaySummary {
+    let mut summary = ReplaySummary::default();
+    let mut previous: Option<DateTime<Utc>> = None;
+
+    for scan in source.scans() {
+        if let (Some(prev), true) = (previous, speed > 0.0) {
+            let gap = (scan.recorded_at - prev).num_milliseconds().max(0) as f64 / speed;
+            tokio::time::sleep(Duration::from_millis(gap as u64)).await;
+        }
+        previous = Some(scan.recorded_at);
+
+        replay_scan(bot, executor, scan, trade_amount, &mut summary).await;
+    }
+    bot.set_time_override(None);
+
+    summary
+}
+
+/// Runs one recorded scan through the bot and executor, folding the outcome
+/// into `summary`. Leaves the bot's time override pinned to the scan.
+pub(crate) async fn replay_scan(
+    bot: &ShortTermArbitrageBot,
+    executor: &TradeExecutor,
+    scan: &RecordedScan,
+    trade_amount: f64,
+    summary: &mut ReplaySummary,
+) {
+    bot.set_time_override(Some(scan.recorded_at));
+    let fetch_prices = |event_id: &str, platform: &str| {
+        let prices = scan.prices_for(platform, event_id);
+        async move { prices }
+    };
+    let opportunities = bot
+        .scan_for_opportunities(&scan.pm_events, &scan.kalshi_events, fetch_prices)
+        .await;
+
+    summary.scans += 1;
+    summary.opportunities += opportunities.len();
+
+    for (pm_event, kalshi_event, opp) in &opportunities {
+        info!(
+            "[REPLAY {}] {} - Profit: {}, ROI: {:.2}%",
+            scan.scan_id, pm_event.title, opp.net_profit, opp.roi_percent
+        );
+        summary.expected_profit += opp.net_profit.to_f64();
+        match executor
+            .execute_arbitrage(opp, pm_event, kalshi_event, trade_amount)
+            .await
+        {
+            Ok(result) => {
+                if result.polymarket_order_id.is_some() || result.kalshi_order_id.is_some() {
+                    bot.record_trade(pm_event, kalshi_event, opp);
+                }
+                if result.success {
+                    summary.executed += 1;
+                } else {
+                    summary.failed += 1;
+                }
+            }
+            Err(_) => summary.failed += 1,
+        }
+    }
+}
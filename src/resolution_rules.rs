@@ -0,0 +1,133 @@
+//! Settlement-source comparison for matched pairs.
+//!
+//! Two markets that settle on different price sources (Polymarket's
+//! Chainlink stream vs Kalshi's CF Benchmarks index, a Coinbase candle vs
+//! Binance) can resolve differently near the strike, so buying both sides is
+//! not a true arb. The price sources named in each event's rules text are
+//! compared; when both name sources and none are shared the pair is
+//! flagged. `SETTLEMENT_SOURCE_CHECK` picks what happens: `warn` (default)
+//! logs it once per pair, `block` also drops the pair, `off` skips the check.
+//! Pairs whose rules are missing or name no known source pass.
+
+use crate::event::Event;
+use std::collections::BTreeSet;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PriceSource {
+    Chainlink,
+    CfBenchmarks,
+    Coinbase,
+    Binance,
+    Kraken,
+    Bitstamp,
+    Pyth,
+}
+
+impl fmt::Display for PriceSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PriceSource::Chainlink => "Chainlink",
+            PriceSource::CfBenchmarks => "CF Benchmarks",
+            PriceSource::Coinbase => "Coinbase",
+            PriceSource::Binance => "Binance",
+            PriceSource::Kraken => "Kraken",
+            PriceSource::Bitstamp => "Bitstamp",
+            PriceSource::Pyth => "Pyth",
+        };
+        f.write_str(name)
+    }
+}
+
+const KEYWORDS: &[(PriceSource, &[&str])] = &[
+    (PriceSource::Chainlink, &["chainlink", "data.chain.link"]),
+    (PriceSource::CfBenchmarks, &["cf benchmarks", "cfbenchmarks", "brti", "real-time index"]),
+    (PriceSource::Coinbase, &["coinbase"]),
+    (PriceSource::Binance, &["binance"]),
+    (PriceSource::Kraken, &["kraken"]),
+    (PriceSource::Bitstamp, &["bitstamp"]),
+    (PriceSource::Pyth, &["pyth"]),
+];
+
+/// Price sources named in a rules text.
+pub fn price_sources(rules: &str) -> BTreeSet<PriceSource> {
+    let lower = rules.to_lowercase();
+    KEYWORDS
+        .iter()
+        .filter(|(_, words)| words.iter().any(|w| lower.contains(w)))
+        .map(|(source, _)| *source)
+        .collect()
+}
+
+fn describe(sources: &BTreeSet<PriceSource>) -> String {
+    sources.iter().map(|s| s.to_string()).collect::<Vec<_>>().join("/")
+}
+
+/// Why the two events settle on different sources, if their rules say so.
+pub fn settlement_mismatch(pm_event: &Event, kalshi_event: &Event) -> Option<String> {
+    let pm = price_sources(pm_event.rules.as_deref()?);
+    let kalshi = price_sources(kalshi_event.rules.as_deref()?);
+    if pm.is_empty() || kalshi.is_empty() || !pm.is_disjoint(&kalshi) {
+        return None;
+    }
+    Some(format!(
+        "Polymarket settles on {}, Kalshi on {}",
+        describe(&pm),
+        describe(&kalshi)
+    ))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RulesCheck {
+    Off,
+    #[default]
+    Warn,
+    Block,
+}
+
+impl RulesCheck {
+    pub fn from_env() -> Self {
+        match std::env::var("SETTLEMENT_SOURCE_CHECK")
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase()
+            .as_str()
+        {
+            "off" | "0" | "false" => RulesCheck::Off,
+            "block" => RulesCheck::Block,
+            _ => RulesCheck::Warn,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(platform: &str, rules: &str) -> Event {
+        Event::new(platform.to_string(), "id".to_string(), "BTC".to_string(), String::new())
+            .with_rules(rules.to_string())
+    }
+
+    #[test]
+    fn flags_pairs_settling_on_different_sources() {
+        let pm = event(
+            "polymarket",
+            "https://data.chain.link/streams/btc-usd\nResolves Up if the Chainlink BTC/USD price ...",
+        );
+        let kalshi = event(
+            "kalshi",
+            "If the average of CF Benchmarks' Real-Time Index (RTI) for BTC ...",
+        );
+        assert_eq!(
+            settlement_mismatch(&pm, &kalshi).as_deref(),
+            Some("Polymarket settles on Chainlink, Kalshi on CF Benchmarks")
+        );
+
+        let coinbase = event("kalshi", "Based on the Coinbase BTC-USD 1-minute candle; Chainlink as backup");
+        assert_eq!(settlement_mismatch(&pm, &coinbase), None);
+        assert_eq!(settlement_mismatch(&pm, &event("kalshi", "Per the official source.")), None);
+        let no_rules = Event::new("kalshi".to_string(), "id".to_string(), String::new(), String::new());
+        assert_eq!(settlement_mismatch(&pm, &no_rules), None);
+    }
+}
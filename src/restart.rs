@@ -0,0 +1,125 @@
+//! Scheduled self-restart and boot-time state verification.
+//!
+//! Long runs are recycled daily at a quiet hour: the bot lets in-flight
+//! strategy ticks finish (up to `SHUTDOWN_GRACE`), flushes its stores and exits with `RESTART_EXIT_CODE` so the
+//! supervisor (systemd `Restart=always`, Docker `restart: always`, ...)
+//! relaunches it. On boot the restored positions are compared with what the
+//! venues actually report.
+
+use crate::adopt::fetch_venue_positions;
+use crate::clients::{KalshiClient, PolymarketClient};
+use crate::position_tracker::PositionTracker;
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+
+/// EX_TEMPFAIL: tells the supervisor this exit is intentional and should be
+/// followed by a relaunch.
+pub const RESTART_EXIT_CODE: i32 = 75;
+
+/// How long a shutdown waits for in-flight strategy ticks to finish.
+pub const SHUTDOWN_GRACE: std::time::Duration = std::time::Duration::from_secs(30);
+
+const QUANTITY_TOLERANCE: f64 = 0.01;
+
+#[derive(Debug, Clone, Copy)]
+pub struct RestartSchedule {
+    at: NaiveTime,
+}
+
+impl RestartSchedule {
+    pub fn new(at: NaiveTime) -> Self {
+        Self { at }
+    }
+
+    /// Reads `RESTART_AT_UTC` (`HH:MM`); scheduled restarts are off when unset.
+    pub fn from_env() -> Option<Self> {
+        let raw = std::env::var("RESTART_AT_UTC").ok()?;
+        let raw = raw.trim();
+        if raw.is_empty() {
+            return None;
+        }
+        match NaiveTime::parse_from_str(raw, "%H:%M") {
+            Ok(at) => Some(Self::new(at)),
+            Err(e) => {
+                tracing::warn!("Ignoring invalid RESTART_AT_UTC '{}': {}", raw, e);
+                None
+            }
+        }
+    }
+
+    pub fn next_after(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let today = now.date_naive().and_time(self.at).and_utc();
+        if today > now {
+            today
+        } else {
+            today + Duration::days(1)
+        }
+    }
+
+    pub fn until_next(&self, now: DateTime<Utc>) -> std::time::Duration {
+        (self.next_after(now) - now)
+            .to_std()
+            .unwrap_or(std::time::Duration::ZERO)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StateDiscrepancy {
+    pub platform: String,
+    pub event_id: String,
+    pub outcome: String,
+    pub tracked_qty: f64,
+    pub venue_qty: f64,
+}
+
+/// Compares open positions restored from the store with venue holdings,
+/// returning every (platform, event, outcome) whose quantities disagree.
+pub async fn verify_restored_state(
+    polymarket_client: &PolymarketClient,
+    kalshi_client: &KalshiClient,
+    position_tracker: &Mutex<PositionTracker>,
+) -> Vec<StateDiscrepancy> {
+    let mut tracked: HashMap<(String, String, String), f64> = HashMap::new();
+    {
+        let tracker = position_tracker.lock().await;
+        for position in tracker.get_open_positions() {
+            *tracked
+                .entry((
+                    position.platform.clone(),
+                    position.event_id.clone(),
+                    position.outcome.to_uppercase(),
+                ))
+                .or_default() += position.amount;
+        }
+    }
+
+    let mut venue: HashMap<(String, String, String), f64> = HashMap::new();
+    for position in fetch_venue_positions(polymarket_client, kalshi_client).await {
+        *venue
+            .entry((position.platform, position.event_id, position.outcome.to_uppercase()))
+            .or_default() += position.amount;
+    }
+
+    let mut keys: Vec<_> = tracked.keys().chain(venue.keys()).cloned().collect();
+    keys.sort();
+    keys.dedup();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let tracked_qty = tracked.get(&key).copied().unwrap_or(0.0);
+            let venue_qty = venue.get(&key).copied().unwrap_or(0.0);
+            if (tracked_qty - venue_qty).abs() <= QUANTITY_TOLERANCE {
+                return None;
+            }
+            let (platform, event_id, outcome) = key;
+            Some(StateDiscrepancy {
+                platform,
+                event_id,
+                outcome,
+                tracked_qty,
+                venue_qty,
+            })
+        })
+        .collect()
+}
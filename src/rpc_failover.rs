@@ -0,0 +1,176 @@
+//! Polygon JSON-RPC transport that fails over between providers.
+//!
+//! `POLYGON_RPC_URL` takes a comma-separated list. Requests go to the
+//! active provider; a timeout, connection error or unparseable response
+//! marks it unhealthy for `RPC_UNHEALTHY_SECS` (default 30) and the request
+//! is retried on the next provider. JSON-RPC error responses (reverts,
+//! "nonce too low") are answers, not outages, and are returned as-is.
+//! Requests time out after `RPC_TIMEOUT_SECS` (default 10).
+//!
+//! Metrics: `rpc_provider_healthy{provider}` (1/0),
+//! `rpc_errors_total{provider}` and `rpc_failovers_total`. Providers are
+//! labelled by host so API keys in URL paths stay out of metrics.
+
+use crate::metrics::metrics;
+use async_trait::async_trait;
+use ethers::providers::{Http, HttpClientError, JsonRpcClient};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+const DEFAULT_UNHEALTHY_SECS: u64 = 30;
+
+struct RpcProvider {
+    transport: Http,
+    label: String,
+    unhealthy_until: Mutex<Option<Instant>>,
+}
+
+impl RpcProvider {
+    fn is_healthy(&self, now: Instant) -> bool {
+        self.unhealthy_until
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_none_or(|until| now >= until)
+    }
+
+    fn set_health(&self, until: Option<Instant>) {
+        *self.unhealthy_until.lock().unwrap_or_else(|e| e.into_inner()) = until;
+        metrics().set_gauge(
+            "rpc_provider_healthy",
+            &[("provider", &self.label)],
+            if until.is_none() { 1.0 } else { 0.0 },
+        );
+    }
+}
+
+pub struct FailoverClient {
+    providers: Vec<RpcProvider>,
+    active: AtomicUsize,
+    unhealthy_for: Duration,
+}
+
+impl fmt::Debug for FailoverClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let labels: Vec<&str> = self.providers.iter().map(|p| p.label.as_str()).collect();
+        f.debug_struct("FailoverClient").field("providers", &labels).finish()
+    }
+}
+
+impl FailoverClient {
+    /// One provider per comma-separated URL in `urls`.
+    pub fn new(urls: &str) -> anyhow::Result<Self> {
+        let parse = |name: &str, default: u64| {
+            std::env::var(name)
+                .ok()
+                .and_then(|s| s.trim().parse::<u64>().ok())
+                .filter(|s| *s > 0)
+                .unwrap_or(default)
+        };
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(parse("RPC_TIMEOUT_SECS", DEFAULT_TIMEOUT_SECS)))
+            .build()?;
+        let providers = urls
+            .split(',')
+            .map(str::trim)
+            .filter(|u| !u.is_empty())
+            .map(|u| {
+                let url = reqwest::Url::parse(u)
+                    .map_err(|e| anyhow::anyhow!("Invalid Polygon RPC URL '{}': {}", u, e))?;
+                let provider = RpcProvider {
+                    label: url.host_str().unwrap_or("unknown").to_string(),
+                    transport: Http::new_with_client(url, http.clone()),
+                    unhealthy_until: Mutex::new(None),
+                };
+                provider.set_health(None);
+                Ok(provider)
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if providers.is_empty() {
+            return Err(anyhow::anyhow!("No Polygon RPC URL configured"));
+        }
+        Ok(Self {
+            providers,
+            active: AtomicUsize::new(0),
+            unhealthy_for: Duration::from_secs(parse("RPC_UNHEALTHY_SECS", DEFAULT_UNHEALTHY_SECS)),
+        })
+    }
+
+    /// Provider indices to try, active first, healthy ones before the rest.
+    fn attempt_order(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let active = self.active.load(Ordering::Relaxed);
+        let n = self.providers.len();
+        let rotation = (0..n).map(|i| (active + i) % n);
+        let (healthy, unhealthy): (Vec<usize>, Vec<usize>) =
+            rotation.partition(|&i| self.providers[i].is_healthy(now));
+        healthy.into_iter().chain(unhealthy).collect()
+    }
+}
+
+#[async_trait]
+impl JsonRpcClient for FailoverClient {
+    type Error = HttpClientError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: fmt::Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let params = serde_json::to_value(&params).map_err(|err| HttpClientError::SerdeJson {
+            err,
+            text: format!("{:?}", params),
+        })?;
+        let mut last_error = None;
+        for index in self.attempt_order() {
+            let provider = &self.providers[index];
+            match provider.transport.request(method, params.clone()).await {
+                Ok(response) => {
+                    provider.set_health(None);
+                    if self.active.swap(index, Ordering::Relaxed) != index {
+                        metrics().inc_counter("rpc_failovers_total", &[]);
+                    }
+                    return Ok(response);
+                }
+                Err(e @ HttpClientError::JsonRpcError(_)) => return Err(e),
+                Err(e) => {
+                    warn!(
+                        "Polygon RPC {} failed on {}: {} - trying the next provider",
+                        method, provider.label, e
+                    );
+                    metrics().inc_counter("rpc_errors_total", &[("provider", &provider.label)]);
+                    provider.set_health(Some(Instant::now() + self.unhealthy_for));
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.expect("at least one provider"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn fails_over_to_the_next_provider() {
+        // Nothing listens on port 1: both fail fast with connection refused.
+        let client = FailoverClient::new("http://127.0.0.1:1, http://localhost:1/key").unwrap();
+        assert_eq!(client.attempt_order(), vec![0, 1]);
+
+        let result: Result<String, _> = client.request("eth_blockNumber", ()).await;
+        assert!(result.is_err());
+        // Both marked unhealthy; the active provider still goes first.
+        assert!(client.providers.iter().all(|p| !p.is_healthy(Instant::now())));
+        assert_eq!(client.attempt_order(), vec![0, 1]);
+
+        client.providers[1].set_health(None);
+        assert_eq!(client.attempt_order(), vec![1, 0]);
+        assert_eq!(client.providers[1].label, "localhost");
+    }
+}
@@ -1,87 +1,470 @@
-use crate::clients::{KalshiClient, PolymarketClient};
+use crate::exchange::{ExchangeClient, Fill, Resolution, SettlementRecord};
+use crate::match_store::MatchStore;
+use crate::metrics::metrics;
+use crate::money::{Price, UsdAmount};
+use crate::notify::{BotEvent, Notifier};
+use crate::polymarket_blockchain::PolymarketBlockchain;
 use crate::position_tracker::{Position, PositionStatus, PositionTracker};
+use crate::rate_limit::VenueLimiter;
 use anyhow::Result;
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::{info, warn};
+use tracing::{error, info, warn};
+
+const DEFAULT_WORKERS: usize = 8;
+// Kalshi's basic tier allows ~10 reads/s; Polymarket's Gamma/CLOB reads are looser.
+const DEFAULT_KALSHI_PER_SECOND: f64 = 8.0;
+const DEFAULT_POLYMARKET_PER_SECOND: f64 = 20.0;
+
+/// Turns won Polymarket outcome tokens back into collateral.
+#[async_trait]
+pub trait Redeemer: Send + Sync {
+    /// Redeems the wallet's tokens for a resolved condition; returns the tx hash.
+    async fn redeem(&self, condition_id: &str) -> Result<String>;
+
+    /// Gas the redemption cost, in POL, when known.
+    async fn gas_paid(&self, _tx_hash: &str) -> Option<f64> {
+        None
+    }
+}
+
+#[async_trait]
+impl Redeemer for PolymarketBlockchain {
+    async fn redeem(&self, condition_id: &str) -> Result<String> {
+        let tx_hash = self.redeem_positions(condition_id).await?;
+        Ok(format!("{:?}", tx_hash))
+    }
+
+    async fn gas_paid(&self, tx_hash: &str) -> Option<f64> {
+        let hash = tx_hash.parse::<ethers::types::H256>().ok()?;
+        match PolymarketBlockchain::gas_paid(self, hash).await {
+            Ok(gas) => gas,
+            Err(e) => {
+                warn!("Could not read gas paid by {}: {}", tx_hash, e);
+                None
+            }
+        }
+    }
+}
+
+/// What a venue settlement record credits one tracked position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VenueCredit {
+    pub position_id: String,
+    pub payout: UsdAmount,
+    /// Filled cost of the position's order, when its fills were found.
+    pub cost: Option<UsdAmount>,
+    pub fees: UsdAmount,
+}
+
+/// Splits a settlement record across the tracked positions in its market.
+/// Each is paid what its shares are owed under the resolution, scaled down
+/// if the venue credited less in total. Costs and fees come from the
+/// position's own fills; when those carry no fees it takes a share (by
+/// shares) of the fees on the record.
+pub fn allocate_record(record: &SettlementRecord, positions: &[Position], fills: &[Fill]) -> Vec<VenueCredit> {
+    let owed: Vec<UsdAmount> = positions
+        .iter()
+        .map(|p| record.resolution.payout(&p.outcome, p.amount).unwrap_or(p.cost))
+        .collect();
+    let total_owed: UsdAmount = owed.iter().copied().sum();
+    let revenue = UsdAmount::from_f64(record.revenue);
+    let total_shares: f64 = positions.iter().map(|p| p.amount).sum();
+    positions
+        .iter()
+        .zip(owed)
+        .map(|(position, owed)| {
+            let own: Vec<&Fill> = fills
+                .iter()
+                .filter(|f| position.order_id.as_deref() == Some(f.order_id.as_str()))
+                .collect();
+            let fill_fees: UsdAmount = own.iter().map(|f| UsdAmount::from_f64(f.fees)).sum();
+            let fees = match record.fees {
+                Some(fees) if fill_fees == UsdAmount::ZERO => UsdAmount::from_f64(fees).pro_rata(position.amount, total_shares),
+                _ => fill_fees,
+            };
+            // Scaled down when the venue credited less than is owed.
+            let payout = if revenue < total_owed {
+                revenue.pro_rata(owed.to_f64(), total_owed.to_f64())
+            } else {
+                owed
+            };
+            VenueCredit {
+                position_id: position.id.clone(),
+                payout,
+                cost: (!own.is_empty()).then(|| own.iter().map(|f| UsdAmount::from_f64(f.cost)).sum()),
+                fees,
+            }
+        })
+        .collect()
+}
 
 pub struct SettlementChecker {
-    polymarket_client: Arc<PolymarketClient>,
-    kalshi_client: Arc<KalshiClient>,
+    polymarket_client: Arc<dyn ExchangeClient>,
+    kalshi_client: Arc<dyn ExchangeClient>,
     position_tracker: Arc<tokio::sync::Mutex<PositionTracker>>,
+    workers: usize,
+    polymarket_limiter: VenueLimiter,
+    kalshi_limiter: VenueLimiter,
+    redeemer: Option<Arc<dyn Redeemer>>,
+    notifier: Option<Arc<Notifier>>,
+    match_store: Option<Arc<MatchStore>>,
+}
+
+/// A closed cross-platform pair whose legs settled the same way.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettlementMismatch {
+    pub polymarket_id: String,
+    pub kalshi_id: String,
+    pub title: String,
+    /// Both legs won (otherwise both lost).
+    pub both_won: bool,
+}
+
+/// Mismatch in a pair's legs: a hedged pair holds opposite outcomes of the
+/// same market, so exactly one leg can win. Legs closed before resolution
+/// say nothing about it.
+pub fn settlement_mismatch(legs: &[&Position]) -> Option<SettlementMismatch> {
+    let [a, b] = legs else {
+        return None;
+    };
+    let (pm, kalshi) = match (a.platform.as_str(), b.platform.as_str()) {
+        ("polymarket", "kalshi") => (a, b),
+        ("kalshi", "polymarket") => (b, a),
+        _ => return None,
+    };
+    let both_won = match (&pm.status, &kalshi.status) {
+        (PositionStatus::Won, PositionStatus::Won) => true,
+        (PositionStatus::Lost, PositionStatus::Lost) => false,
+        _ => return None,
+    };
+    Some(SettlementMismatch {
+        polymarket_id: pm.event_id.clone(),
+        kalshi_id: kalshi.event_id.clone(),
+        title: pm.event_title.clone(),
+        both_won,
+    })
 }
 
 impl SettlementChecker {
     pub fn new(
-        polymarket_client: Arc<PolymarketClient>,
-        kalshi_client: Arc<KalshiClient>,
+        polymarket_client: Arc<dyn ExchangeClient>,
+        kalshi_client: Arc<dyn ExchangeClient>,
         position_tracker: Arc<tokio::sync::Mutex<PositionTracker>>,
     ) -> Self {
         Self {
             polymarket_client,
             kalshi_client,
             position_tracker,
+            workers: DEFAULT_WORKERS,
+            polymarket_limiter: VenueLimiter::per_second(
+                DEFAULT_WORKERS,
+                DEFAULT_POLYMARKET_PER_SECOND,
+            ),
+            kalshi_limiter: VenueLimiter::per_second(DEFAULT_WORKERS, DEFAULT_KALSHI_PER_SECOND),
+            redeemer: None,
+            notifier: None,
+            match_store: None,
         }
     }
 
+    /// Alerts on pairs whose legs settle the same way.
+    pub fn with_notifier(mut self, notifier: Arc<Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Denies pairs whose legs settle the same way, so they aren't matched
+    /// again.
+    pub fn with_match_store(mut self, store: Arc<MatchStore>) -> Self {
+        self.match_store = Some(store);
+        self
+    }
+
+    /// Redeem won Polymarket positions as soon as they settle.
+    pub fn with_redeemer(mut self, redeemer: Arc<dyn Redeemer>) -> Self {
+        self.redeemer = Some(redeemer);
+        self
+    }
+
+    /// Maximum settlement lookups in flight across both venues.
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    pub fn with_venue_rate(
+        mut self,
+        platform: &str,
+        max_in_flight: usize,
+        per_second: f64,
+    ) -> Self {
+        let limiter = VenueLimiter::per_second(max_in_flight, per_second);
+        match platform {
+            "polymarket" => self.polymarket_limiter = limiter,
+            "kalshi" => self.kalshi_limiter = limiter,
+            _ => warn!("Unknown settlement venue '{}'", platform),
+        }
+        self
+    }
+
+    /// Applies `SETTLEMENT_WORKERS`, `SETTLEMENT_KALSHI_RPS` and
+    /// `SETTLEMENT_POLYMARKET_RPS` when set.
+    pub fn with_env_limits(mut self) -> Self {
+        let parse = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|s| s.trim().parse::<f64>().ok())
+        };
+        if let Some(workers) = parse("SETTLEMENT_WORKERS") {
+            self = self.with_workers(workers as usize);
+        }
+        let workers = self.workers;
+        if let Some(rps) = parse("SETTLEMENT_KALSHI_RPS") {
+            self = self.with_venue_rate("kalshi", workers, rps);
+        }
+        if let Some(rps) = parse("SETTLEMENT_POLYMARKET_RPS") {
+            self = self.with_venue_rate("polymarket", workers, rps);
+        }
+        self
+    }
+
+    /// Checks every open position's market once (positions sharing a market
+    /// share the lookup), running up to `workers` lookups concurrently within
+    /// each venue's rate, and settles whatever has resolved. Kalshi
+    /// positions the venue reports as settled are settled at what it
+    /// actually credited and charged.
     pub async fn check_settlements(&self) -> Result<usize> {
-        let mut settled_count = 0;
-        let tracker = self.position_tracker.lock().await;
-        let open_positions = tracker.get_open_positions();
-        drop(tracker);
+        let open_positions: Vec<Position> = {
+            let tracker = self.position_tracker.lock().await;
+            tracker.get_open_positions().into_iter().cloned().collect()
+        };
+        let (mut settled_count, open_positions) = self.settle_from_records(open_positions).await;
 
+        let mut by_market: HashMap<(String, String), Vec<Position>> = HashMap::new();
         for position in open_positions {
-            let position_id = position.id.clone();
-            let event_id = position.event_id.clone();
-            let outcome = position.outcome.clone();
-            let platform = position.platform.clone();
-
-            let settlement_result = match platform.as_str() {
-                "polymarket" => {
-                    self.polymarket_client.check_settlement(&event_id).await
+            by_market
+                .entry((position.platform.clone(), position.event_id.clone()))
+                .or_default()
+                .push(position);
+        }
+
+        let lookups = stream::iter(by_market)
+            .map(|((platform, event_id), positions)| async move {
+                let result = self.lookup_settlement(&platform, &event_id).await;
+                (event_id, positions, result)
+            })
+            .buffer_unordered(self.workers);
+        let results: Vec<_> = lookups.collect().await;
+
+        for (event_id, positions, settlement_result) in results {
+            match settlement_result {
+                Ok(Some(resolution)) => {
+                    let mut redeemable = Vec::new();
+                    for position in positions {
+                        if self.settle(&position, resolution).await {
+                            settled_count += 1;
+                            let pays = resolution
+                                .payout(&position.outcome, position.amount)
+                                .is_some_and(|p| p > UsdAmount::ZERO);
+                            if position.platform == "polymarket" && pays {
+                                redeemable.push(position.id);
+                            }
+                        }
+                    }
+                    if !redeemable.is_empty() {
+                        self.redeem(&event_id, &redeemable).await;
+                    }
                 }
-                "kalshi" => {
-                    self.kalshi_client.check_settlement(&event_id).await
+                Ok(None) => {}
+                Err(e) => {
+                    warn!("Error checking settlement for {}: {}", event_id, e);
                 }
-                _ => Ok(None),
+            }
+        }
+
+        Ok(settled_count)
+    }
+
+    /// Settles Kalshi positions from `/portfolio/settlements` and their
+    /// fills; returns how many settled and the positions left to settle by
+    /// market status. Without records (or when they fail) every position is
+    /// left.
+    async fn settle_from_records(&self, positions: Vec<Position>) -> (usize, Vec<Position>) {
+        let Some(since) = positions
+            .iter()
+            .filter(|p| p.platform == "kalshi")
+            .map(|p| p.created_at)
+            .min()
+        else {
+            return (0, positions);
+        };
+        let records = {
+            let _permit = self.kalshi_limiter.acquire().await;
+            self.kalshi_client.fetch_settlement_records(since).await
+        };
+        let records = match records {
+            Ok(records) => records,
+            Err(e) => {
+                warn!("Kalshi settlement records unavailable, settling from market status: {}", e);
+                return (0, positions);
+            }
+        };
+
+        let mut by_record: HashMap<usize, Vec<Position>> = HashMap::new();
+        let mut rest = Vec::new();
+        for position in positions {
+            let record = records.iter().position(|r| {
+                position.platform == "kalshi" && (r.event_id == position.event_id || r.market == position.event_id)
+            });
+            match record {
+                Some(i) => by_record.entry(i).or_default().push(position),
+                None => rest.push(position),
+            }
+        }
+
+        let mut settled = 0;
+        for (i, positions) in by_record {
+            let record = &records[i];
+            let fills = {
+                let _permit = self.kalshi_limiter.acquire().await;
+                self.kalshi_client.fetch_fills(&record.market).await
             };
+            let fills = fills.unwrap_or_else(|e| {
+                warn!("Kalshi fills for {} unavailable, keeping tracked costs: {}", record.market, e);
+                Vec::new()
+            });
+            for (position, credit) in positions.iter().zip(allocate_record(record, &positions, &fills)) {
+                let mut tracker = self.position_tracker.lock().await;
+                let done = tracker
+                    .settle_from_venue(&position.id, record.resolution, credit.payout, credit.cost, credit.fees)
+                    .is_some();
+                if done {
+                    settled += 1;
+                }
+                self.after_settle(tracker, position, done).await;
+            }
+        }
+        (settled, rest)
+    }
 
-            match settlement_result {
-                Ok(Some(resolved_yes)) => {
-
-                    let won = (resolved_yes && outcome == "YES") 
-                        || (!resolved_yes && outcome == "NO");
-
-                    let payout = if won {
-                        Some(position.amount * 1.0)
-                    } else {
-                        Some(0.0)
-                    };
-
-                    let mut tracker = self.position_tracker.lock().await;
-                    if let Some(profit) = tracker.update_position_settlement(
-                        &position_id,
-                        won,
-                        payout,
-                    ) {
-                        settled_count += 1;
+    async fn lookup_settlement(&self, platform: &str, event_id: &str) -> Result<Option<Resolution>> {
+        let (client, limiter) = match platform {
+            "polymarket" => (&self.polymarket_client, &self.polymarket_limiter),
+            "kalshi" => (&self.kalshi_client, &self.kalshi_limiter),
+            _ => return Ok(None),
+        };
+        let _permit = limiter.acquire().await;
+        client.check_settlement(event_id).await
+    }
+
+    /// One redemption covers every paying position in the market.
+    async fn redeem(&self, condition_id: &str, position_ids: &[String]) {
+        let Some(redeemer) = &self.redeemer else {
+            return;
+        };
+        match redeemer.redeem(condition_id).await {
+            Ok(tx_hash) => {
+                info!("💵 Redeemed {} winning tokens: {}", condition_id, tx_hash);
+                // The gas is split across the positions the redemption covered.
+                let gas_share = redeemer
+                    .gas_paid(&tx_hash)
+                    .await
+                    .map(|gas| gas / position_ids.len().max(1) as f64);
+                let mut tracker = self.position_tracker.lock().await;
+                for id in position_ids {
+                    tracker.record_redemption(id, &tx_hash, gas_share);
+                }
+            }
+            Err(e) => error!(
+                "❌ Redemption of {} failed: {} - redeem it manually on Polymarket",
+                condition_id, e
+            ),
+        }
+    }
+
+    async fn settle(&self, position: &Position, resolution: Resolution) -> bool {
+        let mut tracker = self.position_tracker.lock().await;
+        let settled = match resolution {
+            Resolution::Yes | Resolution::No => {
+                let resolved_yes = resolution == Resolution::Yes;
+                let won = (resolved_yes && position.outcome == "YES") || (!resolved_yes && position.outcome == "NO");
+                let payout = Some(UsdAmount::for_shares(position.amount, if won { Price::ONE } else { Price::ZERO }));
+                match tracker.update_position_settlement(&position.id, won, payout) {
+                    Some(profit) => {
                         info!(
-                            "✅ Position settled: {} - {} - Profit: ${:.2}",
+                            "✅ Position settled: {} - {} - Profit: {}",
                             position.event_title,
                             if won { "WON" } else { "LOST" },
                             profit
                         );
+                        true
                     }
-                }
-                Ok(None) => {
-
-                }
-                Err(e) => {
-                    warn!("Error checking settlement for {}: {}", event_id, e);
+                    None => false,
                 }
             }
+            Resolution::Split { .. } | Resolution::Refunded => {
+                let payout = resolution.payout(&position.outcome, position.amount);
+                tracker.settle_at_payout(&position.id, payout).is_some()
+            }
+        };
+        self.after_settle(tracker, position, settled).await;
+        settled
+    }
+
+    /// Reports the position's pair once its last leg has settled.
+    async fn after_settle(
+        &self,
+        tracker: tokio::sync::MutexGuard<'_, PositionTracker>,
+        position: &Position,
+        settled: bool,
+    ) {
+        let Some(pair) = position
+            .group_id
+            .as_deref()
+            .filter(|_| settled)
+            .and_then(|group| tracker.pair_pnl(group))
+            .filter(|pair| pair.is_closed())
+        else {
+            return;
+        };
+        info!(
+            "🔗 Pair {} closed: {} legs, cost ${:.2}, net profit ${:.2}",
+            pair.group_id, pair.legs, pair.cost, pair.realized_profit
+        );
+        let mismatch = settlement_mismatch(&tracker.positions_in_group(&pair.group_id));
+        drop(tracker);
+        if let Some(mismatch) = mismatch {
+            self.report_mismatch(mismatch).await;
         }
+    }
 
-        Ok(settled_count)
+    async fn report_mismatch(&self, mismatch: SettlementMismatch) {
+        let outcome = if mismatch.both_won { "won" } else { "lost" };
+        error!(
+            "🚨 Both legs {} on {} ({} ↔ {}) - the markets weren't equivalent",
+            outcome, mismatch.title, mismatch.polymarket_id, mismatch.kalshi_id
+        );
+        metrics().inc_counter("pair_settlement_mismatches_total", &[("outcome", outcome)]);
+        if let Some(store) = &self.match_store {
+            store.deny(
+                &mismatch.polymarket_id,
+                &mismatch.kalshi_id,
+                &format!("both legs {}", outcome),
+            );
+        }
+        if let Some(notifier) = &self.notifier {
+            notifier
+                .notify(BotEvent::SettlementMismatch {
+                    polymarket_id: mismatch.polymarket_id,
+                    kalshi_id: mismatch.kalshi_id,
+                    title: mismatch.title,
+                    both_won: mismatch.both_won,
+                })
+                .await;
+        }
     }
 
     pub async fn check_balances(&self) -> Result<(f64, f64)> {
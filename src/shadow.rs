@@ -0,0 +1,219 @@
+//! Shadow threshold sets for cross-platform detection.
+//!
+//! Each scan, every matched pair is also run through the shadow sets: same
+//! prices, different thresholds. Where a set disagrees with the live
+//! decision it is logged, and per-set tallies accumulate, so a proposed
+//! threshold change can be judged on live data. Shadow results are never
+//! executed.
+//!
+//! `SHADOW_THRESHOLDS=tight:min_profit=0.01,min_liquidity=50;wide:max_spread=0.06`
+//! (keys: `min_profit`, `min_liquidity`, `max_spread`; unset keys follow the
+//! live settings).
+
+use crate::arbitrage_detector::{ArbitrageDetector, ArbitrageOpportunity};
+use crate::event::{Event, MarketPrices};
+use crate::metrics::metrics;
+use crate::money::{Price, UsdAmount};
+use crate::spread::SpreadLimit;
+use std::sync::Mutex;
+use tracing::info;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShadowSet {
+    pub name: String,
+    pub min_profit: Option<f64>,
+    pub min_liquidity: Option<f64>,
+    pub max_spread: Option<SpreadLimit>,
+}
+
+impl ShadowSet {
+    /// `name:key=value,...`
+    pub fn parse(s: &str) -> Option<Self> {
+        let (name, params) = s.split_once(':').unwrap_or((s, ""));
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        let mut set = ShadowSet {
+            name: name.to_string(),
+            min_profit: None,
+            min_liquidity: None,
+            max_spread: None,
+        };
+        for param in params.split(',').filter(|p| !p.trim().is_empty()) {
+            let (key, value) = param.split_once('=')?;
+            let value = value.trim();
+            match key.trim() {
+                "min_profit" => set.min_profit = Some(value.parse().ok()?),
+                "min_liquidity" => set.min_liquidity = Some(value.parse().ok()?),
+                "max_spread" => set.max_spread = Some(SpreadLimit::parse(value)?),
+                _ => return None,
+            }
+        }
+        Some(set)
+    }
+}
+
+/// Live settings a shadow set falls back to.
+pub struct LiveSettings {
+    pub min_profit: f64,
+    pub min_liquidity: f64,
+    /// Whether both books passed the live spread limits.
+    pub spread_ok: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShadowTally {
+    pub pairs: usize,
+    /// Pairs the shadow set would trade.
+    pub hits: usize,
+    /// Shadow trades the live set skipped.
+    pub extra: usize,
+    /// Live trades the shadow set would skip.
+    pub missed: usize,
+    /// Net profit per share summed over shadow hits.
+    pub net_profit: Price,
+}
+
+pub struct ShadowEvaluator {
+    sets: Vec<(ShadowSet, Mutex<ShadowTally>)>,
+}
+
+impl ShadowEvaluator {
+    pub fn new(sets: Vec<ShadowSet>) -> Self {
+        Self {
+            sets: sets
+                .into_iter()
+                .map(|set| (set, Mutex::new(ShadowTally::default())))
+                .collect(),
+        }
+    }
+
+    /// `None` when `SHADOW_THRESHOLDS` is unset or has no valid set.
+    pub fn from_env() -> Option<Self> {
+        let raw = std::env::var("SHADOW_THRESHOLDS").ok()?;
+        let sets: Vec<ShadowSet> = raw
+            .split(';')
+            .filter(|s| !s.trim().is_empty())
+            .filter_map(|s| {
+                let set = ShadowSet::parse(s);
+                if set.is_none() {
+                    tracing::warn!("Ignoring invalid SHADOW_THRESHOLDS entry '{}'", s.trim());
+                }
+                set
+            })
+            .collect();
+        (!sets.is_empty()).then(|| Self::new(sets))
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.sets.iter().map(|(set, _)| set.name.as_str()).collect()
+    }
+
+    /// Evaluates one matched pair under every shadow set; `live` is what the
+    /// live settings decided.
+    pub fn observe(
+        &self,
+        pm_event: &Event,
+        pm_prices: &MarketPrices,
+        kalshi_prices: &MarketPrices,
+        inverted: bool,
+        live_settings: &LiveSettings,
+        live: Option<&ArbitrageOpportunity>,
+    ) {
+        for (set, tally) in &self.sets {
+            let min_liquidity = set.min_liquidity.unwrap_or(live_settings.min_liquidity);
+            let spread_ok = match set.max_spread {
+                Some(limit) => limit.allows(pm_prices) && limit.allows(kalshi_prices),
+                None => live_settings.spread_ok,
+            };
+            let min_liquidity = UsdAmount::from_f64(min_liquidity);
+            let shadow = (pm_prices.depth >= min_liquidity
+                && kalshi_prices.depth >= min_liquidity
+                && spread_ok)
+                .then(|| {
+                    ArbitrageDetector::new(set.min_profit.unwrap_or(live_settings.min_profit))
+                        .check_arbitrage_with_polarity(pm_prices, kalshi_prices, inverted)
+                })
+                .flatten();
+
+            let mut tally = tally.lock().unwrap_or_else(|e| e.into_inner());
+            tally.pairs += 1;
+            if let Some(opp) = &shadow {
+                tally.hits += 1;
+                tally.net_profit += opp.net_profit;
+                metrics().inc_counter("shadow_opportunities_total", &[("set", &set.name)]);
+            }
+            match (&shadow, live) {
+                (Some(opp), None) => {
+                    tally.extra += 1;
+                    info!(
+                        "👻 [{}] would trade {}: net {}/share (live skipped)",
+                        set.name, pm_event.title, opp.net_profit
+                    );
+                }
+                (None, Some(opp)) => {
+                    tally.missed += 1;
+                    info!(
+                        "👻 [{}] would skip {} (live trades at {}/share)",
+                        set.name, pm_event.title, opp.net_profit
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub fn tally(&self, name: &str) -> Option<ShadowTally> {
+        self.sets
+            .iter()
+            .find(|(set, _)| set.name == name)
+            .map(|(_, tally)| tally.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    }
+
+    /// Logs the running tallies of every set.
+    pub fn log_summary(&self) {
+        for (set, tally) in &self.sets {
+            let tally = tally.lock().unwrap_or_else(|e| e.into_inner());
+            info!(
+                "👻 [{}] {} pairs: {} hits (+{} vs live, -{} vs live), {}/share total edge",
+                set.name, tally.pairs, tally.hits, tally.extra, tally.missed, tally.net_profit
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tallies_disagreements_with_live() {
+        let tight = ShadowSet::parse("tight:min_profit=0.10").unwrap();
+        let loose = ShadowSet::parse("loose: min_profit=0.01, min_liquidity=50").unwrap();
+        assert_eq!(loose.min_liquidity, Some(50.0));
+        assert!(ShadowSet::parse("bad:min_profit=x").is_none());
+
+        let evaluator = ShadowEvaluator::new(vec![tight, loose]);
+        let event = Event::new("polymarket".into(), "pm".into(), "BTC 15m".into(), String::new());
+        let live_settings = LiveSettings {
+            min_profit: 0.02,
+            min_liquidity: 100.0,
+            spread_ok: true,
+        };
+        // 0.45 + 0.48 = 0.93: 0.05 net after fees, live trades it.
+        let pm = MarketPrices::new(0.55, 0.45, 80.0);
+        let kalshi = MarketPrices::new(0.48, 0.52, 1000.0);
+        let live = ArbitrageDetector::new(0.02).check_arbitrage(&pm, &kalshi);
+        // Live is blocked by liquidity (80 < 100) but the loose set is not.
+        evaluator.observe(&event, &pm, &kalshi, false, &live_settings, None);
+
+        let pm = MarketPrices::new(0.55, 0.45, 1000.0);
+        evaluator.observe(&event, &pm, &kalshi, false, &live_settings, live.as_ref());
+
+        let tight = evaluator.tally("tight").unwrap();
+        assert_eq!((tight.pairs, tight.hits, tight.extra, tight.missed), (2, 0, 0, 1));
+        let loose = evaluator.tally("loose").unwrap();
+        assert_eq!((loose.pairs, loose.hits, loose.extra, loose.missed), (2, 2, 1, 0));
+    }
+}
@@ -0,0 +1,349 @@
+//! Transaction signing for the Polygon helpers.
+//!
+//! `PolymarketBlockchain` signs approvals and redemptions through [`Signer`]:
+//! either the local `POLYMARKET_WALLET_PRIVATE_KEY`, or - with
+//! `POLYMARKET_REMOTE_SIGNER_URL` set - a remote signer speaking
+//! `eth_signTransaction` (Web3Signer, which can keep the key in AWS KMS, or
+//! Clef), so the key never sits on the trading host. `POLYMARKET_SIGNER_ADDRESS`
+//! names the key to sign with and `POLYMARKET_REMOTE_SIGNER_TOKEN`, if set, is
+//! sent as a bearer token. Every signature is checked to recover to that
+//! address before the transaction is broadcast.
+//!
+//! With `POLYMARKET_LEDGER=true` (and a `--features ledger` build) they are
+//! signed on a Ledger instead, at `POLYMARKET_LEDGER_HD_PATH` (default the
+//! first Ledger Live account). Every Ledger signature waits for a button
+//! press, up to `POLYMARKET_LEDGER_CONFIRM_TIMEOUT_SECS` (default 120).
+//! `POLYMARKET_LEDGER_CONFIRM` decides which flows may ask for one: `startup`
+//! (default) only the startup allowance approvals, with automated redemptions
+//! disabled, or `always` for those too, for an operator who stays at the device.
+//!
+//! CLOB orders are still signed by the Polymarket SDK from the local key.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use ethers::signers::LocalWallet;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, Bytes, Signature};
+use ethers::utils::rlp::Rlp;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+const REMOTE_SIGNER_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_LEDGER_HD_PATH: &str = "m/44'/60'/0'/0/0";
+const DEFAULT_LEDGER_CONFIRM_SECS: u64 = 120;
+
+#[async_trait]
+pub trait Signer: Send + Sync {
+    /// The address transactions are sent from.
+    fn address(&self) -> Address;
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature>;
+
+    /// Whether automated flows (e.g. redemptions on settlement) may sign
+    /// without an operator.
+    fn signs_unattended(&self) -> bool {
+        true
+    }
+}
+
+#[async_trait]
+impl Signer for LocalWallet {
+    fn address(&self) -> Address {
+        ethers::signers::Signer::address(self)
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature> {
+        Ok(ethers::signers::Signer::sign_transaction(self, tx).await?)
+    }
+}
+
+/// Signs over JSON-RPC `eth_signTransaction`.
+pub struct RemoteSigner {
+    url: String,
+    address: Address,
+    token: Option<String>,
+    http: reqwest::Client,
+}
+
+impl RemoteSigner {
+    pub fn new(url: impl Into<String>, address: Address) -> Self {
+        Self {
+            url: url.into(),
+            address,
+            token: None,
+            http: reqwest::Client::builder()
+                .timeout(REMOTE_SIGNER_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// `POLYMARKET_REMOTE_SIGNER_URL` with `POLYMARKET_SIGNER_ADDRESS`, when set.
+    pub fn from_env() -> Result<Option<Self>> {
+        let var = |name: &str| std::env::var(name).ok().filter(|s| !s.trim().is_empty());
+        let Some(url) = var("POLYMARKET_REMOTE_SIGNER_URL") else {
+            return Ok(None);
+        };
+        let address: Address = var("POLYMARKET_SIGNER_ADDRESS")
+            .context("POLYMARKET_REMOTE_SIGNER_URL needs POLYMARKET_SIGNER_ADDRESS")?
+            .trim()
+            .parse()
+            .context("Invalid POLYMARKET_SIGNER_ADDRESS")?;
+        let mut signer = Self::new(url.trim(), address);
+        if let Some(token) = var("POLYMARKET_REMOTE_SIGNER_TOKEN") {
+            signer = signer.with_token(token.trim());
+        }
+        Ok(Some(signer))
+    }
+}
+
+/// The signature in an `eth_signTransaction` result: the raw signed
+/// transaction, bare (Web3Signer) or as `{"raw": ...}` (Clef). Errors unless
+/// it signs `tx` and recovers to `address`.
+fn signature_from_response(result: &Value, tx: &TypedTransaction, address: Address) -> Result<Signature> {
+    let raw = result
+        .as_str()
+        .or_else(|| result.get("raw").and_then(Value::as_str))
+        .context("Remote signer returned no raw transaction")?;
+    let raw: Bytes = raw.parse().context("Remote signer returned invalid hex")?;
+    let (signed, signature) = TypedTransaction::decode_signed(&Rlp::new(&raw))
+        .context("Remote signer returned an undecodable transaction")?;
+    if signed.sighash() != tx.sighash() {
+        anyhow::bail!("Remote signer signed a different transaction");
+    }
+    let signer = signature
+        .recover(tx.sighash())
+        .context("Remote signature does not recover")?;
+    if signer != address {
+        anyhow::bail!("Remote signature is from {:?}, expected {:?}", signer, address);
+    }
+    Ok(signature)
+}
+
+#[async_trait]
+impl Signer for RemoteSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature> {
+        let mut params = serde_json::to_value(tx).context("Failed to encode transaction")?;
+        params["from"] = json!(self.address);
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_signTransaction",
+            "params": [params],
+        });
+        let mut request = self.http.post(&self.url).json(&body);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        let response: Value = request
+            .send()
+            .await
+            .context("Remote signer unreachable")?
+            .error_for_status()
+            .context("Remote signer rejected the request")?
+            .json()
+            .await
+            .context("Remote signer returned invalid JSON")?;
+        if let Some(error) = response.get("error") {
+            anyhow::bail!("Remote signer error: {}", error);
+        }
+        signature_from_response(&response["result"], tx, self.address)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerConfirm {
+    /// Only startup allowance approvals sign on the device.
+    Startup,
+    /// Automated flows sign on the device too, each waiting for a press.
+    Always,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerConfig {
+    pub hd_path: String,
+    pub confirm: LedgerConfirm,
+    pub confirm_timeout: Duration,
+}
+
+impl LedgerConfig {
+    /// Read through `var`, when `POLYMARKET_LEDGER` is on.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Option<Self>> {
+        let var = |name: &str| var(name).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let on = var("POLYMARKET_LEDGER").is_some_and(|v| v == "1" || v.eq_ignore_ascii_case("true"));
+        if !on {
+            return Ok(None);
+        }
+        let confirm = match var("POLYMARKET_LEDGER_CONFIRM").as_deref() {
+            None => LedgerConfirm::Startup,
+            Some(v) if v.eq_ignore_ascii_case("startup") => LedgerConfirm::Startup,
+            Some(v) if v.eq_ignore_ascii_case("always") => LedgerConfirm::Always,
+            Some(other) => anyhow::bail!("POLYMARKET_LEDGER_CONFIRM must be startup or always, not {}", other),
+        };
+        let secs = match var("POLYMARKET_LEDGER_CONFIRM_TIMEOUT_SECS") {
+            Some(v) => v
+                .parse::<u64>()
+                .ok()
+                .filter(|secs| *secs > 0)
+                .context("Invalid POLYMARKET_LEDGER_CONFIRM_TIMEOUT_SECS")?,
+            None => DEFAULT_LEDGER_CONFIRM_SECS,
+        };
+        Ok(Some(Self {
+            hd_path: var("POLYMARKET_LEDGER_HD_PATH").unwrap_or_else(|| DEFAULT_LEDGER_HD_PATH.to_string()),
+            confirm,
+            confirm_timeout: Duration::from_secs(secs),
+        }))
+    }
+
+    pub fn from_env() -> Result<Option<Self>> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+}
+
+/// Signs on a Ledger's Ethereum app.
+#[cfg(feature = "ledger")]
+pub struct LedgerSigner {
+    ledger: ethers::signers::Ledger,
+    config: LedgerConfig,
+}
+
+#[cfg(feature = "ledger")]
+impl LedgerSigner {
+    pub async fn connect(config: LedgerConfig, chain_id: u64) -> Result<Self> {
+        let path = ethers::signers::HDPath::Other(config.hd_path.clone());
+        let ledger = ethers::signers::Ledger::new(path, chain_id)
+            .await
+            .context("Failed to open the Ledger - is it unlocked with the Ethereum app open?")?;
+        Ok(Self { ledger, config })
+    }
+}
+
+#[cfg(feature = "ledger")]
+#[async_trait]
+impl Signer for LedgerSigner {
+    fn address(&self) -> Address {
+        ethers::signers::Signer::address(&self.ledger)
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature> {
+        let timeout = self.config.confirm_timeout;
+        tracing::info!("🔐 Confirm the transaction on the Ledger (within {}s)", timeout.as_secs());
+        tokio::time::timeout(timeout, ethers::signers::Signer::sign_transaction(&self.ledger, tx))
+            .await
+            .map_err(|_| anyhow::anyhow!("Ledger not confirmed within {}s", timeout.as_secs()))?
+            .context("Ledger did not sign")
+    }
+
+    fn signs_unattended(&self) -> bool {
+        self.config.confirm == LedgerConfirm::Always
+    }
+}
+
+#[cfg(feature = "ledger")]
+async fn ledger_signer(config: LedgerConfig, chain_id: u64) -> Result<Arc<dyn Signer>> {
+    let ledger = LedgerSigner::connect(config, chain_id).await?;
+    tracing::info!("🔐 Signing on-chain transactions on the Ledger as {:?}", Signer::address(&ledger));
+    Ok(Arc::new(ledger))
+}
+
+#[cfg(not(feature = "ledger"))]
+async fn ledger_signer(_config: LedgerConfig, _chain_id: u64) -> Result<Arc<dyn Signer>> {
+    anyhow::bail!("POLYMARKET_LEDGER needs a build with `--features ledger`")
+}
+
+/// The remote signer or Ledger when configured, else the local key, else none.
+pub async fn from_env(private_key: Option<&str>, chain_id: u64) -> Result<Option<Arc<dyn Signer>>> {
+    let remote = RemoteSigner::from_env()?;
+    let ledger = LedgerConfig::from_env()?;
+    match (remote, ledger) {
+        (Some(_), Some(_)) => anyhow::bail!("Set only one of POLYMARKET_REMOTE_SIGNER_URL and POLYMARKET_LEDGER"),
+        (Some(remote), None) => return Ok(Some(Arc::new(remote))),
+        (None, Some(ledger)) => return ledger_signer(ledger, chain_id).await.map(Some),
+        (None, None) => {}
+    }
+    let Some(key) = private_key.filter(|k| !k.trim().is_empty()) else {
+        return Ok(None);
+    };
+    Ok(Some(Arc::new(local_wallet(key, chain_id)?)))
+}
+
+pub fn local_wallet(private_key: &str, chain_id: u64) -> Result<LocalWallet> {
+    let wallet: LocalWallet = private_key
+        .parse()
+        .context("Invalid private key format. Must be hex string starting with 0x")?;
+    Ok(ethers::signers::Signer::with_chain_id(wallet, chain_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::Eip1559TransactionRequest;
+
+    #[tokio::test]
+    async fn remote_signatures_must_match_the_transaction_and_address() {
+        let wallet = local_wallet(
+            "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318",
+            137,
+        )
+        .unwrap();
+        let tx = TypedTransaction::Eip1559(
+            Eip1559TransactionRequest::new()
+                .from(Signer::address(&wallet))
+                .to(Address::repeat_byte(0x11))
+                .nonce(3)
+                .gas(60_000)
+                .max_fee_per_gas(100)
+                .max_priority_fee_per_gas(30)
+                .chain_id(137),
+        );
+        let signature = Signer::sign_transaction(&wallet, &tx).await.unwrap();
+        let raw = json!(tx.rlp_signed(&signature));
+
+        // The local wallet reports an EIP-155 `v`, the decoded one its parity.
+        let decoded = signature_from_response(&raw, &tx, Signer::address(&wallet)).unwrap();
+        assert_eq!((decoded.r, decoded.s), (signature.r, signature.s));
+        assert_eq!(tx.rlp_signed(&decoded), tx.rlp_signed(&signature));
+        let clef = json!({ "raw": raw, "tx": {} });
+        assert!(signature_from_response(&clef, &tx, Signer::address(&wallet)).is_ok());
+        assert!(signature_from_response(&raw, &tx, Address::repeat_byte(0x22)).is_err());
+
+        let mut other = tx.clone();
+        other.set_nonce(4);
+        assert!(signature_from_response(&raw, &other, Signer::address(&wallet)).is_err());
+    }
+
+    #[test]
+    fn ledger_config_defaults_to_startup_confirmation() {
+        let config = |pairs: &[(&str, &str)]| {
+            let env: std::collections::HashMap<_, _> = pairs.iter().copied().collect();
+            LedgerConfig::from_vars(|name| env.get(name).map(|v| v.to_string()))
+        };
+        assert_eq!(config(&[]).unwrap(), None);
+        let ledger = config(&[("POLYMARKET_LEDGER", "true")]).unwrap().unwrap();
+        assert_eq!(ledger.confirm, LedgerConfirm::Startup);
+        assert_eq!(ledger.hd_path, DEFAULT_LEDGER_HD_PATH);
+        assert_eq!(ledger.confirm_timeout, Duration::from_secs(DEFAULT_LEDGER_CONFIRM_SECS));
+
+        let always = config(&[
+            ("POLYMARKET_LEDGER", "1"),
+            ("POLYMARKET_LEDGER_CONFIRM", "always"),
+            ("POLYMARKET_LEDGER_CONFIRM_TIMEOUT_SECS", "30"),
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(always.confirm, LedgerConfirm::Always);
+        assert_eq!(always.confirm_timeout, Duration::from_secs(30));
+        let invalid = config(&[("POLYMARKET_LEDGER", "true"), ("POLYMARKET_LEDGER_CONFIRM", "never")]);
+        assert!(invalid.is_err());
+    }
+}
@@ -0,0 +1,146 @@
+//! Parameter sweeps over recorded data.
+//!
+//! `simulate` replays the same recording once per point of a `ParamsGrid`
+//! (min edge × similarity × trade size), each against its own mock venues,
+//! and returns one `SimResult` per point so thresholds can be compared
+//! side by side.
+
+use crate::bot::{MarketFilters, ShortTermArbitrageBot};
+use crate::mock::{MockKalshiClient, MockPolymarketClient};
+use crate::replay::{replay_scan, ReplaySource, ReplaySummary};
+use crate::trade_executor::TradeExecutor;
+use std::sync::Arc;
+use tracing::warn;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimParams {
+    pub min_profit_threshold: f64,
+    pub similarity_threshold: f64,
+    pub trade_amount: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParamsGrid {
+    pub filters: MarketFilters,
+    pub min_profit_thresholds: Vec<f64>,
+    pub similarity_thresholds: Vec<f64>,
+    pub trade_amounts: Vec<f64>,
+}
+
+impl ParamsGrid {
+    pub fn new(filters: MarketFilters) -> Self {
+        Self {
+            filters,
+            min_profit_thresholds: vec![0.02],
+            similarity_thresholds: vec![0.80],
+            trade_amounts: vec![100.0],
+        }
+    }
+
+    pub fn with_min_profit_thresholds(mut self, values: Vec<f64>) -> Self {
+        self.min_profit_thresholds = values;
+        self
+    }
+
+    pub fn with_similarity_thresholds(mut self, values: Vec<f64>) -> Self {
+        self.similarity_thresholds = values;
+        self
+    }
+
+    pub fn with_trade_amounts(mut self, values: Vec<f64>) -> Self {
+        self.trade_amounts = values;
+        self
+    }
+
+    /// Every combination, min edge varying slowest and trade size fastest.
+    pub fn combinations(&self) -> Vec<SimParams> {
+        let mut combos = Vec::new();
+        for &min_profit_threshold in &self.min_profit_thresholds {
+            for &similarity_threshold in &self.similarity_thresholds {
+                for &trade_amount in &self.trade_amounts {
+                    combos.push(SimParams {
+                        min_profit_threshold,
+                        similarity_threshold,
+                        trade_amount,
+                    });
+                }
+            }
+        }
+        combos
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SimResult {
+    pub params: SimParams,
+    pub summary: ReplaySummary,
+    /// Dollars spent on orders that filled against the recorded books.
+    pub capital_deployed: f64,
+    /// Share of placed orders that filled (0.0 when nothing was placed).
+    pub fill_rate: f64,
+}
+
+/// Runs the recording through every grid point in parallel. Results come
+/// back in `grid.combinations()` order.
+pub async fn simulate(grid: &ParamsGrid, recorded: &ReplaySource) -> Vec<SimResult> {
+    let recorded = Arc::new(recorded.clone());
+    let handles: Vec<_> = grid
+        .combinations()
+        .into_iter()
+        .map(|params| {
+            let filters = grid.filters.clone();
+            let recorded = recorded.clone();
+            tokio::spawn(async move { run_one(params, filters, &recorded).await })
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        match handle.await {
+            Ok(result) => results.push(result),
+            Err(e) => warn!("Simulation task failed: {}", e),
+        }
+    }
+    results
+}
+
+async fn run_one(params: SimParams, filters: MarketFilters, recorded: &ReplaySource) -> SimResult {
+    let pm = Arc::new(MockPolymarketClient::new());
+    let kalshi = Arc::new(MockKalshiClient::new());
+    pm.set_balance(f64::MAX);
+    kalshi.set_balance(f64::MAX);
+
+    let bot = ShortTermArbitrageBot::new(
+        filters,
+        params.similarity_threshold,
+        params.min_profit_threshold,
+    );
+    let executor = TradeExecutor::new(pm.clone(), kalshi.clone());
+
+    let mut summary = ReplaySummary::default();
+    for scan in recorded.scans() {
+        // Orders fill against the books as they were recorded in this scan.
+        for (platform, event_id, prices) in scan.recorded_prices() {
+            match platform {
+                "kalshi" => kalshi.set_prices(event_id, prices.clone()),
+                _ => pm.set_prices(event_id, prices.clone()),
+            }
+        }
+        replay_scan(&bot, &executor, scan, params.trade_amount, &mut summary).await;
+    }
+
+    let orders: Vec<_> = pm.orders().into_iter().chain(kalshi.orders()).collect();
+    let filled: Vec<_> = orders.iter().filter(|o| o.filled).collect();
+    let fill_rate = if orders.is_empty() {
+        0.0
+    } else {
+        filled.len() as f64 / orders.len() as f64
+    };
+
+    SimResult {
+        params,
+        summary,
+        capital_deployed: filled.iter().map(|o| o.amount).sum(),
+        fill_rate,
+    }
+}
@@ -0,0 +1,141 @@
+//! Why the bot passed on a market or an opportunity.
+//!
+//! Every place that decides not to trade records a `SkipReason`: the
+//! cross-platform and Gabagool scans for markets they couldn't price, match
+//! or find an edge in, and the strategy registry for opportunities its
+//! minimums, pauses, loss limit, budgets and trade caps held back. Each
+//! strategy's stats carry the counts of its latest scan and the running
+//! totals, and every skip is counted in
+//! `opportunities_skipped_total{strategy,reason}`, so thresholds can be
+//! tuned on what they actually filter out.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// No market on the other venue matched.
+    NoMatch,
+    /// The pair settles on different price sources.
+    SettlementMismatch,
+    /// A failed fetch, out of range or crossed quotes.
+    InvalidPrice,
+    /// Quotes older than `MAX_PRICE_AGE_SECS`.
+    StalePrice,
+    /// Book depth under `MIN_LIQUIDITY`.
+    LowLiquidity,
+    /// Bid-ask spread over `MAX_SPREAD`.
+    WideSpread,
+    /// The pair costs $1 or more.
+    NoEdge,
+    /// The gross edge doesn't cover the fees.
+    FeesEatEdge,
+    /// Net edge under the minimum profit.
+    BelowMinProfit,
+    /// The pair traded recently at this price.
+    RecentlyTraded,
+    LowRoiPerHour,
+    LowConfidence,
+    /// The strategy or a venue it trades on is paused.
+    Paused,
+    /// The daily loss limit was hit.
+    LossLimit,
+    /// Over the strategy's capital budget.
+    OverBudget,
+    /// `MAX_TRADES_PER_SCAN` / `MAX_OPEN_POSITIONS` reached.
+    TradeCap,
+}
+
+impl SkipReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SkipReason::NoMatch => "no_match",
+            SkipReason::SettlementMismatch => "settlement_mismatch",
+            SkipReason::InvalidPrice => "invalid_price",
+            SkipReason::StalePrice => "stale_price",
+            SkipReason::LowLiquidity => "low_liquidity",
+            SkipReason::WideSpread => "wide_spread",
+            SkipReason::NoEdge => "no_edge",
+            SkipReason::FeesEatEdge => "fees_eat_edge",
+            SkipReason::BelowMinProfit => "below_min_profit",
+            SkipReason::RecentlyTraded => "recently_traded",
+            SkipReason::LowRoiPerHour => "low_roi_per_hour",
+            SkipReason::LowConfidence => "low_confidence",
+            SkipReason::Paused => "paused",
+            SkipReason::LossLimit => "loss_limit",
+            SkipReason::OverBudget => "over_budget",
+            SkipReason::TradeCap => "trade_cap",
+        }
+    }
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Skips counted by reason.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SkipCounts(BTreeMap<SkipReason, u64>);
+
+impl SkipCounts {
+    pub fn add(&mut self, reason: SkipReason) {
+        *self.0.entry(reason).or_default() += 1;
+    }
+
+    pub fn merge(&mut self, other: &SkipCounts) {
+        for (reason, count) in &other.0 {
+            *self.0.entry(*reason).or_default() += count;
+        }
+    }
+
+    pub fn get(&self, reason: SkipReason) -> u64 {
+        self.0.get(&reason).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> u64 {
+        self.0.values().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (SkipReason, u64)> + '_ {
+        self.0.iter().map(|(reason, count)| (*reason, *count))
+    }
+}
+
+/// `stale_price=3 fees_eat_edge=2`, most frequent first.
+impl fmt::Display for SkipCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut counts: Vec<(SkipReason, u64)> = self.iter().collect();
+        counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        let parts: Vec<String> = counts.iter().map(|(reason, count)| format!("{}={}", reason, count)).collect();
+        f.write_str(&parts.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_merge_and_list_the_most_frequent_first() {
+        let mut scan = SkipCounts::default();
+        scan.add(SkipReason::StalePrice);
+        scan.add(SkipReason::FeesEatEdge);
+        scan.add(SkipReason::FeesEatEdge);
+        let mut totals = SkipCounts::default();
+        totals.add(SkipReason::StalePrice);
+        totals.merge(&scan);
+
+        assert_eq!(scan.to_string(), "fees_eat_edge=2 stale_price=1");
+        assert_eq!(totals.get(SkipReason::StalePrice), 2);
+        assert_eq!(totals.total(), 4);
+        assert_eq!(serde_json::to_string(&SkipReason::FeesEatEdge).unwrap(), r#""fees_eat_edge""#);
+    }
+}
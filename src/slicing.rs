@@ -0,0 +1,97 @@
+//! Order slicing (TWAP) for trades larger than the touch.
+//!
+//! A trade bigger than what rests at the best ask walks the book, paying for
+//! every level past the touch and giving the edge away. With
+//! `ORDER_SLICE_INTERVAL_MS` set, such a trade goes out in slices no bigger
+//! than `ORDER_SLICE_TOUCH_FRACTION` (default 1) of the touch, that many
+//! milliseconds apart, each limited to the prices the opportunity was
+//! detected at. The touch is re-read before every slice, so slicing stops
+//! once the book has moved past those limits, after `ORDER_SLICE_MAX`
+//! (default 10) slices, or when a slice fails.
+
+use crate::event::MarketPrices;
+use crate::money::{Price, UsdAmount};
+use std::time::Duration;
+
+/// Slices under this many dollars aren't worth an order.
+pub const MIN_SLICE: f64 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrderSlicing {
+    /// Pause between slices.
+    pub interval: Duration,
+    /// Share of the touch one slice may take.
+    pub touch_fraction: f64,
+    pub max_slices: usize,
+}
+
+impl OrderSlicing {
+    pub fn new(interval: Duration) -> Self {
+        Self { interval, touch_fraction: 1.0, max_slices: 10 }
+    }
+
+    /// `None` unless `ORDER_SLICE_INTERVAL_MS` is set.
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| std::env::var(name).ok().and_then(|s| s.trim().parse::<f64>().ok());
+        let interval = var("ORDER_SLICE_INTERVAL_MS").filter(|ms| *ms >= 0.0)?;
+        let mut slicing = Self::new(Duration::from_millis(interval as u64));
+        if let Some(fraction) = var("ORDER_SLICE_TOUCH_FRACTION").filter(|f| *f > 0.0 && *f <= 1.0) {
+            slicing.touch_fraction = fraction;
+        }
+        if let Some(max) = var("ORDER_SLICE_MAX").filter(|m| *m >= 1.0) {
+            slicing.max_slices = max as usize;
+        }
+        Some(slicing)
+    }
+
+    /// Whether `amount` dollars is more than one slice of a `touch` may take.
+    pub fn needs_slicing(&self, amount: f64, touch: f64) -> bool {
+        amount > touch * self.touch_fraction
+    }
+
+    /// Dollars of the next slice, with `remaining` left to trade against
+    /// `touch` dollars at the limit, after `done` slices; `None` once
+    /// slicing should stop.
+    pub fn next_slice(&self, remaining: f64, touch: f64, done: usize) -> Option<f64> {
+        if done >= self.max_slices {
+            return None;
+        }
+        Some(remaining.min(touch * self.touch_fraction)).filter(|slice| *slice >= MIN_SLICE)
+    }
+}
+
+/// Dollars resting at the best ask of `outcome`, none of it when that ask is
+/// already past `limit`. `None` when the book wasn't read.
+pub fn touch_notional(prices: &MarketPrices, outcome: &str, limit: Price) -> Option<f64> {
+    let book = prices.book?;
+    let ask = if outcome.eq_ignore_ascii_case("YES") { book.yes_ask } else { book.no_ask }?;
+    if Price::from_f64(ask.price) > limit {
+        return Some(0.0);
+    }
+    Some(UsdAmount::for_shares(ask.size, Price::from_f64(ask.price)).to_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{BookTop, Quote};
+
+    #[test]
+    fn slices_fit_the_touch_until_the_book_moves_away() {
+        let book = BookTop {
+            yes_ask: Some(Quote { price: 0.45, size: 100.0 }),
+            ..Default::default()
+        };
+        let prices = MarketPrices::new(0.45, 0.56, 45.0).with_book(book);
+        assert_eq!(touch_notional(&prices, "YES", Price::from_cents(45)), Some(45.0));
+        assert_eq!(touch_notional(&prices, "YES", Price::from_cents(44)), Some(0.0));
+        assert_eq!(touch_notional(&prices, "NO", Price::from_cents(56)), None);
+
+        let slicing = OrderSlicing { touch_fraction: 0.5, ..OrderSlicing::new(Duration::ZERO) };
+        assert!(slicing.needs_slicing(100.0, 45.0));
+        assert_eq!(slicing.next_slice(100.0, 45.0, 0), Some(22.5));
+        assert_eq!(slicing.next_slice(10.0, 45.0, 1), Some(10.0));
+        assert_eq!(slicing.next_slice(100.0, 0.0, 1), None);
+        assert_eq!(slicing.next_slice(100.0, 45.0, 10), None);
+    }
+}
@@ -0,0 +1,268 @@
+//! Live BTC/ETH/SOL spot prices from Binance trade websockets.
+//!
+//! A background task keeps the last few minutes of prices per coin in shared
+//! state. The fair-value engine reads spot from it, and cross-platform trades
+//! are skipped while spot is moving fast (or the feed is silent), since
+//! venue quotes fetched during the scan are then likely stale.
+//!
+//! Enabled with `SPOT_FEED=binance`.
+
+use crate::health::health;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use futures::StreamExt;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, RwLock};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{info, warn};
+
+const BINANCE_WS_BASE: &str = "wss://stream.binance.com:9443";
+const HISTORY_SECS: i64 = 300;
+const MAX_BACKOFF_SECS: u64 = 30;
+/// Name under `websockets` in `/healthz`.
+const WEBSOCKET_NAME: &str = "binance_spot";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpotTick {
+    pub price: f64,
+    pub at: DateTime<Utc>,
+}
+
+/// Parses a combined-stream aggTrade message into (coin, tick).
+pub fn parse_message(text: &str) -> Option<(String, SpotTick)> {
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    let data = value.get("data").unwrap_or(&value);
+    let coin = data["s"].as_str()?.to_lowercase().strip_suffix("usdt")?.to_string();
+    let price = data["p"].as_str()?.parse::<f64>().ok()?;
+    let at = Utc.timestamp_millis_opt(data["T"].as_i64()?).single()?;
+    Some((coin, SpotTick { price, at }))
+}
+
+pub struct SpotFeed {
+    ws_base: String,
+    coins: Vec<String>,
+    history: RwLock<HashMap<String, VecDeque<SpotTick>>>,
+    max_age: Duration,
+    stale_window: Duration,
+    stale_move: f64,
+}
+
+impl SpotFeed {
+    pub fn new(coins: Vec<String>) -> Self {
+        Self {
+            ws_base: BINANCE_WS_BASE.to_string(),
+            coins: coins.into_iter().map(|c| c.trim().to_lowercase()).collect(),
+            history: RwLock::new(HashMap::new()),
+            max_age: Duration::seconds(5),
+            stale_window: Duration::seconds(10),
+            stale_move: 0.003,
+        }
+    }
+
+    /// `SPOT_FEED=binance`; coins from `SPOT_FEED_COINS` (default btc,eth,sol),
+    /// `BINANCE_WS_URL` to override the endpoint, and the staleness check from
+    /// `SPOT_MAX_AGE_SECS`, `SPOT_STALE_WINDOW_SECS` and `SPOT_STALE_MOVE`.
+    pub fn from_env() -> Option<Self> {
+        if !std::env::var("SPOT_FEED")
+            .map(|s| s.trim().eq_ignore_ascii_case("binance"))
+            .unwrap_or(false)
+        {
+            return None;
+        }
+        let coins = std::env::var("SPOT_FEED_COINS")
+            .unwrap_or_else(|_| "btc,eth,sol".to_string())
+            .split(',')
+            .filter(|c| !c.trim().is_empty())
+            .map(|c| c.to_string())
+            .collect();
+        let parse = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|s| s.trim().parse::<f64>().ok())
+        };
+        let mut feed = Self::new(coins);
+        if let Ok(url) = std::env::var("BINANCE_WS_URL") {
+            feed.ws_base = url.trim_end_matches('/').to_string();
+        }
+        if let Some(secs) = parse("SPOT_MAX_AGE_SECS") {
+            feed.max_age = Duration::milliseconds((secs * 1000.0) as i64);
+        }
+        if let Some(secs) = parse("SPOT_STALE_WINDOW_SECS") {
+            feed.stale_window = Duration::milliseconds((secs * 1000.0) as i64);
+        }
+        if let Some(stale_move) = parse("SPOT_STALE_MOVE") {
+            feed.stale_move = stale_move;
+        }
+        Some(feed)
+    }
+
+    pub fn with_staleness(mut self, max_age: Duration, window: Duration, max_move: f64) -> Self {
+        self.max_age = max_age;
+        self.stale_window = window;
+        self.stale_move = max_move;
+        self
+    }
+
+    pub fn coins(&self) -> &[String] {
+        &self.coins
+    }
+
+    fn stream_url(&self) -> String {
+        let streams: Vec<String> = self
+            .coins
+            .iter()
+            .map(|c| format!("{}usdt@aggTrade", c))
+            .collect();
+        format!("{}/stream?streams={}", self.ws_base, streams.join("/"))
+    }
+
+    /// Stores a tick, keeping at most one sample per second and the last
+    /// `HISTORY_SECS` of history.
+    pub fn record(&self, coin: &str, tick: SpotTick) {
+        let Ok(mut history) = self.history.write() else {
+            return;
+        };
+        let ticks = history.entry(coin.to_lowercase()).or_default();
+        match ticks.back_mut() {
+            Some(last) if tick.at < last.at => return,
+            Some(last) if tick.at - last.at < Duration::seconds(1) => *last = tick,
+            _ => ticks.push_back(tick),
+        }
+        while ticks
+            .front()
+            .is_some_and(|t| tick.at - t.at > Duration::seconds(HISTORY_SECS))
+        {
+            ticks.pop_front();
+        }
+    }
+
+    pub fn latest(&self, coin: &str) -> Option<SpotTick> {
+        let history = self.history.read().ok()?;
+        history.get(&coin.to_lowercase())?.back().copied()
+    }
+
+    /// Latest price if it is no older than the max age.
+    pub fn fresh_price(&self, coin: &str, now: DateTime<Utc>) -> Option<f64> {
+        self.latest(coin)
+            .filter(|t| now - t.at <= self.max_age)
+            .map(|t| t.price)
+    }
+
+    /// Last price at or before `at` (the oldest one if history is shorter).
+    fn price_at(&self, coin: &str, at: DateTime<Utc>) -> Option<f64> {
+        let history = self.history.read().ok()?;
+        let ticks = history.get(&coin.to_lowercase())?;
+        ticks
+            .iter()
+            .rev()
+            .find(|t| t.at <= at)
+            .or(ticks.front())
+            .map(|t| t.price)
+    }
+
+    /// Why venue quotes for `coin` should not be trusted right now, if at all:
+    /// the feed is silent, or spot moved more than the threshold within the
+    /// staleness window.
+    pub fn stale_reason(&self, coin: &str, now: DateTime<Utc>) -> Option<String> {
+        let Some(latest) = self.latest(coin) else {
+            return Some(format!("no {} spot price yet", coin));
+        };
+        let age = now - latest.at;
+        if age > self.max_age {
+            return Some(format!(
+                "{} spot feed silent for {:.1}s",
+                coin,
+                age.num_milliseconds() as f64 / 1000.0
+            ));
+        }
+        let before = self.price_at(coin, now - self.stale_window)?;
+        let moved = latest.price / before - 1.0;
+        if moved.abs() > self.stale_move {
+            return Some(format!(
+                "{} spot moved {:+.2}% in {}s",
+                coin,
+                moved * 100.0,
+                self.stale_window.num_seconds()
+            ));
+        }
+        None
+    }
+
+    /// Streams trades into the shared state forever, reconnecting with
+    /// exponential backoff.
+    pub fn spawn(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let url = self.stream_url();
+            let mut backoff = 1;
+            loop {
+                match tokio_tungstenite::connect_async(url.as_str()).await {
+                    Ok((mut stream, _)) => {
+                        info!("📡 Spot feed connected: {}", self.coins.join(", "));
+                        health().set_websocket(WEBSOCKET_NAME, true);
+                        backoff = 1;
+                        while let Some(message) = stream.next().await {
+                            match message {
+                                Ok(Message::Text(text)) => {
+                                    if let Some((coin, tick)) = parse_message(&text) {
+                                        self.record(&coin, tick);
+                                    }
+                                }
+                                Ok(Message::Close(_)) => break,
+                                Ok(_) => {}
+                                Err(e) => {
+                                    warn!("📡 Spot feed error: {}", e);
+                                    break;
+                                }
+                            }
+                        }
+                        health().set_websocket(WEBSOCKET_NAME, false);
+                        warn!("📡 Spot feed disconnected - reconnecting");
+                    }
+                    Err(e) => {
+                        health().set_websocket(WEBSOCKET_NAME, false);
+                        warn!("📡 Spot feed connect failed: {} (retry in {}s)", e, backoff)
+                    }
+                }
+                tokio::time::sleep(std::time::Duration::from_secs(backoff)).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF_SECS);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_binance_agg_trade() {
+        let msg = r#"{"stream":"btcusdt@aggTrade","data":{"e":"aggTrade","E":1735732800123,"s":"BTCUSDT","a":1,"p":"97123.45","q":"0.01","T":1735732800100,"m":false}}"#;
+        let (coin, tick) = parse_message(msg).unwrap();
+        assert_eq!(coin, "btc");
+        assert_eq!(tick.price, 97123.45);
+        assert_eq!(tick.at.timestamp_millis(), 1735732800100);
+        assert!(parse_message(r#"{"result":null,"id":1}"#).is_none());
+    }
+
+    #[test]
+    fn flags_fast_moves_and_silence() {
+        let feed = SpotFeed::new(vec!["btc".to_string()]).with_staleness(
+            Duration::seconds(5),
+            Duration::seconds(10),
+            0.003,
+        );
+        let t0 = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        assert!(feed.stale_reason("btc", t0).is_some());
+
+        for i in 0..20 {
+            feed.record("btc", SpotTick { price: 100_000.0 + i as f64, at: t0 + Duration::seconds(i) });
+        }
+        let now = t0 + Duration::seconds(20);
+        assert_eq!(feed.stale_reason("btc", now), None);
+        assert_eq!(feed.fresh_price("btc", now), Some(100_019.0));
+
+        feed.record("btc", SpotTick { price: 100_600.0, at: now });
+        assert!(feed.stale_reason("btc", now).unwrap().contains("moved"));
+        assert!(feed.stale_reason("btc", now + Duration::seconds(30)).unwrap().contains("silent"));
+    }
+}
@@ -0,0 +1,130 @@
+//! Maximum acceptable bid-ask spread per market.
+//!
+//! The YES spread is `yes_ask - yes_bid`; without a quoted bid, the YES bid
+//! is one minus the NO ask, so the spread is `yes_ask + no_ask - 1`. Markets
+//! wider than their limit are skipped before detection: their edges vanish
+//! as soon as you try to cross.
+//!
+//! `MAX_SPREAD=0.04` (absolute, in dollars) or `MAX_SPREAD=10%` (of the mid);
+//! `MAX_SPREAD_OVERRIDES=KXBTC15M=0.06;eth-updown=5%` sets per-market limits
+//! by event id or slug prefix (longest prefix wins).
+
+use crate::event::{Event, MarketPrices};
+use crate::money::Price;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpreadLimit {
+    Absolute(f64),
+    /// Fraction of the YES mid price.
+    Relative(f64),
+}
+
+impl SpreadLimit {
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        match s.strip_suffix('%') {
+            Some(pct) => pct.trim().parse::<f64>().ok().map(|p| SpreadLimit::Relative(p / 100.0)),
+            None => s.parse::<f64>().ok().map(SpreadLimit::Absolute),
+        }
+    }
+
+    /// False when the YES spread is over the limit.
+    pub fn allows(&self, prices: &MarketPrices) -> bool {
+        let spread = prices.spread("YES");
+        match *self {
+            SpreadLimit::Absolute(max) => spread <= Price::from_f64(max),
+            SpreadLimit::Relative(max) => {
+                // Twice the mid, so the spread's share of it is doubled.
+                let double_mid = prices.yes_ask + prices.yes_ask - spread;
+                double_mid > Price::ZERO && 2.0 * spread.ratio(double_mid) <= max + 1e-9
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SpreadLimits {
+    default: Option<SpreadLimit>,
+    overrides: Vec<(String, SpreadLimit)>,
+}
+
+impl SpreadLimits {
+    pub fn new(default: Option<SpreadLimit>) -> Self {
+        Self {
+            default,
+            overrides: Vec::new(),
+        }
+    }
+
+    pub fn with_override(mut self, prefix: &str, limit: SpreadLimit) -> Self {
+        self.overrides.push((prefix.to_lowercase(), limit));
+        self
+    }
+
+    /// `None` when neither `MAX_SPREAD` nor `MAX_SPREAD_OVERRIDES` is set.
+    pub fn from_env() -> Option<Self> {
+        let default = std::env::var("MAX_SPREAD")
+            .ok()
+            .and_then(|s| SpreadLimit::parse(&s));
+        let mut limits = Self::new(default);
+        for entry in std::env::var("MAX_SPREAD_OVERRIDES")
+            .unwrap_or_default()
+            .split(';')
+            .filter(|e| !e.trim().is_empty())
+        {
+            match entry
+                .split_once('=')
+                .and_then(|(prefix, limit)| Some((prefix.trim(), SpreadLimit::parse(limit)?)))
+            {
+                Some((prefix, limit)) => limits = limits.with_override(prefix, limit),
+                None => tracing::warn!("Ignoring invalid MAX_SPREAD_OVERRIDES entry '{}'", entry.trim()),
+            }
+        }
+        (limits.default.is_some() || !limits.overrides.is_empty()).then_some(limits)
+    }
+
+    pub fn limit_for(&self, event: &Event) -> Option<SpreadLimit> {
+        let id = event.event_id.to_lowercase();
+        let slug = event.slug.as_deref().unwrap_or_default().to_lowercase();
+        self.overrides
+            .iter()
+            .filter(|(prefix, _)| id.starts_with(prefix) || slug.starts_with(prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, limit)| *limit)
+            .or(self.default)
+    }
+
+    pub fn allows(&self, event: &Event, prices: &MarketPrices) -> bool {
+        self.limit_for(event).is_none_or(|limit| limit.allows(prices))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book(yes_ask: f64, no_ask: f64) -> MarketPrices {
+        MarketPrices::new(yes_ask, no_ask, 1000.0)
+    }
+
+    #[test]
+    fn applies_absolute_relative_and_prefix_limits() {
+        assert_eq!(SpreadLimit::parse("10%"), Some(SpreadLimit::Relative(0.10)));
+        // 0.55 / 0.50 asks: YES bid 0.50, spread 0.05, mid 0.525.
+        let wide = book(0.55, 0.50);
+        assert!(!SpreadLimit::Absolute(0.04).allows(&wide));
+        assert!(SpreadLimit::Absolute(0.05).allows(&wide));
+        assert!(SpreadLimit::Relative(0.10).allows(&wide));
+        assert!(!SpreadLimit::Relative(0.05).allows(&wide));
+        // No asks, no verdict.
+        assert!(SpreadLimit::Absolute(0.0).allows(&MarketPrices::new(0.5, 0.5, 1000.0)));
+
+        let limits = SpreadLimits::new(Some(SpreadLimit::Absolute(0.02)))
+            .with_override("KXBTC", SpreadLimit::Absolute(0.04))
+            .with_override("KXBTC15M", SpreadLimit::Absolute(0.06));
+        let kalshi = Event::new("kalshi".into(), "KXBTC15M-25JAN01".into(), String::new(), String::new());
+        let other = Event::new("kalshi".into(), "KXETH15M-25JAN01".into(), String::new(), String::new());
+        assert!(limits.allows(&kalshi, &wide));
+        assert!(!limits.allows(&other, &wide));
+    }
+}
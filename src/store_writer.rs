@@ -0,0 +1,137 @@
+//! Background writer for JSON state files.
+//!
+//! Snapshots are written to `<path>.tmp` and renamed over the store on a
+//! dedicated thread, so a caller holding a lock never waits on the disk.
+//! Snapshots queued while a write is in progress collapse into the newest.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+use tokio::sync::oneshot;
+use tracing::warn;
+
+enum Job {
+    Write(String),
+    Flush(oneshot::Sender<Result<(), String>>),
+}
+
+pub struct StoreWriter {
+    path: PathBuf,
+    jobs: Option<mpsc::Sender<Job>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl StoreWriter {
+    pub fn spawn(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let (jobs, queue) = mpsc::channel();
+        let target = path.clone();
+        let thread = thread::Builder::new()
+            .name("store-writer".to_string())
+            .spawn(move || run(&target, queue))
+            .expect("failed to spawn the store writer thread");
+        Self {
+            path,
+            jobs: Some(jobs),
+            thread: Some(thread),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Queues a snapshot; it replaces the file once written.
+    pub fn write(&self, data: String) {
+        self.send(Job::Write(data));
+    }
+
+    /// Waits until every snapshot queued so far is on disk. Fails while the
+    /// last write attempt failed.
+    pub async fn flush(&self) -> Result<()> {
+        let (done, written) = oneshot::channel();
+        self.send(Job::Flush(done));
+        match written.await {
+            Ok(result) => result.map_err(anyhow::Error::msg),
+            Err(_) => Err(anyhow::anyhow!("Store writer for {} is gone", self.path.display())),
+        }
+    }
+
+    fn send(&self, job: Job) {
+        if let Some(jobs) = &self.jobs {
+            let _ = jobs.send(job);
+        }
+    }
+}
+
+/// Writes out whatever is still queued before returning.
+impl Drop for StoreWriter {
+    fn drop(&mut self) {
+        drop(self.jobs.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn run(path: &Path, queue: mpsc::Receiver<Job>) {
+    let mut failed: Option<String> = None;
+    while let Ok(job) = queue.recv() {
+        let mut latest = None;
+        let mut waiting = Vec::new();
+        for job in std::iter::once(job).chain(queue.try_iter()) {
+            match job {
+                Job::Write(data) => latest = Some(data),
+                Job::Flush(done) => waiting.push(done),
+            }
+        }
+        if let Some(data) = latest {
+            failed = match write_atomic(path, &data) {
+                Ok(()) => None,
+                Err(e) => {
+                    warn!("Failed to persist {}: {:#}", path.display(), e);
+                    Some(format!("{:#}", e))
+                }
+            };
+        }
+        for done in waiting {
+            let _ = done.send(failed.clone().map_or(Ok(()), Err));
+        }
+    }
+}
+
+fn write_atomic(path: &Path, data: &str) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, data).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn flush_waits_for_the_newest_snapshot() {
+        let dir = std::env::temp_dir().join(format!("store-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("positions.json");
+        let writer = StoreWriter::spawn(&path);
+        for n in 0..50 {
+            writer.write(format!("[{}]", n));
+        }
+        writer.flush().await.unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[49]");
+        assert!(!path.with_extension("json.tmp").exists());
+
+        writer.write("[50]".to_string());
+        drop(writer);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[50]");
+        fs::remove_dir_all(&dir).ok();
+    }
+}
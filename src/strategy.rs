@@ -0,0 +1,1797 @@
+//! Pluggable trading strategies.
+//!
+//! A `Strategy` scans a market snapshot for its own opportunity type and
+//! executes them; `StrategyRegistry` erases the opportunity type so the main
+//! loop can run whichever strategies are enabled (`STRATEGIES`). Each runs on
+//! its own task, after every event listing or on its own `StrategyIntervals`
+//! timer against the latest listing. Strategies that follow books (the
+//! cross-platform one, with a `BookFeed`) also re-evaluate the pair a
+//! `BookUpdate` touched as soon as it arrives.
+//! With a `CapitalAllocation` each strategy only trades within its share of
+//! capital, measured by the cost of its open positions. Opportunities are
+//! taken best first (by expected profit, ROI, or ROI per hour of capital
+//! lockup, see `OpportunityRanking`), and `TradeLimits` cap the trades
+//! per scan and the trades open at once. The opportunities
+//! found by each strategy's latest scan are kept for the dashboard. Runtime
+//! `Controls` can pause strategies, override the trade size and raise the
+//! minimum profit; a paused platform or a hit daily loss limit stops new
+//! entries.
+
+use crate::arbitrage_detector::ArbitrageOpportunity;
+use crate::basket_detector::{BasketDetector, BasketOpportunity};
+use crate::book_feed::{BookFeed, BookUpdate};
+use crate::bot::ShortTermArbitrageBot;
+use crate::control::Controls;
+use crate::event::{Event, MarketPrices};
+use crate::exchange::{ExchangeClient, TimeInForce};
+use crate::gabagool_detector::GabagoolOpportunity;
+use crate::gabagool_executor::GabagoolExecutor;
+use crate::journal::{Journal, JournalEntry};
+use crate::money::{Price, UsdAmount};
+use crate::oracle::OracleCheck;
+use crate::metrics::metrics;
+use crate::position_tracker::{Position, PositionTracker};
+use crate::quote_detector::{QuoteDetector, TwoSidedQuote};
+use crate::skip_reason::{SkipCounts, SkipReason};
+use crate::spot_feed::SpotFeed;
+use crate::strike_detector::{group_ladders, StrikeDetector, StrikeOpportunity};
+use crate::trade_executor::TradeExecutor;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::{watch, Mutex};
+use tokio::task::JoinSet;
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, info_span, instrument, warn, Instrument};
+
+pub const CROSS_PLATFORM: &str = "cross_platform";
+pub const GABAGOOL: &str = "gabagool";
+pub const STRIKE: &str = "strike";
+pub const NEG_RISK: &str = "neg_risk";
+pub const QUOTING: &str = "quoting";
+
+/// Events fetched once per tick and shared by every strategy.
+#[derive(Debug, Clone, Default)]
+pub struct MarketSnapshot {
+    pub pm_events: Vec<Event>,
+    pub kalshi_events: Vec<Event>,
+}
+
+#[async_trait]
+pub trait Strategy: Send + Sync {
+    type Opportunity: Send + Sync;
+
+    fn name(&self) -> &'static str;
+
+    async fn scan(&self, snapshot: &MarketSnapshot) -> Vec<Self::Opportunity>;
+
+    /// Whether `scan_update` reacts to book updates.
+    fn follows_books(&self) -> bool {
+        false
+    }
+
+    /// Re-evaluates only what a book update touched.
+    async fn scan_update(&self, _update: &BookUpdate) -> Vec<Self::Opportunity> {
+        Vec::new()
+    }
+
+    /// Markets the latest scan passed on before finding its opportunities.
+    fn take_skips(&self) -> SkipCounts {
+        SkipCounts::default()
+    }
+
+    /// Platforms `execute` opens positions on; nothing is executed while
+    /// one of them is paused.
+    fn platforms(&self) -> &'static [&'static str];
+
+    /// Configured dollars per trade; `Controls` can override it at runtime.
+    fn trade_amount(&self) -> f64;
+
+    /// Dollars the opportunity would commit if executed at `trade_amount`.
+    fn capital_required(&self, opportunity: &Self::Opportunity, trade_amount: f64) -> f64;
+
+    /// Returns whether the opportunity was traded.
+    async fn execute(&self, opportunity: &Self::Opportunity, trade_amount: f64) -> Result<bool>;
+
+    /// One-line strategy-specific summary for the periodic stats log.
+    async fn statistics(&self) -> Option<String> {
+        None
+    }
+
+    /// Market title, expected net profit and ROI of an opportunity.
+    fn describe(&self, opportunity: &Self::Opportunity) -> (String, f64, f64);
+
+    /// ROI (percent) per hour until the opportunity's capital is free again,
+    /// when its resolution time is known.
+    fn roi_per_hour(&self, _opportunity: &Self::Opportunity) -> Option<f64> {
+        None
+    }
+
+    /// Confidence (0 to 1) in the opportunity, when the strategy scores it.
+    fn confidence(&self, _opportunity: &Self::Opportunity) -> Option<f64> {
+        None
+    }
+}
+
+/// An opportunity found by a strategy's latest scan.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OpportunitySummary {
+    pub strategy: &'static str,
+    pub title: String,
+    pub net_profit: f64,
+    pub roi_percent: f64,
+    pub roi_per_hour: Option<f64>,
+    pub confidence: Option<f64>,
+    pub capital: f64,
+    pub found_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StrategyStatistics {
+    pub name: &'static str,
+    pub scans: u64,
+    pub opportunities: u64,
+    pub executed: u64,
+    pub failed: u64,
+    /// Opportunities skipped because they would exceed the capital budget.
+    pub over_budget: u64,
+    /// Opportunities skipped by the `TradeLimits` caps.
+    pub capped: u64,
+    pub capital_budget: Option<f64>,
+    pub capital_in_use: f64,
+    pub open_positions: usize,
+    pub realized_pnl: f64,
+    pub details: Option<String>,
+    /// Markets and opportunities passed on, by reason, since startup.
+    pub skips: SkipCounts,
+    /// The same for the latest full scan.
+    pub last_scan_skips: SkipCounts,
+}
+
+impl StrategyStatistics {
+    pub fn log(&self) {
+        let budget = self
+            .capital_budget
+            .map(|b| format!(" / ${:.2}", b))
+            .unwrap_or_default();
+        info!(
+            "📊 {} Stats - Opportunities: {}, Executed: {}, Failed: {}, Over Budget: {}, Capped: {}, Capital: ${:.2}{}, Open: {}, PnL: ${:.2}{}",
+            self.name,
+            self.opportunities,
+            self.executed,
+            self.failed,
+            self.over_budget,
+            self.capped,
+            self.capital_in_use,
+            budget,
+            self.open_positions,
+            self.realized_pnl,
+            self.details.as_ref().map(|d| format!(", {}", d)).unwrap_or_default()
+        );
+        if !self.skips.is_empty() {
+            info!(
+                "🚫 {} Skips - Last scan: [{}], Total: [{}]",
+                self.name, self.last_scan_skips, self.skips
+            );
+        }
+    }
+}
+
+/// Shortest interval a strategy may scan on.
+const MIN_STRATEGY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Strategies that scan on their own timer (e.g. sub-second, when prices come
+/// from a fast source) instead of once per event listing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StrategyIntervals {
+    intervals: HashMap<String, Duration>,
+}
+
+impl StrategyIntervals {
+    pub fn with_interval(mut self, strategy: &str, interval: Duration) -> Self {
+        self.intervals
+            .insert(strategy.to_string(), interval.max(MIN_STRATEGY_INTERVAL));
+        self
+    }
+
+    pub fn get(&self, strategy: &str) -> Option<Duration> {
+        self.intervals.get(strategy).copied()
+    }
+
+    /// `STRATEGY_INTERVALS` (`cross_platform=0.5,gabagool=5`, seconds,
+    /// fractions allowed). Strategies left out run after every listing.
+    pub fn from_env() -> Self {
+        let mut intervals = Self::default();
+        let raw = std::env::var("STRATEGY_INTERVALS").unwrap_or_default();
+        for entry in raw.split(',').filter(|s| !s.trim().is_empty()) {
+            match entry.split_once('=').and_then(|(name, secs)| {
+                let secs = secs.trim().parse::<f64>().ok().filter(|s| s.is_finite() && *s > 0.0)?;
+                Some((name.trim().to_lowercase(), Duration::from_secs_f64(secs)))
+            }) {
+                Some((name, interval)) => intervals = intervals.with_interval(&name, interval),
+                None => warn!("Ignoring invalid STRATEGY_INTERVALS entry '{}'", entry.trim()),
+            }
+        }
+        intervals
+    }
+}
+
+/// Time in force of each strategy's entry orders.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrderTimeInForce {
+    default: TimeInForce,
+    strategies: HashMap<String, TimeInForce>,
+}
+
+impl OrderTimeInForce {
+    pub fn with_default(mut self, tif: TimeInForce) -> Self {
+        self.default = tif;
+        self
+    }
+
+    pub fn with_strategy(mut self, strategy: &str, tif: TimeInForce) -> Self {
+        self.strategies.insert(strategy.to_string(), tif);
+        self
+    }
+
+    pub fn get(&self, strategy: &str) -> TimeInForce {
+        self.strategies.get(strategy).copied().unwrap_or(self.default)
+    }
+
+    /// `ORDER_TIME_IN_FORCE` (`fok,neg_risk=gtc:30`): `ioc` (the default),
+    /// `fok`, `gtc` or `gtc:<seconds>` to expire, per strategy as `name=tif`;
+    /// an entry without a name sets it for the rest.
+    pub fn from_env() -> Self {
+        let mut tif = Self::default();
+        let raw = std::env::var("ORDER_TIME_IN_FORCE").unwrap_or_default();
+        for entry in raw.split(',').filter(|s| !s.trim().is_empty()) {
+            let (name, value) = match entry.split_once('=') {
+                Some((name, value)) => (Some(name.trim().to_lowercase()), value),
+                None => (None, entry),
+            };
+            match (name, TimeInForce::parse(value)) {
+                (Some(name), Some(value)) => tif = tif.with_strategy(&name, value),
+                (None, Some(value)) => tif = tif.with_default(value),
+                (_, None) => warn!("Ignoring invalid ORDER_TIME_IN_FORCE entry '{}'", entry.trim()),
+            }
+        }
+        tif
+    }
+}
+
+/// Share of a total capital amount given to each strategy.
+#[derive(Debug, Clone, Default)]
+pub struct CapitalAllocation {
+    total: f64,
+    percents: HashMap<String, f64>,
+}
+
+impl CapitalAllocation {
+    pub fn new(total: f64) -> Self {
+        Self {
+            total,
+            percents: HashMap::new(),
+        }
+    }
+
+    pub fn with_share(mut self, strategy: &str, percent: f64) -> Self {
+        self.percents.insert(strategy.to_string(), percent);
+        self
+    }
+
+    /// Reads `STRATEGY_CAPITAL` (total dollars) and `STRATEGY_ALLOCATION`
+    /// (`cross_platform=60,gabagool=40`, percent of the total). Strategies
+    /// left out of the allocation get no budget cap.
+    pub fn from_env() -> Option<Self> {
+        let total = std::env::var("STRATEGY_CAPITAL")
+            .ok()
+            .and_then(|s| s.trim().parse::<f64>().ok())?;
+        let mut allocation = Self::new(total);
+        let raw = std::env::var("STRATEGY_ALLOCATION").unwrap_or_default();
+        for entry in raw.split(',').filter(|s| !s.trim().is_empty()) {
+            match entry.split_once('=').and_then(|(name, pct)| {
+                pct.trim().parse::<f64>().ok().map(|pct| (name.trim().to_lowercase(), pct))
+            }) {
+                Some((name, pct)) => allocation = allocation.with_share(&name, pct),
+                None => warn!("Ignoring invalid STRATEGY_ALLOCATION entry '{}'", entry.trim()),
+            }
+        }
+        let allocated: f64 = allocation.percents.values().sum();
+        if allocated > 100.0 {
+            warn!("⚠️ STRATEGY_ALLOCATION sums to {:.0}% of STRATEGY_CAPITAL", allocated);
+        }
+        Some(allocation)
+    }
+
+    pub fn budget_for(&self, strategy: &str) -> Option<f64> {
+        self.percents.get(strategy).map(|pct| self.total * pct / 100.0)
+    }
+}
+
+/// Caps shared by every strategy on how many trades are taken per scan and
+/// how many may be open at once (a hedged pair is one trade).
+#[derive(Debug, Default)]
+pub struct TradeLimits {
+    max_open: Option<usize>,
+    max_per_scan: Option<usize>,
+    /// Trades reserved by a scan and not in the tracker yet.
+    in_flight: Mutex<usize>,
+}
+
+impl TradeLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_open(mut self, max: usize) -> Self {
+        self.max_open = Some(max);
+        self
+    }
+
+    pub fn with_max_per_scan(mut self, max: usize) -> Self {
+        self.max_per_scan = Some(max);
+        self
+    }
+
+    /// `MAX_OPEN_POSITIONS` and `MAX_TRADES_PER_SCAN`; unset means no cap.
+    pub fn from_env() -> Self {
+        let parse = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|s| s.trim().parse::<usize>().ok())
+        };
+        let mut limits = Self::new();
+        if let Some(max) = parse("MAX_OPEN_POSITIONS") {
+            limits = limits.with_max_open(max);
+        }
+        if let Some(max) = parse("MAX_TRADES_PER_SCAN") {
+            limits = limits.with_max_per_scan(max);
+        }
+        limits
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.max_open.is_none() && self.max_per_scan.is_none()
+    }
+
+    pub fn describe(&self) -> String {
+        let mut caps = Vec::new();
+        if let Some(max) = self.max_open {
+            caps.push(format!("{} open", max));
+        }
+        if let Some(max) = self.max_per_scan {
+            caps.push(format!("{} per scan", max));
+        }
+        caps.join(", ")
+    }
+}
+
+/// What opportunities are ranked by when a cap or budget leaves room for
+/// only some of them.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum RankBy {
+    /// Expected net profit.
+    #[default]
+    Profit,
+    Roi,
+    /// ROI per hour of capital lockup; opportunities without a known
+    /// resolution time go last.
+    RoiPerHour,
+}
+
+impl RankBy {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "profit" => Some(Self::Profit),
+            "roi" => Some(Self::Roi),
+            "roi_per_hour" => Some(Self::RoiPerHour),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OpportunityRanking {
+    pub by: RankBy,
+    /// Skips opportunities scoring under this ROI (percent) per hour; those
+    /// without a known resolution time aren't scored and pass.
+    pub min_roi_per_hour: Option<f64>,
+    /// Skips opportunities under this confidence (see
+    /// [`crate::confidence`]); unscored ones pass.
+    pub min_confidence: Option<f64>,
+}
+
+impl OpportunityRanking {
+    /// `RANK_OPPORTUNITIES_BY` (`profit`, `roi` or `roi_per_hour`),
+    /// `MIN_ROI_PER_HOUR` and `MIN_CONFIDENCE`.
+    pub fn from_env() -> Self {
+        let raw = std::env::var("RANK_OPPORTUNITIES_BY").unwrap_or_default();
+        let by = if raw.trim().is_empty() {
+            RankBy::default()
+        } else {
+            RankBy::parse(&raw).unwrap_or_else(|| {
+                warn!("Invalid RANK_OPPORTUNITIES_BY '{}' - ranking by profit", raw);
+                RankBy::default()
+            })
+        };
+        let parse = |name: &str| std::env::var(name).ok().and_then(|s| s.trim().parse::<f64>().ok());
+        Self {
+            by,
+            min_roi_per_hour: parse("MIN_ROI_PER_HOUR"),
+            min_confidence: parse("MIN_CONFIDENCE"),
+        }
+    }
+}
+
+/// Object-safe wrapper around a `Strategy`, counting its outcomes.
+#[async_trait]
+pub trait StrategyRunner: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Scans and executes everything found; returns the opportunity count.
+    async fn run_tick(&self, snapshot: &MarketSnapshot) -> usize;
+
+    fn follows_books(&self) -> bool;
+
+    /// Like `run_tick`, for what one book update touched.
+    async fn run_update(&self, update: &BookUpdate) -> usize;
+
+    async fn statistics(&self) -> StrategyStatistics;
+
+    /// Opportunities found by the latest scan.
+    async fn latest_opportunities(&self) -> Vec<OpportunitySummary>;
+}
+
+struct Registered<S: Strategy> {
+    strategy: S,
+    budget: Option<f64>,
+    tracker: Option<Arc<Mutex<PositionTracker>>>,
+    stats: Mutex<StrategyStatistics>,
+    latest: Mutex<Vec<OpportunitySummary>>,
+    controls: Option<Arc<Controls>>,
+    limits: Arc<TradeLimits>,
+    ranking: OpportunityRanking,
+    journal: Option<Arc<Journal>>,
+    /// Held while scanning and executing, so a timed scan and a book update
+    /// never trade the same opportunity twice.
+    running: Mutex<()>,
+}
+
+impl<S: Strategy> Registered<S> {
+    fn paused(&self) -> bool {
+        self.controls.as_ref().is_some_and(|c| c.is_paused(self.strategy.name()))
+    }
+
+    /// A platform the strategy trades on that is paused, if any.
+    fn paused_platform(&self) -> Option<&'static str> {
+        let controls = self.controls.as_ref()?;
+        self.strategy
+            .platforms()
+            .iter()
+            .copied()
+            .find(|platform| controls.is_platform_paused(platform))
+    }
+
+    /// Filters, records and executes what a scan found; returns how many it
+    /// found. A full scan replaces the latest opportunities, an update's scan
+    /// only refreshes the ones it found.
+    async fn process(&self, mut opportunities: Vec<S::Opportunity>, full_scan: bool) -> usize {
+        let name = self.strategy.name();
+        let mut skips = self.strategy.take_skips();
+        if let Some(journal) = &self.journal {
+            let trade_amount = self.trade_amount();
+            for opp in &opportunities {
+                let (title, net_profit, roi_percent) = self.strategy.describe(opp);
+                journal.record(JournalEntry::OpportunityDetected {
+                    strategy: name.to_string(),
+                    title,
+                    net_profit,
+                    roi_percent,
+                    capital: self.strategy.capital_required(opp, trade_amount),
+                });
+            }
+        }
+        if let Some(min_profit) = self.controls.as_ref().and_then(|c| c.min_profit()) {
+            self.skip_where(&mut opportunities, &mut skips, SkipReason::BelowMinProfit, |opp| {
+                let profit = self.strategy.describe(opp).1;
+                (profit < min_profit).then(|| format!("profit ${:.4} under the ${:.4} minimum", profit, min_profit))
+            });
+        }
+        if let Some(min) = self.ranking.min_roi_per_hour {
+            self.skip_where(&mut opportunities, &mut skips, SkipReason::LowRoiPerHour, |opp| {
+                let roi = self.strategy.roi_per_hour(opp).filter(|r| *r < min)?;
+                Some(format!("{:.2}%/h ROI under the {:.2}%/h minimum", roi, min))
+            });
+        }
+        if let Some(min) = self.ranking.min_confidence {
+            self.skip_where(&mut opportunities, &mut skips, SkipReason::LowConfidence, |opp| {
+                let confidence = self.strategy.confidence(opp).filter(|c| *c < min)?;
+                Some(format!("confidence {:.2} under the {:.2} minimum", confidence, min))
+            });
+        }
+        let mut ranked: Vec<(f64, S::Opportunity)> = opportunities
+            .into_iter()
+            .map(|opp| (self.rank_key(&opp), opp))
+            .collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let opportunities: Vec<S::Opportunity> = ranked.into_iter().map(|(_, opp)| opp).collect();
+        let found = opportunities.len();
+        if found > 0 {
+            info!("🔎 {}: Found {} opportunities", name, found);
+        }
+        let trade_amount = self.trade_amount();
+        let found_at = Utc::now();
+        let summaries: Vec<OpportunitySummary> = opportunities
+            .iter()
+            .map(|opp| {
+                let (title, net_profit, roi_percent) = self.strategy.describe(opp);
+                OpportunitySummary {
+                    strategy: name,
+                    title,
+                    net_profit,
+                    roi_percent,
+                    roi_per_hour: self.strategy.roi_per_hour(opp),
+                    confidence: self.strategy.confidence(opp),
+                    capital: self.strategy.capital_required(opp, trade_amount),
+                    found_at,
+                }
+            })
+            .collect();
+        {
+            let mut latest = self.latest.lock().await;
+            if full_scan {
+                *latest = summaries;
+            } else {
+                latest.retain(|old| !summaries.iter().any(|new| new.title == old.title));
+                latest.extend(summaries);
+            }
+        }
+        let (mut opportunities, over_budget) = self.within_budget(opportunities, trade_amount, &mut skips).await;
+        if let Some(platform) = self.paused_platform() {
+            if !opportunities.is_empty() {
+                info!("⏸️ {}: not opening positions while {} is paused", name, platform);
+            }
+            self.skip_where(&mut opportunities, &mut skips, SkipReason::Paused, |_| {
+                Some(format!("{} is paused", platform))
+            });
+        }
+        if self.controls.as_ref().is_some_and(|c| c.loss_limited(found_at.date_naive())) {
+            if !opportunities.is_empty() {
+                info!("🧊 {}: daily loss limit hit, no new positions until 00:00 UTC", name);
+            }
+            self.skip_where(&mut opportunities, &mut skips, SkipReason::LossLimit, |_| {
+                Some("daily loss limit hit".to_string())
+            });
+        }
+        let capped = self.within_limits(&mut opportunities, &mut skips).await;
+
+        let results = futures::future::join_all(
+            opportunities.iter().map(|opp| self.strategy.execute(opp, trade_amount)),
+        )
+        .await;
+        *self.limits.in_flight.lock().await -= opportunities.len();
+
+        if let Some(journal) = &self.journal {
+            for (opp, result) in opportunities.iter().zip(&results) {
+                journal.record(JournalEntry::OpportunityExecuted {
+                    strategy: name.to_string(),
+                    title: self.strategy.describe(opp).0,
+                    traded: matches!(result, Ok(true)),
+                    error: result.as_ref().err().map(|e| e.to_string()),
+                });
+            }
+        }
+
+        let mut stats = self.stats.lock().await;
+        stats.opportunities += found as u64;
+        stats.over_budget += over_budget;
+        stats.capped += capped;
+        for (reason, count) in skips.iter() {
+            metrics().add_counter(
+                "opportunities_skipped_total",
+                &[("strategy", name), ("reason", reason.as_str())],
+                count as f64,
+            );
+        }
+        stats.skips.merge(&skips);
+        if full_scan {
+            stats.last_scan_skips = skips;
+        }
+        for result in results {
+            match result {
+                Ok(true) => stats.executed += 1,
+                Ok(false) => stats.failed += 1,
+                Err(e) => {
+                    error!("Error executing {} trade: {}", name, e);
+                    stats.failed += 1;
+                }
+            }
+        }
+        found
+    }
+
+    /// Counts `opportunity` as skipped for `reason` and journals it.
+    fn skip(&self, skips: &mut SkipCounts, opportunity: &S::Opportunity, reason: SkipReason, detail: String) {
+        skips.add(reason);
+        if let Some(journal) = &self.journal {
+            journal.record(JournalEntry::OpportunitySkipped {
+                strategy: self.strategy.name().to_string(),
+                title: self.strategy.describe(opportunity).0,
+                reason,
+                detail,
+            });
+        }
+    }
+
+    /// Drops the opportunities `skip` gives details for, skipped for `reason`.
+    fn skip_where(
+        &self,
+        opportunities: &mut Vec<S::Opportunity>,
+        skips: &mut SkipCounts,
+        reason: SkipReason,
+        skip: impl Fn(&S::Opportunity) -> Option<String>,
+    ) {
+        opportunities.retain(|opp| match skip(opp) {
+            Some(detail) => {
+                self.skip(skips, opp, reason, detail);
+                false
+            }
+            None => true,
+        });
+    }
+
+    async fn capital_in_use(&self) -> f64 {
+        match &self.tracker {
+            Some(tracker) => tracker.lock().await.capital_in_use(self.strategy.name()),
+            None => 0.0,
+        }
+    }
+
+    fn trade_amount(&self) -> f64 {
+        self.controls
+            .as_ref()
+            .and_then(|c| c.trade_amount())
+            .unwrap_or_else(|| self.strategy.trade_amount())
+    }
+
+    fn rank_key(&self, opportunity: &S::Opportunity) -> f64 {
+        let (_, net_profit, roi_percent) = self.strategy.describe(opportunity);
+        match self.ranking.by {
+            RankBy::Profit => net_profit,
+            RankBy::Roi => roi_percent,
+            RankBy::RoiPerHour => self.strategy.roi_per_hour(opportunity).unwrap_or(f64::NEG_INFINITY),
+        }
+    }
+
+    /// Keeps the best opportunities the per-scan and open trade caps leave
+    /// room for and reserves their slots until they've executed; returns
+    /// how many were dropped.
+    async fn within_limits(&self, opportunities: &mut Vec<S::Opportunity>, skips: &mut SkipCounts) -> u64 {
+        let mut in_flight = self.limits.in_flight.lock().await;
+        let mut allowed = self.limits.max_per_scan.unwrap_or(usize::MAX);
+        if let Some(max_open) = self.limits.max_open {
+            let open = match &self.tracker {
+                Some(tracker) => tracker.lock().await.open_trades(),
+                None => 0,
+            };
+            allowed = allowed.min(max_open.saturating_sub(open + *in_flight));
+        }
+        let capped = opportunities.len().saturating_sub(allowed);
+        for opp in opportunities.iter().skip(allowed) {
+            self.skip(skips, opp, SkipReason::TradeCap, "trade caps reached".to_string());
+        }
+        if capped > 0 {
+            info!(
+                "🚦 {}: trade caps leave room for {} of {} opportunities, taking the best",
+                self.strategy.name(),
+                allowed,
+                opportunities.len()
+            );
+        }
+        opportunities.truncate(allowed);
+        *in_flight += opportunities.len();
+        capped as u64
+    }
+
+    /// Keeps opportunities in ranked order until the budget is used up.
+    async fn within_budget(
+        &self,
+        opportunities: Vec<S::Opportunity>,
+        trade_amount: f64,
+        skips: &mut SkipCounts,
+    ) -> (Vec<S::Opportunity>, u64) {
+        let Some(budget) = self.budget else {
+            return (opportunities, 0);
+        };
+        let mut remaining = budget - self.capital_in_use().await;
+        let mut accepted = Vec::new();
+        let mut skipped = 0;
+        for opp in opportunities {
+            let required = self.strategy.capital_required(&opp, trade_amount);
+            if required <= remaining {
+                remaining -= required;
+                accepted.push(opp);
+            } else {
+                let detail = format!("needs ${:.2}, ${:.2} of its budget left", required, remaining);
+                self.skip(skips, &opp, SkipReason::OverBudget, detail);
+                skipped += 1;
+            }
+        }
+        if skipped > 0 {
+            warn!(
+                "💼 {}: skipped {} opportunities over its ${:.2} budget (${:.2} left)",
+                self.strategy.name(),
+                skipped,
+                budget,
+                remaining
+            );
+        }
+        (accepted, skipped)
+    }
+}
+
+#[async_trait]
+impl<S: Strategy> StrategyRunner for Registered<S> {
+    fn name(&self) -> &'static str {
+        self.strategy.name()
+    }
+
+    async fn run_tick(&self, snapshot: &MarketSnapshot) -> usize {
+        if self.paused() {
+            self.latest.lock().await.clear();
+            return 0;
+        }
+        let _running = self.running.lock().await;
+        let opportunities = self.strategy.scan(snapshot).await;
+        let found = self.process(opportunities, true).await;
+        self.stats.lock().await.scans += 1;
+        found
+    }
+
+    fn follows_books(&self) -> bool {
+        self.strategy.follows_books()
+    }
+
+    async fn run_update(&self, update: &BookUpdate) -> usize {
+        if self.paused() {
+            return 0;
+        }
+        let _running = self.running.lock().await;
+        let opportunities = self.strategy.scan_update(update).await;
+        self.process(opportunities, false).await
+    }
+
+    async fn statistics(&self) -> StrategyStatistics {
+        let mut stats = self.stats.lock().await.clone();
+        if let Some(tracker) = &self.tracker {
+            let tracker = tracker.lock().await;
+            let positions = tracker.get_statistics_for_strategy(self.strategy.name());
+            stats.capital_in_use = tracker.capital_in_use(self.strategy.name());
+            stats.open_positions = positions.open_positions;
+            stats.realized_pnl = positions.total_profit;
+        }
+        stats.details = self.strategy.statistics().await;
+        stats
+    }
+
+    async fn latest_opportunities(&self) -> Vec<OpportunitySummary> {
+        self.latest.lock().await.clone()
+    }
+}
+
+#[derive(Default)]
+pub struct StrategyRegistry {
+    runners: Vec<Arc<dyn StrategyRunner>>,
+    intervals: StrategyIntervals,
+    allocation: Option<CapitalAllocation>,
+    position_tracker: Option<Arc<Mutex<PositionTracker>>>,
+    controls: Option<Arc<Controls>>,
+    limits: Arc<TradeLimits>,
+    ranking: OpportunityRanking,
+    journal: Option<Arc<Journal>>,
+    /// Tasks started by `spawn` and `spawn_updates`, drained by `shutdown`.
+    tasks: std::sync::Mutex<JoinSet<()>>,
+    cancel: CancellationToken,
+}
+
+impl StrategyRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Per-strategy accounting comes from the tracker's positions.
+    pub fn with_position_tracker(mut self, tracker: Arc<Mutex<PositionTracker>>) -> Self {
+        self.position_tracker = Some(tracker);
+        self
+    }
+
+    /// Runtime controls applied to each strategy registered afterwards.
+    pub fn with_controls(mut self, controls: Arc<Controls>) -> Self {
+        self.controls = Some(controls);
+        self
+    }
+
+    /// Own timers for some strategies; see `spawn`.
+    pub fn with_intervals(mut self, intervals: StrategyIntervals) -> Self {
+        self.intervals = intervals;
+        self
+    }
+
+    /// Trade caps shared by the strategies registered afterwards.
+    pub fn with_limits(mut self, limits: TradeLimits) -> Self {
+        self.limits = Arc::new(limits);
+        self
+    }
+
+    /// How strategies registered afterwards order and filter what they find.
+    pub fn with_ranking(mut self, ranking: OpportunityRanking) -> Self {
+        self.ranking = ranking;
+        self
+    }
+
+    /// Journals what strategies registered afterwards find, skip and trade.
+    pub fn with_journal(mut self, journal: Arc<Journal>) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Caps each strategy registered afterwards at its share of capital.
+    pub fn with_allocation(mut self, allocation: CapitalAllocation) -> Self {
+        self.allocation = Some(allocation);
+        self
+    }
+
+    pub fn register<S: Strategy + 'static>(&mut self, strategy: S) {
+        let name = strategy.name();
+        let budget = self.allocation.as_ref().and_then(|a| a.budget_for(name));
+        if let Some(budget) = budget {
+            info!("💼 {} capital budget: ${:.2}", name, budget);
+        }
+        self.runners.push(Arc::new(Registered {
+            strategy,
+            budget,
+            tracker: self.position_tracker.clone(),
+            stats: Mutex::new(StrategyStatistics {
+                name,
+                capital_budget: budget,
+                ..Default::default()
+            }),
+            latest: Mutex::new(Vec::new()),
+            controls: self.controls.clone(),
+            limits: self.limits.clone(),
+            ranking: self.ranking,
+            journal: self.journal.clone(),
+            running: Mutex::new(()),
+        }));
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.runners.iter().map(|r| r.name()).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runners.is_empty()
+    }
+
+    /// Runs every registered strategy concurrently; returns the total number
+    /// of opportunities found.
+    #[instrument(
+        name = "scan",
+        skip_all,
+        fields(pm_events = snapshot.pm_events.len(), kalshi_events = snapshot.kalshi_events.len())
+    )]
+    pub async fn run_tick(&self, snapshot: &MarketSnapshot) -> usize {
+        futures::future::join_all(
+            self.runners
+                .iter()
+                .map(|r| r.run_tick(snapshot).instrument(info_span!("strategy", name = r.name()))),
+        )
+            .await
+            .into_iter()
+            .sum()
+    }
+
+    /// Runs each strategy on its own task: after every snapshot sent on
+    /// `snapshots`, or on its `StrategyIntervals` timer against the latest
+    /// one. `None` (no new entries) idles them until the next snapshot.
+    pub fn spawn(&self, snapshots: watch::Receiver<Option<Arc<MarketSnapshot>>>) {
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        for runner in &self.runners {
+            let runner = runner.clone();
+            let cancel = self.cancel.clone();
+            let mut snapshots = snapshots.clone();
+            let mut ticker = self.intervals.get(runner.name()).map(|every| {
+                info!("⏱️ {} scans every {:?}", runner.name(), every);
+                let mut ticker = tokio::time::interval(every);
+                ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                ticker
+            });
+            tasks.spawn(async move {
+                loop {
+                    // Only the wait is cancelled: a tick that started runs
+                    // to the end, orders and all.
+                    tokio::select! {
+                        _ = cancel.cancelled() => return,
+                        next = async {
+                            match ticker.as_mut() {
+                                Some(ticker) => {
+                                    ticker.tick().await;
+                                    true
+                                }
+                                None => snapshots.changed().await.is_ok(),
+                            }
+                        } => {
+                            if !next {
+                                return;
+                            }
+                        }
+                    }
+                    let Some(snapshot) = snapshots.borrow_and_update().clone() else {
+                        continue;
+                    };
+                    let span = info_span!("strategy", name = runner.name());
+                    if runner.run_tick(&snapshot).instrument(span).await > 0 {
+                        runner.statistics().await.log();
+                    }
+                }
+            });
+        }
+    }
+
+    /// Runs each book-following strategy on every update from `updates`,
+    /// on its own task. Updates arriving while one is evaluated are coalesced.
+    pub fn spawn_updates(&self, updates: &broadcast::Sender<BookUpdate>) {
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        for runner in self.runners.iter().filter(|runner| runner.follows_books()) {
+            let runner = runner.clone();
+            let cancel = self.cancel.clone();
+            let mut updates = updates.subscribe();
+            tasks.spawn(async move {
+                loop {
+                    let first = tokio::select! {
+                        _ = cancel.cancelled() => return,
+                        update = updates.recv() => match update {
+                            Ok(update) => update,
+                            Err(RecvError::Lagged(skipped)) => {
+                                debug!("{} skipped {} book updates", runner.name(), skipped);
+                                continue;
+                            }
+                            Err(RecvError::Closed) => return,
+                        },
+                    };
+                    let mut pending = vec![first];
+                    while let Ok(update) = updates.try_recv() {
+                        if !pending.contains(&update) {
+                            pending.push(update);
+                        }
+                    }
+                    for update in pending {
+                        let span = info_span!(
+                            "book_update",
+                            strategy = runner.name(),
+                            platform = update.platform,
+                            event_id = %update.event_id
+                        );
+                        if runner.run_update(&update).instrument(span).await > 0 {
+                            runner.statistics().await.log();
+                        }
+                    }
+                }
+            });
+        }
+    }
+
+    /// Stops the strategy tasks once the ticks they are running (and the
+    /// orders those place) finish, waiting at most `grace`. Returns how many
+    /// were still running when it ran out; they are aborted.
+    pub async fn shutdown(&self, grace: Duration) -> usize {
+        self.cancel.cancel();
+        let mut tasks = std::mem::take(&mut *self.tasks.lock().unwrap_or_else(|e| e.into_inner()));
+        let drained = tokio::time::timeout(grace, async {
+            while let Some(result) = tasks.join_next().await {
+                if let Err(e) = result {
+                    error!("Strategy task failed: {}", e);
+                }
+            }
+        })
+        .await;
+        if drained.is_ok() {
+            return 0;
+        }
+        let left = tasks.len();
+        tasks.abort_all();
+        left
+    }
+
+    pub async fn statistics(&self) -> Vec<StrategyStatistics> {
+        let mut all = Vec::with_capacity(self.runners.len());
+        for runner in &self.runners {
+            all.push(runner.statistics().await);
+        }
+        all
+    }
+
+    /// Opportunities from every strategy's latest scan.
+    pub async fn latest_opportunities(&self) -> Vec<OpportunitySummary> {
+        let mut all = Vec::new();
+        for runner in &self.runners {
+            all.extend(runner.latest_opportunities().await);
+        }
+        all
+    }
+}
+
+/// Strategy names enabled by `STRATEGIES` (comma-separated), defaulting to
+/// every built-in strategy.
+pub fn enabled_strategies_from_env() -> Vec<String> {
+    let raw = std::env::var("STRATEGIES").unwrap_or_default();
+    let names: Vec<String> = raw
+        .split(',')
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if names.is_empty() {
+        vec![CROSS_PLATFORM.to_string(), GABAGOOL.to_string()]
+    } else {
+        names
+    }
+}
+
+/// Strategy 1: buy complementary outcomes on Polymarket and Kalshi.
+pub struct CrossPlatformStrategy {
+    bot: Arc<ShortTermArbitrageBot>,
+    executor: Arc<TradeExecutor>,
+    polymarket_client: Arc<dyn ExchangeClient>,
+    kalshi_client: Arc<dyn ExchangeClient>,
+    trade_amount: f64,
+    spot_feed: Option<Arc<SpotFeed>>,
+    oracle: Option<Arc<OracleCheck>>,
+    book_feed: Option<Arc<BookFeed>>,
+}
+
+impl CrossPlatformStrategy {
+    pub fn new(
+        bot: Arc<ShortTermArbitrageBot>,
+        executor: Arc<TradeExecutor>,
+        polymarket_client: Arc<dyn ExchangeClient>,
+        kalshi_client: Arc<dyn ExchangeClient>,
+    ) -> Self {
+        Self {
+            bot,
+            executor,
+            polymarket_client,
+            kalshi_client,
+            trade_amount: 100.0,
+            spot_feed: None,
+            oracle: None,
+            book_feed: None,
+        }
+    }
+
+    pub fn with_trade_amount(mut self, trade_amount: f64) -> Self {
+        self.trade_amount = trade_amount;
+        self
+    }
+
+    /// Skip crypto markets while the spot feed says venue quotes are stale.
+    pub fn with_spot_feed(mut self, feed: Arc<SpotFeed>) -> Self {
+        self.spot_feed = Some(feed);
+        self
+    }
+
+    /// Reject 15m crypto trades whose quotes contradict the on-chain oracle.
+    pub fn with_oracle(mut self, oracle: Arc<OracleCheck>) -> Self {
+        self.oracle = Some(oracle);
+        self
+    }
+
+    /// Follow matched pairs' books and re-price a pair on every update to it.
+    pub fn with_book_feed(mut self, feed: Arc<BookFeed>) -> Self {
+        self.book_feed = Some(feed);
+        self
+    }
+
+    /// Venue prices, or zeros (never tradable) when the fetch fails.
+    async fn fetch_prices(&self, event_id: String, platform: String) -> MarketPrices {
+        let client = if platform == "kalshi" {
+            &self.kalshi_client
+        } else {
+            &self.polymarket_client
+        };
+        // Zeros never validate, so a failed fetch skips the pair.
+        client.fetch_prices(&event_id).await.unwrap_or_else(|e| {
+            debug!("Failed to fetch {} prices for {}: {}", platform, event_id, e);
+            MarketPrices::new(0.0, 0.0, 0.0)
+        })
+    }
+}
+
+#[async_trait]
+impl Strategy for CrossPlatformStrategy {
+    type Opportunity = (Event, Event, ArbitrageOpportunity);
+
+    fn name(&self) -> &'static str {
+        CROSS_PLATFORM
+    }
+
+    fn platforms(&self) -> &'static [&'static str] {
+        &["polymarket", "kalshi"]
+    }
+
+    fn trade_amount(&self) -> f64 {
+        self.trade_amount
+    }
+
+    /// Both legs are sized at `trade_amount`.
+    fn capital_required(&self, _opportunity: &Self::Opportunity, trade_amount: f64) -> f64 {
+        trade_amount * 2.0
+    }
+
+    fn describe(&self, (pm_event, kalshi_event, opp): &Self::Opportunity) -> (String, f64, f64) {
+        let title = format!("{} ↔ {}", pm_event.title, kalshi_event.event_id);
+        (title, opp.net_profit.to_f64(), opp.roi_percent)
+    }
+
+    fn roi_per_hour(&self, (_, _, opp): &Self::Opportunity) -> Option<f64> {
+        opp.roi_per_hour
+    }
+
+    fn confidence(&self, (_, _, opp): &Self::Opportunity) -> Option<f64> {
+        opp.confidence
+    }
+
+    async fn scan(&self, snapshot: &MarketSnapshot) -> Vec<Self::Opportunity> {
+        let fetch_prices =
+            |event_id: &str, platform: &str| self.fetch_prices(event_id.to_string(), platform.to_string());
+        let opportunities = self
+            .bot
+            .scan_for_opportunities(&snapshot.pm_events, &snapshot.kalshi_events, fetch_prices)
+            .await;
+        if let Some(feed) = &self.book_feed {
+            feed.watch_pairs(&self.bot.matched_pairs()).await;
+        }
+        opportunities
+    }
+
+    fn follows_books(&self) -> bool {
+        self.book_feed.is_some()
+    }
+
+    async fn scan_update(&self, update: &BookUpdate) -> Vec<Self::Opportunity> {
+        let fetch_prices =
+            |event_id: &str, platform: &str| self.fetch_prices(event_id.to_string(), platform.to_string());
+        self.bot.scan_pair(update.platform, &update.event_id, fetch_prices).await
+    }
+
+    fn take_skips(&self) -> SkipCounts {
+        self.bot.take_arbitrage_skips()
+    }
+
+    async fn execute(&self, opportunity: &Self::Opportunity, trade_amount: f64) -> Result<bool> {
+        let (pm_event, kalshi_event, opp) = opportunity;
+        info!(
+            "🚨 Cross-Platform Opportunity: {} - Profit: {}, ROI: {:.2}%",
+            pm_event.title, opp.net_profit, opp.roi_percent
+        );
+        if let (Some(feed), Some(coin)) = (&self.spot_feed, pm_event.coin_from_slug()) {
+            if let Some(reason) = feed.stale_reason(&coin, Utc::now()) {
+                warn!("⏸️ Skipping {}: quotes may be stale ({})", pm_event.title, reason);
+                return Ok(false);
+            }
+        }
+        if let Some(oracle) = &self.oracle {
+            let legs = [
+                (opp.polymarket_action.1.as_str(), opp.polymarket_action.2.to_f64()),
+                (opp.kalshi_action.1.as_str(), opp.kalshi_action.2.to_f64()),
+            ];
+            match oracle.check(pm_event, &legs).await {
+                Ok(Some(reason)) => {
+                    warn!("🔗 Rejecting {}: {}", pm_event.title, reason);
+                    return Ok(false);
+                }
+                Ok(None) => {}
+                Err(e) => warn!("🔗 Oracle cross-check failed for {}: {}", pm_event.title, e),
+            }
+        }
+        let trade_result = self
+            .executor
+            .execute_arbitrage(opp, pm_event, kalshi_event, trade_amount)
+            .await?;
+        if trade_result.polymarket_order_id.is_some() || trade_result.kalshi_order_id.is_some() {
+            self.bot.record_trade(pm_event, kalshi_event, opp);
+        }
+        if trade_result.success {
+            info!(
+                "✅ Cross-platform trade executed! PM: {:?}, Kalshi: {:?}",
+                trade_result.polymarket_order_id, trade_result.kalshi_order_id
+            );
+        } else if trade_result.skipped.is_none() {
+            warn!(
+                "⚠️ Cross-platform trade failed: {}",
+                trade_result.error.unwrap_or_default()
+            );
+        }
+        Ok(trade_result.success)
+    }
+}
+
+/// Strategy 2: accumulate both sides of one Polymarket market below $1/pair.
+pub struct GabagoolStrategy {
+    bot: Arc<ShortTermArbitrageBot>,
+    executor: Arc<GabagoolExecutor>,
+    polymarket_client: Arc<dyn ExchangeClient>,
+    trade_amount: f64,
+}
+
+impl GabagoolStrategy {
+    pub fn new(
+        bot: Arc<ShortTermArbitrageBot>,
+        executor: Arc<GabagoolExecutor>,
+        polymarket_client: Arc<dyn ExchangeClient>,
+    ) -> Self {
+        Self {
+            bot,
+            executor,
+            polymarket_client,
+            trade_amount: 100.0,
+        }
+    }
+
+    pub fn with_trade_amount(mut self, trade_amount: f64) -> Self {
+        self.trade_amount = trade_amount;
+        self
+    }
+}
+
+#[async_trait]
+impl Strategy for GabagoolStrategy {
+    type Opportunity = GabagoolOpportunity;
+
+    fn name(&self) -> &'static str {
+        GABAGOOL
+    }
+
+    fn platforms(&self) -> &'static [&'static str] {
+        &["polymarket"]
+    }
+
+    fn trade_amount(&self) -> f64 {
+        self.trade_amount
+    }
+
+    fn capital_required(&self, _opportunity: &Self::Opportunity, trade_amount: f64) -> f64 {
+        trade_amount
+    }
+
+    fn describe(&self, opp: &Self::Opportunity) -> (String, f64, f64) {
+        let title = format!("{} (buy {} @ {:.2})", opp.event.title, opp.cheap_side, opp.cheap_price);
+        (title, opp.net_profit, opp.roi_percent)
+    }
+
+    fn roi_per_hour(&self, opp: &Self::Opportunity) -> Option<f64> {
+        opp.roi_per_hour
+    }
+
+    fn confidence(&self, opp: &Self::Opportunity) -> Option<f64> {
+        opp.confidence
+    }
+
+    async fn scan(&self, snapshot: &MarketSnapshot) -> Vec<Self::Opportunity> {
+        let pm = &self.polymarket_client;
+        let executor = &self.executor;
+        let fetch_prices = |event_id: &str| {
+            let event_id = event_id.to_string();
+            let pm = pm.clone();
+            async move {
+                pm.fetch_prices(&event_id).await.unwrap_or_else(|e| {
+                    debug!("Failed to fetch polymarket prices for {}: {}", event_id, e);
+                    MarketPrices::new(0.0, 0.0, 0.0)
+                })
+            }
+        };
+        let get_position_balance = |event_id: &str| {
+            let event_id = event_id.to_string();
+            let executor = executor.clone();
+            async move { executor.get_position_balance(&event_id).await }
+        };
+        self.bot
+            .scan_gabagool_opportunities(&snapshot.pm_events, fetch_prices, get_position_balance)
+            .await
+    }
+
+    fn take_skips(&self) -> SkipCounts {
+        self.bot.take_gabagool_skips()
+    }
+
+    async fn execute(&self, opp: &Self::Opportunity, trade_amount: f64) -> Result<bool> {
+        info!(
+            "🎯 Gabagool Opportunity: {} - Buy {} @ ${:.4}, Profit: ${:.4} ({:.2}% ROI), Pair Cost: ${:.4}",
+            opp.event.title,
+            opp.cheap_side,
+            opp.cheap_price,
+            opp.net_profit,
+            opp.roi_percent,
+            opp.pair_cost_after
+        );
+        if opp.profit_locked {
+            info!("🔒 Profit already LOCKED for this position!");
+        }
+
+        let success = self.executor.execute_trade(opp, trade_amount).await?;
+        if success {
+            info!("✅ Gabagool trade executed successfully!");
+        } else {
+            warn!("⚠️ Gabagool trade execution returned false");
+        }
+        Ok(success)
+    }
+
+    async fn statistics(&self) -> Option<String> {
+        let stats = self.executor.get_statistics().await;
+        let by_coin = stats
+            .by_coin
+            .iter()
+            .map(|(coin, b)| format!("{} ${:.2}", coin, b.locked_profit))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Some(format!(
+            "Events: {}, YES: {:.2}, NO: {:.2}, Total Cost: ${:.2}, Locked Profit: ${:.2} ({:.2} pairs) [{}]",
+            stats.total_events,
+            stats.total_yes_qty,
+            stats.total_no_qty,
+            stats.total_cost,
+            stats.locked_profit,
+            stats.locked_pairs,
+            by_coin
+        ))
+    }
+}
+
+/// Strategy 3: buy across Polymarket strike ladders that violate monotonicity.
+pub struct StrikeStrategy {
+    detector: StrikeDetector,
+    polymarket_client: Arc<dyn ExchangeClient>,
+    position_tracker: Option<Arc<Mutex<PositionTracker>>>,
+    trade_amount: f64,
+    min_liquidity: f64,
+    tif: TimeInForce,
+}
+
+impl StrikeStrategy {
+    pub fn new(detector: StrikeDetector, polymarket_client: Arc<dyn ExchangeClient>) -> Self {
+        Self {
+            detector,
+            polymarket_client,
+            position_tracker: None,
+            trade_amount: 100.0,
+            min_liquidity: 100.0,
+            tif: TimeInForce::default(),
+        }
+    }
+
+    pub fn with_position_tracker(mut self, tracker: Arc<Mutex<PositionTracker>>) -> Self {
+        self.position_tracker = Some(tracker);
+        self
+    }
+
+    pub fn with_trade_amount(mut self, trade_amount: f64) -> Self {
+        self.trade_amount = trade_amount;
+        self
+    }
+
+    pub fn with_min_liquidity(mut self, min_liquidity: f64) -> Self {
+        self.min_liquidity = min_liquidity;
+        self
+    }
+
+    /// Time in force of the leg orders (IOC by default).
+    pub fn with_time_in_force(mut self, tif: TimeInForce) -> Self {
+        self.tif = tif;
+        self
+    }
+}
+
+#[async_trait]
+impl Strategy for StrikeStrategy {
+    type Opportunity = StrikeOpportunity;
+
+    fn name(&self) -> &'static str {
+        STRIKE
+    }
+
+    fn platforms(&self) -> &'static [&'static str] {
+        &["polymarket"]
+    }
+
+    fn trade_amount(&self) -> f64 {
+        self.trade_amount
+    }
+
+    fn capital_required(&self, _opportunity: &Self::Opportunity, trade_amount: f64) -> f64 {
+        trade_amount
+    }
+
+    fn describe(&self, opp: &Self::Opportunity) -> (String, f64, f64) {
+        let title = format!("{} YES / {} NO", opp.yes_event.title, opp.no_event.title);
+        (title, opp.net_profit.to_f64(), opp.roi_percent)
+    }
+
+    async fn scan(&self, _snapshot: &MarketSnapshot) -> Vec<Self::Opportunity> {
+        let markets = match self.polymarket_client.fetch_markets().await {
+            Ok(markets) => markets,
+            Err(e) => {
+                warn!("Failed to fetch Polymarket markets for strike ladders: {}", e);
+                return Vec::new();
+            }
+        };
+        let now = Utc::now();
+        let markets: Vec<Event> = markets
+            .into_iter()
+            .filter(|m| m.resolution_date.is_some_and(|d| d > now))
+            .collect();
+        let ladders = group_ladders(&markets);
+
+        let ids: Vec<&str> = ladders
+            .values()
+            .flatten()
+            .map(|m| m.event.event_id.as_str())
+            .collect();
+        let fetched = futures::future::join_all(ids.iter().map(|id| async move {
+            (id.to_string(), self.polymarket_client.fetch_prices(id).await)
+        }))
+        .await;
+        let prices: HashMap<String, MarketPrices> = fetched
+            .into_iter()
+            .filter_map(|(id, prices)| prices.ok().map(|p| (id, p)))
+            .filter(|(_, p)| p.depth >= UsdAmount::from_f64(self.min_liquidity))
+            .collect();
+
+        ladders
+            .values()
+            .filter_map(|ladder| self.detector.check_ladder(ladder, &prices))
+            .collect()
+    }
+
+    /// Buys equal share counts of both legs so every outcome pays at least $1/share.
+    async fn execute(&self, opp: &Self::Opportunity, trade_amount: f64) -> Result<bool> {
+        info!(
+            "🪜 Strike Opportunity: {} YES {} @ {} + NO {} @ {} - Profit: {} ({:.2}% ROI)",
+            opp.underlying,
+            opp.yes_event.title,
+            opp.yes_price,
+            opp.no_event.title,
+            opp.no_price,
+            opp.net_profit,
+            opp.roi_percent
+        );
+
+        let shares = UsdAmount::from_f64(trade_amount).shares_at(opp.total_cost);
+        let legs = [
+            (&opp.yes_event, "YES", opp.yes_price),
+            (&opp.no_event, "NO", opp.no_price),
+        ];
+        let results = futures::future::join_all(legs.iter().map(|(event, outcome, price)| {
+            self.polymarket_client.place_order(
+                event.event_id.clone(),
+                outcome.to_string(),
+                UsdAmount::for_shares(shares, *price).to_f64(),
+                price.to_f64(),
+                self.tif,
+            )
+        }))
+        .await;
+
+        let mut filled = 0;
+        for ((event, outcome, price), result) in legs.iter().zip(results) {
+            match result {
+                Ok(order_id) => {
+                    filled += 1;
+                    if let Some(tracker) = &self.position_tracker {
+                        let position = Position::new(
+                            "polymarket".to_string(),
+                            event,
+                            outcome.to_string(),
+                            shares,
+                            UsdAmount::for_shares(shares, *price),
+                            *price,
+                            order_id,
+                        )
+                        .with_strategy(STRIKE);
+                        tracker.lock().await.add_position(position);
+                    }
+                }
+                Err(e) => error!("Strike {} leg on {} failed: {}", outcome, event.title, e),
+            }
+        }
+
+        if filled == 1 {
+            warn!("⚠️ Strike trade left one leg open - review {} positions", opp.underlying);
+        }
+        Ok(filled == legs.len())
+    }
+}
+
+/// Strategy 4: buy every outcome of a Polymarket neg-risk event when the
+/// basket costs less than the $1 it is guaranteed to pay.
+pub struct NegRiskStrategy {
+    detector: BasketDetector,
+    polymarket_client: Arc<dyn ExchangeClient>,
+    position_tracker: Option<Arc<Mutex<PositionTracker>>>,
+    trade_amount: f64,
+    min_liquidity: f64,
+    tif: TimeInForce,
+}
+
+impl NegRiskStrategy {
+    pub fn new(detector: BasketDetector, polymarket_client: Arc<dyn ExchangeClient>) -> Self {
+        Self {
+            detector,
+            polymarket_client,
+            position_tracker: None,
+            trade_amount: 100.0,
+            min_liquidity: 100.0,
+            tif: TimeInForce::default(),
+        }
+    }
+
+    pub fn with_position_tracker(mut self, tracker: Arc<Mutex<PositionTracker>>) -> Self {
+        self.position_tracker = Some(tracker);
+        self
+    }
+
+    pub fn with_trade_amount(mut self, trade_amount: f64) -> Self {
+        self.trade_amount = trade_amount;
+        self
+    }
+
+    pub fn with_min_liquidity(mut self, min_liquidity: f64) -> Self {
+        self.min_liquidity = min_liquidity;
+        self
+    }
+
+    /// Time in force of the leg orders (IOC by default).
+    pub fn with_time_in_force(mut self, tif: TimeInForce) -> Self {
+        self.tif = tif;
+        self
+    }
+}
+
+#[async_trait]
+impl Strategy for NegRiskStrategy {
+    type Opportunity = BasketOpportunity;
+
+    fn name(&self) -> &'static str {
+        NEG_RISK
+    }
+
+    fn platforms(&self) -> &'static [&'static str] {
+        &["polymarket"]
+    }
+
+    fn trade_amount(&self) -> f64 {
+        self.trade_amount
+    }
+
+    fn capital_required(&self, _opportunity: &Self::Opportunity, trade_amount: f64) -> f64 {
+        trade_amount
+    }
+
+    fn describe(&self, opp: &Self::Opportunity) -> (String, f64, f64) {
+        let title = format!("{} ({} outcomes)", opp.event.title, opp.legs.len());
+        (title, opp.net_profit.to_f64(), opp.roi_percent)
+    }
+
+    async fn scan(&self, snapshot: &MarketSnapshot) -> Vec<Self::Opportunity> {
+        let now = Utc::now();
+        let events: Vec<&Event> = snapshot
+            .pm_events
+            .iter()
+            .filter(|e| e.is_multi_outcome())
+            .filter(|e| e.resolution_date.is_none_or(|d| d > now))
+            .collect();
+
+        let ids: Vec<&str> = events
+            .iter()
+            .flat_map(|e| e.outcomes.iter().map(|o| o.market_id.as_str()))
+            .collect();
+        let fetched = futures::future::join_all(ids.iter().map(|id| async move {
+            (id.to_string(), self.polymarket_client.fetch_prices(id).await)
+        }))
+        .await;
+        let prices: HashMap<String, MarketPrices> = fetched
+            .into_iter()
+            .filter_map(|(id, prices)| prices.ok().map(|p| (id, p)))
+            .filter(|(_, p)| p.depth >= UsdAmount::from_f64(self.min_liquidity))
+            .collect();
+
+        events
+            .into_iter()
+            .filter_map(|event| self.detector.check_basket(event, &prices))
+            .collect()
+    }
+
+    /// Buys the same number of YES shares of every outcome, so whichever
+    /// resolves YES pays $1/share.
+    async fn execute(&self, opp: &Self::Opportunity, trade_amount: f64) -> Result<bool> {
+        info!(
+            "🧺 Neg-risk Opportunity: {} ({} outcomes) - Basket: {} - Profit: {} ({:.2}% ROI)",
+            opp.event.title,
+            opp.legs.len(),
+            opp.total_cost,
+            opp.net_profit,
+            opp.roi_percent
+        );
+
+        let shares = UsdAmount::from_f64(trade_amount).shares_at(opp.total_cost);
+        let results = futures::future::join_all(opp.legs.iter().map(|leg| {
+            self.polymarket_client.place_order(
+                leg.outcome.market_id.clone(),
+                "YES".to_string(),
+                UsdAmount::for_shares(shares, leg.price).to_f64(),
+                leg.price.to_f64(),
+                self.tif,
+            )
+        }))
+        .await;
+
+        let mut filled = 0;
+        for (leg, result) in opp.legs.iter().zip(results) {
+            match result {
+                Ok(order_id) => {
+                    filled += 1;
+                    if let Some(tracker) = &self.position_tracker {
+                        let position = Position::new(
+                            "polymarket".to_string(),
+                            &opp.event.outcome_event(&leg.outcome),
+                            "YES".to_string(),
+                            shares,
+                            UsdAmount::for_shares(shares, leg.price),
+                            leg.price,
+                            order_id,
+                        )
+                        .with_strategy(NEG_RISK);
+                        tracker.lock().await.add_position(position);
+                    }
+                }
+                Err(e) => error!("Neg-risk leg {} on {} failed: {}", leg.outcome.name, opp.event.title, e),
+            }
+        }
+
+        if filled > 0 && filled < opp.legs.len() {
+            warn!(
+                "⚠️ Neg-risk basket on {} partially filled ({}/{}) - review positions",
+                opp.event.title,
+                filled,
+                opp.legs.len()
+            );
+        }
+        Ok(filled == opp.legs.len())
+    }
+}
+
+/// Strategy 5: rest post-only bids on both sides of a Polymarket market at
+/// under $1 a pair, booked into the Gabagool pair balances that steer it.
+pub struct QuotingStrategy {
+    bot: Arc<ShortTermArbitrageBot>,
+    detector: QuoteDetector,
+    executor: Arc<GabagoolExecutor>,
+    polymarket_client: Arc<dyn ExchangeClient>,
+    trade_amount: f64,
+    rest_secs: u64,
+}
+
+impl QuotingStrategy {
+    pub fn new(
+        bot: Arc<ShortTermArbitrageBot>,
+        detector: QuoteDetector,
+        executor: Arc<GabagoolExecutor>,
+        polymarket_client: Arc<dyn ExchangeClient>,
+    ) -> Self {
+        Self {
+            bot,
+            detector,
+            executor,
+            polymarket_client,
+            trade_amount: 100.0,
+            rest_secs: 30,
+        }
+    }
+
+    pub fn with_trade_amount(mut self, trade_amount: f64) -> Self {
+        self.trade_amount = trade_amount;
+        self
+    }
+
+    /// Seconds each quote rests before it expires and is requoted (30 by
+    /// default). Markets resolving sooner are not quoted.
+    pub fn with_rest_secs(mut self, rest_secs: u64) -> Self {
+        self.rest_secs = rest_secs.max(1);
+        self
+    }
+}
+
+#[async_trait]
+impl Strategy for QuotingStrategy {
+    type Opportunity = TwoSidedQuote;
+
+    fn name(&self) -> &'static str {
+        QUOTING
+    }
+
+    fn platforms(&self) -> &'static [&'static str] {
+        &["polymarket"]
+    }
+
+    fn trade_amount(&self) -> f64 {
+        self.trade_amount
+    }
+
+    fn capital_required(&self, quote: &Self::Opportunity, trade_amount: f64) -> f64 {
+        let bids: Price = quote.bids().iter().map(|(_, bid)| *bid).sum();
+        UsdAmount::for_shares(quote.pair_shares(trade_amount), bids).to_f64()
+    }
+
+    fn describe(&self, quote: &Self::Opportunity) -> (String, f64, f64) {
+        let bids = quote
+            .bids()
+            .iter()
+            .map(|(side, bid)| format!("{} @ {}", side, bid))
+            .collect::<Vec<_>>()
+            .join(" / ");
+        (format!("{} (bid {})", quote.event.title, bids), quote.net_profit.to_f64(), quote.roi_percent)
+    }
+
+    fn roi_per_hour(&self, quote: &Self::Opportunity) -> Option<f64> {
+        quote.roi_per_hour
+    }
+
+    async fn scan(&self, snapshot: &MarketSnapshot) -> Vec<Self::Opportunity> {
+        let quote_until = Utc::now() + chrono::Duration::seconds(self.rest_secs as i64);
+        let events: Vec<Event> = self
+            .bot
+            .filter_events(&snapshot.pm_events)
+            .into_iter()
+            .filter(|e| e.resolution_date.is_none_or(|d| d > quote_until))
+            .collect();
+        let checked = futures::future::join_all(events.iter().map(|event| async move {
+            let (prices, (yes_qty, yes_cost, no_qty, no_cost)) = tokio::join!(
+                self.polymarket_client.fetch_prices(&event.event_id),
+                self.executor.get_position_balance(&event.event_id)
+            );
+            match prices {
+                Ok(prices) => self.detector.check_quote(event, &prices, yes_qty, no_qty, yes_cost, no_cost),
+                Err(e) => {
+                    debug!("Failed to fetch polymarket prices for {}: {}", event.event_id, e);
+                    None
+                }
+            }
+        }))
+        .await;
+        checked.into_iter().flatten().collect()
+    }
+
+    async fn execute(&self, quote: &Self::Opportunity, trade_amount: f64) -> Result<bool> {
+        let shares = quote.pair_shares(trade_amount);
+        info!(
+            "🪧 Quoting {} - {:.2} shares a side, pair cost {} ({:.2}% ROI)",
+            self.describe(quote).0,
+            shares,
+            quote.pair_cost,
+            quote.roi_percent
+        );
+        let mut placed = false;
+        for (side, bid) in quote.bids() {
+            match self.executor.rest_quote(quote, side, bid, shares, self.rest_secs).await {
+                Ok(rested) => placed |= rested,
+                Err(e) => warn!("⚠️ {} quote on {} failed: {}", side, quote.event.title, e),
+            }
+        }
+        Ok(placed)
+    }
+}
@@ -0,0 +1,238 @@
+//! Strike terms parsed from market titles ("Bitcoin above $105,000 on ...").
+//!
+//! `MarketTerms` widens this to ranges ("between $100,000 and $101,000") and
+//! up/down markets so `EventMatcher` can refuse pairs that resolve on
+//! different levels or assets however alike their titles read.
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StrikeDirection {
+    Above,
+    Below,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrikeTerms {
+    /// Upper-case asset symbol (BTC, ETH, ...).
+    pub underlying: String,
+    pub direction: StrikeDirection,
+    pub strike: f64,
+}
+
+/// What a market resolves on, as far as its title says.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarketTerms {
+    Strike(StrikeTerms),
+    /// Settles inside `[low, high]`.
+    Range { underlying: String, low: f64, high: f64 },
+    /// Up or down over the market's window (the 15-minute markets).
+    UpDown { underlying: String },
+}
+
+impl MarketTerms {
+    pub fn underlying(&self) -> &str {
+        match self {
+            MarketTerms::Strike(terms) => &terms.underlying,
+            MarketTerms::Range { underlying, .. } | MarketTerms::UpDown { underlying } => underlying,
+        }
+    }
+
+    /// Same asset, same kind of market and the same levels. Strikes may face
+    /// opposite ways: that's an inverted pair, priced as such by the bot.
+    pub fn agrees_with(&self, other: &MarketTerms) -> bool {
+        let same = |a: f64, b: f64| (a - b).abs() <= 1e-9 * a.abs().max(1.0);
+        self.underlying() == other.underlying()
+            && match (self, other) {
+                (MarketTerms::Strike(a), MarketTerms::Strike(b)) => same(a.strike, b.strike),
+                (
+                    MarketTerms::Range { low: l1, high: h1, .. },
+                    MarketTerms::Range { low: l2, high: h2, .. },
+                ) => same(*l1, *l2) && same(*h1, *h2),
+                (MarketTerms::UpDown { .. }, MarketTerms::UpDown { .. }) => true,
+                _ => false,
+            }
+    }
+}
+
+const UNDERLYINGS: &[(&str, &[&str])] = &[
+    ("BTC", &["bitcoin", "btc"]),
+    ("ETH", &["ethereum", "eth"]),
+    ("SOL", &["solana", "sol"]),
+    ("XRP", &["xrp", "ripple"]),
+    ("DOGE", &["dogecoin", "doge"]),
+];
+
+fn strike_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)\b(above|over|greater than|at least|reach|hit|below|under|less than)\s+\$?\s*([0-9][0-9,]*(?:\.[0-9]+)?)\s*(k|m)?\b",
+        )
+        .expect("valid strike regex")
+    })
+}
+
+fn range_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)(?:\bbetween\s+\$?\s*|\$\s*)([0-9][0-9,]*(?:\.[0-9]+)?)\s*(k|m)?\s*(?:and|to|-)\s*\$?\s*([0-9][0-9,]*(?:\.[0-9]+)?)\s*(k|m)?\b",
+        )
+        .expect("valid range regex")
+    })
+}
+
+fn up_down_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)\b(up or down|up/down|price up|go up|higher or lower)\b").expect("valid up/down regex")
+    })
+}
+
+/// "105,000" / "100" + "k" as a number.
+fn parse_amount(digits: &str, suffix: Option<&str>) -> Option<f64> {
+    let value: f64 = digits.replace(',', "").parse().ok()?;
+    Some(match suffix.map(str::to_lowercase).as_deref() {
+        Some("k") => value * 1_000.0,
+        Some("m") => value * 1_000_000.0,
+        _ => value,
+    })
+}
+
+/// Kalshi's subtitle shape: "$100,000 or above".
+fn trailing_strike_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)\$\s*([0-9][0-9,]*(?:\.[0-9]+)?)\s*(k|m)?\s+or\s+(above|more|higher|below|less|lower)\b")
+            .expect("valid trailing strike regex")
+    })
+}
+
+/// Upper-case symbol of the first known asset named in `text`.
+pub fn parse_underlying(text: &str) -> Option<String> {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    UNDERLYINGS
+        .iter()
+        .find(|(_, names)| names.iter().any(|n| words.contains(n)))
+        .map(|(symbol, _)| symbol.to_string())
+}
+
+/// Parses "<asset> ... above/below $<strike>" (or "$<strike> or above")
+/// out of a title.
+pub fn parse_strike(text: &str) -> Option<StrikeTerms> {
+    let underlying = parse_underlying(text)?;
+    let (direction, strike) = if let Some(caps) = strike_regex().captures(text) {
+        (caps[1].to_lowercase(), parse_amount(&caps[2], caps.get(3).map(|m| m.as_str()))?)
+    } else {
+        let caps = trailing_strike_regex().captures(text)?;
+        (caps[3].to_lowercase(), parse_amount(&caps[1], caps.get(2).map(|m| m.as_str()))?)
+    };
+    let direction = match direction.as_str() {
+        "below" | "under" | "less than" | "less" | "lower" => StrikeDirection::Below,
+        _ => StrikeDirection::Above,
+    };
+    Some(StrikeTerms {
+        underlying,
+        direction,
+        strike,
+    })
+}
+
+/// Parses a range, strike or up/down market out of a title or slug
+/// (a slug's hyphens read as spaces, so "btc-above-100k" works too).
+pub fn parse_terms(text: &str) -> Option<MarketTerms> {
+    let text = if text.contains(char::is_whitespace) {
+        text.to_string()
+    } else {
+        text.replace('-', " ")
+    };
+    let underlying = parse_underlying(&text)?;
+    if let Some(caps) = range_regex().captures(&text) {
+        let low = parse_amount(&caps[1], caps.get(2).map(|m| m.as_str()))?;
+        let high = parse_amount(&caps[3], caps.get(4).map(|m| m.as_str()))?;
+        if low < high {
+            return Some(MarketTerms::Range { underlying, low, high });
+        }
+    }
+    if let Some(terms) = parse_strike(&text) {
+        return Some(MarketTerms::Strike(terms));
+    }
+    up_down_regex()
+        .is_match(&text)
+        .then_some(MarketTerms::UpDown { underlying })
+}
+
+/// True when two titles name the same asset and strike from opposite sides
+/// ("above $100k" vs "below $100k"), so one market's YES is the other's NO.
+pub fn inverted_pair(a: &str, b: &str) -> bool {
+    match (parse_strike(a), parse_strike(b)) {
+        (Some(a), Some(b)) => {
+            a.underlying == b.underlying && a.strike == b.strike && a.direction != b.direction
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_common_title_shapes() {
+        let terms = parse_strike("Will Bitcoin be above $105,000 on January 1?").unwrap();
+        assert_eq!(terms.underlying, "BTC");
+        assert_eq!(terms.direction, StrikeDirection::Above);
+        assert_eq!(terms.strike, 105_000.0);
+
+        let terms = parse_strike("BTC above $100k").unwrap();
+        assert_eq!(terms.strike, 100_000.0);
+
+        let terms = parse_strike("Ethereum below $3,250.50 by Friday?").unwrap();
+        assert_eq!(terms.underlying, "ETH");
+        assert_eq!(terms.direction, StrikeDirection::Below);
+        assert_eq!(terms.strike, 3_250.5);
+    }
+
+    #[test]
+    fn ignores_titles_without_strike_or_asset() {
+        assert!(parse_strike("Bitcoin Up or Down 15 minutes").is_none());
+        assert!(parse_strike("Will the Fed cut above 50bps?").is_none());
+        // "sol" inside another word is not Solana.
+        assert!(parse_underlying("Will the console sell above 1m units?").is_none());
+    }
+
+    #[test]
+    fn detects_inverted_pairs() {
+        assert!(inverted_pair("BTC above $100k on Jan 1?", "Bitcoin below $100,000 on Jan 1?"));
+        assert!(!inverted_pair("BTC above $100k", "Bitcoin above $100,000"));
+        assert!(!inverted_pair("BTC above $100k", "BTC below $105k"));
+        assert!(!inverted_pair("Bitcoin Up or Down", "BTC below $100k"));
+    }
+
+    #[test]
+    fn compares_ranges_strikes_and_up_down_markets() {
+        let terms = |text: &str| parse_terms(text).unwrap();
+        assert_eq!(
+            terms("Bitcoin price between $100,000 and $101,000 on Jan 1?"),
+            MarketTerms::Range {
+                underlying: "BTC".to_string(),
+                low: 100_000.0,
+                high: 101_000.0,
+            }
+        );
+        assert_eq!(terms("btc-above-100k-on-january-1"), terms("BTC above $100,000"));
+
+        assert!(terms("BTC above $100,000").agrees_with(&terms("Bitcoin below $100k")));
+        assert!(!terms("BTC above $100,000").agrees_with(&terms("BTC above $105,000")));
+        assert!(!terms("BTC above $100,000").agrees_with(&terms("ETH above $100,000")));
+        assert!(terms("Bitcoin Up or Down - 3:15PM ET").agrees_with(&terms("BTC price up in next 15 mins?")));
+        assert!(!terms("Bitcoin Up or Down").agrees_with(&terms("BTC $100,000 to $100,249.99")));
+        assert!(parse_terms("Will the Fed cut rates?").is_none());
+    }
+}
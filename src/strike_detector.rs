@@ -0,0 +1,192 @@
+//! Monotonicity arbitrage across Polymarket strike ladders.
+//!
+//! For one underlying and expiry, P(price above K) can only fall as K rises
+//! (and P(below K) can only rise). When a ladder violates that, buying YES on
+//! the market that should be more likely and NO on the one that should be
+//! less likely pays at least $1 in every outcome (and $2 between the strikes),
+//! so a combined cost under $1 is locked profit.
+
+use crate::event::{Event, MarketPrices};
+use crate::money::Price;
+use crate::strike::{parse_strike, StrikeDirection, StrikeTerms};
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+/// Markets sharing underlying, direction and expiry date.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LadderKey {
+    pub underlying: String,
+    pub direction: StrikeDirection,
+    pub expiry: NaiveDate,
+}
+
+#[derive(Debug, Clone)]
+pub struct StrikeMarket {
+    pub event: Event,
+    pub terms: StrikeTerms,
+}
+
+/// Groups markets into strike ladders, sorted by strike; markets without
+/// parseable terms or an expiry, and ladders with a single strike, are dropped.
+pub fn group_ladders(events: &[Event]) -> HashMap<LadderKey, Vec<StrikeMarket>> {
+    let mut ladders: HashMap<LadderKey, Vec<StrikeMarket>> = HashMap::new();
+    for event in events {
+        let Some(terms) = parse_strike(&event.title) else {
+            continue;
+        };
+        let Some(expiry) = event.resolution_date.map(|d| d.date_naive()) else {
+            continue;
+        };
+        ladders
+            .entry(LadderKey {
+                underlying: terms.underlying.clone(),
+                direction: terms.direction,
+                expiry,
+            })
+            .or_default()
+            .push(StrikeMarket {
+                event: event.clone(),
+                terms,
+            });
+    }
+    ladders.retain(|_, markets| {
+        markets.sort_by(|a, b| a.terms.strike.total_cmp(&b.terms.strike));
+        markets.dedup_by(|a, b| a.terms.strike == b.terms.strike);
+        markets.len() > 1
+    });
+    ladders
+}
+
+#[derive(Debug, Clone)]
+pub struct StrikeOpportunity {
+    pub underlying: String,
+    /// Market that should be the more likely one; bought YES.
+    pub yes_event: Event,
+    pub yes_price: Price,
+    /// Market that should be the less likely one; bought NO.
+    pub no_event: Event,
+    pub no_price: Price,
+    pub total_cost: Price,
+    pub fees: Price,
+    pub net_profit: Price,
+    pub roi_percent: f64,
+}
+
+pub struct StrikeDetector {
+    min_profit_threshold: f64,
+    fee_per_leg: f64,
+}
+
+impl StrikeDetector {
+    pub fn new(min_profit_threshold: f64) -> Self {
+        Self {
+            min_profit_threshold,
+            fee_per_leg: 0.01,
+        }
+    }
+
+    pub fn with_fee_per_leg(mut self, fee: f64) -> Self {
+        self.fee_per_leg = fee;
+        self
+    }
+
+    /// Best violating pair in a strike-sorted ladder, if any clears the
+    /// threshold. `prices` is keyed by event id.
+    pub fn check_ladder(
+        &self,
+        ladder: &[StrikeMarket],
+        prices: &HashMap<String, MarketPrices>,
+    ) -> Option<StrikeOpportunity> {
+        let mut best: Option<StrikeOpportunity> = None;
+        for (i, low) in ladder.iter().enumerate() {
+            for high in &ladder[i + 1..] {
+                // Above: the lower strike is the likelier market; Below: the higher.
+                let (likely, unlikely) = match low.terms.direction {
+                    StrikeDirection::Above => (low, high),
+                    StrikeDirection::Below => (high, low),
+                };
+                let (Some(likely_prices), Some(unlikely_prices)) = (
+                    prices.get(&likely.event.event_id),
+                    prices.get(&unlikely.event.event_id),
+                ) else {
+                    continue;
+                };
+                let yes_price = likely_prices.yes_ask;
+                let no_price = unlikely_prices.no_ask;
+                if yes_price <= Price::ZERO || no_price <= Price::ZERO {
+                    continue;
+                }
+
+                let total_cost = yes_price + no_price;
+                let fees = Price::from_f64(self.fee_per_leg) + Price::from_f64(self.fee_per_leg);
+                let net_profit = total_cost.complement() - fees;
+                if net_profit <= Price::from_f64(self.min_profit_threshold) {
+                    continue;
+                }
+                if best.as_ref().is_none_or(|b| net_profit > b.net_profit) {
+                    best = Some(StrikeOpportunity {
+                        underlying: low.terms.underlying.clone(),
+                        yes_event: likely.event.clone(),
+                        yes_price,
+                        no_event: unlikely.event.clone(),
+                        no_price,
+                        total_cost,
+                        fees,
+                        net_profit,
+                        roi_percent: net_profit.ratio(total_cost) * 100.0,
+                    });
+                }
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn market(id: &str, title: &str) -> Event {
+        Event::new("polymarket".to_string(), id.to_string(), title.to_string(), String::new())
+            .with_resolution_date(Utc.with_ymd_and_hms(2025, 1, 1, 17, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn finds_inverted_above_ladder() {
+        let events = vec![
+            market("100k", "Bitcoin above $100,000 on January 1?"),
+            market("105k", "Bitcoin above $105,000 on January 1?"),
+            market("other", "Bitcoin Up or Down 15 minutes"),
+        ];
+        let ladders = group_ladders(&events);
+        assert_eq!(ladders.len(), 1);
+        let ladder = ladders.values().next().unwrap();
+
+        let mut prices = HashMap::new();
+        // 105k trades richer than 100k: buy YES 100k (0.40) + NO 105k (0.50).
+        prices.insert("100k".to_string(), MarketPrices::new(0.40, 0.62, 1000.0));
+        prices.insert("105k".to_string(), MarketPrices::new(0.52, 0.50, 1000.0));
+
+        let opp = StrikeDetector::new(0.02).check_ladder(ladder, &prices).unwrap();
+        assert_eq!(opp.yes_event.event_id, "100k");
+        assert_eq!(opp.no_event.event_id, "105k");
+        assert_eq!(opp.net_profit, Price::from_cents(8));
+    }
+
+    #[test]
+    fn consistent_ladder_has_no_opportunity() {
+        let events = vec![
+            market("90k", "BTC below $90k on January 1?"),
+            market("95k", "BTC below $95k on January 1?"),
+        ];
+        let ladders = group_ladders(&events);
+        let ladder = ladders.values().next().unwrap();
+
+        let mut prices = HashMap::new();
+        prices.insert("90k".to_string(), MarketPrices::new(0.20, 0.82, 1000.0));
+        prices.insert("95k".to_string(), MarketPrices::new(0.35, 0.67, 1000.0));
+
+        assert!(StrikeDetector::new(0.0).check_ladder(ladder, &prices).is_none());
+    }
+}
@@ -0,0 +1,229 @@
+//! Yearly tax/accounting report from the position history.
+//!
+//! Every position realized (settled, closed or redeemed) in a calendar year
+//! (UTC) becomes one row: acquired and disposed dates, proceeds, cost basis
+//! (cost plus venue fees) and gain. Kalshi rows carry the order id, matching
+//! the events on Kalshi's 1099; Polymarket rows carry the redemption tx hash
+//! and the gas it paid in POL, converted to dollars as an expense when
+//! `TAX_POL_USD` gives a POL/USD rate. `tax-report <year> [dir]` writes
+//! `{year}-kalshi.csv`, `{year}-polymarket.csv` and a per-platform
+//! `{year}-summary.csv` (default dir `reports`).
+
+use crate::money::sum_usd;
+use crate::position_tracker::Position;
+use anyhow::{Context, Result};
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+pub const DEFAULT_REPORT_DIR: &str = "reports";
+
+/// One realized position.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaxRow {
+    pub platform: String,
+    pub acquired: NaiveDate,
+    pub disposed: NaiveDate,
+    pub description: String,
+    pub event_id: String,
+    pub shares: f64,
+    pub proceeds: f64,
+    /// Cost plus venue fees.
+    pub cost_basis: f64,
+    pub fees: f64,
+    pub gain: f64,
+    pub order_id: Option<String>,
+    pub tx_hash: Option<String>,
+    pub gas_pol: Option<f64>,
+    pub gas_usd: Option<f64>,
+}
+
+/// Realized totals for one platform.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PlatformSummary {
+    pub platform: String,
+    pub positions: usize,
+    pub proceeds: f64,
+    pub cost_basis: f64,
+    pub fees: f64,
+    pub gain: f64,
+    pub gas_pol: f64,
+    pub gas_usd: Option<f64>,
+    /// Gain less gas, when gas has a dollar value.
+    pub net_gain: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaxReport {
+    pub year: i32,
+    pub rows: Vec<TaxRow>,
+    pub summaries: Vec<PlatformSummary>,
+}
+
+fn tax_row(position: &Position, pol_usd: Option<f64>) -> Option<TaxRow> {
+    let gain = position.profit?;
+    let fees = position.fees.unwrap_or_default();
+    let cost_basis = position.cost + fees;
+    Some(TaxRow {
+        platform: position.platform.clone(),
+        acquired: position.created_at.date_naive(),
+        disposed: position.settled_at?.date_naive(),
+        description: format!("{} {}", position.event_title, position.outcome),
+        event_id: position.event_id.clone(),
+        shares: position.amount,
+        proceeds: position.payout.unwrap_or(cost_basis + gain).to_f64(),
+        cost_basis: cost_basis.to_f64(),
+        fees: fees.to_f64(),
+        gain: gain.to_f64(),
+        order_id: position.order_id.clone(),
+        tx_hash: position.redemption_tx.clone(),
+        gas_pol: position.gas_pol,
+        gas_usd: position.gas_pol.zip(pol_usd).map(|(gas, rate)| gas * rate),
+    })
+}
+
+fn summarize(platform: &str, rows: &[&TaxRow], pol_usd: Option<f64>) -> PlatformSummary {
+    let gain = sum_usd(rows.iter().map(|r| r.gain));
+    let gas_pol: f64 = rows.iter().filter_map(|r| r.gas_pol).sum();
+    let gas_usd = pol_usd.map(|rate| gas_pol * rate);
+    PlatformSummary {
+        platform: platform.to_string(),
+        positions: rows.len(),
+        proceeds: sum_usd(rows.iter().map(|r| r.proceeds)),
+        cost_basis: sum_usd(rows.iter().map(|r| r.cost_basis)),
+        fees: sum_usd(rows.iter().map(|r| r.fees)),
+        gain,
+        gas_pol,
+        gas_usd,
+        net_gain: gas_usd.map(|gas| gain - gas),
+    }
+}
+
+impl TaxReport {
+    /// Positions realized during `year`, by disposal date; `pol_usd` prices gas.
+    pub fn build<'a>(
+        positions: impl IntoIterator<Item = &'a Position>,
+        year: i32,
+        pol_usd: Option<f64>,
+    ) -> Self {
+        let mut rows: Vec<TaxRow> = positions
+            .into_iter()
+            .filter_map(|p| tax_row(p, pol_usd))
+            .filter(|r| r.disposed.year() == year)
+            .collect();
+        rows.sort_by_key(|r| (r.disposed, r.acquired));
+        let mut by_platform: BTreeMap<&str, Vec<&TaxRow>> = BTreeMap::new();
+        for row in &rows {
+            by_platform.entry(row.platform.as_str()).or_default().push(row);
+        }
+        let summaries = by_platform
+            .iter()
+            .map(|(platform, rows)| summarize(platform, rows, pol_usd))
+            .collect();
+        Self { year, rows, summaries }
+    }
+
+    /// Writes a ledger per platform and the summary into `dir`; returns the
+    /// files written.
+    pub fn write(&self, dir: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        let mut written = Vec::new();
+        for summary in &self.summaries {
+            let path = dir.join(format!("{}-{}.csv", self.year, summary.platform));
+            let rows = self.rows.iter().filter(|r| r.platform == summary.platform);
+            write_csv(&path, rows)?;
+            written.push(path);
+        }
+        let path = dir.join(format!("{}-summary.csv", self.year));
+        write_csv(&path, &self.summaries)?;
+        written.push(path);
+        Ok(written)
+    }
+
+    pub fn log(&self) {
+        for s in &self.summaries {
+            info!(
+                "🧾 {} {}: {} positions, proceeds ${:.2}, cost basis ${:.2} (fees ${:.2}), gain ${:.2}, gas {:.4} POL{}",
+                self.year,
+                s.platform,
+                s.positions,
+                s.proceeds,
+                s.cost_basis,
+                s.fees,
+                s.gain,
+                s.gas_pol,
+                s.gas_usd.map_or(String::new(), |usd| format!(" (${:.2})", usd))
+            );
+        }
+    }
+}
+
+fn write_csv<'a, T: Serialize + 'a>(path: &Path, rows: impl IntoIterator<Item = &'a T>) -> Result<()> {
+    let mut writer =
+        csv::Writer::from_path(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// `TAX_POL_USD`, the POL/USD rate gas is priced at.
+pub fn pol_usd_from_env() -> Option<f64> {
+    std::env::var("TAX_POL_USD")
+        .ok()
+        .and_then(|s| s.trim().parse::<f64>().ok())
+        .filter(|rate| *rate > 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Event;
+    use crate::money::{Price, UsdAmount};
+    use chrono::{TimeZone, Utc};
+
+    fn realized(platform: &str, day: (i32, u32), profit: f64, fees: Option<f64>, gas: Option<f64>) -> Position {
+        let event = Event::new(platform.to_string(), "id".to_string(), "BTC up?".to_string(), String::new());
+        let mut position = Position::new(platform.to_string(), &event, "YES".to_string(), 10.0, UsdAmount::from_f64(5.0), Price::from_cents(50), None);
+        position.settled_at = Some(Utc.with_ymd_and_hms(day.0, day.1, 1, 0, 0, 0).unwrap());
+        position.profit = Some(UsdAmount::from_f64(profit));
+        position.fees = fees.map(UsdAmount::from_f64);
+        position.gas_pol = gas;
+        position
+    }
+
+    #[test]
+    fn reports_the_year_per_platform() {
+        let positions = [
+            realized("kalshi", (2025, 3), 4.9, Some(0.1), None),
+            realized("polymarket", (2025, 6), 5.0, None, Some(0.02)),
+            realized("polymarket", (2025, 7), -5.0, None, None),
+            realized("polymarket", (2024, 12), 5.0, None, None),
+            Position::new(
+                "kalshi".to_string(),
+                &Event::new("kalshi".to_string(), "id".to_string(), String::new(), String::new()),
+                "NO".to_string(),
+                1.0,
+                UsdAmount::from_f64(0.5),
+                Price::from_cents(50),
+                None,
+            ),
+        ];
+        let report = TaxReport::build(&positions, 2025, Some(0.5));
+        assert_eq!(report.rows.len(), 3);
+        let kalshi = &report.summaries[0];
+        assert_eq!((kalshi.platform.as_str(), kalshi.positions), ("kalshi", 1));
+        assert!((kalshi.cost_basis - 5.1).abs() < 1e-9);
+        assert!((kalshi.proceeds - 10.0).abs() < 1e-9);
+        let polymarket = &report.summaries[1];
+        assert_eq!(polymarket.positions, 2);
+        assert!(polymarket.gain.abs() < 1e-9);
+        assert!((polymarket.gas_usd.unwrap() - 0.01).abs() < 1e-9);
+        assert!((polymarket.net_gain.unwrap() + 0.01).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,62 @@
+//! OpenTelemetry export of the scan → execute spans.
+//!
+//! The pipeline is instrumented with `tracing` spans: `scan` (one per tick)
+//! with a `strategy` child per strategy, `scan_for_opportunities`, every
+//! venue `fetch_prices`, `execute_arbitrage` with its `preflight`, waits for
+//! an order lane and each `place_order`. With `OTEL_EXPORTER_OTLP_ENDPOINT`
+//! set (e.g. `http://localhost:4318`) they're batched to that collector over
+//! OTLP/HTTP as service `OTEL_SERVICE_NAME` (default
+//! `polymarket-kalshi-arbitrage-bot`), which shows where the time between
+//! detection and order placement goes.
+
+use anyhow::{Context, Result};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::SpanExporter;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing::Subscriber;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+pub const DEFAULT_SERVICE_NAME: &str = "polymarket-kalshi-arbitrage-bot";
+
+/// Flushes pending spans when dropped.
+pub struct Telemetry {
+    provider: SdkTracerProvider,
+}
+
+impl Telemetry {
+    /// The exporter and its `tracing` layer, when `OTEL_EXPORTER_OTLP_ENDPOINT`
+    /// is set. The exporter reads the endpoint (and any `OTEL_EXPORTER_OTLP_*`
+    /// headers or timeout) itself.
+    pub fn from_env<S>() -> Result<Option<(Self, impl Layer<S>)>>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        if std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").map_or(true, |s| s.trim().is_empty()) {
+            return Ok(None);
+        }
+        let service = std::env::var("OTEL_SERVICE_NAME")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_SERVICE_NAME.to_string());
+        let exporter = SpanExporter::builder()
+            .with_http()
+            .build()
+            .context("Failed to build OTLP span exporter")?;
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(Resource::builder().with_service_name(service).build())
+            .build();
+        let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer(DEFAULT_SERVICE_NAME));
+        Ok(Some((Self { provider }, layer)))
+    }
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("Failed to flush OpenTelemetry spans: {}", e);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Per-venue price ticks and size steps.
+//!
+//! Venues reject limit orders off their price grid: Kalshi trades whole
+//! cents, Polymarket CLOB markets each have a tick size (0.1 down to 0.0001)
+//! and take sizes to two decimals. Prices are snapped toward the safe side —
+//! buy limits down (never pay more than the max), sell limits up (never sell
+//! under the min) — and sizes are rounded down.
+
+use crate::exchange::OrderSide;
+use crate::money::Price;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::{Decimal, RoundingStrategy};
+
+const ONE_CENT: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
+/// Kalshi prices are whole cents.
+pub const KALSHI_TICK: Price = Price::new(ONE_CENT);
+/// Used when a Polymarket market's tick size can't be fetched.
+pub const POLYMARKET_DEFAULT_TICK: Price = Price::new(ONE_CENT);
+/// Polymarket order sizes, in decimal places.
+pub const POLYMARKET_SIZE_DECIMALS: u32 = 2;
+
+/// Snaps `price` onto the `tick` grid on the safe side for `side`, kept
+/// within `[tick, 1 - tick]`. `None` when the tick is not positive.
+pub fn snap_price(price: Price, tick: Price, side: OrderSide) -> Option<Price> {
+    let tick = tick.value();
+    if tick <= Decimal::ZERO {
+        return None;
+    }
+    let steps = price.value() / tick;
+    let steps = match side {
+        OrderSide::Buy => steps.floor(),
+        OrderSide::Sell => steps.ceil(),
+    };
+    let max_steps = (Decimal::ONE / tick).floor() - Decimal::ONE;
+    Some(Price::new(steps.clamp(Decimal::ONE, max_steps) * tick))
+}
+
+/// Rounds a share count down to `decimals` places.
+pub fn snap_size(shares: f64, decimals: u32) -> f64 {
+    Decimal::from_f64(shares)
+        .unwrap_or_default()
+        .round_dp(6)
+        .round_dp_with_strategy(decimals, RoundingStrategy::ToZero)
+        .to_f64()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snaps_toward_the_safe_side() {
+        let tick = Price::from_f64(0.01);
+        assert_eq!(snap_price(Price::from_f64(0.456), tick, OrderSide::Buy), Some(Price::from_f64(0.45)));
+        assert_eq!(snap_price(Price::from_f64(0.451), tick, OrderSide::Sell), Some(Price::from_f64(0.46)));
+        assert_eq!(snap_price(Price::from_f64(0.45), tick, OrderSide::Sell), Some(Price::from_f64(0.45)));
+        // Clamped to the tradable range.
+        assert_eq!(snap_price(Price::from_f64(0.001), tick, OrderSide::Buy), Some(tick));
+        assert_eq!(snap_price(Price::from_f64(0.995), tick, OrderSide::Sell), Some(Price::from_f64(0.99)));
+        let fine = Price::from_f64(0.001);
+        assert_eq!(snap_price(Price::from_f64(0.4567), fine, OrderSide::Buy), Some(Price::from_f64(0.456)));
+
+        assert_eq!(snap_size(222.2222, 2), 222.22);
+        assert_eq!(snap_size(249.99999999999997, 2), 250.0);
+    }
+}
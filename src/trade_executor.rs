@@ -1,11 +1,27 @@
 use crate::arbitrage_detector::ArbitrageOpportunity;
-use crate::clients::{KalshiClient, PolymarketClient};
+use crate::debug_capture::DebugCapture;
 use crate::event::Event;
+use crate::exchange::{confirm_fill, ExchangeClient, OrderFill, TimeInForce, FILL_CONFIRM_TIMEOUT};
+use crate::flatten::{limit_price, DEFAULT_FLATTEN_TOLERANCE};
+use crate::latency::ExecutionLatency;
+use crate::metrics::metrics;
+use crate::money::{Price, UsdAmount};
+use crate::notify::{BotEvent, Notifier};
 use crate::position_tracker::{Position, PositionTracker};
+use crate::preflight::{check_balance, OrderSizeLimits};
+use crate::slicing::{touch_notional, OrderSlicing, MIN_SLICE};
+use crate::strategy::CROSS_PLATFORM;
 use anyhow::Result;
+use chrono::{DateTime, Timelike, Utc};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
-use tracing::{error, info, warn};
+use tokio::time::Instant;
+use tracing::{error, info, instrument, warn};
+
+/// Excess on one leg under this many shares is contract rounding (Kalshi
+/// fills whole contracts), not a short fill on the other.
+const MIN_UNHEDGED_SHARES: f64 = 1.0;
 
 #[derive(Debug, Clone)]
 pub struct TradeResult {
@@ -13,20 +29,178 @@ pub struct TradeResult {
     pub polymarket_order_id: Option<String>,
     pub kalshi_order_id: Option<String>,
     pub error: Option<String>,
+    /// Unhedged leg left behind by a one-sided fill, after the leg failure
+    /// policy ran.
+    pub residual: Option<ResidualExposure>,
+    /// Why pre-flight checks blocked the trade; nothing was submitted.
+    pub skipped: Option<String>,
+}
+
+impl TradeResult {
+    /// One result for a sliced trade: traded when a slice was and none
+    /// failed, with every slice's order ids.
+    fn from_slices(slices: Vec<TradeResult>) -> Self {
+        let join = |ids: Vec<String>| (!ids.is_empty()).then(|| ids.join(","));
+        let errors: Vec<String> = slices.iter().filter_map(|r| r.error.clone()).collect();
+        let all_skipped = slices.iter().all(|r| r.skipped.is_some());
+        Self {
+            success: slices.iter().any(|r| r.success)
+                && slices.iter().all(|r| r.success || r.skipped.is_some()),
+            polymarket_order_id: join(slices.iter().filter_map(|r| r.polymarket_order_id.clone()).collect()),
+            kalshi_order_id: join(slices.iter().filter_map(|r| r.kalshi_order_id.clone()).collect()),
+            error: (!errors.is_empty()).then(|| errors.join("; ")),
+            residual: slices.iter().find_map(|r| r.residual.clone()),
+            skipped: slices.first().and_then(|r| r.skipped.clone()).filter(|_| all_skipped),
+        }
+    }
+
+    fn skipped(reason: String) -> Self {
+        Self {
+            success: false,
+            polymarket_order_id: None,
+            kalshi_order_id: None,
+            error: Some(reason.clone()),
+            residual: None,
+            skipped: Some(reason),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResidualExposure {
+    pub platform: String,
+    pub event_id: String,
+    pub outcome: String,
+    pub shares: f64,
+}
+
+/// What to do when one leg of a cross-platform trade fills and the other fails.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LegFailurePolicy {
+    /// Keep the filled leg. Residual exposure: the whole filled leg, unhedged.
+    Hold,
+    /// Sell the filled leg back at no less than `tolerance` under its entry
+    /// price. Residual exposure: whatever of the sell doesn't fill, the whole
+    /// leg when the filled venue rejects it.
+    Unwind { tolerance: f64 },
+    /// Retry the failed leg every `interval` for up to `max_wait`, then unwind.
+    /// Residual exposure: none when the hedge or the unwind goes through; an
+    /// outage longer than `max_wait` on the failed venue ends like `Unwind`.
+    RetryThenUnwind {
+        max_wait: Duration,
+        interval: Duration,
+        tolerance: f64,
+    },
+    /// Buy the other outcome of the filled leg on the same venue (NO against
+    /// a filled YES), so the pair pays $1 whichever way the market resolves,
+    /// as long as both sides together cost at most `max_pair_cost` a share.
+    /// Residual exposure: none, the loss capped at `max_pair_cost - 1` a
+    /// share; what the offset didn't fill, or the whole filled leg when it is
+    /// dearer or rejected.
+    Offset { max_pair_cost: f64 },
+}
+
+impl LegFailurePolicy {
+    /// `LEG_FAILURE_POLICY=hold|unwind|retry|offset` (default hold), tuned by
+    /// `LEG_UNWIND_TOLERANCE`, `LEG_RETRY_MAX_WAIT_SECS`, `LEG_RETRY_INTERVAL_MS`
+    /// and `LEG_HEDGE_MAX_PAIR_COST` (default 1.05).
+    pub fn from_env() -> Self {
+        let parse = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|s| s.trim().parse::<f64>().ok())
+        };
+        let tolerance = parse("LEG_UNWIND_TOLERANCE").unwrap_or(DEFAULT_FLATTEN_TOLERANCE);
+        match std::env::var("LEG_FAILURE_POLICY")
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase()
+            .as_str()
+        {
+            "unwind" => LegFailurePolicy::Unwind { tolerance },
+            "retry" => LegFailurePolicy::RetryThenUnwind {
+                max_wait: Duration::from_secs_f64(parse("LEG_RETRY_MAX_WAIT_SECS").unwrap_or(30.0)),
+                interval: Duration::from_millis(
+                    parse("LEG_RETRY_INTERVAL_MS").unwrap_or(1000.0) as u64,
+                ),
+                tolerance,
+            },
+            "offset" => LegFailurePolicy::Offset {
+                max_pair_cost: parse("LEG_HEDGE_MAX_PAIR_COST").unwrap_or(1.05),
+            },
+            _ => LegFailurePolicy::Hold,
+        }
+    }
+}
+
+/// Order the two legs of a cross-platform trade go out in.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LegSequence {
+    /// Both at once.
+    #[default]
+    Parallel,
+    /// This venue's leg first; the other only once its fill is confirmed,
+    /// and only for the shares that filled, so a rejected or unfilled first
+    /// leg leaves nothing to unwind.
+    First(&'static str),
+    /// The venue with the slower p90 fills (see `ExecutionLatency`) first;
+    /// Polymarket until both have been timed.
+    SlowerFirst,
+}
+
+impl LegSequence {
+    /// `LEG_SEQUENCE=parallel|polymarket_first|kalshi_first|slower_first`
+    /// (default parallel).
+    pub fn from_env() -> Self {
+        match std::env::var("LEG_SEQUENCE")
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase()
+            .as_str()
+        {
+            "polymarket_first" => LegSequence::First("polymarket"),
+            "kalshi_first" => LegSequence::First("kalshi"),
+            "slower_first" => LegSequence::SlowerFirst,
+            "" | "parallel" => LegSequence::Parallel,
+            other => {
+                warn!("Unknown LEG_SEQUENCE '{}', placing legs in parallel", other);
+                LegSequence::Parallel
+            }
+        }
+    }
 }
 
 pub struct TradeExecutor {
-    polymarket_client: PolymarketClient,
-    kalshi_client: KalshiClient,
+    polymarket_client: Arc<dyn ExchangeClient>,
+    kalshi_client: Arc<dyn ExchangeClient>,
     position_tracker: Option<Arc<Mutex<PositionTracker>>>,
+    debug_capture: Option<Arc<DebugCapture>>,
+    leg_failure_policy: LegFailurePolicy,
+    order_limits: OrderSizeLimits,
+    notifier: Option<Arc<Notifier>>,
+    tif: TimeInForce,
+    slicing: Option<OrderSlicing>,
+    latency: Option<Arc<ExecutionLatency>>,
+    leg_sequence: LegSequence,
 }
 
 impl TradeExecutor {
-    pub fn new(polymarket_client: PolymarketClient, kalshi_client: KalshiClient) -> Self {
+    pub fn new(
+        polymarket_client: Arc<dyn ExchangeClient>,
+        kalshi_client: Arc<dyn ExchangeClient>,
+    ) -> Self {
         Self {
             polymarket_client,
             kalshi_client,
             position_tracker: None,
+            debug_capture: None,
+            leg_failure_policy: LegFailurePolicy::Hold,
+            order_limits: OrderSizeLimits::default(),
+            notifier: None,
+            tif: TimeInForce::default(),
+            slicing: None,
+            latency: None,
+            leg_sequence: LegSequence::Parallel,
         }
     }
 
@@ -35,6 +209,76 @@ impl TradeExecutor {
         self
     }
 
+    pub fn with_debug_capture(mut self, capture: Arc<DebugCapture>) -> Self {
+        self.debug_capture = Some(capture);
+        self
+    }
+
+    pub fn with_leg_failure_policy(mut self, policy: LegFailurePolicy) -> Self {
+        self.leg_failure_policy = policy;
+        self
+    }
+
+    pub fn with_leg_sequence(mut self, sequence: LegSequence) -> Self {
+        self.leg_sequence = sequence;
+        self
+    }
+
+    pub fn with_order_limits(mut self, limits: OrderSizeLimits) -> Self {
+        self.order_limits = limits;
+        self
+    }
+
+    pub fn with_notifier(mut self, notifier: Arc<Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Time in force of both legs' orders (IOC by default).
+    pub fn with_time_in_force(mut self, tif: TimeInForce) -> Self {
+        self.tif = tif;
+        self
+    }
+
+    /// Splits trades larger than the touch into timed slices.
+    pub fn with_slicing(mut self, slicing: OrderSlicing) -> Self {
+        self.slicing = Some(slicing);
+        self
+    }
+
+    /// Times each filled leg from its opportunity's detection.
+    pub fn with_latency(mut self, latency: Arc<ExecutionLatency>) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    async fn notify(&self, event: BotEvent) {
+        if let Some(notifier) = &self.notifier {
+            notifier.notify(event).await;
+        }
+    }
+
+    /// Why either leg would be rejected before it reaches the venue.
+    #[instrument(skip_all)]
+    async fn preflight(&self, opportunity: &ArbitrageOpportunity, amount: f64) -> Option<String> {
+        if let Some(reason) = self
+            .order_limits
+            .check_leg("polymarket", amount, opportunity.polymarket_action.2)
+            .or_else(|| self.order_limits.check_leg("kalshi", amount, opportunity.kalshi_action.2))
+        {
+            return Some(reason);
+        }
+        let (pm, kalshi) = tokio::join!(
+            check_balance(self.polymarket_client.as_ref(), amount),
+            check_balance(self.kalshi_client.as_ref(), amount)
+        );
+        pm.or(kalshi)
+    }
+
+    #[instrument(
+        skip_all,
+        fields(strategy = %opportunity.strategy, event = %pm_event.title, amount)
+    )]
     pub async fn execute_arbitrage(
         &self,
         opportunity: &ArbitrageOpportunity,
@@ -42,26 +286,162 @@ impl TradeExecutor {
         kalshi_event: &Event,
         amount: f64,
     ) -> Result<TradeResult> {
+        let Some(slicing) = self.slicing else {
+            return self.execute_pair(opportunity, pm_event, kalshi_event, amount).await;
+        };
+        let mut touch = self.touch(opportunity, pm_event, kalshi_event).await;
+        match touch {
+            Some(at_touch) if slicing.needs_slicing(amount, at_touch) => info!(
+                "🔪 ${:.2} a leg is more than the ${:.2} at the touch - slicing {}",
+                amount, at_touch, pm_event.title
+            ),
+            _ => return self.execute_pair(opportunity, pm_event, kalshi_event, amount).await,
+        }
+
+        let mut remaining = amount;
+        let mut slices = Vec::new();
+        while let Some(slice) = slicing.next_slice(remaining, touch.unwrap_or(0.0), slices.len()) {
+            let result = self.execute_pair(opportunity, pm_event, kalshi_event, slice).await?;
+            let failed = !result.success;
+            slices.push(result);
+            remaining -= slice;
+            if failed || remaining < MIN_SLICE {
+                break;
+            }
+            tokio::time::sleep(slicing.interval).await;
+            touch = self.touch(opportunity, pm_event, kalshi_event).await;
+        }
+        if slices.is_empty() {
+            let reason = format!("only ${:.2} at the touch", touch.unwrap_or(0.0));
+            warn!("⏭️ Skipping {}: {}", pm_event.title, reason);
+            return Ok(TradeResult::skipped(reason));
+        }
+        if remaining >= MIN_SLICE {
+            info!("🔪 Stopped slicing {} with ${:.2} a leg untraded", pm_event.title, remaining);
+        }
+        Ok(TradeResult::from_slices(slices))
+    }
+
+    /// Dollars resting at the touch within the opportunity's limits, on the
+    /// thinner leg; `None` when either book can't be read.
+    async fn touch(
+        &self,
+        opportunity: &ArbitrageOpportunity,
+        pm_event: &Event,
+        kalshi_event: &Event,
+    ) -> Option<f64> {
+        let (pm, kalshi) = tokio::join!(
+            self.polymarket_client.fetch_prices(&pm_event.event_id),
+            self.kalshi_client.fetch_prices(&kalshi_event.event_id)
+        );
+        let (_, pm_outcome, pm_limit) = &opportunity.polymarket_action;
+        let (_, kalshi_outcome, kalshi_limit) = &opportunity.kalshi_action;
+        let pm = touch_notional(&pm.ok()?, pm_outcome, *pm_limit)?;
+        let kalshi = touch_notional(&kalshi.ok()?, kalshi_outcome, *kalshi_limit)?;
+        Some(pm.min(kalshi))
+    }
+
+    /// The venue whose leg goes out first; `None` for both at once.
+    fn first_leg(&self) -> Option<&'static str> {
+        match self.leg_sequence {
+            LegSequence::Parallel => None,
+            LegSequence::First(platform) => Some(platform),
+            LegSequence::SlowerFirst => {
+                let p90 = |platform: &str| {
+                    let latency = self.latency.as_ref()?;
+                    latency.percentile(platform, Utc::now().hour(), 90.0, 1)
+                };
+                match (p90("polymarket"), p90("kalshi")) {
+                    (Some(pm), Some(kalshi)) if kalshi > pm => Some("kalshi"),
+                    _ => Some("polymarket"),
+                }
+            }
+        }
+    }
+
+    /// Both legs for `amount` dollars each, in one go.
+    async fn execute_pair(
+        &self,
+        opportunity: &ArbitrageOpportunity,
+        pm_event: &Event,
+        kalshi_event: &Event,
+        amount: f64,
+    ) -> Result<TradeResult> {
+        if let Some(reason) = self.preflight(opportunity, amount).await {
+            warn!("⏭️ Skipping {}: {}", pm_event.title, reason);
+            return Ok(TradeResult::skipped(reason));
+        }
         info!(
-            "Executing arbitrage: {} - Expected profit: ${:.4} ({:.2}% ROI)",
+            "Executing arbitrage: {} - Expected profit: {} ({:.2}% ROI)",
             opportunity.strategy, opportunity.net_profit, opportunity.roi_percent
         );
 
-        let (pm_result, kalshi_result) = tokio::join!(
-            self.execute_polymarket_trade(
-                pm_event,
-                &opportunity.polymarket_action,
-                amount
-            ),
-            self.execute_kalshi_trade(
-                kalshi_event,
-                &opportunity.kalshi_action,
-                amount
-            )
-        );
+        // Dollars each leg is sent for; a sequenced first leg that fills in
+        // part shrinks both to the shares it got.
+        let (mut pm, mut kalshi, pm_amount, kalshi_amount) = match self.first_leg() {
+            None => {
+                let (pm, kalshi) = tokio::join!(
+                    self.place_leg(true, opportunity, pm_event, kalshi_event, amount, true),
+                    self.place_leg(false, opportunity, pm_event, kalshi_event, amount, true)
+                );
+                (pm, kalshi, amount, amount)
+            }
+            Some(first) => {
+                self.execute_sequenced(first != "kalshi", opportunity, pm_event, kalshi_event, amount)
+                    .await
+            }
+        };
+        pm.fail_if_unfilled("Polymarket");
+        kalshi.fail_if_unfilled("Kalshi");
+        if let (Some(latency), Some(detected_at)) = (&self.latency, opportunity.detected_at) {
+            // Timed to the confirmed fill, not the order acknowledgement.
+            for (platform, leg) in [("polymarket", &pm), ("kalshi", &kalshi)] {
+                if let Some(Ok((fill, filled_at))) = &leg.fill {
+                    if fill.shares > 0.0 {
+                        latency.record(platform, detected_at, *filled_at);
+                    }
+                }
+            }
+        }
+
+        if let LegFailurePolicy::RetryThenUnwind {
+            max_wait, interval, ..
+        } = self.leg_failure_policy
+        {
+            if pm.order.is_ok() != kalshi.order.is_ok() {
+                let deadline = Instant::now() + max_wait;
+                while Instant::now() < deadline {
+                    tokio::time::sleep(interval).await;
+                    if pm.order.is_err() {
+                        warn!("🔁 Retrying Polymarket leg");
+                        metrics().inc_counter("retries_total", &[("venue", "polymarket"), ("reason", "leg")]);
+                        pm = self
+                            .place_leg(true, opportunity, pm_event, kalshi_event, pm_amount, true)
+                            .await;
+                        pm.fail_if_unfilled("Polymarket");
+                    } else {
+                        warn!("🔁 Retrying Kalshi leg");
+                        metrics().inc_counter("retries_total", &[("venue", "kalshi"), ("reason", "leg")]);
+                        kalshi = self
+                            .place_leg(false, opportunity, pm_event, kalshi_event, kalshi_amount, true)
+                            .await;
+                        kalshi.fail_if_unfilled("Kalshi");
+                    }
+                    if pm.order.is_ok() && kalshi.order.is_ok() {
+                        break;
+                    }
+                }
+            }
+        }
 
+        let (pm_price, kalshi_price) = (opportunity.polymarket_action.2, opportunity.kalshi_action.2);
+        let (pm_shares, pm_cost) = pm.bought(pm_amount, pm_price);
+        let (kalshi_shares, kalshi_cost) = kalshi.bought(kalshi_amount, kalshi_price);
+        let (pm_result, kalshi_result) = (pm.order, kalshi.order);
         let pm_success = pm_result.is_ok();
         let kalshi_success = kalshi_result.is_ok();
+        // Links the legs of this trade in the position tracker.
+        let group_id = uuid::Uuid::new_v4().to_string();
 
         if pm_success && kalshi_success {
             info!(
@@ -73,30 +453,87 @@ impl TradeExecutor {
             let pm_order_id = pm_result.unwrap();
             let kalshi_order_id = kalshi_result.unwrap();
 
+            // The leg that filled less of its order leaves that much of the
+            // other leg unhedged.
+            let pm_ordered = UsdAmount::from_f64(pm_amount).shares_at(pm_price);
+            let kalshi_ordered = UsdAmount::from_f64(kalshi_amount).shares_at(kalshi_price);
+            let hedged = (pm_shares / pm_ordered).min(kalshi_shares / kalshi_ordered);
+            let pm_excess = pm_shares - hedged * pm_ordered;
+            let kalshi_excess = kalshi_shares - hedged * kalshi_ordered;
+            let mut cover = None;
+            if pm_excess >= MIN_UNHEDGED_SHARES {
+                warn!(
+                    "⚠️ Kalshi leg filled {:.4} of {:.4} shares - {:.4} Polymarket shares unhedged",
+                    kalshi_shares, kalshi_ordered, pm_excess
+                );
+                let covered = self.cover_excess(true, opportunity, pm_event, kalshi_event, pm_excess).await;
+                cover = Some((true, covered));
+            } else if kalshi_excess >= MIN_UNHEDGED_SHARES {
+                warn!(
+                    "⚠️ Polymarket leg filled {:.4} of {:.4} shares - {:.4} Kalshi shares unhedged",
+                    pm_shares, pm_ordered, kalshi_excess
+                );
+                let covered = self.cover_excess(false, opportunity, pm_event, kalshi_event, kalshi_excess).await;
+                cover = Some((false, covered));
+            }
+            let (residual, offset, unwound) = match cover {
+                Some((on_polymarket, c)) => (c.residual, c.offset, c.unwound.map(|fill| (on_polymarket, fill))),
+                None => (None, None, None),
+            };
+
             if let Some(tracker) = &self.position_tracker {
                 let mut tracker = tracker.lock().await;
-
                 let pm_position = Position::new(
                     "polymarket".to_string(),
                     pm_event,
                     opportunity.polymarket_action.1.clone(),
-                    amount / opportunity.polymarket_action.2,
-                    amount * opportunity.polymarket_action.2,
-                    opportunity.polymarket_action.2,
+                    pm_shares,
+                    pm_cost,
+                    pm_price,
                     pm_order_id.clone(),
                 );
-                tracker.add_position(pm_position);
-
                 let kalshi_position = Position::new(
                     "kalshi".to_string(),
                     kalshi_event,
                     opportunity.kalshi_action.1.clone(),
-                    amount / opportunity.kalshi_action.2,
-                    amount * opportunity.kalshi_action.2,
-                    opportunity.kalshi_action.2,
+                    kalshi_shares,
+                    kalshi_cost,
+                    kalshi_price,
                     kalshi_order_id.clone(),
                 );
-                tracker.add_position(kalshi_position);
+                // Shares an unwind sold back close off their leg.
+                let unwound = unwound.map(|(on_polymarket, fill)| {
+                    let leg = if on_polymarket { &pm_position } else { &kalshi_position };
+                    (leg.id.clone(), fill)
+                });
+                for position in [pm_position, kalshi_position].into_iter().chain(offset) {
+                    tracker.add_position(
+                        position
+                            .with_strategy(CROSS_PLATFORM)
+                            .with_group(&group_id)
+                            .with_detected_edge(opportunity.roi_percent / 100.0),
+                    );
+                }
+                if let Some((id, fill)) = unwound {
+                    tracker.close_shares(&id, fill.shares, fill.notional());
+                }
+            }
+
+            self.notify(BotEvent::TradeExecuted {
+                strategy: CROSS_PLATFORM.to_string(),
+                title: pm_event.title.clone(),
+                amount: (pm_cost + kalshi_cost).to_f64(),
+                expected_profit: opportunity.net_profit.to_f64(),
+            })
+            .await;
+            if let Some(r) = &residual {
+                self.notify(BotEvent::UnhedgedLeg {
+                    platform: r.platform.clone(),
+                    event_id: r.event_id.clone(),
+                    outcome: r.outcome.clone(),
+                    shares: r.shares,
+                })
+                .await;
             }
 
             Ok(TradeResult {
@@ -104,14 +541,16 @@ impl TradeExecutor {
                 polymarket_order_id: pm_order_id,
                 kalshi_order_id: kalshi_order_id,
                 error: None,
+                residual,
+                skipped: None,
             })
         } else {
 
             let mut errors = Vec::new();
-            if let Err(e) = pm_result {
+            if let Err(e) = &pm_result {
                 errors.push(format!("Polymarket: {}", e));
             }
-            if let Err(e) = kalshi_result {
+            if let Err(e) = &kalshi_result {
                 errors.push(format!("Kalshi: {}", e));
             }
 
@@ -119,11 +558,77 @@ impl TradeExecutor {
 
             warn!("⚠️ Arbitrage execution failed: {}", error_msg);
 
-            if pm_success {
-                warn!("Polymarket trade succeeded but Kalshi failed - may need to cancel PM trade");
+            let mut cover = Cover::default();
+            if pm_success != kalshi_success {
+                if let Some(capture) = &self.debug_capture {
+                    capture
+                        .observe_failed_leg(&format!(
+                            "{} / {}: {}",
+                            pm_event.event_id, kalshi_event.event_id, error_msg
+                        ))
+                        .await;
+                }
+                let shares = if pm_success { pm_shares } else { kalshi_shares };
+                cover = self
+                    .cover_excess(pm_success, opportunity, pm_event, kalshi_event, shares)
+                    .await;
             }
-            if kalshi_success {
-                warn!("Kalshi trade succeeded but Polymarket failed - may need to cancel Kalshi trade");
+            let Cover { residual, offset, unwound } = cover;
+
+            let tracked = self
+                .position_tracker
+                .as_ref()
+                .filter(|_| pm_success != kalshi_success);
+            if let Some(tracker) = tracked {
+                // Track the filled leg, and its offset, so they are marked to
+                // market and settled; what an unwind sold back is closed.
+                let (event, (_, outcome, price), order_id, shares, cost) = if pm_success {
+                    (pm_event, &opportunity.polymarket_action, pm_result.as_ref().ok().cloned().flatten(), pm_shares, pm_cost)
+                } else {
+                    (kalshi_event, &opportunity.kalshi_action, kalshi_result.as_ref().ok().cloned().flatten(), kalshi_shares, kalshi_cost)
+                };
+                let position = Position::new(
+                    event.platform.clone(),
+                    event,
+                    outcome.clone(),
+                    shares,
+                    cost,
+                    *price,
+                    order_id,
+                );
+                let id = position.id.clone();
+                let mut tracker = tracker.lock().await;
+                for position in std::iter::once(position).chain(offset) {
+                    tracker.add_position(
+                        position
+                            .with_strategy(CROSS_PLATFORM)
+                            .with_group(&group_id)
+                            .with_detected_edge(opportunity.roi_percent / 100.0),
+                    );
+                }
+                if let Some(fill) = unwound {
+                    tracker.close_shares(&id, fill.shares, fill.notional());
+                }
+            }
+
+            match &residual {
+                Some(r) => {
+                    self.notify(BotEvent::UnhedgedLeg {
+                        platform: r.platform.clone(),
+                        event_id: r.event_id.clone(),
+                        outcome: r.outcome.clone(),
+                        shares: r.shares,
+                    })
+                    .await
+                }
+                None => {
+                    self.notify(BotEvent::TradeFailed {
+                        strategy: CROSS_PLATFORM.to_string(),
+                        title: pm_event.title.clone(),
+                        error: error_msg.clone(),
+                    })
+                    .await
+                }
             }
 
             Ok(TradeResult {
@@ -131,20 +636,325 @@ impl TradeExecutor {
                 polymarket_order_id: pm_result.ok().flatten(),
                 kalshi_order_id: kalshi_result.ok().flatten(),
                 error: Some(error_msg),
+                residual,
+                skipped: None,
             })
         }
     }
 
+    /// One leg for `amount` dollars: Polymarket's when `on_polymarket`, else
+    /// Kalshi's. Its fill is confirmed when `confirm` is set or execution
+    /// latency is being timed.
+    async fn place_leg(
+        &self,
+        on_polymarket: bool,
+        opportunity: &ArbitrageOpportunity,
+        pm_event: &Event,
+        kalshi_event: &Event,
+        amount: f64,
+        confirm: bool,
+    ) -> Leg {
+        let (client, event, action) = if on_polymarket {
+            (&self.polymarket_client, pm_event, &opportunity.polymarket_action)
+        } else {
+            (&self.kalshi_client, kalshi_event, &opportunity.kalshi_action)
+        };
+        let order = if on_polymarket {
+            self.execute_polymarket_trade(event, action, amount).await
+        } else {
+            self.execute_kalshi_trade(event, action, amount).await
+        };
+        let timing = self.latency.is_some() && opportunity.detected_at.is_some();
+        let fill = match &order {
+            Ok(order_id) if confirm || timing => Some(
+                self.leg_fill(client.as_ref(), event, action, order_id.as_deref(), amount)
+                    .await
+                    .map(|fill| (fill, Utc::now())),
+            ),
+            _ => None,
+        };
+        Leg { order, fill }
+    }
+
+    /// Places the first leg (Polymarket's when `pm_first`) and waits for its
+    /// fill; the other leg goes out only for the shares that filled. Returns
+    /// the Polymarket and Kalshi legs and the dollars each spent.
+    async fn execute_sequenced(
+        &self,
+        pm_first: bool,
+        opportunity: &ArbitrageOpportunity,
+        pm_event: &Event,
+        kalshi_event: &Event,
+        amount: f64,
+    ) -> (Leg, Leg, f64, f64) {
+        let (name, action, other_action) = if pm_first {
+            ("Polymarket", &opportunity.polymarket_action, &opportunity.kalshi_action)
+        } else {
+            ("Kalshi", &opportunity.kalshi_action, &opportunity.polymarket_action)
+        };
+        info!("⛓️ Placing the {} leg first", name);
+        let mut first = self
+            .place_leg(pm_first, opportunity, pm_event, kalshi_event, amount, true)
+            .await;
+
+        let fill = match (&first.order, &first.fill) {
+            (Ok(_), Some(Ok((fill, _)))) if fill.shares > 0.0 => Ok(*fill),
+            (Ok(_), Some(Ok(_))) => {
+                first.order = Err(anyhow::anyhow!("{} leg did not fill", name));
+                Err(anyhow::anyhow!("not sent, the {} leg did not fill", name))
+            }
+            (Ok(_), Some(Err(e))) => Err(anyhow::anyhow!(
+                "not sent, the {} leg's fill could not be confirmed: {}",
+                name,
+                e
+            )),
+            _ => Err(anyhow::anyhow!("not sent, the {} leg failed", name)),
+        };
+        let (second, first_amount, second_amount) = match fill {
+            Ok(fill) => {
+                let first_amount = UsdAmount::for_shares(fill.shares, action.2).to_f64();
+                let second_amount = UsdAmount::for_shares(fill.shares, other_action.2).to_f64();
+                if first_amount < amount - 0.005 {
+                    info!(
+                        "⛓️ {} leg filled {:.4} shares (${:.2} of ${:.2}) - sizing the other leg to match",
+                        name, fill.shares, first_amount, amount
+                    );
+                }
+                let second = self
+                    .place_leg(!pm_first, opportunity, pm_event, kalshi_event, second_amount, false)
+                    .await;
+                (second, first_amount, second_amount)
+            }
+            Err(e) => (Leg { order: Err(e), fill: None }, amount, amount),
+        };
+        if pm_first {
+            (first, second, first_amount, second_amount)
+        } else {
+            (second, first, second_amount, first_amount)
+        }
+    }
+
+    /// Runs the leg failure policy on `excess` shares of one leg, Polymarket's
+    /// when `on_polymarket`.
+    async fn cover_excess(
+        &self,
+        on_polymarket: bool,
+        opportunity: &ArbitrageOpportunity,
+        pm_event: &Event,
+        kalshi_event: &Event,
+        excess: f64,
+    ) -> Cover {
+        let (client, event, action) = if on_polymarket {
+            (&self.polymarket_client, pm_event, &opportunity.polymarket_action)
+        } else {
+            (&self.kalshi_client, kalshi_event, &opportunity.kalshi_action)
+        };
+        self.handle_one_sided_fill(client.as_ref(), event, action, excess).await
+    }
+
+    /// Applies the leg failure policy to `shares` of the leg that filled.
+    async fn handle_one_sided_fill(
+        &self,
+        client: &dyn ExchangeClient,
+        event: &Event,
+        action: &(String, String, Price),
+        shares: f64,
+    ) -> Cover {
+        let (_, outcome, price) = action;
+        let residual = ResidualExposure {
+            platform: client.platform().to_string(),
+            event_id: event.event_id.clone(),
+            outcome: outcome.clone(),
+            shares,
+        };
+        let tolerance = match self.leg_failure_policy {
+            LegFailurePolicy::Hold => {
+                warn!(
+                    "⚠️ Holding unhedged {} {} {:.4} shares on {}",
+                    residual.event_id, residual.outcome, residual.shares, residual.platform
+                );
+                return Cover::unhedged(residual);
+            }
+            LegFailurePolicy::Offset { max_pair_cost } => {
+                let Some(position) = self.offset(client, event, &residual, *price, max_pair_cost).await else {
+                    return Cover::unhedged(residual);
+                };
+                let left = residual.shares - position.amount;
+                if left > 1e-9 {
+                    warn!(
+                        "⚠️ Offset on {} covered {:.4} of {:.4} {} shares - {:.4} left unhedged",
+                        residual.platform, position.amount, residual.shares, residual.outcome, left
+                    );
+                }
+                return Cover {
+                    residual: (left > 1e-9).then_some(ResidualExposure { shares: left, ..residual }),
+                    offset: Some(position),
+                    ..Default::default()
+                };
+            }
+            LegFailurePolicy::Unwind { tolerance }
+            | LegFailurePolicy::RetryThenUnwind { tolerance, .. } => tolerance,
+        };
+
+        let min_price = limit_price(*price, tolerance);
+        let sold = match client
+            .sell_order(event.event_id.clone(), outcome.clone(), residual.shares, min_price.to_f64())
+            .await
+        {
+            Ok(order_id) => {
+                confirm_fill(
+                    client,
+                    &event.event_id,
+                    order_id.as_deref(),
+                    residual.shares,
+                    min_price.to_f64(),
+                    FILL_CONFIRM_TIMEOUT,
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        match sold {
+            Ok(fill) if fill.shares > 0.0 => {
+                let left = residual.shares - fill.shares;
+                if left > 1e-9 {
+                    warn!(
+                        "⚠️ Unwind on {} sold {:.4} of {:.4} {} shares at ≥{} - {:.4} left unhedged",
+                        residual.platform, fill.shares, residual.shares, residual.outcome, min_price, left
+                    );
+                    return Cover {
+                        residual: Some(ResidualExposure { shares: left, ..residual }),
+                        unwound: Some(fill),
+                        ..Default::default()
+                    };
+                }
+                info!(
+                    "↩️ Unwound {} {} {:.4} shares on {} at {}",
+                    residual.event_id,
+                    residual.outcome,
+                    fill.shares,
+                    residual.platform,
+                    Price::from_f64(fill.price)
+                );
+                Cover { unwound: Some(fill), ..Default::default() }
+            }
+            Ok(_) => {
+                warn!(
+                    "⚠️ Unwind on {} did not fill at ≥{} - {:.4} {} shares left unhedged",
+                    residual.platform, min_price, residual.shares, residual.outcome
+                );
+                Cover::unhedged(residual)
+            }
+            Err(e) => {
+                error!(
+                    "❌ Unwind failed on {}: {} - {:.4} {} shares left unhedged",
+                    residual.platform, e, residual.shares, residual.outcome
+                );
+                Cover::unhedged(residual)
+            }
+        }
+    }
+
+    /// Buys as many shares of the other outcome as `residual` holds, on the
+    /// same venue, when its ask keeps the pair within `max_pair_cost` a
+    /// share over the leg's `entry` price. The position holds the shares
+    /// that filled; `None` when none did or the order fails.
+    async fn offset(
+        &self,
+        client: &dyn ExchangeClient,
+        event: &Event,
+        residual: &ResidualExposure,
+        entry: Price,
+        max_pair_cost: f64,
+    ) -> Option<Position> {
+        let other = if residual.outcome.eq_ignore_ascii_case("YES") { "NO" } else { "YES" };
+        let ask = match client.fetch_prices(&event.event_id).await {
+            Ok(prices) => prices.ask_for(other),
+            Err(e) => {
+                error!(
+                    "❌ Offset failed on {}: {} - {:.4} {} shares left unhedged",
+                    residual.platform, e, residual.shares, residual.outcome
+                );
+                return None;
+            }
+        };
+        let limit = Price::from_f64(max_pair_cost) - entry;
+        if ask <= Price::ZERO || ask > limit {
+            warn!(
+                "⚠️ {} ask {} on {} is over the {} offset limit - {:.4} {} shares left unhedged",
+                other, ask, residual.platform, limit, residual.shares, residual.outcome
+            );
+            return None;
+        }
+
+        let cost = UsdAmount::for_shares(residual.shares, ask);
+        let bought = match client
+            .place_order(event.event_id.clone(), other.to_string(), cost.to_f64(), ask.to_f64(), TimeInForce::Ioc)
+            .await
+        {
+            Ok(order_id) => confirm_fill(
+                client,
+                &event.event_id,
+                order_id.as_deref(),
+                residual.shares,
+                ask.to_f64(),
+                FILL_CONFIRM_TIMEOUT,
+            )
+            .await
+            .map(|fill| (order_id, fill)),
+            Err(e) => Err(e),
+        };
+        match bought {
+            Ok((order_id, fill)) if fill.shares > 0.0 => {
+                let price = Price::from_f64(fill.price);
+                info!(
+                    "🛡️ Offset {} {} {:.4} of {:.4} shares on {} with {} at {}, loss capped at {}",
+                    residual.event_id,
+                    residual.outcome,
+                    fill.shares,
+                    residual.shares,
+                    residual.platform,
+                    other,
+                    price,
+                    UsdAmount::for_shares(fill.shares, (entry + price - Price::ONE).max(Price::ZERO))
+                );
+                Some(Position::new(
+                    residual.platform.clone(),
+                    event,
+                    other.to_string(),
+                    fill.shares,
+                    fill.notional(),
+                    price,
+                    order_id,
+                ))
+            }
+            Ok(_) => {
+                warn!(
+                    "⚠️ {} offset on {} did not fill at ≤{} - {:.4} {} shares left unhedged",
+                    other, residual.platform, ask, residual.shares, residual.outcome
+                );
+                None
+            }
+            Err(e) => {
+                error!(
+                    "❌ Offset failed on {}: {} - {:.4} {} shares left unhedged",
+                    residual.platform, e, residual.shares, residual.outcome
+                );
+                None
+            }
+        }
+    }
+
     async fn execute_polymarket_trade(
         &self,
         event: &Event,
-        action: &(String, String, f64),
+        action: &(String, String, Price),
         amount: f64,
     ) -> Result<Option<String>> {
         let (action_type, outcome, max_price) = action;
 
         info!(
-            "Placing {} order on Polymarket: {} {} @ ${:.4} (amount: ${:.2})",
+            "Placing {} order on Polymarket: {} @ {} (amount: ${:.2})",
             action_type, outcome, max_price, amount
         );
 
@@ -154,7 +964,8 @@ impl TradeExecutor {
                 event.event_id.clone(),
                 outcome.clone(),
                 amount,
-                *max_price,
+                max_price.to_f64(),
+                self.tif,
             )
             .await
         {
@@ -171,13 +982,13 @@ impl TradeExecutor {
     async fn execute_kalshi_trade(
         &self,
         event: &Event,
-        action: &(String, String, f64),
+        action: &(String, String, Price),
         amount: f64,
     ) -> Result<Option<String>> {
         let (action_type, outcome, price) = action;
 
         info!(
-            "Placing {} order on Kalshi: {} {} @ ${:.4} (amount: ${:.2})",
+            "Placing {} order on Kalshi: {} @ {} (amount: ${:.2})",
             action_type, outcome, price, amount
         );
 
@@ -187,7 +998,8 @@ impl TradeExecutor {
                 event.event_id.clone(),
                 outcome.clone(),
                 amount,
-                *price,
+                price.to_f64(),
+                self.tif,
             )
             .await
         {
@@ -220,18 +1032,85 @@ impl TradeExecutor {
         }
     }
 
-    pub async fn get_order_status(&self, platform: &str, order_id: &str) -> Result<String> {
+    /// How much of `order_id`, placed in `event_id` on `platform`, has
+    /// filled.
+    pub async fn get_order_status(&self, platform: &str, event_id: &str, order_id: &str) -> Result<OrderFill> {
         match platform {
-            "polymarket" => {
-
-                Ok("filled".to_string())
-            }
-            "kalshi" => {
-
-                Ok("filled".to_string())
-            }
+            "polymarket" => self.polymarket_client.order_fill(event_id, order_id).await,
+            "kalshi" => self.kalshi_client.order_fill(event_id, order_id).await,
             _ => Err(anyhow::anyhow!("Unknown platform: {}", platform)),
         }
     }
+
+    /// Waits for a leg to stop filling. A leg still resting when the wait
+    /// runs out is reported, since it may yet fill unhedged.
+    async fn leg_fill(
+        &self,
+        client: &dyn ExchangeClient,
+        event: &Event,
+        action: &(String, String, Price),
+        order_id: Option<&str>,
+        amount: f64,
+    ) -> Result<OrderFill> {
+        let (_, outcome, price) = action;
+        let shares = UsdAmount::from_f64(amount).shares_at(*price);
+        let fill = confirm_fill(client, &event.event_id, order_id, shares, price.to_f64(), FILL_CONFIRM_TIMEOUT).await?;
+        if !fill.done {
+            warn!(
+                "⚠️ {} {} order {} on {} still resting after {:?} with {:.4} of {:.4} shares filled",
+                event.event_id,
+                outcome,
+                order_id.unwrap_or("(no id)"),
+                client.platform(),
+                FILL_CONFIRM_TIMEOUT,
+                fill.shares,
+                shares
+            );
+        }
+        Ok(fill)
+    }
 }
 
+
+/// A placed leg.
+struct Leg {
+    /// Order id, or why the order failed.
+    order: Result<Option<String>>,
+    /// What filled and when that was confirmed; `None` when not checked.
+    fill: Option<Result<(OrderFill, DateTime<Utc>)>>,
+}
+
+/// What the leg failure policy left of a one-sided fill.
+#[derive(Default)]
+struct Cover {
+    /// Shares still unhedged.
+    residual: Option<ResidualExposure>,
+    /// The other outcome, bought against the leg.
+    offset: Option<Position>,
+    /// What an unwind sold back.
+    unwound: Option<OrderFill>,
+}
+
+impl Cover {
+    fn unhedged(residual: ResidualExposure) -> Self {
+        Cover { residual: Some(residual), ..Default::default() }
+    }
+}
+
+impl Leg {
+    /// Fails an accepted order whose confirmed fill is empty.
+    fn fail_if_unfilled(&mut self, name: &str) {
+        if self.order.is_ok() && matches!(&self.fill, Some(Ok((fill, _))) if fill.shares <= 0.0) {
+            self.order = Err(anyhow::anyhow!("{} leg did not fill", name));
+        }
+    }
+
+    /// Shares and dollars bought: the confirmed fill, else the whole order
+    /// for `amount` at `price`.
+    fn bought(&self, amount: f64, price: Price) -> (f64, UsdAmount) {
+        match &self.fill {
+            Some(Ok((fill, _))) => (fill.shares, fill.notional()),
+            _ => (UsdAmount::from_f64(amount).shares_at(price), UsdAmount::from_f64(amount)),
+        }
+    }
+}
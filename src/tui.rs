@@ -0,0 +1,268 @@
+//! Terminal dashboard for `--tui`.
+//!
+//! Renders the same snapshot as the web dashboard (matched markets and their
+//! quotes, open positions, the latest opportunities and venue balances) as
+//! tables in the terminal, redrawn every `DASHBOARD_REFRESH_SECS`, so it works
+//! over SSH without hosting anything. Logs go to `TUI_LOG_PATH` (default
+//! `logs/bot.log`) instead of the screen. `q`, Esc or Ctrl-C quits.
+
+use crate::dashboard::{Dashboard, DashboardSnapshot};
+use crate::money::Price;
+use anyhow::Result;
+use chrono::Utc;
+use ratatui::backend::{Backend, CrosstermBackend};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph, Row, Table};
+use ratatui::{Frame, Terminal};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub const DEFAULT_LOG_PATH: &str = "logs/bot.log";
+/// How often keys are checked between redraws.
+const KEY_POLL: Duration = Duration::from_millis(100);
+
+/// `TUI_LOG_PATH`, or the default.
+pub fn log_path_from_env() -> String {
+    std::env::var("TUI_LOG_PATH")
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_LOG_PATH.to_string())
+}
+
+fn money(v: f64) -> String {
+    format!("${:.2}", v)
+}
+
+fn price(v: impl Into<f64>) -> String {
+    format!("{:.3}", v.into())
+}
+
+fn header(snapshot: &DashboardSnapshot) -> Line<'static> {
+    let balances = match &snapshot.balances {
+        Some(b) => format!("Polymarket {}  Kalshi {}", money(b.polymarket), money(b.kalshi)),
+        None => "Balances n/a".to_string(),
+    };
+    Line::from(format!(
+        " {}  Realized PnL {}  Open {}  Updated {}  (q to quit)",
+        balances,
+        money(snapshot.total_profit),
+        snapshot.open_positions.len(),
+        snapshot.at.format("%H:%M:%S")
+    ))
+}
+
+fn pairs_table(snapshot: &DashboardSnapshot) -> Table<'static> {
+    let rows = snapshot.pairs.iter().map(|p| {
+        let style = if !p.tradable {
+            Style::default().fg(Color::DarkGray)
+        } else if p.edge() > Price::ZERO {
+            Style::default().fg(Color::Green)
+        } else {
+            Style::default()
+        };
+        Row::new(vec![
+            p.polymarket_title.clone(),
+            p.kalshi_id.clone(),
+            price(p.pm_yes_ask),
+            price(p.pm_no_ask),
+            price(p.kalshi_yes_ask),
+            price(p.kalshi_no_ask),
+            price(p.spread),
+            price(p.pair_cost),
+            format!("{:+.3}", p.edge().to_f64()),
+        ])
+        .style(style)
+    });
+    Table::new(
+        rows,
+        [
+            Constraint::Fill(3),
+            Constraint::Fill(2),
+            Constraint::Length(7),
+            Constraint::Length(7),
+            Constraint::Length(7),
+            Constraint::Length(7),
+            Constraint::Length(7),
+            Constraint::Length(7),
+            Constraint::Length(7),
+        ],
+    )
+    .header(
+        Row::new(["Polymarket", "Kalshi", "PM YES", "PM NO", "K YES", "K NO", "Spread", "Pair", "Edge"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::bordered().title(format!(" Matched markets ({}) ", snapshot.pairs.len())))
+}
+
+fn positions_table(snapshot: &DashboardSnapshot) -> Table<'static> {
+    let now = Utc::now();
+    let rows = snapshot.open_positions.iter().map(|p| {
+        Row::new(vec![
+            p.platform.clone(),
+            p.event_title.clone(),
+            p.outcome.clone(),
+            format!("{:.2}", p.shares),
+            price(p.price),
+            money(p.cost),
+            format!("{}m", (now - p.created_at).num_minutes()),
+        ])
+    });
+    Table::new(
+        rows,
+        [
+            Constraint::Length(10),
+            Constraint::Fill(1),
+            Constraint::Length(7),
+            Constraint::Length(9),
+            Constraint::Length(7),
+            Constraint::Length(9),
+            Constraint::Length(6),
+        ],
+    )
+    .header(
+        Row::new(["Platform", "Event", "Outcome", "Shares", "Price", "Cost", "Age"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::bordered().title(format!(" Open positions ({}) ", snapshot.open_positions.len())))
+}
+
+fn opportunities_table(snapshot: &DashboardSnapshot) -> Table<'static> {
+    let rows = snapshot.opportunities.iter().map(|o| {
+        Row::new(vec![
+            o.strategy.to_string(),
+            o.title.clone(),
+            money(o.net_profit),
+            format!("{:.2}%", o.roi_percent),
+            money(o.capital),
+        ])
+    });
+    Table::new(
+        rows,
+        [
+            Constraint::Length(14),
+            Constraint::Fill(1),
+            Constraint::Length(9),
+            Constraint::Length(8),
+            Constraint::Length(9),
+        ],
+    )
+    .header(
+        Row::new(["Strategy", "Opportunity", "Profit", "ROI", "Capital"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::bordered().title(format!(" Opportunities ({}) ", snapshot.opportunities.len())))
+}
+
+/// Draws one snapshot.
+pub fn render(frame: &mut Frame, snapshot: &DashboardSnapshot) {
+    let [top, pairs, positions, opportunities] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Fill(2),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+    ])
+    .areas(frame.area());
+    frame.render_widget(Paragraph::new(header(snapshot)), top);
+    frame.render_widget(pairs_table(snapshot), pairs);
+    frame.render_widget(positions_table(snapshot), positions);
+    frame.render_widget(opportunities_table(snapshot), opportunities);
+}
+
+/// Leaves raw mode and the alternate screen, even on panic.
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(std::io::stdout(), LeaveAlternateScreen);
+    }
+}
+
+/// Whether a key press asks to quit; drains pending events without blocking.
+fn quit_pressed() -> Result<bool> {
+    while event::poll(Duration::ZERO)? {
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            let ctrl_c = key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+            if ctrl_c || matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+async fn draw<B: Backend>(terminal: &mut Terminal<B>, dashboard: &Dashboard) -> Result<()> {
+    let snapshot = dashboard.snapshot().await;
+    terminal.draw(|frame| render(frame, &snapshot))?;
+    Ok(())
+}
+
+/// Takes over the terminal until the user quits.
+pub async fn run(dashboard: Arc<Dashboard>) -> Result<()> {
+    enable_raw_mode()?;
+    let _guard = TerminalGuard;
+    execute!(std::io::stdout(), EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(std::io::stdout()))?;
+    let mut redraw = tokio::time::interval(dashboard.refresh());
+    let mut keys = tokio::time::interval(KEY_POLL);
+    loop {
+        tokio::select! {
+            _ = redraw.tick() => draw(&mut terminal, &dashboard).await?,
+            _ = keys.tick() => {
+                if quit_pressed()? {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::PairQuote;
+    use ratatui::backend::TestBackend;
+
+    #[test]
+    fn renders_matched_markets() {
+        let snapshot = DashboardSnapshot {
+            at: Utc::now(),
+            pairs: vec![PairQuote {
+                polymarket_id: "0xpm".to_string(),
+                polymarket_title: "Bitcoin Up or Down".to_string(),
+                kalshi_id: "KXBTC15M-A".to_string(),
+                score: 0.9,
+                pm_yes_ask: Price::from_cents(45),
+                pm_no_ask: Price::from_cents(56),
+                kalshi_yes_ask: Price::from_cents(47),
+                kalshi_no_ask: Price::from_cents(52),
+                spread: Price::from_cents(2),
+                pair_cost: Price::from_cents(97),
+                tradable: true,
+            }],
+            open_positions: Vec::new(),
+            opportunities: Vec::new(),
+            recent_trades: Vec::new(),
+            balances: None,
+            gabagool: Vec::new(),
+            total_profit: 12.5,
+        };
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        terminal.draw(|frame| render(frame, &snapshot)).unwrap();
+        let screen: String = terminal.backend().buffer().content.iter().map(|c| c.symbol()).collect();
+        assert!(screen.contains("Bitcoin Up or Down"));
+        assert!(screen.contains("KXBTC15M-A"));
+        assert!(screen.contains("+0.030"));
+        assert!(screen.contains("Realized PnL $12.50"));
+    }
+}
@@ -0,0 +1,140 @@
+//! UMA dispute monitoring for held Polymarket markets.
+//!
+//! Polymarket resolves through UMA's optimistic oracle: a proposed outcome
+//! stands after a challenge window unless someone disputes it, and a disputed
+//! one waits for a DVM vote that can overturn it. Gamma reports this as
+//! `umaResolutionStatus` (and the history in `umaResolutionStatuses`). Each
+//! settlement pass checks the markets behind open Polymarket positions,
+//! alerts once per disputed market, and settlement accounting holds off
+//! (see `clients::gamma_resolution`) until the resolution is final.
+
+use crate::clients::PolymarketClient;
+use crate::position_tracker::PositionTracker;
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UmaStatus {
+    /// Outcome proposed, challenge window still open.
+    Proposed,
+    Disputed,
+    Resolved,
+}
+
+impl UmaStatus {
+    pub fn parse(status: &str) -> Option<Self> {
+        match status.trim().to_lowercase().as_str() {
+            "proposed" => Some(UmaStatus::Proposed),
+            "disputed" | "challenged" => Some(UmaStatus::Disputed),
+            "resolved" | "settled" | "finalized" => Some(UmaStatus::Resolved),
+            _ => None,
+        }
+    }
+}
+
+/// Latest UMA status of a Gamma market, from `umaResolutionStatus` or else
+/// the last entry of `umaResolutionStatuses` (a JSON-encoded list).
+pub fn uma_status(market: &Value) -> Option<UmaStatus> {
+    if let Some(status) = market["umaResolutionStatus"].as_str().and_then(UmaStatus::parse) {
+        return Some(status);
+    }
+    let history: Vec<String> = match &market["umaResolutionStatuses"] {
+        Value::String(s) => serde_json::from_str(s).ok()?,
+        Value::Array(items) => items.iter().filter_map(|v| v.as_str().map(String::from)).collect(),
+        _ => return None,
+    };
+    history.last().and_then(|s| UmaStatus::parse(s))
+}
+
+/// Whether the market's proposed resolution can still change. Markets
+/// without UMA fields are taken as final.
+pub fn awaiting_finalization(market: &Value) -> bool {
+    matches!(uma_status(market), Some(UmaStatus::Proposed | UmaStatus::Disputed))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisputedMarket {
+    pub condition_id: String,
+    pub title: String,
+}
+
+pub struct UmaMonitor {
+    polymarket_client: Arc<PolymarketClient>,
+    position_tracker: Arc<tokio::sync::Mutex<PositionTracker>>,
+    /// Condition ids already alerted on, until their dispute finalizes.
+    disputed: Mutex<HashSet<String>>,
+}
+
+impl UmaMonitor {
+    pub fn new(
+        polymarket_client: Arc<PolymarketClient>,
+        position_tracker: Arc<tokio::sync::Mutex<PositionTracker>>,
+    ) -> Self {
+        Self {
+            polymarket_client,
+            position_tracker,
+            disputed: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Markets newly seen disputed among `markets`; logs disputes that have
+    /// since resolved.
+    pub fn observe(&self, markets: &[Value]) -> Vec<DisputedMarket> {
+        let mut disputed = self.disputed.lock().unwrap_or_else(|e| e.into_inner());
+        let mut newly = Vec::new();
+        for market in markets {
+            let Some(condition_id) = market["conditionId"].as_str() else {
+                continue;
+            };
+            match uma_status(market) {
+                Some(UmaStatus::Disputed) if disputed.insert(condition_id.to_string()) => {
+                    newly.push(DisputedMarket {
+                        condition_id: condition_id.to_string(),
+                        title: market["question"].as_str().unwrap_or(condition_id).to_string(),
+                    });
+                }
+                Some(UmaStatus::Resolved) if disputed.remove(condition_id) => {
+                    info!("⚖️ UMA dispute on {} resolved; settling normally", condition_id);
+                }
+                _ => {}
+            }
+        }
+        newly
+    }
+
+    /// Checks the markets behind open Polymarket positions.
+    pub async fn check(&self) -> Result<Vec<DisputedMarket>> {
+        let mut condition_ids: Vec<String> = {
+            let tracker = self.position_tracker.lock().await;
+            tracker
+                .get_open_positions()
+                .into_iter()
+                .filter(|p| p.platform == "polymarket")
+                .map(|p| p.event_id.clone())
+                .collect()
+        };
+        condition_ids.sort();
+        condition_ids.dedup();
+        let markets = self.polymarket_client.fetch_gamma_markets(&condition_ids).await?;
+        Ok(self.observe(&markets))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_uma_status_and_history() {
+        assert_eq!(uma_status(&json!({"umaResolutionStatus": "disputed"})), Some(UmaStatus::Disputed));
+        let history = json!({"umaResolutionStatuses": "[\"proposed\", \"disputed\", \"resolved\"]"});
+        assert_eq!(uma_status(&history), Some(UmaStatus::Resolved));
+        assert!(!awaiting_finalization(&history));
+        assert!(awaiting_finalization(&json!({"umaResolutionStatus": "proposed"})));
+        assert!(!awaiting_finalization(&json!({"closed": true})));
+    }
+}
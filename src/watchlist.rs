@@ -0,0 +1,218 @@
+//! Watch-only market pairs.
+//!
+//! Pairs listed in `WATCHLIST` are priced every tick whether or not they pass
+//! the bot's filters or matcher, and their spreads and hypothetical edges are
+//! logged (console + monitor log) without ever trading. Useful for studying a
+//! series before enabling a strategy on it.
+
+use crate::arbitrage_detector::Fees;
+use crate::event::{Event, MarketPrices};
+use crate::exchange::ExchangeClient;
+use crate::money::Price;
+use crate::monitor_logger::append_monitor_log;
+use chrono::{DateTime, Utc};
+use std::sync::RwLock;
+use tracing::{info, warn};
+
+/// A Polymarket market (slug or event id) paired with a Kalshi ticker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchPair {
+    pub polymarket: String,
+    pub kalshi: String,
+}
+
+impl WatchPair {
+    /// Parses `pm-slug:KALSHI-TICKER`.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let (polymarket, kalshi) = raw.trim().split_once(':')?;
+        let (polymarket, kalshi) = (polymarket.trim(), kalshi.trim());
+        if polymarket.is_empty() || kalshi.is_empty() {
+            return None;
+        }
+        Some(Self {
+            polymarket: polymarket.to_string(),
+            kalshi: kalshi.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WatchReading {
+    pub pair: WatchPair,
+    pub title: Option<String>,
+    pub pm_prices: MarketPrices,
+    pub kalshi_prices: MarketPrices,
+    pub at: DateTime<Utc>,
+}
+
+impl WatchReading {
+    /// Polymarket YES minus Kalshi YES.
+    pub fn yes_spread(&self) -> Price {
+        self.pm_prices.yes_ask - self.kalshi_prices.yes_ask
+    }
+
+    /// Net edge of buying YES on Kalshi and NO on Polymarket (negative = no edge).
+    pub fn edge_kalshi_yes(&self, fees: &Fees) -> Price {
+        (self.kalshi_prices.yes_ask + self.pm_prices.no_ask).complement() - fees.per_pair()
+    }
+
+    /// Net edge of buying NO on Kalshi and YES on Polymarket (negative = no edge).
+    pub fn edge_kalshi_no(&self, fees: &Fees) -> Price {
+        (self.kalshi_prices.no_ask + self.pm_prices.yes_ask).complement() - fees.per_pair()
+    }
+
+    pub fn best_edge(&self, fees: &Fees) -> Price {
+        self.edge_kalshi_yes(fees).max(self.edge_kalshi_no(fees))
+    }
+}
+
+pub struct WatchList {
+    pairs: Vec<WatchPair>,
+    fees: Fees,
+    latest: RwLock<Vec<WatchReading>>,
+}
+
+impl WatchList {
+    pub fn new(pairs: Vec<WatchPair>) -> Self {
+        Self {
+            pairs,
+            fees: Fees::default(),
+            latest: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Reads `WATCHLIST` (comma-separated `pm-slug:KALSHI-TICKER` pairs).
+    pub fn from_env() -> Option<Self> {
+        let raw = std::env::var("WATCHLIST").ok()?;
+        let mut pairs = Vec::new();
+        for entry in raw.split(',').filter(|s| !s.trim().is_empty()) {
+            match WatchPair::parse(entry) {
+                Some(pair) => pairs.push(pair),
+                None => warn!(
+                    "Ignoring invalid WATCHLIST entry '{}' (expected pm-slug:KALSHI-TICKER)",
+                    entry.trim()
+                ),
+            }
+        }
+        if pairs.is_empty() {
+            None
+        } else {
+            Some(Self::new(pairs))
+        }
+    }
+
+    pub fn with_fees(mut self, fees: Fees) -> Self {
+        self.fees = fees;
+        self
+    }
+
+    pub fn pairs(&self) -> &[WatchPair] {
+        &self.pairs
+    }
+
+    /// Readings from the most recent tick.
+    pub fn latest(&self) -> Vec<WatchReading> {
+        self.latest.read().map(|r| r.clone()).unwrap_or_default()
+    }
+
+    /// Prices every pair and logs it. `pm_events` is only used to resolve
+    /// slugs to event ids; pairs not in it are fetched by the raw id.
+    pub async fn run_tick(
+        &self,
+        pm_events: &[Event],
+        polymarket_client: &dyn ExchangeClient,
+        kalshi_client: &dyn ExchangeClient,
+    ) -> Vec<WatchReading> {
+        let futures = self.pairs.iter().map(|pair| async move {
+            let pm_event = pm_events.iter().find(|e| {
+                e.event_id == pair.polymarket || e.slug.as_deref() == Some(pair.polymarket.as_str())
+            });
+            let pm_id = pm_event
+                .map(|e| e.event_id.clone())
+                .unwrap_or_else(|| pair.polymarket.clone());
+            let (pm_prices, kalshi_prices) = tokio::join!(
+                polymarket_client.fetch_prices(&pm_id),
+                kalshi_client.fetch_prices(&pair.kalshi)
+            );
+            match (pm_prices, kalshi_prices) {
+                (Ok(pm_prices), Ok(kalshi_prices)) => Some(WatchReading {
+                    pair: pair.clone(),
+                    title: pm_event.map(|e| e.title.clone()),
+                    pm_prices,
+                    kalshi_prices,
+                    at: Utc::now(),
+                }),
+                (pm, kalshi) => {
+                    let err = pm.err().or(kalshi.err()).map(|e| e.to_string());
+                    warn!(
+                        "👀 Watch {} / {}: price fetch failed: {}",
+                        pair.polymarket,
+                        pair.kalshi,
+                        err.unwrap_or_default()
+                    );
+                    None
+                }
+            }
+        });
+        let readings: Vec<WatchReading> = futures::future::join_all(futures)
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
+
+        for reading in &readings {
+            let line = format!(
+                "WATCH {} / {}  |  PM yes={:.3} no={:.3}  |  Kalshi yes={:.3} no={:.3}  |  spread={:+.3}  edge={:+.4}",
+                reading.pair.polymarket,
+                reading.pair.kalshi,
+                reading.pm_prices.yes_ask.to_f64(),
+                reading.pm_prices.no_ask.to_f64(),
+                reading.kalshi_prices.yes_ask.to_f64(),
+                reading.kalshi_prices.no_ask.to_f64(),
+                reading.yes_spread().to_f64(),
+                reading.best_edge(&self.fees).to_f64()
+            );
+            info!("👀 {}", line);
+            append_monitor_log(&format!("[{}] {}", reading.at.to_rfc3339(), line), &reading.at);
+        }
+
+        if let Ok(mut latest) = self.latest.write() {
+            *latest = readings.clone();
+        }
+        readings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pairs() {
+        assert_eq!(
+            WatchPair::parse(" btc-updown-15m : KXBTC15M-25JAN01 "),
+            Some(WatchPair {
+                polymarket: "btc-updown-15m".to_string(),
+                kalshi: "KXBTC15M-25JAN01".to_string(),
+            })
+        );
+        assert_eq!(WatchPair::parse("no-ticker:"), None);
+        assert_eq!(WatchPair::parse("missing-separator"), None);
+    }
+
+    #[test]
+    fn best_edge_picks_cheaper_side() {
+        let reading = WatchReading {
+            pair: WatchPair::parse("a:b").unwrap(),
+            title: None,
+            pm_prices: MarketPrices::new(0.55, 0.45, 1000.0),
+            kalshi_prices: MarketPrices::new(0.40, 0.60, 1000.0),
+            at: Utc::now(),
+        };
+        let fees = Fees::default();
+        assert_eq!(reading.edge_kalshi_yes(&fees), Price::from_cents(13));
+        assert_eq!(reading.edge_kalshi_no(&fees), Price::from_cents(-17));
+        assert_eq!(reading.best_edge(&fees), Price::from_cents(13));
+        assert_eq!(reading.yes_spread(), Price::from_cents(15));
+    }
+}
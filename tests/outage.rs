@@ -0,0 +1,173 @@
+use polymarket_kalshi_arbitrage_bot::{
+    arbitrage_detector::ArbitrageOpportunity,
+    event::{Event, MarketPrices},
+    mock::{MockKalshiClient, MockPolymarketClient},
+    money::Price,
+    trade_executor::{LegFailurePolicy, TradeExecutor, TradeResult},
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+const PM_ID: &str = "0xpm-btc-15m";
+const KALSHI_ID: &str = "KXBTC15M-TEST";
+const AMOUNT: f64 = 90.0;
+const PM_NO: f64 = 0.45;
+
+struct Paper {
+    pm: Arc<MockPolymarketClient>,
+    kalshi: Arc<MockKalshiClient>,
+}
+
+fn paper() -> Paper {
+    let pm = Arc::new(MockPolymarketClient::new());
+    let kalshi = Arc::new(MockKalshiClient::new());
+    pm.set_prices(PM_ID, MarketPrices::new(0.55, PM_NO, 1000.0));
+    kalshi.set_prices(KALSHI_ID, MarketPrices::new(0.40, 0.60, 1000.0));
+    Paper { pm, kalshi }
+}
+
+fn opportunity() -> ArbitrageOpportunity {
+    ArbitrageOpportunity {
+        strategy: "Buy Yes on Kalshi + Buy No on Polymarket".to_string(),
+        kalshi_action: ("BUY".to_string(), "YES".to_string(), Price::from_cents(40)),
+        polymarket_action: ("BUY".to_string(), "NO".to_string(), Price::from_f64(PM_NO)),
+        total_cost: Price::from_cents(85),
+        gross_profit: Price::from_cents(15),
+        fees: Price::from_cents(2),
+        net_profit: Price::from_cents(13),
+        roi_percent: 15.3,
+        hours_to_resolution: None,
+        roi_per_hour: None,
+        confidence: None,
+        detected_at: None,
+    }
+}
+
+async fn execute(p: &Paper, policy: LegFailurePolicy) -> TradeResult {
+    let pm_event = Event::new("polymarket".into(), PM_ID.into(), "BTC".into(), String::new());
+    let kalshi_event = Event::new("kalshi".into(), KALSHI_ID.into(), "BTC".into(), String::new());
+    TradeExecutor::new(p.pm.clone(), p.kalshi.clone())
+        .with_leg_failure_policy(policy)
+        .execute_arbitrage(&opportunity(), &pm_event, &kalshi_event, AMOUNT)
+        .await
+        .unwrap()
+}
+
+const TEN_MINUTES: Duration = Duration::from_secs(600);
+
+#[tokio::test]
+async fn hold_leaves_the_filled_leg() {
+    let p = paper();
+    p.kalshi.schedule_outage(Duration::ZERO, TEN_MINUTES);
+
+    let result = execute(&p, LegFailurePolicy::Hold).await;
+
+    assert!(!result.success);
+    let residual = result.residual.unwrap();
+    assert_eq!(residual.platform, "polymarket");
+    assert!((residual.shares - AMOUNT / PM_NO).abs() < 1e-9);
+    p.pm.assert_residual(PM_ID, "NO", AMOUNT / PM_NO);
+    p.kalshi.assert_flat();
+}
+
+#[tokio::test]
+async fn unwind_sells_the_filled_leg_back() {
+    let p = paper();
+    p.kalshi.schedule_outage(Duration::ZERO, TEN_MINUTES);
+
+    let result = execute(&p, LegFailurePolicy::Unwind { tolerance: 0.05 }).await;
+
+    assert!(!result.success);
+    assert!(result.residual.is_none());
+    p.pm.assert_flat();
+    p.kalshi.assert_flat();
+}
+
+#[tokio::test]
+async fn unwind_that_does_not_fill_leaves_the_leg_unhedged() {
+    let p = paper();
+    p.kalshi.schedule_outage(Duration::ZERO, TEN_MINUTES);
+    // The NO bid sits under the unwind's limit, so the sell is killed unfilled.
+    let mut prices = MarketPrices::new(0.55, PM_NO, 1000.0);
+    prices.no_bid = Some(Price::from_cents(30));
+    p.pm.set_prices(PM_ID, prices);
+
+    let result = execute(&p, LegFailurePolicy::Unwind { tolerance: 0.05 }).await;
+
+    assert!(!result.success);
+    let residual = result.residual.expect("the unsold leg is still unhedged");
+    assert!((residual.shares - AMOUNT / PM_NO).abs() < 1e-9);
+    p.pm.assert_residual(PM_ID, "NO", AMOUNT / PM_NO);
+}
+
+#[tokio::test]
+async fn retry_hedges_through_a_short_outage() {
+    let p = paper();
+    p.kalshi.schedule_outage(Duration::ZERO, Duration::from_millis(150));
+
+    let result = execute(
+        &p,
+        LegFailurePolicy::RetryThenUnwind {
+            max_wait: Duration::from_secs(2),
+            interval: Duration::from_millis(50),
+            tolerance: 0.05,
+        },
+    )
+    .await;
+
+    assert!(result.success);
+    assert!(result.residual.is_none());
+    p.pm.assert_residual(PM_ID, "NO", AMOUNT / PM_NO);
+    p.kalshi.assert_residual(KALSHI_ID, "YES", AMOUNT / 0.40);
+}
+
+#[tokio::test]
+async fn filled_venue_going_down_mid_retry_leaves_residual() {
+    let p = paper();
+    p.kalshi.schedule_outage(Duration::ZERO, TEN_MINUTES);
+    // Polymarket fills, then drops out before the retry window ends.
+    p.pm.schedule_outage(Duration::from_millis(50), TEN_MINUTES);
+
+    let result = execute(
+        &p,
+        LegFailurePolicy::RetryThenUnwind {
+            max_wait: Duration::from_millis(150),
+            interval: Duration::from_millis(50),
+            tolerance: 0.05,
+        },
+    )
+    .await;
+
+    assert!(!result.success);
+    assert_eq!(result.residual.unwrap().outcome, "NO");
+    p.pm.assert_residual(PM_ID, "NO", AMOUNT / PM_NO);
+    p.kalshi.assert_flat();
+}
+
+#[tokio::test]
+async fn offset_buys_the_other_side_on_the_filled_venue() {
+    let p = paper();
+    p.pm.schedule_outage(Duration::ZERO, TEN_MINUTES);
+
+    let result = execute(&p, LegFailurePolicy::Offset { max_pair_cost: 1.05 }).await;
+
+    // Kalshi YES at 0.40 plus NO at 0.60 pays $1 either way.
+    assert!(!result.success);
+    assert!(result.residual.is_none());
+    p.pm.assert_flat();
+    let shares = AMOUNT / 0.40;
+    assert!((p.kalshi.exposure(KALSHI_ID, "YES") - shares).abs() < 1e-6);
+    assert!((p.kalshi.exposure(KALSHI_ID, "NO") - shares).abs() < 1e-6);
+}
+
+#[tokio::test]
+async fn offset_over_the_max_pair_cost_holds_the_leg() {
+    let p = paper();
+    p.pm.schedule_outage(Duration::ZERO, TEN_MINUTES);
+
+    let result = execute(&p, LegFailurePolicy::Offset { max_pair_cost: 0.98 }).await;
+
+    assert!(!result.success);
+    assert_eq!(result.residual.unwrap().outcome, "YES");
+    p.kalshi.assert_residual(KALSHI_ID, "YES", AMOUNT / 0.40);
+}
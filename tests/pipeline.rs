@@ -0,0 +1,1365 @@
+use chrono::{Duration, Timelike, Utc};
+use polymarket_kalshi_arbitrage_bot::{
+    analytics::PerformanceReport,
+    balance_floor::BalanceFloors,
+    arbitrage_detector::ArbitrageOpportunity,
+    basket_detector::BasketDetector,
+    book_feed::{BookFeed, BookUpdate},
+    control::{ControlApi, Controls},
+    dashboard::Dashboard,
+    bot::{MarketFilters, ShortTermArbitrageBot, TradeDedup},
+    early_exit::EarlyExit,
+    event::{BookTop, Event, EventOutcome, MarketPrices, Quote},
+    exchange::{ExchangeClient, Resolution, SettlementRecord, TimeInForce},
+    gabagool_detector::GabagoolOpportunity,
+    gabagool_executor::{GabagoolExecutor, MakerMode, Merger, Rebalance},
+    journal::{self, Journal, JournalEntry, JournaledClient},
+    latency::{EdgeDecay, ExecutionLatency},
+    match_store::{MatchSource, MatchStore},
+    quote_detector::QuoteDetector,
+    recorder::{MarketRecorder, OpportunityRecord, OPPORTUNITIES_FILE},
+    mock::{MockKalshiClient, MockOrder, MockPolymarketClient},
+    money::{Price, UsdAmount},
+    position_monitor::{ExitRules, PositionMonitor},
+    position_tracker::{ExportFormat, Position, PositionTracker},
+    settlement_checker::{Redeemer, SettlementChecker},
+    skip_reason::SkipReason,
+    slicing::OrderSlicing,
+    strategy::{
+        CapitalAllocation, CrossPlatformStrategy, MarketSnapshot, NegRiskStrategy, OrderTimeInForce,
+        QuotingStrategy, Strategy, StrategyIntervals, StrategyRegistry, TradeLimits, CROSS_PLATFORM, GABAGOOL,
+    },
+    trade_executor::{LegSequence, TradeExecutor},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+const PM_ID: &str = "0xpm-btc-15m";
+const KALSHI_ID: &str = "KXBTC15M-TEST";
+
+fn matched_events() -> (Event, Event) {
+    let resolves = Utc::now() + Duration::minutes(20);
+    let pm = Event::new(
+        "polymarket".to_string(),
+        PM_ID.to_string(),
+        "Bitcoin Up or Down 15 minutes".to_string(),
+        String::new(),
+    )
+    .with_slug("btc-updown-15m-1700000000".to_string())
+    .with_resolution_date(resolves);
+    let kalshi = Event::new(
+        "kalshi".to_string(),
+        KALSHI_ID.to_string(),
+        "Bitcoin Up or Down 15 minutes".to_string(),
+        String::new(),
+    )
+    .with_slug(KALSHI_ID.to_string())
+    .with_resolution_date(resolves);
+    (pm, kalshi)
+}
+
+fn filters() -> MarketFilters {
+    MarketFilters {
+        categories: vec!["crypto".to_string()],
+        max_hours_until_resolution: 1,
+        min_liquidity: 100.0,
+        coin_filter: None,
+    }
+}
+
+struct Harness {
+    pm: Arc<MockPolymarketClient>,
+    kalshi: Arc<MockKalshiClient>,
+    tracker: Arc<Mutex<PositionTracker>>,
+    bot: ShortTermArbitrageBot,
+    executor: TradeExecutor,
+    settlement: SettlementChecker,
+}
+
+fn harness() -> Harness {
+    let pm = Arc::new(MockPolymarketClient::new());
+    let kalshi = Arc::new(MockKalshiClient::new());
+    let (pm_event, kalshi_event) = matched_events();
+    pm.set_events(vec![pm_event]);
+    kalshi.set_events(vec![kalshi_event]);
+    // Kalshi YES 0.40 + Polymarket NO 0.45 = 0.85 per pair.
+    pm.set_prices(PM_ID, MarketPrices::new(0.55, 0.45, 1000.0));
+    kalshi.set_prices(KALSHI_ID, MarketPrices::new(0.40, 0.60, 1000.0));
+
+    let tracker = Arc::new(Mutex::new(PositionTracker::new()));
+    let executor = TradeExecutor::new(pm.clone(), kalshi.clone())
+        .with_position_tracker(tracker.clone());
+    let settlement = SettlementChecker::new(pm.clone(), kalshi.clone(), tracker.clone());
+
+    Harness {
+        pm,
+        kalshi,
+        tracker,
+        bot: ShortTermArbitrageBot::new(filters(), 0.7, 0.02),
+        executor,
+        settlement,
+    }
+}
+
+async fn scan(h: &Harness) -> Vec<(Event, Event, ArbitrageOpportunity)> {
+    let pm_events = h.pm.fetch_events().await.unwrap();
+    let kalshi_events = h.kalshi.fetch_events().await.unwrap();
+    let pm = h.pm.clone();
+    let kalshi = h.kalshi.clone();
+    h.bot
+        .scan_for_opportunities(&pm_events, &kalshi_events, move |event_id: &str, platform: &str| {
+            let event_id = event_id.to_string();
+            let platform = platform.to_string();
+            let pm = pm.clone();
+            let kalshi = kalshi.clone();
+            async move {
+                match platform.as_str() {
+                    "polymarket" => pm.fetch_prices(&event_id).await.unwrap(),
+                    _ => kalshi.fetch_prices(&event_id).await.unwrap(),
+                }
+            }
+        })
+        .await
+}
+
+#[tokio::test]
+async fn scan_detects_cross_platform_opportunity() {
+    let h = harness();
+    let opportunities = scan(&h).await;
+
+    assert_eq!(opportunities.len(), 1);
+    let (_, _, opp) = &opportunities[0];
+    assert_eq!(opp.kalshi_action.1, "YES");
+    assert_eq!(opp.polymarket_action.1, "NO");
+    assert_eq!(opp.total_cost, Price::from_cents(85));
+}
+
+#[tokio::test]
+async fn slow_fills_raise_the_profit_a_pair_must_clear() {
+    let latency = Arc::new(ExecutionLatency::default());
+    let mut decay = EdgeDecay::new(latency.clone(), 0.4);
+    decay.min_samples = 1;
+    decay.max_extra = 0.2;
+    let h = Harness {
+        bot: ShortTermArbitrageBot::new(filters(), 0.7, 0.02).with_edge_decay(decay),
+        ..harness()
+    };
+    let executor = TradeExecutor::new(h.pm.clone(), h.kalshi.clone()).with_latency(latency.clone());
+    h.kalshi.set_latency(std::time::Duration::from_millis(300));
+
+    // No fills timed yet: the $0.13 pair clears the $0.02 minimum.
+    let opportunities = scan(&h).await;
+    let (pm_event, kalshi_event, opp) = &opportunities[0];
+    assert!(opp.detected_at.is_some());
+    assert!(executor.execute_arbitrage(opp, pm_event, kalshi_event, 50.0).await.unwrap().success);
+    let kalshi_secs = latency.percentile("kalshi", Utc::now().hour(), 90.0, 1).unwrap();
+    assert!(kalshi_secs >= 0.3);
+    assert!(latency.percentile("polymarket", Utc::now().hour(), 90.0, 1).unwrap() < kalshi_secs);
+
+    // Kalshi's 0.3s+ costs over $0.12 a share at $0.40/s: not enough left.
+    assert!(scan(&h).await.is_empty());
+}
+
+#[tokio::test]
+async fn recorder_keeps_every_detected_opportunity_with_its_quotes() {
+    let dir = std::env::temp_dir().join(format!("opps-{}", uuid::Uuid::new_v4()));
+    let recorder = Arc::new(MarketRecorder::new(&dir));
+    let h = Harness {
+        bot: ShortTermArbitrageBot::new(filters(), 0.7, 0.02).with_recorder(recorder.clone()),
+        ..harness()
+    };
+    let (pm_event, kalshi_event) = matched_events();
+    let scan_id = recorder.record_events(&[pm_event], &[kalshi_event]);
+    assert_eq!(scan(&h).await.len(), 1);
+    // Detected again on the next scan, still untraded.
+    recorder.record_events(&[], &[]);
+    assert_eq!(scan(&h).await.len(), 1);
+    recorder.flush().await;
+
+    let day = std::fs::read_dir(&dir).unwrap().next().unwrap().unwrap().path();
+    let rows: Vec<OpportunityRecord> = csv::Reader::from_path(day.join(OPPORTUNITIES_FILE))
+        .unwrap()
+        .deserialize()
+        .map(Result::unwrap)
+        .collect();
+    std::fs::remove_dir_all(&dir).ok();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].scan_id, scan_id);
+    assert!(rows[1].scan_id > scan_id);
+    let row = &rows[0];
+    assert_eq!((row.strategy.as_str(), row.market_type.as_str()), ("cross_platform", "btc-15m"));
+    assert_eq!(row.kalshi_event_id.as_deref(), Some(KALSHI_ID));
+    assert_eq!((row.pm_no_ask, row.kalshi_yes_ask), (0.45, Some(0.40)));
+    assert!((row.total_cost - 0.85).abs() < 1e-9);
+    assert!((row.net_profit - 0.13).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn scan_skips_thin_books() {
+    let h = harness();
+    h.kalshi.set_prices(KALSHI_ID, MarketPrices::new(0.40, 0.60, 10.0));
+
+    assert!(scan(&h).await.is_empty());
+}
+
+#[tokio::test]
+async fn confirmed_pair_matches_without_rescoring() {
+    let mut h = harness();
+    let (pm_event, mut kalshi_event) = matched_events();
+    kalshi_event.title = "BTC price up in next 15 mins?".to_string();
+    h.kalshi.set_events(vec![kalshi_event]);
+    assert!(scan(&h).await.is_empty());
+
+    let store = Arc::new(MatchStore::new());
+    store.confirm(&pm_event.event_id, KALSHI_ID, MatchSource::Manual);
+    h.bot = ShortTermArbitrageBot::new(filters(), 0.7, 0.02).with_match_store(store);
+    assert_eq!(scan(&h).await.len(), 1);
+}
+
+#[tokio::test]
+async fn newly_listed_market_waits_out_warmup() {
+    let mut h = harness();
+    // The bot's clock jumps ahead of the mock quotes' fetch time.
+    h.bot = ShortTermArbitrageBot::new(filters(), 0.7, 0.02)
+        .with_warmup(Duration::minutes(5))
+        .with_max_price_age(None);
+    let (pm_event, _) = matched_events();
+    let listed = Event::new(
+        "polymarket".to_string(),
+        "0xpm-eth-15m".to_string(),
+        "Ethereum Up or Down 15 minutes".to_string(),
+        String::new(),
+    );
+    h.pm.set_events(vec![listed.clone()]);
+    let now = Utc::now();
+    h.bot.set_time_override(Some(now));
+    assert!(scan(&h).await.is_empty());
+
+    // Listed after the first scan: placeholder quotes are not trusted yet.
+    h.pm.set_events(vec![listed, pm_event]);
+    assert!(scan(&h).await.is_empty());
+
+    h.bot.set_time_override(Some(now + Duration::minutes(6)));
+    assert_eq!(scan(&h).await.len(), 1);
+}
+
+#[tokio::test]
+async fn traded_pair_is_not_redetected_until_cooldown_and_price_move() {
+    let mut h = harness();
+    h.bot = ShortTermArbitrageBot::new(filters(), 0.7, 0.02)
+        .with_trade_dedup(TradeDedup {
+            cooldown: Duration::minutes(5),
+            band_cents: 1,
+        })
+        .with_max_price_age(None);
+    let now = Utc::now();
+    h.bot.set_time_override(Some(now));
+    let opportunities = scan(&h).await;
+    let (pm_event, kalshi_event, opp) = &opportunities[0];
+    h.bot.record_trade(pm_event, kalshi_event, opp);
+
+    // Inside the cooldown, even at a better price.
+    h.kalshi.set_prices(KALSHI_ID, MarketPrices::new(0.38, 0.62, 1000.0));
+    assert!(scan(&h).await.is_empty());
+
+    // Cooldown over, but the quote is the one already traded.
+    h.bot.set_time_override(Some(now + Duration::minutes(6)));
+    h.kalshi.set_prices(KALSHI_ID, MarketPrices::new(0.40, 0.60, 1000.0));
+    assert!(scan(&h).await.is_empty());
+
+    h.kalshi.set_prices(KALSHI_ID, MarketPrices::new(0.38, 0.62, 1000.0));
+    assert_eq!(scan(&h).await.len(), 1);
+}
+
+#[tokio::test]
+async fn broken_and_stale_quotes_are_skipped() {
+    let h = harness();
+    // A failed fetch comes back as zeros: no asks at all, not a free arb.
+    h.pm.set_prices(PM_ID, MarketPrices::new(0.0, 0.0, 0.0));
+    assert!(scan(&h).await.is_empty());
+
+    let stale = Utc::now() - Duration::minutes(2);
+    h.pm.set_prices(PM_ID, MarketPrices::new(0.55, 0.45, 1000.0).with_fetched_at(stale));
+    assert!(scan(&h).await.is_empty());
+
+    h.pm.set_prices(PM_ID, MarketPrices::new(0.55, 0.45, 1000.0));
+    assert_eq!(scan(&h).await.len(), 1);
+}
+
+#[tokio::test]
+async fn full_pipeline_executes_and_settles() {
+    let h = harness();
+    let opportunities = scan(&h).await;
+    let (pm_event, kalshi_event, opp) = &opportunities[0];
+
+    let result = h
+        .executor
+        .execute_arbitrage(opp, pm_event, kalshi_event, 100.0)
+        .await
+        .unwrap();
+    assert!(result.success);
+    assert_eq!(h.pm.orders().len(), 1);
+    assert_eq!(h.kalshi.orders().len(), 1);
+    assert_eq!(h.tracker.lock().await.get_open_positions().len(), 2);
+
+    assert_eq!(h.settlement.check_settlements().await.unwrap(), 0);
+
+    // YES resolves: the Kalshi YES leg wins, the Polymarket NO leg loses.
+    h.pm.set_settlement(PM_ID, true);
+    h.kalshi.set_settlement(KALSHI_ID, true);
+    assert_eq!(h.settlement.check_settlements().await.unwrap(), 2);
+
+    let stats = h.settlement.get_statistics().await;
+    assert_eq!(stats.open_positions, 0);
+    assert_eq!(stats.won_positions, 1);
+    assert_eq!(stats.lost_positions, 1);
+    assert_eq!(stats.by_platform["kalshi"].won, 1);
+    assert_eq!(stats.by_platform["polymarket"].lost, 1);
+    assert_eq!(stats.by_strategy[CROSS_PLATFORM].positions, 2);
+    let btc = &stats.by_coin["btc"];
+    assert_eq!(btc.positions, 2);
+    assert!((btc.profit - stats.total_profit).abs() < 1e-9);
+    assert_eq!(stats.by_hour.values().map(|b| b.positions).sum::<usize>(), 2);
+
+    let report = PerformanceReport::from_tracker(&*h.tracker.lock().await, 50);
+    assert_eq!(report.trades, 1);
+    assert!((report.avg_edge_detected.unwrap() - opp.roi_percent / 100.0).abs() < 1e-9);
+    assert!((report.total_profit - stats.total_profit).abs() < 1e-9);
+
+    let dir = std::env::temp_dir().join(format!("ledger-{}", uuid::Uuid::new_v4()));
+    let tracker = h.tracker.lock().await;
+    assert_eq!(tracker.export(dir.join("trades.csv"), ExportFormat::Csv).unwrap(), 2);
+    let csv = std::fs::read_to_string(dir.join("trades.csv")).unwrap();
+    assert!(csv.starts_with("created_at,settled_at,id,group_id,strategy,platform,event_id"));
+    assert_eq!(csv.lines().count(), 3);
+    assert!(csv.contains(KALSHI_ID) && csv.contains(",Won,"));
+    tracker.export(dir.join("trades.json"), ExportFormat::Json).unwrap();
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.join("trades.json")).unwrap()).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 2);
+    assert!(json[0]["settled_at"].is_string());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[derive(Default)]
+struct RecordingRedeemer {
+    redeemed: std::sync::Mutex<Vec<String>>,
+}
+
+#[async_trait::async_trait]
+impl Redeemer for RecordingRedeemer {
+    async fn redeem(&self, condition_id: &str) -> anyhow::Result<String> {
+        self.redeemed.lock().unwrap().push(condition_id.to_string());
+        Ok("0xredeemed".to_string())
+    }
+}
+
+#[tokio::test]
+async fn legs_go_out_with_the_strategy_time_in_force() {
+    let h = harness();
+    let opportunities = scan(&h).await;
+    let (pm_event, kalshi_event, opp) = &opportunities[0];
+    let tif = OrderTimeInForce::default()
+        .with_default(TimeInForce::Fok)
+        .with_strategy(CROSS_PLATFORM, TimeInForce::parse("gtc:30").unwrap());
+    assert_eq!(tif.get(GABAGOOL), TimeInForce::Fok);
+
+    let executor = TradeExecutor::new(h.pm.clone(), h.kalshi.clone()).with_time_in_force(tif.get(CROSS_PLATFORM));
+    executor
+        .execute_arbitrage(opp, pm_event, kalshi_event, 100.0)
+        .await
+        .unwrap();
+    let expiring = TimeInForce::Gtc { expires_after: Some(30) };
+    assert_eq!(h.pm.orders()[0].tif, expiring);
+    assert_eq!(h.kalshi.orders()[0].tif, expiring);
+    // Left unset, legs go out immediate-or-cancel.
+    assert_eq!(TimeInForce::default(), TimeInForce::Ioc);
+}
+
+#[tokio::test]
+async fn trades_larger_than_the_touch_go_out_in_slices() {
+    let h = harness();
+    let opportunities = scan(&h).await;
+    let (pm_event, kalshi_event, opp) = &opportunities[0];
+    // $45 of Polymarket NO at the touch; Kalshi has plenty.
+    let no_ask = |price, size| BookTop { no_ask: Some(Quote { price, size }), ..Default::default() };
+    h.pm.set_prices(PM_ID, MarketPrices::new(0.55, 0.45, 1000.0).with_book(no_ask(0.45, 100.0)));
+    h.kalshi.set_prices(
+        KALSHI_ID,
+        MarketPrices::new(0.40, 0.60, 1000.0)
+            .with_book(BookTop { yes_ask: Some(Quote { price: 0.40, size: 1000.0 }), ..Default::default() }),
+    );
+
+    let executor = TradeExecutor::new(h.pm.clone(), h.kalshi.clone())
+        .with_position_tracker(h.tracker.clone())
+        .with_slicing(OrderSlicing::new(std::time::Duration::ZERO));
+    let result = executor.execute_arbitrage(opp, pm_event, kalshi_event, 100.0).await.unwrap();
+    assert!(result.success);
+    let amounts = |orders: Vec<MockOrder>| {
+        orders.iter().map(|o| o.amount).collect::<Vec<_>>()
+    };
+    assert_eq!(amounts(h.pm.orders()), vec![45.0, 45.0, 10.0]);
+    assert_eq!(amounts(h.kalshi.orders()), vec![45.0, 45.0, 10.0]);
+    assert_eq!(result.polymarket_order_id.unwrap().split(',').count(), 3);
+    assert_eq!(h.tracker.lock().await.get_open_positions().len(), 6);
+
+    // Once the touch is past the limit, nothing more goes out.
+    h.pm.set_prices(PM_ID, MarketPrices::new(0.55, 0.47, 1000.0).with_book(no_ask(0.47, 100.0)));
+    let result = executor.execute_arbitrage(opp, pm_event, kalshi_event, 100.0).await.unwrap();
+    assert!(result.skipped.is_some());
+    assert_eq!(h.pm.orders().len(), 3);
+}
+
+#[tokio::test]
+async fn won_polymarket_leg_is_redeemed() {
+    let h = harness();
+    let redeemer = Arc::new(RecordingRedeemer::default());
+    let settlement = SettlementChecker::new(h.pm.clone(), h.kalshi.clone(), h.tracker.clone())
+        .with_redeemer(redeemer.clone());
+    let opportunities = scan(&h).await;
+    let (pm_event, kalshi_event, opp) = &opportunities[0];
+    h.executor
+        .execute_arbitrage(opp, pm_event, kalshi_event, 100.0)
+        .await
+        .unwrap();
+
+    // NO resolves: the Polymarket NO leg wins and is redeemed.
+    h.pm.set_settlement(PM_ID, false);
+    h.kalshi.set_settlement(KALSHI_ID, false);
+    assert_eq!(settlement.check_settlements().await.unwrap(), 2);
+
+    assert_eq!(*redeemer.redeemed.lock().unwrap(), vec![PM_ID.to_string()]);
+    let tracker = h.tracker.lock().await;
+    let positions = tracker.get_all_positions();
+    let pm_leg = positions.iter().find(|p| p.platform == "polymarket").unwrap();
+    assert_eq!(pm_leg.redemption_tx.as_deref(), Some("0xredeemed"));
+    let kalshi_leg = positions.iter().find(|p| p.platform == "kalshi").unwrap();
+    assert!(kalshi_leg.redemption_tx.is_none());
+}
+
+#[tokio::test]
+async fn voided_and_split_markets_settle_instead_of_staying_open() {
+    let h = harness();
+    let redeemer = Arc::new(RecordingRedeemer::default());
+    let settlement = SettlementChecker::new(h.pm.clone(), h.kalshi.clone(), h.tracker.clone())
+        .with_redeemer(redeemer.clone());
+    let opportunities = scan(&h).await;
+    let (pm_event, kalshi_event, opp) = &opportunities[0];
+    h.executor
+        .execute_arbitrage(opp, pm_event, kalshi_event, 100.0)
+        .await
+        .unwrap();
+
+    // Kalshi voids its market; Polymarket resolves 50/50.
+    h.kalshi.set_resolution(KALSHI_ID, Resolution::Refunded);
+    h.pm.set_resolution(PM_ID, Resolution::Split { yes: 0.5, no: 0.5 });
+    assert_eq!(settlement.check_settlements().await.unwrap(), 2);
+    assert_eq!(*redeemer.redeemed.lock().unwrap(), vec![PM_ID.to_string()]);
+
+    let tracker = h.tracker.lock().await;
+    assert!(tracker.get_open_positions().is_empty());
+    let positions = tracker.get_all_positions();
+    let kalshi_leg = positions.iter().find(|p| p.platform == "kalshi").unwrap();
+    assert_eq!(kalshi_leg.payout, Some(kalshi_leg.cost));
+    assert_eq!(kalshi_leg.profit, Some(UsdAmount::ZERO));
+    let pm_leg = positions.iter().find(|p| p.platform == "polymarket").unwrap();
+    assert_eq!(pm_leg.payout, Some(UsdAmount::for_shares(pm_leg.amount, Price::from_cents(50))));
+}
+
+#[tokio::test]
+async fn kalshi_settles_at_credited_amount_net_of_fees() {
+    let h = harness();
+    let opportunities = scan(&h).await;
+    let (pm_event, kalshi_event, opp) = &opportunities[0];
+    h.executor
+        .execute_arbitrage(opp, pm_event, kalshi_event, 100.0)
+        .await
+        .unwrap();
+
+    // Kalshi YES wins; its market status is never consulted.
+    h.kalshi.set_fee_per_share(0.01);
+    h.kalshi.add_settlement_record(SettlementRecord {
+        event_id: KALSHI_ID.to_string(),
+        market: KALSHI_ID.to_string(),
+        resolution: Resolution::Yes,
+        revenue: 250.0,
+        fees: None,
+    });
+    h.pm.set_resolution(PM_ID, Resolution::Yes);
+    assert_eq!(h.settlement.check_settlements().await.unwrap(), 2);
+
+    let tracker = h.tracker.lock().await;
+    let positions = tracker.get_all_positions();
+    let kalshi_leg = positions.iter().find(|p| p.platform == "kalshi").unwrap();
+    assert_eq!(kalshi_leg.payout, Some(UsdAmount::from_f64(250.0)));
+    assert_eq!(kalshi_leg.fees, Some(UsdAmount::from_f64(2.5)));
+    assert_eq!(kalshi_leg.profit, Some(UsdAmount::from_f64(147.5)));
+}
+
+#[tokio::test]
+async fn pair_settling_the_same_way_is_denied() {
+    let mut h = harness();
+    let store = Arc::new(MatchStore::new());
+    let settlement = SettlementChecker::new(h.pm.clone(), h.kalshi.clone(), h.tracker.clone())
+        .with_match_store(store.clone());
+    h.bot = ShortTermArbitrageBot::new(filters(), 0.7, 0.02).with_match_store(store.clone());
+    let opportunities = scan(&h).await;
+    let (pm_event, kalshi_event, opp) = &opportunities[0];
+    h.executor
+        .execute_arbitrage(opp, pm_event, kalshi_event, 100.0)
+        .await
+        .unwrap();
+    store.confirm(PM_ID, KALSHI_ID, MatchSource::Traded);
+
+    // Polymarket NO and Kalshi YES both lose.
+    h.pm.set_resolution(PM_ID, Resolution::Yes);
+    h.kalshi.set_resolution(KALSHI_ID, Resolution::No);
+    assert_eq!(settlement.check_settlements().await.unwrap(), 2);
+
+    assert!(store.is_denied(PM_ID, KALSHI_ID));
+    assert_eq!(store.kalshi_for(PM_ID), None);
+    assert!(scan(&h).await.is_empty());
+}
+
+#[tokio::test]
+async fn converged_pair_exits_before_settlement() {
+    let h = harness();
+    let opportunities = scan(&h).await;
+    let (pm_event, kalshi_event, opp) = &opportunities[0];
+    h.executor
+        .execute_arbitrage(opp, pm_event, kalshi_event, 100.0)
+        .await
+        .unwrap();
+
+    let early_exit = EarlyExit::new(h.pm.clone(), h.kalshi.clone(), h.tracker.clone(), 0.8);
+    // Still priced at entry: selling would lose the spread.
+    assert_eq!(early_exit.run_tick().await, 0);
+
+    // Venues converge: NO bid 0.50 on Polymarket, YES bid 0.49 on Kalshi.
+    h.pm.set_prices(PM_ID, MarketPrices::new(0.50, 0.50, 1000.0));
+    h.kalshi.set_prices(KALSHI_ID, MarketPrices::new(0.49, 0.51, 1000.0));
+    assert_eq!(early_exit.run_tick().await, 1);
+
+    h.pm.assert_flat();
+    h.kalshi.assert_flat();
+    let stats = h.settlement.get_statistics().await;
+    assert_eq!(stats.open_positions, 0);
+    assert!(stats.total_profit > 0.8 * (100.0 / 0.45 - 200.0));
+    let pairs = h.tracker.lock().await.pair_pnls();
+    assert_eq!(pairs.len(), 1);
+    assert!(pairs[0].is_closed());
+    assert!((pairs[0].realized_profit - stats.total_profit).abs() < 1e-9);
+    assert!((pairs[0].cost - 200.0).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn failed_leg_reports_error_and_tracks_residual() {
+    let h = harness();
+    let opportunities = scan(&h).await;
+    let (pm_event, kalshi_event, opp) = &opportunities[0];
+
+    h.pm.fail_next_orders(1, "exchange unavailable");
+    let result = h
+        .executor
+        .execute_arbitrage(opp, pm_event, kalshi_event, 100.0)
+        .await
+        .unwrap();
+
+    assert!(!result.success);
+    assert!(result.kalshi_order_id.is_some());
+    assert!(result.error.unwrap().contains("exchange unavailable"));
+    let tracker = h.tracker.lock().await;
+    let open = tracker.get_open_positions();
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].platform, "kalshi");
+    let group = open[0].group_id.clone().expect("residual leg keeps its pair id");
+    assert_eq!(tracker.unhedged_legs(CROSS_PLATFORM).len(), 1);
+    let pair = tracker.pair_pnl(&group).unwrap();
+    assert_eq!((pair.legs, pair.open_legs), (1, 1));
+}
+
+#[tokio::test]
+async fn accepted_but_unfilled_leg_counts_as_a_one_sided_fill() {
+    let h = harness();
+    let opportunities = scan(&h).await;
+    let (pm_event, kalshi_event, opp) = &opportunities[0];
+
+    // The Kalshi YES ask moves past the limit: its order rests unfilled.
+    h.kalshi.set_prices(KALSHI_ID, MarketPrices::new(0.50, 0.50, 1000.0));
+    let result = h
+        .executor
+        .execute_arbitrage(opp, pm_event, kalshi_event, 100.0)
+        .await
+        .unwrap();
+
+    assert!(!result.success);
+    assert!(result.error.unwrap().contains("Kalshi leg did not fill"));
+    let residual = result.residual.expect("the filled Polymarket leg is unhedged");
+    assert_eq!(residual.platform, "polymarket");
+    assert!((residual.shares - 100.0 / 0.45).abs() < 1e-6);
+    let tracker = h.tracker.lock().await;
+    let open = tracker.get_open_positions();
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].platform, "polymarket");
+    assert_eq!(open[0].cost, UsdAmount::from_f64(100.0));
+}
+
+#[tokio::test]
+async fn sequenced_legs_hold_back_kalshi_until_polymarket_fills() {
+    let h = harness();
+    let executor = TradeExecutor::new(h.pm.clone(), h.kalshi.clone())
+        .with_position_tracker(h.tracker.clone())
+        .with_leg_sequence(LegSequence::First("polymarket"));
+    let opportunities = scan(&h).await;
+    let (pm_event, kalshi_event, opp) = &opportunities[0];
+
+    h.pm.fail_next_orders(1, "exchange unavailable");
+    let result = executor.execute_arbitrage(opp, pm_event, kalshi_event, 100.0).await.unwrap();
+    assert!(!result.success);
+    assert!(result.residual.is_none());
+    assert!(result.error.unwrap().contains("not sent"));
+    assert!(h.kalshi.orders().is_empty());
+    assert!(h.tracker.lock().await.get_open_positions().is_empty());
+
+    // Accepted but unfilled: the NO ask moved past the limit.
+    h.pm.set_prices(PM_ID, MarketPrices::new(0.50, 0.50, 1000.0));
+    let result = executor.execute_arbitrage(opp, pm_event, kalshi_event, 100.0).await.unwrap();
+    assert!(!result.success);
+    assert!(result.residual.is_none());
+    assert!(result.error.unwrap().contains("did not fill"));
+    assert!(h.kalshi.orders().is_empty());
+    assert!(h.tracker.lock().await.get_open_positions().is_empty());
+
+    h.pm.set_prices(PM_ID, MarketPrices::new(0.55, 0.45, 1000.0));
+    let result = executor.execute_arbitrage(opp, pm_event, kalshi_event, 100.0).await.unwrap();
+    assert!(result.success);
+    assert_eq!((h.pm.orders().len(), h.kalshi.orders().len()), (2, 1));
+    h.pm.assert_residual(PM_ID, "NO", 100.0 / 0.45);
+}
+
+#[tokio::test]
+async fn short_balance_skips_before_any_leg_is_sent() {
+    let h = harness();
+    let opportunities = scan(&h).await;
+    let (pm_event, kalshi_event, opp) = &opportunities[0];
+
+    h.kalshi.set_balance(50.0);
+    let result = h
+        .executor
+        .execute_arbitrage(opp, pm_event, kalshi_event, 100.0)
+        .await
+        .unwrap();
+
+    assert!(!result.success);
+    assert!(result.skipped.unwrap().contains("kalshi balance"));
+    assert!(h.pm.orders().is_empty());
+    assert!(h.kalshi.orders().is_empty());
+    assert!(h.tracker.lock().await.get_open_positions().is_empty());
+}
+
+#[tokio::test]
+async fn stop_loss_closes_unhedged_leg() {
+    let h = harness();
+    let opportunities = scan(&h).await;
+    let (pm_event, kalshi_event, opp) = &opportunities[0];
+    h.pm.fail_next_orders(1, "exchange unavailable");
+    h.executor
+        .execute_arbitrage(opp, pm_event, kalshi_event, 100.0)
+        .await
+        .unwrap();
+
+    let monitor = PositionMonitor::new(
+        h.pm.clone(),
+        h.kalshi.clone(),
+        h.tracker.clone(),
+        ExitRules { take_profit: Some(0.5), stop_loss: Some(0.3) },
+    );
+    // Kalshi YES bought at 0.40; a 0.35 bid is within the stop.
+    h.kalshi.set_prices(KALSHI_ID, MarketPrices::new(0.36, 0.65, 1000.0));
+    assert_eq!(monitor.run_tick().await, 0);
+
+    h.kalshi.set_prices(KALSHI_ID, MarketPrices::new(0.26, 0.75, 1000.0));
+    assert_eq!(monitor.run_tick().await, 1);
+    h.kalshi.assert_flat();
+    let stats = h.settlement.get_statistics().await;
+    assert_eq!(stats.open_positions, 0);
+    assert!(stats.total_profit < -30.0);
+}
+
+#[tokio::test]
+async fn gabagool_accumulates_both_sides() {
+    let pm = Arc::new(MockPolymarketClient::new());
+    let (event, _) = matched_events();
+    pm.set_prices(PM_ID, MarketPrices::new(0.45, 0.40, 1000.0));
+    let tracker = Arc::new(Mutex::new(PositionTracker::new()));
+    let executor = GabagoolExecutor::new(pm.clone()).with_position_tracker(tracker.clone());
+
+    for (side, price) in [("YES", 0.45), ("NO", 0.40)] {
+        let opp = GabagoolOpportunity {
+            event: event.clone(),
+            cheap_side: side.to_string(),
+            cheap_price: price,
+            net_profit: 0.15,
+            roi_percent: 17.65,
+            pair_cost_after: 0.85,
+            total_cost: 0.85,
+            profit_locked: false,
+            hours_to_resolution: None,
+            roi_per_hour: None,
+            confidence: None,
+        };
+        assert!(executor.execute_trade(&opp, 9.0).await.unwrap());
+    }
+
+    let (yes_qty, yes_cost, no_qty, no_cost) = executor.get_position_balance(PM_ID).await;
+    assert!((yes_qty - 20.0).abs() < 1e-9);
+    assert!((no_qty - 22.5).abs() < 1e-9);
+    assert_eq!(yes_cost + no_cost, UsdAmount::from_f64(18.0));
+    assert!(pm.orders().iter().all(|o| o.filled));
+    assert_eq!(tracker.lock().await.get_open_positions().len(), 2);
+
+    let stats = executor.get_statistics().await;
+    assert!((stats.locked_pairs - 20.0).abs() < 1e-9);
+    assert!((stats.locked_profit - 2.0).abs() < 1e-9);
+    assert_eq!(stats.by_coin.keys().collect::<Vec<_>>(), vec!["btc"]);
+    assert!((stats.by_coin["btc"].locked_profit - 2.0).abs() < 1e-9);
+
+    let pair_costs = executor.pair_costs().await;
+    assert!((pair_costs[0].pair_cost.unwrap() - 0.85).abs() < 1e-9);
+    assert!((pair_costs[0].locked_profit - 2.0).abs() < 1e-9);
+}
+
+#[derive(Default)]
+struct RecordingMerger {
+    merged: std::sync::Mutex<Vec<(String, f64)>>,
+}
+
+#[async_trait::async_trait]
+impl Merger for RecordingMerger {
+    async fn merge(&self, condition_id: &str, pairs: f64) -> anyhow::Result<String> {
+        self.merged.lock().unwrap().push((condition_id.to_string(), pairs));
+        Ok("0xmerged".to_string())
+    }
+}
+
+#[tokio::test]
+async fn gabagool_merges_pairs_under_a_dollar_as_soon_as_they_are_held() {
+    let pm = Arc::new(MockPolymarketClient::new());
+    let (event, _) = matched_events();
+    pm.set_prices(PM_ID, MarketPrices::new(0.45, 0.40, 1000.0));
+    let tracker = Arc::new(Mutex::new(PositionTracker::new()));
+    let merger = Arc::new(RecordingMerger::default());
+    let executor = GabagoolExecutor::new(pm.clone())
+        .with_position_tracker(tracker.clone())
+        .with_merger(merger.clone(), 10.0);
+
+    for (side, price) in [("YES", 0.45), ("NO", 0.40)] {
+        let opp = GabagoolOpportunity {
+            event: event.clone(),
+            cheap_side: side.to_string(),
+            cheap_price: price,
+            net_profit: 0.15,
+            roi_percent: 17.65,
+            pair_cost_after: 0.85,
+            total_cost: 0.85,
+            profit_locked: false,
+            hours_to_resolution: None,
+            roi_per_hour: None,
+            confidence: None,
+        };
+        assert!(executor.execute_trade(&opp, 9.0).await.unwrap());
+    }
+
+    // 20 YES @ 0.45 and 22.5 NO @ 0.40: the 20 pairs merge, 2.5 NO stay.
+    assert_eq!(*merger.merged.lock().unwrap(), vec![(PM_ID.to_string(), 20.0)]);
+    let (yes_qty, yes_cost, no_qty, no_cost) = executor.get_position_balance(PM_ID).await;
+    assert_eq!(yes_qty, 0.0);
+    assert_eq!(yes_cost, UsdAmount::ZERO);
+    assert!((no_qty - 2.5).abs() < 1e-9);
+    assert_eq!(no_cost, UsdAmount::from_f64(1.0));
+    let tracker = tracker.lock().await;
+    assert!((tracker.get_total_profit() - 3.0).abs() < 1e-9);
+    let open = tracker.get_open_positions();
+    assert_eq!(open.len(), 1);
+    assert!((open[0].amount - 2.5).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn gabagool_trims_a_heavy_side_once_its_bid_covers_the_cost() {
+    let pm = Arc::new(MockPolymarketClient::new());
+    let (event, _) = matched_events();
+    pm.set_prices(PM_ID, MarketPrices::new(0.40, 0.45, 1000.0).with_bids(Some(0.35), Some(0.40)));
+    let tracker = Arc::new(Mutex::new(PositionTracker::new()));
+    let executor = GabagoolExecutor::new(pm.clone()).with_position_tracker(tracker.clone());
+    for (side, price, amount) in [("YES", 0.40, 40.0), ("NO", 0.45, 9.0)] {
+        let opp = GabagoolOpportunity {
+            event: event.clone(),
+            cheap_side: side.to_string(),
+            cheap_price: price,
+            net_profit: 0.15,
+            roi_percent: 17.65,
+            pair_cost_after: 0.85,
+            total_cost: 0.85,
+            profit_locked: false,
+            hours_to_resolution: None,
+            roi_per_hour: None,
+            confidence: None,
+        };
+        assert!(executor.execute_trade(&opp, amount).await.unwrap());
+    }
+    let rebalance = Rebalance::new(2.0);
+
+    // 100 YES against 20 NO, but YES bids under its $0.40 cost.
+    assert_eq!(executor.rebalance(PM_ID, &rebalance).await.unwrap(), None);
+
+    pm.set_prices(PM_ID, MarketPrices::new(0.43, 0.45, 1000.0).with_bids(Some(0.42), Some(0.40)));
+    let trimmed = executor.rebalance(PM_ID, &rebalance).await.unwrap().unwrap();
+
+    // Half of the 80 excess YES sold.
+    assert!((trimmed - 40.0).abs() < 1e-9);
+    let (yes_qty, yes_cost, no_qty, _) = executor.get_position_balance(PM_ID).await;
+    assert!((yes_qty - 60.0).abs() < 1e-9);
+    assert_eq!(yes_cost, UsdAmount::from_f64(24.0));
+    assert!((no_qty - 20.0).abs() < 1e-9);
+    assert!((pm.exposure(PM_ID, "YES") - 60.0).abs() < 1e-6);
+    let tracker = tracker.lock().await;
+    let yes_open: f64 = tracker.get_open_positions().iter().filter(|p| p.outcome == "YES").map(|p| p.amount).sum();
+    assert!((yes_open - 60.0).abs() < 1e-9);
+    assert!((tracker.get_total_profit() - 0.8).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn gabagool_maker_rests_inside_the_spread_until_close_to_resolution() {
+    let pm = Arc::new(MockPolymarketClient::new());
+    let (event, _) = matched_events();
+    pm.set_prices(PM_ID, MarketPrices::new(0.45, 0.40, 1000.0).with_bids(Some(0.42), Some(0.36)));
+    let executor = GabagoolExecutor::new(pm.clone()).with_maker_mode(MakerMode::default());
+    let opp = |event: &Event, side: &str, price: f64| GabagoolOpportunity {
+        event: event.clone(),
+        cheap_side: side.to_string(),
+        cheap_price: price,
+        net_profit: 0.15,
+        roi_percent: 17.65,
+        pair_cost_after: 0.85,
+        total_cost: 0.85,
+        profit_locked: false,
+        hours_to_resolution: None,
+        roi_per_hour: None,
+        confidence: None,
+    };
+
+    // A tick above the bid, post-only; not requoted while it rests.
+    assert!(executor.execute_trade(&opp(&event, "YES", 0.45), 9.0).await.unwrap());
+    assert!(!executor.execute_trade(&opp(&event, "YES", 0.45), 9.0).await.unwrap());
+    let orders = pm.orders();
+    assert_eq!(orders.len(), 1);
+    assert!((orders[0].price - 0.43).abs() < 1e-9);
+    assert_eq!(orders[0].tif, TimeInForce::PostOnly { expires_after: Some(60) });
+    assert!(!orders[0].filled);
+
+    // Minutes from resolution it crosses at the ask.
+    let closing = event.clone().with_resolution_date(Utc::now() + Duration::minutes(2));
+    assert!(executor.execute_trade(&opp(&closing, "NO", 0.40), 9.0).await.unwrap());
+    let crossed = pm.orders().pop().unwrap();
+    assert!((crossed.price - 0.40).abs() < 1e-9);
+    assert_eq!(crossed.tif, TimeInForce::Ioc);
+    assert!(crossed.filled);
+
+    // A one-tick spread leaves no room inside: join the bid.
+    let cents = Price::from_cents;
+    assert_eq!(MakerMode::default().quote(cents(44), cents(45)), Some(cents(44)));
+    assert_eq!(MakerMode::default().quote(Price::ZERO, cents(45)), None);
+}
+
+#[tokio::test]
+async fn quoting_rests_post_only_bids_on_both_sides() {
+    let pm = Arc::new(MockPolymarketClient::new());
+    let (event, _) = matched_events();
+    pm.set_prices(PM_ID, MarketPrices::new(0.46, 0.56, 1000.0).with_bids(Some(0.44), Some(0.52)));
+    let gabagool = Arc::new(GabagoolExecutor::new(pm.clone()));
+    let quoting = QuotingStrategy::new(
+        Arc::new(ShortTermArbitrageBot::new(filters(), 0.7, 0.02)),
+        QuoteDetector::new(0.97),
+        gabagool.clone(),
+        pm.clone(),
+    );
+    let snapshot = MarketSnapshot { pm_events: vec![event], kalshi_events: Vec::new() };
+
+    let quotes = quoting.scan(&snapshot).await;
+    assert_eq!(quotes[0].bids(), vec![("YES", Price::from_cents(44)), ("NO", Price::from_cents(53))]);
+    assert!(quoting.execute(&quotes[0], 97.0).await.unwrap());
+    let orders = pm.orders();
+    assert_eq!(orders.len(), 2);
+    assert!(orders.iter().all(|o| o.tif.is_post_only() && !o.filled));
+    let (yes_qty, _, no_qty, _) = gabagool.get_position_balance(PM_ID).await;
+    assert!((yes_qty - 100.0).abs() < 1e-9 && (no_qty - 100.0).abs() < 1e-9);
+
+    // Still resting: nothing is requoted.
+    assert!(!quoting.execute(&quotes[0], 97.0).await.unwrap());
+    assert_eq!(pm.orders().len(), 2);
+}
+
+#[tokio::test]
+async fn gabagool_reconciles_to_on_chain_holdings() {
+    let pm = Arc::new(MockPolymarketClient::new());
+    let (event, _) = matched_events();
+    let executor = GabagoolExecutor::new(pm.clone());
+    for (side, price) in [("YES", 0.45), ("NO", 0.40)] {
+        let opp = GabagoolOpportunity {
+            event: event.clone(),
+            cheap_side: side.to_string(),
+            cheap_price: price,
+            net_profit: 0.15,
+            roi_percent: 17.65,
+            pair_cost_after: 0.85,
+            total_cost: 0.85,
+            profit_locked: false,
+            hours_to_resolution: None,
+            roi_per_hour: None,
+            confidence: None,
+        };
+        executor.execute_trade(&opp, 9.0).await.unwrap();
+    }
+
+    // YES only half filled on-chain; NO matches.
+    let holdings =
+        |ids: Vec<String>| async move { Ok(ids.into_iter().map(|id| (id, (10.0, 22.5))).collect()) };
+    assert_eq!(executor.reconcile_holdings(holdings).await, 1);
+    let (yes_qty, yes_cost, no_qty, no_cost) = executor.get_position_balance(PM_ID).await;
+    assert!((yes_qty - 10.0).abs() < 1e-9);
+    assert_eq!(yes_cost, UsdAmount::from_f64(4.5));
+    assert!((no_qty - 22.5).abs() < 1e-9);
+    assert_eq!(no_cost, UsdAmount::from_f64(9.0));
+
+    assert_eq!(executor.reconcile_holdings(holdings).await, 0);
+}
+
+#[tokio::test]
+async fn journal_records_each_decision_in_sequence() {
+    let h = harness();
+    let path = std::env::temp_dir().join(format!("journal-{}.jsonl", uuid::Uuid::new_v4()));
+    let journal = Arc::new(Journal::open(&path).unwrap());
+    let pm: Arc<dyn ExchangeClient> = Arc::new(JournaledClient::new(h.pm.clone(), journal.clone()));
+    let kalshi: Arc<dyn ExchangeClient> = Arc::new(JournaledClient::new(h.kalshi.clone(), journal.clone()));
+    let tracker = Arc::new(Mutex::new(PositionTracker::new().with_journal(journal.clone())));
+    let executor = Arc::new(TradeExecutor::new(pm.clone(), kalshi.clone()).with_position_tracker(tracker.clone()));
+    // Room in the budget for one pair.
+    let mut strategies = StrategyRegistry::new()
+        .with_position_tracker(tracker.clone())
+        .with_journal(journal.clone())
+        .with_allocation(CapitalAllocation::new(150.0).with_share(CROSS_PLATFORM, 100.0));
+    strategies.register(
+        CrossPlatformStrategy::new(Arc::new(h.bot), executor, pm.clone(), kalshi.clone()).with_trade_amount(50.0),
+    );
+
+    let (pm_event, kalshi_event) = matched_events();
+    let snapshot = MarketSnapshot {
+        pm_events: vec![pm_event],
+        kalshi_events: vec![kalshi_event],
+    };
+    strategies.run_tick(&snapshot).await;
+    strategies.run_tick(&snapshot).await;
+
+    h.pm.set_settlement(PM_ID, true);
+    h.kalshi.set_settlement(KALSHI_ID, true);
+    let settlement = SettlementChecker::new(pm, kalshi, tracker);
+    assert_eq!(settlement.check_settlements().await.unwrap(), 2);
+
+    let records = journal::read(&path).unwrap();
+    assert!(records.iter().enumerate().all(|(i, r)| r.seq == i as u64 + 1));
+    let kinds: Vec<&str> = records
+        .iter()
+        .map(|r| match &r.entry {
+            JournalEntry::OpportunityDetected { .. } => "detected",
+            JournalEntry::OpportunitySkipped { .. } => "skipped",
+            JournalEntry::OpportunityExecuted { .. } => "executed",
+            JournalEntry::OrderSubmitted { .. } => "submitted",
+            JournalEntry::OrderResponse { .. } => "response",
+            JournalEntry::Fill { .. } => "fill",
+            JournalEntry::Settled { .. } => "settled",
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            "detected", "submitted", "response", "submitted", "response", "fill", "fill", "executed",
+            "detected", "skipped", "settled", "settled",
+        ]
+    );
+    let JournalEntry::OpportunitySkipped { reason, detail, .. } = &records[9].entry else {
+        unreachable!()
+    };
+    assert_eq!(*reason, SkipReason::OverBudget);
+    assert!(detail.contains("budget"), "{}", detail);
+    std::fs::remove_file(&path).ok();
+}
+
+#[tokio::test]
+async fn stats_count_what_each_scan_skipped() {
+    let h = harness();
+    let executor = Arc::new(
+        TradeExecutor::new(h.pm.clone(), h.kalshi.clone()).with_position_tracker(h.tracker.clone()),
+    );
+    let mut strategies = StrategyRegistry::new()
+        .with_position_tracker(h.tracker.clone())
+        .with_allocation(CapitalAllocation::new(150.0).with_share(CROSS_PLATFORM, 100.0));
+    strategies.register(
+        CrossPlatformStrategy::new(Arc::new(h.bot), executor, h.pm.clone(), h.kalshi.clone())
+            .with_trade_amount(50.0),
+    );
+    let (pm_event, kalshi_event) = matched_events();
+    let snapshot = MarketSnapshot {
+        pm_events: vec![pm_event],
+        kalshi_events: vec![kalshi_event],
+    };
+
+    // Kalshi YES 0.54 + Polymarket NO 0.45: a cent of edge, two of fees.
+    h.kalshi.set_prices(KALSHI_ID, MarketPrices::new(0.54, 0.60, 1000.0));
+    assert_eq!(strategies.run_tick(&snapshot).await, 0);
+    let stats = &strategies.statistics().await[0];
+    assert_eq!(stats.last_scan_skips.get(SkipReason::FeesEatEdge), 1);
+
+    h.kalshi.set_prices(KALSHI_ID, MarketPrices::new(0.40, 0.60, 1000.0));
+    strategies.run_tick(&snapshot).await;
+    strategies.run_tick(&snapshot).await;
+    let stats = &strategies.statistics().await[0];
+    assert_eq!(stats.executed, 1);
+    assert_eq!(stats.last_scan_skips.iter().collect::<Vec<_>>(), vec![(SkipReason::OverBudget, 1)]);
+    assert_eq!(stats.skips.get(SkipReason::FeesEatEdge), 1);
+    assert_eq!(stats.skips.total(), 2);
+}
+
+#[tokio::test]
+async fn registry_runs_registered_strategies() {
+    let h = harness();
+    let executor = Arc::new(
+        TradeExecutor::new(h.pm.clone(), h.kalshi.clone()).with_position_tracker(h.tracker.clone()),
+    );
+    let mut strategies = StrategyRegistry::new();
+    strategies.register(
+        CrossPlatformStrategy::new(Arc::new(h.bot), executor, h.pm.clone(), h.kalshi.clone())
+            .with_trade_amount(50.0),
+    );
+    let strategies = Arc::new(strategies);
+
+    let (pm_events, kalshi_events) = matched_events();
+    let snapshot = MarketSnapshot {
+        pm_events: vec![pm_events],
+        kalshi_events: vec![kalshi_events],
+    };
+    assert_eq!(strategies.run_tick(&snapshot).await, 1);
+
+    let stats = strategies.statistics().await;
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].name, "cross_platform");
+    assert_eq!(stats[0].executed, 1);
+    assert_eq!(h.tracker.lock().await.get_open_positions().len(), 2);
+
+    let dashboard = Dashboard::new(h.tracker.clone())
+        .with_strategies(strategies.clone())
+        .with_balances(h.pm.clone(), h.kalshi.clone());
+    let snapshot = dashboard.snapshot().await;
+    assert_eq!(snapshot.open_positions.len(), 2);
+    assert_eq!(snapshot.recent_trades.len(), 2);
+    assert_eq!(snapshot.opportunities.len(), 1);
+    assert_eq!(snapshot.opportunities[0].strategy, "cross_platform");
+    assert!((snapshot.opportunities[0].capital - 100.0).abs() < 1e-9);
+    assert!(snapshot.balances.is_some());
+}
+
+#[tokio::test]
+async fn strategies_scan_on_their_own_tasks() {
+    let h = harness();
+    let executor = Arc::new(
+        TradeExecutor::new(h.pm.clone(), h.kalshi.clone()).with_position_tracker(h.tracker.clone()),
+    );
+    let bot = Arc::new(h.bot);
+    let build = |intervals: StrategyIntervals| {
+        let mut strategies = StrategyRegistry::new().with_intervals(intervals);
+        strategies.register(CrossPlatformStrategy::new(
+            bot.clone(),
+            executor.clone(),
+            h.pm.clone(),
+            h.kalshi.clone(),
+        ));
+        strategies
+    };
+    let per_listing = build(StrategyIntervals::default());
+    let timed = build(StrategyIntervals::default().with_interval(CROSS_PLATFORM, std::time::Duration::from_millis(50)));
+
+    let (snapshots, rx) = tokio::sync::watch::channel(None);
+    per_listing.spawn(rx.clone());
+    timed.spawn(rx);
+    let (pm_event, kalshi_event) = matched_events();
+    snapshots.send_replace(Some(Arc::new(MarketSnapshot {
+        pm_events: vec![pm_event],
+        kalshi_events: vec![kalshi_event],
+    })));
+    tokio::time::sleep(std::time::Duration::from_millis(400)).await;
+
+    assert_eq!(per_listing.statistics().await[0].scans, 1);
+    assert!(timed.statistics().await[0].scans >= 3);
+
+    // No new entries: the timed strategy idles.
+    snapshots.send_replace(None);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    let scans = timed.statistics().await[0].scans;
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    assert_eq!(timed.statistics().await[0].scans, scans);
+
+    // Shutdown drains both registries' idle tasks; nothing scans afterwards.
+    assert_eq!(per_listing.shutdown(std::time::Duration::from_secs(1)).await, 0);
+    assert_eq!(timed.shutdown(std::time::Duration::from_secs(1)).await, 0);
+    snapshots.send_replace(Some(Arc::new(MarketSnapshot::default())));
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert_eq!(per_listing.statistics().await[0].scans, 1);
+}
+
+#[tokio::test]
+async fn book_update_reprices_its_pair() {
+    let h = harness();
+    // Fair to start with: 0.55 + 0.60 and 0.45 + 0.40 both cost more than a dollar.
+    h.kalshi.set_prices(KALSHI_ID, MarketPrices::new(0.40, 0.60, 1000.0));
+    h.pm.set_prices(PM_ID, MarketPrices::new(0.55, 0.62, 1000.0));
+    let executor = Arc::new(
+        TradeExecutor::new(h.pm.clone(), h.kalshi.clone()).with_position_tracker(h.tracker.clone()),
+    );
+    let feed = Arc::new(BookFeed::new());
+    let mut strategies = StrategyRegistry::new();
+    strategies.register(
+        CrossPlatformStrategy::new(Arc::new(h.bot), executor, h.pm.clone(), h.kalshi.clone())
+            .with_trade_amount(50.0)
+            .with_book_feed(feed.clone()),
+    );
+    let (pm_event, kalshi_event) = matched_events();
+    let snapshot = MarketSnapshot {
+        pm_events: vec![pm_event],
+        kalshi_events: vec![kalshi_event],
+    };
+    assert_eq!(strategies.run_tick(&snapshot).await, 0);
+    strategies.spawn_updates(feed.updates());
+
+    // The Polymarket NO ask drops between timed scans.
+    h.pm.set_prices(PM_ID, MarketPrices::new(0.55, 0.45, 1000.0));
+    feed.updates()
+        .send(BookUpdate { platform: "polymarket", event_id: PM_ID.to_string() })
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+    let stats = strategies.statistics().await;
+    assert_eq!(stats[0].scans, 1);
+    assert_eq!(stats[0].executed, 1);
+    assert_eq!(h.tracker.lock().await.get_open_positions().len(), 2);
+}
+
+#[tokio::test]
+async fn strategy_budget_caps_capital_in_use() {
+    let h = harness();
+    let executor = Arc::new(
+        TradeExecutor::new(h.pm.clone(), h.kalshi.clone()).with_position_tracker(h.tracker.clone()),
+    );
+    let mut strategies = StrategyRegistry::new()
+        .with_position_tracker(h.tracker.clone())
+        .with_allocation(CapitalAllocation::new(250.0).with_share("cross_platform", 60.0));
+    strategies.register(
+        CrossPlatformStrategy::new(Arc::new(h.bot), executor, h.pm.clone(), h.kalshi.clone())
+            .with_trade_amount(50.0),
+    );
+
+    let (pm_events, kalshi_events) = matched_events();
+    let snapshot = MarketSnapshot {
+        pm_events: vec![pm_events],
+        kalshi_events: vec![kalshi_events],
+    };
+    strategies.run_tick(&snapshot).await;
+    strategies.run_tick(&snapshot).await;
+
+    let stats = &strategies.statistics().await[0];
+    assert_eq!(stats.capital_budget, Some(150.0));
+    assert_eq!(stats.executed, 1);
+    assert_eq!(stats.over_budget, 1);
+    assert!((stats.capital_in_use - 100.0).abs() < 1e-9);
+    assert_eq!(stats.open_positions, 2);
+}
+
+#[tokio::test]
+async fn open_trade_cap_stops_new_entries() {
+    let h = harness();
+    let executor = Arc::new(
+        TradeExecutor::new(h.pm.clone(), h.kalshi.clone()).with_position_tracker(h.tracker.clone()),
+    );
+    let mut strategies = StrategyRegistry::new()
+        .with_position_tracker(h.tracker.clone())
+        .with_limits(TradeLimits::new().with_max_open(1));
+    strategies.register(
+        CrossPlatformStrategy::new(Arc::new(h.bot), executor, h.pm.clone(), h.kalshi.clone())
+            .with_trade_amount(50.0),
+    );
+
+    let (pm_events, kalshi_events) = matched_events();
+    let snapshot = MarketSnapshot {
+        pm_events: vec![pm_events],
+        kalshi_events: vec![kalshi_events],
+    };
+    strategies.run_tick(&snapshot).await;
+    strategies.run_tick(&snapshot).await;
+
+    // The pair's two legs are one open trade, which fills the cap.
+    let stats = &strategies.statistics().await[0];
+    assert_eq!(stats.executed, 1);
+    assert_eq!(stats.capped, 1);
+    assert_eq!(stats.open_positions, 2);
+}
+
+#[tokio::test]
+async fn controls_pause_resize_and_close_at_runtime() {
+    let h = harness();
+    let executor = Arc::new(
+        TradeExecutor::new(h.pm.clone(), h.kalshi.clone()).with_position_tracker(h.tracker.clone()),
+    );
+    let controls = Arc::new(Controls::new());
+    let mut strategies = StrategyRegistry::new()
+        .with_position_tracker(h.tracker.clone())
+        .with_controls(controls.clone());
+    strategies.register(
+        CrossPlatformStrategy::new(Arc::new(h.bot), executor, h.pm.clone(), h.kalshi.clone())
+            .with_trade_amount(50.0),
+    );
+    let (pm_events, kalshi_events) = matched_events();
+    let snapshot = MarketSnapshot {
+        pm_events: vec![pm_events],
+        kalshi_events: vec![kalshi_events],
+    };
+
+    controls.pause(Some(CROSS_PLATFORM));
+    assert_eq!(strategies.run_tick(&snapshot).await, 0);
+    controls.resume(None);
+    controls.set_min_profit(Some(0.5));
+    assert_eq!(strategies.run_tick(&snapshot).await, 0);
+    controls.set_min_profit(None);
+    controls.set_trade_amount(Some(20.0));
+    assert_eq!(strategies.run_tick(&snapshot).await, 1);
+    let stats = &strategies.statistics().await[0];
+    assert_eq!(stats.executed, 1);
+    assert!((stats.capital_in_use - 40.0).abs() < 1e-9);
+
+    let api = ControlApi::new(controls, "token", h.pm.clone(), h.kalshi.clone(), h.tracker.clone());
+    let kalshi_leg = h.tracker.lock().await.get_positions_by_platform("kalshi")[0].id.clone();
+    let closed = api.close_position(&kalshi_leg, 0.05).await.unwrap();
+    assert_eq!(closed.position_id, kalshi_leg);
+    assert!(closed.shares > 0.0 && closed.price >= closed.min_price);
+    assert!(api.close_position(&kalshi_leg, 0.05).await.is_err());
+    assert_eq!(h.tracker.lock().await.get_open_positions().len(), 1);
+}
+
+#[tokio::test]
+async fn low_balance_pauses_new_positions_on_that_platform() {
+    let h = harness();
+    let executor = Arc::new(
+        TradeExecutor::new(h.pm.clone(), h.kalshi.clone()).with_position_tracker(h.tracker.clone()),
+    );
+    let controls = Arc::new(Controls::new());
+    let mut strategies = StrategyRegistry::new()
+        .with_position_tracker(h.tracker.clone())
+        .with_controls(controls.clone());
+    strategies.register(
+        CrossPlatformStrategy::new(Arc::new(h.bot), executor, h.pm.clone(), h.kalshi.clone())
+            .with_trade_amount(50.0),
+    );
+    let (pm_events, kalshi_events) = matched_events();
+    let snapshot = MarketSnapshot {
+        pm_events: vec![pm_events],
+        kalshi_events: vec![kalshi_events],
+    };
+
+    let floors = BalanceFloors::new(std::time::Duration::from_secs(60)).with_floor("kalshi", 100.0);
+    h.kalshi.set_balance(40.0);
+    assert!(floors.apply(&controls, "kalshi", h.kalshi.get_balance().await.unwrap()).is_some());
+    strategies.run_tick(&snapshot).await;
+    assert_eq!(strategies.statistics().await[0].executed, 0);
+    assert!(h.tracker.lock().await.get_open_positions().is_empty());
+
+    h.kalshi.set_balance(500.0);
+    assert!(floors.apply(&controls, "kalshi", h.kalshi.get_balance().await.unwrap()).is_some());
+    strategies.run_tick(&snapshot).await;
+    assert_eq!(strategies.statistics().await[0].executed, 1);
+}
+
+#[tokio::test]
+async fn settlements_run_concurrently_within_worker_pool() {
+    let h = harness();
+    h.kalshi.set_latency(std::time::Duration::from_millis(100));
+    {
+        let mut tracker = h.tracker.lock().await;
+        for i in 0..10 {
+            let ticker = format!("KXBTC15M-T{}", i);
+            let event = Event::new("kalshi".to_string(), ticker.clone(), ticker.clone(), String::new());
+            for _ in 0..2 {
+                tracker.add_position(Position::new(
+                    "kalshi".to_string(),
+                    &event,
+                    "YES".to_string(),
+                    10.0,
+                    UsdAmount::from_f64(5.0),
+                    Price::from_cents(50),
+                    None,
+                ));
+            }
+            h.kalshi.set_settlement(&ticker, true);
+        }
+    }
+
+    let settlement = SettlementChecker::new(h.pm.clone(), h.kalshi.clone(), h.tracker.clone())
+        .with_workers(10)
+        .with_venue_rate("kalshi", 10, 1000.0);
+    let started = std::time::Instant::now();
+    assert_eq!(settlement.check_settlements().await.unwrap(), 20);
+    assert!(started.elapsed() < std::time::Duration::from_millis(500));
+    assert!(h.tracker.lock().await.get_open_positions().is_empty());
+}
+
+#[tokio::test]
+async fn neg_risk_basket_buys_every_outcome() {
+    let pm = Arc::new(MockPolymarketClient::new());
+    let tracker = Arc::new(Mutex::new(PositionTracker::new()));
+    let outcomes: Vec<EventOutcome> = [("Alice", 0.50), ("Bob", 0.30), ("Carol", 0.12)]
+        .iter()
+        .map(|(name, ask)| {
+            let market_id = format!("0x{}", name.to_lowercase());
+            pm.set_prices(&market_id, MarketPrices::new(*ask, 1.0 - ask, 1000.0));
+            EventOutcome {
+                name: name.to_string(),
+                market_id,
+                yes_token_id: None,
+                no_token_id: None,
+            }
+        })
+        .collect();
+    let election = Event::new(
+        "polymarket".to_string(),
+        "0xelection".to_string(),
+        "Who will win?".to_string(),
+        String::new(),
+    )
+    .with_resolution_date(Utc::now() + Duration::days(30))
+    .with_outcomes(outcomes);
+
+    let mut strategies = StrategyRegistry::new().with_position_tracker(tracker.clone());
+    strategies.register(
+        NegRiskStrategy::new(BasketDetector::new(0.02), pm.clone())
+            .with_position_tracker(tracker.clone())
+            .with_trade_amount(92.0),
+    );
+    let snapshot = MarketSnapshot {
+        pm_events: vec![election],
+        kalshi_events: Vec::new(),
+    };
+    assert_eq!(strategies.run_tick(&snapshot).await, 1);
+
+    // 92 / 0.92 = 100 YES shares of each outcome.
+    assert_eq!(pm.orders().len(), 3);
+    assert!(pm.orders().iter().all(|o| o.filled && (o.shares - 100.0).abs() < 1e-9));
+    let tracker = tracker.lock().await;
+    assert_eq!(tracker.get_open_positions().len(), 3);
+    assert!((tracker.capital_in_use("neg_risk") - 92.0).abs() < 1e-9);
+}
@@ -0,0 +1,72 @@
+use chrono::{Duration, Utc};
+use polymarket_kalshi_arbitrage_bot::{
+    bot::MarketFilters,
+    event::{Event, MarketPrices},
+    recorder::MarketRecorder,
+    replay::ReplaySource,
+    simulate::{simulate, ParamsGrid},
+};
+
+async fn recording() -> ReplaySource {
+    let dir = std::env::temp_dir().join(format!("sim-test-{}", uuid::Uuid::new_v4()));
+    let recorder = MarketRecorder::new(&dir);
+    let resolves = Utc::now() + Duration::minutes(20);
+    let pm = Event::new(
+        "polymarket".to_string(),
+        "0xpm-btc-15m".to_string(),
+        "Bitcoin Up or Down 15 minutes".to_string(),
+        String::new(),
+    )
+    .with_slug("btc-updown-15m-1700000000".to_string())
+    .with_resolution_date(resolves);
+    let kalshi = Event::new(
+        "kalshi".to_string(),
+        "KXBTC15M-TEST".to_string(),
+        "Bitcoin Up or Down 15 minutes".to_string(),
+        String::new(),
+    )
+    .with_slug("KXBTC15M-TEST".to_string())
+    .with_resolution_date(resolves);
+
+    recorder.record_events(&[pm], &[kalshi]);
+    recorder.record_prices("polymarket", "0xpm-btc-15m", &MarketPrices::new(0.55, 0.45, 1000.0));
+    recorder.record_prices("kalshi", "KXBTC15M-TEST", &MarketPrices::new(0.40, 0.60, 1000.0));
+    recorder.flush().await;
+
+    let source = ReplaySource::load(&dir).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+    source
+}
+
+#[tokio::test]
+async fn sweep_reports_one_result_per_grid_point() {
+    let filters = MarketFilters {
+        categories: vec!["crypto".to_string()],
+        max_hours_until_resolution: 1,
+        min_liquidity: 100.0,
+        coin_filter: None,
+    };
+    let grid = ParamsGrid::new(filters)
+        .with_min_profit_thresholds(vec![0.02, 0.50])
+        .with_similarity_thresholds(vec![0.7])
+        .with_trade_amounts(vec![50.0, 100.0]);
+
+    let results = simulate(&grid, &recording().await).await;
+    assert_eq!(results.len(), 4);
+    assert_eq!(
+        results.iter().map(|r| r.params).collect::<Vec<_>>(),
+        grid.combinations()
+    );
+
+    for result in &results {
+        assert_eq!(result.summary.scans, 1);
+        if result.params.min_profit_threshold < 0.10 {
+            assert_eq!(result.summary.executed, 1);
+            assert_eq!(result.fill_rate, 1.0);
+            assert!((result.capital_deployed - 2.0 * result.params.trade_amount).abs() < 1e-9);
+        } else {
+            assert_eq!(result.summary.opportunities, 0);
+            assert_eq!(result.capital_deployed, 0.0);
+        }
+    }
+}